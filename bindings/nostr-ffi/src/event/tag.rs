@@ -247,6 +247,10 @@ pub enum TagKind {
     Proxy,
     Emoji,
     Request,
+    Protected,
+    Alt,
+    K,
+    Q,
     Unknown {
         unknown: String,
     },
@@ -305,6 +309,10 @@ impl From<tag::TagKind> for TagKind {
             tag::TagKind::Proxy => Self::Proxy,
             tag::TagKind::Emoji => Self::Emoji,
             tag::TagKind::Request => Self::Request,
+            tag::TagKind::Protected => Self::Protected,
+            tag::TagKind::Alt => Self::Alt,
+            tag::TagKind::K => Self::K,
+            tag::TagKind::Q => Self::Q,
             tag::TagKind::Custom(unknown) => Self::Unknown { unknown },
         }
     }
@@ -363,6 +371,10 @@ impl From<TagKind> for tag::TagKind {
             TagKind::Proxy => Self::Proxy,
             TagKind::Emoji => Self::Emoji,
             TagKind::Request => Self::Request,
+            TagKind::Protected => Self::Protected,
+            TagKind::Alt => Self::Alt,
+            TagKind::K => Self::K,
+            TagKind::Q => Self::Q,
             TagKind::Unknown { unknown } => Self::Custom(unknown),
         }
     }
@@ -562,6 +574,17 @@ pub enum TagEnum {
         status: DataVendingMachineStatus,
         extra_info: Option<String>,
     },
+    Protected,
+    Alt {
+        description: String,
+    },
+    Kind {
+        kind: u64,
+    },
+    Quote {
+        event_id: Arc<EventId>,
+        relay_url: Option<String>,
+    },
 }
 
 impl From<tag::Tag> for TagEnum {
@@ -732,6 +755,18 @@ impl From<tag::Tag> for TagEnum {
                     extra_info,
                 }
             }
+            tag::Tag::Protected => Self::Protected,
+            tag::Tag::Alt(description) => Self::Alt { description },
+            tag::Tag::Kind(kind) => Self::Kind {
+                kind: kind.as_u64(),
+            },
+            tag::Tag::Quote {
+                event_id,
+                relay_url,
+            } => Self::Quote {
+                event_id: Arc::new(event_id.into()),
+                relay_url: relay_url.map(|u| u.to_string()),
+            },
         }
     }
 }
@@ -876,6 +911,16 @@ impl TryFrom<TagEnum> for tag::Tag {
                     extra_info,
                 })
             }
+            TagEnum::Protected => Ok(Self::Protected),
+            TagEnum::Alt { description } => Ok(Self::Alt(description)),
+            TagEnum::Kind { kind } => Ok(Self::Kind(Kind::from(kind))),
+            TagEnum::Quote {
+                event_id,
+                relay_url,
+            } => Ok(Self::Quote {
+                event_id: **event_id,
+                relay_url: relay_url.map(UncheckedUrl::from),
+            }),
         }
     }
 }
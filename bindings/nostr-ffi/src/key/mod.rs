@@ -97,6 +97,25 @@ impl Keys {
         })
     }
 
+    /// Derive `Keys` from BIP-39 mnemonics (ENGLISH wordlist) with custom `account`, `typ` (change) and `index`.
+    ///
+    /// Derivation path: `m/44'/1237'/<account>'/<typ>/<index>` (all default to `0`).
+    ///
+    /// <https://github.com/nostr-protocol/nips/blob/master/06.md>
+    #[uniffi::constructor]
+    pub fn from_mnemonic_advanced(
+        mnemonic: String,
+        passphrase: Option<String>,
+        account: Option<u32>,
+        typ: Option<u32>,
+        index: Option<u32>,
+    ) -> Result<Self> {
+        Ok(Self {
+            inner: key::Keys::from_mnemonic_advanced(mnemonic, passphrase, account, typ, index)
+                .map_err(|e| NostrError::Generic { err: e.to_string() })?,
+        })
+    }
+
     pub fn public_key(&self) -> Arc<PublicKey> {
         Arc::new(self.inner.public_key().into())
     }
@@ -105,6 +124,11 @@ impl Keys {
         Ok(Arc::new(self.inner.secret_key()?.into()))
     }
 
+    /// Check if keys are watch-only (no secret key)
+    pub fn is_watch_only(&self) -> bool {
+        self.inner.is_watch_only()
+    }
+
     pub fn sign_schnorr(&self, message: Vec<u8>) -> Result<String> {
         let message = Message::from_slice(&message)?;
         Ok(self.inner.sign_schnorr(&message)?.to_string())
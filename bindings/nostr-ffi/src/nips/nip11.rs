@@ -127,6 +127,8 @@ pub struct Limitation {
     pub max_filters: Option<i32>,
     /// Relay will clamp each filter's limit value to this number
     pub max_limit: Option<i32>,
+    /// Relay will use this value as `limit` for the filters without one
+    pub default_limit: Option<i32>,
     /// Maximum length of subscription id as a string
     pub max_subid_length: Option<i32>,
     /// Maximum number of elements in the tags list
@@ -139,6 +141,8 @@ pub struct Limitation {
     pub auth_required: Option<bool>,
     /// Relay requires payment before a new connection may perform any action
     pub payment_required: Option<bool>,
+    /// Relay requires some kind of condition to be fulfilled in order to accept events
+    pub restricted_writes: Option<bool>,
     /// 'created_at' lower limit
     pub created_at_lower_limit: Option<Arc<Timestamp>>,
     /// 'created_at' upper limit
@@ -152,12 +156,14 @@ impl From<nip11::Limitation> for Limitation {
             max_subscriptions,
             max_filters,
             max_limit,
+            default_limit,
             max_subid_length,
             max_event_tags,
             max_content_length,
             min_pow_difficulty,
             auth_required,
             payment_required,
+            restricted_writes,
             created_at_lower_limit,
             created_at_upper_limit,
         } = inner;
@@ -166,12 +172,14 @@ impl From<nip11::Limitation> for Limitation {
             max_subscriptions,
             max_filters,
             max_limit,
+            default_limit,
             max_subid_length,
             max_event_tags,
             max_content_length,
             min_pow_difficulty,
             auth_required,
             payment_required,
+            restricted_writes,
             created_at_lower_limit: created_at_lower_limit.map(|c| Arc::new(c.into())),
             created_at_upper_limit: created_at_upper_limit.map(|c| Arc::new(c.into())),
         }
@@ -251,7 +259,7 @@ pub struct FeeSchedule {
     /// The duration for which the fee is valid
     pub period: Option<i32>,
     /// The event kinds the fee allows the client to publish to the relay
-    pub kinds: Option<Vec<String>>,
+    pub kinds: Option<Vec<u16>>,
 }
 
 impl From<nip11::FeeSchedule> for FeeSchedule {
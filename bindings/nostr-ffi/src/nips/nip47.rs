@@ -104,6 +104,8 @@ pub enum Method {
     ListInvoices,
     /// List Payments
     ListPayments,
+    /// List Transactions
+    ListTransactions,
     /// Get Balance
     GetBalance,
 }
@@ -117,6 +119,7 @@ impl From<nip47::Method> for Method {
             nip47::Method::LookupInvoice => Self::LookupInvoice,
             nip47::Method::ListInvoices => Self::ListInvoices,
             nip47::Method::ListPayments => Self::ListPayments,
+            nip47::Method::ListTransactions => Self::ListTransactions,
             nip47::Method::GetBalance => Self::GetBalance,
         }
     }
@@ -131,6 +134,7 @@ impl From<Method> for nip47::Method {
             Method::LookupInvoice => Self::LookupInvoice,
             Method::ListInvoices => Self::ListInvoices,
             Method::ListPayments => Self::ListPayments,
+            Method::ListTransactions => Self::ListTransactions,
             Method::GetBalance => Self::GetBalance,
         }
     }
@@ -163,6 +167,10 @@ pub enum RequestParams {
     ListPayments {
         list_payments: ListPaymentsRequestParams,
     },
+    /// List Transactions
+    ListTransactions {
+        list_transactions: ListTransactionsRequestParams,
+    },
     /// Get Balance
     GetBalance,
 }
@@ -188,6 +196,9 @@ impl From<nip47::RequestParams> for RequestParams {
             nip47::RequestParams::ListPayments(list_payments) => Self::ListPayments {
                 list_payments: list_payments.into(),
             },
+            nip47::RequestParams::ListTransactions(list_transactions) => Self::ListTransactions {
+                list_transactions: list_transactions.into(),
+            },
             nip47::RequestParams::GetBalance => Self::GetBalance,
         }
     }
@@ -206,6 +217,9 @@ impl From<RequestParams> for nip47::RequestParams {
             RequestParams::ListPayments { list_payments } => {
                 Self::ListPayments(list_payments.into())
             }
+            RequestParams::ListTransactions { list_transactions } => {
+                Self::ListTransactions(list_transactions.into())
+            }
             RequestParams::GetBalance => Self::GetBalance,
         }
     }
@@ -440,6 +454,76 @@ impl From<ListPaymentsRequestParams> for nip47::ListPaymentsRequestParams {
     }
 }
 
+/// Transaction Type
+#[derive(Enum)]
+pub enum TransactionType {
+    /// Incoming payments
+    Incoming,
+    /// Outgoing payments
+    Outgoing,
+}
+
+impl From<nip47::TransactionType> for TransactionType {
+    fn from(value: nip47::TransactionType) -> Self {
+        match value {
+            nip47::TransactionType::Incoming => Self::Incoming,
+            nip47::TransactionType::Outgoing => Self::Outgoing,
+        }
+    }
+}
+
+impl From<TransactionType> for nip47::TransactionType {
+    fn from(value: TransactionType) -> Self {
+        match value {
+            TransactionType::Incoming => Self::Incoming,
+            TransactionType::Outgoing => Self::Outgoing,
+        }
+    }
+}
+
+/// List Transactions Request Params
+#[derive(Record)]
+pub struct ListTransactionsRequestParams {
+    /// Starting timestamp in seconds since epoch
+    pub from: Option<u64>,
+    /// Ending timestamp in seconds since epoch
+    pub until: Option<u64>,
+    /// Number of transactions to return
+    pub limit: Option<u64>,
+    /// Offset of the first transaction to return
+    pub offset: Option<u64>,
+    /// If true, include unpaid invoices
+    pub unpaid: Option<bool>,
+    /// Transaction type (all types if `None`)
+    pub transaction_type: Option<TransactionType>,
+}
+
+impl From<nip47::ListTransactionsRequestParams> for ListTransactionsRequestParams {
+    fn from(value: nip47::ListTransactionsRequestParams) -> Self {
+        Self {
+            from: value.from,
+            until: value.until,
+            limit: value.limit,
+            offset: value.offset,
+            unpaid: value.unpaid,
+            transaction_type: value.transaction_type.map(|t| t.into()),
+        }
+    }
+}
+
+impl From<ListTransactionsRequestParams> for nip47::ListTransactionsRequestParams {
+    fn from(value: ListTransactionsRequestParams) -> Self {
+        Self {
+            from: value.from,
+            until: value.until,
+            limit: value.limit,
+            offset: value.offset,
+            unpaid: value.unpaid,
+            transaction_type: value.transaction_type.map(|t| t.into()),
+        }
+    }
+}
+
 /// NIP47 Request
 #[derive(Object)]
 pub struct Request {
@@ -609,6 +693,69 @@ impl From<ListPaymentResponseResult> for nip47::ListPaymentResponseResult {
     }
 }
 
+/// NIP47 Response Result
+#[derive(Record)]
+pub struct TransactionResponseResult {
+    /// Transaction type
+    pub transaction_type: TransactionType,
+    /// Bolt11 invoice
+    pub invoice: Option<String>,
+    /// Invoice description
+    pub description: Option<String>,
+    /// Invoice description hash
+    pub description_hash: Option<String>,
+    /// Payment preimage
+    pub preimage: Option<String>,
+    /// Payment hash
+    pub payment_hash: String,
+    /// Amount in millisatoshis
+    pub amount: u64,
+    /// Fees paid in millisatoshis
+    pub fees_paid: u64,
+    /// Creation timestamp in seconds since epoch
+    pub created_at: u64,
+    /// Expiration timestamp in seconds since epoch
+    pub expires_at: Option<u64>,
+    /// Settlement timestamp in seconds since epoch
+    pub settled_at: Option<u64>,
+}
+
+impl From<nip47::TransactionResponseResult> for TransactionResponseResult {
+    fn from(value: nip47::TransactionResponseResult) -> Self {
+        Self {
+            transaction_type: value.transaction_type.into(),
+            invoice: value.invoice,
+            description: value.description,
+            description_hash: value.description_hash,
+            preimage: value.preimage,
+            payment_hash: value.payment_hash,
+            amount: value.amount,
+            fees_paid: value.fees_paid,
+            created_at: value.created_at,
+            expires_at: value.expires_at,
+            settled_at: value.settled_at,
+        }
+    }
+}
+
+impl From<TransactionResponseResult> for nip47::TransactionResponseResult {
+    fn from(value: TransactionResponseResult) -> Self {
+        Self {
+            transaction_type: value.transaction_type.into(),
+            invoice: value.invoice,
+            description: value.description,
+            description_hash: value.description_hash,
+            preimage: value.preimage,
+            payment_hash: value.payment_hash,
+            amount: value.amount,
+            fees_paid: value.fees_paid,
+            created_at: value.created_at,
+            expires_at: value.expires_at,
+            settled_at: value.settled_at,
+        }
+    }
+}
+
 /// Budget renewal type
 #[derive(Enum)]
 pub enum BudgetType {
@@ -702,6 +849,10 @@ pub enum ResponseResult {
     ListPayments {
         list_payments: Vec<ListPaymentResponseResult>,
     },
+    /// List Transactions
+    ListTransactions {
+        list_transactions: Vec<TransactionResponseResult>,
+    },
     /// Get Balance
     GetBalance {
         get_balance: GetBalanceResponseResult,
@@ -729,6 +880,13 @@ impl From<nip47::ResponseResult> for ResponseResult {
             nip47::ResponseResult::ListPayments(list_payments) => Self::ListPayments {
                 list_payments: list_payments.into_iter().map(|p| p.into()).collect(),
             },
+            nip47::ResponseResult::ListTransactions(list_transactions) => Self::ListTransactions {
+                list_transactions: list_transactions
+                    .transactions
+                    .into_iter()
+                    .map(|t| t.into())
+                    .collect(),
+            },
             nip47::ResponseResult::GetBalance(get_balance) => Self::GetBalance {
                 get_balance: get_balance.into(),
             },
@@ -751,6 +909,11 @@ impl From<ResponseResult> for nip47::ResponseResult {
             ResponseResult::ListPayments { list_payments } => {
                 Self::ListPayments(list_payments.into_iter().map(|p| p.into()).collect())
             }
+            ResponseResult::ListTransactions { list_transactions } => {
+                Self::ListTransactions(nip47::ListTransactionsResponseResult {
+                    transactions: list_transactions.into_iter().map(|t| t.into()).collect(),
+                })
+            }
             ResponseResult::GetBalance { get_balance } => Self::GetBalance(get_balance.into()),
         }
     }
@@ -153,7 +153,7 @@ impl Metadata {
     }
 
     pub fn get_custom_field(&self, key: String) -> Result<Option<JsonValue>> {
-        match self.inner.custom.get(&key).cloned() {
+        match self.inner.get_custom_field(key).cloned() {
             Some(value) => Ok(Some(value.try_into()?)),
             None => Ok(None),
         }
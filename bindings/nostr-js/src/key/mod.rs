@@ -94,4 +94,10 @@ impl JsKeys {
     pub fn secret_key(&self) -> Result<JsSecretKey> {
         Ok(self.inner.secret_key().map_err(into_err)?.into())
     }
+
+    /// Check if keys are watch-only (no secret key)
+    #[wasm_bindgen(js_name = isWatchOnly)]
+    pub fn is_watch_only(&self) -> bool {
+        self.inner.is_watch_only()
+    }
 }
@@ -6,6 +6,7 @@ use std::ops::Deref;
 use std::sync::Arc;
 
 use nostr_ffi::helper::unwrap_or_clone_arc;
+use nostr_sdk::client::DynNostrSigner;
 use nostr_sdk::database::DynNostrDatabase;
 use uniffi::Object;
 
@@ -34,7 +35,7 @@ impl ClientBuilder {
     }
 
     pub fn signer(self: Arc<Self>, signer: Arc<ClientSigner>) -> Self {
-        let signer: nostr_sdk::ClientSigner = signer.as_ref().deref().clone();
+        let signer: Arc<DynNostrSigner> = signer.as_ref().deref().clone();
         let mut builder = unwrap_or_clone_arc(self);
         builder.inner = builder.inner.signer(signer);
         builder
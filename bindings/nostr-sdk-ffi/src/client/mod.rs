@@ -13,6 +13,7 @@ use nostr_ffi::{
     RelayMessage,
 };
 use nostr_sdk::client::blocking::Client as ClientSdk;
+use nostr_sdk::client::DynNostrSigner;
 use nostr_sdk::relay::RelayPoolNotification as RelayPoolNotificationSdk;
 use nostr_sdk::{NegentropyOptions, Options as OptionsSdk};
 use uniffi::Object;
@@ -50,7 +51,7 @@ impl Client {
         let opts: OptionsSdk = opts.as_ref().deref().clone().shutdown_on_drop(true);
         let mut builder = nostr_sdk::ClientBuilder::new().opts(opts);
         if let Some(signer) = signer {
-            let signer: nostr_sdk::ClientSigner = signer.as_ref().deref().clone();
+            let signer: Arc<DynNostrSigner> = signer.as_ref().deref().clone();
             builder = builder.signer(signer);
         }
         Self {
@@ -70,8 +71,8 @@ impl Client {
         Arc::new(self.inner.database().into())
     }
 
-    pub fn start(&self) {
-        self.inner.start();
+    pub fn start(&self) -> Result<()> {
+        Ok(self.inner.start()?)
     }
 
     pub fn stop(&self) -> Result<()> {
@@ -172,17 +173,20 @@ impl Client {
     // TODO: add req_events_of_with_opts
 
     pub fn send_msg(&self, msg: ClientMessage) -> Result<()> {
-        Ok(self.inner.send_msg(msg.into())?)
+        self.inner.send_msg(msg.into())?;
+        Ok(())
     }
 
     pub fn send_msg_to(&self, url: String, msg: ClientMessage) -> Result<()> {
-        Ok(self.inner.send_msg_to(url, msg.into())?)
+        self.inner.send_msg_to(url, msg.into())?;
+        Ok(())
     }
 
     pub fn send_event(&self, event: Arc<Event>) -> Result<Arc<EventId>> {
         Ok(Arc::new(
             self.inner
                 .send_event(event.as_ref().deref().clone())?
+                .val
                 .into(),
         ))
     }
@@ -191,6 +195,7 @@ impl Client {
         Ok(Arc::new(
             self.inner
                 .send_event_to(url, event.as_ref().deref().clone())?
+                .val
                 .into(),
         ))
     }
@@ -202,6 +207,7 @@ impl Client {
         Ok(Arc::new(
             self.inner
                 .send_event_builder(builder.as_ref().deref().clone())?
+                .val
                 .into(),
         ))
     }
@@ -217,13 +223,17 @@ impl Client {
         Ok(Arc::new(
             self.inner
                 .send_event_builder_to(url, builder.as_ref().deref().clone())?
+                .val
                 .into(),
         ))
     }
 
     pub fn set_metadata(&self, metadata: Arc<Metadata>) -> Result<Arc<EventId>> {
         Ok(Arc::new(
-            self.inner.set_metadata(metadata.as_ref().deref())?.into(),
+            self.inner
+                .set_metadata(metadata.as_ref().deref())?
+                .val
+                .into(),
         ))
     }
 
@@ -236,6 +246,7 @@ impl Client {
         Ok(Arc::new(
             self.inner
                 .send_direct_msg(**receiver, msg, reply.map(|r| **r))?
+                .val
                 .into(),
         ))
     }
@@ -248,15 +259,17 @@ impl Client {
         Ok(Arc::new(
             self.inner
                 .file_metadata(description, metadata.as_ref().deref().clone())?
+                .val
                 .into(),
         ))
     }
 
     pub fn reconcile(&self, filter: Arc<Filter>) -> Result<()> {
-        Ok(self.inner.reconcile(
+        self.inner.reconcile(
             filter.as_ref().deref().clone(),
             NegentropyOptions::default(),
-        )?)
+        )?;
+        Ok(())
     }
 
     pub fn handle_notifications(self: Arc<Self>, handler: Box<dyn HandleNotification>) {
@@ -267,9 +280,9 @@ impl Client {
                     RelayPoolNotificationSdk::Message { relay_url, message } => {
                         handler.handle_msg(relay_url.to_string(), message.into())
                     }
-                    RelayPoolNotificationSdk::Event { relay_url, event } => {
-                        handler.handle(relay_url.to_string(), Arc::new(event.into()))
-                    }
+                    RelayPoolNotificationSdk::Event {
+                        relay_url, event, ..
+                    } => handler.handle(relay_url.to_string(), Arc::new(event.into())),
                     _ => (),
                 }
 
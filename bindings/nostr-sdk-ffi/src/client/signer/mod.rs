@@ -6,7 +6,7 @@ use std::ops::Deref;
 use std::sync::Arc;
 
 use nostr_ffi::Keys;
-use nostr_sdk::client::signer;
+use nostr_sdk::client::signer::{DynNostrSigner, IntoNostrSigner};
 use uniffi::Object;
 
 pub mod nip46;
@@ -15,19 +15,19 @@ use self::nip46::Nip46Signer;
 
 #[derive(Object)]
 pub struct ClientSigner {
-    inner: signer::ClientSigner,
+    inner: Arc<DynNostrSigner>,
 }
 
 impl Deref for ClientSigner {
-    type Target = signer::ClientSigner;
+    type Target = Arc<DynNostrSigner>;
 
     fn deref(&self) -> &Self::Target {
         &self.inner
     }
 }
 
-impl From<signer::ClientSigner> for ClientSigner {
-    fn from(inner: signer::ClientSigner) -> Self {
+impl From<Arc<DynNostrSigner>> for ClientSigner {
+    fn from(inner: Arc<DynNostrSigner>) -> Self {
         Self { inner }
     }
 }
@@ -37,14 +37,14 @@ impl ClientSigner {
     #[uniffi::constructor]
     pub fn keys(keys: Arc<Keys>) -> Self {
         Self {
-            inner: signer::ClientSigner::Keys(keys.as_ref().deref().clone()),
+            inner: keys.as_ref().deref().clone().into_nostr_signer(),
         }
     }
 
     #[uniffi::constructor]
     pub fn nip46(nip46: Arc<Nip46Signer>) -> Self {
         Self {
-            inner: signer::ClientSigner::NIP46(nip46.as_ref().deref().clone()),
+            inner: nip46.as_ref().deref().clone().into_nostr_signer(),
         }
     }
 }
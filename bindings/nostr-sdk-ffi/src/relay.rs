@@ -98,6 +98,8 @@ pub enum RelayStatus {
     Stopped,
     /// Relay completely disconnected
     Terminated,
+    /// Auto reconnection given up, after too many failed attempts
+    GaveUp,
 }
 
 impl From<nostr_sdk::RelayStatus> for RelayStatus {
@@ -110,6 +112,7 @@ impl From<nostr_sdk::RelayStatus> for RelayStatus {
             nostr_sdk::RelayStatus::Disconnected => Self::Disconnected,
             nostr_sdk::RelayStatus::Stopped => Self::Stopped,
             nostr_sdk::RelayStatus::Terminated => Self::Terminated,
+            nostr_sdk::RelayStatus::GaveUp => Self::GaveUp,
         }
     }
 }
@@ -224,11 +227,14 @@ impl Relay {
     }
 
     pub fn req_events_of(&self, filters: Vec<Arc<Filter>>, timeout: Duration) {
-        let filters = filters
-            .into_iter()
-            .map(|f| f.as_ref().deref().clone())
-            .collect();
-        self.inner
-            .req_events_of(filters, timeout, FilterOptions::ExitOnEOSE);
+        block_on(async move {
+            let filters = filters
+                .into_iter()
+                .map(|f| f.as_ref().deref().clone())
+                .collect();
+            self.inner
+                .req_events_of(filters, timeout, FilterOptions::ExitOnEOSE)
+                .await;
+        })
     }
 }
@@ -198,7 +198,8 @@ impl JsClient {
         self.inner
             .send_msg(msg.deref().clone())
             .await
-            .map_err(into_err)
+            .map_err(into_err)?;
+        Ok(())
     }
 
     /// Send client message to a specific relay
@@ -207,7 +208,8 @@ impl JsClient {
         self.inner
             .send_msg_to(url, msg.deref().clone())
             .await
-            .map_err(into_err)
+            .map_err(into_err)?;
+        Ok(())
     }
 
     /// Send event
@@ -220,7 +222,7 @@ impl JsClient {
             .send_event(event.deref().clone())
             .await
             .map_err(into_err)
-            .map(|id| id.into())
+            .map(|output| output.val.into())
     }
 
     /// Send event to specific relay
@@ -233,7 +235,7 @@ impl JsClient {
             .send_event_to(url, event.deref().clone())
             .await
             .map_err(into_err)
-            .map(|id| id.into())
+            .map(|output| output.val.into())
     }
 
     /// Take an [`EventBuilder`], sign it by using the [`ClientSigner`] and broadcast to all relays.
@@ -245,7 +247,7 @@ impl JsClient {
             .send_event_builder(builder.deref().clone())
             .await
             .map_err(into_err)
-            .map(|id| id.into())
+            .map(|output| output.val.into())
     }
 
     /// Take an [`EventBuilder`], sign it by using the [`ClientSigner`] and broadcast to specific relays.
@@ -261,7 +263,7 @@ impl JsClient {
             .send_event_builder_to(url, builder.deref().clone())
             .await
             .map_err(into_err)
-            .map(|id| id.into())
+            .map(|output| output.val.into())
     }
 
     /// Update metadata
@@ -273,7 +275,7 @@ impl JsClient {
             .set_metadata(metadata.deref())
             .await
             .map_err(into_err)
-            .map(|id| id.into())
+            .map(|output| output.val.into())
     }
 
     /// Publish text note
@@ -285,7 +287,7 @@ impl JsClient {
             .publish_text_note(content, tags.into_iter().map(|t| t.into()))
             .await
             .map_err(into_err)
-            .map(|id| id.into())
+            .map(|output| output.val.into())
     }
 
     /// Set contact list
@@ -298,7 +300,7 @@ impl JsClient {
             .set_contact_list(list)
             .await
             .map_err(into_err)
-            .map(|id| id.into())
+            .map(|output| output.val.into())
     }
 
     // /// Get contact list
@@ -358,7 +360,7 @@ impl JsClient {
             .send_direct_msg(receiver.into(), msg, reply.map(|id| id.into()))
             .await
             .map_err(into_err)
-            .map(|id| id.into())
+            .map(|output| output.val.into())
     }
 
     /// Repost event
@@ -372,7 +374,7 @@ impl JsClient {
             .repost_event(event_id.into(), public_key.into())
             .await
             .map_err(into_err)
-            .map(|id| id.into())
+            .map(|output| output.val.into())
     }
 
     /// Delete event
@@ -384,7 +386,7 @@ impl JsClient {
             .delete_event(**event_id)
             .await
             .map_err(into_err)
-            .map(|id| id.into())
+            .map(|output| output.val.into())
     }
 
     /// Like event
@@ -395,7 +397,7 @@ impl JsClient {
             .like(event_id.into(), public_key.into())
             .await
             .map_err(into_err)
-            .map(|id| id.into())
+            .map(|output| output.val.into())
     }
 
     /// Disike event
@@ -410,7 +412,7 @@ impl JsClient {
             .dislike(event_id.into(), public_key.into())
             .await
             .map_err(into_err)
-            .map(|id| id.into())
+            .map(|output| output.val.into())
     }
 
     /// React to an [`Event`]
@@ -426,7 +428,7 @@ impl JsClient {
             .reaction(event_id.into(), public_key.into(), content)
             .await
             .map_err(into_err)
-            .map(|id| id.into())
+            .map(|output| output.val.into())
     }
 
     /// Create new channel
@@ -438,7 +440,7 @@ impl JsClient {
             .new_channel(metadata.deref())
             .await
             .map_err(into_err)
-            .map(|id| id.into())
+            .map(|output| output.val.into())
     }
 
     /// Update channel metadata
@@ -459,7 +461,7 @@ impl JsClient {
             .set_channel_metadata(channel_id.into(), relay_url, metadata.deref())
             .await
             .map_err(into_err)
-            .map(|id| id.into())
+            .map(|output| output.val.into())
     }
 
     /// Send message to channel
@@ -477,7 +479,7 @@ impl JsClient {
             .send_channel_msg(channel_id.into(), relay_url, msg)
             .await
             .map_err(into_err)
-            .map(|id| id.into())
+            .map(|output| output.val.into())
     }
 
     /// Hide channel message
@@ -493,7 +495,7 @@ impl JsClient {
             .hide_channel_msg(message_id.into(), reason)
             .await
             .map_err(into_err)
-            .map(|id| id.into())
+            .map(|output| output.val.into())
     }
 
     /// Mute channel user
@@ -509,7 +511,7 @@ impl JsClient {
             .mute_channel_user(pubkey.into(), reason)
             .await
             .map_err(into_err)
-            .map(|id| id.into())
+            .map(|output| output.val.into())
     }
 
     /// Negentropy reconciliation
@@ -519,7 +521,8 @@ impl JsClient {
         self.inner
             .reconcile(filter.deref().clone(), NegentropyOptions::default())
             .await
-            .map_err(into_err)
+            .map_err(into_err)?;
+        Ok(())
     }
 
     /// Handle notifications
@@ -575,7 +578,7 @@ impl JsClient {
                             return Ok(true);
                         }
                     }
-                    RelayPoolNotification::Event { relay_url, event } => {
+                    RelayPoolNotification::Event { relay_url, event, .. } => {
                         let event: JsEvent = event.into();
                         if callback.handle_event(relay_url.to_string(), event).await.as_bool().unwrap_or_default() {
                             tracing::info!("Received `true` in `handleEvent`: exiting from `handleNotifications`");
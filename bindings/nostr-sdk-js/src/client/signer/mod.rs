@@ -3,10 +3,11 @@
 // Distributed under the MIT software license
 
 use std::ops::Deref;
+use std::sync::Arc;
 
 use nostr_js::key::JsKeys;
 use nostr_js::nips::nip07::JsNip07Signer;
-use nostr_sdk::ClientSigner;
+use nostr_sdk::client::{DynNostrSigner, IntoNostrSigner};
 use wasm_bindgen::prelude::*;
 
 pub mod nip46;
@@ -15,19 +16,19 @@ use self::nip46::JsNip46Signer;
 
 #[wasm_bindgen(js_name = ClientSigner)]
 pub struct JsClientSigner {
-    inner: nostr_sdk::ClientSigner,
+    inner: Arc<DynNostrSigner>,
 }
 
 impl Deref for JsClientSigner {
-    type Target = ClientSigner;
+    type Target = Arc<DynNostrSigner>;
 
     fn deref(&self) -> &Self::Target {
         &self.inner
     }
 }
 
-impl From<ClientSigner> for JsClientSigner {
-    fn from(inner: ClientSigner) -> Self {
+impl From<Arc<DynNostrSigner>> for JsClientSigner {
+    fn from(inner: Arc<DynNostrSigner>) -> Self {
         Self { inner }
     }
 }
@@ -37,21 +38,21 @@ impl JsClientSigner {
     /// Private Key Client Signer
     pub fn keys(keys: &JsKeys) -> Self {
         Self {
-            inner: ClientSigner::Keys(keys.deref().clone()),
+            inner: keys.deref().clone().into_nostr_signer(),
         }
     }
 
     /// NIP07 Client Signer
     pub fn nip07(signer: &JsNip07Signer) -> Self {
         Self {
-            inner: ClientSigner::NIP07(signer.deref().clone()),
+            inner: signer.deref().clone().into_nostr_signer(),
         }
     }
 
     /// NIP46 Client Signer
     pub fn nip46(signer: &JsNip46Signer) -> Self {
         Self {
-            inner: ClientSigner::NIP46(signer.deref().clone()),
+            inner: signer.deref().clone().into_nostr_signer(),
         }
     }
 }
@@ -40,6 +40,8 @@ pub enum JsRelayStatus {
     Stopped,
     /// Relay completely disconnected
     Terminated,
+    /// Auto reconnection given up, after too many failed attempts
+    GaveUp,
 }
 
 impl From<RelayStatus> for JsRelayStatus {
@@ -52,6 +54,7 @@ impl From<RelayStatus> for JsRelayStatus {
             RelayStatus::Disconnected => Self::Disconnected,
             RelayStatus::Stopped => Self::Stopped,
             RelayStatus::Terminated => Self::Terminated,
+            RelayStatus::GaveUp => Self::GaveUp,
         }
     }
 }
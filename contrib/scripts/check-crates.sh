@@ -36,6 +36,11 @@ buildargs=(
     "-p nostr-sdk --features sqlite"
 )
 
+# `proptest` requires a newer rust version than the MSRV
+if [ "$is_msrv" == false ]; then
+    buildargs+=("-p nostr-test-vectors")
+fi
+
 for arg in "${buildargs[@]}"; do
     if [[ $version == "" ]]; then
         echo  "Checking '$arg' [default]"
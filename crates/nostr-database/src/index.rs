@@ -15,6 +15,9 @@ use nostr::{Alphabet, Event, EventId, Filter, GenericTagValue, Kind, Timestamp};
 use thiserror::Error;
 use tokio::sync::RwLock;
 
+use crate::integrity::IntegrityIssue;
+#[cfg(feature = "language")]
+use crate::language;
 use crate::raw::RawEvent;
 use crate::tag_indexes::{hash, TagIndexValues, TagIndexes, TAG_INDEX_VALUE_SIZE};
 use crate::Order;
@@ -268,6 +271,32 @@ impl<'a> EventOrRawEvent<'a> {
         }
     }
 
+    /// Detect content language, if not already labeled
+    #[cfg(feature = "language")]
+    fn language(&self) -> Option<&'static str> {
+        let (kind, content) = match self {
+            Self::Event(e) => (e.kind(), e.content()),
+            Self::EventOwned(e) => (e.kind(), e.content()),
+            Self::Raw(r) => (r.kind, r.content.as_str()),
+        };
+
+        if !language::is_detectable(&kind) {
+            return None;
+        }
+
+        let labeled: bool = match self {
+            Self::Event(e) => language::has_language_label(e.iter_tags().map(|t| t.as_vec())),
+            Self::EventOwned(e) => language::has_language_label(e.iter_tags().map(|t| t.as_vec())),
+            Self::Raw(r) => language::has_language_label(r.tags.iter().cloned()),
+        };
+
+        if labeled {
+            return None;
+        }
+
+        language::detect_language(content)
+    }
+
     fn identifier(&self) -> Option<&str> {
         match self {
             Self::Event(e) => e.identifier(),
@@ -529,12 +558,24 @@ impl DatabaseIndexes {
 
         // Insert event
         if should_insert {
+            #[cfg(feature = "language")]
+            let language: Option<&str> = event.language();
+
+            #[allow(unused_mut)]
+            let mut tags: TagIndexes = event.tags();
+
+            // Index detected language as `l` label
+            #[cfg(feature = "language")]
+            if let Some(language) = language {
+                tags.entry(Alphabet::L).or_default().insert(hash(language));
+            }
+
             let e: ArcEventIndex = Arc::new(EventIndex {
                 created_at,
                 event_id: event_id.clone(),
                 pubkey: pubkey_prefix,
                 kind,
-                tags: Arc::new(event.tags()),
+                tags: Arc::new(tags),
             });
 
             index.insert(e.clone());
@@ -833,6 +874,75 @@ impl DatabaseIndexes {
         }
     }
 
+    /// Check indexes consistency, also against the [`EventId`]s of the stored events
+    #[tracing::instrument(skip_all)]
+    pub async fn check_integrity(
+        &self,
+        stored: &HashSet<EventId>,
+    ) -> HashMap<EventId, IntegrityIssue> {
+        let index = self.index.read().await;
+        let ids_index = self.ids_index.read().await;
+        let kind_author_index = self.kind_author_index.read().await;
+        let kind_author_tags_index = self.kind_author_tags_index.read().await;
+
+        let mut issues: HashMap<EventId, IntegrityIssue> = HashMap::new();
+
+        // Every indexed event must be in both the main and the IDs indexes
+        for ev in index.iter() {
+            if ids_index.get(&ev.event_id) != Some(ev) {
+                issues.insert(*ev.event_id, IntegrityIssue::InconsistentIndex);
+            }
+        }
+
+        for ev in ids_index
+            .values()
+            .chain(kind_author_index.values())
+            .chain(kind_author_tags_index.values())
+        {
+            if !index.contains(ev) {
+                issues.insert(*ev.event_id, IntegrityIssue::InconsistentIndex);
+            }
+        }
+
+        // Compare with stored events
+        for event_id in ids_index.keys() {
+            if !stored.contains(event_id) {
+                issues
+                    .entry(**event_id)
+                    .or_insert(IntegrityIssue::NotStored);
+            }
+        }
+
+        for event_id in stored.iter() {
+            if !ids_index.contains_key(event_id) {
+                issues
+                    .entry(*event_id)
+                    .or_insert(IntegrityIssue::NotIndexed);
+            }
+        }
+
+        issues
+    }
+
+    /// Remove events from indexes
+    ///
+    /// Unlike deletion, the [`EventId`]s aren't marked as deleted, so the events can be indexed again.
+    pub async fn remove<'a, I>(&self, ids: I)
+    where
+        I: IntoIterator<Item = &'a EventId>,
+    {
+        let mut index = self.index.write().await;
+        let mut ids_index = self.ids_index.write().await;
+        let mut kind_author_index = self.kind_author_index.write().await;
+        let mut kind_author_tags_index = self.kind_author_tags_index.write().await;
+
+        let ids: HashSet<&EventId> = ids.into_iter().collect();
+        index.retain(|ev| !ids.contains(ev.event_id.as_ref()));
+        ids_index.retain(|event_id, _| !ids.contains(event_id.as_ref()));
+        kind_author_index.retain(|_, ev| !ids.contains(ev.event_id.as_ref()));
+        kind_author_tags_index.retain(|_, ev| !ids.contains(ev.event_id.as_ref()));
+    }
+
     /// Clear indexes
     pub async fn clear(&self) {
         let mut index = self.index.write().await;
@@ -847,7 +957,7 @@ impl DatabaseIndexes {
 #[cfg(test)]
 mod tests {
     use nostr::secp256k1::SecretKey;
-    use nostr::{FromBech32, JsonUtil, Keys};
+    use nostr::{EventBuilder, FromBech32, JsonUtil, Keys};
 
     use super::*;
 
@@ -1041,4 +1151,90 @@ mod tests {
             vec![Event::from_json(EVENTS[13]).unwrap().id(),]
         );
     }
+
+    #[tokio::test]
+    #[cfg(feature = "language")]
+    async fn test_language_index() {
+        use nostr::{Tag, TagKind};
+
+        let keys = Keys::generate();
+        let indexes = DatabaseIndexes::new();
+
+        let english = EventBuilder::text_note(
+            "This is a fairly long english sentence, written to be detected reliably.",
+            [],
+        )
+        .to_event(&keys)
+        .unwrap();
+        let italian = EventBuilder::text_note(
+            "Questa è una frase italiana abbastanza lunga da essere riconosciuta.",
+            [],
+        )
+        .to_event(&keys)
+        .unwrap();
+        let labeled = EventBuilder::text_note(
+            "This is a fairly long english sentence, but labeled as italian.",
+            [Tag::Generic(
+                TagKind::Custom(String::from("l")),
+                vec![String::from("it"), String::from("ISO-639-1")],
+            )],
+        )
+        .to_event(&keys)
+        .unwrap();
+
+        let bare_labeled = EventBuilder::text_note(
+            "This is another long english sentence, but labeled as italian without namespace.",
+            [Tag::Generic(
+                TagKind::Custom(String::from("l")),
+                vec![String::from("it")],
+            )],
+        )
+        .to_event(&keys)
+        .unwrap();
+
+        for event in [&english, &italian, &labeled, &bare_labeled] {
+            indexes.index_event(event).await;
+        }
+
+        assert_eq!(
+            indexes
+                .query([Filter::new().languages(["en"])], Order::Desc)
+                .await,
+            vec![english.id()]
+        );
+
+        let mut italian_ids = indexes
+            .query([Filter::new().languages(["it"])], Order::Desc)
+            .await;
+        italian_ids.sort();
+        let mut expected = vec![italian.id(), labeled.id(), bare_labeled.id()];
+        expected.sort();
+        assert_eq!(italian_ids, expected);
+    }
+
+    #[tokio::test]
+    async fn test_check_integrity() {
+        let indexes = DatabaseIndexes::new();
+
+        let keys = Keys::generate();
+        let a = EventBuilder::text_note("A", []).to_event(&keys).unwrap();
+        let b = EventBuilder::text_note("B", []).to_event(&keys).unwrap();
+        let c = EventBuilder::text_note("C", []).to_event(&keys).unwrap();
+        indexes.index_event(&a).await;
+        indexes.index_event(&b).await;
+
+        // `b` indexed but not stored, `c` stored but not indexed
+        let stored: HashSet<EventId> = [a.id(), c.id()].into_iter().collect();
+        let issues = indexes.check_integrity(&stored).await;
+        assert_eq!(issues.len(), 2);
+        assert_eq!(issues.get(&b.id()), Some(&IntegrityIssue::NotStored));
+        assert_eq!(issues.get(&c.id()), Some(&IntegrityIssue::NotIndexed));
+
+        // Remove `b` and index `c`
+        indexes.remove([&b.id()]).await;
+        assert!(!indexes.has_event_id_been_deleted(&b.id()).await);
+        indexes.index_event(&c).await;
+        assert!(indexes.check_integrity(&stored).await.is_empty());
+        assert_eq!(indexes.count([Filter::new()]).await, 2);
+    }
 }
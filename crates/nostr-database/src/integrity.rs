@@ -0,0 +1,136 @@
+// Copyright (c) 2022-2023 Yuki Kishimoto
+// Copyright (c) 2023-2024 Rust Nostr Developers
+// Distributed under the MIT software license
+
+//! Database integrity
+
+use std::collections::HashMap;
+use std::fmt;
+
+use nostr::{Event, EventId};
+
+/// Integrity issue
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IntegrityIssue {
+    /// Stored event can't be decoded
+    Undecodable,
+    /// Event ID doesn't match the event content or the key under which is stored
+    InvalidId,
+    /// Invalid event signature
+    InvalidSignature,
+    /// Event stored but not indexed (can't be queried)
+    NotIndexed,
+    /// Event indexed but not stored
+    NotStored,
+    /// Event index entries are inconsistent
+    InconsistentIndex,
+}
+
+impl IntegrityIssue {
+    /// Check if the issue is only in the indexes (the event can be indexed again)
+    pub fn is_index_issue(&self) -> bool {
+        matches!(self, Self::NotIndexed | Self::InconsistentIndex)
+    }
+}
+
+impl fmt::Display for IntegrityIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Undecodable => write!(f, "undecodable"),
+            Self::InvalidId => write!(f, "invalid id"),
+            Self::InvalidSignature => write!(f, "invalid signature"),
+            Self::NotIndexed => write!(f, "not indexed"),
+            Self::NotStored => write!(f, "not stored"),
+            Self::InconsistentIndex => write!(f, "inconsistent index"),
+        }
+    }
+}
+
+/// Integrity report
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IntegrityReport {
+    /// Number of checked stored events
+    pub checked: usize,
+    /// Corrupted entries
+    pub corrupted: HashMap<EventId, IntegrityIssue>,
+    /// Corrupted entries have been pruned
+    pub pruned: bool,
+    /// Number of events indexed again, instead of being pruned
+    pub reindexed: usize,
+}
+
+impl IntegrityReport {
+    /// Check if no corrupted entries were found
+    pub fn is_ok(&self) -> bool {
+        self.corrupted.is_empty()
+    }
+
+    /// Add corrupted entry
+    ///
+    /// Keep the first [`IntegrityIssue`] found for the [`EventId`].
+    pub fn add(&mut self, event_id: EventId, issue: IntegrityIssue) {
+        self.corrupted.entry(event_id).or_insert(issue);
+    }
+
+    /// Verify stored [`Event`] and add it to the corrupted entries if invalid
+    ///
+    /// `event_id` is the [`EventId`] under which the [`Event`] is stored.
+    pub fn verify_event(&mut self, event_id: EventId, event: &Event) {
+        self.checked += 1;
+        if event_id != event.id() || event.verify_id().is_err() {
+            self.add(event_id, IntegrityIssue::InvalidId);
+        } else if event.verify_signature().is_err() {
+            self.add(event_id, IntegrityIssue::InvalidSignature);
+        }
+    }
+}
+
+impl Extend<(EventId, IntegrityIssue)> for IntegrityReport {
+    fn extend<T>(&mut self, iter: T)
+    where
+        T: IntoIterator<Item = (EventId, IntegrityIssue)>,
+    {
+        for (event_id, issue) in iter.into_iter() {
+            self.add(event_id, issue);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nostr::{EventBuilder, JsonUtil, Keys};
+
+    use super::*;
+
+    #[test]
+    fn test_verify_event() {
+        let keys = Keys::generate();
+        let event = EventBuilder::text_note("Test", []).to_event(&keys).unwrap();
+
+        let mut report = IntegrityReport::default();
+        report.verify_event(event.id(), &event);
+        assert!(report.is_ok());
+
+        // Stored under another ID
+        let other = EventBuilder::text_note("Other", [])
+            .to_event(&keys)
+            .unwrap();
+        report.verify_event(other.id(), &event);
+        assert_eq!(
+            report.corrupted.get(&other.id()),
+            Some(&IntegrityIssue::InvalidId)
+        );
+
+        // Tampered content
+        let json = event.as_json().replace("Test", "Tampered");
+        let tampered = Event::from_json(json).unwrap();
+        report.verify_event(tampered.id(), &tampered);
+        assert_eq!(
+            report.corrupted.get(&tampered.id()),
+            Some(&IntegrityIssue::InvalidId)
+        );
+
+        assert_eq!(report.checked, 3);
+        assert!(!report.is_ok());
+    }
+}
@@ -0,0 +1,83 @@
+// Copyright (c) 2022-2023 Yuki Kishimoto
+// Copyright (c) 2023-2024 Rust Nostr Developers
+// Distributed under the MIT software license
+
+//! Content language detection
+//!
+//! The detected language is indexed as NIP32 `l` label (ISO-639-1 code),
+//! so events can be filtered with `Filter::languages`.
+//!
+//! The detected language is known only by the database indexes: relays and any other filter matching
+//! see only the `l` tags of the event.
+
+use isolang::Language;
+use nostr::Kind;
+
+/// NIP32 language label namespace
+pub const LANGUAGE_NAMESPACE: &str = "ISO-639-1";
+
+/// Detect the language of a text
+///
+/// Return the ISO-639-1 code (ex. `en`) or `None` if the detection is not reliable.
+pub fn detect_language<S>(text: S) -> Option<&'static str>
+where
+    S: AsRef<str>,
+{
+    let info = whatlang::detect(text.as_ref())?;
+    if !info.is_reliable() {
+        return None;
+    }
+    Language::from_639_3(info.lang().code())?.to_639_1()
+}
+
+/// Check if the content of the [`Kind`] should be language detected
+pub(crate) fn is_detectable(kind: &Kind) -> bool {
+    matches!(kind, Kind::TextNote | Kind::LongFormTextNote)
+}
+
+/// Check if the tags already include a language label
+///
+/// An `l` tag is a language label if has the ISO-639-1 namespace or, without namespace,
+/// if its value is an ISO-639-1 code (`Filter::languages` match it anyway).
+pub(crate) fn has_language_label<I, S>(tags: I) -> bool
+where
+    I: IntoIterator<Item = Vec<S>>,
+    S: AsRef<str>,
+{
+    tags.into_iter().any(|t| match t.as_slice() {
+        [kind, _, namespace, ..] if kind.as_ref() == "l" => {
+            namespace.as_ref() == LANGUAGE_NAMESPACE
+        }
+        [kind, value] if kind.as_ref() == "l" => Language::from_639_1(value.as_ref()).is_some(),
+        _ => false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_language() {
+        assert_eq!(
+            detect_language(
+                "This is a fairly long english sentence, written to be detected reliably."
+            ),
+            Some("en")
+        );
+        assert_eq!(
+            detect_language("Questa è una frase italiana abbastanza lunga da essere riconosciuta."),
+            Some("it")
+        );
+        assert_eq!(detect_language("gm"), None);
+    }
+
+    #[test]
+    fn test_has_language_label() {
+        assert!(has_language_label(vec![vec!["l", "en", "ISO-639-1"]]));
+        assert!(has_language_label(vec![vec!["l", "en"]]));
+        assert!(!has_language_label(vec![vec!["l", "en", "ugc"]]));
+        assert!(!has_language_label(vec![vec!["l", "funny"]]));
+        assert!(!has_language_label(vec![vec!["t", "nostr"]]));
+    }
+}
@@ -0,0 +1,319 @@
+// Copyright (c) 2022-2023 Yuki Kishimoto
+// Copyright (c) 2023-2024 Rust Nostr Developers
+// Distributed under the MIT software license
+
+//! Lazy database
+//!
+//! Open (and migrate) the database on first use instead of at construction time.
+
+use std::collections::HashSet;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use nostr::nips::nip01::Coordinate;
+use nostr::{Event, EventId, Filter, Timestamp, Url};
+use tokio::sync::OnceCell;
+
+use crate::{
+    Backend, DatabaseError, DatabaseOptions, DynNostrDatabase, IntegrityReport, IntoNostrDatabase,
+    NostrDatabase, Order, SendOutsideWasm,
+};
+
+#[cfg(not(target_arch = "wasm32"))]
+type BoxedFuture<T> = Pin<Box<dyn Future<Output = T> + Send>>;
+#[cfg(target_arch = "wasm32")]
+type BoxedFuture<T> = Pin<Box<dyn Future<Output = T>>>;
+
+type Factory = dyn Fn() -> BoxedFuture<Result<Arc<DynNostrDatabase>, DatabaseError>> + Send + Sync;
+
+type ProgressCallback = dyn Fn(DatabaseStatus) + Send + Sync;
+
+/// Database opening status
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DatabaseStatus {
+    /// Opening (and migrating) the database
+    Opening,
+    /// Database ready
+    Ready,
+    /// Failed to open the database
+    Failed,
+}
+
+/// Lazy database
+///
+/// Wrap a database factory: the database is opened the first time [`LazyDatabase::open`]
+/// is called or the first time it's accessed. If opening fails, the error is returned
+/// and the next access will retry.
+pub struct LazyDatabase {
+    factory: Arc<Factory>,
+    progress: Option<Arc<ProgressCallback>>,
+    database: OnceCell<Arc<DynNostrDatabase>>,
+}
+
+impl fmt::Debug for LazyDatabase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LazyDatabase")
+            .field("database", &self.database.get())
+            .finish()
+    }
+}
+
+impl LazyDatabase {
+    /// New lazy database from factory
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use nostr_database::lazy::LazyDatabase;
+    /// use nostr_database::{DatabaseError, MemoryDatabase};
+    ///
+    /// let database = LazyDatabase::new(|| async {
+    ///     Ok::<MemoryDatabase, DatabaseError>(MemoryDatabase::default())
+    /// });
+    /// ```
+    pub fn new<F, Fut, D, E>(factory: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<D, E>> + SendOutsideWasm + 'static,
+        D: IntoNostrDatabase,
+        E: Into<DatabaseError>,
+    {
+        Self {
+            factory: Arc::new(move || {
+                let fut = factory();
+                Box::pin(async move {
+                    fut.await
+                        .map(IntoNostrDatabase::into_nostr_database)
+                        .map_err(Into::into)
+                })
+            }),
+            progress: None,
+            database: OnceCell::new(),
+        }
+    }
+
+    /// Set progress callback
+    pub fn progress<F>(self, callback: F) -> Self
+    where
+        F: Fn(DatabaseStatus) + Send + Sync + 'static,
+    {
+        Self {
+            progress: Some(Arc::new(callback)),
+            ..self
+        }
+    }
+
+    /// Check if the database is already open
+    pub fn is_open(&self) -> bool {
+        self.database.initialized()
+    }
+
+    /// Open the database, if not already open
+    pub async fn open(&self) -> Result<&Arc<DynNostrDatabase>, DatabaseError> {
+        self.database
+            .get_or_try_init(|| async {
+                self.notify(DatabaseStatus::Opening);
+                match (self.factory)().await {
+                    Ok(database) => {
+                        self.notify(DatabaseStatus::Ready);
+                        Ok(database)
+                    }
+                    Err(e) => {
+                        tracing::error!("Impossible to open database: {e}");
+                        self.notify(DatabaseStatus::Failed);
+                        Err(e)
+                    }
+                }
+            })
+            .await
+    }
+
+    fn notify(&self, status: DatabaseStatus) {
+        if let Some(progress) = &self.progress {
+            progress(status);
+        }
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl NostrDatabase for LazyDatabase {
+    type Err = DatabaseError;
+
+    fn backend(&self) -> Backend {
+        match self.database.get() {
+            Some(database) => database.backend(),
+            None => Backend::Custom(String::from("lazy")),
+        }
+    }
+
+    fn opts(&self) -> DatabaseOptions {
+        match self.database.get() {
+            Some(database) => database.opts(),
+            None => DatabaseOptions::default(),
+        }
+    }
+
+    async fn save_event(&self, event: &Event) -> Result<bool, Self::Err> {
+        self.open().await?.save_event(event).await
+    }
+
+    async fn has_event_already_been_saved(&self, event_id: &EventId) -> Result<bool, Self::Err> {
+        self.open()
+            .await?
+            .has_event_already_been_saved(event_id)
+            .await
+    }
+
+    async fn has_event_already_been_seen(&self, event_id: &EventId) -> Result<bool, Self::Err> {
+        self.open()
+            .await?
+            .has_event_already_been_seen(event_id)
+            .await
+    }
+
+    async fn has_event_id_been_deleted(&self, event_id: &EventId) -> Result<bool, Self::Err> {
+        self.open().await?.has_event_id_been_deleted(event_id).await
+    }
+
+    async fn has_coordinate_been_deleted(
+        &self,
+        coordinate: &Coordinate,
+        timestamp: Timestamp,
+    ) -> Result<bool, Self::Err> {
+        self.open()
+            .await?
+            .has_coordinate_been_deleted(coordinate, timestamp)
+            .await
+    }
+
+    async fn event_id_seen(&self, event_id: EventId, relay_url: Url) -> Result<(), Self::Err> {
+        self.open().await?.event_id_seen(event_id, relay_url).await
+    }
+
+    async fn event_seen_on_relays(
+        &self,
+        event_id: EventId,
+    ) -> Result<Option<HashSet<Url>>, Self::Err> {
+        self.open().await?.event_seen_on_relays(event_id).await
+    }
+
+    async fn event_by_id(&self, event_id: EventId) -> Result<Event, Self::Err> {
+        self.open().await?.event_by_id(event_id).await
+    }
+
+    async fn count(&self, filters: Vec<Filter>) -> Result<usize, Self::Err> {
+        self.open().await?.count(filters).await
+    }
+
+    async fn query(&self, filters: Vec<Filter>, order: Order) -> Result<Vec<Event>, Self::Err> {
+        self.open().await?.query(filters, order).await
+    }
+
+    async fn event_ids_by_filters(
+        &self,
+        filters: Vec<Filter>,
+        order: Order,
+    ) -> Result<Vec<EventId>, Self::Err> {
+        self.open()
+            .await?
+            .event_ids_by_filters(filters, order)
+            .await
+    }
+
+    async fn negentropy_items(
+        &self,
+        filter: Filter,
+    ) -> Result<Vec<(EventId, Timestamp)>, Self::Err> {
+        self.open().await?.negentropy_items(filter).await
+    }
+
+    async fn wipe(&self) -> Result<(), Self::Err> {
+        self.open().await?.wipe().await
+    }
+
+    async fn verify_integrity(&self, prune: bool) -> Result<IntegrityReport, Self::Err> {
+        self.open().await?.verify_integrity(prune).await
+    }
+
+    async fn save_scheduled_event(&self, event: &Event) -> Result<(), Self::Err> {
+        self.open().await?.save_scheduled_event(event).await
+    }
+
+    async fn scheduled_events(&self) -> Result<Vec<Event>, Self::Err> {
+        self.open().await?.scheduled_events().await
+    }
+
+    async fn remove_scheduled_event(&self, event_id: EventId) -> Result<bool, Self::Err> {
+        self.open().await?.remove_scheduled_event(event_id).await
+    }
+
+    async fn save_outbox_event(&self, event: &Event) -> Result<(), Self::Err> {
+        self.open().await?.save_outbox_event(event).await
+    }
+
+    async fn outbox_events(&self) -> Result<Vec<Event>, Self::Err> {
+        self.open().await?.outbox_events().await
+    }
+
+    async fn remove_outbox_event(&self, event_id: EventId) -> Result<bool, Self::Err> {
+        self.open().await?.remove_outbox_event(event_id).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+
+    use super::*;
+    use crate::MemoryDatabase;
+
+    #[tokio::test]
+    async fn test_lazy_open() {
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let statuses = Arc::new(Mutex::new(Vec::new()));
+
+        let a = attempts.clone();
+        let s = statuses.clone();
+        let database = LazyDatabase::new(move || {
+            let attempt = a.fetch_add(1, Ordering::SeqCst);
+            async move {
+                if attempt == 0 {
+                    Err(DatabaseError::NotFound)
+                } else {
+                    Ok(MemoryDatabase::default())
+                }
+            }
+        })
+        .progress(move |status| s.lock().unwrap().push(status));
+
+        assert!(!database.is_open());
+        assert_eq!(attempts.load(Ordering::SeqCst), 0);
+
+        // First attempt fails
+        assert!(database.open().await.is_err());
+        assert!(!database.is_open());
+
+        // Retry on access
+        assert_eq!(database.count(vec![Filter::new()]).await.unwrap(), 0);
+        assert!(database.is_open());
+        assert!(matches!(database.backend(), Backend::Memory));
+
+        // Already open
+        database.open().await.unwrap();
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
+
+        assert_eq!(
+            *statuses.lock().unwrap(),
+            vec![
+                DatabaseStatus::Opening,
+                DatabaseStatus::Failed,
+                DatabaseStatus::Opening,
+                DatabaseStatus::Ready
+            ]
+        );
+    }
+}
@@ -8,33 +8,50 @@
 #![warn(rustdoc::bare_urls)]
 
 use core::fmt;
-use std::collections::{BTreeSet, HashSet};
+use std::cmp::Reverse;
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::sync::Arc;
 
 pub use async_trait::async_trait;
 pub use nostr;
 use nostr::nips::nip01::Coordinate;
+use nostr::nips::nip19::{Nip19Event, Nip19Profile};
+use nostr::nips::nip21::Nip21;
+use nostr::nips::nip65;
 use nostr::secp256k1::XOnlyPublicKey;
-use nostr::{Event, EventId, Filter, JsonUtil, Kind, Metadata, Timestamp, Url};
+use nostr::{
+    Alphabet, Event, EventId, Filter, JsonUtil, Kind, Metadata, RelayMetadata, Tag, Timestamp,
+    UncheckedUrl, Url,
+};
 
 mod error;
 #[cfg(feature = "flatbuf")]
 pub mod flatbuffers;
 pub mod index;
+pub mod integrity;
+#[cfg(feature = "language")]
+pub mod language;
+pub mod lazy;
 pub mod memory;
 mod options;
 pub mod profile;
 mod raw;
+pub mod relay_info;
 mod tag_indexes;
 
 pub use self::error::DatabaseError;
 #[cfg(feature = "flatbuf")]
 pub use self::flatbuffers::{FlatBufferBuilder, FlatBufferDecode, FlatBufferEncode};
 pub use self::index::{DatabaseIndexes, EventIndexResult};
+pub use self::integrity::{IntegrityIssue, IntegrityReport};
+#[cfg(feature = "language")]
+pub use self::language::detect_language;
+pub use self::lazy::{DatabaseStatus, LazyDatabase};
 pub use self::memory::MemoryDatabase;
 pub use self::options::DatabaseOptions;
 pub use self::profile::Profile;
 pub use self::raw::RawEvent;
+pub use self::relay_info::RelayInfo;
 
 /// Backend
 pub enum Backend {
@@ -172,8 +189,83 @@ pub trait NostrDatabase: AsyncTraitDeps {
 
     /// Wipe all data
     async fn wipe(&self) -> Result<(), Self::Err>;
+
+    /// Verify database integrity
+    ///
+    /// Re-validate the IDs and signatures of the stored events and check the indexes consistency.
+    /// If `prune` is `true`, the valid events with only index issues are indexed again,
+    /// while the other corrupted entries are removed from both the store and the indexes.
+    ///
+    /// This may take a while for big databases: consider to run it in a background task.
+    async fn verify_integrity(&self, prune: bool) -> Result<IntegrityReport, Self::Err> {
+        let _ = prune;
+        Err(DatabaseError::NotSupported.into())
+    }
+
+    /// Save scheduled [`Event`]
+    ///
+    /// The event will be published at its `created_at`.
+    /// Scheduled events are kept apart from the stored events, so they aren't returned by queries.
+    async fn save_scheduled_event(&self, event: &Event) -> Result<(), Self::Err> {
+        let _ = event;
+        Err(DatabaseError::NotSupported.into())
+    }
+
+    /// Get scheduled events, sorted by publish time (`created_at`, ascending)
+    async fn scheduled_events(&self) -> Result<Vec<Event>, Self::Err> {
+        Err(DatabaseError::NotSupported.into())
+    }
+
+    /// Remove scheduled [`Event`]
+    ///
+    /// Return `false` if the event wasn't scheduled.
+    async fn remove_scheduled_event(&self, event_id: EventId) -> Result<bool, Self::Err> {
+        let _ = event_id;
+        Err(DatabaseError::NotSupported.into())
+    }
+
+    /// Save outbox [`Event`]
+    ///
+    /// The event is waiting to be published (i.e. signed while offline).
+    /// Outbox events are kept apart from the stored events, like the scheduled ones.
+    async fn save_outbox_event(&self, event: &Event) -> Result<(), Self::Err> {
+        let _ = event;
+        Err(DatabaseError::NotSupported.into())
+    }
+
+    /// Get outbox events, sorted by `created_at` (ascending)
+    async fn outbox_events(&self) -> Result<Vec<Event>, Self::Err> {
+        Err(DatabaseError::NotSupported.into())
+    }
+
+    /// Remove outbox [`Event`]
+    ///
+    /// Return `false` if the event wasn't in the outbox.
+    async fn remove_outbox_event(&self, event_id: EventId) -> Result<bool, Self::Err> {
+        let _ = event_id;
+        Err(DatabaseError::NotSupported.into())
+    }
+
+    /// Save [`RelayInfo`]
+    ///
+    /// Replace the previous info of the same relay.
+    async fn save_relay_info(&self, info: RelayInfo) -> Result<(), Self::Err> {
+        let _ = info;
+        Err(DatabaseError::NotSupported.into())
+    }
+
+    /// Get cached [`RelayInfo`]
+    async fn relay_info(&self, url: &Url) -> Result<Option<RelayInfo>, Self::Err> {
+        let _ = url;
+        Err(DatabaseError::NotSupported.into())
+    }
 }
 
+/// Max number of relay hints
+const MAX_RELAY_HINTS: usize = 3;
+/// Number of recent events used to rank the relay hints
+const RELAY_HINTS_SAMPLE_SIZE: usize = 100;
+
 /// Nostr Database Extension
 #[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
 #[cfg_attr(not(target_arch = "wasm32"), async_trait)]
@@ -248,6 +340,266 @@ pub trait NostrDatabaseExt: NostrDatabase {
             None => Ok(BTreeSet::new()),
         }
     }
+
+    /// Get events mentioning [`XOnlyPublicKey`] since [`Timestamp`]
+    ///
+    /// Return the events where the public key is `p` tagged, one of its events is quoted (`q` tag)
+    /// or it's referenced via `nostr:` URI in the content of notes.
+    /// Events are deduplicated, ordered from the newest and the public key own events are excluded.
+    #[tracing::instrument(skip_all, level = "trace")]
+    async fn mentions(
+        &self,
+        public_key: XOnlyPublicKey,
+        since: Timestamp,
+    ) -> Result<Vec<Event>, Self::Err> {
+        // Own events, to find quotes and references
+        let own_ids: HashSet<EventId> = self
+            .event_ids_by_filters(vec![Filter::new().author(public_key)], Order::Desc)
+            .await?
+            .into_iter()
+            .collect();
+
+        // Tagged
+        let mut filters: Vec<Filter> = vec![Filter::new().pubkey(public_key).since(since)];
+        if !own_ids.is_empty() {
+            filters.push(
+                Filter::new()
+                    .custom_tag(Alphabet::Q, own_ids.iter().copied())
+                    .since(since),
+            );
+        }
+        let mut events: Vec<Event> = self.query(filters, Order::Desc).await?;
+
+        // Referenced via `nostr:` URI
+        let filter = Filter::new()
+            .kinds([Kind::TextNote, Kind::LongFormTextNote])
+            .since(since);
+        events.extend(
+            self.query(vec![filter], Order::Desc)
+                .await?
+                .into_iter()
+                .filter(|e| is_referenced(e.content(), &public_key, &own_ids)),
+        );
+
+        let mut ids: HashSet<EventId> = HashSet::with_capacity(events.len());
+        events.retain(|e| e.author_ref() != &public_key && ids.insert(e.id()));
+        events.sort_by_key(|e| Reverse(e.created_at()));
+
+        Ok(events)
+    }
+
+    /// Get the NIP65 relay list of [`XOnlyPublicKey`]
+    ///
+    /// Relays without [`RelayMetadata`] are used both for read and write.
+    /// Return an empty map if the relay list is not stored.
+    #[tracing::instrument(skip_all, level = "trace")]
+    async fn relay_list(
+        &self,
+        public_key: XOnlyPublicKey,
+    ) -> Result<HashMap<Url, Option<RelayMetadata>>, Self::Err> {
+        let filter = Filter::new()
+            .author(public_key)
+            .kind(Kind::RelayList)
+            .limit(1);
+        match self.query(vec![filter], Order::Desc).await?.first() {
+            Some(event) => Ok(nip65::extract_relay_list(event)
+                .into_iter()
+                .filter_map(|(url, metadata)| Some((Url::try_from(url).ok()?, metadata)))
+                .collect()),
+            None => Ok(HashMap::new()),
+        }
+    }
+
+    /// Get the best relay hints for [`XOnlyPublicKey`]
+    ///
+    /// Candidates are the write relays of the NIP65 relay list (or, if not available, the relays
+    /// where the author events have been seen), ranked by the number of recent author events seen on them.
+    #[tracing::instrument(skip_all, level = "trace")]
+    async fn relay_hints(&self, public_key: XOnlyPublicKey) -> Result<Vec<Url>, Self::Err> {
+        // Write relays (NIP65)
+        let write_relays: HashSet<Url> = self
+            .relay_list(public_key)
+            .await?
+            .into_iter()
+            .filter(|(_, metadata)| !matches!(metadata, Some(RelayMetadata::Read)))
+            .map(|(url, _)| url)
+            .collect();
+
+        // Reliability
+        let filter = Filter::new()
+            .author(public_key)
+            .limit(RELAY_HINTS_SAMPLE_SIZE);
+        let event_ids: Vec<EventId> = self.event_ids_by_filters(vec![filter], Order::Desc).await?;
+        let mut scores: HashMap<Url, usize> = HashMap::new();
+        for event_id in event_ids.into_iter() {
+            if let Some(urls) = self.event_seen_on_relays(event_id).await? {
+                for url in urls.into_iter() {
+                    *scores.entry(url).or_default() += 1;
+                }
+            }
+        }
+
+        let candidates: Vec<Url> = if write_relays.is_empty() {
+            scores.keys().cloned().collect()
+        } else {
+            write_relays.into_iter().collect()
+        };
+
+        Ok(rank_relays(candidates, &scores))
+    }
+
+    /// Get [`Nip19Event`] of [`Event`] with relay hints
+    ///
+    /// Prefer the relays where the event has been seen, ranked by the author [`relay_hints`](NostrDatabaseExt::relay_hints).
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use nostr::{Event, Keys, EventBuilder};
+    /// use nostr::nips::nip19::ToBech32;
+    /// use nostr_database::{MemoryDatabase, NostrDatabaseExt};
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// # let event: Event = EventBuilder::text_note("", []).to_event(&Keys::generate()).unwrap();
+    /// let database = MemoryDatabase::default();
+    /// let nevent: String = database.nevent(&event).await.unwrap().to_bech32().unwrap();
+    /// # }
+    /// ```
+    #[tracing::instrument(skip_all, level = "trace")]
+    async fn nevent(&self, event: &Event) -> Result<Nip19Event, Self::Err> {
+        let author_hints: Vec<Url> = self.relay_hints(event.author()).await?;
+        let seen: HashSet<Url> = self
+            .event_seen_on_relays(event.id())
+            .await?
+            .unwrap_or_default();
+
+        // Relays where the event has been seen first, then author relays
+        let scores: HashMap<Url, usize> = author_hints
+            .iter()
+            .enumerate()
+            .map(|(index, url)| (url.clone(), author_hints.len() - index))
+            .collect();
+        let mut relays: Vec<Url> = rank_relays(seen.into_iter().collect(), &scores);
+        for url in author_hints.into_iter() {
+            if relays.len() >= MAX_RELAY_HINTS {
+                break;
+            }
+            if !relays.contains(&url) {
+                relays.push(url);
+            }
+        }
+
+        Ok(Nip19Event {
+            event_id: event.id(),
+            author: Some(event.author()),
+            kind: Some(event.kind()),
+            relays: relays.into_iter().map(|u| u.to_string()).collect(),
+        })
+    }
+
+    /// Get [`Nip19Profile`] of [`XOnlyPublicKey`] with relay hints
+    ///
+    /// See [`relay_hints`](NostrDatabaseExt::relay_hints).
+    #[tracing::instrument(skip_all, level = "trace")]
+    async fn nprofile(&self, public_key: XOnlyPublicKey) -> Result<Nip19Profile, Self::Err> {
+        let relays: Vec<Url> = self.relay_hints(public_key).await?;
+        Ok(Nip19Profile::new(public_key, relays))
+    }
+
+    /// Get the known relays
+    ///
+    /// Relays are collected from the NIP65 relay lists and from the relay hints of the contact lists,
+    /// and sorted by the number of references (descending).
+    /// Useful to build relay discovery UIs (see also [`NostrDatabase::relay_info`]).
+    #[tracing::instrument(skip_all, level = "trace")]
+    async fn known_relays(&self) -> Result<Vec<Url>, Self::Err> {
+        let filter = Filter::new().kinds([Kind::RelayList, Kind::ContactList]);
+        let events: Vec<Event> = self.query(vec![filter], Order::Desc).await?;
+
+        let mut scores: HashMap<Url, usize> = HashMap::new();
+        for event in events.into_iter() {
+            let urls: Vec<UncheckedUrl> = if event.kind() == Kind::RelayList {
+                nip65::extract_relay_list(&event)
+                    .into_iter()
+                    .map(|(url, _)| url)
+                    .collect()
+            } else {
+                event
+                    .iter_tags()
+                    .filter_map(|tag| match tag {
+                        Tag::PublicKey { relay_url, .. } => relay_url.clone(),
+                        _ => None,
+                    })
+                    .collect()
+            };
+
+            for url in urls.into_iter() {
+                if let Ok(url) = Url::try_from(url) {
+                    *scores.entry(url).or_default() += 1;
+                }
+            }
+        }
+
+        let mut relays: Vec<(Url, usize)> = scores.into_iter().collect();
+        relays.sort_by(|(a, score_a), (b, score_b)| score_b.cmp(score_a).then_with(|| a.cmp(b)));
+        Ok(relays.into_iter().map(|(url, _)| url).collect())
+    }
+
+    /// Import events
+    ///
+    /// Verify the events in batch (see [`Event::verify_batch`]) and save the valid ones:
+    /// the invalid events are skipped.
+    /// Return the number of saved events.
+    #[tracing::instrument(skip_all, level = "trace")]
+    async fn import_events(&self, events: Vec<Event>) -> Result<usize, Self::Err> {
+        let results = Event::verify_batch(&events);
+
+        let mut saved: usize = 0;
+        for (event, res) in events.iter().zip(results.into_iter()) {
+            match res {
+                Ok(()) => {
+                    if self.save_event(event).await? {
+                        saved += 1;
+                    }
+                }
+                Err(e) => tracing::warn!("Skipped invalid event {}: {e}", event.id()),
+            }
+        }
+
+        Ok(saved)
+    }
+}
+
+/// Sort relays by score (descending) and keep the best [`MAX_RELAY_HINTS`]
+fn rank_relays(mut relays: Vec<Url>, scores: &HashMap<Url, usize>) -> Vec<Url> {
+    relays.sort_by(|a, b| {
+        let score_a: usize = scores.get(a).copied().unwrap_or_default();
+        let score_b: usize = scores.get(b).copied().unwrap_or_default();
+        score_b.cmp(&score_a).then_with(|| a.cmp(b))
+    });
+    relays.truncate(MAX_RELAY_HINTS);
+    relays
+}
+
+/// Check if `content` references the public key or one of its events via `nostr:` URI
+fn is_referenced(content: &str, public_key: &XOnlyPublicKey, own_ids: &HashSet<EventId>) -> bool {
+    content.match_indices("nostr:").any(|(index, prefix)| {
+        let start: usize = index + prefix.len();
+        let data: &str = &content[start..];
+        let len: usize = data
+            .find(|c: char| !c.is_ascii_alphanumeric())
+            .unwrap_or(data.len());
+        match Nip21::parse(&content[index..start + len]) {
+            Ok(Nip21::Pubkey(pk)) => &pk == public_key,
+            Ok(Nip21::Profile(profile)) => &profile.public_key == public_key,
+            Ok(Nip21::EventId(id)) => own_ids.contains(&id),
+            Ok(Nip21::Event(event)) => {
+                own_ids.contains(&event.event_id) || event.author.as_ref() == Some(public_key)
+            }
+            Ok(Nip21::Coordinate(coordinate)) => &coordinate.pubkey == public_key,
+            Err(..) => false,
+        }
+    })
 }
 
 #[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
@@ -362,6 +714,48 @@ impl<T: NostrDatabase> NostrDatabase for EraseNostrDatabaseError<T> {
     async fn wipe(&self) -> Result<(), Self::Err> {
         self.0.wipe().await.map_err(Into::into)
     }
+
+    async fn verify_integrity(&self, prune: bool) -> Result<IntegrityReport, Self::Err> {
+        self.0.verify_integrity(prune).await.map_err(Into::into)
+    }
+
+    async fn save_scheduled_event(&self, event: &Event) -> Result<(), Self::Err> {
+        self.0.save_scheduled_event(event).await.map_err(Into::into)
+    }
+
+    async fn scheduled_events(&self) -> Result<Vec<Event>, Self::Err> {
+        self.0.scheduled_events().await.map_err(Into::into)
+    }
+
+    async fn remove_scheduled_event(&self, event_id: EventId) -> Result<bool, Self::Err> {
+        self.0
+            .remove_scheduled_event(event_id)
+            .await
+            .map_err(Into::into)
+    }
+
+    async fn save_outbox_event(&self, event: &Event) -> Result<(), Self::Err> {
+        self.0.save_outbox_event(event).await.map_err(Into::into)
+    }
+
+    async fn outbox_events(&self) -> Result<Vec<Event>, Self::Err> {
+        self.0.outbox_events().await.map_err(Into::into)
+    }
+
+    async fn remove_outbox_event(&self, event_id: EventId) -> Result<bool, Self::Err> {
+        self.0
+            .remove_outbox_event(event_id)
+            .await
+            .map_err(Into::into)
+    }
+
+    async fn save_relay_info(&self, info: RelayInfo) -> Result<(), Self::Err> {
+        self.0.save_relay_info(info).await.map_err(Into::into)
+    }
+
+    async fn relay_info(&self, url: &Url) -> Result<Option<RelayInfo>, Self::Err> {
+        self.0.relay_info(url).await.map_err(Into::into)
+    }
 }
 
 /// Alias for `Send` on non-wasm, empty trait (implemented by everything) on
@@ -397,3 +791,206 @@ impl<T> SyncOutsideWasm for T {}
 /// implemented, while other targets will.
 pub trait AsyncTraitDeps: std::fmt::Debug + SendOutsideWasm + SyncOutsideWasm {}
 impl<T: std::fmt::Debug + SendOutsideWasm + SyncOutsideWasm> AsyncTraitDeps for T {}
+
+#[cfg(test)]
+mod tests {
+    use nostr::nips::nip19::ToBech32;
+    use nostr::{Contact, EventBuilder, Keys, TagKind};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_import_events() {
+        let database = MemoryDatabase::new(DatabaseOptions { events: true });
+
+        let keys = Keys::generate();
+        let valid = EventBuilder::text_note("Valid", [])
+            .to_event(&keys)
+            .unwrap();
+        let invalid = Event::new(
+            valid.id(),
+            valid.author(),
+            valid.created_at(),
+            valid.kind(),
+            [],
+            "Tampered",
+            valid.signature(),
+        );
+        let other = EventBuilder::text_note("Other", [])
+            .to_event(&keys)
+            .unwrap();
+
+        let saved = database
+            .import_events(vec![valid.clone(), invalid, other.clone()])
+            .await
+            .unwrap();
+        assert_eq!(saved, 2);
+
+        let events = database
+            .query(vec![Filter::new().author(keys.public_key())], Order::Desc)
+            .await
+            .unwrap();
+        assert_eq!(events.len(), 2);
+        assert!(events.iter().all(|e| e.content() != "Tampered"));
+    }
+
+    #[tokio::test]
+    async fn test_mentions() {
+        let database = MemoryDatabase::new(DatabaseOptions { events: true });
+
+        let keys = Keys::generate();
+        let other = Keys::generate();
+        let since = Timestamp::from(0);
+
+        let own = EventBuilder::text_note("My note", [])
+            .to_event(&keys)
+            .unwrap();
+        let tagged = EventBuilder::text_note("Hello", [Tag::public_key(keys.public_key())])
+            .to_event(&other)
+            .unwrap();
+        let quote = EventBuilder::text_note(
+            "Quoting",
+            [Tag::Generic(
+                TagKind::Custom(String::from("q")),
+                vec![own.id().to_hex()],
+            )],
+        )
+        .to_event(&other)
+        .unwrap();
+        let uri = EventBuilder::text_note(
+            format!("Follow nostr:{}!", keys.public_key().to_bech32().unwrap()),
+            [],
+        )
+        .to_event(&other)
+        .unwrap();
+        let unrelated = EventBuilder::text_note("Unrelated nostr:", [])
+            .to_event(&other)
+            .unwrap();
+        let self_tagged = EventBuilder::text_note("Me", [Tag::public_key(keys.public_key())])
+            .to_event(&keys)
+            .unwrap();
+
+        for event in [&own, &tagged, &quote, &uri, &unrelated, &self_tagged] {
+            database.save_event(event).await.unwrap();
+        }
+
+        let mentions: HashSet<EventId> = database
+            .mentions(keys.public_key(), since)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|e| e.id())
+            .collect();
+        let expected: HashSet<EventId> = [tagged.id(), quote.id(), uri.id()].into_iter().collect();
+        assert_eq!(mentions, expected);
+    }
+
+    #[tokio::test]
+    async fn test_relay_hints() {
+        let database = MemoryDatabase::new(DatabaseOptions { events: true });
+        let keys = Keys::generate();
+
+        let damus = Url::parse("wss://relay.damus.io").unwrap();
+        let nos = Url::parse("wss://nos.lol").unwrap();
+        let read = Url::parse("wss://read.example.com").unwrap();
+        let other = Url::parse("wss://other.example.com").unwrap();
+
+        let relay_list = EventBuilder::relay_list([
+            (damus.clone().into(), None),
+            (nos.clone().into(), Some(RelayMetadata::Write)),
+            (read.clone().into(), Some(RelayMetadata::Read)),
+        ])
+        .to_event(&keys)
+        .unwrap();
+        let note = EventBuilder::text_note("Hello", [])
+            .to_event(&keys)
+            .unwrap();
+        database.save_event(&relay_list).await.unwrap();
+        database.save_event(&note).await.unwrap();
+
+        // `nos.lol` is more reliable
+        database
+            .event_id_seen(relay_list.id(), nos.clone())
+            .await
+            .unwrap();
+        database
+            .event_id_seen(note.id(), nos.clone())
+            .await
+            .unwrap();
+        database
+            .event_id_seen(note.id(), damus.clone())
+            .await
+            .unwrap();
+        database
+            .event_id_seen(note.id(), read.clone())
+            .await
+            .unwrap();
+
+        let relay_list = database.relay_list(keys.public_key()).await.unwrap();
+        assert_eq!(relay_list.len(), 3);
+        assert_eq!(relay_list.get(&damus), Some(&None));
+        assert_eq!(relay_list.get(&read), Some(&Some(RelayMetadata::Read)));
+        assert!(database
+            .relay_list(Keys::generate().public_key())
+            .await
+            .unwrap()
+            .is_empty());
+
+        assert_eq!(
+            database.relay_hints(keys.public_key()).await.unwrap(),
+            vec![nos.clone(), damus.clone()]
+        );
+
+        let profile = database.nprofile(keys.public_key()).await.unwrap();
+        assert_eq!(profile.relays, vec![nos.to_string(), damus.to_string()]);
+
+        // Seen relays first
+        database
+            .event_id_seen(note.id(), other.clone())
+            .await
+            .unwrap();
+        let nevent = database.nevent(&note).await.unwrap();
+        assert_eq!(nevent.author, Some(keys.public_key()));
+        assert_eq!(
+            nevent.relays,
+            vec![nos.to_string(), damus.to_string(), other.to_string()]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_known_relays() {
+        let database = MemoryDatabase::new(DatabaseOptions { events: true });
+        let keys = Keys::generate();
+        let other = Keys::generate();
+
+        let damus = Url::parse("wss://relay.damus.io").unwrap();
+        let nos = Url::parse("wss://nos.lol").unwrap();
+
+        let relay_list = EventBuilder::relay_list([
+            (damus.clone().into(), None),
+            (nos.clone().into(), Some(RelayMetadata::Read)),
+        ])
+        .to_event(&keys)
+        .unwrap();
+        let contact_list = EventBuilder::contact_list([
+            Contact::new::<String>(other.public_key(), Some(nos.clone().into()), None),
+            Contact::new::<String>(keys.public_key(), None, None),
+        ])
+        .to_event(&other)
+        .unwrap();
+        database.save_event(&relay_list).await.unwrap();
+        database.save_event(&contact_list).await.unwrap();
+
+        assert_eq!(
+            database.known_relays().await.unwrap(),
+            vec![nos, damus.clone()]
+        );
+
+        // Relay info
+        assert!(database.relay_info(&damus).await.unwrap().is_none());
+        let mut info = RelayInfo::new(damus.clone());
+        info.name = Some(String::from("Damus"));
+        database.save_relay_info(info.clone()).await.unwrap();
+        assert_eq!(database.relay_info(&damus).await.unwrap(), Some(info));
+    }
+}
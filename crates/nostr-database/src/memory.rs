@@ -13,8 +13,8 @@ use nostr::{Event, EventId, Filter, Timestamp, Url};
 use tokio::sync::RwLock;
 
 use crate::{
-    Backend, DatabaseError, DatabaseIndexes, DatabaseOptions, EventIndexResult, NostrDatabase,
-    Order,
+    Backend, DatabaseError, DatabaseIndexes, DatabaseOptions, EventIndexResult, IntegrityReport,
+    NostrDatabase, Order, RelayInfo,
 };
 
 /// Memory Database (RAM)
@@ -23,6 +23,9 @@ pub struct MemoryDatabase {
     opts: DatabaseOptions,
     seen_event_ids: Arc<RwLock<HashMap<EventId, HashSet<Url>>>>,
     events: Arc<RwLock<HashMap<EventId, Event>>>,
+    scheduled: Arc<RwLock<HashMap<EventId, Event>>>,
+    outbox: Arc<RwLock<HashMap<EventId, Event>>>,
+    relays: Arc<RwLock<HashMap<Url, RelayInfo>>>,
     indexes: DatabaseIndexes,
 }
 
@@ -41,6 +44,9 @@ impl MemoryDatabase {
             opts,
             seen_event_ids: Arc::new(RwLock::new(HashMap::new())),
             events: Arc::new(RwLock::new(HashMap::new())),
+            scheduled: Arc::new(RwLock::new(HashMap::new())),
+            outbox: Arc::new(RwLock::new(HashMap::new())),
+            relays: Arc::new(RwLock::new(HashMap::new())),
             indexes: DatabaseIndexes::new(),
         }
     }
@@ -207,6 +213,141 @@ impl NostrDatabase for MemoryDatabase {
         seen_event_ids.clear();
         let mut events = self.events.write().await;
         events.clear();
+        let mut scheduled = self.scheduled.write().await;
+        scheduled.clear();
+        let mut outbox = self.outbox.write().await;
+        outbox.clear();
+        let mut relays = self.relays.write().await;
+        relays.clear();
         Ok(())
     }
+
+    #[tracing::instrument(skip_all)]
+    async fn verify_integrity(&self, prune: bool) -> Result<IntegrityReport, Self::Err> {
+        if self.opts.events {
+            let mut events = self.events.write().await;
+
+            let mut report = IntegrityReport::default();
+            for (event_id, event) in events.iter() {
+                report.verify_event(*event_id, event);
+            }
+
+            let stored: HashSet<EventId> = events.keys().copied().collect();
+            report.extend(self.indexes.check_integrity(&stored).await);
+
+            if prune && !report.is_ok() {
+                // Valid events with only index issues are indexed again
+                let mut to_reindex: Vec<Event> = Vec::new();
+                for (event_id, issue) in report.corrupted.iter() {
+                    match events.get(event_id) {
+                        Some(event) if issue.is_index_issue() => to_reindex.push(event.clone()),
+                        _ => {
+                            events.remove(event_id);
+                        }
+                    }
+                }
+
+                self.indexes.remove(report.corrupted.keys()).await;
+
+                for event in to_reindex.into_iter() {
+                    let EventIndexResult {
+                        to_store,
+                        to_discard,
+                    } = self.indexes.index_event(&event).await;
+
+                    // Replaced or deleted in the meantime
+                    if !to_store {
+                        events.remove(&event.id());
+                    }
+
+                    for event_id in to_discard.iter() {
+                        events.remove(event_id);
+                    }
+
+                    report.reindexed += 1;
+                }
+
+                report.pruned = true;
+            }
+
+            Ok(report)
+        } else {
+            Err(DatabaseError::FeatureDisabled)
+        }
+    }
+
+    async fn save_scheduled_event(&self, event: &Event) -> Result<(), Self::Err> {
+        let mut scheduled = self.scheduled.write().await;
+        scheduled.insert(event.id(), event.clone());
+        Ok(())
+    }
+
+    async fn scheduled_events(&self) -> Result<Vec<Event>, Self::Err> {
+        let scheduled = self.scheduled.read().await;
+        let mut events: Vec<Event> = scheduled.values().cloned().collect();
+        events.sort_by_key(|e| e.created_at());
+        Ok(events)
+    }
+
+    async fn remove_scheduled_event(&self, event_id: EventId) -> Result<bool, Self::Err> {
+        let mut scheduled = self.scheduled.write().await;
+        Ok(scheduled.remove(&event_id).is_some())
+    }
+
+    async fn save_outbox_event(&self, event: &Event) -> Result<(), Self::Err> {
+        let mut outbox = self.outbox.write().await;
+        outbox.insert(event.id(), event.clone());
+        Ok(())
+    }
+
+    async fn outbox_events(&self) -> Result<Vec<Event>, Self::Err> {
+        let outbox = self.outbox.read().await;
+        let mut events: Vec<Event> = outbox.values().cloned().collect();
+        events.sort_by_key(|e| e.created_at());
+        Ok(events)
+    }
+
+    async fn remove_outbox_event(&self, event_id: EventId) -> Result<bool, Self::Err> {
+        let mut outbox = self.outbox.write().await;
+        Ok(outbox.remove(&event_id).is_some())
+    }
+
+    async fn save_relay_info(&self, info: RelayInfo) -> Result<(), Self::Err> {
+        let mut relays = self.relays.write().await;
+        relays.insert(info.url.clone(), info);
+        Ok(())
+    }
+
+    async fn relay_info(&self, url: &Url) -> Result<Option<RelayInfo>, Self::Err> {
+        let relays = self.relays.read().await;
+        Ok(relays.get(url).cloned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nostr::{EventBuilder, Keys};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_verify_integrity_reindex() {
+        let db = MemoryDatabase::new(DatabaseOptions { events: true });
+
+        let keys = Keys::generate();
+        let event = EventBuilder::text_note("Test", []).to_event(&keys).unwrap();
+        db.save_event(&event).await.unwrap();
+
+        // Lose the index entries
+        db.indexes.remove([&event.id()]).await;
+        assert_eq!(db.count(vec![Filter::new()]).await.unwrap(), 0);
+
+        let report = db.verify_integrity(true).await.unwrap();
+        assert!(report.pruned);
+        assert_eq!(report.reindexed, 1);
+
+        // Event kept and queryable again
+        assert!(db.verify_integrity(false).await.unwrap().is_ok());
+        assert_eq!(db.count(vec![Filter::new()]).await.unwrap(), 1);
+    }
 }
@@ -0,0 +1,47 @@
+// Copyright (c) 2022-2023 Yuki Kishimoto
+// Copyright (c) 2023-2024 Rust Nostr Developers
+// Distributed under the MIT software license
+
+//! Relay info
+
+use std::time::Duration;
+
+use nostr::{Timestamp, Url};
+
+/// Cached relay info (from the NIP11 information document)
+///
+/// <https://github.com/nostr-protocol/nips/blob/master/11.md>
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RelayInfo {
+    /// Relay url
+    pub url: Url,
+    /// Name
+    pub name: Option<String>,
+    /// Description
+    pub description: Option<String>,
+    /// Icon URL
+    pub icon: Option<String>,
+    /// Contact
+    pub contact: Option<String>,
+    /// When the info has been fetched
+    pub updated_at: Timestamp,
+}
+
+impl RelayInfo {
+    /// Compose new empty relay info, fetched now
+    pub fn new(url: Url) -> Self {
+        Self {
+            url,
+            name: None,
+            description: None,
+            icon: None,
+            contact: None,
+            updated_at: Timestamp::now(),
+        }
+    }
+
+    /// Check if the info is older than `ttl`
+    pub fn is_expired(&self, ttl: Duration) -> bool {
+        self.updated_at + ttl < Timestamp::now()
+    }
+}
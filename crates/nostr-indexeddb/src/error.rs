@@ -18,6 +18,14 @@ pub enum IndexedDBError {
         /// Message given to the DomException
         message: String,
     },
+    /// Opening database that was not fully upgraded
+    #[error("database not fully upgraded: version={current}, latest={latest}")]
+    NotFullyUpgraded {
+        /// Current database version
+        current: u32,
+        /// Latest database version
+        latest: u32,
+    },
     /// Database error
     #[error(transparent)]
     Database(#[from] DatabaseError),
@@ -10,9 +10,11 @@
 #![allow(unknown_lints, clippy::arc_with_non_send_sync)]
 #![cfg_attr(not(target_arch = "wasm32"), allow(unused))]
 
+use std::cell::Cell;
 use std::collections::{HashMap, HashSet};
 use std::fmt;
 use std::future::IntoFuture;
+use std::rc::Rc;
 use std::sync::Arc;
 
 pub extern crate nostr;
@@ -120,21 +122,30 @@ impl WebDatabase {
 
             self.db.close();
 
+            let not_upgraded: Rc<Cell<Option<u32>>> = Rc::new(Cell::new(None));
+            let not_upgraded_version = not_upgraded.clone();
+
             let mut db_req: OpenDbRequest = IdbDatabase::open_u32(&name, CURRENT_DB_VERSION)?;
             db_req.set_on_upgrade_needed(Some(
                 move |evt: &IdbVersionChangeEvent| -> Result<(), JsValue> {
                     // Sanity check.
                     // There should be no upgrade needed since the database should have already been
-                    // upgraded to the latest version.
-                    panic!(
-                        "Opening database that was not fully upgraded: \
-                         DB version: {}; latest version: {CURRENT_DB_VERSION}",
-                        evt.old_version()
-                    )
+                    // upgraded to the latest version: abort the upgrade.
+                    not_upgraded_version.set(Some(evt.old_version() as u32));
+                    Err(JsValue::from_str("database not fully upgraded"))
                 },
             ));
 
-            self.db = Arc::new(db_req.into_future().await?);
+            let db = db_req.into_future().await;
+
+            if let Some(current) = not_upgraded.get() {
+                return Err(IndexedDBError::NotFullyUpgraded {
+                    current,
+                    latest: CURRENT_DB_VERSION,
+                });
+            }
+
+            self.db = Arc::new(db?);
         }
 
         Ok(())
@@ -25,7 +25,7 @@ async fn main() -> Result<()> {
 
     thread::sleep(Duration::from_secs(15)).await;
 
-    client.start().await;
+    client.start().await?;
 
     thread::sleep(Duration::from_secs(10)).await;
 
@@ -25,7 +25,10 @@ async fn main() -> Result<()> {
     // Create a text note POW event
     let event: Event =
         EventBuilder::text_note("POW text note from nostr-sdk", []).to_pow_event(&my_keys, 20)?;
-    client.send_event(event).await?;
+    let output = client.send_event(event).await?;
+    println!("Event ID: {}", output.val);
+    println!("Sent to: {:?}", output.success());
+    println!("Not sent to: {:?}", output.failed());
 
     // Send multiple events at once
     let mut events: Vec<Event> = Vec::new();
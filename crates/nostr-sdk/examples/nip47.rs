@@ -20,56 +20,17 @@ async fn main() -> Result<()> {
     invoice = String::from(invoice.trim());
 
     let nwc_uri =
-        NostrWalletConnectURI::from_str(&nwc_uri_string).expect("Failed to parse NWC URI");
+        NostrWalletConnectURI::from_str(nwc_uri_string.trim()).expect("Failed to parse NWC URI");
 
-    let my_keys = Keys::new(nwc_uri.secret);
+    let nwc = NostrWalletConnect::new(nwc_uri);
 
-    let client = Client::new(&my_keys);
-    client.add_relay(nwc_uri.relay_url.clone()).await?;
+    let balance = nwc.get_balance().await?;
+    println!("Balance: {balance} sat");
 
-    client.connect().await;
-    println!("Connected to relay {}", nwc_uri.relay_url);
+    let preimage = nwc.pay_invoice(invoice).await?;
+    println!("Payment sent. Preimage: {preimage}");
 
-    let req = nip47::Request {
-        method: Method::PayInvoice,
-        params: RequestParams::PayInvoice(PayInvoiceRequestParams { invoice }),
-    };
-
-    let encrypted = nip04::encrypt(&nwc_uri.secret, &nwc_uri.public_key, req.as_json()).unwrap();
-    let p_tag = Tag::public_key(nwc_uri.public_key);
-
-    let req_event = EventBuilder::new(Kind::WalletConnectRequest, encrypted, [p_tag])
-        .to_event(&Keys::new(nwc_uri.secret))
-        .unwrap();
-
-    let subscription = Filter::new()
-        .author(nwc_uri.public_key)
-        .kind(Kind::WalletConnectResponse)
-        .event(req_event.id)
-        .since(Timestamp::now());
-
-    client.subscribe(vec![subscription]).await;
-
-    client.send_event(req_event).await.unwrap();
-
-    client
-        .handle_notifications(|notification| async {
-            if let RelayPoolNotification::Event { event, .. } = notification {
-                let decrypt_res =
-                    nip04::decrypt(&nwc_uri.secret, &nwc_uri.public_key, &event.content).unwrap();
-                println!("{:?}", decrypt_res);
-
-                let nip47_res = nip47::Response::from_json(decrypt_res).unwrap();
-
-                if let Some(ResponseResult::PayInvoice(pay_invoice_result)) = nip47_res.result {
-                    println!("Payment sent. Preimage: {}", pay_invoice_result.preimage);
-                } else {
-                    println!("Unexpected result: {:?}", nip47_res.as_json());
-                }
-            }
-            Ok(true)
-        })
-        .await?;
+    nwc.shutdown().await?;
 
     Ok(())
 }
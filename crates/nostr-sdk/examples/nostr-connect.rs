@@ -24,27 +24,27 @@ async fn main() -> Result<()> {
     println!("Nostr Connect URI: {nostr_connect_uri}");
     println!("\n###############################################\n");
 
+    // Request signer public key since we not added in Nip46Signer::new
+    signer
+        .req_signer_public_key(Some(Duration::from_secs(180)))
+        .await?;
+
     let client = Client::new(signer);
     client.add_relay(relay_url).await?;
     client.connect().await;
 
-    // Request signer public key since we not added in Client::with_remote_signer
-    client
-        .req_signer_public_key(Some(Duration::from_secs(180)))
-        .await?;
-
-    let id = client
+    let output = client
         .publish_text_note("Testing nostr-sdk nostr-connect client", [])
         .await?;
-    println!("Published text note: {id}\n");
+    println!("Published text note: {}\n", output.val);
 
     let receiver = XOnlyPublicKey::from_bech32(
         "npub1drvpzev3syqt0kjrls50050uzf25gehpz9vgdw08hvex7e0vgfeq0eseet",
     )?;
-    client
+    let output = client
         .send_direct_msg(receiver, "Hello from nostr-sdk", None)
         .await?;
-    println!("Sent DM: {id}");
+    println!("Sent DM: {}", output.val);
 
     Ok(())
 }
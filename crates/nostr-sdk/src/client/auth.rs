@@ -0,0 +1,213 @@
+// Copyright (c) 2022-2023 Yuki Kishimoto
+// Copyright (c) 2023-2024 Rust Nostr Developers
+// Distributed under the MIT software license
+
+//! Automatic authentication (NIP42)
+
+use std::collections::HashMap;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_utility::thread;
+use nostr::{Event, EventBuilder, RelayMessage, Url};
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::Mutex;
+
+use super::options::DEFAULT_SEND_TIMEOUT;
+use super::{Client, Error};
+use crate::relay::pool::{self, Error as RelayPoolError};
+use crate::relay::{
+    Error as RelayError, Relay, RelayPoolNotification, RelaySendOptions, AUTH_REQUIRED_PREFIX,
+};
+use crate::util::TryIntoUrl;
+
+type AnsweredChallenges = Arc<Mutex<HashMap<Url, String>>>;
+
+impl Client {
+    /// Authenticate to relay (NIP42)
+    ///
+    /// Sign the `AUTH` event for the `challenge` received from the relay and send it.
+    ///
+    /// To authenticate automatically, see [`Options::automatic_authentication`](super::Options::automatic_authentication).
+    ///
+    /// <https://github.com/nostr-protocol/nips/blob/master/42.md>
+    pub async fn authenticate<U, S>(&self, url: U, challenge: S) -> Result<(), Error>
+    where
+        U: TryIntoUrl,
+        pool::Error: From<<U as TryIntoUrl>::Err>,
+        S: Into<String>,
+    {
+        let relay: Relay = self.relay(url).await?;
+        let builder = EventBuilder::auth(challenge, relay.url());
+        let event: Event = self.internal_sign_event_builder(builder).await?;
+        let timeout: Option<Duration> = self.opts.send_timeout;
+        let opts = RelaySendOptions::new().timeout(timeout);
+        relay
+            .auth(event, opts)
+            .await
+            .map_err(RelayPoolError::Relay)?;
+        Ok(())
+    }
+
+    /// Spawn the authenticator, if automatic authentication is enabled and not already running
+    ///
+    /// The authenticator exit when the pool is stopped.
+    pub(super) fn spawn_authenticator(&self) {
+        if !self.opts.get_automatic_authentication()
+            || self.authenticator.swap(true, Ordering::SeqCst)
+        {
+            return;
+        }
+
+        let client: Client = self.internal_clone();
+        let mut notifications = self.notifications();
+        thread::spawn(async move {
+            tracing::debug!("Authenticator started");
+
+            // Last challenge answered for every relay, to not send duplicated `AUTH`
+            let answered: AnsweredChallenges = Arc::new(Mutex::new(HashMap::new()));
+
+            loop {
+                let notification = match notifications.recv().await {
+                    Ok(notification) => notification,
+                    Err(RecvError::Lagged(skipped)) => {
+                        tracing::warn!("Authenticator skipped {skipped} notifications");
+                        continue;
+                    }
+                    Err(RecvError::Closed) => break,
+                };
+
+                match notification {
+                    RelayPoolNotification::Message { relay_url, message } => match message {
+                        RelayMessage::Auth { challenge } => {
+                            let client: Client = client.internal_clone();
+                            let answered = answered.clone();
+                            thread::spawn(async move {
+                                client
+                                    .auto_authenticate(&answered, relay_url, challenge)
+                                    .await;
+                            });
+                        }
+                        RelayMessage::Ok {
+                            status: false,
+                            message,
+                            ..
+                        } if message.starts_with(AUTH_REQUIRED_PREFIX) => {
+                            // The relay will send the event again, once authenticated
+                            let client: Client = client.internal_clone();
+                            let answered = answered.clone();
+                            thread::spawn(async move {
+                                client.ensure_authenticated(&answered, relay_url).await;
+                            });
+                        }
+                        RelayMessage::Closed {
+                            subscription_id,
+                            message,
+                        } if message.starts_with(AUTH_REQUIRED_PREFIX) => {
+                            let client: Client = client.internal_clone();
+                            let answered = answered.clone();
+                            thread::spawn(async move {
+                                if let Some(relay) =
+                                    client.ensure_authenticated(&answered, relay_url).await
+                                {
+                                    match relay.resubscribe_id(&subscription_id, None).await {
+                                        Ok(()) => (),
+                                        // Not a long-lived subscription: the relay send the `REQ` again
+                                        Err(RelayError::InternalIdNotFound) => (),
+                                        Err(e) => tracing::error!(
+                                            "Impossible to resubscribe {subscription_id} to {}: {e}",
+                                            relay.url()
+                                        ),
+                                    }
+                                }
+                            });
+                        }
+                        _ => (),
+                    },
+                    RelayPoolNotification::Stop | RelayPoolNotification::Shutdown => break,
+                    _ => (),
+                }
+            }
+
+            client.authenticator.store(false, Ordering::SeqCst);
+            tracing::debug!("Authenticator exited");
+        });
+    }
+
+    /// Authenticate, if the challenge wasn't already answered
+    async fn auto_authenticate(
+        &self,
+        answered: &AnsweredChallenges,
+        relay_url: Url,
+        challenge: String,
+    ) {
+        {
+            let mut answered = answered.lock().await;
+            if answered.get(&relay_url) == Some(&challenge) {
+                tracing::debug!("Challenge already answered for {relay_url}");
+                return;
+            }
+            answered.insert(relay_url.clone(), challenge.clone());
+        }
+
+        match self.authenticate(relay_url.clone(), challenge).await {
+            Ok(..) => tracing::info!("Authenticated to {relay_url}"),
+            Err(e) => {
+                tracing::error!("Impossible to authenticate to {relay_url}: {e}");
+                // Allow to retry
+                answered.lock().await.remove(&relay_url);
+            }
+        }
+    }
+
+    /// Authenticate with the last challenge, if not already authenticated, and wait for the relay to accept the `AUTH`
+    async fn ensure_authenticated(
+        &self,
+        answered: &AnsweredChallenges,
+        relay_url: Url,
+    ) -> Option<Relay> {
+        let relay: Relay = self.relay(relay_url.clone()).await.ok()?;
+
+        if !relay.is_authenticated() {
+            match relay.auth_challenge().await {
+                Some(challenge) => self.auto_authenticate(answered, relay_url, challenge).await,
+                None => tracing::warn!(
+                    "Relay {relay_url} require authentication but no challenge was received"
+                ),
+            }
+        }
+
+        let timeout: Duration = self.opts.send_timeout.unwrap_or(DEFAULT_SEND_TIMEOUT);
+        if relay.wait_for_authentication(timeout).await {
+            Some(relay)
+        } else {
+            None
+        }
+    }
+
+    /// Clone for the background tasks, without shutting down the client when dropped
+    fn internal_clone(&self) -> Self {
+        let mut opts = self.opts.clone();
+        opts.shutdown_on_drop = false;
+        Self {
+            pool: self.pool.clone(),
+            signer: self.signer.clone(),
+            lazy_database: self.lazy_database.clone(),
+            opts,
+            scheduler: self.scheduler.clone(),
+            metadata_refresher: self.metadata_refresher.clone(),
+            authenticator: self.authenticator.clone(),
+            gossip_relays: self.gossip_relays.clone(),
+            #[cfg(feature = "nip44")]
+            conversation_keys: self.conversation_keys.clone(),
+            #[cfg(feature = "nip44")]
+            mute_list: self.mute_list.clone(),
+            #[cfg(feature = "nip57")]
+            zapper: self.zapper.clone(),
+            #[cfg(feature = "nip05")]
+            nip05_cache: self.nip05_cache.clone(),
+            dropped: self.dropped.clone(),
+        }
+    }
+}
@@ -17,9 +17,9 @@ use nostr::{
 use nostr_database::DynNostrDatabase;
 use tokio::sync::broadcast;
 
-use super::signer::ClientSigner;
+use super::signer::{DynNostrSigner, IntoNostrSigner};
 use super::{Error, Options, TryIntoUrl};
-use crate::relay::{pool, Relay, RelayOptions, RelayPoolNotification};
+use crate::relay::{pool, Output, Relay, RelayOptions, RelayPoolNotification};
 use crate::{ClientBuilder, NegentropyOptions, RUNTIME};
 
 #[derive(Debug, Clone)]
@@ -36,7 +36,7 @@ impl From<super::Client> for Client {
 impl Client {
     pub fn new<S>(signer: S) -> Self
     where
-        S: Into<ClientSigner>,
+        S: IntoNostrSigner,
     {
         Self {
             client: super::Client::new(signer),
@@ -45,7 +45,7 @@ impl Client {
 
     pub fn with_opts<S>(signer: S, opts: Options) -> Self
     where
-        S: Into<ClientSigner>,
+        S: IntoNostrSigner,
     {
         Self {
             client: super::Client::with_opts(signer, opts),
@@ -66,12 +66,12 @@ impl Client {
     /// Get current client signer
     ///
     /// Rise error if it not set.
-    pub fn signer(&self) -> Result<ClientSigner, Error> {
+    pub fn signer(&self) -> Result<Arc<DynNostrSigner>, Error> {
         RUNTIME.block_on(async { self.client.signer().await })
     }
 
     /// Set client signer
-    pub fn set_signer(&self, signer: Option<ClientSigner>) {
+    pub fn set_signer(&self, signer: Option<Arc<DynNostrSigner>>) {
         RUNTIME.block_on(async { self.client.set_signer(signer).await })
     }
 
@@ -95,7 +95,7 @@ impl Client {
     }
 
     /// Start a previously stopped client
-    pub fn start(&self) {
+    pub fn start(&self) -> Result<(), Error> {
         RUNTIME.block_on(async { self.client.start().await })
     }
 
@@ -192,10 +192,8 @@ impl Client {
         RUNTIME.block_on(async { self.client.disconnect().await })
     }
 
-    pub fn subscribe(&self, filters: Vec<Filter>) {
-        RUNTIME.block_on(async {
-            self.client.subscribe(filters).await;
-        })
+    pub fn subscribe(&self, filters: Vec<Filter>) -> Output<()> {
+        RUNTIME.block_on(async { self.client.subscribe(filters).await })
     }
 
     pub fn unsubscribe(&self) {
@@ -218,11 +216,11 @@ impl Client {
         })
     }
 
-    pub fn send_msg(&self, msg: ClientMessage) -> Result<(), Error> {
+    pub fn send_msg(&self, msg: ClientMessage) -> Result<Output<()>, Error> {
         RUNTIME.block_on(async { self.client.send_msg(msg).await })
     }
 
-    pub fn send_msg_to<U>(&self, url: U, msg: ClientMessage) -> Result<(), Error>
+    pub fn send_msg_to<U>(&self, url: U, msg: ClientMessage) -> Result<Output<()>, Error>
     where
         U: TryIntoUrl,
         pool::Error: From<<U as TryIntoUrl>::Err>,
@@ -231,11 +229,11 @@ impl Client {
     }
 
     /// Send event
-    pub fn send_event(&self, event: Event) -> Result<EventId, Error> {
+    pub fn send_event(&self, event: Event) -> Result<Output<EventId>, Error> {
         RUNTIME.block_on(async { self.client.send_event(event).await })
     }
 
-    pub fn send_event_to<U>(&self, url: U, event: Event) -> Result<EventId, Error>
+    pub fn send_event_to<U>(&self, url: U, event: Event) -> Result<Output<EventId>, Error>
     where
         U: TryIntoUrl,
         pool::Error: From<<U as TryIntoUrl>::Err>,
@@ -243,11 +241,15 @@ impl Client {
         RUNTIME.block_on(async { self.client.send_event_to(url, event).await })
     }
 
-    pub fn send_event_builder(&self, builder: EventBuilder) -> Result<EventId, Error> {
+    pub fn send_event_builder(&self, builder: EventBuilder) -> Result<Output<EventId>, Error> {
         RUNTIME.block_on(async { self.client.send_event_builder(builder).await })
     }
 
-    pub fn send_event_builder_to<U>(&self, url: U, builder: EventBuilder) -> Result<EventId, Error>
+    pub fn send_event_builder_to<U>(
+        &self,
+        url: U,
+        builder: EventBuilder,
+    ) -> Result<Output<EventId>, Error>
     where
         U: TryIntoUrl,
         pool::Error: From<<U as TryIntoUrl>::Err>,
@@ -255,11 +257,11 @@ impl Client {
         RUNTIME.block_on(async { self.client.send_event_builder_to(url, builder).await })
     }
 
-    pub fn set_metadata(&self, metadata: &Metadata) -> Result<EventId, Error> {
+    pub fn set_metadata(&self, metadata: &Metadata) -> Result<Output<EventId>, Error> {
         RUNTIME.block_on(async { self.client.set_metadata(metadata).await })
     }
 
-    pub fn publish_text_note<S, I>(&self, content: S, tags: I) -> Result<EventId, Error>
+    pub fn publish_text_note<S, I>(&self, content: S, tags: I) -> Result<Output<EventId>, Error>
     where
         S: Into<String>,
         I: IntoIterator<Item = Tag>,
@@ -268,7 +270,7 @@ impl Client {
     }
 
     #[deprecated(since = "0.27.0")]
-    pub fn add_recommended_relay<U>(&self, url: U) -> Result<EventId, Error>
+    pub fn add_recommended_relay<U>(&self, url: U) -> Result<Output<EventId>, Error>
     where
         U: TryIntoUrl,
         Error: From<<U as TryIntoUrl>::Err>,
@@ -277,7 +279,7 @@ impl Client {
         RUNTIME.block_on(async { self.client.add_recommended_relay(url).await })
     }
 
-    pub fn set_contact_list(&self, list: Vec<Contact>) -> Result<EventId, Error> {
+    pub fn set_contact_list(&self, list: Vec<Contact>) -> Result<Output<EventId>, Error> {
         RUNTIME.block_on(async { self.client.set_contact_list(list).await })
     }
 
@@ -305,7 +307,7 @@ impl Client {
         receiver: XOnlyPublicKey,
         msg: S,
         reply: Option<EventId>,
-    ) -> Result<EventId, Error>
+    ) -> Result<Output<EventId>, Error>
     where
         S: Into<String>,
     {
@@ -316,19 +318,27 @@ impl Client {
         &self,
         event_id: EventId,
         public_key: XOnlyPublicKey,
-    ) -> Result<EventId, Error> {
+    ) -> Result<Output<EventId>, Error> {
         RUNTIME.block_on(async { self.client.repost_event(event_id, public_key).await })
     }
 
-    pub fn delete_event(&self, event_id: EventId) -> Result<EventId, Error> {
+    pub fn delete_event(&self, event_id: EventId) -> Result<Output<EventId>, Error> {
         RUNTIME.block_on(async { self.client.delete_event(event_id).await })
     }
 
-    pub fn like(&self, event_id: EventId, public_key: XOnlyPublicKey) -> Result<EventId, Error> {
+    pub fn like(
+        &self,
+        event_id: EventId,
+        public_key: XOnlyPublicKey,
+    ) -> Result<Output<EventId>, Error> {
         RUNTIME.block_on(async { self.client.like(event_id, public_key).await })
     }
 
-    pub fn dislike(&self, event_id: EventId, public_key: XOnlyPublicKey) -> Result<EventId, Error> {
+    pub fn dislike(
+        &self,
+        event_id: EventId,
+        public_key: XOnlyPublicKey,
+    ) -> Result<Output<EventId>, Error> {
         RUNTIME.block_on(async { self.client.dislike(event_id, public_key).await })
     }
 
@@ -337,14 +347,14 @@ impl Client {
         event_id: EventId,
         public_key: XOnlyPublicKey,
         content: S,
-    ) -> Result<EventId, Error>
+    ) -> Result<Output<EventId>, Error>
     where
         S: Into<String>,
     {
         RUNTIME.block_on(async { self.client.reaction(event_id, public_key, content).await })
     }
 
-    pub fn new_channel(&self, metadata: &Metadata) -> Result<EventId, Error> {
+    pub fn new_channel(&self, metadata: &Metadata) -> Result<Output<EventId>, Error> {
         RUNTIME.block_on(async { self.client.new_channel(metadata).await })
     }
 
@@ -353,7 +363,7 @@ impl Client {
         channel_id: EventId,
         relay_url: Option<Url>,
         metadata: &Metadata,
-    ) -> Result<EventId, Error> {
+    ) -> Result<Output<EventId>, Error> {
         RUNTIME.block_on(async {
             self.client
                 .set_channel_metadata(channel_id, relay_url, metadata)
@@ -366,7 +376,7 @@ impl Client {
         channel_id: EventId,
         relay_url: Url,
         msg: S,
-    ) -> Result<EventId, Error>
+    ) -> Result<Output<EventId>, Error>
     where
         S: Into<String>,
     {
@@ -381,7 +391,7 @@ impl Client {
         &self,
         message_id: EventId,
         reason: Option<S>,
-    ) -> Result<EventId, Error>
+    ) -> Result<Output<EventId>, Error>
     where
         S: Into<String>,
     {
@@ -392,7 +402,7 @@ impl Client {
         &self,
         pubkey: XOnlyPublicKey,
         reason: Option<S>,
-    ) -> Result<EventId, Error>
+    ) -> Result<Output<EventId>, Error>
     where
         S: Into<String>,
     {
@@ -402,7 +412,7 @@ impl Client {
     /// Create an auth event
     ///
     /// <https://github.com/nostr-protocol/nips/blob/master/42.md>
-    pub fn auth<S>(&self, challenge: S, relay: Url) -> Result<EventId, Error>
+    pub fn auth<S>(&self, challenge: S, relay: Url) -> Result<Output<EventId>, Error>
     where
         S: Into<String>,
     {
@@ -418,7 +428,7 @@ impl Client {
         bolt11: S,
         preimage: Option<S>,
         zap_request: Event,
-    ) -> Result<EventId, Error>
+    ) -> Result<Output<EventId>, Error>
     where
         S: Into<String>,
     {
@@ -435,7 +445,7 @@ impl Client {
         bolt11: S,
         preimage: Option<S>,
         zap_request: Event,
-    ) -> Result<EventId, Error>
+    ) -> Result<Output<EventId>, Error>
     where
         S: Into<String>,
     {
@@ -445,7 +455,11 @@ impl Client {
     /// File metadata
     ///
     /// <https://github.com/nostr-protocol/nips/blob/master/94.md>
-    pub fn file_metadata<S>(&self, description: S, metadata: FileMetadata) -> Result<EventId, Error>
+    pub fn file_metadata<S>(
+        &self,
+        description: S,
+        metadata: FileMetadata,
+    ) -> Result<Output<EventId>, Error>
     where
         S: Into<String>,
     {
@@ -453,7 +467,7 @@ impl Client {
     }
 
     /// Negentropy reconciliation
-    pub fn reconcile(&self, filter: Filter, opts: NegentropyOptions) -> Result<(), Error> {
+    pub fn reconcile(&self, filter: Filter, opts: NegentropyOptions) -> Result<Output<()>, Error> {
         RUNTIME.block_on(async move { self.client.reconcile(filter, opts).await })
     }
 
@@ -0,0 +1,197 @@
+// Copyright (c) 2022-2023 Yuki Kishimoto
+// Copyright (c) 2023-2024 Rust Nostr Developers
+// Distributed under the MIT software license
+
+//! Bookmarks (NIP51)
+//!
+//! <https://github.com/nostr-protocol/nips/blob/master/51.md>
+
+use std::time::Duration;
+
+use nostr::nips::nip51::{Bookmark, Bookmarks};
+use nostr::secp256k1::XOnlyPublicKey;
+use nostr::{Event, EventBuilder, EventId, Filter, Kind, Timestamp};
+
+use super::{Client, Error};
+use crate::relay::{GetEventsOptions, Output};
+
+impl Client {
+    /// Get the bookmarks (kind `10003`) of the signer
+    pub async fn bookmarks(&self, timeout: Option<Duration>) -> Result<Bookmarks, Error> {
+        match self.fetch_latest_bookmarks(None, timeout).await? {
+            Some(event) => Ok(Bookmarks::from_event(&event)?),
+            None => Ok(Bookmarks::new()),
+        }
+    }
+
+    /// Get the bookmark sets (kind `30003`) of the signer
+    pub async fn bookmark_sets(&self, timeout: Option<Duration>) -> Result<Vec<Bookmarks>, Error> {
+        let public_key: XOnlyPublicKey = self.signer_public_key().await?;
+        let filter: Filter = Filter::new().author(public_key).kind(Kind::BookmarkSet);
+        let events: Vec<Event> = self
+            .get_events_with_opts(
+                vec![filter],
+                timeout,
+                GetEventsOptions::new().collapse_replaceable(true),
+            )
+            .await?;
+        Ok(events
+            .iter()
+            .filter_map(|event| Bookmarks::from_event(event).ok())
+            .collect())
+    }
+
+    /// Bookmark a note, an article (by its coordinate), a hashtag or a URL
+    ///
+    /// Fetch the latest bookmarks (kind `10003`), add the bookmark and publish the new list.
+    /// The encrypted private bookmarks (content of the event) are preserved.
+    ///
+    /// Return `None` if already bookmarked.
+    ///
+    /// Return [`Error::ListNotFound`] if the bookmarks can't be fetched,
+    /// unless [`Options::create_missing_lists`](super::Options::create_missing_lists) is enabled.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use nostr_sdk::prelude::*;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #   let my_keys = Keys::generate();
+    /// #   let client = Client::new(&my_keys);
+    /// // Long-form article (i.e. parsed from `naddr` with `Coordinate::from_bech32`)
+    /// let public_key = XOnlyPublicKey::from_bech32(
+    ///     "npub14f8usejl26twx0dhuxjh9cas7keav9vr0v8nvtwtrjqx3vycc76qqh9nsy",
+    /// )
+    /// .unwrap();
+    /// let coordinate = Coordinate::new(Kind::LongFormTextNote, public_key).identifier("my-article");
+    /// client.bookmark(coordinate).await.unwrap();
+    /// # }
+    /// ```
+    pub async fn bookmark<T>(&self, bookmark: T) -> Result<Option<Output<EventId>>, Error>
+    where
+        T: Into<Bookmark>,
+    {
+        let bookmark: Bookmark = bookmark.into();
+        self.update_bookmarks(None, |list| list.add(bookmark)).await
+    }
+
+    /// Remove a bookmark (kind `10003`)
+    ///
+    /// Return `None` if not bookmarked.
+    ///
+    /// Return [`Error::ListNotFound`] if the bookmarks can't be fetched.
+    pub async fn unbookmark<T>(&self, bookmark: T) -> Result<Option<Output<EventId>>, Error>
+    where
+        T: Into<Bookmark>,
+    {
+        let bookmark: Bookmark = bookmark.into();
+        self.update_bookmarks(None, |list| list.remove(&bookmark))
+            .await
+    }
+
+    /// Add a bookmark to a bookmark set (kind `30003`)
+    ///
+    /// Return `None` if already bookmarked.
+    ///
+    /// Return [`Error::ListNotFound`] if the set can't be fetched,
+    /// unless [`Options::create_missing_lists`](super::Options::create_missing_lists) is enabled
+    /// (i.e. to create a new set).
+    pub async fn bookmark_in_set<S, T>(
+        &self,
+        identifier: S,
+        bookmark: T,
+    ) -> Result<Option<Output<EventId>>, Error>
+    where
+        S: Into<String>,
+        T: Into<Bookmark>,
+    {
+        let bookmark: Bookmark = bookmark.into();
+        self.update_bookmarks(Some(identifier.into()), |list| list.add(bookmark))
+            .await
+    }
+
+    /// Remove a bookmark from a bookmark set (kind `30003`)
+    ///
+    /// Return `None` if not bookmarked.
+    ///
+    /// Return [`Error::ListNotFound`] if the set can't be fetched.
+    pub async fn unbookmark_from_set<S, T>(
+        &self,
+        identifier: S,
+        bookmark: T,
+    ) -> Result<Option<Output<EventId>>, Error>
+    where
+        S: Into<String>,
+        T: Into<Bookmark>,
+    {
+        let bookmark: Bookmark = bookmark.into();
+        self.update_bookmarks(Some(identifier.into()), |list| list.remove(&bookmark))
+            .await
+    }
+
+    /// Fetch the latest bookmarks (`None` identifier) or bookmark set
+    async fn fetch_latest_bookmarks(
+        &self,
+        identifier: Option<&str>,
+        timeout: Option<Duration>,
+    ) -> Result<Option<Event>, Error> {
+        let public_key: XOnlyPublicKey = self.signer_public_key().await?;
+        let filter: Filter = match identifier {
+            Some(identifier) => Filter::new()
+                .author(public_key)
+                .kind(Kind::BookmarkSet)
+                .identifier(identifier),
+            None => Filter::new().author(public_key).kind(Kind::Bookmarks),
+        };
+        Ok(self
+            .get_events_of(vec![filter.limit(1)], timeout)
+            .await?
+            .into_iter()
+            .max_by_key(|e| e.created_at()))
+    }
+
+    /// Apply `f` to the latest bookmarks (or bookmark set) and publish them, if changed
+    async fn update_bookmarks<F>(
+        &self,
+        identifier: Option<String>,
+        f: F,
+    ) -> Result<Option<Output<EventId>>, Error>
+    where
+        F: FnOnce(&mut Bookmarks) -> bool,
+    {
+        let latest: Option<Event> = self
+            .fetch_latest_bookmarks(identifier.as_deref(), None)
+            .await?;
+
+        let (mut list, content): (Bookmarks, String) = match &latest {
+            Some(event) => (Bookmarks::from_event(event)?, event.content().to_string()),
+            // Don't replace a list that can't be fetched with an almost empty one
+            None if !self.opts.create_missing_lists => {
+                let kind: Kind = match identifier {
+                    Some(..) => Kind::BookmarkSet,
+                    None => Kind::Bookmarks,
+                };
+                return Err(Error::ListNotFound(kind));
+            }
+            None => match identifier {
+                Some(identifier) => (Bookmarks::set(identifier), String::new()),
+                None => (Bookmarks::new(), String::new()),
+            },
+        };
+
+        if !f(&mut list) {
+            return Ok(None);
+        }
+
+        // The new list must be newer than the replaced one, also with clock skews
+        let now: Timestamp = Timestamp::now();
+        let created_at: Timestamp = match &latest {
+            Some(latest) if latest.created_at() >= now => latest.created_at() + 1u64,
+            _ => now,
+        };
+
+        let builder: EventBuilder = list.to_event_builder(content).custom_created_at(created_at);
+        Ok(Some(self.send_event_builder(builder).await?))
+    }
+}
@@ -6,17 +6,23 @@
 
 use std::sync::Arc;
 
+use nostr_database::lazy::LazyDatabase;
 use nostr_database::memory::MemoryDatabase;
 use nostr_database::{DynNostrDatabase, IntoNostrDatabase};
 
-use super::signer::ClientSigner;
+#[cfg(feature = "nip57")]
+use super::nip57::{DynZapPayer, ZapPayer};
+use super::signer::{DynNostrSigner, IntoNostrSigner};
 use crate::{Client, Options};
 
 /// Client builder
 #[derive(Debug, Clone)]
 pub struct ClientBuilder {
-    pub(super) signer: Option<ClientSigner>,
+    pub(super) signer: Option<Arc<DynNostrSigner>>,
     pub(super) database: Arc<DynNostrDatabase>,
+    pub(super) lazy_database: Option<Arc<LazyDatabase>>,
+    #[cfg(feature = "nip57")]
+    pub(super) zapper: Option<Arc<DynZapPayer>>,
     pub(super) opts: Options,
 }
 
@@ -25,6 +31,9 @@ impl Default for ClientBuilder {
         Self {
             signer: None,
             database: Arc::new(MemoryDatabase::default()),
+            lazy_database: None,
+            #[cfg(feature = "nip57")]
+            zapper: None,
             opts: Options::default(),
         }
     }
@@ -50,9 +59,9 @@ impl ClientBuilder {
     /// ```
     pub fn signer<S>(mut self, signer: S) -> Self
     where
-        S: Into<ClientSigner>,
+        S: IntoNostrSigner,
     {
-        self.signer = Some(signer.into());
+        self.signer = Some(signer.into_nostr_signer());
         self
     }
 
@@ -62,6 +71,43 @@ impl ClientBuilder {
         D: IntoNostrDatabase,
     {
         self.database = database.into_nostr_database();
+        self.lazy_database = None;
+        self
+    }
+
+    /// Set lazy database
+    ///
+    /// The database is opened (and migrated) on [`Client::start`] (or on first access)
+    /// instead of blocking the builder construction.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use nostr_sdk::prelude::*;
+    ///
+    /// let database = LazyDatabase::new(|| async {
+    ///     Ok::<MemoryDatabase, DatabaseError>(MemoryDatabase::default())
+    /// })
+    /// .progress(|status| println!("Database: {status:?}"));
+    /// let builder = ClientBuilder::new().lazy_database(database);
+    ///
+    /// let _client: Client = builder.build();
+    /// ```
+    pub fn lazy_database(mut self, database: LazyDatabase) -> Self {
+        let database: Arc<LazyDatabase> = Arc::new(database);
+        self.database = database.clone().into_nostr_database();
+        self.lazy_database = Some(database);
+        self
+    }
+
+    /// Set zap payer
+    ///
+    /// Used to pay the invoices of the zaps sent with [`Client::zap`].
+    #[cfg(feature = "nip57")]
+    pub fn zapper<P>(mut self, zapper: P) -> Self
+    where
+        P: ZapPayer + 'static,
+    {
+        self.zapper = Some(Arc::new(zapper));
         self
     }
 
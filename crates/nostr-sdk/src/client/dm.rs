@@ -0,0 +1,306 @@
+// Copyright (c) 2022-2023 Yuki Kishimoto
+// Copyright (c) 2023-2024 Rust Nostr Developers
+// Distributed under the MIT software license
+
+//! Direct messages over NIP04 and NIP17
+//!
+//! <https://github.com/nostr-protocol/nips/blob/master/04.md>
+//! <https://github.com/nostr-protocol/nips/blob/master/17.md>
+
+use std::sync::Arc;
+
+use async_utility::futures_util::stream;
+use async_utility::thread;
+use nostr::nips::nip59::{self, UnwrappedGift};
+use nostr::secp256k1::XOnlyPublicKey;
+use nostr::{Event, EventBuilder, EventId, JsonUtil, Kind, Tag, Timestamp, UnsignedEvent};
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::mpsc;
+
+use super::options::DmPolicy;
+use super::signer::DynNostrSigner;
+use super::{Client, Error};
+use crate::relay::{Output, RelayPoolNotification};
+use crate::util::BoxedStream;
+
+/// Direct message protocol
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DmProtocol {
+    /// Encrypted direct message (NIP04)
+    Nip04,
+    /// Private direct message (NIP17)
+    Nip17,
+}
+
+/// Decrypted direct message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DirectMessage {
+    /// Event ID (of the kind `4` event or of the kind `14` rumor)
+    pub id: EventId,
+    /// Sender
+    pub sender: XOnlyPublicKey,
+    /// Receiver
+    pub receiver: XOnlyPublicKey,
+    /// Decrypted content
+    pub content: String,
+    /// Timestamp
+    pub created_at: Timestamp,
+    /// Protocol
+    pub protocol: DmProtocol,
+}
+
+impl Client {
+    /// Send direct message
+    ///
+    /// The protocol is selected by [`Options::dm_policy`](super::Options::dm_policy).
+    /// With [`DmPolicy::PreferNip17`], the message is sent as NIP04 if the signer doesn't support NIP44.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use nostr_sdk::prelude::*;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #   let my_keys = Keys::generate();
+    /// #   let client = Client::new(&my_keys);
+    /// let alice_pubkey = XOnlyPublicKey::from_bech32(
+    ///     "npub14f8usejl26twx0dhuxjh9cas7keav9vr0v8nvtwtrjqx3vycc76qqh9nsy",
+    /// )
+    /// .unwrap();
+    ///
+    /// client.send_dm(alice_pubkey, "Hello Alice!").await.unwrap();
+    /// # }
+    /// ```
+    pub async fn send_dm<S>(
+        &self,
+        receiver: XOnlyPublicKey,
+        content: S,
+    ) -> Result<Output<EventId>, Error>
+    where
+        S: Into<String>,
+    {
+        let content: String = content.into();
+        match self.opts.dm_policy {
+            DmPolicy::Nip04 => self.send_direct_msg(receiver, content, None).await,
+            DmPolicy::Nip17 => self.send_private_msg(receiver, content).await,
+            DmPolicy::PreferNip17 => match self.send_private_msg(receiver, content.clone()).await {
+                Err(Error::SignerMethodNotSupported(method)) => {
+                    tracing::debug!("Signer doesn't support {method}: falling back to NIP04");
+                    self.send_direct_msg(receiver, content, None).await
+                }
+                res => res,
+            },
+        }
+    }
+
+    /// Send private direct message (NIP17)
+    ///
+    /// The kind `14` rumor is gift wrapped to the receiver and to the sender (to sync the sent messages across devices).
+    /// Return the ID of the gift wrap sent to the receiver.
+    ///
+    /// <https://github.com/nostr-protocol/nips/blob/master/17.md>
+    pub async fn send_private_msg<S>(
+        &self,
+        receiver: XOnlyPublicKey,
+        content: S,
+    ) -> Result<Output<EventId>, Error>
+    where
+        S: Into<String>,
+    {
+        let signer: Arc<DynNostrSigner> = self.signer().await?;
+        let public_key: XOnlyPublicKey = signer.get_public_key().await?;
+        let rumor: UnsignedEvent =
+            EventBuilder::private_msg_rumor(receiver, content, None).to_unsigned_event(public_key);
+
+        let wrapped: Event = gift_wrap(signer.as_ref(), &receiver, &rumor).await?;
+        let output: Output<EventId> = self.send_event(wrapped).await?;
+
+        if receiver != public_key {
+            let wrapped: Event = gift_wrap(signer.as_ref(), &public_key, &rumor).await?;
+            if let Err(e) = self.send_event(wrapped).await {
+                tracing::warn!("Impossible to send the private message copy to the sender: {e}");
+            }
+        }
+
+        Ok(output)
+    }
+
+    /// Get a stream of the decrypted direct messages (both NIP04 and NIP17)
+    ///
+    /// The messages are taken from the notifications: subscribe to the kind `4` and `1059` events
+    /// tagging the signer public key to receive them. Events that can't be decrypted are skipped.
+    ///
+    /// The signer is the one configured when this method is called. The NIP17 messages require a signer supporting NIP44.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use nostr_sdk::prelude::*;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #   let my_keys = Keys::generate();
+    /// #   let client = Client::new(&my_keys);
+    /// let mut messages = client.dm_notifications().await.unwrap();
+    ///
+    /// let filter = Filter::new()
+    ///     .kinds([Kind::EncryptedDirectMessage, Kind::GiftWrap])
+    ///     .pubkey(my_keys.public_key());
+    /// client.subscribe(vec![filter]).await;
+    ///
+    /// while let Some(msg) = messages.next().await {
+    ///     println!("{}: {}", msg.sender, msg.content);
+    /// }
+    /// # }
+    /// ```
+    pub async fn dm_notifications(&self) -> Result<BoxedStream<DirectMessage>, Error> {
+        let signer: Arc<DynNostrSigner> = self.signer().await?;
+        let public_key: XOnlyPublicKey = signer.get_public_key().await?;
+        let mut notifications = self.notifications();
+        let (tx, rx) = mpsc::channel::<DirectMessage>(self.opts.pool.notification_channel_size);
+
+        thread::spawn(async move {
+            loop {
+                match notifications.recv().await {
+                    Ok(RelayPoolNotification::Event { event, .. }) => {
+                        let res: Result<Option<DirectMessage>, Error> = match event.kind() {
+                            Kind::EncryptedDirectMessage => {
+                                decrypt_nip04(signer.as_ref(), &public_key, &event).await
+                            }
+                            Kind::GiftWrap => decrypt_nip17(signer.as_ref(), &event).await,
+                            _ => continue,
+                        };
+
+                        match res {
+                            Ok(Some(msg)) => {
+                                if tx.send(msg).await.is_err() {
+                                    break;
+                                }
+                            }
+                            Ok(None) => (),
+                            Err(e) => {
+                                tracing::warn!(
+                                    "Impossible to decrypt direct message {}: {e}",
+                                    event.id()
+                                )
+                            }
+                        }
+                    }
+                    Ok(RelayPoolNotification::Stop) | Ok(RelayPoolNotification::Shutdown) => break,
+                    Ok(_) => (),
+                    Err(RecvError::Lagged(num)) => {
+                        tracing::warn!("Direct messages stream lagged: {num} notifications skipped")
+                    }
+                    Err(RecvError::Closed) => break,
+                }
+            }
+
+            tracing::debug!("Exited from direct messages stream");
+        });
+
+        Ok(Box::pin(stream::unfold(rx, |mut rx| async move {
+            let msg: DirectMessage = rx.recv().await?;
+            Some((msg, rx))
+        })))
+    }
+}
+
+/// Seal the rumor with the signer and gift wrap it to the receiver (NIP59)
+async fn gift_wrap(
+    signer: &DynNostrSigner,
+    receiver: &XOnlyPublicKey,
+    rumor: &UnsignedEvent,
+) -> Result<Event, Error> {
+    let content: String = signer.nip44_encrypt(*receiver, rumor.as_json()).await?;
+    let seal: UnsignedEvent = EventBuilder::new(Kind::Seal, content, [])
+        .tweak_created_at(nip59::RANGE_RANDOM_TIMESTAMP_TWEAK)
+        .to_unsigned_event(rumor.pubkey);
+    let seal: Event = signer.sign_event(seal).await?;
+    Ok(EventBuilder::gift_wrap_from_seal(receiver, &seal, None)?)
+}
+
+/// Unwrap the gift wrap with the signer (NIP59)
+async fn unwrap_gift_wrap(
+    signer: &DynNostrSigner,
+    gift_wrap: &Event,
+) -> Result<UnwrappedGift, Error> {
+    let seal: String = signer
+        .nip44_decrypt(gift_wrap.author(), gift_wrap.content().to_string())
+        .await?;
+    let seal: Event = Event::from_json(seal).map_err(nip59::Error::from)?;
+    if seal.kind() != Kind::Seal {
+        return Err(nip59::Error::NotSeal.into());
+    }
+    seal.verify().map_err(nip59::Error::from)?;
+
+    let rumor: String = signer
+        .nip44_decrypt(seal.author(), seal.content().to_string())
+        .await?;
+    let rumor: UnsignedEvent = UnsignedEvent::from_json(rumor).map_err(nip59::Error::from)?;
+    if rumor.pubkey != seal.author() {
+        return Err(nip59::Error::SenderMismatch.into());
+    }
+
+    Ok(UnwrappedGift {
+        sender: seal.author(),
+        rumor,
+    })
+}
+
+async fn decrypt_nip04(
+    signer: &DynNostrSigner,
+    public_key: &XOnlyPublicKey,
+    event: &Event,
+) -> Result<Option<DirectMessage>, Error> {
+    let receiver: XOnlyPublicKey = match event.public_keys().next() {
+        Some(receiver) => *receiver,
+        None => return Ok(None),
+    };
+
+    // Sent or received message
+    let peer: XOnlyPublicKey = if &event.author() == public_key {
+        receiver
+    } else if &receiver == public_key {
+        event.author()
+    } else {
+        return Ok(None);
+    };
+
+    let content: String = signer
+        .nip04_decrypt(peer, event.content().to_string())
+        .await?;
+    Ok(Some(DirectMessage {
+        id: event.id(),
+        sender: event.author(),
+        receiver,
+        content,
+        created_at: event.created_at(),
+        protocol: DmProtocol::Nip04,
+    }))
+}
+
+async fn decrypt_nip17(
+    signer: &DynNostrSigner,
+    event: &Event,
+) -> Result<Option<DirectMessage>, Error> {
+    let UnwrappedGift { sender, rumor } = unwrap_gift_wrap(signer, event).await?;
+    if rumor.kind != Kind::PrivateDirectMessage {
+        return Ok(None);
+    }
+
+    let receiver: XOnlyPublicKey = match rumor.tags.iter().find_map(|tag| match tag {
+        Tag::PublicKey { public_key, .. } => Some(*public_key),
+        _ => None,
+    }) {
+        Some(receiver) => receiver,
+        None => return Ok(None),
+    };
+
+    Ok(Some(DirectMessage {
+        id: rumor.id,
+        sender,
+        receiver,
+        content: rumor.content,
+        created_at: rumor.created_at,
+        protocol: DmProtocol::Nip17,
+    }))
+}
@@ -0,0 +1,281 @@
+// Copyright (c) 2022-2023 Yuki Kishimoto
+// Copyright (c) 2023-2024 Rust Nostr Developers
+// Distributed under the MIT software license
+
+//! Gossip (NIP65 outbox model)
+//!
+//! <https://github.com/nostr-protocol/nips/blob/master/65.md>
+
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+use std::time::Duration;
+
+use nostr::secp256k1::XOnlyPublicKey;
+use nostr::types::time::Instant;
+use nostr::{Alphabet, Event, Filter, GenericTagValue, Kind, RelayMetadata, Url};
+use nostr_database::NostrDatabaseExt;
+
+use super::{Client, Error};
+use crate::relay::{FilterOptions, RelayOptions};
+
+impl Client {
+    /// Fetch the NIP65 relay lists of the user and of its contacts
+    ///
+    /// The relay lists are stored in the database and used to route events and requests
+    /// when [`Options::gossip`](super::Options::gossip) is enabled.
+    ///
+    /// If timeout is set to `None`, the default from [`Options`](super::Options) will be used.
+    pub async fn sync_relay_lists(&self, timeout: Option<Duration>) -> Result<(), Error> {
+        let public_key: XOnlyPublicKey = self.signer_public_key().await?;
+        let mut public_keys: Vec<XOnlyPublicKey> =
+            self.get_contact_list_public_keys(timeout).await?;
+        public_keys.push(public_key);
+
+        let chunk_size: usize = self.opts.get_req_filters_chunk_size().max(1);
+        let filters: Vec<Filter> = public_keys
+            .chunks(chunk_size)
+            .map(|chunk| {
+                Filter::new()
+                    .authors(chunk.iter().copied())
+                    .kind(Kind::RelayList)
+            })
+            .collect();
+
+        let timeout: Duration = timeout.unwrap_or(self.opts.timeout);
+        let events: Vec<Event> = self
+            .pool
+            .get_events_of(filters, timeout, FilterOptions::ExitOnEOSE)
+            .await?;
+        tracing::debug!(
+            "Synced relay lists of {} public keys ({} events received)",
+            public_keys.len(),
+            events.len()
+        );
+
+        Ok(())
+    }
+
+    /// Get the relays where to send the [`Event`]
+    ///
+    /// Return an empty set if the relay lists are unknown.
+    pub(super) async fn gossip_event_relays(&self, event: &Event) -> Result<HashSet<Url>, Error> {
+        let author: XOnlyPublicKey = event.author();
+
+        // Outbox
+        let mut urls: HashSet<Url> = self
+            .gossip_relay_lists([author], RelayMetadata::Write)
+            .await?
+            .into_values()
+            .flatten()
+            .collect();
+
+        // Inbox of the tagged public keys (skipped for lists, i.e. contact list)
+        if !event.kind().is_replaceable() && !event.kind().is_parameterized_replaceable() {
+            let public_keys: Vec<XOnlyPublicKey> = event
+                .public_keys()
+                .filter(|p| **p != author)
+                .copied()
+                .collect();
+            urls.extend(
+                self.gossip_relay_lists(public_keys, RelayMetadata::Read)
+                    .await?
+                    .into_values()
+                    .flatten(),
+            );
+        }
+
+        if !urls.is_empty() {
+            self.add_gossip_relays(urls.iter().cloned()).await?;
+        }
+
+        Ok(urls)
+    }
+
+    /// Split the filters by relay
+    ///
+    /// Return `None` if the relay lists of the authors and of the tagged public keys are unknown.
+    pub(super) async fn gossip_filters_routes(
+        &self,
+        filters: &[Filter],
+    ) -> Result<Option<HashMap<Url, Vec<Filter>>>, Error> {
+        let pool_relays: Vec<Url> = self.pool.relays().await.into_keys().collect();
+        let mut routes: HashMap<Url, Vec<Filter>> = HashMap::new();
+        let mut routed: bool = false;
+
+        for filter in filters.iter() {
+            if filter.authors.is_empty() {
+                // Inbox of the tagged public keys
+                let mut urls: HashSet<Url> = self
+                    .gossip_relay_lists(tagged_public_keys(filter), RelayMetadata::Read)
+                    .await?
+                    .into_values()
+                    .flatten()
+                    .collect();
+                routed |= !urls.is_empty();
+                urls.extend(pool_relays.iter().cloned());
+                for url in urls.into_iter() {
+                    routes.entry(url).or_default().push(filter.clone());
+                }
+            } else {
+                // Outbox of the authors
+                let lists: HashMap<XOnlyPublicKey, Vec<Url>> = self
+                    .gossip_relay_lists(filter.authors.iter().copied(), RelayMetadata::Write)
+                    .await?;
+                let mut authors_by_relay: HashMap<Url, Vec<XOnlyPublicKey>> = HashMap::new();
+                let mut unknown: Vec<XOnlyPublicKey> = Vec::new();
+                for author in filter.authors.iter() {
+                    match lists.get(author) {
+                        Some(urls) => {
+                            routed = true;
+                            for url in urls.iter() {
+                                authors_by_relay
+                                    .entry(url.clone())
+                                    .or_default()
+                                    .push(*author);
+                            }
+                        }
+                        None => unknown.push(*author),
+                    }
+                }
+
+                if !unknown.is_empty() {
+                    for url in pool_relays.iter() {
+                        authors_by_relay
+                            .entry(url.clone())
+                            .or_default()
+                            .extend(unknown.iter().copied());
+                    }
+                }
+
+                for (url, authors) in authors_by_relay.into_iter() {
+                    let mut filter: Filter = filter.clone();
+                    filter.authors = authors.into_iter().collect();
+                    routes.entry(url).or_default().push(filter);
+                }
+            }
+        }
+
+        if !routed {
+            return Ok(None);
+        }
+
+        self.add_gossip_relays(routes.keys().cloned()).await?;
+
+        Ok(Some(routes))
+    }
+
+    /// Get the read or write relays of the public keys, up to [`Options::gossip_relays_per_pubkey`](super::Options::gossip_relays_per_pubkey) each
+    ///
+    /// The relays are ranked by the number of public keys listing them, to cover all the public keys
+    /// with fewer relays. Public keys without a known relay list are not included.
+    async fn gossip_relay_lists<I>(
+        &self,
+        public_keys: I,
+        metadata: RelayMetadata,
+    ) -> Result<HashMap<XOnlyPublicKey, Vec<Url>>, Error>
+    where
+        I: IntoIterator<Item = XOnlyPublicKey>,
+    {
+        let database = self.database();
+        let mut lists: HashMap<XOnlyPublicKey, Vec<Url>> = HashMap::new();
+        let mut frequency: HashMap<Url, usize> = HashMap::new();
+        for public_key in public_keys.into_iter() {
+            if lists.contains_key(&public_key) {
+                continue;
+            }
+
+            let urls: Vec<Url> = database
+                .relay_list(public_key)
+                .await?
+                .into_iter()
+                .filter(|(_, m)| m.as_ref().map_or(true, |m| m == &metadata))
+                .map(|(url, _)| url)
+                .collect();
+            if urls.is_empty() {
+                continue;
+            }
+
+            for url in urls.iter() {
+                *frequency.entry(url.clone()).or_default() += 1;
+            }
+            lists.insert(public_key, urls);
+        }
+
+        for urls in lists.values_mut() {
+            urls.sort_by(|a, b| frequency[b].cmp(&frequency[a]).then_with(|| a.cmp(b)));
+            urls.truncate(self.opts.gossip_relays_per_pubkey);
+        }
+
+        Ok(lists)
+    }
+
+    /// Add and connect the relays not already in the pool
+    ///
+    /// The added relays are tracked, to not publish them in the user relay list and to remove
+    /// the least recently used ones above [`Options::gossip_max_relays`](super::Options::gossip_max_relays).
+    async fn add_gossip_relays<I>(&self, urls: I) -> Result<(), Error>
+    where
+        I: IntoIterator<Item = Url>,
+    {
+        let urls: HashSet<Url> = urls.into_iter().collect();
+        let now: Instant = Instant::now();
+
+        for url in urls.iter() {
+            if self
+                .internal_add_relay(url.clone(), RelayOptions::new())
+                .await?
+            {
+                tracing::debug!("Added gossip relay {url}");
+                self.gossip_relays.write().await.insert(url.clone(), now);
+                self.connect_relay(url.clone()).await?;
+            } else if let Some(last_used) = self.gossip_relays.write().await.get_mut(url) {
+                *last_used = now;
+            }
+        }
+
+        self.evict_gossip_relays(&urls).await
+    }
+
+    /// Remove the least recently used gossip relays above [`Options::gossip_max_relays`](super::Options::gossip_max_relays)
+    ///
+    /// The relays in `in_use` are never removed.
+    async fn evict_gossip_relays(&self, in_use: &HashSet<Url>) -> Result<(), Error> {
+        let mut gossip_relays = self.gossip_relays.write().await;
+        let excess: usize = gossip_relays
+            .len()
+            .saturating_sub(self.opts.gossip_max_relays);
+        if excess == 0 {
+            return Ok(());
+        }
+
+        let mut candidates: Vec<(Url, Instant)> = gossip_relays
+            .iter()
+            .filter(|(url, ..)| !in_use.contains(url))
+            .map(|(url, last_used)| (url.clone(), *last_used))
+            .collect();
+        candidates.sort_by_key(|(_, last_used)| *last_used);
+
+        for (url, ..) in candidates.into_iter().take(excess) {
+            gossip_relays.remove(&url);
+            self.pool.remove_relay(url.clone()).await?;
+            tracing::debug!("Removed unused gossip relay {url}");
+        }
+
+        Ok(())
+    }
+}
+
+/// Get the public keys of the `#p` filter
+fn tagged_public_keys(filter: &Filter) -> Vec<XOnlyPublicKey> {
+    match filter.generic_tags.get(&Alphabet::P) {
+        Some(values) => values
+            .iter()
+            .filter_map(|value| match value {
+                GenericTagValue::Pubkey(public_key) => Some(*public_key),
+                GenericTagValue::String(s) => XOnlyPublicKey::from_str(s).ok(),
+                GenericTagValue::EventId(..) => None,
+            })
+            .collect(),
+        None => Vec::new(),
+    }
+}
@@ -0,0 +1,128 @@
+// Copyright (c) 2022-2023 Yuki Kishimoto
+// Copyright (c) 2023-2024 Rust Nostr Developers
+// Distributed under the MIT software license
+
+//! Relay groups
+
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
+
+use nostr::{Event, EventBuilder, EventId, Filter, Url};
+
+use super::{Client, Error};
+use crate::relay::{pool, FilterOptions, Output, RelaySendOptions};
+use crate::util::TryIntoUrl;
+
+impl Client {
+    /// Get relay groups
+    pub async fn relay_groups(&self) -> HashMap<String, HashSet<Url>> {
+        self.pool.groups().await
+    }
+
+    /// Add relay to a named group (i.e. `dm`, `feed`, `search` or `backup`)
+    ///
+    /// The relay must be already added (see [`Client::add_relay`]).
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use nostr_sdk::prelude::*;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #   let my_keys = Keys::generate();
+    /// #   let client = Client::new(&my_keys);
+    /// client.add_relay("wss://relay.damus.io").await.unwrap();
+    /// client
+    ///     .add_relay_to_group("backup", "wss://relay.damus.io")
+    ///     .await
+    ///     .unwrap();
+    /// # }
+    /// ```
+    pub async fn add_relay_to_group<S, U>(&self, group: S, url: U) -> Result<(), Error>
+    where
+        S: Into<String>,
+        U: TryIntoUrl,
+        pool::Error: From<<U as TryIntoUrl>::Err>,
+    {
+        Ok(self.pool.add_relay_to_group(group, url).await?)
+    }
+
+    /// Remove relay from a group
+    ///
+    /// The relay is **NOT** removed from the client.
+    pub async fn remove_relay_from_group<U>(&self, group: &str, url: U) -> Result<(), Error>
+    where
+        U: TryIntoUrl,
+        pool::Error: From<<U as TryIntoUrl>::Err>,
+    {
+        Ok(self.pool.remove_relay_from_group(group, url).await?)
+    }
+
+    /// Send event to the relays of a group
+    ///
+    /// This method will wait for the `OK` message from the relays.
+    pub async fn send_event_to_group(
+        &self,
+        group: &str,
+        event: Event,
+    ) -> Result<Output<EventId>, Error> {
+        let opts = RelaySendOptions::new()
+            .skip_disconnected(self.opts.get_skip_disconnected_relays())
+            .timeout(self.opts.send_timeout);
+        Ok(self.pool.send_event_to_group(group, event, opts).await?)
+    }
+
+    /// Signs the [`EventBuilder`] into an [`Event`] and sends it to the relays of a group
+    pub async fn send_event_builder_to_group(
+        &self,
+        group: &str,
+        builder: EventBuilder,
+    ) -> Result<Output<EventId>, Error> {
+        let event: Event = self.internal_sign_event_builder(builder).await?;
+        self.send_event_to_group(group, event).await
+    }
+
+    /// Subscribe to filters on the relays of a group
+    pub async fn subscribe_group(
+        &self,
+        group: &str,
+        filters: Vec<Filter>,
+    ) -> Result<Output<()>, Error> {
+        Ok(self
+            .pool
+            .subscribe_group(group, filters, self.subscription_wait())
+            .await?)
+    }
+
+    /// Unsubscribe from filters on the relays of a group
+    pub async fn unsubscribe_group(&self, group: &str) -> Result<(), Error> {
+        Ok(self
+            .pool
+            .unsubscribe_group(group, self.subscription_wait())
+            .await?)
+    }
+
+    /// Get events of filters from the relays of a group
+    ///
+    /// If timeout is set to `None`, the default from [`Options`](super::Options) will be used.
+    pub async fn get_events_from_group(
+        &self,
+        group: &str,
+        filters: Vec<Filter>,
+        timeout: Option<Duration>,
+    ) -> Result<Vec<Event>, Error> {
+        let timeout: Duration = timeout.unwrap_or(self.opts.timeout);
+        Ok(self
+            .pool
+            .get_events_from_group(group, filters, timeout, FilterOptions::ExitOnEOSE)
+            .await?)
+    }
+
+    fn subscription_wait(&self) -> Option<Duration> {
+        if self.opts.get_wait_for_subscription() {
+            self.opts.send_timeout
+        } else {
+            None
+        }
+    }
+}
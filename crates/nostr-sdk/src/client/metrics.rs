@@ -0,0 +1,138 @@
+// Copyright (c) 2022-2023 Yuki Kishimoto
+// Copyright (c) 2023-2024 Rust Nostr Developers
+// Distributed under the MIT software license
+
+//! Client metrics
+
+use std::collections::HashMap;
+use std::time::Duration;
+
+use nostr::Url;
+
+use super::Client;
+use crate::relay::RelayConnectionStats;
+
+/// Metrics of a relay
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RelayMetrics {
+    /// `EVENT` messages sent
+    pub events_sent: usize,
+    /// `EVENT` messages received
+    pub events_received: usize,
+    /// `OK` messages received with `true` status
+    pub oks_accepted: usize,
+    /// `OK` messages received with `false` status
+    pub oks_rejected: usize,
+    /// `EOSE` messages received
+    pub eoses: usize,
+    /// `AUTH` challenges received (NIP42)
+    pub auth_challenges: usize,
+    /// Bytes sent
+    pub bytes_sent: usize,
+    /// Bytes received
+    pub bytes_received: usize,
+    /// Average time between the sending of an event and its `OK` acceptance
+    pub publish_latency: Option<Duration>,
+}
+
+impl From<&RelayConnectionStats> for RelayMetrics {
+    fn from(stats: &RelayConnectionStats) -> Self {
+        Self {
+            events_sent: stats.events_sent(),
+            events_received: stats.events_received(),
+            oks_accepted: stats.oks_accepted(),
+            oks_rejected: stats.oks_rejected(),
+            eoses: stats.eoses(),
+            auth_challenges: stats.auth_challenges(),
+            bytes_sent: stats.bytes_sent(),
+            bytes_received: stats.bytes_received(),
+            publish_latency: stats.publish_latency(),
+        }
+    }
+}
+
+/// Metrics of the [`Client`], by relay
+///
+/// Counters start when the relay is added to the client.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ClientMetrics {
+    /// Metrics by relay
+    pub relays: HashMap<Url, RelayMetrics>,
+}
+
+impl ClientMetrics {
+    /// Aggregate the metrics of all the relays
+    ///
+    /// The publish latency is the average of the relay latencies.
+    pub fn total(&self) -> RelayMetrics {
+        let mut total = RelayMetrics::default();
+        let mut latencies: Vec<Duration> = Vec::new();
+        for metrics in self.relays.values() {
+            total.events_sent += metrics.events_sent;
+            total.events_received += metrics.events_received;
+            total.oks_accepted += metrics.oks_accepted;
+            total.oks_rejected += metrics.oks_rejected;
+            total.eoses += metrics.eoses;
+            total.auth_challenges += metrics.auth_challenges;
+            total.bytes_sent += metrics.bytes_sent;
+            total.bytes_received += metrics.bytes_received;
+            latencies.extend(metrics.publish_latency);
+        }
+        let sum: Duration = latencies.iter().sum();
+        total.publish_latency = sum.checked_div(latencies.len() as u32);
+        total
+    }
+
+    /// Emit the metrics as `tracing` events (`INFO` level), one per relay plus the total
+    pub fn trace(&self) {
+        for (url, metrics) in self.relays.iter() {
+            trace_metrics(url.as_str(), metrics);
+        }
+        trace_metrics("total", &self.total());
+    }
+}
+
+fn trace_metrics(relay: &str, metrics: &RelayMetrics) {
+    tracing::info!(
+        relay,
+        events_sent = metrics.events_sent,
+        events_received = metrics.events_received,
+        oks_accepted = metrics.oks_accepted,
+        oks_rejected = metrics.oks_rejected,
+        eoses = metrics.eoses,
+        auth_challenges = metrics.auth_challenges,
+        bytes_sent = metrics.bytes_sent,
+        bytes_received = metrics.bytes_received,
+        publish_latency_ms = metrics.publish_latency.map(|l| l.as_millis() as u64),
+        "Client metrics"
+    );
+}
+
+impl Client {
+    /// Get the metrics of the relays
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use nostr_sdk::prelude::*;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #   let my_keys = Keys::generate();
+    /// #   let client = Client::new(&my_keys);
+    /// let metrics = client.metrics().await;
+    /// println!("Events sent: {}", metrics.total().events_sent);
+    ///
+    /// // Emit as `tracing` fields
+    /// metrics.trace();
+    /// # }
+    /// ```
+    pub async fn metrics(&self) -> ClientMetrics {
+        let relays = self.relays().await;
+        ClientMetrics {
+            relays: relays
+                .into_iter()
+                .map(|(url, relay)| (url, RelayMetrics::from(&relay.stats())))
+                .collect(),
+        }
+    }
+}
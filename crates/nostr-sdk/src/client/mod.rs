@@ -4,48 +4,105 @@
 
 //! Client
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::future::Future;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 
 use async_utility::thread;
 use nostr::event::builder::Error as EventBuilderError;
 use nostr::key::XOnlyPublicKey;
-#[cfg(feature = "nip46")]
-use nostr::nips::nip46::{Request, Response};
+#[cfg(feature = "nip05")]
+use nostr::nips::nip05::{self, Nip05Cache};
+#[cfg(feature = "nip11")]
+use nostr::nips::nip11::RelayInformationDocument;
+use nostr::nips::nip21::Nip21;
+use nostr::nips::nip25::{ExternalContent, Reactions};
+#[cfg(feature = "nip44")]
+use nostr::nips::nip51::MuteList;
+use nostr::nips::nip65;
 use nostr::nips::nip94::FileMetadata;
+#[cfg(feature = "nip98")]
+use nostr::nips::nip98::{self, HttpData};
 use nostr::types::metadata::Error as MetadataError;
+use nostr::types::time::Instant;
 use nostr::url::Url;
 use nostr::util::EventIdOrCoordinate;
 use nostr::{
     ClientMessage, Contact, Event, EventBuilder, EventId, Filter, JsonUtil, Keys, Kind, Metadata,
-    Result, Tag, Timestamp,
+    RelayMetadata, Result, Tag, Timestamp, UncheckedUrl, UnsignedEvent,
 };
-use nostr_database::DynNostrDatabase;
+use nostr_database::lazy::LazyDatabase;
+#[cfg(feature = "nip11")]
+use nostr_database::RelayInfo;
+use nostr_database::{DatabaseError, DynNostrDatabase, Order, Profile, SendOutsideWasm};
+#[cfg(feature = "nip44")]
+use tokio::sync::Mutex;
 use tokio::sync::{broadcast, RwLock};
 
+mod auth;
 #[cfg(feature = "blocking")]
 pub mod blocking;
+mod bookmarks;
 pub mod builder;
+#[cfg(all(feature = "nip04", feature = "nip44", feature = "nip59"))]
+pub mod dm;
+mod gossip;
+mod groups;
+mod metrics;
+#[cfg(feature = "nip44")]
+mod mute;
+mod nip10;
+#[cfg(feature = "nip44")]
+mod nip44;
+pub mod nip53;
+#[cfg(feature = "nip57")]
+pub mod nip57;
+pub mod nip90;
+#[cfg(feature = "nip96")]
+pub mod nip96;
 pub mod options;
+mod outbox;
+mod refresher;
+mod scheduler;
 pub mod signer;
 
 pub use self::builder::ClientBuilder;
-pub use self::options::Options;
+#[cfg(all(feature = "nip04", feature = "nip44", feature = "nip59"))]
+pub use self::dm::{DirectMessage, DmProtocol};
+pub use self::metrics::{ClientMetrics, RelayMetrics};
+#[cfg(feature = "nip44")]
+use self::nip44::ConversationKeyCache;
+pub use self::nip53::LiveEventTracker;
+#[cfg(feature = "nip57")]
+pub use self::nip57::{DynZapPayer, ZapPayer, ZapTarget};
+pub use self::nip90::JobHandle;
+#[cfg(feature = "nip96")]
+pub use self::nip96::FileSource;
+pub use self::options::{DmPolicy, DuplicateGuard, Options};
 #[cfg(feature = "nip46")]
 pub use self::signer::nip46::Nip46Signer;
-pub use self::signer::{ClientSigner, ClientSignerType};
+pub use self::signer::{DynNostrSigner, IntoNostrSigner, NostrSigner};
 use crate::relay::pool::{self, Error as RelayPoolError, RelayPool};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::relay::RelayProxy;
 use crate::relay::{
-    FilterOptions, NegentropyOptions, Relay, RelayOptions, RelayPoolNotification, RelaySendOptions,
+    CountReport, FilterOptions, GetEventsOptions, InternalSubscriptionId, NegentropyOptions,
+    Output, RebroadcastOptions, Relay, RelayHookEvent, RelayOptions, RelayPoolNotification,
+    RelaySendOptions, ReqHandle, SubscriptionHandle,
 };
-use crate::util::TryIntoUrl;
+use crate::util::{BoxedStream, TryIntoUrl};
+
+/// Max number of events loaded and sent at once by [`Client::rebroadcast`]
+const REBROADCAST_PAGE_SIZE: usize = 500;
 
 /// [`Client`] error
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
+    /// I/O error
+    #[error(transparent)]
+    IO(#[from] std::io::Error),
     /// Keys error
     #[error(transparent)]
     Keys(#[from] nostr::key::Error),
@@ -70,24 +127,85 @@ pub enum Error {
     /// Metadata error
     #[error(transparent)]
     Metadata(#[from] MetadataError),
+    /// Database error
+    #[error("database error: {0}")]
+    Database(#[from] DatabaseError),
     /// Notification Handler error
     #[error("notification handler error: {0}")]
     Handler(String),
     /// Signer not configured
     #[error("signer not configured")]
     SignerNotConfigured,
-    /// Signer not configured
-    #[error("wrong signer: expected={expected}, found={found}")]
-    WrongSigner {
-        /// Expected client signer type
-        expected: ClientSignerType,
-        /// Found client signer type
-        found: ClientSignerType,
+    /// Custom signer error
+    #[error("signer error: {0}")]
+    Signer(String),
+    /// Method not supported by the signer
+    #[error("method not supported by the signer: {0}")]
+    SignerMethodNotSupported(String),
+    /// Already reacted to or reposted the event
+    #[error("duplicate: already published as {0}")]
+    Duplicate(EventId),
+    /// Not a job request (NIP90)
+    #[error("not a job request")]
+    NotJobRequest,
+    /// Job failed (NIP90)
+    #[error("job failed: {0}")]
+    JobFailed(String),
+    /// Job result not received in time (NIP90)
+    #[error("job result not received in time")]
+    JobTimeout,
+    /// Event not found
+    #[error("event not found: {0}")]
+    EventNotFound(EventId),
+    /// Event vetoed by an outgoing hook
+    #[error("event vetoed: {0}")]
+    EventVetoed(String),
+    /// Unexpected event kind
+    #[error("wrong kind: expected={expected}, found={found}")]
+    WrongKind {
+        /// Expected kind
+        expected: Kind,
+        /// Found kind
+        found: Kind,
+    },
+    /// A newer contact list was received while updating it
+    #[error("contact list conflict: a newer contact list was received")]
+    ContactListConflict,
+    /// List not found while updating it
+    #[error("list not found: kind={0}")]
+    ListNotFound(Kind),
+    /// Zap payer not configured (NIP57)
+    #[error("zap payer not configured")]
+    ZapPayerNotConfigured,
+    /// Zap payment failed (NIP57)
+    #[error("zap payment failed: {0}")]
+    ZapPayment(String),
+    /// The invoice received from the LNURL service doesn't match the zap amount (NIP57)
+    #[error("zap invoice amount mismatch: expected={expected} msat, found={found:?} msat")]
+    ZapInvoiceAmountMismatch {
+        /// Requested amount
+        expected: u64,
+        /// Invoice amount (`None` if missing or not decodable)
+        found: Option<u64>,
     },
+    /// MIME type not accepted by the file server (NIP96)
+    #[error("MIME type not accepted by the server: {0}")]
+    UnsupportedMimeType(String),
     /// NIP04 error
     #[cfg(feature = "nip04")]
     #[error(transparent)]
     NIP04(#[from] nostr::nips::nip04::Error),
+    /// NIP11 error
+    #[cfg(feature = "nip11")]
+    #[error(transparent)]
+    NIP11(#[from] nostr::nips::nip11::Error),
+    /// NIP21 error
+    #[error(transparent)]
+    NIP21(#[from] nostr::nips::nip21::Error),
+    /// NIP44 error
+    #[cfg(feature = "nip44")]
+    #[error(transparent)]
+    NIP44(#[from] nostr::nips::nip44::Error),
     /// NIP07 error
     #[cfg(all(feature = "nip07", target_arch = "wasm32"))]
     #[error(transparent)]
@@ -96,6 +214,21 @@ pub enum Error {
     #[cfg(feature = "nip46")]
     #[error(transparent)]
     NIP46(#[from] nostr::nips::nip46::Error),
+    /// NIP51 error
+    #[error(transparent)]
+    NIP51(#[from] nostr::nips::nip51::Error),
+    /// NIP57 error
+    #[cfg(feature = "nip57")]
+    #[error(transparent)]
+    NIP57(#[from] nostr::nips::nip57::Error),
+    /// NIP59 error
+    #[cfg(feature = "nip59")]
+    #[error(transparent)]
+    NIP59(#[from] nostr::nips::nip59::Error),
+    /// NIP96 error
+    #[cfg(feature = "nip96")]
+    #[error(transparent)]
+    NIP96(#[from] nostr::nips::nip96::Error),
     /// JSON error
     #[cfg(feature = "nip46")]
     #[error(transparent)]
@@ -126,8 +259,21 @@ pub enum Error {
 #[derive(Debug, Clone)]
 pub struct Client {
     pool: RelayPool,
-    signer: Arc<RwLock<Option<ClientSigner>>>,
+    signer: Arc<RwLock<Option<Arc<DynNostrSigner>>>>,
+    lazy_database: Option<Arc<LazyDatabase>>,
     opts: Options,
+    scheduler: Arc<AtomicBool>,
+    metadata_refresher: Arc<AtomicU64>,
+    authenticator: Arc<AtomicBool>,
+    gossip_relays: Arc<RwLock<HashMap<Url, Instant>>>,
+    #[cfg(feature = "nip44")]
+    conversation_keys: Arc<Mutex<ConversationKeyCache>>,
+    #[cfg(feature = "nip44")]
+    mute_list: Arc<RwLock<MuteList>>,
+    #[cfg(feature = "nip57")]
+    zapper: Option<Arc<DynZapPayer>>,
+    #[cfg(feature = "nip05")]
+    nip05_cache: Nip05Cache,
     dropped: Arc<AtomicBool>,
 }
 
@@ -173,7 +319,7 @@ impl Client {
     /// ```
     pub fn new<S>(signer: S) -> Self
     where
-        S: Into<ClientSigner>,
+        S: IntoNostrSigner,
     {
         Self::with_opts(signer, Options::default())
     }
@@ -192,17 +338,35 @@ impl Client {
     /// ```
     pub fn with_opts<S>(signer: S, opts: Options) -> Self
     where
-        S: Into<ClientSigner>,
+        S: IntoNostrSigner,
     {
         ClientBuilder::new().signer(signer).opts(opts).build()
     }
 
     /// Compose [`Client`] from [`ClientBuilder`]
     pub fn from_builder(builder: ClientBuilder) -> Self {
+        #[cfg(feature = "nip44")]
+        let conversation_keys =
+            ConversationKeyCache::new(builder.opts.conversation_keys_cache_size);
+        let pool = RelayPool::with_database(builder.opts.pool, builder.database);
+        pool.update_automatic_authentication(builder.opts.get_automatic_authentication());
         Self {
-            pool: RelayPool::with_database(builder.opts.pool, builder.database),
+            pool,
             signer: Arc::new(RwLock::new(builder.signer)),
+            lazy_database: builder.lazy_database,
             opts: builder.opts,
+            scheduler: Arc::new(AtomicBool::new(false)),
+            metadata_refresher: Arc::new(AtomicU64::new(0)),
+            authenticator: Arc::new(AtomicBool::new(false)),
+            gossip_relays: Arc::new(RwLock::new(HashMap::new())),
+            #[cfg(feature = "nip44")]
+            conversation_keys: Arc::new(Mutex::new(conversation_keys)),
+            #[cfg(feature = "nip44")]
+            mute_list: Arc::new(RwLock::new(MuteList::new())),
+            #[cfg(feature = "nip57")]
+            zapper: builder.zapper,
+            #[cfg(feature = "nip05")]
+            nip05_cache: Nip05Cache::default(),
             dropped: Arc::new(AtomicBool::new(false)),
         }
     }
@@ -215,33 +379,32 @@ impl Client {
     /// Get current client signer
     ///
     /// Rise error if it not set.
-    pub async fn signer(&self) -> Result<ClientSigner, Error> {
+    pub async fn signer(&self) -> Result<Arc<DynNostrSigner>, Error> {
         let signer = self.signer.read().await;
         signer.clone().ok_or(Error::SignerNotConfigured)
     }
 
     /// Set client signer
-    pub async fn set_signer(&self, signer: Option<ClientSigner>) {
+    pub async fn set_signer(&self, signer: Option<Arc<DynNostrSigner>>) {
         let mut s = self.signer.write().await;
         *s = signer;
     }
 
     /// Get current [`Keys`]
+    ///
+    /// Return watch-only [`Keys`] of the signer public key (random [`Keys`] if no signer is configured).
     #[deprecated(since = "0.27.0", note = "Use `client.signer().await` instead.")]
     pub async fn keys(&self) -> Keys {
-        let signer = self.signer.read().await;
-        if let Some(ClientSigner::Keys(keys)) = &*signer {
-            keys.clone()
-        } else {
-            Keys::generate()
+        match self.signer_public_key().await {
+            Ok(public_key) => Keys::watch_only(public_key),
+            Err(_) => Keys::generate(),
         }
     }
 
     /// Change [`Keys`]
     #[deprecated(since = "0.27.0", note = "Use `client.set_signer(...).await` instead.")]
     pub async fn set_keys(&self, keys: &Keys) {
-        self.set_signer(Some(ClientSigner::Keys(keys.clone())))
-            .await;
+        self.set_signer(Some(keys.into_nostr_signer())).await;
     }
 
     /// Get [`RelayPool`]
@@ -255,9 +418,16 @@ impl Client {
     }
 
     /// Start a previously stopped client
-    pub async fn start(&self) {
+    ///
+    /// If a lazy database was set in the [`ClientBuilder`], it's opened (and migrated) here:
+    /// opening errors are returned and the client is not started.
+    pub async fn start(&self) -> Result<(), Error> {
+        if let Some(database) = &self.lazy_database {
+            database.open().await?;
+        }
         self.pool.start();
         self.connect().await;
+        Ok(())
     }
 
     /// Stop the client
@@ -282,6 +452,90 @@ impl Client {
         self.pool.notifications()
     }
 
+    /// Register an async hook, called on relay connection events (connect, disconnect, auth success/failure and permanent failure)
+    pub async fn add_relay_hook<F, Fut>(&self, hook: F)
+    where
+        F: Fn(Url, RelayHookEvent) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + SendOutsideWasm + 'static,
+    {
+        self.pool.add_hook(hook).await;
+    }
+
+    /// Register an async hook, called on every event built and signed by the client
+    ///
+    /// The hook can mutate the [`EventBuilder`] (i.e. attach a client tag) or veto the event,
+    /// returning the reason as error: the sending is then aborted with [`Error::EventVetoed`].
+    /// Events signed outside the client (i.e. passed to [`Client::send_event`]) are not intercepted.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use nostr_sdk::prelude::*;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #   let my_keys = Keys::generate();
+    /// #   let client = Client::new(&my_keys);
+    /// client
+    ///     .add_outgoing_hook(|builder: EventBuilder| async move {
+    ///         if builder.content().contains("password") {
+    ///             return Err(String::from("content may leak a secret"));
+    ///         }
+    ///         Ok(builder.add_tags([Tag::Generic(
+    ///             TagKind::Custom(String::from("client")),
+    ///             vec![String::from("my-client")],
+    ///         )]))
+    ///     })
+    ///     .await;
+    /// # }
+    /// ```
+    pub async fn add_outgoing_hook<F, Fut>(&self, hook: F)
+    where
+        F: Fn(EventBuilder) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<EventBuilder, String>> + SendOutsideWasm + 'static,
+    {
+        self.pool.middleware().add_outgoing(hook).await;
+    }
+
+    /// Register an async hook, called on every verified event received from relays
+    ///
+    /// The hook can transform the event or filter it out, returning `None`.
+    /// Filtered events are neither saved into the database nor notified.
+    /// Transformed events must still be valid (signature included), otherwise they are discarded.
+    ///
+    /// Hooks are awaited by the notification handler: keep them fast.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use nostr_sdk::prelude::*;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #   let my_keys = Keys::generate();
+    /// #   let client = Client::new(&my_keys);
+    /// client
+    ///     .add_incoming_hook(|_relay_url: Url, event: Event| async move {
+    ///         if event.content().contains("spam") {
+    ///             None
+    ///         } else {
+    ///             Some(event)
+    ///         }
+    ///     })
+    ///     .await;
+    /// # }
+    /// ```
+    pub async fn add_incoming_hook<F, Fut>(&self, hook: F)
+    where
+        F: Fn(Url, Event) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Option<Event>> + SendOutsideWasm + 'static,
+    {
+        self.pool.middleware().add_incoming(hook).await;
+    }
+
+    /// Remove all the outgoing and incoming event hooks
+    pub async fn clear_event_hooks(&self) {
+        self.pool.middleware().clear().await;
+    }
+
     /// Get relays
     pub async fn relays(&self) -> HashMap<Url, Relay> {
         self.pool.relays().await
@@ -296,13 +550,68 @@ impl Client {
         Ok(self.pool.relay(url).await?)
     }
 
+    /// Get relay info (NIP11)
+    ///
+    /// Return the info cached in the database if younger than [`Options::relay_info_ttl`],
+    /// otherwise fetch the relay information document and update the cache.
+    /// If the fetch fails, the expired info is returned (if any).
+    ///
+    /// To list the relays known by the database, see [`NostrDatabaseExt::known_relays`](nostr_database::NostrDatabaseExt::known_relays).
+    ///
+    /// <https://github.com/nostr-protocol/nips/blob/master/11.md>
+    #[cfg(feature = "nip11")]
+    pub async fn relay_info<U>(&self, url: U) -> Result<RelayInfo, Error>
+    where
+        U: TryIntoUrl,
+        Error: From<<U as TryIntoUrl>::Err>,
+    {
+        let url: Url = url.try_into_url()?;
+        let database = self.database();
+
+        let cached: Option<RelayInfo> = match database.relay_info(&url).await {
+            Ok(info) => info,
+            Err(DatabaseError::NotSupported) => None,
+            Err(e) => return Err(e.into()),
+        };
+
+        if let Some(info) = &cached {
+            if !info.is_expired(self.opts.relay_info_ttl) {
+                return Ok(info.clone());
+            }
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        let proxy = self.opts.proxy;
+        #[cfg(target_arch = "wasm32")]
+        let proxy = None;
+
+        match RelayInformationDocument::get(url.clone(), proxy).await {
+            Ok(document) => {
+                let info: RelayInfo = crate::relay::relay_info_from_document(url, &document);
+                match database.save_relay_info(info.clone()).await {
+                    Ok(()) | Err(DatabaseError::NotSupported) => (),
+                    Err(e) => return Err(e.into()),
+                }
+                Ok(info)
+            }
+            Err(e) => match cached {
+                Some(info) => {
+                    tracing::warn!("Impossible to refresh relay info of {url}: {e}");
+                    Ok(info)
+                }
+                None => Err(e.into()),
+            },
+        }
+    }
+
     /// Add new relay
     ///
     /// This method **NOT** automatically start connection with relay!
     ///
     /// Return `false` if the relay already exists.
     ///
-    /// To use a proxy, see `Client::add_relay_with_opts`.
+    /// The relay uses the client proxy, if any (see [`Options::proxy`]).
+    /// To use a different proxy or connect directly, see `Client::add_relay_with_opts`.
     ///
     /// # Example
     /// ```rust,no_run
@@ -323,11 +632,7 @@ impl Client {
         U: TryIntoUrl,
         pool::Error: From<<U as TryIntoUrl>::Err>,
     {
-        #[cfg(not(target_arch = "wasm32"))]
-        let opts: RelayOptions = RelayOptions::new().proxy(self.opts.proxy);
-        #[cfg(target_arch = "wasm32")]
-        let opts: RelayOptions = RelayOptions::new();
-        self.add_relay_with_opts(url, opts).await
+        self.add_relay_with_opts(url, RelayOptions::new()).await
     }
 
     /// Add new relay with [`RelayOptions`]
@@ -336,9 +641,13 @@ impl Client {
     ///
     /// Return `false` if the relay already exists.
     ///
+    /// If the proxy is [`RelayProxy::Inherit`] (default), the client proxy is used.
+    /// This allows routing only some relays through Tor, keeping the others direct.
+    ///
     /// # Example
     /// ```rust,no_run
     /// use std::net::{Ipv4Addr, SocketAddr, SocketAddrV4};
+    /// use std::time::Duration;
     ///
     /// use nostr_sdk::prelude::*;
     ///
@@ -347,7 +656,11 @@ impl Client {
     /// #   let my_keys = Keys::generate();
     /// #   let client = Client::new(&my_keys);
     /// let proxy = Some(SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 9050)));
-    /// let opts = RelayOptions::new().proxy(proxy).write(false).retry_sec(11);
+    /// let policy = ReconnectPolicy::fixed(Duration::from_secs(11));
+    /// let opts = RelayOptions::new()
+    ///     .proxy(proxy)
+    ///     .write(false)
+    ///     .reconnect_policy(policy);
     /// client
     ///     .add_relay_with_opts("wss://relay.nostr.info", opts)
     ///     .await
@@ -361,6 +674,24 @@ impl Client {
         U: TryIntoUrl,
         pool::Error: From<<U as TryIntoUrl>::Err>,
     {
+        let url: Url = url.try_into_url().map_err(RelayPoolError::from)?;
+
+        // Explicitly added: no longer a gossip relay
+        self.gossip_relays.write().await.remove(&url);
+
+        self.internal_add_relay(url, opts).await
+    }
+
+    pub(super) async fn internal_add_relay(
+        &self,
+        url: Url,
+        opts: RelayOptions,
+    ) -> Result<bool, Error> {
+        #[cfg(not(target_arch = "wasm32"))]
+        let opts: RelayOptions = match opts.proxy {
+            RelayProxy::Inherit => opts.proxy(self.opts.proxy),
+            _ => opts,
+        };
         Ok(self.pool.add_relay(url, opts).await?)
     }
 
@@ -382,7 +713,9 @@ impl Client {
         U: TryIntoUrl,
         pool::Error: From<<U as TryIntoUrl>::Err>,
     {
-        self.pool.remove_relay(url).await?;
+        let url: Url = url.try_into_url().map_err(RelayPoolError::from)?;
+        self.pool.remove_relay::<Url>(url.clone()).await?;
+        self.gossip_relays.write().await.remove(&url);
         Ok(())
     }
 
@@ -401,6 +734,78 @@ impl Client {
         Ok(())
     }
 
+    /// Publish the relay list (NIP65) of the current relays
+    ///
+    /// The read/write flags of the [`RelayOptions`] of every relay are published as markers.
+    /// Relays with both read and write disabled and relays added by gossip are skipped.
+    ///
+    /// <https://github.com/nostr-protocol/nips/blob/master/65.md>
+    pub async fn publish_relay_list(&self) -> Result<Output<EventId>, Error> {
+        let mut relays = self.relays().await;
+        let gossip_relays = self.gossip_relays.read().await;
+        relays.retain(|url, _| !gossip_relays.contains_key(url));
+        drop(gossip_relays);
+
+        let list = relays.into_iter().filter_map(|(url, relay)| {
+            let opts: RelayOptions = relay.opts();
+            let metadata: Option<RelayMetadata> = match (opts.get_read(), opts.get_write()) {
+                (true, true) => None,
+                (true, false) => Some(RelayMetadata::Read),
+                (false, true) => Some(RelayMetadata::Write),
+                (false, false) => return None,
+            };
+            Some((UncheckedUrl::from(url.to_string()), metadata))
+        });
+        let builder = EventBuilder::relay_list(list);
+        self.send_event_builder(builder).await
+    }
+
+    /// Apply a relay list (NIP65) to the pool
+    ///
+    /// Missing relays are added (without connecting to them) and the read/write flags of the
+    /// listed relays are updated. Relays not in the list are left untouched.
+    ///
+    /// <https://github.com/nostr-protocol/nips/blob/master/65.md>
+    pub async fn apply_relay_list(&self, event: &Event) -> Result<(), Error> {
+        if event.kind() != Kind::RelayList {
+            return Err(Error::WrongKind {
+                expected: Kind::RelayList,
+                found: event.kind(),
+            });
+        }
+
+        for (url, metadata) in nip65::extract_relay_list(event).into_iter() {
+            let url: Url = match Url::try_from(url) {
+                Ok(url) => url,
+                Err(e) => {
+                    tracing::warn!("Invalid relay list url: {e}");
+                    continue;
+                }
+            };
+            let (read, write): (bool, bool) = match metadata {
+                Some(RelayMetadata::Read) => (true, false),
+                Some(RelayMetadata::Write) => (false, true),
+                None => (true, true),
+            };
+
+            let relays = self.relays().await;
+            match relays.get(&url) {
+                Some(relay) => {
+                    let opts: RelayOptions = relay.opts();
+                    opts.update_read(read);
+                    opts.update_write(write);
+                    self.gossip_relays.write().await.remove(&url);
+                }
+                None => {
+                    let opts: RelayOptions = RelayOptions::new().read(read).write(write);
+                    self.add_relay_with_opts(url, opts).await?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     /// Connect to a previously added relay
     ///
     /// # Example
@@ -423,6 +828,7 @@ impl Client {
         pool::Error: From<<U as TryIntoUrl>::Err>,
     {
         let relay: Relay = self.relay(url).await?;
+        self.spawn_authenticator();
         self.pool
             .connect_relay(&relay, self.opts.connection_timeout)
             .await;
@@ -457,6 +863,8 @@ impl Client {
 
     /// Connect relays
     ///
+    /// If [`Options::prefetch_contacts()`] is set, start also the contacts metadata and relay lists refresher.
+    ///
     /// # Example
     /// ```rust,no_run
     /// use nostr_sdk::prelude::*;
@@ -469,7 +877,15 @@ impl Client {
     /// # }
     /// ```
     pub async fn connect(&self) {
+        self.spawn_authenticator();
         self.pool.connect(self.opts.connection_timeout).await;
+        self.spawn_scheduler();
+
+        if let Some(staleness) = self.opts.prefetch_contacts {
+            if let Err(e) = self.refresh_contacts_metadata(staleness).await {
+                tracing::error!("Impossible to start contacts prefetch: {e}");
+            }
+        }
     }
 
     /// Disconnect from all relays
@@ -506,18 +922,117 @@ impl Client {
     /// client.subscribe(vec![subscription]).await;
     /// # }
     /// ```
-    pub async fn subscribe(&self, filters: Vec<Filter>) {
+    pub async fn subscribe(&self, filters: Vec<Filter>) -> Output<()> {
         let wait: Option<Duration> = if self.opts.get_wait_for_subscription() {
             self.opts.send_timeout
         } else {
             None
         };
-        self.pool.subscribe(filters, wait).await;
+        self.pool.subscribe(filters, wait).await
     }
 
     /// Subscribe to filters with custom wait
-    pub async fn subscribe_with_custom_wait(&self, filters: Vec<Filter>, wait: Option<Duration>) {
-        self.pool.subscribe(filters, wait).await;
+    pub async fn subscribe_with_custom_wait(
+        &self,
+        filters: Vec<Filter>,
+        wait: Option<Duration>,
+    ) -> Output<()> {
+        self.pool.subscribe(filters, wait).await
+    }
+
+    /// Subscribe to filters with a custom [`InternalSubscriptionId`]
+    ///
+    /// Return a [`SubscriptionHandle`] to update or close the subscription independently
+    /// from the one managed by [`Client::subscribe`].
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use nostr_sdk::prelude::*;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #   let my_keys = Keys::generate();
+    /// #   let client = Client::new(&my_keys);
+    /// let filter = Filter::new().kind(Kind::TextNote).since(Timestamp::now());
+    /// let handle = client.subscribe_with_id("notes", vec![filter]).await.val;
+    ///
+    /// let mut notifications = client.notifications();
+    /// while let Ok(notification) = notifications.recv().await {
+    ///     if let RelayPoolNotification::Event {
+    ///         relay_url,
+    ///         subscription_id,
+    ///         event,
+    ///     } = notification
+    ///     {
+    ///         if handle.is_subscription(&relay_url, &subscription_id).await {
+    ///             println!("{}", event.content());
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// handle.close().await;
+    /// # }
+    /// ```
+    pub async fn subscribe_with_id<I>(
+        &self,
+        internal_id: I,
+        filters: Vec<Filter>,
+    ) -> Output<SubscriptionHandle>
+    where
+        I: Into<InternalSubscriptionId>,
+    {
+        let wait: Option<Duration> = if self.opts.get_wait_for_subscription() {
+            self.opts.send_timeout
+        } else {
+            None
+        };
+        let handle: SubscriptionHandle = self.pool.subscription_handle(internal_id.into(), wait);
+        handle.update(filters).await.map(|()| handle)
+    }
+
+    /// Get [`SubscriptionHandle`] of an existing subscription with a custom [`InternalSubscriptionId`]
+    ///
+    /// Return `None` if the subscription doesn't exist or has been closed.
+    pub async fn subscription<I>(&self, internal_id: I) -> Option<SubscriptionHandle>
+    where
+        I: Into<InternalSubscriptionId>,
+    {
+        let wait: Option<Duration> = if self.opts.get_wait_for_subscription() {
+            self.opts.send_timeout
+        } else {
+            None
+        };
+        self.pool.subscription(&internal_id.into(), wait).await
+    }
+
+    /// Get filters of the subscriptions with a custom [`InternalSubscriptionId`]
+    pub async fn subscriptions(&self) -> HashMap<InternalSubscriptionId, Vec<Filter>> {
+        self.pool.subscriptions().await
+    }
+
+    /// Close the subscription with a custom [`InternalSubscriptionId`]
+    pub async fn unsubscribe_with_id<I>(&self, internal_id: I)
+    where
+        I: Into<InternalSubscriptionId>,
+    {
+        let wait: Option<Duration> = if self.opts.get_wait_for_subscription() {
+            self.opts.send_timeout
+        } else {
+            None
+        };
+        self.pool
+            .unsubscribe_with_internal_id(internal_id.into(), wait)
+            .await;
+    }
+
+    /// Close all subscriptions
+    pub async fn unsubscribe_all(&self) {
+        let wait: Option<Duration> = if self.opts.get_wait_for_subscription() {
+            self.opts.send_timeout
+        } else {
+            None
+        };
+        self.pool.unsubscribe_all(wait).await;
     }
 
     /// Unsubscribe from filters
@@ -572,25 +1087,329 @@ impl Client {
     /// Get events of filters with [`FilterOptions`]
     ///
     /// If timeout is set to `None`, the default from [`Options`] will be used.
+    ///
+    /// If gossip is enabled, the filters are routed with the NIP65 relay lists (see [`Options::gossip`]).
     pub async fn get_events_of_with_opts(
         &self,
         filters: Vec<Filter>,
         timeout: Option<Duration>,
-        opts: FilterOptions,
-    ) -> Result<Vec<Event>, Error> {
-        let timeout: Duration = match timeout {
-            Some(t) => t,
-            None => self.opts.timeout,
-        };
-        Ok(self.pool.get_events_of(filters, timeout, opts).await?)
-    }
-
-    /// Request events of filters
-    /// All events will be received on notification listener (`client.notifications()`)
-    /// until the EOSE "end of stored events" message is received from the relay.
-    ///
-    /// If timeout is set to `None`, the default from [`Options`] will be used.
-    pub async fn req_events_of(&self, filters: Vec<Filter>, timeout: Option<Duration>) {
+        opts: FilterOptions,
+    ) -> Result<Vec<Event>, Error> {
+        let timeout: Duration = match timeout {
+            Some(t) => t,
+            None => self.opts.timeout,
+        };
+
+        if self.opts.gossip {
+            if let Some(routes) = self.gossip_filters_routes(&filters).await? {
+                return Ok(self
+                    .pool
+                    .get_events_routed(filters, routes, timeout, opts)
+                    .await?);
+            }
+        }
+
+        Ok(self.pool.get_events_of(filters, timeout, opts).await?)
+    }
+
+    /// Get events of filters with [`GetEventsOptions`]
+    ///
+    /// Like [`Client::get_events_of_with_opts`], but the results can be ordered,
+    /// the replaceable events collapsed (only the newest of every coordinate is kept)
+    /// and their number capped across all relays.
+    ///
+    /// If timeout is set to `None`, the default from [`Options`] will be used.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use nostr_sdk::prelude::*;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #   let my_keys = Keys::generate();
+    /// #   let client = Client::new(&my_keys);
+    /// let filter = Filter::new().kind(Kind::Metadata).limit(100);
+    ///
+    /// let opts = GetEventsOptions::new()
+    ///     .order(EventsOrder::CreatedAtDesc)
+    ///     .collapse_replaceable(true)
+    ///     .max_events(Some(50));
+    /// let events = client
+    ///     .get_events_with_opts(vec![filter], None, opts)
+    ///     .await
+    ///     .unwrap();
+    /// # }
+    /// ```
+    pub async fn get_events_with_opts(
+        &self,
+        filters: Vec<Filter>,
+        timeout: Option<Duration>,
+        opts: GetEventsOptions,
+    ) -> Result<Vec<Event>, Error> {
+        let events: Vec<Event> = self
+            .get_events_of_with_opts(filters, timeout, opts.filter)
+            .await?;
+        Ok(opts.apply(events))
+    }
+
+    /// Stream events of filters
+    ///
+    /// Like [`Client::get_events_of`], but the events are yielded as they arrive (stored events first)
+    /// instead of being collected into a `Vec`: useful to render the results incrementally
+    /// or to keep the memory bounded for large queries.
+    ///
+    /// If timeout is set to `None`, the default from [`Options`] will be used.
+    ///
+    /// If gossip is enabled, the filters are routed with the NIP65 relay lists (see [`Options::gossip`]).
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use std::time::Duration;
+    ///
+    /// use nostr_sdk::prelude::*;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #   let my_keys = Keys::generate();
+    /// #   let client = Client::new(&my_keys);
+    /// let filter = Filter::new().kind(Kind::TextNote).limit(1000);
+    ///
+    /// let timeout = Duration::from_secs(10);
+    /// let mut stream = client
+    ///     .stream_events_of(vec![filter], Some(timeout))
+    ///     .await
+    ///     .unwrap();
+    ///
+    /// while let Some(event) = stream.next().await {
+    ///     println!("{}", event.id());
+    /// }
+    /// # }
+    /// ```
+    pub async fn stream_events_of(
+        &self,
+        filters: Vec<Filter>,
+        timeout: Option<Duration>,
+    ) -> Result<BoxedStream<Event>, Error> {
+        let timeout: Duration = match timeout {
+            Some(t) => t,
+            None => self.opts.timeout,
+        };
+        let opts = FilterOptions::ExitOnEOSE;
+
+        if self.opts.gossip {
+            if let Some(routes) = self.gossip_filters_routes(&filters).await? {
+                return Ok(Box::pin(
+                    self.pool
+                        .stream_events_routed(filters, routes, timeout, opts)
+                        .await,
+                ));
+            }
+        }
+
+        Ok(Box::pin(
+            self.pool.stream_events_of(filters, timeout, opts).await,
+        ))
+    }
+
+    /// Fetch the entity referenced by a `nostr:` URI (NIP21)
+    ///
+    /// `npub` and `nprofile` URIs resolve to the profile metadata event.
+    /// For replaceable entities, the latest version is returned.
+    ///
+    /// If timeout is set to `None`, the default from [`Options`] will be used.
+    pub async fn fetch_nostr_uri<S>(
+        &self,
+        uri: S,
+        timeout: Option<Duration>,
+    ) -> Result<Option<Event>, Error>
+    where
+        S: AsRef<str>,
+    {
+        let nip21: Nip21 = Nip21::parse(uri)?;
+        let events: Vec<Event> = self.get_events_of(vec![nip21.to_filter()], timeout).await?;
+        Ok(events.into_iter().max_by_key(|e| e.created_at()))
+    }
+
+    /// Fetch the profile metadata of `public_key`
+    ///
+    /// The local database is checked first: if the metadata isn't there, it's requested to relays
+    /// (and stored in the database). If no metadata is found, the [`Profile`] has empty metadata.
+    ///
+    /// If timeout is set to `None`, the default from [`Options`] will be used.
+    pub async fn fetch_metadata(
+        &self,
+        public_key: XOnlyPublicKey,
+        timeout: Option<Duration>,
+    ) -> Result<Profile, Error> {
+        let filter: Filter = Filter::new()
+            .author(public_key)
+            .kind(Kind::Metadata)
+            .limit(1);
+
+        let database = self.database();
+        let mut events: Vec<Event> = database.query(vec![filter.clone()], Order::Desc).await?;
+        if events.is_empty() {
+            events = self.get_events_of(vec![filter], timeout).await?;
+        }
+
+        match events.into_iter().max_by_key(|e| e.created_at()) {
+            Some(event) => Ok(Profile::new(
+                public_key,
+                Metadata::from_json(event.content())?,
+            )),
+            None => Ok(Profile::from(public_key)),
+        }
+    }
+
+    /// Fetch the profile metadata of many public keys
+    ///
+    /// Like [`Client::fetch_metadata`]: the metadata missing in the local database are requested
+    /// to relays in chunks of [`Options::req_filters_chunk_size`] public keys.
+    /// Invalid metadata are skipped.
+    pub async fn fetch_metadata_batch<I>(
+        &self,
+        public_keys: I,
+        timeout: Option<Duration>,
+    ) -> Result<HashMap<XOnlyPublicKey, Profile>, Error>
+    where
+        I: IntoIterator<Item = XOnlyPublicKey>,
+    {
+        let public_keys: HashSet<XOnlyPublicKey> = public_keys.into_iter().collect();
+        if public_keys.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        fn keep_latest(latest: &mut HashMap<XOnlyPublicKey, Event>, events: Vec<Event>) {
+            for event in events.into_iter() {
+                match latest.get(&event.author()) {
+                    Some(e) if e.created_at() >= event.created_at() => (),
+                    _ => {
+                        latest.insert(event.author(), event);
+                    }
+                }
+            }
+        }
+
+        let mut latest: HashMap<XOnlyPublicKey, Event> = HashMap::new();
+
+        // Local database
+        let filter: Filter = Filter::new()
+            .authors(public_keys.iter().copied())
+            .kind(Kind::Metadata);
+        let database = self.database();
+        keep_latest(
+            &mut latest,
+            database.query(vec![filter], Order::Desc).await?,
+        );
+
+        // Relays
+        let missing: Vec<XOnlyPublicKey> = public_keys
+            .iter()
+            .filter(|p| !latest.contains_key(p))
+            .copied()
+            .collect();
+        let chunk_size: usize = self.opts.get_req_filters_chunk_size();
+        for chunk in missing.chunks(chunk_size) {
+            let filter: Filter = Filter::new()
+                .authors(chunk.iter().copied())
+                .kind(Kind::Metadata);
+            keep_latest(
+                &mut latest,
+                self.get_events_of(vec![filter], timeout).await?,
+            );
+        }
+
+        Ok(public_keys
+            .into_iter()
+            .map(|public_key| {
+                let profile: Profile = match latest.get(&public_key) {
+                    Some(event) => match Metadata::from_json(event.content()) {
+                        Ok(metadata) => Profile::new(public_key, metadata),
+                        Err(e) => {
+                            tracing::warn!("Invalid metadata of {public_key}: {e}");
+                            Profile::from(public_key)
+                        }
+                    },
+                    None => Profile::from(public_key),
+                };
+                (public_key, profile)
+            })
+            .collect())
+    }
+
+    /// Count events of filters
+    ///
+    /// Send `COUNT` (NIP45) to the relays supporting it (detected with NIP11 or at runtime) and count
+    /// the matching events in the local database. Relays that don't support `COUNT` fall back to `REQ`.
+    ///
+    /// The [`CountReport`] contains the per-relay counts and the database count:
+    /// use [`CountReport::total`] to get the aggregate.
+    ///
+    /// If timeout is set to `None`, the default from [`Options`] will be used.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use std::time::Duration;
+    ///
+    /// use nostr_sdk::prelude::*;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #   let my_keys = Keys::generate();
+    /// #   let client = Client::new(&my_keys);
+    /// let filter = Filter::new().kind(Kind::TextNote).author(my_keys.public_key());
+    ///
+    /// let report = client
+    ///     .count_events_of(vec![filter], Some(Duration::from_secs(10)))
+    ///     .await
+    ///     .unwrap();
+    /// println!("Notes: {}", report.total());
+    /// # }
+    /// ```
+    pub async fn count_events_of(
+        &self,
+        filters: Vec<Filter>,
+        timeout: Option<Duration>,
+    ) -> Result<CountReport, Error> {
+        let timeout: Duration = match timeout {
+            Some(t) => t,
+            None => self.opts.timeout,
+        };
+        Ok(self.pool.count_events_of(filters, timeout).await?)
+    }
+
+    /// Request events of filters
+    /// All events will be received on notification listener (`client.notifications()`)
+    /// until the EOSE "end of stored events" message is received from the relay.
+    ///
+    /// If timeout is set to `None`, the default from [`Options`] will be used.
+    ///
+    /// The returned [`ReqHandle`] can be used to wait for completion, check the per-relay status
+    /// or cancel the request (i.e. when the user navigates away).
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// # use std::time::Duration;
+    /// # use nostr_sdk::prelude::*;
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #   let my_keys = Keys::generate();
+    /// #   let client = Client::new(&my_keys);
+    /// let filter = Filter::new().kind(Kind::TextNote).limit(100);
+    /// let handle = client.req_events_of(vec![filter], None).await;
+    ///
+    /// // ...
+    ///
+    /// if !handle.is_completed().await {
+    ///     handle.cancel().await;
+    /// }
+    /// # }
+    /// ```
+    pub async fn req_events_of(
+        &self,
+        filters: Vec<Filter>,
+        timeout: Option<Duration>,
+    ) -> ReqHandle {
         self.req_events_of_with_opts(filters, timeout, FilterOptions::ExitOnEOSE)
             .await
     }
@@ -603,23 +1422,22 @@ impl Client {
         filters: Vec<Filter>,
         timeout: Option<Duration>,
         opts: FilterOptions,
-    ) {
+    ) -> ReqHandle {
         let timeout: Duration = match timeout {
             Some(t) => t,
             None => self.opts.timeout,
         };
-        self.pool.req_events_of(filters, timeout, opts).await;
+        self.pool.req_events_of(filters, timeout, opts).await
     }
 
     /// Send client message
-    pub async fn send_msg(&self, msg: ClientMessage) -> Result<(), Error> {
+    pub async fn send_msg(&self, msg: ClientMessage) -> Result<Output<()>, Error> {
         let wait: Option<Duration> = if self.opts.get_wait_for_send() {
             self.opts.send_timeout
         } else {
             None
         };
-        self.pool.send_msg(msg, wait).await?;
-        Ok(())
+        Ok(self.pool.send_msg(msg, wait).await?)
     }
 
     /// Batch send client messages
@@ -627,13 +1445,12 @@ impl Client {
         &self,
         msgs: Vec<ClientMessage>,
         wait: Option<Duration>,
-    ) -> Result<(), Error> {
-        self.pool.batch_msg(msgs, wait).await?;
-        Ok(())
+    ) -> Result<Output<()>, Error> {
+        Ok(self.pool.batch_msg(msgs, wait).await?)
     }
 
     /// Send client message to a specific relay
-    pub async fn send_msg_to<U>(&self, url: U, msg: ClientMessage) -> Result<(), Error>
+    pub async fn send_msg_to<U>(&self, url: U, msg: ClientMessage) -> Result<Output<()>, Error>
     where
         U: TryIntoUrl,
         pool::Error: From<<U as TryIntoUrl>::Err>,
@@ -650,29 +1467,156 @@ impl Client {
     ///
     /// This method will wait for the `OK` message from the relay.
     /// If you not want to wait for the `OK` message, use `send_msg` method instead.
-    pub async fn send_event(&self, event: Event) -> Result<EventId, Error> {
+    ///
+    /// Protected events (NIP70) are sent only to the relays where the client is authenticated (see [`Client::authenticate`]).
+    ///
+    /// If gossip is enabled, the event is routed with the NIP65 relay lists (see [`Options::gossip`]).
+    ///
+    /// In offline mode, the event is only queued into the outbox (see [`Options::offline`]).
+    pub async fn send_event(&self, event: Event) -> Result<Output<EventId>, Error> {
+        if self.opts.get_offline() {
+            let event_id: EventId = event.id();
+            self.queue_outbox_event(&event).await?;
+            return Ok(Output::new(event_id));
+        }
+
+        self.internal_send_event(event).await
+    }
+
+    async fn internal_send_event(&self, event: Event) -> Result<Output<EventId>, Error> {
         let timeout: Option<Duration> = self.opts.send_timeout;
         let opts = RelaySendOptions::new()
             .skip_disconnected(self.opts.get_skip_disconnected_relays())
             .timeout(timeout);
+
+        if self.opts.gossip {
+            let urls: HashSet<Url> = self.gossip_event_relays(&event).await?;
+            if !urls.is_empty() {
+                return Ok(self.pool.send_event_to_urls(urls, event, opts).await?);
+            }
+        }
+
         Ok(self.pool.send_event(event, opts).await?)
     }
 
     /// Send multiple [`Event`] at once
+    ///
+    /// In offline mode, the events are only queued into the outbox (see [`Options::offline`]).
     pub async fn batch_event(
         &self,
         events: Vec<Event>,
         opts: RelaySendOptions,
-    ) -> Result<(), Error> {
-        self.pool.batch_event(events, opts).await?;
-        Ok(())
+    ) -> Result<Output<()>, Error> {
+        if self.opts.get_offline() {
+            for event in events.iter() {
+                self.queue_outbox_event(event).await?;
+            }
+            return Ok(Output::new(()));
+        }
+
+        Ok(self.pool.batch_event(events, opts).await?)
+    }
+
+    /// Rebroadcast the events of the database matching the filter
+    ///
+    /// Useful after adding a new relay or after restoring the database from a backup.
+    ///
+    /// The events are read from the database and sent in pages, from the newest to the oldest.
+    /// The `limit` of the filter, if set, caps the total number of rebroadcasted events.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use nostr_sdk::prelude::*;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #   let my_keys = Keys::generate();
+    /// #   let client = Client::new(&my_keys);
+    /// let url = Url::parse("wss://relay.damus.io").unwrap();
+    /// client.add_relay(url.clone()).await.unwrap();
+    /// client.connect_relay(url.clone()).await.unwrap();
+    ///
+    /// let filter = Filter::new().author(my_keys.public_key());
+    /// let opts = RebroadcastOptions::new().relays([url]);
+    /// let output = client.rebroadcast(filter, opts).await.unwrap();
+    /// println!("Rebroadcasted to {} relays", output.success().len());
+    /// # }
+    /// ```
+    pub async fn rebroadcast(
+        &self,
+        filter: Filter,
+        opts: RebroadcastOptions,
+    ) -> Result<Output<()>, Error> {
+        let database = self.database();
+        let mut output: Output<()> = Output::new(());
+        let mut remaining: Option<usize> = filter.limit;
+        let mut until: Option<Timestamp> = filter.until;
+
+        // IDs of the events already sent with `created_at` equal to `until`
+        let mut boundary: HashSet<EventId> = HashSet::new();
+
+        // Page from the newest to the oldest event, to not load all the events in memory
+        loop {
+            let page_size: usize = match remaining {
+                Some(0) => break,
+                Some(remaining) => remaining.min(REBROADCAST_PAGE_SIZE),
+                None => REBROADCAST_PAGE_SIZE,
+            };
+
+            let mut page_filter: Filter = filter.clone().limit(page_size);
+            if let Some(until) = until {
+                page_filter = page_filter.until(until);
+            }
+
+            let events: Vec<Event> = database.query(vec![page_filter], Order::Desc).await?;
+            let full: bool = events.len() >= page_size;
+            let events: Vec<Event> = events
+                .into_iter()
+                .filter(|event| !boundary.contains(&event.id()))
+                .collect();
+
+            let oldest: Timestamp = match events.last() {
+                Some(event) => event.created_at(),
+                None => break,
+            };
+            if until != Some(oldest) {
+                boundary.clear();
+            }
+            boundary.extend(
+                events
+                    .iter()
+                    .filter(|event| event.created_at() == oldest)
+                    .map(|event| event.id()),
+            );
+            until = Some(oldest);
+
+            if let Some(remaining) = remaining.as_mut() {
+                *remaining = remaining.saturating_sub(events.len());
+            }
+
+            let page: Output<()> = match &opts.relays {
+                Some(urls) => {
+                    self.pool
+                        .batch_event_to_urls(urls.clone(), events, opts.send)
+                        .await?
+                }
+                None => self.pool.batch_event(events, opts.send).await?,
+            };
+            output.merge(page);
+
+            if !full {
+                break;
+            }
+        }
+
+        Ok(output)
     }
 
     /// Send event to specific relay
     ///
     /// This method will wait for the `OK` message from the relay.
     /// If you not want to wait for the `OK` message, use `send_msg` method instead.
-    pub async fn send_event_to<U>(&self, url: U, event: Event) -> Result<EventId, Error>
+    pub async fn send_event_to<U>(&self, url: U, event: Event) -> Result<Output<EventId>, Error>
     where
         U: TryIntoUrl,
         pool::Error: From<<U as TryIntoUrl>::Err>,
@@ -685,70 +1629,51 @@ impl Client {
     }
 
     async fn internal_sign_event_builder(&self, builder: EventBuilder) -> Result<Event, Error> {
-        match self.signer().await? {
-            ClientSigner::Keys(keys) => {
-                let difficulty: u8 = self.opts.get_difficulty();
-                if difficulty > 0 {
-                    Ok(builder.to_pow_event(&keys, difficulty)?)
-                } else {
-                    Ok(builder.to_event(&keys)?)
-                }
-            }
-            #[cfg(all(feature = "nip07", target_arch = "wasm32"))]
-            ClientSigner::NIP07(nip07) => {
-                let public_key: XOnlyPublicKey = nip07.get_public_key().await?;
-                let unsigned = {
-                    let difficulty: u8 = self.opts.get_difficulty();
-                    if difficulty > 0 {
-                        builder.to_unsigned_pow_event(public_key, difficulty)
-                    } else {
-                        builder.to_unsigned_event(public_key)
-                    }
-                };
-                Ok(nip07.sign_event(unsigned).await?)
-            }
-            #[cfg(feature = "nip46")]
-            ClientSigner::NIP46(nip46) => {
-                let signer_public_key: XOnlyPublicKey = nip46
-                    .signer_public_key()
-                    .await
-                    .ok_or(Error::SignerPublicKeyNotFound)?;
-                let unsigned = {
-                    let difficulty: u8 = self.opts.get_difficulty();
-                    if difficulty > 0 {
-                        builder.to_unsigned_pow_event(signer_public_key, difficulty)
-                    } else {
-                        builder.to_unsigned_event(signer_public_key)
-                    }
-                };
-                let res: Response = self
-                    .send_req_to_signer(Request::SignEvent(unsigned), self.opts.nip46_timeout)
-                    .await?;
-                if let Response::SignEvent(event) = res {
-                    Ok(event)
-                } else {
-                    Err(Error::ResponseNotMatchRequest)
-                }
+        let builder: EventBuilder = if self.opts.autoalt {
+            builder.default_alt()
+        } else {
+            builder
+        };
+
+        let builder: EventBuilder = self
+            .pool
+            .middleware()
+            .outgoing(builder)
+            .await
+            .map_err(Error::EventVetoed)?;
+
+        let signer: Arc<DynNostrSigner> = self.signer().await?;
+        let public_key: XOnlyPublicKey = signer.get_public_key().await?;
+        let unsigned: UnsignedEvent = {
+            let difficulty: u8 = self.opts.get_difficulty();
+            if difficulty > 0 {
+                builder.to_unsigned_pow_event(public_key, difficulty)
+            } else {
+                builder.to_unsigned_event(public_key)
             }
-        }
+        };
+        signer.sign_event(unsigned).await
     }
 
-    /// Take an [`EventBuilder`], sign it by using the [`ClientSigner`] and broadcast to all relays.
+    /// Take an [`EventBuilder`], sign it by using the [`NostrSigner`] and broadcast to all relays.
     ///
-    /// Rise an error if the [`ClientSigner`] is not set.
-    pub async fn send_event_builder(&self, builder: EventBuilder) -> Result<EventId, Error> {
+    /// Rise an error if the [`NostrSigner`] is not set.
+    pub async fn send_event_builder(
+        &self,
+        builder: EventBuilder,
+    ) -> Result<Output<EventId>, Error> {
         let event: Event = self.internal_sign_event_builder(builder).await?;
         self.send_event(event).await
     }
 
-    /// Take an [`EventBuilder`], sign it by using the [`ClientSigner`] and broadcast to specific relays.
+    /// Take an [`EventBuilder`], sign it by using the [`NostrSigner`] and broadcast to specific relays.
     ///
-    /// Rise an error if the [`ClientSigner`] is not set.
+    /// Rise an error if the [`NostrSigner`] is not set.
     pub async fn send_event_builder_to<U>(
         &self,
         url: U,
         builder: EventBuilder,
-    ) -> Result<EventId, Error>
+    ) -> Result<Output<EventId>, Error>
     where
         U: TryIntoUrl,
         pool::Error: From<<U as TryIntoUrl>::Err>,
@@ -757,6 +1682,36 @@ impl Client {
         self.send_event_to(url, event).await
     }
 
+    /// Sign the HTTP auth event (kind `27235`) by using the [`NostrSigner`] and build the `Authorization` header value
+    ///
+    /// Return `Nostr <base64-encoded event>`.
+    /// Rise an error if the [`NostrSigner`] is not set.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use nostr_sdk::prelude::*;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #   let my_keys = Keys::generate();
+    /// #   let client = Client::new(&my_keys);
+    /// let url = UncheckedUrl::from("https://example.com/upload");
+    /// let data = HttpData::new(url, HttpMethod::POST).body(b"nostr");
+    /// let header: String = client.http_authorization_header(data).await.unwrap();
+    /// # }
+    /// ```
+    ///
+    /// <https://github.com/nostr-protocol/nips/blob/master/98.md>
+    #[cfg(feature = "nip98")]
+    pub async fn http_authorization_header(&self, data: HttpData) -> Result<String, Error> {
+        let signer: Arc<DynNostrSigner> = self.signer().await?;
+        let public_key: XOnlyPublicKey = signer.get_public_key().await?;
+        let event: Event = signer
+            .sign_event(data.to_unsigned_event(public_key))
+            .await?;
+        Ok(nip98::authorization_header(&event))
+    }
+
     /// Update metadata
     ///
     /// <https://github.com/nostr-protocol/nips/blob/master/01.md>
@@ -779,7 +1734,7 @@ impl Client {
     /// client.set_metadata(&metadata).await.unwrap();
     /// # }
     /// ```
-    pub async fn set_metadata(&self, metadata: &Metadata) -> Result<EventId, Error> {
+    pub async fn set_metadata(&self, metadata: &Metadata) -> Result<Output<EventId>, Error> {
         let builder = EventBuilder::metadata(metadata);
         self.send_event_builder(builder).await
     }
@@ -802,7 +1757,11 @@ impl Client {
     ///     .unwrap();
     /// # }
     /// ```
-    pub async fn publish_text_note<S, I>(&self, content: S, tags: I) -> Result<EventId, Error>
+    pub async fn publish_text_note<S, I>(
+        &self,
+        content: S,
+        tags: I,
+    ) -> Result<Output<EventId>, Error>
     where
         S: Into<String>,
         I: IntoIterator<Item = Tag>,
@@ -815,7 +1774,7 @@ impl Client {
     ///
     /// <https://github.com/nostr-protocol/nips/blob/master/01.md>
     #[deprecated(since = "0.27.0")]
-    pub async fn add_recommended_relay<U>(&self, url: U) -> Result<EventId, Error>
+    pub async fn add_recommended_relay<U>(&self, url: U) -> Result<Output<EventId>, Error>
     where
         U: TryIntoUrl,
         Error: From<<U as TryIntoUrl>::Err>,
@@ -829,7 +1788,7 @@ impl Client {
     /// Set contact list
     ///
     /// <https://github.com/nostr-protocol/nips/blob/master/02.md>
-    pub async fn set_contact_list<I>(&self, list: I) -> Result<EventId, Error>
+    pub async fn set_contact_list<I>(&self, list: I) -> Result<Output<EventId>, Error>
     where
         I: IntoIterator<Item = Contact>,
     {
@@ -837,29 +1796,152 @@ impl Client {
         self.send_event_builder(builder).await
     }
 
-    async fn get_contact_list_filters(&self) -> Result<Vec<Filter>, Error> {
-        let mut filter: Filter = Filter::new().kind(Kind::ContactList).limit(1);
-
-        match self.signer().await? {
-            ClientSigner::Keys(keys) => {
-                filter = filter.author(keys.public_key());
-            }
-            #[cfg(all(feature = "nip07", target_arch = "wasm32"))]
-            ClientSigner::NIP07(nip07) => {
-                let public_key: XOnlyPublicKey = nip07.get_public_key().await?;
-                filter = filter.author(public_key);
+    /// Follow a public key
+    ///
+    /// Fetch the latest contact list, add (or update) the public key and publish the new list.
+    /// The other contacts, tags and the content of the list are preserved.
+    ///
+    /// Return `None` if the public key is already followed with the same relay hint and petname.
+    ///
+    /// Return [`Error::ListNotFound`] if the contact list can't be fetched,
+    /// unless [`Options::create_missing_lists`] is enabled.
+    ///
+    /// Return [`Error::ContactListConflict`] if a newer contact list is received while updating it,
+    /// to not clobber it: in this case, call again this method.
+    ///
+    /// <https://github.com/nostr-protocol/nips/blob/master/02.md>
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use nostr_sdk::prelude::*;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #   let my_keys = Keys::generate();
+    /// #   let client = Client::new(&my_keys);
+    /// let public_key = XOnlyPublicKey::from_bech32(
+    ///     "npub14f8usejl26twx0dhuxjh9cas7keav9vr0v8nvtwtrjqx3vycc76qqh9nsy",
+    /// )
+    /// .unwrap();
+    ///
+    /// client
+    ///     .follow(public_key, None, Some("alice"))
+    ///     .await
+    ///     .unwrap();
+    /// # }
+    /// ```
+    pub async fn follow<S>(
+        &self,
+        public_key: XOnlyPublicKey,
+        relay_hint: Option<UncheckedUrl>,
+        petname: Option<S>,
+    ) -> Result<Option<Output<EventId>>, Error>
+    where
+        S: Into<String>,
+    {
+        let contact = Tag::PublicKey {
+            public_key,
+            relay_url: relay_hint,
+            alias: petname.map(|p| p.into()),
+            uppercase: false,
+        };
+        self.update_contact_list(|tags| {
+            match tags.iter().position(|tag| is_contact(tag, &public_key)) {
+                Some(index) if tags[index] == contact => false,
+                Some(index) => {
+                    tags[index] = contact;
+                    true
+                }
+                None => {
+                    tags.push(contact);
+                    true
+                }
             }
-            #[cfg(feature = "nip46")]
-            ClientSigner::NIP46(nip46) => {
-                let signer_public_key = nip46
-                    .signer_public_key()
-                    .await
-                    .ok_or(Error::SignerPublicKeyNotFound)?;
-
-                filter = filter.author(signer_public_key);
+        })
+        .await
+    }
+
+    /// Unfollow a public key
+    ///
+    /// Fetch the latest contact list, remove the public key and publish the new list.
+    ///
+    /// Return `None` if the public key is not followed.
+    ///
+    /// Return [`Error::ListNotFound`] if the contact list can't be fetched,
+    /// unless [`Options::create_missing_lists`] is enabled.
+    ///
+    /// Return [`Error::ContactListConflict`] if a newer contact list is received while updating it,
+    /// to not clobber it: in this case, call again this method.
+    ///
+    /// <https://github.com/nostr-protocol/nips/blob/master/02.md>
+    pub async fn unfollow(
+        &self,
+        public_key: XOnlyPublicKey,
+    ) -> Result<Option<Output<EventId>>, Error> {
+        self.update_contact_list(|tags| {
+            let len: usize = tags.len();
+            tags.retain(|tag| !is_contact(tag, &public_key));
+            tags.len() != len
+        })
+        .await
+    }
+
+    /// Apply `f` to the tags of the latest contact list and publish it, if changed
+    async fn update_contact_list<F>(&self, f: F) -> Result<Option<Output<EventId>>, Error>
+    where
+        F: FnOnce(&mut Vec<Tag>) -> bool,
+    {
+        let filters: Vec<Filter> = self.get_contact_list_filters().await?;
+        let latest: Option<Event> = self
+            .get_events_of(filters.clone(), None)
+            .await?
+            .into_iter()
+            .max_by_key(|e| e.created_at());
+
+        let (content, mut tags): (String, Vec<Tag>) = match &latest {
+            Some(event) => (event.content().to_string(), event.tags().to_vec()),
+            None if self.opts.create_missing_lists => (String::new(), Vec::new()),
+            None => return Err(Error::ListNotFound(Kind::ContactList)),
+        };
+
+        if !f(&mut tags) {
+            return Ok(None);
+        }
+
+        // Check that a newer contact list wasn't received in the meantime
+        let stored: Vec<Event> = self.database().query(filters, Order::Desc).await?;
+        if let Some(stored) = stored.first() {
+            match &latest {
+                Some(latest) if stored.id() == latest.id() => (),
+                Some(latest) if stored.created_at() < latest.created_at() => (),
+                _ => return Err(Error::ContactListConflict),
             }
+        }
+
+        // The new list must be newer than the replaced one, also with clock skews
+        let now: Timestamp = Timestamp::now();
+        let created_at: Timestamp = match &latest {
+            Some(latest) if latest.created_at() >= now => latest.created_at() + 1u64,
+            _ => now,
         };
 
+        let builder =
+            EventBuilder::new(Kind::ContactList, content, tags).custom_created_at(created_at);
+        Ok(Some(self.send_event_builder(builder).await?))
+    }
+
+    /// Get public key of the current signer
+    async fn signer_public_key(&self) -> Result<XOnlyPublicKey, Error> {
+        let signer: Arc<DynNostrSigner> = self.signer().await?;
+        signer.get_public_key().await
+    }
+
+    async fn get_contact_list_filters(&self) -> Result<Vec<Filter>, Error> {
+        let public_key: XOnlyPublicKey = self.signer_public_key().await?;
+        let filter: Filter = Filter::new()
+            .author(public_key)
+            .kind(Kind::ContactList)
+            .limit(1);
         Ok(vec![filter])
     }
 
@@ -922,6 +2004,8 @@ impl Client {
     }
 
     /// Get contact list [`Metadata`]
+    ///
+    /// To keep the contacts metadata always fresh, see [`Client::refresh_contacts_metadata`].
     pub async fn get_contact_list_metadata(
         &self,
         timeout: Option<Duration>,
@@ -953,6 +2037,37 @@ impl Client {
         Ok(contacts)
     }
 
+    /// Verify the NIP05 identifiers of the contacts
+    ///
+    /// Return the verification status of each contact with a NIP05 identifier in the metadata.
+    /// The identifiers are verified concurrently (see [`nip05::verify_batch`](nostr::nips::nip05::verify_batch))
+    /// and the `.well-known` responses are cached for 1 hour.
+    ///
+    /// <https://github.com/nostr-protocol/nips/blob/master/05.md>
+    #[cfg(feature = "nip05")]
+    pub async fn verify_nip05_for_contacts(
+        &self,
+        timeout: Option<Duration>,
+    ) -> Result<HashMap<XOnlyPublicKey, bool>, Error> {
+        let contacts = self.get_contact_list_metadata(timeout).await?;
+        let identifiers = contacts
+            .into_iter()
+            .filter_map(|(public_key, metadata)| metadata.nip05.map(|nip05| (public_key, nip05)));
+
+        #[cfg(not(target_arch = "wasm32"))]
+        let proxy = self.opts.proxy;
+        #[cfg(target_arch = "wasm32")]
+        let proxy = None;
+
+        Ok(
+            nip05::verify_batch_with_cache(identifiers, proxy, &self.nip05_cache)
+                .await
+                .into_iter()
+                .map(|(public_key, _, res)| (public_key, res.is_ok()))
+                .collect(),
+        )
+    }
+
     /// Send encrypted direct message
     ///
     /// <https://github.com/nostr-protocol/nips/blob/master/04.md>
@@ -982,44 +2097,19 @@ impl Client {
         receiver: XOnlyPublicKey,
         msg: S,
         reply_to: Option<EventId>,
-    ) -> Result<EventId, Error>
+    ) -> Result<Output<EventId>, Error>
     where
         S: Into<String>,
     {
-        let builder: EventBuilder = match self.signer().await? {
-            ClientSigner::Keys(keys) => {
-                EventBuilder::encrypted_direct_msg(&keys, receiver, msg, reply_to)?
-            }
-            #[cfg(all(feature = "nip07", target_arch = "wasm32"))]
-            ClientSigner::NIP07(nip07) => {
-                let content: String = nip07.nip04_encrypt(receiver, msg.into()).await?;
-                EventBuilder::new(
-                    Kind::EncryptedDirectMessage,
-                    content,
-                    [Tag::public_key(receiver)],
-                )
-            }
-            #[cfg(feature = "nip46")]
-            ClientSigner::NIP46(..) => {
-                let req = Request::Nip04Encrypt {
-                    public_key: receiver,
-                    text: msg.into(),
-                };
-                let res: Response = self
-                    .send_req_to_signer(req, self.opts.nip46_timeout)
-                    .await?;
-                if let Response::Nip04Encrypt(content) = res {
-                    EventBuilder::new(
-                        Kind::EncryptedDirectMessage,
-                        content,
-                        [Tag::public_key(receiver)],
-                    )
-                } else {
-                    return Err(Error::ResponseNotMatchRequest);
-                }
-            }
-        };
+        let signer: Arc<DynNostrSigner> = self.signer().await?;
+        let content: String = signer.nip04_encrypt(receiver, msg.into()).await?;
+
+        let mut tags: Vec<Tag> = vec![Tag::public_key(receiver)];
+        if let Some(reply_to) = reply_to {
+            tags.push(Tag::event(reply_to));
+        }
 
+        let builder = EventBuilder::new(Kind::EncryptedDirectMessage, content, tags);
         self.send_event_builder(builder).await
     }
 
@@ -1028,7 +2118,7 @@ impl Client {
         &self,
         event_id: EventId,
         public_key: XOnlyPublicKey,
-    ) -> Result<EventId, Error> {
+    ) -> Result<Output<EventId>, Error> {
         let builder = EventBuilder::repost(event_id, public_key);
         self.send_event_builder(builder).await
     }
@@ -1036,7 +2126,7 @@ impl Client {
     /// Delete event
     ///
     /// <https://github.com/nostr-protocol/nips/blob/master/09.md>
-    pub async fn delete_event<T>(&self, id: T) -> Result<EventId, Error>
+    pub async fn delete_event<T>(&self, id: T) -> Result<Output<EventId>, Error>
     where
         T: Into<EventIdOrCoordinate>,
     {
@@ -1073,7 +2163,7 @@ impl Client {
         &self,
         event_id: EventId,
         public_key: XOnlyPublicKey,
-    ) -> Result<EventId, Error> {
+    ) -> Result<Output<EventId>, Error> {
         let builder = EventBuilder::reaction(event_id, public_key, "+");
         self.send_event_builder(builder).await
     }
@@ -1107,7 +2197,7 @@ impl Client {
         &self,
         event_id: EventId,
         public_key: XOnlyPublicKey,
-    ) -> Result<EventId, Error> {
+    ) -> Result<Output<EventId>, Error> {
         let builder = EventBuilder::reaction(event_id, public_key, "-");
         self.send_event_builder(builder).await
     }
@@ -1142,7 +2232,7 @@ impl Client {
         event_id: EventId,
         public_key: XOnlyPublicKey,
         content: S,
-    ) -> Result<EventId, Error>
+    ) -> Result<Output<EventId>, Error>
     where
         S: Into<String>,
     {
@@ -1150,10 +2240,132 @@ impl Client {
         self.send_event_builder(builder).await
     }
 
+    /// Get the aggregated reactions of an event
+    ///
+    /// Fetch the kind `7` events and count them by content and by author (see [`Reactions::from_events`]).
+    ///
+    /// If timeout is set to `None`, the default from [`Options`] will be used.
+    ///
+    /// <https://github.com/nostr-protocol/nips/blob/master/25.md>
+    pub async fn reactions_of(
+        &self,
+        event_id: EventId,
+        timeout: Option<Duration>,
+    ) -> Result<Reactions, Error> {
+        let filter: Filter = Filter::new().kind(Kind::Reaction).event(event_id);
+        let events: Vec<Event> = self.get_events_of(vec![filter], timeout).await?;
+        Ok(Reactions::from_events(&event_id, &events))
+    }
+
+    /// React to a website
+    ///
+    /// <https://github.com/nostr-protocol/nips/blob/master/25.md>
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use nostr_sdk::prelude::*;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #   let my_keys = Keys::generate();
+    /// #   let client = Client::new(&my_keys);
+    /// let url = Url::parse("https://example.com").unwrap();
+    /// client.react_to_url(url, "+").await.unwrap();
+    /// # }
+    /// ```
+    pub async fn react_to_url<S>(&self, url: Url, content: S) -> Result<Output<EventId>, Error>
+    where
+        S: Into<String>,
+    {
+        let builder = EventBuilder::external_reaction(ExternalContent::Url(url), content);
+        self.send_event_builder(builder).await
+    }
+
+    /// Like event, if not already reacted to it
+    ///
+    /// Return [`Error::Duplicate`] with the ID of the previous reaction,
+    /// unless [`DuplicateGuard::Force`] is used.
+    ///
+    /// <https://github.com/nostr-protocol/nips/blob/master/25.md>
+    pub async fn like_once(
+        &self,
+        event_id: EventId,
+        public_key: XOnlyPublicKey,
+        guard: DuplicateGuard,
+    ) -> Result<Output<EventId>, Error> {
+        self.reaction_once(event_id, public_key, "+", guard).await
+    }
+
+    /// React to an [`Event`], if not already reacted to it
+    ///
+    /// Return [`Error::Duplicate`] with the ID of the previous reaction,
+    /// unless [`DuplicateGuard::Force`] is used.
+    ///
+    /// <https://github.com/nostr-protocol/nips/blob/master/25.md>
+    pub async fn reaction_once<S>(
+        &self,
+        event_id: EventId,
+        public_key: XOnlyPublicKey,
+        content: S,
+        guard: DuplicateGuard,
+    ) -> Result<Output<EventId>, Error>
+    where
+        S: Into<String>,
+    {
+        self.check_duplicate(Kind::Reaction, event_id, guard)
+            .await?;
+        self.reaction(event_id, public_key, content).await
+    }
+
+    /// Repost event, if not already reposted
+    ///
+    /// Return [`Error::Duplicate`] with the ID of the previous repost,
+    /// unless [`DuplicateGuard::Force`] is used.
+    pub async fn repost_event_once(
+        &self,
+        event_id: EventId,
+        public_key: XOnlyPublicKey,
+        guard: DuplicateGuard,
+    ) -> Result<Output<EventId>, Error> {
+        self.check_duplicate(Kind::Repost, event_id, guard).await?;
+        self.repost_event(event_id, public_key).await
+    }
+
+    /// Check if the signer already published an event of [`Kind`] referencing `event_id`
+    async fn check_duplicate(
+        &self,
+        kind: Kind,
+        event_id: EventId,
+        guard: DuplicateGuard,
+    ) -> Result<(), Error> {
+        if guard == DuplicateGuard::Force {
+            return Ok(());
+        }
+
+        let public_key: XOnlyPublicKey = self.signer_public_key().await?;
+        let filter: Filter = Filter::new()
+            .author(public_key)
+            .kind(kind)
+            .event(event_id)
+            .limit(1);
+
+        let events: Vec<Event> = match guard {
+            DuplicateGuard::DatabaseAndRelays(timeout) => {
+                self.get_events_of(vec![filter], timeout).await?
+            }
+            _ => self.database().query(vec![filter], Order::Desc).await?,
+        };
+
+        match events.first() {
+            Some(event) => Err(Error::Duplicate(event.id())),
+            None => Ok(()),
+        }
+    }
+
     /// Create new channel
     ///
     /// <https://github.com/nostr-protocol/nips/blob/master/28.md>
-    pub async fn new_channel(&self, metadata: &Metadata) -> Result<EventId, Error> {
+    pub async fn new_channel(&self, metadata: &Metadata) -> Result<Output<EventId>, Error> {
         let builder = EventBuilder::channel(metadata);
         self.send_event_builder(builder).await
     }
@@ -1166,7 +2378,7 @@ impl Client {
         channel_id: EventId,
         relay_url: Option<Url>,
         metadata: &Metadata,
-    ) -> Result<EventId, Error> {
+    ) -> Result<Output<EventId>, Error> {
         let builder = EventBuilder::channel_metadata(channel_id, relay_url, metadata);
         self.send_event_builder(builder).await
     }
@@ -1179,7 +2391,7 @@ impl Client {
         channel_id: EventId,
         relay_url: Url,
         msg: S,
-    ) -> Result<EventId, Error>
+    ) -> Result<Output<EventId>, Error>
     where
         S: Into<String>,
     {
@@ -1194,7 +2406,7 @@ impl Client {
         &self,
         message_id: EventId,
         reason: Option<S>,
-    ) -> Result<EventId, Error>
+    ) -> Result<Output<EventId>, Error>
     where
         S: Into<String>,
     {
@@ -1209,7 +2421,7 @@ impl Client {
         &self,
         pubkey: XOnlyPublicKey,
         reason: Option<S>,
-    ) -> Result<EventId, Error>
+    ) -> Result<Output<EventId>, Error>
     where
         S: Into<String>,
     {
@@ -1220,7 +2432,7 @@ impl Client {
     /// Create an auth event
     ///
     /// <https://github.com/nostr-protocol/nips/blob/master/42.md>
-    pub async fn auth<S>(&self, challenge: S, relay: Url) -> Result<EventId, Error>
+    pub async fn auth<S>(&self, challenge: S, relay: Url) -> Result<Output<EventId>, Error>
     where
         S: Into<String>,
     {
@@ -1237,7 +2449,7 @@ impl Client {
         bolt11: S,
         preimage: Option<S>,
         zap_request: Event,
-    ) -> Result<EventId, Error>
+    ) -> Result<Output<EventId>, Error>
     where
         S: Into<String>,
     {
@@ -1255,7 +2467,7 @@ impl Client {
         bolt11: S,
         preimage: Option<S>,
         zap_request: Event,
-    ) -> Result<EventId, Error>
+    ) -> Result<Output<EventId>, Error>
     where
         S: Into<String>,
     {
@@ -1270,7 +2482,7 @@ impl Client {
         &self,
         description: S,
         metadata: FileMetadata,
-    ) -> Result<EventId, Error>
+    ) -> Result<Output<EventId>, Error>
     where
         S: Into<String>,
     {
@@ -1281,7 +2493,13 @@ impl Client {
     /// Negentropy reconciliation
     ///
     /// <https://github.com/hoytech/negentropy>
-    pub async fn reconcile(&self, filter: Filter, opts: NegentropyOptions) -> Result<(), Error> {
+    ///
+    /// Relays that don't support negentropy fall back to `REQ`: see the [`Output::relays`] report.
+    pub async fn reconcile(
+        &self,
+        filter: Filter,
+        opts: NegentropyOptions,
+    ) -> Result<Output<()>, Error> {
         Ok(self.pool.reconcile(filter, opts).await?)
     }
 
@@ -1291,7 +2509,7 @@ impl Client {
         filter: Filter,
         items: Vec<(EventId, Timestamp)>,
         opts: NegentropyOptions,
-    ) -> Result<(), Error> {
+    ) -> Result<Output<()>, Error> {
         Ok(self.pool.reconcile_with_items(filter, items, opts).await?)
     }
 
@@ -1303,6 +2521,9 @@ impl Client {
     }
 
     /// Handle notifications
+    ///
+    /// If [`Options::filter_muted()`] is enabled, the events muted by the cached mute list are skipped
+    /// (see [`Client::fetch_mute_list`]).
     pub async fn handle_notifications<F, Fut>(&self, func: F) -> Result<(), Error>
     where
         F: Fn(RelayPoolNotification) -> Fut,
@@ -1310,6 +2531,15 @@ impl Client {
     {
         let mut notifications = self.notifications();
         while let Ok(notification) = notifications.recv().await {
+            #[cfg(feature = "nip44")]
+            if self.opts.filter_muted {
+                if let RelayPoolNotification::Event { event, .. } = &notification {
+                    if self.is_muted(event).await {
+                        continue;
+                    }
+                }
+            }
+
             let stop: bool = RelayPoolNotification::Stop == notification;
             let shutdown: bool = RelayPoolNotification::Shutdown == notification;
             let exit: bool = func(notification)
@@ -1322,3 +2552,8 @@ impl Client {
         Ok(())
     }
 }
+
+/// Check if `tag` is the contact list entry of `public_key`
+fn is_contact(tag: &Tag, public_key: &XOnlyPublicKey) -> bool {
+    matches!(tag, Tag::PublicKey { public_key: pk, uppercase: false, .. } if pk == public_key)
+}
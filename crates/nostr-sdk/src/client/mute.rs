@@ -0,0 +1,188 @@
+// Copyright (c) 2022-2023 Yuki Kishimoto
+// Copyright (c) 2023-2024 Rust Nostr Developers
+// Distributed under the MIT software license
+
+//! Mute list (NIP51)
+//!
+//! <https://github.com/nostr-protocol/nips/blob/master/51.md>
+
+use std::time::Duration;
+
+use nostr::nips::nip51::{MuteItem, MuteList};
+use nostr::secp256k1::XOnlyPublicKey;
+use nostr::{Event, EventBuilder, EventId, Filter, Kind, Timestamp};
+
+use super::{Client, Error};
+use crate::relay::Output;
+
+impl Client {
+    /// Fetch the mute list (kind `10000`) of the signer, decrypting the private items
+    ///
+    /// The private items are decrypted with NIP44, falling back to NIP04 for the lists
+    /// published by older clients.
+    ///
+    /// The fetched list is cached and used by [`Client::is_muted`].
+    pub async fn fetch_mute_list(&self, timeout: Option<Duration>) -> Result<MuteList, Error> {
+        let (list, ..) = self.fetch_latest_mute_list(timeout).await?;
+        Ok(list)
+    }
+
+    /// Get the cached mute list
+    ///
+    /// Empty until [`Client::fetch_mute_list`], [`Client::mute`] or [`Client::unmute`] is called.
+    pub async fn mute_list(&self) -> MuteList {
+        self.mute_list.read().await.clone()
+    }
+
+    /// Mute a public key, a thread, a hashtag or a word
+    ///
+    /// Fetch the latest mute list, add the item to the public or private (NIP44 encrypted) section
+    /// and publish the new list.
+    ///
+    /// Return `None` if the item is already muted.
+    ///
+    /// Return [`Error::ListNotFound`] if the mute list can't be fetched,
+    /// unless [`Options::create_missing_lists`](super::Options::create_missing_lists) is enabled.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use nostr_sdk::prelude::*;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #   let my_keys = Keys::generate();
+    /// #   let client = Client::new(&my_keys);
+    /// let public_key = XOnlyPublicKey::from_bech32(
+    ///     "npub14f8usejl26twx0dhuxjh9cas7keav9vr0v8nvtwtrjqx3vycc76qqh9nsy",
+    /// )
+    /// .unwrap();
+    ///
+    /// client.mute(public_key, true).await.unwrap();
+    /// client
+    ///     .mute(MuteItem::Hashtag(String::from("spam")), false)
+    ///     .await
+    ///     .unwrap();
+    /// # }
+    /// ```
+    pub async fn mute<T>(&self, item: T, private: bool) -> Result<Option<Output<EventId>>, Error>
+    where
+        T: Into<MuteItem>,
+    {
+        let item: MuteItem = item.into();
+        self.update_mute_list(|list| list.add(item, private)).await
+    }
+
+    /// Unmute a public key, a thread, a hashtag or a word
+    ///
+    /// The item is removed from both the public and private sections.
+    ///
+    /// Return `None` if the item is not muted.
+    ///
+    /// Return [`Error::ListNotFound`] if the mute list can't be fetched.
+    pub async fn unmute<T>(&self, item: T) -> Result<Option<Output<EventId>>, Error>
+    where
+        T: Into<MuteItem>,
+    {
+        let item: MuteItem = item.into();
+        self.update_mute_list(|list| list.remove(&item)).await
+    }
+
+    /// Check if the event is muted by the cached mute list
+    ///
+    /// See [`MuteList::is_muted`].
+    pub async fn is_muted(&self, event: &Event) -> bool {
+        let list = self.mute_list.read().await;
+        list.is_muted(event)
+    }
+
+    /// Fetch the latest mute list and its timestamp, and update the cache
+    async fn fetch_latest_mute_list(
+        &self,
+        timeout: Option<Duration>,
+    ) -> Result<(MuteList, Option<Timestamp>), Error> {
+        let public_key: XOnlyPublicKey = self.signer_public_key().await?;
+        let filter: Filter = Filter::new()
+            .author(public_key)
+            .kind(Kind::MuteList)
+            .limit(1);
+        let latest: Option<Event> = self
+            .get_events_of(vec![filter], timeout)
+            .await?
+            .into_iter()
+            .max_by_key(|e| e.created_at());
+
+        let (list, created_at) = match latest {
+            Some(event) => {
+                let mut list: MuteList = MuteList::from_event(&event)?;
+                if !event.content().is_empty() {
+                    let json: String = self
+                        .decrypt_private_items(public_key, event.content())
+                        .await?;
+                    list.private = MuteList::parse_private(json)?;
+                }
+                (list, Some(event.created_at()))
+            }
+            None => (MuteList::new(), None),
+        };
+
+        let mut cache = self.mute_list.write().await;
+        *cache = list.clone();
+
+        Ok((list, created_at))
+    }
+
+    /// Apply `f` to the latest mute list and publish it, if changed
+    async fn update_mute_list<F>(&self, f: F) -> Result<Option<Output<EventId>>, Error>
+    where
+        F: FnOnce(&mut MuteList) -> bool,
+    {
+        let (mut list, latest) = self.fetch_latest_mute_list(None).await?;
+
+        // Don't replace a list that can't be fetched with an almost empty one
+        if latest.is_none() && !self.opts.create_missing_lists {
+            return Err(Error::ListNotFound(Kind::MuteList));
+        }
+
+        if !f(&mut list) {
+            return Ok(None);
+        }
+
+        let content: String = if list.private.is_empty() {
+            String::new()
+        } else {
+            let public_key: XOnlyPublicKey = self.signer_public_key().await?;
+            self.nip44_encrypt(public_key, list.private_json()).await?
+        };
+
+        // The new list must be newer than the replaced one, also with clock skews
+        let now: Timestamp = Timestamp::now();
+        let created_at: Timestamp = match latest {
+            Some(latest) if latest >= now => latest + 1u64,
+            _ => now,
+        };
+
+        let builder: EventBuilder = list.to_event_builder(content).custom_created_at(created_at);
+        let output: Output<EventId> = self.send_event_builder(builder).await?;
+
+        let mut cache = self.mute_list.write().await;
+        *cache = list;
+
+        Ok(Some(output))
+    }
+
+    /// Decrypt the private items with NIP44 or, if encrypted by older clients, with NIP04
+    async fn decrypt_private_items(
+        &self,
+        public_key: XOnlyPublicKey,
+        content: &str,
+    ) -> Result<String, Error> {
+        // NIP04 payloads have the `?iv=` suffix, not valid in base64 NIP44 payloads
+        #[cfg(feature = "nip04")]
+        if content.contains("?iv=") {
+            let signer = self.signer().await?;
+            return signer.nip04_decrypt(public_key, content.to_string()).await;
+        }
+
+        self.nip44_decrypt(public_key, content).await
+    }
+}
@@ -0,0 +1,120 @@
+// Copyright (c) 2022-2023 Yuki Kishimoto
+// Copyright (c) 2023-2024 Rust Nostr Developers
+// Distributed under the MIT software license
+
+//! Threads (NIP10)
+//!
+//! <https://github.com/nostr-protocol/nips/blob/master/10.md>
+
+use std::collections::BTreeSet;
+use std::time::Duration;
+
+use nostr::nips::nip10::{self, Thread};
+use nostr::{Event, EventId, Filter, Kind, Timestamp};
+
+use super::{Client, Error};
+use crate::relay::{Output, SubscriptionHandle};
+
+impl Client {
+    /// Fetch the thread of an event
+    ///
+    /// Fetch the root of the thread (see [`nip10::root_id`]) and its replies, level by level,
+    /// up to `depth` levels (unlimited if `None`).
+    ///
+    /// If timeout is set to `None`, the default from [`Options`](super::Options) will be used.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use nostr_sdk::prelude::*;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #   let my_keys = Keys::generate();
+    /// #   let client = Client::new(&my_keys);
+    /// # let event_id = EventId::all_zeros();
+    /// let mut thread = client.fetch_thread(event_id, Some(3), None).await.unwrap();
+    ///
+    /// // Live updates
+    /// let handle = client.subscribe_thread(&thread).await.val;
+    /// let mut notifications = client.notifications();
+    /// while let Ok(notification) = notifications.recv().await {
+    ///     if let RelayPoolNotification::Event {
+    ///         relay_url,
+    ///         subscription_id,
+    ///         event,
+    ///     } = notification
+    ///     {
+    ///         if handle.is_subscription(&relay_url, &subscription_id).await
+    ///             && thread.insert(event)
+    ///         {
+    ///             println!("{} replies", thread.len() - 1);
+    ///         }
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    pub async fn fetch_thread(
+        &self,
+        event_id: EventId,
+        depth: Option<usize>,
+        timeout: Option<Duration>,
+    ) -> Result<Thread, Error> {
+        let event: Event = self.fetch_event(event_id, timeout).await?;
+        let root: Event = match nip10::root_id(&event) {
+            Some(root_id) => self.fetch_event(root_id, timeout).await?,
+            None => event,
+        };
+
+        // Replies tagging the root, plus the ones (i.e. with positional tags) tagging only their parent
+        let mut replies: Vec<Event> = Vec::new();
+        let mut seen: BTreeSet<EventId> = BTreeSet::new();
+        let mut frontier: Vec<EventId> = vec![root.id()];
+        let mut level: usize = 0;
+        let chunk_size: usize = self.opts.get_req_filters_chunk_size().max(1);
+        while !frontier.is_empty() && depth.map_or(true, |depth| level < depth) {
+            let mut events: Vec<Event> = Vec::new();
+            for chunk in frontier.chunks(chunk_size) {
+                let filter: Filter = Filter::new()
+                    .kind(Kind::TextNote)
+                    .events(chunk.iter().copied());
+                events.extend(self.get_events_of(vec![filter], timeout).await?);
+            }
+            frontier = events
+                .iter()
+                .map(|e| e.id())
+                .filter(|id| seen.insert(*id))
+                .collect();
+            replies.extend(events.into_iter().filter(|e| frontier.contains(&e.id())));
+            level += 1;
+        }
+
+        Ok(Thread::from_events(root, replies, depth))
+    }
+
+    /// Subscribe to the new replies of a thread
+    ///
+    /// Match the replies tagging any event of the thread (NIP10 replies tag also the root):
+    /// insert the received events with [`Thread::insert`].
+    /// Close the subscription with [`SubscriptionHandle::close`].
+    pub async fn subscribe_thread(&self, thread: &Thread) -> Output<SubscriptionHandle> {
+        let filter: Filter = Filter::new()
+            .kind(Kind::TextNote)
+            .events(thread.ids())
+            .since(Timestamp::now());
+        self.subscribe_with_id(format!("thread-{}", thread.id()), vec![filter])
+            .await
+    }
+
+    async fn fetch_event(
+        &self,
+        event_id: EventId,
+        timeout: Option<Duration>,
+    ) -> Result<Event, Error> {
+        let filter: Filter = Filter::new().id(event_id).limit(1);
+        self.get_events_of(vec![filter], timeout)
+            .await?
+            .into_iter()
+            .next()
+            .ok_or(Error::EventNotFound(event_id))
+    }
+}
@@ -0,0 +1,132 @@
+// Copyright (c) 2022-2023 Yuki Kishimoto
+// Copyright (c) 2023-2024 Rust Nostr Developers
+// Distributed under the MIT software license
+
+//! NIP44 conversation keys cache
+//!
+//! <https://github.com/nostr-protocol/nips/blob/master/44.md>
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+
+use nostr::nips::nip44::{self, ConversationKey};
+use nostr::secp256k1::XOnlyPublicKey;
+
+use super::{Client, DynNostrSigner, Error};
+
+/// LRU cache of [`ConversationKey`], by signer and peer public keys
+#[derive(Debug)]
+pub(crate) struct ConversationKeyCache {
+    capacity: usize,
+    keys: HashMap<(XOnlyPublicKey, XOnlyPublicKey), ConversationKey>,
+    order: VecDeque<(XOnlyPublicKey, XOnlyPublicKey)>,
+}
+
+impl ConversationKeyCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            keys: HashMap::new(),
+            order: VecDeque::new(),
+        }
+    }
+
+    /// Get the cached [`ConversationKey`] or derive (and cache) it with the signer
+    pub async fn get_or_derive(
+        &mut self,
+        signer: &DynNostrSigner,
+        public_key: &XOnlyPublicKey,
+    ) -> Result<ConversationKey, Error> {
+        let id = (signer.get_public_key().await?, *public_key);
+
+        if let Some(conversation_key) = self.keys.get(&id).copied() {
+            // Mark as most recently used
+            if let Some(pos) = self.order.iter().position(|i| i == &id) {
+                self.order.remove(pos);
+            }
+            self.order.push_back(id);
+            return Ok(conversation_key);
+        }
+
+        let conversation_key = signer.nip44_conversation_key(*public_key).await?;
+
+        if self.capacity > 0 {
+            while self.order.len() >= self.capacity {
+                if let Some(old) = self.order.pop_front() {
+                    self.keys.remove(&old);
+                }
+            }
+            self.keys.insert(id, conversation_key);
+            self.order.push_back(id);
+        }
+
+        Ok(conversation_key)
+    }
+}
+
+impl Client {
+    /// Get NIP44 [`ConversationKey`] with `public_key`
+    ///
+    /// The conversation keys are cached (see [`Options::conversation_keys_cache_size`](super::Options::conversation_keys_cache_size)),
+    /// to avoid recomputing ECDH and HKDF for every message.
+    ///
+    /// Available only for the signers exposing it (see [`NostrSigner::nip44_conversation_key`](super::NostrSigner::nip44_conversation_key)).
+    pub async fn conversation_key(
+        &self,
+        public_key: XOnlyPublicKey,
+    ) -> Result<ConversationKey, Error> {
+        let signer: Arc<DynNostrSigner> = self.signer().await?;
+        let mut cache = self.conversation_keys.lock().await;
+        cache.get_or_derive(signer.as_ref(), &public_key).await
+    }
+
+    /// Encrypt `content` for `public_key` with NIP44, using the cached [`ConversationKey`]
+    ///
+    /// If the signer doesn't expose the conversation key, the content is encrypted by the signer.
+    pub async fn nip44_encrypt<T>(
+        &self,
+        public_key: XOnlyPublicKey,
+        content: T,
+    ) -> Result<String, Error>
+    where
+        T: AsRef<[u8]>,
+    {
+        match self.conversation_key(public_key).await {
+            Ok(conversation_key) => Ok(nip44::encrypt_with_conversation_key(
+                &conversation_key,
+                content,
+            )?),
+            Err(Error::SignerMethodNotSupported(..)) => {
+                let content: String = String::from_utf8_lossy(content.as_ref()).into_owned();
+                let signer: Arc<DynNostrSigner> = self.signer().await?;
+                signer.nip44_encrypt(public_key, content).await
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Decrypt NIP44 `payload` from `public_key`, using the cached [`ConversationKey`]
+    ///
+    /// If the signer doesn't expose the conversation key, the payload is decrypted by the signer.
+    pub async fn nip44_decrypt<T>(
+        &self,
+        public_key: XOnlyPublicKey,
+        payload: T,
+    ) -> Result<String, Error>
+    where
+        T: AsRef<[u8]>,
+    {
+        match self.conversation_key(public_key).await {
+            Ok(conversation_key) => Ok(nip44::decrypt_with_conversation_key(
+                &conversation_key,
+                payload,
+            )?),
+            Err(Error::SignerMethodNotSupported(..)) => {
+                let payload: String = String::from_utf8_lossy(payload.as_ref()).into_owned();
+                let signer: Arc<DynNostrSigner> = self.signer().await?;
+                signer.nip44_decrypt(public_key, payload).await
+            }
+            Err(e) => Err(e),
+        }
+    }
+}
@@ -0,0 +1,189 @@
+// Copyright (c) 2022-2023 Yuki Kishimoto
+// Copyright (c) 2023-2024 Rust Nostr Developers
+// Distributed under the MIT software license
+
+//! Live Activities tracker (NIP53)
+//!
+//! <https://github.com/nostr-protocol/nips/blob/master/53.md>
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use async_utility::thread;
+use nostr::nips::nip01::Coordinate;
+use nostr::nips::nip53::{LiveEvent, LiveEventUpdate};
+use nostr::{Event, Filter, Kind, Timestamp};
+use nostr_database::Order;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::{broadcast, Notify, RwLock};
+
+use super::{Client, Error};
+use crate::relay::pool::Error as RelayPoolError;
+use crate::relay::{InternalSubscriptionId, Relay, RelayPoolNotification};
+
+/// Live event tracker
+///
+/// Keep track of a live event (kind `30311`) and emit [`LiveEventUpdate`]s
+/// every time a new version of the event is received.
+#[derive(Debug, Clone)]
+pub struct LiveEventTracker {
+    coordinate: Coordinate,
+    internal_id: InternalSubscriptionId,
+    current: Arc<RwLock<Option<(Timestamp, LiveEvent)>>>,
+    updates: broadcast::Sender<LiveEventUpdate>,
+    relays: Vec<Relay>,
+    stopped: Arc<AtomicBool>,
+    shutdown: Arc<Notify>,
+}
+
+impl LiveEventTracker {
+    /// Get tracked [`Coordinate`]
+    pub fn coordinate(&self) -> Coordinate {
+        self.coordinate.clone()
+    }
+
+    /// Get current [`LiveEvent`]
+    ///
+    /// Return `None` if the live event hasn't been received yet.
+    pub async fn current(&self) -> Option<LiveEvent> {
+        let current = self.current.read().await;
+        current.as_ref().map(|(_, live_event)| live_event.clone())
+    }
+
+    /// Get new [`LiveEventUpdate`] listener
+    pub fn updates(&self) -> broadcast::Receiver<LiveEventUpdate> {
+        self.updates.subscribe()
+    }
+
+    /// Check if tracker is stopped
+    pub fn is_stopped(&self) -> bool {
+        self.stopped.load(Ordering::SeqCst)
+    }
+
+    /// Stop tracking the live event and close the subscriptions
+    pub async fn stop(&self) {
+        self.stopped.store(true, Ordering::SeqCst);
+        self.shutdown.notify_one();
+        for relay in self.relays.iter() {
+            if let Err(e) = relay
+                .unsubscribe_with_internal_id(self.internal_id.clone(), None)
+                .await
+            {
+                tracing::error!("Impossible to unsubscribe from {}: {e}", relay.url());
+            }
+        }
+    }
+
+    fn is_tracked(&self, event: &Event) -> bool {
+        event.kind() == Kind::LiveEvent
+            && event.author_ref() == &self.coordinate.pubkey
+            && event.identifier() == Some(self.coordinate.identifier.as_str())
+    }
+
+    /// Update the current [`LiveEvent`] and send the [`LiveEventUpdate`]s
+    async fn update(&self, event: Event) {
+        let created_at: Timestamp = event.created_at();
+        let new: LiveEvent = match LiveEvent::try_from(event.tags().to_vec()) {
+            Ok(live_event) => live_event,
+            Err(e) => {
+                tracing::error!("Impossible to parse live event {}: {e}", event.id());
+                return;
+            }
+        };
+
+        let mut current = self.current.write().await;
+
+        let updates: Vec<LiveEventUpdate> = match current.as_ref() {
+            // Older or same version of the replaceable event
+            Some((timestamp, _)) if created_at <= *timestamp => return,
+            Some((_, old)) => old.diff(&new),
+            None => LiveEvent::new(new.id.clone()).diff(&new),
+        };
+
+        *current = Some((created_at, new));
+
+        for update in updates.into_iter() {
+            let _ = self.updates.send(update);
+        }
+    }
+}
+
+impl Client {
+    /// Track a live event (NIP53)
+    ///
+    /// Subscribe to the live event [`Coordinate`] on all relays and return a [`LiveEventTracker`],
+    /// that emits status changes, participant joins/leaves and streaming URL changes.
+    ///
+    /// <https://github.com/nostr-protocol/nips/blob/master/53.md>
+    pub async fn track_live_event(
+        &self,
+        coordinate: Coordinate,
+    ) -> Result<LiveEventTracker, Error> {
+        let filter: Filter = Filter::from(coordinate.clone());
+        let internal_id = InternalSubscriptionId::Custom(format!(
+            "nip53:{}:{}",
+            coordinate.pubkey, coordinate.identifier
+        ));
+
+        let (updates, ..) = broadcast::channel(self.opts.pool.notification_channel_size);
+        let tracker = LiveEventTracker {
+            coordinate,
+            internal_id: internal_id.clone(),
+            current: Arc::new(RwLock::new(None)),
+            updates,
+            relays: self.relays().await.into_values().collect(),
+            stopped: Arc::new(AtomicBool::new(false)),
+            shutdown: Arc::new(Notify::new()),
+        };
+
+        // Load the latest version already stored in the database
+        let stored: Vec<Event> = self
+            .database()
+            .query(vec![filter.clone().limit(1)], Order::Desc)
+            .await
+            .map_err(RelayPoolError::from)?;
+        if let Some(event) = stored.into_iter().next() {
+            tracker.update(event).await;
+        }
+
+        // Get notification listener before subscribe, to not miss any event
+        let mut notifications = self.notifications();
+
+        // Subscribe
+        for relay in tracker.relays.iter() {
+            if let Err(e) = relay
+                .subscribe_with_internal_id(internal_id.clone(), vec![filter.clone()], None)
+                .await
+            {
+                tracing::error!("Impossible to subscribe to {}: {e}", relay.url());
+            }
+        }
+
+        let t = tracker.clone();
+        thread::spawn(async move {
+            loop {
+                tokio::select! {
+                    // The permit is stored if the tracker is stopped while handling an event
+                    _ = t.shutdown.notified() => break,
+                    res = notifications.recv() => match res {
+                        Ok(RelayPoolNotification::Event { event, .. }) => {
+                            if t.is_tracked(&event) {
+                                t.update(event).await;
+                            }
+                        }
+                        Ok(RelayPoolNotification::Stop) | Ok(RelayPoolNotification::Shutdown) => break,
+                        Ok(_) => (),
+                        Err(RecvError::Lagged(num)) => {
+                            tracing::warn!("Live event tracker lagged: {num} notifications skipped")
+                        }
+                        Err(RecvError::Closed) => break,
+                    },
+                }
+            }
+
+            tracing::debug!("Exited from live event tracker");
+        });
+
+        Ok(tracker)
+    }
+}
@@ -0,0 +1,280 @@
+// Copyright (c) 2022-2023 Yuki Kishimoto
+// Copyright (c) 2023-2024 Rust Nostr Developers
+// Distributed under the MIT software license
+
+//! Zaps (NIP57)
+//!
+//! <https://github.com/nostr-protocol/nips/blob/master/57.md>
+
+use std::time::Duration;
+
+use async_utility::time;
+use nostr::nips::nip57::{self, LnUrlPayResponse, ZapRequestData, Zaps};
+use nostr::secp256k1::XOnlyPublicKey;
+use nostr::{Event, EventBuilder, EventId, Filter, JsonUtil, Kind, Tag, UncheckedUrl, Url};
+use nostr_database::{async_trait, AsyncTraitDeps, Profile};
+use tokio::sync::broadcast::error::RecvError;
+
+use super::{Client, Error};
+#[cfg(feature = "nip47")]
+use crate::nwc::NostrWalletConnect;
+use crate::relay::{InternalSubscriptionId, RelayPoolNotification};
+
+/// Zap payer
+///
+/// Pay the bolt11 invoices of the zaps sent with [`Client::zap`].
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+pub trait ZapPayer: AsyncTraitDeps {
+    /// Pay a bolt11 invoice
+    async fn pay_invoice(&self, invoice: String) -> Result<(), Error>;
+}
+
+/// Dynamic zap payer
+pub type DynZapPayer = dyn ZapPayer;
+
+#[cfg(feature = "nip47")]
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl ZapPayer for NostrWalletConnect {
+    async fn pay_invoice(&self, invoice: String) -> Result<(), Error> {
+        NostrWalletConnect::pay_invoice(self, invoice)
+            .await
+            .map(|_preimage| ())
+            .map_err(|e| Error::ZapPayment(e.to_string()))
+    }
+}
+
+/// Zap target
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ZapTarget {
+    /// Public key
+    PublicKey(XOnlyPublicKey),
+    /// Event (the author is zapped)
+    Event(EventId),
+}
+
+impl From<XOnlyPublicKey> for ZapTarget {
+    fn from(public_key: XOnlyPublicKey) -> Self {
+        Self::PublicKey(public_key)
+    }
+}
+
+impl From<EventId> for ZapTarget {
+    fn from(event_id: EventId) -> Self {
+        Self::Event(event_id)
+    }
+}
+
+impl Client {
+    /// Send a zap (NIP57)
+    ///
+    /// Resolve the LNURL of the recipient (from `lud16` or `lud06` of the metadata), send the zap request
+    /// to the LNURL service, pay the received bolt11 invoice with the [`ZapPayer`]
+    /// (see [`ClientBuilder::zapper`](super::ClientBuilder::zapper)) and wait for the zap receipt.
+    ///
+    /// Return `None` if the invoice was paid but the zap receipt was not seen within the default timeout of [`Options`](super::Options).
+    ///
+    /// The invoice is paid only if its amount matches `amount_msat`, otherwise [`Error::ZapInvoiceAmountMismatch`] is returned.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use std::str::FromStr;
+    ///
+    /// use nostr_sdk::prelude::*;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #   let my_keys = Keys::generate();
+    /// # let uri = NostrWalletConnectURI::from_str("nostr+walletconnect://b889ff5b1513b641e2a139f661a661364979c5beee91842f8f0ef42ab558e9d4?relay=wss%3A%2F%2Frelay.damus.io&secret=71a8c14c1407c113601079c4302dab36460f0ccd0ad506f1f2dc73b5100e4f3c").unwrap();
+    /// let client = ClientBuilder::new()
+    ///     .signer(my_keys)
+    ///     .zapper(NostrWalletConnect::new(uri))
+    ///     .build();
+    ///
+    /// let public_key = XOnlyPublicKey::from_bech32(
+    ///     "npub1drvpzev3syqt0kjrls50050uzf25gehpz9vgdw08hvex7e0vgfeq0eseet",
+    /// )
+    /// .unwrap();
+    /// let receipt: Option<Event> = client
+    ///     .zap(public_key, 21_000, Some(String::from("Great post!")))
+    ///     .await
+    ///     .unwrap();
+    /// # }
+    /// ```
+    pub async fn zap<T>(
+        &self,
+        target: T,
+        amount_msat: u64,
+        comment: Option<String>,
+    ) -> Result<Option<Event>, Error>
+    where
+        T: Into<ZapTarget>,
+    {
+        let zapper = self.zapper.as_ref().ok_or(Error::ZapPayerNotConfigured)?;
+
+        #[cfg(not(target_arch = "wasm32"))]
+        let proxy = self.opts.proxy;
+        #[cfg(target_arch = "wasm32")]
+        let proxy = None;
+
+        // Recipient
+        let target: ZapTarget = target.into();
+        let public_key: XOnlyPublicKey = match target {
+            ZapTarget::PublicKey(public_key) => public_key,
+            ZapTarget::Event(event_id) => {
+                let filter = Filter::new().id(event_id).limit(1);
+                let events: Vec<Event> = self.get_events_of(vec![filter], None).await?;
+                events
+                    .first()
+                    .map(|e| e.author())
+                    .ok_or(Error::EventNotFound(event_id))?
+            }
+        };
+
+        // LNURL
+        let profile: Profile = self.fetch_metadata(public_key, None).await?;
+        let url: Url = nip57::lnurl_pay_url_from_metadata(&profile.metadata())?;
+        let lnurl: String = nip57::lnurl_encode(&url)?;
+        let pay: LnUrlPayResponse = nip57::get_lnurl_pay(&url, proxy).await?;
+        let receipt_author: XOnlyPublicKey = pay.zapper().ok_or(nip57::Error::ZapsNotSupported)?;
+
+        // Zap request
+        let relays = self
+            .relays()
+            .await
+            .into_keys()
+            .map(|url| UncheckedUrl::from(url.to_string()));
+        let mut data = ZapRequestData::new(public_key, relays)
+            .amount(amount_msat)
+            .lnurl(lnurl.clone());
+        if let ZapTarget::Event(event_id) = target {
+            data = data.event_id(event_id);
+        }
+        if let Some(comment) = comment {
+            data = data.message(comment);
+        }
+        let zap_request: Event = self
+            .internal_sign_event_builder(EventBuilder::public_zap_request(data))
+            .await?;
+
+        let invoice: String =
+            nip57::get_zap_invoice(&pay, &zap_request, amount_msat, Some(&lnurl), proxy).await?;
+
+        // Never trust the LNURL service: pay only the requested amount
+        let invoice_amount: Option<u64> = nip57::bolt11_amount_msat(&invoice);
+        if invoice_amount != Some(amount_msat) {
+            return Err(Error::ZapInvoiceAmountMismatch {
+                expected: amount_msat,
+                found: invoice_amount,
+            });
+        }
+
+        // Subscribe to the zap receipts before paying, to not miss it
+        let filter = Filter::new()
+            .kind(Kind::ZapReceipt)
+            .author(receipt_author)
+            .pubkey(public_key)
+            .since(zap_request.created_at());
+        let internal_id = InternalSubscriptionId::Custom(format!("nip57:{}", zap_request.id()));
+        let relays = self.relays().await;
+        let mut notifications = self.notifications();
+        for relay in relays.values() {
+            if let Err(e) = relay
+                .subscribe_with_internal_id(internal_id.clone(), vec![filter.clone()], None)
+                .await
+            {
+                tracing::error!("Impossible to subscribe to {}: {e}", relay.url());
+            }
+        }
+
+        let receipt: Result<Option<Event>, Error> = match zapper.pay_invoice(invoice).await {
+            Ok(()) => {
+                let timeout: Duration = self.opts.timeout;
+                let zap_request_id: EventId = zap_request.id();
+                Ok(time::timeout(Some(timeout), async move {
+                    loop {
+                        match notifications.recv().await {
+                            Ok(RelayPoolNotification::Event { event, .. }) => {
+                                if is_zap_receipt_of(&event, &zap_request_id) {
+                                    return Some(event);
+                                }
+                            }
+                            Ok(RelayPoolNotification::Stop)
+                            | Ok(RelayPoolNotification::Shutdown)
+                            | Err(RecvError::Closed) => return None,
+                            Ok(_) | Err(RecvError::Lagged(..)) => (),
+                        }
+                    }
+                })
+                .await
+                .flatten())
+            }
+            Err(e) => Err(e),
+        };
+
+        for relay in relays.values() {
+            if let Err(e) = relay
+                .unsubscribe_with_internal_id(internal_id.clone(), None)
+                .await
+            {
+                tracing::error!("Impossible to unsubscribe from {}: {e}", relay.url());
+            }
+        }
+
+        receipt
+    }
+
+    /// Get the aggregated zaps of an event
+    ///
+    /// Fetch the kind `9735` zap receipts and validate them against their zap request and
+    /// the `nostrPubkey` of the LNURL of the event author (see [`Zaps::from_receipts`]).
+    ///
+    /// If timeout is set to `None`, the default from [`Options`](super::Options) will be used.
+    pub async fn zaps_of(
+        &self,
+        event_id: EventId,
+        timeout: Option<Duration>,
+    ) -> Result<Zaps, Error> {
+        #[cfg(not(target_arch = "wasm32"))]
+        let proxy = self.opts.proxy;
+        #[cfg(target_arch = "wasm32")]
+        let proxy = None;
+
+        // Recipient
+        let filter = Filter::new().id(event_id).limit(1);
+        let events: Vec<Event> = self.get_events_of(vec![filter], timeout).await?;
+        let public_key: XOnlyPublicKey = events
+            .first()
+            .map(|e| e.author())
+            .ok_or(Error::EventNotFound(event_id))?;
+
+        // Key of the LNURL server, signing the zap receipts
+        let profile: Profile = self.fetch_metadata(public_key, timeout).await?;
+        let url: Url = nip57::lnurl_pay_url_from_metadata(&profile.metadata())?;
+        let pay: LnUrlPayResponse = nip57::get_lnurl_pay(&url, proxy).await?;
+        let zapper: XOnlyPublicKey = pay.zapper().ok_or(nip57::Error::ZapsNotSupported)?;
+
+        let filter = Filter::new()
+            .kind(Kind::ZapReceipt)
+            .author(zapper)
+            .event(event_id);
+        let receipts: Vec<Event> = self.get_events_of(vec![filter], timeout).await?;
+        Ok(Zaps::from_receipts(&event_id, &zapper, &receipts))
+    }
+}
+
+/// Check if `event` is the zap receipt of the zap request
+fn is_zap_receipt_of(event: &Event, zap_request_id: &EventId) -> bool {
+    if event.kind() != Kind::ZapReceipt {
+        return false;
+    }
+
+    event.iter_tags().any(|tag| match tag {
+        Tag::Description(description) => match Event::from_json(description) {
+            Ok(zap_request) => &zap_request.id() == zap_request_id,
+            Err(_) => false,
+        },
+        _ => false,
+    })
+}
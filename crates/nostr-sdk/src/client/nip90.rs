@@ -0,0 +1,277 @@
+// Copyright (c) 2022-2023 Yuki Kishimoto
+// Copyright (c) 2023-2024 Rust Nostr Developers
+// Distributed under the MIT software license
+
+//! Data Vending Machine client (NIP90)
+//!
+//! <https://github.com/nostr-protocol/nips/blob/master/90.md>
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_utility::{thread, time};
+use nostr::nips::nip90::{DataVendingMachineStatus, JobFeedback};
+use nostr::secp256k1::XOnlyPublicKey;
+use nostr::{Event, EventBuilder, EventId, Filter, Kind};
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::{broadcast, watch, RwLock};
+
+use super::{Client, Error};
+use crate::relay::pool::Error as RelayPoolError;
+use crate::relay::{InternalSubscriptionId, Relay, RelayPoolNotification};
+
+/// Job results (or errors) by service provider
+type JobResults = HashMap<XOnlyPublicKey, Result<Event, String>>;
+
+/// Job handle
+///
+/// Keep track of a job request (kind `5000-5999`): emit the [`JobFeedback`]s of the service providers
+/// and collect their job results (kind `6000-6999`).
+#[derive(Debug, Clone)]
+pub struct JobHandle {
+    request: Event,
+    internal_id: InternalSubscriptionId,
+    feedback: broadcast::Sender<JobFeedback>,
+    payment_required: Arc<RwLock<Option<JobFeedback>>>,
+    results: watch::Receiver<JobResults>,
+    relays: Vec<Relay>,
+    stopped: Arc<AtomicBool>,
+}
+
+impl JobHandle {
+    /// Get job request ID
+    pub fn id(&self) -> EventId {
+        self.request.id()
+    }
+
+    /// Get job request
+    pub fn request(&self) -> &Event {
+        &self.request
+    }
+
+    /// Get new [`JobFeedback`] listener
+    pub fn feedback(&self) -> broadcast::Receiver<JobFeedback> {
+        self.feedback.subscribe()
+    }
+
+    /// Get the last `payment-required` feedback
+    ///
+    /// The service provider will continue the job once the `bolt11` invoice (or a zap) is paid.
+    pub async fn payment_required(&self) -> Option<JobFeedback> {
+        let payment_required = self.payment_required.read().await;
+        payment_required.clone()
+    }
+
+    /// Get the job results (or the `error` feedbacks) received so far, by service provider
+    pub fn results(&self) -> JobResults {
+        self.results.borrow().clone()
+    }
+
+    /// Wait for the first job result
+    ///
+    /// The `error` feedbacks of a service provider don't stop the job: the others can still send the result.
+    /// If the result is not received in time, return [`Error::JobFailed`] with the errors of the
+    /// service providers or [`Error::JobTimeout`] if none sent an `error` feedback.
+    pub async fn result(&self, timeout: Duration) -> Result<Event, Error> {
+        let mut results = self.results.clone();
+        let res: Option<Event> = time::timeout(Some(timeout), async move {
+            loop {
+                if let Some(event) = results.borrow().values().find_map(|res| res.clone().ok()) {
+                    return Some(event);
+                }
+                if results.changed().await.is_err() {
+                    return None;
+                }
+            }
+        })
+        .await
+        .flatten();
+
+        match res {
+            Some(event) => Ok(event),
+            None => {
+                let errors: Vec<String> = self
+                    .results
+                    .borrow()
+                    .iter()
+                    .filter_map(|(provider, res)| {
+                        res.as_ref().err().map(|e| format!("{provider}: {e}"))
+                    })
+                    .collect();
+                if errors.is_empty() {
+                    Err(Error::JobTimeout)
+                } else {
+                    Err(Error::JobFailed(errors.join(", ")))
+                }
+            }
+        }
+    }
+
+    /// Check if handle is stopped
+    pub fn is_stopped(&self) -> bool {
+        self.stopped.load(Ordering::SeqCst)
+    }
+
+    /// Stop tracking the job and close the subscriptions
+    pub async fn stop(&self) {
+        self.stopped.store(true, Ordering::SeqCst);
+        for relay in self.relays.iter() {
+            if let Err(e) = relay
+                .unsubscribe_with_internal_id(self.internal_id.clone(), None)
+                .await
+            {
+                tracing::error!("Impossible to unsubscribe from {}: {e}", relay.url());
+            }
+        }
+    }
+
+    fn is_result(&self, event: &Event) -> bool {
+        event.kind() == self.request.kind() + 1000 && self.is_related(event)
+    }
+
+    fn is_related(&self, event: &Event) -> bool {
+        let id: EventId = self.request.id();
+        event.event_ids().any(|e| e == &id)
+    }
+}
+
+impl Client {
+    /// Submit a job request to the Data Vending Machines (NIP90)
+    ///
+    /// Subscribe to the job feedback (kind `7000`) and result on all relays, publish the job request
+    /// and return a [`JobHandle`], that collects the job results of the service providers.
+    ///
+    /// The results are collected until [`JobHandle::stop`] is called or the pool is stopped.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use std::time::Duration;
+    ///
+    /// use nostr_sdk::prelude::*;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #   let my_keys = Keys::generate();
+    /// #   let client = Client::new(&my_keys);
+    /// let input = Tag::parse(vec!["i", "https://example.com/podcast.mp3", "url"]).unwrap();
+    /// let request = EventBuilder::job_request(Kind::JobRequest(5000), [input]).unwrap();
+    /// let job = client.submit_job(request).await.unwrap();
+    /// let result: Event = job.result(Duration::from_secs(120)).await.unwrap();
+    /// # }
+    /// ```
+    ///
+    /// <https://github.com/nostr-protocol/nips/blob/master/90.md>
+    pub async fn submit_job(&self, request: EventBuilder) -> Result<JobHandle, Error> {
+        let request: Event = self.internal_sign_event_builder(request).await?;
+        if !request.kind().is_job_request() {
+            return Err(Error::NotJobRequest);
+        }
+
+        let filter = Filter::new()
+            .kinds([Kind::JobFeedback, request.kind() + 1000])
+            .event(request.id())
+            .since(request.created_at());
+        let internal_id = InternalSubscriptionId::Custom(format!("nip90:{}", request.id()));
+
+        let (feedback, ..) = broadcast::channel(self.opts.pool.notification_channel_size);
+        let (results_sender, results) = watch::channel::<JobResults>(HashMap::new());
+        let handle = JobHandle {
+            request: request.clone(),
+            internal_id: internal_id.clone(),
+            feedback,
+            payment_required: Arc::new(RwLock::new(None)),
+            results,
+            relays: self.relays().await.into_values().collect(),
+            stopped: Arc::new(AtomicBool::new(false)),
+        };
+
+        // Get notification listener before subscribe, to not miss any event
+        let mut notifications = self.notifications();
+
+        // Subscribe before publishing the request
+        for relay in handle.relays.iter() {
+            if let Err(e) = relay
+                .subscribe_with_internal_id(internal_id.clone(), vec![filter.clone()], None)
+                .await
+            {
+                tracing::error!("Impossible to subscribe to {}: {e}", relay.url());
+            }
+        }
+
+        let h = handle.clone();
+        thread::spawn(async move {
+            loop {
+                if h.is_stopped() {
+                    break;
+                }
+
+                match notifications.recv().await {
+                    Ok(RelayPoolNotification::Event { event, .. }) => {
+                        if h.is_result(&event) {
+                            results_sender.send_modify(|results| {
+                                results.insert(event.author(), Ok(event));
+                            });
+                            continue;
+                        }
+
+                        if event.kind() != Kind::JobFeedback || !h.is_related(&event) {
+                            continue;
+                        }
+
+                        match JobFeedback::from_event(&event) {
+                            Ok(feedback) => {
+                                match feedback.status {
+                                    DataVendingMachineStatus::PaymentRequired => {
+                                        let mut payment_required = h.payment_required.write().await;
+                                        *payment_required = Some(feedback.clone());
+                                    }
+                                    DataVendingMachineStatus::Error => {
+                                        let error: String =
+                                            feedback.extra_info.clone().unwrap_or_default();
+                                        // Don't replace a result already received
+                                        results_sender.send_modify(|results| {
+                                            results.entry(feedback.provider).or_insert(Err(error));
+                                        });
+                                    }
+                                    _ => (),
+                                }
+                                let _ = h.feedback.send(feedback);
+                            }
+                            Err(e) => tracing::error!(
+                                "Impossible to parse job feedback {}: {e}",
+                                event.id()
+                            ),
+                        }
+                    }
+                    Ok(RelayPoolNotification::Stop) | Ok(RelayPoolNotification::Shutdown) => break,
+                    Ok(_) => (),
+                    Err(RecvError::Lagged(num)) => {
+                        tracing::warn!("Job handle lagged: {num} notifications skipped")
+                    }
+                    Err(RecvError::Closed) => break,
+                }
+            }
+
+            if !h.is_stopped() {
+                h.stop().await;
+            }
+            tracing::debug!("Exited from job handle");
+        });
+
+        let res: Result<(), Error> = match self.send_event(request).await {
+            Ok(output) if output.is_success() => Ok(()),
+            Ok(output) => Err(Error::RelayPool(RelayPoolError::EventNotPublished(
+                output.val,
+            ))),
+            Err(e) => Err(e),
+        };
+        if let Err(e) = res {
+            handle.stop().await;
+            return Err(e);
+        }
+
+        Ok(handle)
+    }
+}
@@ -0,0 +1,129 @@
+// Copyright (c) 2022-2023 Yuki Kishimoto
+// Copyright (c) 2023-2024 Rust Nostr Developers
+// Distributed under the MIT software license
+
+//! File upload (NIP96 and NIP94)
+//!
+//! <https://github.com/nostr-protocol/nips/blob/master/96.md>
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::{Path, PathBuf};
+
+use nostr::nips::nip94::FileMetadata;
+use nostr::nips::nip96::{self, ServerConfig, UploadResponse};
+use nostr::{Event, EventBuilder, Url};
+
+use super::{Client, Error};
+
+/// File to upload
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FileSource {
+    /// File path
+    #[cfg(not(target_arch = "wasm32"))]
+    Path(PathBuf),
+    /// File content
+    Bytes(Vec<u8>),
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl From<PathBuf> for FileSource {
+    fn from(path: PathBuf) -> Self {
+        Self::Path(path)
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl From<&Path> for FileSource {
+    fn from(path: &Path) -> Self {
+        Self::Path(path.to_path_buf())
+    }
+}
+
+impl From<Vec<u8>> for FileSource {
+    fn from(bytes: Vec<u8>) -> Self {
+        Self::Bytes(bytes)
+    }
+}
+
+impl From<&[u8]> for FileSource {
+    fn from(bytes: &[u8]) -> Self {
+        Self::Bytes(bytes.to_vec())
+    }
+}
+
+impl FileSource {
+    async fn read(self) -> Result<Vec<u8>, Error> {
+        match self {
+            #[cfg(not(target_arch = "wasm32"))]
+            Self::Path(path) => Ok(tokio::fs::read(path).await?),
+            Self::Bytes(bytes) => Ok(bytes),
+        }
+    }
+}
+
+impl Client {
+    /// Upload a file to a NIP96 server and publish its file metadata (NIP94)
+    ///
+    /// Fetch the server configuration, upload the file with an HTTP auth (NIP98) and
+    /// publish the kind `1063` event built from the server response.
+    /// The hash returned by the server is verified against the file. The size and the image dimensions
+    /// are computed locally, unless the server transformed the file.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use std::path::Path;
+    ///
+    /// use nostr_sdk::prelude::*;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #   let my_keys = Keys::generate();
+    /// #   let client = Client::new(&my_keys);
+    /// let server = Url::parse("https://nostr.build").unwrap();
+    /// let event: Event = client
+    ///     .upload_file(Path::new("./image.png"), "image/png", server)
+    ///     .await
+    ///     .unwrap();
+    /// # }
+    /// ```
+    ///
+    /// <https://github.com/nostr-protocol/nips/blob/master/96.md>
+    pub async fn upload_file<F, S>(
+        &self,
+        file: F,
+        mime_type: S,
+        server_url: Url,
+    ) -> Result<Event, Error>
+    where
+        F: Into<FileSource>,
+        S: AsRef<str>,
+    {
+        let mime_type: &str = mime_type.as_ref();
+        let data: Vec<u8> = file.into().read().await?;
+
+        #[cfg(not(target_arch = "wasm32"))]
+        let proxy = self.opts.proxy;
+        #[cfg(target_arch = "wasm32")]
+        let proxy = None;
+
+        let config: ServerConfig = ServerConfig::get(&server_url, proxy).await?;
+        if !config.accepts(mime_type) {
+            return Err(Error::UnsupportedMimeType(mime_type.to_string()));
+        }
+
+        // HTTP auth
+        let builder = EventBuilder::http_auth(nip96::upload_http_data(&config, &data));
+        let auth: Event = self.internal_sign_event_builder(builder).await?;
+
+        let response: UploadResponse =
+            nip96::upload_data(&config, &auth, &data, mime_type, proxy).await?;
+        let metadata: FileMetadata = response.file_metadata(&data, mime_type)?;
+        let description: String = response.nip94_event.map(|e| e.content).unwrap_or_default();
+
+        let builder = EventBuilder::file_metadata(description, metadata);
+        let event: Event = self.internal_sign_event_builder(builder).await?;
+        self.send_event(event.clone()).await?;
+
+        Ok(event)
+    }
+}
@@ -29,6 +29,10 @@ pub struct Options {
     ///
     /// If the relay made just 1 attempt, the relay will not be skipped
     skip_disconnected_relays: Arc<AtomicBool>,
+    /// Automatically authenticate to relays (NIP42) (default: false)
+    automatic_authentication: Arc<AtomicBool>,
+    /// Queue the sent events into the outbox, without any network (default: false)
+    offline: Arc<AtomicBool>,
     /// Timeout (default: 60)
     ///
     /// Used in `get_events_of`, `req_events_of` and similar as default timeout.
@@ -39,14 +43,36 @@ pub struct Options {
     pub connection_timeout: Option<Duration>,
     /// Send timeout (default: 20 secs)
     pub send_timeout: Option<Duration>,
-    /// NIP46 timeout (default: 180 secs)
-    #[cfg(feature = "nip46")]
-    pub nip46_timeout: Option<Duration>,
     /// Proxy
+    ///
+    /// Used by the relays with [`RelayProxy::Inherit`](crate::RelayProxy::Inherit) (default) and for HTTP requests.
     #[cfg(not(target_arch = "wasm32"))]
     pub proxy: Option<SocketAddr>,
     /// Shutdown on [Client](super::Client) drop
     pub shutdown_on_drop: bool,
+    /// Automatically attach `alt` tags (NIP31) to non-kind-1 events (default: false)
+    pub autoalt: bool,
+    /// Route events and requests with the outbox model (NIP65) (default: false)
+    pub gossip: bool,
+    /// Max number of relays used for each public key, when gossip is enabled (default: 3)
+    pub gossip_relays_per_pubkey: usize,
+    /// Max number of relays added by gossip (default: 50)
+    pub gossip_max_relays: usize,
+    /// Prefetch the contacts metadata and relay lists after connecting (default: None)
+    pub prefetch_contacts: Option<Duration>,
+    /// Create a new list, when updating one that is not found (default: false)
+    pub create_missing_lists: bool,
+    /// Direct messages policy (default: prefer NIP17)
+    pub dm_policy: DmPolicy,
+    /// Max number of cached NIP44 conversation keys (default: 256)
+    #[cfg(feature = "nip44")]
+    pub conversation_keys_cache_size: usize,
+    /// Skip the muted events in [`Client::handle_notifications`](super::Client::handle_notifications) (default: false)
+    #[cfg(feature = "nip44")]
+    pub filter_muted: bool,
+    /// Max age of the cached NIP11 relay info (default: 1 day)
+    #[cfg(feature = "nip11")]
+    pub relay_info_ttl: Duration,
     /// Pool Options
     pub pool: RelayPoolOptions,
 }
@@ -59,14 +85,27 @@ impl Default for Options {
             difficulty: Arc::new(AtomicU8::new(0)),
             req_filters_chunk_size: Arc::new(AtomicU8::new(10)),
             skip_disconnected_relays: Arc::new(AtomicBool::new(true)),
+            automatic_authentication: Arc::new(AtomicBool::new(false)),
+            offline: Arc::new(AtomicBool::new(false)),
             timeout: Duration::from_secs(60),
             connection_timeout: None,
             send_timeout: Some(DEFAULT_SEND_TIMEOUT),
-            #[cfg(feature = "nip46")]
-            nip46_timeout: Some(Duration::from_secs(180)),
             #[cfg(not(target_arch = "wasm32"))]
             proxy: None,
             shutdown_on_drop: false,
+            autoalt: false,
+            gossip: false,
+            gossip_relays_per_pubkey: 3,
+            gossip_max_relays: 50,
+            prefetch_contacts: None,
+            create_missing_lists: false,
+            dm_policy: DmPolicy::default(),
+            #[cfg(feature = "nip44")]
+            conversation_keys_cache_size: 256,
+            #[cfg(feature = "nip44")]
+            filter_muted: false,
+            #[cfg(feature = "nip11")]
+            relay_info_ttl: Duration::from_secs(60 * 60 * 24),
             pool: RelayPoolOptions::default(),
         }
     }
@@ -152,6 +191,42 @@ impl Options {
         self.skip_disconnected_relays.load(Ordering::SeqCst)
     }
 
+    /// Automatically authenticate to relays (NIP42) (default: false)
+    ///
+    /// When a relay send an `AUTH` challenge, the client sign the auth event with the configured signer and send it.
+    /// Events and subscriptions rejected with `auth-required:` are sent again after the authentication.
+    ///
+    /// <https://github.com/nostr-protocol/nips/blob/master/42.md>
+    pub fn automatic_authentication(self, enabled: bool) -> Self {
+        Self {
+            automatic_authentication: Arc::new(AtomicBool::new(enabled)),
+            ..self
+        }
+    }
+
+    pub(crate) fn get_automatic_authentication(&self) -> bool {
+        self.automatic_authentication.load(Ordering::SeqCst)
+    }
+
+    /// Offline mode (default: false)
+    ///
+    /// The sent events are signed and stored into the database and the outbox,
+    /// without any network, until [`Client::flush_outbox`](super::Client::flush_outbox) is called.
+    pub fn offline(self, offline: bool) -> Self {
+        Self {
+            offline: Arc::new(AtomicBool::new(offline)),
+            ..self
+        }
+    }
+
+    pub(crate) fn get_offline(&self) -> bool {
+        self.offline.load(Ordering::SeqCst)
+    }
+
+    pub(crate) fn update_offline(&self, offline: bool) {
+        self.offline.store(offline, Ordering::SeqCst);
+    }
+
     /// Set default timeout
     pub fn timeout(self, timeout: Duration) -> Self {
         Self { timeout, ..self }
@@ -173,15 +248,6 @@ impl Options {
         }
     }
 
-    /// Set NIP46 timeout
-    #[cfg(feature = "nip46")]
-    pub fn nip46_timeout(self, timeout: Option<Duration>) -> Self {
-        Self {
-            nip46_timeout: timeout,
-            ..self
-        }
-    }
-
     /// Proxy
     #[cfg(not(target_arch = "wasm32"))]
     pub fn proxy(mut self, proxy: Option<SocketAddr>) -> Self {
@@ -197,8 +263,146 @@ impl Options {
         }
     }
 
+    /// Automatically attach a default `alt` tag (NIP31) to non-kind-1 events, if missing
+    ///
+    /// Help other clients to gracefully show events of unknown kinds.
+    pub fn autoalt(self, value: bool) -> Self {
+        Self {
+            autoalt: value,
+            ..self
+        }
+    }
+
+    /// Route events and requests with the outbox model (NIP65)
+    ///
+    /// Events are sent to the write relays of the author (and, for non-replaceable events, to the read relays
+    /// of the tagged public keys). Requests with `authors` are sent to the write relays of the authors,
+    /// requests with `#p` also to the read relays of the tagged public keys.
+    /// Public keys without a known relay list use the relays of the pool.
+    ///
+    /// The relay lists are read from the database: see [`Client::sync_relay_lists`](super::Client::sync_relay_lists).
+    pub fn gossip(self, enabled: bool) -> Self {
+        Self {
+            gossip: enabled,
+            ..self
+        }
+    }
+
+    /// Set max number of relays used for each public key, when gossip is enabled (default: 3)
+    pub fn gossip_relays_per_pubkey(self, max: usize) -> Self {
+        Self {
+            gossip_relays_per_pubkey: max,
+            ..self
+        }
+    }
+
+    /// Set max number of relays added by gossip (default: 50)
+    ///
+    /// When the limit is exceeded, the least recently used gossip relays are removed.
+    /// The relays added with [`Client::add_relay`](super::Client::add_relay) are never removed.
+    pub fn gossip_max_relays(self, max: usize) -> Self {
+        Self {
+            gossip_max_relays: max,
+            ..self
+        }
+    }
+
+    /// Prefetch the contacts metadata and relay lists after connecting (default: None)
+    ///
+    /// If set, [`Client::connect`](super::Client::connect) start the background task of
+    /// [`Client::refresh_contacts_metadata`](super::Client::refresh_contacts_metadata),
+    /// keeping fresh the events of the contacts not refreshed since `staleness`.
+    pub fn prefetch_contacts(self, staleness: Option<Duration>) -> Self {
+        Self {
+            prefetch_contacts: staleness,
+            ..self
+        }
+    }
+
+    /// Create a new list when updating one that is not found (default: false)
+    ///
+    /// By default, [`Client::follow`](super::Client::follow), [`Client::unfollow`](super::Client::unfollow)
+    /// [`Client::mute`](super::Client::mute) and [`Client::bookmark`](super::Client::bookmark)
+    /// fail if the list can't be fetched,
+    /// to not replace it with an almost empty one.
+    pub fn create_missing_lists(self, create: bool) -> Self {
+        Self {
+            create_missing_lists: create,
+            ..self
+        }
+    }
+
+    /// Set direct messages policy (default: prefer NIP17)
+    ///
+    /// See [`Client::send_dm`](super::Client::send_dm).
+    pub fn dm_policy(self, policy: DmPolicy) -> Self {
+        Self {
+            dm_policy: policy,
+            ..self
+        }
+    }
+
+    /// Set max number of cached NIP44 conversation keys (default: 256)
+    ///
+    /// Set to `0` to disable the cache.
+    #[cfg(feature = "nip44")]
+    pub fn conversation_keys_cache_size(self, size: usize) -> Self {
+        Self {
+            conversation_keys_cache_size: size,
+            ..self
+        }
+    }
+
+    /// Skip the events muted by the cached mute list in [`Client::handle_notifications`](super::Client::handle_notifications) (default: false)
+    ///
+    /// See [`Client::fetch_mute_list`](super::Client::fetch_mute_list).
+    #[cfg(feature = "nip44")]
+    pub fn filter_muted(self, filter: bool) -> Self {
+        Self {
+            filter_muted: filter,
+            ..self
+        }
+    }
+
+    /// Set max age of the cached NIP11 relay info (default: 1 day)
+    ///
+    /// See [`Client::relay_info`](super::Client::relay_info).
+    #[cfg(feature = "nip11")]
+    pub fn relay_info_ttl(self, ttl: Duration) -> Self {
+        Self {
+            relay_info_ttl: ttl,
+            ..self
+        }
+    }
+
     /// Set pool options
     pub fn pool(self, opts: RelayPoolOptions) -> Self {
         Self { pool: opts, ..self }
     }
 }
+
+/// Guard against duplicate reactions and reposts
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DuplicateGuard {
+    /// Check the local database (default)
+    #[default]
+    Database,
+    /// Check the local database and the relays
+    ///
+    /// If `None` is passed, the default timeout of [`Options`] is used.
+    DatabaseAndRelays(Option<Duration>),
+    /// Skip the check
+    Force,
+}
+
+/// Direct messages policy
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DmPolicy {
+    /// Encrypted direct messages (NIP04)
+    Nip04,
+    /// Private direct messages (NIP17)
+    Nip17,
+    /// Private direct messages (NIP17), falling back to NIP04 if the signer doesn't support NIP44 (default)
+    #[default]
+    PreferNip17,
+}
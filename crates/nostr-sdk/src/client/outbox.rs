@@ -0,0 +1,86 @@
+// Copyright (c) 2022-2023 Yuki Kishimoto
+// Copyright (c) 2023-2024 Rust Nostr Developers
+// Distributed under the MIT software license
+
+//! Offline mode and outbox
+
+use nostr::{Event, EventId};
+
+use super::{Client, Error};
+
+impl Client {
+    /// Enable/disable the offline mode
+    ///
+    /// See [`Options::offline`](super::Options::offline).
+    pub fn set_offline(&self, offline: bool) {
+        self.opts.update_offline(offline);
+    }
+
+    /// Check if the offline mode is enabled
+    pub fn is_offline(&self) -> bool {
+        self.opts.get_offline()
+    }
+
+    /// Get the events waiting to be published, sorted by `created_at`
+    ///
+    /// Require a database that support the outbox.
+    pub async fn outbox(&self) -> Result<Vec<Event>, Error> {
+        Ok(self.database().outbox_events().await?)
+    }
+
+    /// Publish the events of the outbox
+    ///
+    /// The events are sent also if the offline mode is still enabled.
+    /// Published events are removed from the outbox, the others are kept for the next flush.
+    ///
+    /// Return the IDs of the published events.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use nostr_sdk::prelude::*;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #   let my_keys = Keys::generate();
+    /// let opts = Options::new().offline(true);
+    /// let client = Client::with_opts(&my_keys, opts);
+    ///
+    /// // Signed and queued, without any network
+    /// client
+    ///     .publish_text_note("Written offline", [])
+    ///     .await
+    ///     .unwrap();
+    ///
+    /// // Back online
+    /// client.set_offline(false);
+    /// client.add_relay("wss://relay.damus.io").await.unwrap();
+    /// client.connect().await;
+    /// let published = client.flush_outbox().await.unwrap();
+    /// # }
+    /// ```
+    pub async fn flush_outbox(&self) -> Result<Vec<EventId>, Error> {
+        let mut published: Vec<EventId> = Vec::new();
+        for event in self.outbox().await?.into_iter() {
+            let event_id: EventId = event.id();
+            match self.internal_send_event(event).await {
+                Ok(output) if output.is_success() => {
+                    self.database().remove_outbox_event(event_id).await?;
+                    published.push(event_id);
+                }
+                // Keep it for the next flush
+                Ok(..) => tracing::error!("Outbox event {event_id} not published to any relay"),
+                Err(e) => tracing::error!("Impossible to publish outbox event {event_id}: {e}"),
+            }
+        }
+        Ok(published)
+    }
+
+    /// Store the event into the database and the outbox
+    pub(super) async fn queue_outbox_event(&self, event: &Event) -> Result<(), Error> {
+        let database = self.database();
+        database.save_event(event).await?;
+        database.save_outbox_event(event).await?;
+        tracing::debug!("Event {} queued into the outbox", event.id());
+        Ok(())
+    }
+}
@@ -0,0 +1,182 @@
+// Copyright (c) 2022-2023 Yuki Kishimoto
+// Copyright (c) 2023-2024 Rust Nostr Developers
+// Distributed under the MIT software license
+
+//! Contact metadata and relay lists refresher
+
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+use async_utility::thread;
+use nostr::secp256k1::XOnlyPublicKey;
+use nostr::{Filter, Kind, Timestamp};
+
+use super::{Client, Error};
+use crate::relay::pool::RelayPool;
+use crate::relay::FilterOptions;
+
+const REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+impl Client {
+    /// Keep the contacts metadata fresh
+    ///
+    /// Spawn a background task that periodically fetch the contact list, the metadata (kind 0)
+    /// and the relay lists (kind 10002) of the contacts not refreshed since `staleness`. The contacts are requested
+    /// immediately, in batches of [`Options::req_filters_chunk_size`](super::Options::req_filters_chunk_size) authors.
+    ///
+    /// The received events are stored in the database: use [`NostrDatabaseExt::contacts`](nostr_database::NostrDatabaseExt::contacts)
+    /// or [`NostrDatabaseExt::profile`](nostr_database::NostrDatabaseExt::profile) to read the metadata.
+    /// The relay lists are used by the outbox model (see [`Options::gossip()`](super::Options::gossip())).
+    ///
+    /// To automatically start the task on [`Client::connect`], see [`Options::prefetch_contacts()`](super::Options::prefetch_contacts()).
+    ///
+    /// The task exit when the pool is stopped or when [`Client::stop_contacts_metadata_refresher`] is called.
+    /// If the task is already running, this method does nothing.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use std::time::Duration;
+    ///
+    /// use nostr_sdk::prelude::*;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #   let my_keys = Keys::generate();
+    /// #   let client = Client::new(&my_keys);
+    /// client
+    ///     .refresh_contacts_metadata(Duration::from_secs(3600))
+    ///     .await
+    ///     .unwrap();
+    ///
+    /// let profiles = client
+    ///     .database()
+    ///     .contacts(my_keys.public_key())
+    ///     .await
+    ///     .unwrap();
+    /// # }
+    /// ```
+    pub async fn refresh_contacts_metadata(&self, staleness: Duration) -> Result<(), Error> {
+        let public_key: XOnlyPublicKey = self.signer_public_key().await?;
+
+        // Odd generation: a task is running. Each task gets its own generation, so a task that
+        // has been stopped exit also if a new one has been started in the meantime.
+        let started =
+            self.metadata_refresher
+                .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |g| {
+                    (g % 2 == 0).then_some(g + 1)
+                });
+        let generation: u64 = match started {
+            Ok(previous) => previous + 1,
+            Err(..) => return Ok(()),
+        };
+
+        let pool: RelayPool = self.pool.clone();
+        let timeout: Duration = self.opts.timeout;
+        let chunk_size: usize = self.opts.get_req_filters_chunk_size().max(1);
+        let current = self.metadata_refresher.clone();
+        thread::spawn(async move {
+            tracing::debug!("Contacts metadata refresher started");
+
+            let mut last_refresh: HashMap<XOnlyPublicKey, Timestamp> = HashMap::new();
+
+            while pool.is_running() && current.load(Ordering::SeqCst) == generation {
+                // Contact list
+                let filter = Filter::new()
+                    .author(public_key)
+                    .kind(Kind::ContactList)
+                    .limit(1);
+                let contacts: Vec<XOnlyPublicKey> = match pool
+                    .get_events_of(vec![filter], timeout, FilterOptions::ExitOnEOSE)
+                    .await
+                {
+                    Ok(events) => match events.iter().max_by_key(|e| e.created_at()) {
+                        Some(event) => event.public_keys().copied().collect(),
+                        None => Vec::new(),
+                    },
+                    Err(e) => {
+                        tracing::error!("Impossible to get contact list: {e}");
+                        Vec::new()
+                    }
+                };
+
+                // Forget the removed contacts
+                let contacts_set: HashSet<&XOnlyPublicKey> = contacts.iter().collect();
+                last_refresh.retain(|public_key, _| contacts_set.contains(public_key));
+
+                let now = Timestamp::now();
+                let stale: Vec<XOnlyPublicKey> = contacts
+                    .into_iter()
+                    .filter(|public_key| match last_refresh.get(public_key) {
+                        Some(timestamp) => *timestamp + staleness <= now,
+                        None => true,
+                    })
+                    .collect();
+
+                if stale.is_empty() {
+                    thread::sleep(REFRESH_INTERVAL).await;
+                    continue;
+                }
+
+                let mut failed: bool = false;
+                for chunk in stale.chunks(chunk_size) {
+                    if !pool.is_running() || current.load(Ordering::SeqCst) != generation {
+                        break;
+                    }
+
+                    let filter: Filter = Filter::new()
+                        .authors(chunk.iter().copied())
+                        .kinds([Kind::Metadata, Kind::RelayList]);
+                    match pool
+                        .get_events_of(vec![filter], timeout, FilterOptions::ExitOnEOSE)
+                        .await
+                    {
+                        Ok(events) => {
+                            tracing::debug!(
+                                "Refreshed metadata and relay lists of {} contacts ({} events received)",
+                                chunk.len(),
+                                events.len()
+                            );
+                            let now = Timestamp::now();
+                            for public_key in chunk.iter() {
+                                last_refresh.insert(*public_key, now);
+                            }
+                        }
+                        // Retry at the next round
+                        Err(e) => {
+                            tracing::error!("Impossible to refresh contacts metadata: {e}");
+                            failed = true;
+                        }
+                    }
+                }
+
+                // Don't retry the failed batches immediately
+                if failed {
+                    thread::sleep(REFRESH_INTERVAL).await;
+                }
+            }
+
+            // Mark as stopped, unless already stopped
+            let _ = current.compare_exchange(
+                generation,
+                generation + 1,
+                Ordering::SeqCst,
+                Ordering::SeqCst,
+            );
+            tracing::debug!("Contacts metadata refresher exited");
+        });
+
+        Ok(())
+    }
+
+    /// Stop the contacts metadata and relay lists refresher
+    ///
+    /// See [`Client::refresh_contacts_metadata`].
+    pub fn stop_contacts_metadata_refresher(&self) {
+        let _ = self
+            .metadata_refresher
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |g| {
+                (g % 2 == 1).then_some(g + 1)
+            });
+    }
+}
@@ -0,0 +1,127 @@
+// Copyright (c) 2022-2023 Yuki Kishimoto
+// Copyright (c) 2023-2024 Rust Nostr Developers
+// Distributed under the MIT software license
+
+//! Event publish scheduling
+
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_utility::thread;
+use nostr::{Event, EventBuilder, EventId, Timestamp};
+use nostr_database::{DatabaseError, DynNostrDatabase};
+
+use super::{Client, Error};
+use crate::relay::pool::RelayPool;
+use crate::relay::RelaySendOptions;
+
+const SCHEDULER_INTERVAL: Duration = Duration::from_secs(5);
+
+impl Client {
+    /// Schedule [`Event`] publishing
+    ///
+    /// The event is signed now, with `publish_at` as `created_at`, and persisted in the database.
+    /// It will be published when due, while the client is running (see [`Client::connect`]).
+    ///
+    /// Require a database that support scheduled events.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use std::time::Duration;
+    ///
+    /// use nostr_sdk::prelude::*;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// #   let my_keys = Keys::generate();
+    /// #   let client = Client::new(&my_keys);
+    /// let publish_at = Timestamp::now() + Duration::from_secs(3600);
+    /// let builder = EventBuilder::text_note("Scheduled note", []);
+    /// client.schedule_event(builder, publish_at).await.unwrap();
+    /// # }
+    /// ```
+    pub async fn schedule_event(
+        &self,
+        builder: EventBuilder,
+        publish_at: Timestamp,
+    ) -> Result<EventId, Error> {
+        let event: Event = self
+            .internal_sign_event_builder(builder.custom_created_at(publish_at))
+            .await?;
+        self.database().save_scheduled_event(&event).await?;
+        self.spawn_scheduler();
+        Ok(event.id())
+    }
+
+    /// Get scheduled events not published yet, sorted by publish time
+    pub async fn scheduled_events(&self) -> Result<Vec<Event>, Error> {
+        Ok(self.database().scheduled_events().await?)
+    }
+
+    /// Cancel scheduled event
+    ///
+    /// Return `false` if the event wasn't scheduled (or was already published).
+    pub async fn cancel_scheduled_event(&self, event_id: EventId) -> Result<bool, Error> {
+        Ok(self.database().remove_scheduled_event(event_id).await?)
+    }
+
+    /// Spawn the scheduler, if not already running
+    ///
+    /// The scheduler exit when the pool is stopped.
+    pub(super) fn spawn_scheduler(&self) {
+        if self.scheduler.swap(true, Ordering::SeqCst) {
+            return;
+        }
+
+        let pool: RelayPool = self.pool.clone();
+        let database: Arc<DynNostrDatabase> = self.database();
+        let opts = RelaySendOptions::new()
+            .skip_disconnected(self.opts.get_skip_disconnected_relays())
+            .timeout(self.opts.send_timeout);
+        let scheduler = self.scheduler.clone();
+        thread::spawn(async move {
+            tracing::debug!("Scheduler started");
+
+            while pool.is_running() {
+                match database.scheduled_events().await {
+                    Ok(events) => {
+                        let now = Timestamp::now();
+                        for event in events.into_iter().take_while(|e| e.created_at() <= now) {
+                            let event_id: EventId = event.id();
+                            match pool.send_event(event, opts).await {
+                                Ok(output) if output.is_success() => {
+                                    tracing::info!("Scheduled event {event_id} published");
+                                    if let Err(e) = database.remove_scheduled_event(event_id).await
+                                    {
+                                        tracing::error!(
+                                            "Impossible to remove scheduled event {event_id}: {e}"
+                                        );
+                                    }
+                                }
+                                // Retry at the next iteration
+                                Ok(..) => tracing::error!(
+                                    "Scheduled event {event_id} not published to any relay"
+                                ),
+                                Err(e) => tracing::error!(
+                                    "Impossible to publish scheduled event {event_id}: {e}"
+                                ),
+                            }
+                        }
+                    }
+                    // Scheduling not supported by the database
+                    Err(DatabaseError::NotSupported) => break,
+                    Err(e) => {
+                        tracing::error!("Impossible to get scheduled events: {e}");
+                        break;
+                    }
+                }
+
+                thread::sleep(SCHEDULER_INTERVAL).await;
+            }
+
+            scheduler.store(false, Ordering::SeqCst);
+            tracing::debug!("Scheduler exited");
+        });
+    }
+}
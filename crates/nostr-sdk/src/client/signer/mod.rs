@@ -4,110 +4,239 @@
 
 //! Client Signers
 
-use std::fmt;
+use std::sync::Arc;
 
+#[cfg(feature = "nip04")]
+use nostr::nips::nip04;
 #[cfg(all(feature = "nip07", target_arch = "wasm32"))]
 use nostr::nips::nip07::Nip07Signer;
-use nostr::Keys;
+#[cfg(feature = "nip44")]
+use nostr::nips::nip44::{self, ConversationKey, Version};
+use nostr::secp256k1::XOnlyPublicKey;
+use nostr::{Event, Keys, UnsignedEvent};
+use nostr_database::{async_trait, AsyncTraitDeps};
 
 #[cfg(feature = "nip46")]
 pub mod nip46;
 
-#[cfg(feature = "nip46")]
-use self::nip46::Nip46Signer;
-#[cfg(feature = "nip46")]
 use super::Error;
 
-/// Client Signer Type
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
-pub enum ClientSignerType {
-    /// Keys
-    Keys,
-    /// NIP07
-    #[cfg(all(feature = "nip07", target_arch = "wasm32"))]
-    NIP07,
-    /// NIP46
-    #[cfg(feature = "nip46")]
-    NIP46,
+/// Dynamic [`NostrSigner`]
+pub type DynNostrSigner = dyn NostrSigner;
+
+/// A type that can be converted into a [`NostrSigner`] for the [`Client`](super::Client)
+pub trait IntoNostrSigner {
+    #[doc(hidden)]
+    fn into_nostr_signer(self) -> Arc<DynNostrSigner>;
 }
 
-// TODO: better display
-impl fmt::Display for ClientSignerType {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            Self::Keys => write!(f, "Keys"),
-            #[cfg(all(feature = "nip07", target_arch = "wasm32"))]
-            Self::NIP07 => write!(f, "NIP07"),
-            #[cfg(feature = "nip46")]
-            Self::NIP46 => write!(f, "NIP46"),
-        }
+impl IntoNostrSigner for Arc<DynNostrSigner> {
+    fn into_nostr_signer(self) -> Arc<DynNostrSigner> {
+        self
     }
 }
 
-/// Client signer
-#[derive(Debug, Clone)]
-pub enum ClientSigner {
-    /// Private Keys
-    Keys(Keys),
-    /// NIP07 signer
-    #[cfg(all(feature = "nip07", target_arch = "wasm32"))]
-    NIP07(Nip07Signer),
-    /// NIP46 signer
-    #[cfg(feature = "nip46")]
-    NIP46(Nip46Signer),
+impl<T> IntoNostrSigner for T
+where
+    T: NostrSigner + Sized + 'static,
+{
+    fn into_nostr_signer(self) -> Arc<DynNostrSigner> {
+        Arc::new(self)
+    }
 }
 
-impl ClientSigner {
-    /// Get Client Signer Type
-    pub fn r#type(&self) -> ClientSignerType {
-        match self {
-            Self::Keys(..) => ClientSignerType::Keys,
-            #[cfg(all(feature = "nip07", target_arch = "wasm32"))]
-            Self::NIP07(..) => ClientSignerType::NIP07,
-            #[cfg(feature = "nip46")]
-            Self::NIP46(..) => ClientSignerType::NIP46,
-        }
+impl IntoNostrSigner for &Keys {
+    fn into_nostr_signer(self) -> Arc<DynNostrSigner> {
+        Arc::new(self.clone())
     }
 }
 
-impl From<Keys> for ClientSigner {
-    fn from(keys: Keys) -> Self {
-        Self::Keys(keys)
+/// Nostr signer
+///
+/// Implemented for [`Keys`], the NIP07 signer and the NIP46 signer.
+/// Implement it to plug a custom signer (i.e. hardware wallet or remote KMS) into the [`Client`](super::Client).
+/// The encryption methods are optional: by default they return [`Error::SignerMethodNotSupported`].
+///
+/// # Example
+/// ```rust,no_run
+/// use nostr_sdk::client::Error;
+/// use nostr_sdk::prelude::*;
+///
+/// #[derive(Debug)]
+/// struct MySigner {
+///     keys: Keys,
+/// }
+///
+/// #[async_trait]
+/// impl NostrSigner for MySigner {
+///     async fn get_public_key(&self) -> Result<XOnlyPublicKey, Error> {
+///         Ok(self.keys.public_key())
+///     }
+///
+///     async fn sign_event(&self, unsigned: UnsignedEvent) -> Result<Event, Error> {
+///         Ok(unsigned.sign(&self.keys)?)
+///     }
+/// }
+///
+/// let signer = MySigner { keys: Keys::generate() };
+/// let client = Client::new(signer);
+/// ```
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+pub trait NostrSigner: AsyncTraitDeps {
+    /// Get signer public key
+    async fn get_public_key(&self) -> Result<XOnlyPublicKey, Error>;
+
+    /// Sign an [`UnsignedEvent`]
+    async fn sign_event(&self, unsigned: UnsignedEvent) -> Result<Event, Error>;
+
+    /// NIP04 encrypt `content` for `public_key`
+    async fn nip04_encrypt(
+        &self,
+        _public_key: XOnlyPublicKey,
+        _content: String,
+    ) -> Result<String, Error> {
+        Err(Error::SignerMethodNotSupported(String::from(
+            "nip04_encrypt",
+        )))
+    }
+
+    /// NIP04 decrypt `encrypted_content` from `public_key`
+    async fn nip04_decrypt(
+        &self,
+        _public_key: XOnlyPublicKey,
+        _encrypted_content: String,
+    ) -> Result<String, Error> {
+        Err(Error::SignerMethodNotSupported(String::from(
+            "nip04_decrypt",
+        )))
+    }
+
+    /// NIP44 encrypt `content` for `public_key`
+    async fn nip44_encrypt(
+        &self,
+        _public_key: XOnlyPublicKey,
+        _content: String,
+    ) -> Result<String, Error> {
+        Err(Error::SignerMethodNotSupported(String::from(
+            "nip44_encrypt",
+        )))
+    }
+
+    /// NIP44 decrypt `payload` from `public_key`
+    async fn nip44_decrypt(
+        &self,
+        _public_key: XOnlyPublicKey,
+        _payload: String,
+    ) -> Result<String, Error> {
+        Err(Error::SignerMethodNotSupported(String::from(
+            "nip44_decrypt",
+        )))
+    }
+
+    /// Derive the NIP44 [`ConversationKey`] with `public_key`
+    ///
+    /// Used by the [`Client`](super::Client) to cache the conversation keys.
+    #[cfg(feature = "nip44")]
+    async fn nip44_conversation_key(
+        &self,
+        _public_key: XOnlyPublicKey,
+    ) -> Result<ConversationKey, Error> {
+        Err(Error::SignerMethodNotSupported(String::from(
+            "nip44_conversation_key",
+        )))
     }
 }
 
-impl From<&Keys> for ClientSigner {
-    fn from(keys: &Keys) -> Self {
-        Self::Keys(keys.clone())
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl NostrSigner for Keys {
+    async fn get_public_key(&self) -> Result<XOnlyPublicKey, Error> {
+        Ok(self.public_key())
+    }
+
+    async fn sign_event(&self, unsigned: UnsignedEvent) -> Result<Event, Error> {
+        Ok(unsigned.sign(self)?)
+    }
+
+    #[cfg(feature = "nip04")]
+    async fn nip04_encrypt(
+        &self,
+        public_key: XOnlyPublicKey,
+        content: String,
+    ) -> Result<String, Error> {
+        Ok(nip04::encrypt(&self.secret_key()?, &public_key, content)?)
+    }
+
+    #[cfg(feature = "nip04")]
+    async fn nip04_decrypt(
+        &self,
+        public_key: XOnlyPublicKey,
+        encrypted_content: String,
+    ) -> Result<String, Error> {
+        Ok(nip04::decrypt(
+            &self.secret_key()?,
+            &public_key,
+            encrypted_content,
+        )?)
+    }
+
+    #[cfg(feature = "nip44")]
+    async fn nip44_encrypt(
+        &self,
+        public_key: XOnlyPublicKey,
+        content: String,
+    ) -> Result<String, Error> {
+        Ok(nip44::encrypt(
+            &self.secret_key()?,
+            &public_key,
+            content,
+            Version::V2,
+        )?)
+    }
+
+    #[cfg(feature = "nip44")]
+    async fn nip44_decrypt(
+        &self,
+        public_key: XOnlyPublicKey,
+        payload: String,
+    ) -> Result<String, Error> {
+        Ok(nip44::decrypt(&self.secret_key()?, &public_key, payload)?)
+    }
+
+    #[cfg(feature = "nip44")]
+    async fn nip44_conversation_key(
+        &self,
+        public_key: XOnlyPublicKey,
+    ) -> Result<ConversationKey, Error> {
+        Ok(ConversationKey::derive(&self.secret_key()?, &public_key))
     }
 }
 
 #[cfg(all(feature = "nip07", target_arch = "wasm32"))]
-impl From<Nip07Signer> for ClientSigner {
-    fn from(nip07: Nip07Signer) -> Self {
-        Self::NIP07(nip07)
+#[async_trait(?Send)]
+impl NostrSigner for Nip07Signer {
+    async fn get_public_key(&self) -> Result<XOnlyPublicKey, Error> {
+        Ok(Nip07Signer::get_public_key(self).await?)
     }
-}
 
-#[cfg(feature = "nip46")]
-impl From<Nip46Signer> for ClientSigner {
-    fn from(nip46: Nip46Signer) -> Self {
-        Self::NIP46(nip46)
+    async fn sign_event(&self, unsigned: UnsignedEvent) -> Result<Event, Error> {
+        Ok(Nip07Signer::sign_event(self, unsigned).await?)
     }
-}
 
-#[cfg(feature = "nip46")]
-impl TryFrom<ClientSigner> for Nip46Signer {
-    type Error = Error;
-
-    fn try_from(signer: ClientSigner) -> Result<Self, Self::Error> {
-        if let ClientSigner::NIP46(nip46) = signer {
-            Ok(nip46)
-        } else {
-            Err(Error::WrongSigner {
-                expected: ClientSignerType::NIP46,
-                found: signer.r#type(),
-            })
-        }
+    async fn nip04_encrypt(
+        &self,
+        public_key: XOnlyPublicKey,
+        content: String,
+    ) -> Result<String, Error> {
+        Ok(Nip07Signer::nip04_encrypt(self, public_key, content).await?)
+    }
+
+    async fn nip04_decrypt(
+        &self,
+        public_key: XOnlyPublicKey,
+        encrypted_content: String,
+    ) -> Result<String, Error> {
+        Ok(Nip07Signer::nip04_decrypt(self, public_key, encrypted_content).await?)
     }
 }
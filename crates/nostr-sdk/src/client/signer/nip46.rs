@@ -6,32 +6,149 @@
 //!
 //! <https://github.com/nostr-protocol/nips/blob/master/46.md>
 
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 
-use async_utility::time;
+use async_utility::{thread, time};
+use nostr::key::SecretKey;
 use nostr::nips::nip04;
-use nostr::nips::nip46::{Message, NostrConnectMetadata, NostrConnectURI, Request, Response};
+use nostr::nips::nip46::{
+    self, BunkerURI, Message, NostrConnectMetadata, NostrConnectURI, Request, Response, CONNECT_ACK,
+};
 use nostr::secp256k1::XOnlyPublicKey;
+use nostr::serde_json::{self, Value};
 use nostr::{
-    serde_json, ClientMessage, EventBuilder, Filter, JsonUtil, Keys, Kind, SubscriptionId,
-    Timestamp, Url,
+    ClientMessage, Event, EventBuilder, Filter, JsonUtil, Keys, Kind, SubscriptionId, Timestamp,
+    UnsignedEvent, Url,
+};
+use nostr_database::async_trait;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::{oneshot, Mutex, OnceCell};
+
+use super::NostrSigner;
+use crate::client::Error;
+use crate::relay::pool::{Error as RelayPoolError, RelayPool};
+use crate::relay::{
+    InternalSubscriptionId, Relay, RelayOptions, RelayPoolNotification, RelayPoolOptions,
+    RelaySendOptions,
 };
-use tokio::sync::Mutex;
 
-#[cfg(feature = "blocking")]
-use crate::client::blocking::Client as BlockingClient;
-use crate::client::{Client, Error};
-use crate::relay::RelayPoolNotification;
-#[cfg(feature = "blocking")]
-use crate::RUNTIME;
+const NIP46_INTERNAL_ID: &str = "nip46";
+
+/// Requests sent to the signer and waiting for the response, by request id
+#[derive(Debug, Clone, Default)]
+struct PendingRequests {
+    inner: Arc<Mutex<HashMap<String, oneshot::Sender<Message>>>>,
+}
+
+impl PendingRequests {
+    /// Register a request (before sending it)
+    async fn insert(&self, id: String) -> oneshot::Receiver<Message> {
+        let (tx, rx) = oneshot::channel::<Message>();
+        let mut inner = self.inner.lock().await;
+        inner.insert(id, tx);
+        rx
+    }
+
+    async fn remove(&self, id: &str) {
+        let mut inner = self.inner.lock().await;
+        inner.remove(id);
+    }
+
+    /// Drop all the pending requests: the senders are dropped and the requests fail
+    async fn clear(&self) {
+        let mut inner = self.inner.lock().await;
+        inner.clear();
+    }
+
+    /// Dispatch a response to its pending request (if any)
+    async fn dispatch(&self, msg: Message) {
+        if let Message::Response { id, .. } = &msg {
+            let mut inner = self.inner.lock().await;
+            match inner.remove(id) {
+                Some(sender) => {
+                    if sender.send(msg).is_err() {
+                        tracing::warn!("NIP46 request dropped before receiving the response");
+                    }
+                }
+                None => tracing::debug!("Received response for unknown NIP46 request {id}"),
+            }
+        }
+    }
+
+    /// Wait for the response of a request
+    ///
+    /// The request is removed if the timeout expire.
+    async fn wait(
+        &self,
+        id: &str,
+        rx: oneshot::Receiver<Message>,
+        timeout: Option<Duration>,
+    ) -> Result<Message, Error> {
+        match time::timeout(timeout, rx).await {
+            Some(Ok(msg)) => Ok(msg),
+            Some(Err(_)) => Err(Error::Generic),
+            None => {
+                self.remove(id).await;
+                Err(Error::Timeout)
+            }
+        }
+    }
+}
+
+/// Relay pool dedicated to the signer (connected at the first request)
+///
+/// Shut down when the last [`Nip46Signer`] handle is dropped.
+#[derive(Default)]
+struct SignerPool {
+    cell: OnceCell<RelayPool>,
+}
+
+impl Drop for SignerPool {
+    fn drop(&mut self) {
+        if let Some(pool) = self.cell.take() {
+            tracing::debug!("Shutting down the NIP46 relay pool...");
+            thread::spawn(async move {
+                if let Err(e) = pool.shutdown().await {
+                    tracing::error!("Impossible to shutdown the NIP46 relay pool: {e}");
+                }
+            });
+        }
+    }
+}
 
 /// NIP46 Signer
-#[derive(Debug, Clone)]
+///
+/// The requests are sent through a dedicated connection to the signer relay, opened at the first request.
+///
+/// The app keys and the connection secret are redacted in [`Debug`](fmt::Debug).
+#[derive(Clone)]
 pub struct Nip46Signer {
     relay_url: Url,
     app_keys: Keys,
     signer_public_key: Arc<Mutex<Option<XOnlyPublicKey>>>,
+    pool: Arc<SignerPool>,
+    pending: PendingRequests,
+    listening: Arc<AtomicBool>,
+    secret: Option<String>,
+    connected: Arc<Mutex<bool>>,
+    timeout: Option<Duration>,
+}
+
+impl fmt::Debug for Nip46Signer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Nip46Signer")
+            .field("relay_url", &self.relay_url)
+            .field("app_keys", &self.app_keys)
+            .field("signer_public_key", &self.signer_public_key)
+            .field("listening", &self.listening)
+            .field("secret", &self.secret.as_ref().map(|_| "<redacted>"))
+            .field("timeout", &self.timeout)
+            .finish()
+    }
 }
 
 impl Nip46Signer {
@@ -41,9 +158,51 @@ impl Nip46Signer {
             relay_url,
             app_keys,
             signer_public_key: Arc::new(Mutex::new(signer_public_key)),
+            pool: Arc::new(SignerPool::default()),
+            pending: PendingRequests::default(),
+            listening: Arc::new(AtomicBool::new(false)),
+            secret: None,
+            connected: Arc::new(Mutex::new(true)),
+            timeout: Some(Duration::from_secs(180)),
         }
     }
 
+    /// Set the timeout of the requests signed by the [`NostrSigner`] methods (default: 180 secs)
+    pub fn timeout(self, timeout: Option<Duration>) -> Self {
+        Self { timeout, ..self }
+    }
+
+    /// New NIP46 remote signer from [`BunkerURI`]
+    ///
+    /// The `connect` request (with the secret, if any) is sent to the signer before the first request.
+    /// Only the first relay of the URI is used.
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use std::str::FromStr;
+    ///
+    /// use nostr_sdk::prelude::*;
+    ///
+    /// # #[tokio::main]
+    /// # async fn main() {
+    /// let uri = BunkerURI::from_str("bunker://b2d670de53b27691c0c3400225b65c35a26d06093bcc41f48ffc71e0907f9d4a?relay=wss://relay.nsec.app&secret=abcd").unwrap();
+    /// let signer = Nip46Signer::from_bunker_uri(uri, Keys::generate()).unwrap();
+    /// let client = Client::new(signer);
+    /// # }
+    /// ```
+    pub fn from_bunker_uri(uri: BunkerURI, app_keys: Keys) -> Result<Self, Error> {
+        let relay_url: Url = uri
+            .relays
+            .into_iter()
+            .next()
+            .ok_or(nip46::Error::InvalidURI)?;
+        Ok(Self {
+            secret: uri.secret,
+            connected: Arc::new(Mutex::new(false)),
+            ..Self::new(relay_url, app_keys, Some(uri.signer_public_key))
+        })
+    }
+
     /// Get signer relay [`Url`]
     pub fn relay_url(&self) -> Url {
         self.relay_url.clone()
@@ -60,16 +219,28 @@ impl Nip46Signer {
         *pubkey = Some(public_key);
     }
 
+    /// Get the relay pool connected to the signer relay (connect at the first call)
+    async fn pool(&self) -> Result<&RelayPool, Error> {
+        self.pool
+            .cell
+            .get_or_try_init(|| async {
+                let pool = RelayPool::new(RelayPoolOptions::default());
+                pool.add_relay(self.relay_url(), RelayOptions::default())
+                    .await?;
+                pool.connect(self.timeout).await;
+                Ok::<RelayPool, Error>(pool)
+            })
+            .await
+    }
+
     /// Compose Nostr Connect URI
     pub fn nostr_connect_uri(&self, metadata: NostrConnectMetadata) -> NostrConnectURI {
         NostrConnectURI::with_metadata(self.app_keys.public_key(), self.relay_url(), metadata)
     }
-}
 
-impl Client {
     /// Request the [`XOnlyPublicKey`] of the signer (sent with `Connect` request)
     ///
-    /// Call not required if you already added in `Client::with_remote_signer`.
+    /// Call not required if you already added in [`Nip46Signer::new`].
     ///
     /// # Example
     /// ```rust,no_run
@@ -82,13 +253,14 @@ impl Client {
     ///     let app_keys = Keys::generate();
     ///     let relay_url = Url::parse("wss://relay.example.com").unwrap();
     ///     let signer = Nip46Signer::new(relay_url, app_keys, None);
-    ///     let client = Client::new(signer);
     ///
     ///     // Signer public key MUST be requested in this case
-    ///     client
+    ///     signer
     ///         .req_signer_public_key(Some(Duration::from_secs(180)))
     ///         .await
     ///         .unwrap();
+    ///
+    ///     let client = Client::new(signer);
     /// }
     /// ```
     ///
@@ -108,16 +280,15 @@ impl Client {
     ///     .unwrap();
     ///     let signer = Nip46Signer::new(relay_url, app_keys, Some(signer_public_key));
     ///
-    ///     // Signer public key request isn't needed since we already added in client constructor
+    ///     // Signer public key request isn't needed since we already added in signer constructor
     ///     let _client = Client::new(signer);
     /// }
     /// ```
     pub async fn req_signer_public_key(&self, timeout: Option<Duration>) -> Result<(), Error> {
-        let signer: Nip46Signer = self.signer().await?.try_into()?;
-
-        if signer.signer_public_key().await.is_none() {
-            let public_key = signer.app_keys.public_key();
-            let secret_key = signer.app_keys.secret_key()?;
+        if self.signer_public_key().await.is_none() {
+            let public_key = self.app_keys.public_key();
+            let secret_key = self.app_keys.secret_key()?;
+            let pool: &RelayPool = self.pool().await?;
 
             let id = SubscriptionId::generate();
             let filter = Filter::new()
@@ -126,13 +297,14 @@ impl Client {
                 .since(Timestamp::now());
 
             // Subscribe
-            self.send_msg_to(
-                signer.relay_url(),
+            let mut notifications = pool.notifications();
+            pool.send_msg_to(
+                self.relay_url(),
                 ClientMessage::req(id.clone(), vec![filter]),
+                None,
             )
             .await?;
 
-            let mut notifications = self.notifications();
             time::timeout(timeout, async {
                 while let Ok(notification) = notifications.recv().await {
                     if let RelayPoolNotification::Event { event, .. } = notification {
@@ -140,8 +312,8 @@ impl Client {
                             let msg: String =
                                 nip04::decrypt(&secret_key, event.author_ref(), event.content())?;
                             let msg = Message::from_json(msg)?;
-                            if let Ok(Request::Connect(pk)) = msg.to_request() {
-                                signer.set_signer_public_key(pk).await;
+                            if let Ok(Request::Connect { public_key, .. }) = msg.to_request() {
+                                self.set_signer_public_key(public_key).await;
                                 break;
                             }
                         }
@@ -154,145 +326,355 @@ impl Client {
             .ok_or(Error::Timeout)??;
 
             // Unsubscribe
-            self.send_msg_to(signer.relay_url(), ClientMessage::close(id))
+            pool.send_msg_to(self.relay_url(), ClientMessage::close(id), None)
                 .await?;
         }
 
         Ok(())
     }
 
+    /// Subscribe to the signer relay (if not already subscribed) and spawn the responses listener
+    ///
+    /// A single subscription is kept for the whole life of the signer:
+    /// the responses are dispatched to the pending requests by their id.
+    async fn subscribe(&self) -> Result<(), Error> {
+        if self
+            .listening
+            .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
+            .is_err()
+        {
+            return Ok(());
+        }
+
+        let public_key = self.app_keys.public_key();
+        let secret_key = self.app_keys.secret_key()?;
+
+        let filter = Filter::new()
+            .pubkey(public_key)
+            .kind(Kind::NostrConnect)
+            .since(Timestamp::now());
+
+        let res = async {
+            let pool: &RelayPool = self.pool().await?;
+
+            // Get notification listener before subscribe, to not miss any response
+            let notifications = pool.notifications();
+
+            // Subscribe
+            let relay: Relay = pool.relay(self.relay_url()).await?;
+            relay
+                .subscribe_with_internal_id(
+                    InternalSubscriptionId::from(NIP46_INTERNAL_ID),
+                    vec![filter],
+                    self.timeout,
+                )
+                .await
+                .map_err(RelayPoolError::from)?;
+            Ok::<_, Error>(notifications)
+        }
+        .await;
+
+        let mut notifications = match res {
+            Ok(notifications) => notifications,
+            Err(e) => {
+                self.listening.store(false, Ordering::SeqCst);
+                return Err(e);
+            }
+        };
+
+        // Not hold the signer (and so the pool) in the listener: the pool is shut down
+        // when the last signer handle is dropped and the listener exit.
+        let relay_url: Url = self.relay_url();
+        let pending: PendingRequests = self.pending.clone();
+        let listening: Arc<AtomicBool> = self.listening.clone();
+        thread::spawn(async move {
+            tracing::debug!("NIP46 listener started for {relay_url}");
+
+            loop {
+                match notifications.recv().await {
+                    Ok(RelayPoolNotification::Event { event, .. }) => {
+                        if event.kind() == Kind::NostrConnect {
+                            if let Err(e) = handle_event(&pending, &secret_key, &event).await {
+                                tracing::error!("Impossible to handle NIP46 event: {e}");
+                            }
+                        }
+                    }
+                    Ok(RelayPoolNotification::Stop) | Ok(RelayPoolNotification::Shutdown) => break,
+                    Ok(_) => (),
+                    Err(RecvError::Lagged(num)) => {
+                        tracing::warn!("NIP46 listener lagged: {num} notifications skipped")
+                    }
+                    Err(RecvError::Closed) => break,
+                }
+            }
+
+            // Drop pending requests: the senders will be dropped and the requests will fail
+            pending.clear().await;
+            listening.store(false, Ordering::SeqCst);
+
+            tracing::debug!("Exited from NIP46 listener of {relay_url}");
+        });
+
+        Ok(())
+    }
+
+    /// Send the `connect` request to a signer composed from a [`BunkerURI`] (if not already done)
+    async fn connect_to_signer(&self, timeout: Option<Duration>) -> Result<(), Error> {
+        let mut connected = self.connected.lock().await;
+        if !*connected {
+            let public_key = self
+                .signer_public_key()
+                .await
+                .ok_or(Error::SignerPublicKeyNotFound)?;
+            let req = Request::Connect {
+                public_key,
+                secret: self.secret.clone(),
+            };
+            match self.internal_send_req_to_signer(req, timeout).await? {
+                Response::Connect => *connected = true,
+                _ => return Err(Error::ResponseNotMatchRequest),
+            }
+        }
+        Ok(())
+    }
+
     /// Send NIP46 [`Request`] to signer
+    ///
+    /// Requests can be sent concurrently: all of them share the same subscription
+    /// and each response is matched to its request by id.
+    ///
+    /// If the signer was composed from a [`BunkerURI`], the `connect` request is sent first.
     pub async fn send_req_to_signer(
         &self,
         req: Request,
         timeout: Option<Duration>,
     ) -> Result<Response, Error> {
-        let signer: Nip46Signer = self.signer().await?.try_into()?;
+        if let Request::Connect { .. } = req {
+            return self.internal_send_req_to_signer(req, timeout).await;
+        }
+
+        self.connect_to_signer(timeout).await?;
+        self.internal_send_req_to_signer(req, timeout).await
+    }
 
-        let signer_pubkey = signer
+    async fn internal_send_req_to_signer(
+        &self,
+        req: Request,
+        timeout: Option<Duration>,
+    ) -> Result<Response, Error> {
+        let signer_pubkey = self
             .signer_public_key()
             .await
             .ok_or(Error::SignerPublicKeyNotFound)?;
 
+        // Make sure that the subscription to the signer relay is active
+        self.subscribe().await?;
+
         let msg = Message::request(req.clone());
         let req_id = msg.id();
 
-        let public_key = signer.app_keys.public_key();
-        let secret_key = signer.app_keys.secret_key()?;
-
         // Build request
-        let event = EventBuilder::nostr_connect(&signer.app_keys, signer_pubkey, msg)?
-            .to_event(&signer.app_keys)?;
+        let event = EventBuilder::nostr_connect(&self.app_keys, signer_pubkey, msg)?
+            .to_event(&self.app_keys)?;
+
+        // Register the request before sending it
+        let rx = self.pending.insert(req_id.clone()).await;
 
         // Send request to signer
-        self.send_event_to(signer.relay_url(), event).await?;
+        let opts = RelaySendOptions::new().timeout(self.timeout);
+        let res: Result<(), Error> = match self.pool().await {
+            Ok(pool) => match pool.send_event_to(self.relay_url(), event, opts).await {
+                Ok(output) if output.is_success() => Ok(()),
+                Ok(output) => Err(Error::RelayPool(RelayPoolError::EventNotPublished(
+                    output.val,
+                ))),
+                Err(e) => Err(e.into()),
+            },
+            Err(e) => Err(e),
+        };
+        if let Err(e) = res {
+            self.pending.remove(&req_id).await;
+            return Err(e);
+        }
 
-        let sub_id = SubscriptionId::generate();
-        let filter = Filter::new()
-            .pubkey(public_key)
-            .kind(Kind::NostrConnect)
-            .since(Timestamp::now());
+        match self.pending.wait(&req_id, rx, timeout).await? {
+            Message::Response { result, error, .. } => {
+                tracing::debug!("Received response for NIP46 request {req_id}");
 
-        // Subscribe
-        self.send_msg_to(
-            signer.relay_url(),
-            ClientMessage::req(sub_id.clone(), vec![filter]),
-        )
-        .await?;
-
-        let mut notifications = self.notifications();
-        let future = async {
-            while let Ok(notification) = notifications.recv().await {
-                if let RelayPoolNotification::Event { event, .. } = notification {
-                    if event.kind() == Kind::NostrConnect {
-                        let msg = nip04::decrypt(&secret_key, event.author_ref(), event.content())?;
-                        let msg = Message::from_json(msg)?;
-
-                        tracing::debug!("New message received: {msg:?}");
-
-                        if let Message::Response { id, result, error } = &msg {
-                            if &req_id == id {
-                                if let Some(result) = result {
-                                    let res = match req {
-                                        Request::Describe => Response::Describe(
-                                            serde_json::from_value(result.to_owned())?,
-                                        ),
-                                        Request::GetPublicKey => {
-                                            let pubkey = serde_json::from_value(result.to_owned())?;
-                                            Response::GetPublicKey(pubkey)
-                                        }
-                                        Request::SignEvent(_) => {
-                                            let sig = serde_json::from_value(result.to_owned())?;
-                                            Response::SignEvent(sig)
-                                        }
-                                        Request::Delegate { .. } => Response::Delegate(
-                                            serde_json::from_value(result.to_owned())?,
-                                        ),
-                                        Request::Nip04Encrypt { .. } => Response::Nip04Encrypt(
-                                            serde_json::from_value(result.to_owned())?,
-                                        ),
-                                        Request::Nip04Decrypt { .. } => Response::Nip04Decrypt(
-                                            serde_json::from_value(result.to_owned())?,
-                                        ),
-                                        Request::SignSchnorr { .. } => Response::SignSchnorr(
-                                            serde_json::from_value(result.to_owned())?,
-                                        ),
-                                        _ => break,
-                                    };
-
-                                    // Unsubscribe
-                                    self.send_msg_to(
-                                        signer.relay_url(),
-                                        ClientMessage::close(sub_id.clone()),
-                                    )
-                                    .await?;
-                                    return Ok(res);
-                                }
-
-                                if let Some(error) = error {
-                                    // Unsubscribe
-                                    self.send_msg_to(
-                                        signer.relay_url(),
-                                        ClientMessage::close(sub_id.clone()),
-                                    )
-                                    .await?;
-                                    return Err(Error::Response(error.to_owned()));
-                                }
+                if let Some(result) = result {
+                    return parse_response(req, result);
+                }
 
-                                break;
-                            }
-                        }
-                    }
+                if let Some(error) = error {
+                    return Err(Error::Response(error));
                 }
+
+                Err(Error::Generic)
             }
+            Message::Request { .. } => Err(Error::Generic),
+        }
+    }
+}
 
-            Err(Error::Generic)
-        };
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+impl NostrSigner for Nip46Signer {
+    async fn get_public_key(&self) -> Result<XOnlyPublicKey, Error> {
+        self.signer_public_key()
+            .await
+            .ok_or(Error::SignerPublicKeyNotFound)
+    }
 
-        let res: Result<Response, Error> =
-            time::timeout(timeout, future).await.ok_or(Error::Timeout)?;
+    async fn sign_event(&self, unsigned: UnsignedEvent) -> Result<Event, Error> {
+        match self
+            .send_req_to_signer(Request::SignEvent(unsigned), self.timeout)
+            .await?
+        {
+            Response::SignEvent(event) => Ok(event),
+            _ => Err(Error::ResponseNotMatchRequest),
+        }
+    }
 
-        // Unsubscribe
-        self.send_msg_to(signer.relay_url(), ClientMessage::close(sub_id))
-            .await?;
+    async fn nip04_encrypt(
+        &self,
+        public_key: XOnlyPublicKey,
+        content: String,
+    ) -> Result<String, Error> {
+        let req = Request::Nip04Encrypt {
+            public_key,
+            text: content,
+        };
+        match self.send_req_to_signer(req, self.timeout).await? {
+            Response::Nip04Encrypt(content) => Ok(content),
+            _ => Err(Error::ResponseNotMatchRequest),
+        }
+    }
 
-        res
+    async fn nip04_decrypt(
+        &self,
+        public_key: XOnlyPublicKey,
+        encrypted_content: String,
+    ) -> Result<String, Error> {
+        let req = Request::Nip04Decrypt {
+            public_key,
+            text: encrypted_content,
+        };
+        match self.send_req_to_signer(req, self.timeout).await? {
+            Response::Nip04Decrypt(content) => Ok(content),
+            _ => Err(Error::ResponseNotMatchRequest),
+        }
     }
 }
 
-#[cfg(feature = "blocking")]
-impl BlockingClient {
-    #[allow(missing_docs)]
-    pub fn req_signer_public_key(&self, timeout: Option<Duration>) -> Result<(), Error> {
-        RUNTIME.block_on(async { self.client.req_signer_public_key(timeout).await })
+/// Decrypt NIP46 event and dispatch the response to the pending request (if any)
+async fn handle_event(
+    pending: &PendingRequests,
+    secret_key: &SecretKey,
+    event: &Event,
+) -> Result<(), Error> {
+    let msg: String = nip04::decrypt(secret_key, event.author_ref(), event.content())?;
+    let msg = Message::from_json(msg)?;
+
+    tracing::debug!("New message received: {msg:?}");
+
+    pending.dispatch(msg).await;
+
+    Ok(())
+}
+
+fn parse_response(req: Request, result: Value) -> Result<Response, Error> {
+    Ok(match req {
+        Request::Connect { secret, .. } => {
+            let result: String = serde_json::from_value(result)?;
+            // Some signers reply with the secret instead of `ack`
+            if result == CONNECT_ACK || Some(&result) == secret.as_ref() {
+                Response::Connect
+            } else {
+                return Err(Error::Response(result));
+            }
+        }
+        Request::Describe => Response::Describe(serde_json::from_value(result)?),
+        Request::GetPublicKey => Response::GetPublicKey(serde_json::from_value(result)?),
+        Request::SignEvent(_) | Request::SignEventTemplate(_) => {
+            Response::SignEvent(serde_json::from_value(result)?)
+        }
+        Request::Delegate { .. } => Response::Delegate(serde_json::from_value(result)?),
+        Request::Nip04Encrypt { .. } => Response::Nip04Encrypt(serde_json::from_value(result)?),
+        Request::Nip04Decrypt { .. } => Response::Nip04Decrypt(serde_json::from_value(result)?),
+        Request::SignSchnorr { .. } => Response::SignSchnorr(serde_json::from_value(result)?),
+        _ => return Err(Error::Generic),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(id: &str) -> Message {
+        Message::Response {
+            id: id.to_string(),
+            result: Some(Value::String(String::from("ack"))),
+            error: None,
+        }
     }
 
-    #[allow(missing_docs)]
-    pub fn send_req_to_signer(
-        &self,
-        req: Request,
-        timeout: Option<Duration>,
-    ) -> Result<Response, Error> {
-        RUNTIME.block_on(async { self.client.send_req_to_signer(req, timeout).await })
+    #[tokio::test]
+    async fn test_pending_requests_matching() {
+        let pending = PendingRequests::default();
+        let rx_a = pending.insert(String::from("a")).await;
+        let rx_b = pending.insert(String::from("b")).await;
+
+        // Unknown request and requests are ignored
+        pending.dispatch(response("c")).await;
+        pending
+            .dispatch(Message::Request {
+                id: String::from("a"),
+                method: String::from("ping"),
+                params: Vec::new(),
+            })
+            .await;
+        assert_eq!(pending.inner.lock().await.len(), 2);
+
+        // Responses are matched by id, also out of order
+        pending.dispatch(response("b")).await;
+        pending.dispatch(response("a")).await;
+        let timeout = Some(Duration::from_secs(1));
+        assert_eq!(
+            pending.wait("b", rx_b, timeout).await.unwrap(),
+            response("b")
+        );
+        assert_eq!(
+            pending.wait("a", rx_a, timeout).await.unwrap(),
+            response("a")
+        );
+        assert!(pending.inner.lock().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_pending_requests_timeout() {
+        let pending = PendingRequests::default();
+        let rx = pending.insert(String::from("a")).await;
+
+        let timeout = Some(Duration::from_millis(10));
+        assert!(matches!(
+            pending.wait("a", rx, timeout).await.unwrap_err(),
+            Error::Timeout
+        ));
+        assert!(pending.inner.lock().await.is_empty());
+
+        // Late response
+        pending.dispatch(response("a")).await;
+        assert!(pending.inner.lock().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_pending_requests_clear() {
+        let pending = PendingRequests::default();
+        let rx = pending.insert(String::from("a")).await;
+        pending.clear().await;
+        assert!(matches!(
+            pending.wait("a", rx, None).await.unwrap_err(),
+            Error::Generic
+        ));
     }
 }
@@ -19,7 +19,7 @@
 compile_error!("`blocking` feature can't be enabled for WASM targets");
 
 pub use nostr::{self, *};
-pub use nostr_database::{self as database, NostrDatabase, NostrDatabaseExt, Profile};
+pub use nostr_database::{self as database, NostrDatabase, NostrDatabaseExt, Profile, RelayInfo};
 #[cfg(all(target_arch = "wasm32", feature = "indexeddb"))]
 pub use nostr_indexeddb::{IndexedDBError, WebDatabase};
 #[cfg(feature = "sqlite")]
@@ -30,17 +30,30 @@ use once_cell::sync::Lazy;
 use tokio::runtime::Runtime;
 
 pub mod client;
+#[cfg(feature = "nip47")]
+pub mod nwc;
 pub mod prelude;
 pub mod relay;
 pub mod util;
 
 #[cfg(feature = "blocking")]
 pub use self::client::blocking;
-pub use self::client::{Client, ClientBuilder, ClientSigner, Options};
+pub use self::client::{
+    Client, ClientBuilder, DuplicateGuard, IntoNostrSigner, NostrSigner, Options,
+};
+#[cfg(feature = "nip47")]
+pub use self::nwc::service::{WalletService, WalletServiceHandler};
+#[cfg(feature = "nip47")]
+pub use self::nwc::{NostrWalletConnect, NostrWalletConnectOptions};
+#[cfg(not(target_arch = "wasm32"))]
+pub use self::relay::RelayProxy;
 pub use self::relay::{
-    ActiveSubscription, FilterOptions, InternalSubscriptionId, NegentropyOptions, Relay,
-    RelayConnectionStats, RelayOptions, RelayPoolNotification, RelayPoolOptions, RelaySendOptions,
-    RelayStatus,
+    ActiveSubscription, CountReport, EventsOrder, FilterOptions, GetEventsOptions,
+    InternalSubscriptionId, LimitViolation, NegentropyOptions, Output, OverflowPolicy, RateLimits,
+    RebroadcastOptions, ReconnectPolicy, Relay, RelayCapability, RelayConnectionStats,
+    RelayHookEvent, RelayOptions, RelayPoolNotification, RelayPoolOptions, RelayPoolReport,
+    RelayPoolSnapshot, RelayReport, RelaySendOptions, RelaySnapshot, RelayStatus, ReqDistribution,
+    ReqHandle, ReqStatus, SubscriptionHandle, SubscriptionLimits,
 };
 
 #[cfg(feature = "blocking")]
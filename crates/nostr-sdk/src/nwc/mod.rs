@@ -0,0 +1,403 @@
+// Copyright (c) 2022-2023 Yuki Kishimoto
+// Copyright (c) 2023-2024 Rust Nostr Developers
+// Distributed under the MIT software license
+
+//! Nostr Wallet Connect client (NIP47)
+//!
+//! <https://github.com/nostr-protocol/nips/blob/master/47.md>
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_utility::{thread, time};
+use nostr::nips::nip04;
+use nostr::nips::nip47::{
+    self, GetBalanceResponseResult, ListTransactionsRequestParams, LookupInvoiceRequestParams,
+    LookupInvoiceResponseResult, MakeInvoiceRequestParams, MakeInvoiceResponseResult, Method,
+    NIP47Error, NostrWalletConnectURI, PayInvoiceRequestParams, PayInvoiceResponseResult, Request,
+    RequestParams, Response, ResponseResult, TransactionResponseResult,
+};
+use nostr::{Event, EventBuilder, EventId, Filter, JsonUtil, Keys, Kind, Tag, Timestamp};
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::{oneshot, Mutex, RwLock};
+
+use crate::client::{self, Client};
+use crate::relay::RelayPoolNotification;
+
+pub mod service;
+
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Nostr Wallet Connect error
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// Client error
+    #[error(transparent)]
+    Client(#[from] client::Error),
+    /// Keys error
+    #[error(transparent)]
+    Keys(#[from] nostr::key::Error),
+    /// Event builder error
+    #[error(transparent)]
+    EventBuilder(#[from] nostr::event::builder::Error),
+    /// NIP04 error
+    #[error(transparent)]
+    NIP04(#[from] nip04::Error),
+    /// NIP47 error
+    #[error(transparent)]
+    NIP47(#[from] nip47::Error),
+    /// Error returned by the wallet service
+    #[error("wallet error: {0}")]
+    Wallet(NIP47Error),
+    /// The result don't match the request method
+    #[error("unexpected result")]
+    UnexpectedResult,
+    /// Method not supported by the wallet service
+    #[error("method not supported by the wallet: {0}")]
+    UnsupportedMethod(Method),
+    /// Wallet service info event not found
+    #[error("wallet service info not found")]
+    InfoNotFound,
+    /// Response not received in time
+    #[error("response not received in time")]
+    Timeout,
+    /// Client shutdown before receiving the response
+    #[error("client shutdown before receiving the response")]
+    Shutdown,
+}
+
+/// Nostr Wallet Connect options
+#[derive(Debug, Clone, Copy)]
+pub struct NostrWalletConnectOptions {
+    timeout: Duration,
+}
+
+impl Default for NostrWalletConnectOptions {
+    fn default() -> Self {
+        Self {
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+}
+
+impl NostrWalletConnectOptions {
+    /// New default options
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the timeout of the requests (default: 60 secs)
+    pub fn timeout(self, timeout: Duration) -> Self {
+        Self { timeout }
+    }
+}
+
+/// Nostr Wallet Connect client
+///
+/// Send the requests to the wallet service of the [`NostrWalletConnectURI`] and wait for the responses.
+/// The connection to the relay is established on the first request.
+///
+/// # Example
+/// ```rust,no_run
+/// use std::str::FromStr;
+///
+/// use nostr_sdk::prelude::*;
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let uri = NostrWalletConnectURI::from_str("nostr+walletconnect://...").unwrap();
+/// let nwc = NostrWalletConnect::new(uri);
+/// let balance: u64 = nwc.get_balance().await.unwrap();
+/// let preimage: String = nwc.pay_invoice("lnbc...").await.unwrap();
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct NostrWalletConnect {
+    uri: NostrWalletConnectURI,
+    keys: Keys,
+    client: Client,
+    opts: NostrWalletConnectOptions,
+    pending: Arc<Mutex<HashMap<EventId, oneshot::Sender<Response>>>>,
+    bootstrapped: Arc<Mutex<bool>>,
+    methods: Arc<RwLock<Option<Vec<Method>>>>,
+}
+
+impl NostrWalletConnect {
+    /// New Nostr Wallet Connect client
+    pub fn new(uri: NostrWalletConnectURI) -> Self {
+        Self::with_opts(uri, NostrWalletConnectOptions::default())
+    }
+
+    /// New Nostr Wallet Connect client with [`NostrWalletConnectOptions`]
+    pub fn with_opts(uri: NostrWalletConnectURI, opts: NostrWalletConnectOptions) -> Self {
+        let keys = Keys::new(uri.secret);
+        Self {
+            client: Client::new(&keys),
+            uri,
+            keys,
+            opts,
+            pending: Arc::new(Mutex::new(HashMap::new())),
+            bootstrapped: Arc::new(Mutex::new(false)),
+            methods: Arc::new(RwLock::new(None)),
+        }
+    }
+
+    /// Get [`NostrWalletConnectURI`]
+    pub fn uri(&self) -> &NostrWalletConnectURI {
+        &self.uri
+    }
+
+    /// Get the methods supported by the wallet service
+    ///
+    /// Fetch the wallet service info event (kind `13194`). Once fetched, the requests
+    /// of the unsupported methods fail with [`Error::UnsupportedMethod`] without being sent.
+    pub async fn supported_methods(&self) -> Result<Vec<Method>, Error> {
+        self.bootstrap().await?;
+
+        let filter = Filter::new()
+            .author(self.uri.public_key)
+            .kind(Kind::WalletConnectInfo)
+            .limit(1);
+        let events: Vec<Event> = self
+            .client
+            .get_events_of(vec![filter], Some(self.opts.timeout))
+            .await?;
+        let event: Event = events
+            .into_iter()
+            .max_by_key(|e| e.created_at())
+            .ok_or(Error::InfoNotFound)?;
+
+        let supported: Vec<Method> = nip47::supported_methods(event.content());
+        let mut methods = self.methods.write().await;
+        *methods = Some(supported.clone());
+        Ok(supported)
+    }
+
+    /// Pay a bolt11 invoice
+    ///
+    /// Return the preimage.
+    pub async fn pay_invoice<S>(&self, invoice: S) -> Result<String, Error>
+    where
+        S: Into<String>,
+    {
+        let params = PayInvoiceRequestParams {
+            invoice: invoice.into(),
+        };
+        match self
+            .send_request(Request {
+                method: Method::PayInvoice,
+                params: RequestParams::PayInvoice(params),
+            })
+            .await?
+        {
+            ResponseResult::PayInvoice(PayInvoiceResponseResult { preimage }) => Ok(preimage),
+            _ => Err(Error::UnexpectedResult),
+        }
+    }
+
+    /// Create a bolt11 invoice
+    pub async fn make_invoice(
+        &self,
+        params: MakeInvoiceRequestParams,
+    ) -> Result<MakeInvoiceResponseResult, Error> {
+        match self
+            .send_request(Request {
+                method: Method::MakeInvoice,
+                params: RequestParams::MakeInvoice(params),
+            })
+            .await?
+        {
+            ResponseResult::MakeInvoice(result) => Ok(result),
+            _ => Err(Error::UnexpectedResult),
+        }
+    }
+
+    /// Lookup an invoice by payment hash or bolt11
+    pub async fn lookup_invoice(
+        &self,
+        params: LookupInvoiceRequestParams,
+    ) -> Result<LookupInvoiceResponseResult, Error> {
+        match self
+            .send_request(Request {
+                method: Method::LookupInvoice,
+                params: RequestParams::LookupInvoice(params),
+            })
+            .await?
+        {
+            ResponseResult::LookupInvoice(result) => Ok(result),
+            _ => Err(Error::UnexpectedResult),
+        }
+    }
+
+    /// Get the wallet balance
+    pub async fn get_balance(&self) -> Result<u64, Error> {
+        match self
+            .send_request(Request {
+                method: Method::GetBalance,
+                params: RequestParams::GetBalance,
+            })
+            .await?
+        {
+            ResponseResult::GetBalance(GetBalanceResponseResult { balance, .. }) => Ok(balance),
+            _ => Err(Error::UnexpectedResult),
+        }
+    }
+
+    /// List the incoming and outgoing transactions
+    pub async fn list_transactions(
+        &self,
+        params: ListTransactionsRequestParams,
+    ) -> Result<Vec<TransactionResponseResult>, Error> {
+        match self
+            .send_request(Request {
+                method: Method::ListTransactions,
+                params: RequestParams::ListTransactions(params),
+            })
+            .await?
+        {
+            ResponseResult::ListTransactions(result) => Ok(result.transactions),
+            _ => Err(Error::UnexpectedResult),
+        }
+    }
+
+    /// Send a NIP47 [`Request`] and wait for the [`ResponseResult`]
+    ///
+    /// Requests can be sent concurrently: each response is matched to its request by event ID.
+    pub async fn send_request(&self, req: Request) -> Result<ResponseResult, Error> {
+        let method: Method = req.method;
+        if let Some(methods) = self.methods.read().await.as_ref() {
+            if !methods.contains(&method) {
+                return Err(Error::UnsupportedMethod(method));
+            }
+        }
+
+        self.bootstrap().await?;
+
+        let content: String =
+            nip04::encrypt(&self.uri.secret, &self.uri.public_key, req.as_json())?;
+        let event: Event = EventBuilder::new(
+            Kind::WalletConnectRequest,
+            content,
+            [Tag::public_key(self.uri.public_key)],
+        )
+        .to_event(&self.keys)?;
+        let id: EventId = event.id();
+
+        // Register the request before sending it
+        let (tx, rx) = oneshot::channel::<Response>();
+        self.pending.lock().await.insert(id, tx);
+
+        if let Err(e) = self.client.send_event(event).await {
+            self.pending.lock().await.remove(&id);
+            return Err(e.into());
+        }
+
+        match time::timeout(Some(self.opts.timeout), rx).await {
+            Some(Ok(res)) => {
+                tracing::debug!("Received response for NWC request {id}");
+
+                if let Some(error) = res.error {
+                    return Err(Error::Wallet(error));
+                }
+
+                match res.result {
+                    Some(result) if res.result_type == method => Ok(result),
+                    _ => Err(Error::UnexpectedResult),
+                }
+            }
+            Some(Err(_)) => Err(Error::Shutdown),
+            None => {
+                self.pending.lock().await.remove(&id);
+                Err(Error::Timeout)
+            }
+        }
+    }
+
+    /// Shutdown the client
+    pub async fn shutdown(self) -> Result<(), Error> {
+        Ok(self.client.shutdown().await?)
+    }
+
+    /// Connect to the relay, subscribe to the responses and spawn the listener (if not already done)
+    async fn bootstrap(&self) -> Result<(), Error> {
+        let mut bootstrapped = self.bootstrapped.lock().await;
+        if *bootstrapped {
+            return Ok(());
+        }
+
+        self.client.add_relay(self.uri.relay_url.clone()).await?;
+        self.client.connect().await;
+
+        // Get notification listener before subscribe, to not miss any response
+        let mut notifications = self.client.notifications();
+
+        let filter = Filter::new()
+            .author(self.uri.public_key)
+            .pubkey(self.keys.public_key())
+            .kind(Kind::WalletConnectResponse)
+            .since(Timestamp::now());
+        self.client.subscribe(vec![filter]).await;
+
+        let nwc = self.clone();
+        thread::spawn(async move {
+            tracing::debug!("NWC listener started for {}", nwc.uri.relay_url);
+
+            loop {
+                match notifications.recv().await {
+                    Ok(RelayPoolNotification::Event { event, .. }) => {
+                        if event.kind() == Kind::WalletConnectResponse
+                            && event.author() == nwc.uri.public_key
+                        {
+                            if let Err(e) = nwc.handle_response(&event).await {
+                                tracing::error!("Impossible to handle NWC response: {e}");
+                            }
+                        }
+                    }
+                    Ok(RelayPoolNotification::Stop) | Ok(RelayPoolNotification::Shutdown) => break,
+                    Ok(_) => (),
+                    Err(RecvError::Lagged(num)) => {
+                        tracing::warn!("NWC listener lagged: {num} notifications skipped")
+                    }
+                    Err(RecvError::Closed) => break,
+                }
+            }
+
+            // Drop pending requests: the senders will be dropped and the requests will fail
+            nwc.pending.lock().await.clear();
+            *nwc.bootstrapped.lock().await = false;
+
+            tracing::debug!("Exited from NWC listener of {}", nwc.uri.relay_url);
+        });
+
+        *bootstrapped = true;
+
+        Ok(())
+    }
+
+    /// Decrypt the response and dispatch it to the pending request (if any)
+    async fn handle_response(&self, event: &Event) -> Result<(), Error> {
+        let id: EventId = match event.event_ids().next() {
+            Some(id) => *id,
+            None => {
+                tracing::warn!("NWC response {} without request ID", event.id());
+                return Ok(());
+            }
+        };
+
+        let mut pending = self.pending.lock().await;
+        match pending.remove(&id) {
+            Some(sender) => {
+                let msg: String =
+                    nip04::decrypt(&self.uri.secret, event.author_ref(), event.content())?;
+                let res = Response::from_json(msg)?;
+                if sender.send(res).is_err() {
+                    tracing::warn!("NWC request dropped before receiving the response");
+                }
+            }
+            None => tracing::debug!("Received response for unknown NWC request {id}"),
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,381 @@
+// Copyright (c) 2022-2023 Yuki Kishimoto
+// Copyright (c) 2023-2024 Rust Nostr Developers
+// Distributed under the MIT software license
+
+//! Nostr Wallet Connect service (NIP47)
+//!
+//! <https://github.com/nostr-protocol/nips/blob/master/47.md>
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use async_utility::thread;
+use nostr::nips::nip04;
+use nostr::nips::nip47::{
+    ErrorCode, GetBalanceResponseResult, ListInvoicesRequestParams, ListPaymentResponseResult,
+    ListPaymentsRequestParams, ListTransactionsRequestParams, ListTransactionsResponseResult,
+    LookupInvoiceRequestParams, LookupInvoiceResponseResult, MakeInvoiceRequestParams,
+    MakeInvoiceResponseResult, Method, NIP47Error, NostrWalletConnectURI, PayInvoiceRequestParams,
+    PayInvoiceResponseResult, PayKeysendRequestParams, PayKeysendResponseResult, Request,
+    RequestParams, Response, ResponseResult,
+};
+use nostr::secp256k1::XOnlyPublicKey;
+use nostr::{Event, EventBuilder, Filter, JsonUtil, Keys, Kind, Tag, Timestamp, Url};
+use nostr_database::{async_trait, AsyncTraitDeps};
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::RwLock;
+
+use super::Error;
+use crate::client::Client;
+use crate::relay::RelayPoolNotification;
+
+fn not_implemented(method: Method) -> NIP47Error {
+    NIP47Error {
+        code: ErrorCode::NotImplemented,
+        message: format!("{method} not implemented"),
+    }
+}
+
+/// Wallet service handler
+///
+/// Implement the methods announced in [`WalletServiceHandler::supported_methods`]:
+/// the others reply with a `NOT_IMPLEMENTED` error.
+/// `app` is the public key of the connection that sent the request.
+#[cfg_attr(target_arch = "wasm32", async_trait(?Send))]
+#[cfg_attr(not(target_arch = "wasm32"), async_trait)]
+pub trait WalletServiceHandler: AsyncTraitDeps {
+    /// Methods supported by the wallet (published in the info event)
+    fn supported_methods(&self) -> Vec<Method>;
+
+    /// Pay a bolt11 invoice
+    async fn pay_invoice(
+        &self,
+        _app: XOnlyPublicKey,
+        _params: PayInvoiceRequestParams,
+    ) -> Result<PayInvoiceResponseResult, NIP47Error> {
+        Err(not_implemented(Method::PayInvoice))
+    }
+
+    /// Send a keysend payment
+    async fn pay_keysend(
+        &self,
+        _app: XOnlyPublicKey,
+        _params: PayKeysendRequestParams,
+    ) -> Result<PayKeysendResponseResult, NIP47Error> {
+        Err(not_implemented(Method::PayKeysend))
+    }
+
+    /// Create a bolt11 invoice
+    async fn make_invoice(
+        &self,
+        _app: XOnlyPublicKey,
+        _params: MakeInvoiceRequestParams,
+    ) -> Result<MakeInvoiceResponseResult, NIP47Error> {
+        Err(not_implemented(Method::MakeInvoice))
+    }
+
+    /// Lookup an invoice
+    async fn lookup_invoice(
+        &self,
+        _app: XOnlyPublicKey,
+        _params: LookupInvoiceRequestParams,
+    ) -> Result<LookupInvoiceResponseResult, NIP47Error> {
+        Err(not_implemented(Method::LookupInvoice))
+    }
+
+    /// List invoices
+    async fn list_invoices(
+        &self,
+        _app: XOnlyPublicKey,
+        _params: ListInvoicesRequestParams,
+    ) -> Result<Vec<LookupInvoiceResponseResult>, NIP47Error> {
+        Err(not_implemented(Method::ListInvoices))
+    }
+
+    /// List payments
+    async fn list_payments(
+        &self,
+        _app: XOnlyPublicKey,
+        _params: ListPaymentsRequestParams,
+    ) -> Result<Vec<ListPaymentResponseResult>, NIP47Error> {
+        Err(not_implemented(Method::ListPayments))
+    }
+
+    /// List transactions
+    async fn list_transactions(
+        &self,
+        _app: XOnlyPublicKey,
+        _params: ListTransactionsRequestParams,
+    ) -> Result<ListTransactionsResponseResult, NIP47Error> {
+        Err(not_implemented(Method::ListTransactions))
+    }
+
+    /// Get balance
+    async fn get_balance(
+        &self,
+        _app: XOnlyPublicKey,
+    ) -> Result<GetBalanceResponseResult, NIP47Error> {
+        Err(not_implemented(Method::GetBalance))
+    }
+}
+
+/// Nostr Wallet Connect service
+///
+/// Listen for the requests (kind `23194`) of the authorized connections, dispatch them
+/// to the [`WalletServiceHandler`] and publish the responses (kind `23195`).
+///
+/// # Example
+/// ```rust,no_run
+/// use nostr_sdk::prelude::*;
+///
+/// #[derive(Debug)]
+/// struct MyWallet;
+///
+/// #[async_trait]
+/// impl WalletServiceHandler for MyWallet {
+///     fn supported_methods(&self) -> Vec<Method> {
+///         vec![Method::GetBalance]
+///     }
+///
+///     async fn get_balance(
+///         &self,
+///         _app: XOnlyPublicKey,
+///     ) -> Result<GetBalanceResponseResult, NIP47Error> {
+///         Ok(GetBalanceResponseResult {
+///             balance: 21_000,
+///             max_amount: None,
+///             budget_renewal: None,
+///         })
+///     }
+/// }
+///
+/// # #[tokio::main]
+/// # async fn main() {
+/// let relay_url = Url::parse("wss://relay.example.com").unwrap();
+/// let service = WalletService::new(Keys::generate(), relay_url, MyWallet);
+/// let uri: NostrWalletConnectURI = service.new_connection(None).await.unwrap();
+/// println!("{}", uri.display_secret());
+/// service.run().await.unwrap();
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct WalletService<H> {
+    keys: Keys,
+    relay_url: Url,
+    client: Client,
+    handler: Arc<H>,
+    connections: Arc<RwLock<HashSet<XOnlyPublicKey>>>,
+}
+
+impl<H> Clone for WalletService<H> {
+    fn clone(&self) -> Self {
+        Self {
+            keys: self.keys.clone(),
+            relay_url: self.relay_url.clone(),
+            client: self.client.clone(),
+            handler: self.handler.clone(),
+            connections: self.connections.clone(),
+        }
+    }
+}
+
+impl<H> WalletService<H>
+where
+    H: WalletServiceHandler + 'static,
+{
+    /// New wallet service
+    pub fn new(keys: Keys, relay_url: Url, handler: H) -> Self {
+        Self {
+            client: Client::new(&keys),
+            keys,
+            relay_url,
+            handler: Arc::new(handler),
+            connections: Arc::new(RwLock::new(HashSet::new())),
+        }
+    }
+
+    /// Get wallet service public key
+    pub fn public_key(&self) -> XOnlyPublicKey {
+        self.keys.public_key()
+    }
+
+    /// Create a new connection
+    ///
+    /// Generate the connection secret, authorize it and return the [`NostrWalletConnectURI`] to give to the app.
+    pub async fn new_connection(
+        &self,
+        lud16: Option<String>,
+    ) -> Result<NostrWalletConnectURI, Error> {
+        let app_keys = Keys::generate();
+        let uri = NostrWalletConnectURI::new(
+            self.public_key(),
+            self.relay_url.clone(),
+            app_keys.secret_key()?,
+            lud16,
+        )?;
+        self.authorize(app_keys.public_key()).await;
+        Ok(uri)
+    }
+
+    /// Authorize a connection by its public key
+    pub async fn authorize(&self, app: XOnlyPublicKey) {
+        let mut connections = self.connections.write().await;
+        connections.insert(app);
+    }
+
+    /// Revoke a connection: its next requests will be ignored
+    pub async fn revoke(&self, app: &XOnlyPublicKey) {
+        let mut connections = self.connections.write().await;
+        connections.remove(app);
+    }
+
+    /// Connect to the relay, publish the info event (kind `13194`) and handle the requests
+    ///
+    /// Return when the service is shutdown.
+    pub async fn run(&self) -> Result<(), Error> {
+        self.client.add_relay(self.relay_url.clone()).await?;
+        self.client.connect().await;
+
+        // Info event
+        let content: String = self
+            .handler
+            .supported_methods()
+            .iter()
+            .map(|m| m.to_string())
+            .collect::<Vec<String>>()
+            .join(" ");
+        let info: Event =
+            EventBuilder::new(Kind::WalletConnectInfo, content, []).to_event(&self.keys)?;
+        self.client.send_event(info).await?;
+
+        // Get notification listener before subscribe, to not miss any request
+        let mut notifications = self.client.notifications();
+
+        let filter = Filter::new()
+            .pubkey(self.public_key())
+            .kind(Kind::WalletConnectRequest)
+            .since(Timestamp::now());
+        self.client.subscribe(vec![filter]).await;
+
+        tracing::info!("NWC service started on {}", self.relay_url);
+
+        loop {
+            match notifications.recv().await {
+                Ok(RelayPoolNotification::Event { event, .. }) => {
+                    if event.kind() != Kind::WalletConnectRequest {
+                        continue;
+                    }
+
+                    let connections = self.connections.read().await;
+                    if !connections.contains(&event.author()) {
+                        tracing::warn!("Ignored NWC request from unknown app {}", event.author());
+                        continue;
+                    }
+
+                    // Handle requests concurrently, to not block on slow payments
+                    let service = self.clone();
+                    thread::spawn(async move {
+                        if let Err(e) = service.handle_request(event).await {
+                            tracing::error!("Impossible to handle NWC request: {e}");
+                        }
+                    });
+                }
+                Ok(RelayPoolNotification::Stop) | Ok(RelayPoolNotification::Shutdown) => break,
+                Ok(_) => (),
+                Err(RecvError::Lagged(num)) => {
+                    tracing::warn!("NWC service lagged: {num} notifications skipped")
+                }
+                Err(RecvError::Closed) => break,
+            }
+        }
+
+        tracing::info!("NWC service stopped");
+
+        Ok(())
+    }
+
+    /// Shutdown the service
+    pub async fn shutdown(self) -> Result<(), Error> {
+        Ok(self.client.shutdown().await?)
+    }
+
+    async fn handle_request(&self, event: Event) -> Result<(), Error> {
+        let app: XOnlyPublicKey = event.author();
+        let secret_key = self.keys.secret_key()?;
+        let msg: String = nip04::decrypt(&secret_key, &app, event.content())?;
+        let req = Request::from_json(msg)?;
+
+        tracing::debug!(
+            "Received NWC request {} from {app}: {}",
+            event.id(),
+            req.method
+        );
+
+        let result = match req.params {
+            RequestParams::PayInvoice(p) => self
+                .handler
+                .pay_invoice(app, p)
+                .await
+                .map(ResponseResult::PayInvoice),
+            RequestParams::PayKeysend(p) => self
+                .handler
+                .pay_keysend(app, p)
+                .await
+                .map(ResponseResult::PayKeysend),
+            RequestParams::MakeInvoice(p) => self
+                .handler
+                .make_invoice(app, p)
+                .await
+                .map(ResponseResult::MakeInvoice),
+            RequestParams::LookupInvoice(p) => self
+                .handler
+                .lookup_invoice(app, p)
+                .await
+                .map(ResponseResult::LookupInvoice),
+            RequestParams::ListInvoices(p) => self
+                .handler
+                .list_invoices(app, p)
+                .await
+                .map(ResponseResult::ListInvoices),
+            RequestParams::ListPayments(p) => self
+                .handler
+                .list_payments(app, p)
+                .await
+                .map(ResponseResult::ListPayments),
+            RequestParams::ListTransactions(p) => self
+                .handler
+                .list_transactions(app, p)
+                .await
+                .map(ResponseResult::ListTransactions),
+            RequestParams::GetBalance => self
+                .handler
+                .get_balance(app)
+                .await
+                .map(ResponseResult::GetBalance),
+        };
+
+        let res = match result {
+            Ok(result) => Response {
+                result_type: req.method,
+                error: None,
+                result: Some(result),
+            },
+            Err(error) => Response {
+                result_type: req.method,
+                error: Some(error),
+                result: None,
+            },
+        };
+
+        let content: String = nip04::encrypt(&secret_key, &app, res.as_json())?;
+        let response: Event = EventBuilder::new(
+            Kind::WalletConnectResponse,
+            content,
+            [Tag::public_key(app), Tag::event(event.id())],
+        )
+        .to_event(&self.keys)?;
+        self.client.send_event(response).await?;
+
+        Ok(())
+    }
+}
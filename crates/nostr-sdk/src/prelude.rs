@@ -14,4 +14,5 @@ pub use nostr_database::*;
 // Internal modules
 pub use crate::client::*;
 pub use crate::relay::*;
+pub use crate::util::{BoxedStream, Stream, StreamExt};
 pub use crate::*;
@@ -0,0 +1,108 @@
+// Copyright (c) 2022-2023 Yuki Kishimoto
+// Copyright (c) 2023-2024 Rust Nostr Developers
+// Distributed under the MIT software license
+
+//! Relay capabilities
+
+use std::collections::HashMap;
+use std::fmt;
+
+use nostr::Url;
+
+/// Relay capability
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RelayCapability {
+    /// Event counts (NIP45)
+    Count,
+    /// Search capability (NIP50)
+    Search,
+    /// Authentication of clients to relays (NIP42)
+    Auth,
+    /// Negentropy syncing
+    Negentropy,
+}
+
+impl fmt::Display for RelayCapability {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Count => write!(f, "count"),
+            Self::Search => write!(f, "search"),
+            Self::Auth => write!(f, "auth"),
+            Self::Negentropy => write!(f, "negentropy"),
+        }
+    }
+}
+
+impl RelayCapability {
+    /// Get the NIP announcing the capability in the NIP11 `supported_nips`
+    ///
+    /// Return `None` if the capability can be only discovered at runtime.
+    pub fn nip(&self) -> Option<u16> {
+        match self {
+            Self::Count => Some(45),
+            Self::Search => Some(50),
+            Self::Auth => Some(42),
+            Self::Negentropy => None,
+        }
+    }
+}
+
+/// Per-relay report
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RelayReport<T> {
+    /// Success
+    Success(T),
+    /// Capability not supported by the relay: fallen back to `REQ`
+    Fallback {
+        /// Unsupported capability
+        capability: RelayCapability,
+        /// Output of the fallback
+        output: T,
+    },
+    /// Capability not supported by the relay: relay skipped
+    Skipped(RelayCapability),
+    /// Failed
+    Failed(String),
+}
+
+impl<T> RelayReport<T> {
+    /// Get output, if any
+    pub fn output(&self) -> Option<&T> {
+        match self {
+            Self::Success(output) | Self::Fallback { output, .. } => Some(output),
+            Self::Skipped(..) | Self::Failed(..) => None,
+        }
+    }
+
+    /// Check if the relay was skipped or failed
+    pub fn is_err(&self) -> bool {
+        self.output().is_none()
+    }
+}
+
+/// [`RelayPool`](super::pool::RelayPool) report, by relay url
+pub type RelayPoolReport<T> = HashMap<Url, RelayReport<T>>;
+
+/// Events count report
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CountReport {
+    /// Per-relay counts
+    pub relays: RelayPoolReport<usize>,
+    /// Number of matching events in the local database
+    pub database: usize,
+}
+
+impl CountReport {
+    /// Aggregate count
+    ///
+    /// The counts of different relays can't be deduplicated, so the aggregate is the max between
+    /// the relays and the local database counts (a lower bound of the distinct matching events).
+    /// If no relay returned a count, it's the database count.
+    pub fn total(&self) -> usize {
+        self.relays
+            .values()
+            .filter_map(|report| report.output())
+            .copied()
+            .fold(self.database, usize::max)
+    }
+}
@@ -0,0 +1,158 @@
+// Copyright (c) 2022-2023 Yuki Kishimoto
+// Copyright (c) 2023-2024 Rust Nostr Developers
+// Distributed under the MIT software license
+
+//! REQ chunking
+//!
+//! Split the REQ filters so that the messages fit the relay limitation (NIP11)
+//! and paginate the filters with a `limit` higher than the relay `max_limit`.
+
+use std::mem;
+
+#[cfg(feature = "nip11")]
+use nostr::nips::nip11::RelayInformationDocument;
+use nostr::secp256k1::XOnlyPublicKey;
+use nostr::{ClientMessage, EventId, Filter, JsonUtil, SubscriptionId};
+
+/// Relay REQ limits
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct ReqLimits {
+    /// Max size of the `REQ` message, in bytes
+    pub max_message_length: Option<usize>,
+    /// Max number of filters per `REQ`
+    pub max_filters: Option<usize>,
+    /// Max `limit` value of a filter
+    pub max_limit: Option<usize>,
+}
+
+/// `REQ` chunk
+#[derive(Debug, Clone)]
+pub(crate) enum ReqChunk {
+    /// Filters that fit a single `REQ`
+    Single(Vec<Filter>),
+    /// Filter to paginate
+    Paginated {
+        filter: Box<Filter>,
+        /// Number of requested events
+        total: usize,
+        /// Max number of events per page
+        page: usize,
+    },
+}
+
+impl ReqLimits {
+    #[cfg(feature = "nip11")]
+    pub fn from_document(document: &RelayInformationDocument) -> Self {
+        match &document.limitation {
+            Some(limitation) => Self {
+                max_message_length: positive(limitation.max_message_length),
+                max_filters: positive(limitation.max_filters),
+                max_limit: positive(limitation.max_limit),
+            },
+            None => Self::default(),
+        }
+    }
+
+    /// Split filters in `REQ` chunks
+    pub fn chunks(&self, filters: Vec<Filter>) -> Vec<ReqChunk> {
+        let overhead: usize = req_overhead();
+
+        let mut chunks: Vec<ReqChunk> = Vec::new();
+        let mut group: Vec<Filter> = Vec::new();
+        let mut group_size: usize = overhead;
+
+        for filter in filters.into_iter() {
+            // Paginate filters with `limit` higher than the relay `max_limit`
+            if let (Some(max_limit), Some(limit)) = (self.max_limit, filter.limit) {
+                if limit > max_limit {
+                    chunks.push(ReqChunk::Paginated {
+                        filter: Box::new(filter),
+                        total: limit,
+                        page: max_limit,
+                    });
+                    continue;
+                }
+            }
+
+            for filter in self.split_filter(filter, overhead) {
+                // Filter JSON + comma
+                let size: usize = filter.as_json().len() + 1;
+                let too_many: bool = self.max_filters.map_or(false, |max| group.len() >= max);
+                let too_large: bool = self
+                    .max_message_length
+                    .map_or(false, |max| group_size + size > max);
+                if !group.is_empty() && (too_many || too_large) {
+                    chunks.push(ReqChunk::Single(mem::take(&mut group)));
+                    group_size = overhead;
+                }
+                group_size += size;
+                group.push(filter);
+            }
+        }
+
+        if !group.is_empty() {
+            chunks.push(ReqChunk::Single(group));
+        }
+
+        chunks
+    }
+
+    /// Split filter in halves (by `ids` or `authors`) until fit the `max_message_length`
+    fn split_filter(&self, filter: Filter, overhead: usize) -> Vec<Filter> {
+        let max: usize = match self.max_message_length {
+            Some(max) => max,
+            None => return vec![filter],
+        };
+
+        if overhead + filter.as_json().len() <= max {
+            return vec![filter];
+        }
+
+        let (first, second) = if filter.ids.len() > 1 && filter.ids.len() >= filter.authors.len() {
+            let mut ids: Vec<EventId> = filter.ids.iter().copied().collect();
+            let half: Vec<EventId> = ids.split_off(ids.len() / 2);
+            (
+                Filter {
+                    ids: ids.into_iter().collect(),
+                    ..filter.clone()
+                },
+                Filter {
+                    ids: half.into_iter().collect(),
+                    ..filter
+                },
+            )
+        } else if filter.authors.len() > 1 {
+            let mut authors: Vec<XOnlyPublicKey> = filter.authors.iter().copied().collect();
+            let half: Vec<XOnlyPublicKey> = authors.split_off(authors.len() / 2);
+            (
+                Filter {
+                    authors: authors.into_iter().collect(),
+                    ..filter.clone()
+                },
+                Filter {
+                    authors: half.into_iter().collect(),
+                    ..filter
+                },
+            )
+        } else {
+            tracing::warn!("Filter exceed the relay max message length and can't be split");
+            return vec![filter];
+        };
+
+        let mut filters: Vec<Filter> = self.split_filter(first, overhead);
+        filters.extend(self.split_filter(second, overhead));
+        filters
+    }
+}
+
+/// Size of a `REQ` message without filters
+fn req_overhead() -> usize {
+    ClientMessage::req(SubscriptionId::generate(), Vec::new())
+        .as_json()
+        .len()
+}
+
+#[cfg(feature = "nip11")]
+pub(super) fn positive(value: Option<i32>) -> Option<usize> {
+    value.filter(|v| *v > 0).map(|v| v as usize)
+}
@@ -0,0 +1,90 @@
+// Copyright (c) 2022-2023 Yuki Kishimoto
+// Copyright (c) 2023-2024 Rust Nostr Developers
+// Distributed under the MIT software license
+
+//! Relay debug frames
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+use nostr::{Timestamp, Url};
+use tokio::sync::{broadcast, RwLock};
+
+/// Raw frame direction
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FrameDirection {
+    /// Received from the relay
+    Inbound,
+    /// Sent to the relay
+    Outbound,
+}
+
+/// Raw WebSocket frame
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DebugFrame {
+    /// Relay url
+    pub relay_url: Url,
+    /// Direction
+    pub direction: FrameDirection,
+    /// Raw data (invalid UTF-8 sequences are replaced)
+    pub data: String,
+    /// Timestamp
+    pub timestamp: Timestamp,
+}
+
+/// Debug frames stream and capture
+#[derive(Debug, Clone)]
+pub(crate) struct DebugCapture {
+    sender: broadcast::Sender<DebugFrame>,
+    frames: Arc<RwLock<VecDeque<DebugFrame>>>,
+}
+
+impl DebugCapture {
+    pub fn new() -> Self {
+        let (sender, ..) = broadcast::channel(1024);
+        Self {
+            sender,
+            frames: Arc::new(RwLock::new(VecDeque::new())),
+        }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<DebugFrame> {
+        self.sender.subscribe()
+    }
+
+    pub async fn frames(&self) -> Vec<DebugFrame> {
+        let frames = self.frames.read().await;
+        frames.iter().cloned().collect()
+    }
+
+    /// Notify the frame and keep the last `capacity` frames
+    pub async fn record(
+        &self,
+        relay_url: &Url,
+        direction: FrameDirection,
+        data: &[u8],
+        capacity: usize,
+    ) {
+        if self.sender.receiver_count() == 0 && capacity == 0 {
+            return;
+        }
+
+        let frame = DebugFrame {
+            relay_url: relay_url.clone(),
+            direction,
+            data: String::from_utf8_lossy(data).to_string(),
+            timestamp: Timestamp::now(),
+        };
+
+        if capacity > 0 {
+            let mut frames = self.frames.write().await;
+            while frames.len() >= capacity {
+                frames.pop_front();
+            }
+            frames.push_back(frame.clone());
+        }
+
+        // Error only if there are no receivers
+        let _ = self.sender.send(frame);
+    }
+}
@@ -0,0 +1,82 @@
+// Copyright (c) 2022-2023 Yuki Kishimoto
+// Copyright (c) 2023-2024 Rust Nostr Developers
+// Distributed under the MIT software license
+
+//! Relay connection hooks
+
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use async_utility::thread;
+use nostr::Url;
+use nostr_database::SendOutsideWasm;
+use tokio::sync::RwLock;
+
+#[cfg(not(target_arch = "wasm32"))]
+type BoxedFuture = Pin<Box<dyn Future<Output = ()> + Send>>;
+#[cfg(target_arch = "wasm32")]
+type BoxedFuture = Pin<Box<dyn Future<Output = ()>>>;
+
+type Hook = Arc<dyn Fn(Url, RelayHookEvent) -> BoxedFuture + Send + Sync>;
+
+/// Relay connection event
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RelayHookEvent {
+    /// Connection established
+    Connected,
+    /// Connection lost (the relay will try to reconnect, if enabled)
+    Disconnected,
+    /// `AUTH` accepted by the relay (NIP42)
+    Authenticated,
+    /// `AUTH` rejected by the relay or not acknowledged in time (NIP42)
+    AuthenticationFailed {
+        /// Error
+        error: String,
+    },
+    /// Auto-reconnection given up (see [`ReconnectPolicy::max_attempts`](super::ReconnectPolicy::max_attempts))
+    PermanentFailure {
+        /// Number of consecutive failed connection attempts
+        attempts: usize,
+    },
+}
+
+/// Relay connection hooks
+#[derive(Clone, Default)]
+pub(crate) struct RelayHooks {
+    hooks: Arc<RwLock<Vec<Hook>>>,
+}
+
+impl fmt::Debug for RelayHooks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RelayHooks").finish()
+    }
+}
+
+impl RelayHooks {
+    pub async fn add<F, Fut>(&self, hook: F)
+    where
+        F: Fn(Url, RelayHookEvent) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + SendOutsideWasm + 'static,
+    {
+        let mut hooks = self.hooks.write().await;
+        hooks.push(Arc::new(move |url, event| {
+            Box::pin(hook(url, event)) as BoxedFuture
+        }));
+    }
+
+    pub async fn clear(&self) {
+        let mut hooks = self.hooks.write().await;
+        hooks.clear();
+    }
+
+    /// Call every hook in a separated task, to not block the caller
+    pub async fn dispatch(&self, relay_url: &Url, event: RelayHookEvent) {
+        let hooks = self.hooks.read().await;
+        for hook in hooks.iter() {
+            let fut: BoxedFuture = hook(relay_url.clone(), event.clone());
+            thread::spawn(fut);
+        }
+    }
+}
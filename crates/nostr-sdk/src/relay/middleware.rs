@@ -0,0 +1,106 @@
+// Copyright (c) 2022-2023 Yuki Kishimoto
+// Copyright (c) 2023-2024 Rust Nostr Developers
+// Distributed under the MIT software license
+
+//! Event middleware
+
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use nostr::event::Error;
+use nostr::{Event, EventBuilder, Url};
+use nostr_database::SendOutsideWasm;
+use tokio::sync::RwLock;
+
+#[cfg(not(target_arch = "wasm32"))]
+type BoxedFuture<T> = Pin<Box<dyn Future<Output = T> + Send>>;
+#[cfg(target_arch = "wasm32")]
+type BoxedFuture<T> = Pin<Box<dyn Future<Output = T>>>;
+
+type OutgoingHook =
+    Arc<dyn Fn(EventBuilder) -> BoxedFuture<Result<EventBuilder, String>> + Send + Sync>;
+type IncomingHook = Arc<dyn Fn(Url, Event) -> BoxedFuture<Option<Event>> + Send + Sync>;
+
+/// Outgoing and incoming event hooks
+///
+/// Hooks are called in registration order, each one receiving the output of the previous one.
+#[derive(Clone, Default)]
+pub(crate) struct EventMiddleware {
+    outgoing: Arc<RwLock<Vec<OutgoingHook>>>,
+    incoming: Arc<RwLock<Vec<IncomingHook>>>,
+}
+
+impl fmt::Debug for EventMiddleware {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EventMiddleware").finish()
+    }
+}
+
+impl EventMiddleware {
+    pub async fn add_outgoing<F, Fut>(&self, hook: F)
+    where
+        F: Fn(EventBuilder) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<EventBuilder, String>> + SendOutsideWasm + 'static,
+    {
+        let mut hooks = self.outgoing.write().await;
+        hooks.push(Arc::new(move |builder| {
+            Box::pin(hook(builder)) as BoxedFuture<Result<EventBuilder, String>>
+        }));
+    }
+
+    pub async fn add_incoming<F, Fut>(&self, hook: F)
+    where
+        F: Fn(Url, Event) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Option<Event>> + SendOutsideWasm + 'static,
+    {
+        let mut hooks = self.incoming.write().await;
+        hooks.push(Arc::new(move |url, event| {
+            Box::pin(hook(url, event)) as BoxedFuture<Option<Event>>
+        }));
+    }
+
+    pub async fn clear(&self) {
+        self.outgoing.write().await.clear();
+        self.incoming.write().await.clear();
+    }
+
+    /// Pass the builder through the outgoing hooks
+    ///
+    /// Return the veto reason as error.
+    pub async fn outgoing(&self, mut builder: EventBuilder) -> Result<EventBuilder, String> {
+        // Clone the hooks, to not hold the lock while awaiting them
+        let hooks: Vec<OutgoingHook> = self.outgoing.read().await.clone();
+        for hook in hooks.iter() {
+            builder = hook(builder).await?;
+        }
+        Ok(builder)
+    }
+
+    /// Pass the event through the incoming hooks
+    ///
+    /// Return `None` if the event has been filtered out.
+    /// The event returned by the hooks is verified again, to not accept forged events.
+    pub async fn incoming(
+        &self,
+        relay_url: &Url,
+        mut event: Event,
+    ) -> Result<Option<Event>, Error> {
+        let hooks: Vec<IncomingHook> = self.incoming.read().await.clone();
+        if hooks.is_empty() {
+            return Ok(Some(event));
+        }
+
+        for hook in hooks.iter() {
+            event = match hook(relay_url.clone(), event).await {
+                Some(event) => event,
+                None => return Ok(None),
+            };
+        }
+
+        event.verify()?;
+
+        Ok(Some(event))
+    }
+}
@@ -8,14 +8,16 @@ use std::collections::{HashMap, HashSet};
 #[cfg(not(target_arch = "wasm32"))]
 use std::net::SocketAddr;
 use std::ops::Mul;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 use std::{cmp, fmt};
 
 #[cfg(not(target_arch = "wasm32"))]
 use async_utility::futures_util::stream::AbortHandle;
-use async_utility::{futures_util, thread, time};
+use async_utility::{thread, time};
+#[cfg(all(not(target_arch = "wasm32"), unix))]
+use async_wsocket::futures_util::future::Either;
 use async_wsocket::futures_util::{Future, SinkExt, StreamExt};
 use async_wsocket::WsMessage;
 use nostr::message::relay::NegentropyErrorCode;
@@ -24,35 +26,74 @@ use nostr::negentropy::{self, Bytes, Negentropy};
 #[cfg(feature = "nip11")]
 use nostr::nips::nip11::RelayInformationDocument;
 use nostr::secp256k1::rand::{self, Rng};
+use nostr::types::time::Instant;
 use nostr::{
     ClientMessage, Event, EventId, Filter, JsonUtil, Keys, RawRelayMessage, RelayMessage,
     SubscriptionId, Timestamp, Url,
 };
+#[cfg(feature = "nip11")]
+use nostr_database::RelayInfo;
 use nostr_database::{DatabaseError, DynNostrDatabase, Order};
 use thiserror::Error;
+use tokio::sync::broadcast::error::RecvError;
 use tokio::sync::mpsc::{self, Receiver, Sender};
 use tokio::sync::{broadcast, oneshot, Mutex, RwLock};
 
+mod capability;
+mod chunking;
+#[cfg(feature = "debug")]
+mod debug;
+mod hooks;
 pub mod limits;
+mod middleware;
 mod options;
+mod output;
 pub mod pool;
+mod ratelimit;
+mod request;
+mod snapshot;
 mod stats;
-
+#[cfg(all(not(target_arch = "wasm32"), unix))]
+pub mod unix;
+mod validation;
+
+pub use self::capability::{CountReport, RelayCapability, RelayPoolReport, RelayReport};
+use self::chunking::{ReqChunk, ReqLimits};
+#[cfg(feature = "debug")]
+use self::debug::DebugCapture;
+#[cfg(feature = "debug")]
+pub use self::debug::{DebugFrame, FrameDirection};
+pub use self::hooks::RelayHookEvent;
 pub use self::limits::Limits;
+#[cfg(not(target_arch = "wasm32"))]
+pub use self::options::RelayProxy;
+use self::options::MIN_RECONNECT_DELAY;
 pub use self::options::{
-    FilterOptions, NegentropyOptions, RelayOptions, RelayPoolOptions, RelaySendOptions,
+    EventsOrder, FilterOptions, GetEventsOptions, NegentropyOptions, OverflowPolicy, RateLimits,
+    RebroadcastOptions, ReconnectPolicy, RelayOptions, RelayPoolOptions, RelaySendOptions,
+    ReqDistribution, SubscriptionLimits,
 };
-use self::options::{MAX_ADJ_RETRY_SEC, MIN_RETRY_SEC};
-pub use self::pool::{RelayPoolMessage, RelayPoolNotification};
+#[cfg(not(target_arch = "wasm32"))]
+use self::options::{MIN_PING_INTERVAL, MIN_PONG_TIMEOUT};
+pub use self::output::Output;
+pub use self::pool::{RelayPoolMessage, RelayPoolNotification, SubscriptionHandle};
+use self::ratelimit::RateLimiter;
+pub use self::request::{ReqHandle, ReqStatus};
+pub use self::snapshot::{RelayPoolSnapshot, RelaySnapshot};
 pub use self::stats::RelayConnectionStats;
+pub use self::validation::LimitViolation;
+use self::validation::MessageLimits;
 #[cfg(feature = "blocking")]
 use crate::RUNTIME;
 
 type Message = (RelayEvent, Option<oneshot::Sender<bool>>);
 
 const MIN_UPTIME: f64 = 0.90;
-#[cfg(not(target_arch = "wasm32"))]
-const PING_INTERVAL: u64 = 55;
+const AUTH_POLL_INTERVAL: Duration = Duration::from_millis(100);
+/// Machine-readable prefix of `OK` and `CLOSED` messages (NIP42)
+pub(crate) const AUTH_REQUIRED_PREFIX: &str = "auth-required:";
+#[cfg(feature = "nip11")]
+const DOCUMENT_REFRESH_INTERVAL: Duration = Duration::from_secs(60 * 60);
 
 /// [`Relay`] error
 #[derive(Debug, Error)]
@@ -78,6 +119,9 @@ pub enum Error {
     /// Relay not connected
     #[error("relay not connected")]
     NotConnected,
+    /// Not authenticated
+    #[error("not authenticated to relay")]
+    NotAuthenticated,
     /// Event not published
     #[error("event not published: {0}")]
     EventNotPublished(String),
@@ -92,6 +136,9 @@ pub enum Error {
         /// Not published events
         not_published: HashMap<EventId, String>,
     },
+    /// Subscription closed by the relay
+    #[error("subscription closed: {0}")]
+    SubscriptionClosed(String),
     /// Loop terminated
     #[error("loop terminated")]
     LoopTerminated,
@@ -119,6 +166,12 @@ pub enum Error {
     /// Negentropy not supported
     #[error("negentropy not supported")]
     NegentropyNotSupported,
+    /// Capability not supported
+    #[error("{0} not supported by relay")]
+    UnsupportedCapability(RelayCapability),
+    /// Message violates the relay limitation (NIP11)
+    #[error("relay limitation: {0}")]
+    Limitation(LimitViolation),
     /// Unknown negentropy error
     #[error("unknown negentropy error")]
     UnknownNegentropyError,
@@ -141,6 +194,8 @@ pub enum RelayStatus {
     Stopped,
     /// Relay completely disconnected
     Terminated,
+    /// Auto reconnection given up, after too many failed attempts (see [`ReconnectPolicy::max_attempts`])
+    GaveUp,
 }
 
 impl fmt::Display for RelayStatus {
@@ -153,6 +208,7 @@ impl fmt::Display for RelayStatus {
             Self::Disconnected => write!(f, "Disconnected"),
             Self::Stopped => write!(f, "Stopped"),
             Self::Terminated => write!(f, "Terminated"),
+            Self::GaveUp => write!(f, "Gave up"),
         }
     }
 }
@@ -220,6 +276,10 @@ pub struct ActiveSubscription {
     id: SubscriptionId,
     /// Subscriptions filters
     filters: Vec<Filter>,
+    /// Timestamp of the last event received after `EOSE`, or of the `EOSE` (`0` if none)
+    last_event_at: Arc<AtomicU64>,
+    /// `EOSE` received for the last `REQ`
+    eose: Arc<AtomicBool>,
 }
 
 impl Default for ActiveSubscription {
@@ -234,6 +294,8 @@ impl ActiveSubscription {
         Self {
             id: SubscriptionId::generate(),
             filters: Vec::new(),
+            last_event_at: Arc::new(AtomicU64::new(0)),
+            eose: Arc::new(AtomicBool::new(false)),
         }
     }
 
@@ -242,6 +304,8 @@ impl ActiveSubscription {
         Self {
             id: SubscriptionId::generate(),
             filters,
+            last_event_at: Arc::new(AtomicU64::new(0)),
+            eose: Arc::new(AtomicBool::new(false)),
         }
     }
 
@@ -254,6 +318,60 @@ impl ActiveSubscription {
     pub fn filters(&self) -> Vec<Filter> {
         self.filters.clone()
     }
+
+    /// Get the timestamp of the last event received for this subscription
+    ///
+    /// Stored events (received before `EOSE`) are not counted, since they may be out of order:
+    /// when `EOSE` is received, the timestamp is moved to now.
+    pub fn last_event_at(&self) -> Option<Timestamp> {
+        match self.last_event_at.load(Ordering::SeqCst) {
+            0 => None,
+            timestamp => Some(Timestamp::from(timestamp)),
+        }
+    }
+
+    /// Update the timestamp of the last event received
+    ///
+    /// Ignored until `EOSE`. Timestamps in the future are capped to now.
+    fn update_last_event_at(&self, created_at: u64) {
+        if self.eose.load(Ordering::SeqCst) {
+            let created_at: u64 = created_at.min(Timestamp::now().as_u64());
+            self.last_event_at.fetch_max(created_at, Ordering::SeqCst);
+        }
+    }
+
+    /// Mark the stored events as received, moving the timestamp of the last event to now
+    fn set_eose(&self) {
+        self.eose.store(true, Ordering::SeqCst);
+        self.last_event_at
+            .fetch_max(Timestamp::now().as_u64(), Ordering::SeqCst);
+    }
+
+    /// Mark the stored events as pending, before sending the `REQ`
+    fn reset_eose(&self) {
+        self.eose.store(false, Ordering::SeqCst);
+    }
+
+    /// Get the filters to send when resubscribing
+    ///
+    /// If the stored events were already received, `since` is moved to the timestamp of the
+    /// last event (or of the `EOSE`), to not download again the events already received.
+    fn resubscribe_filters(&self) -> Vec<Filter> {
+        match self.last_event_at() {
+            Some(last_event_at) => self
+                .filters
+                .iter()
+                .cloned()
+                .map(|mut filter| {
+                    if filter.since.map_or(true, |since| since < last_event_at) {
+                        filter.since = Some(last_event_at);
+                    }
+                    filter
+                })
+                .collect(),
+            None => self.filters.clone(),
+        }
+    }
 }
 
 /// Relay
@@ -263,17 +381,26 @@ pub struct Relay {
     status: Arc<RwLock<RelayStatus>>,
     #[cfg(feature = "nip11")]
     document: Arc<RwLock<RelayInformationDocument>>,
+    #[cfg(feature = "nip11")]
+    document_fetched_at: Arc<AtomicU64>,
     opts: RelayOptions,
     stats: RelayConnectionStats,
     database: Arc<DynNostrDatabase>,
     scheduled_for_stop: Arc<AtomicBool>,
     scheduled_for_termination: Arc<AtomicBool>,
+    authenticated: Arc<AtomicBool>,
+    auth_challenge: Arc<RwLock<Option<String>>>,
+    automatic_authentication: Arc<AtomicBool>,
+    consecutive_failures: Arc<AtomicUsize>,
+    capabilities: Arc<RwLock<HashMap<RelayCapability, bool>>>,
     pool_sender: Sender<RelayPoolMessage>,
     relay_sender: Sender<Message>,
     relay_receiver: Arc<Mutex<Receiver<Message>>>,
     notification_sender: broadcast::Sender<RelayPoolNotification>,
     subscriptions: Arc<RwLock<HashMap<InternalSubscriptionId, ActiveSubscription>>>,
     limits: Limits,
+    #[cfg(feature = "debug")]
+    debug: DebugCapture,
 }
 
 impl PartialEq for Relay {
@@ -282,6 +409,52 @@ impl PartialEq for Relay {
     }
 }
 
+/// Send `CLOSE` for a subscription when dropped, unless disarmed
+struct CloseOnDrop {
+    relay: Relay,
+    id: Option<SubscriptionId>,
+}
+
+impl CloseOnDrop {
+    fn new(relay: Relay, id: SubscriptionId) -> Self {
+        Self {
+            relay,
+            id: Some(id),
+        }
+    }
+
+    fn disarm(mut self) {
+        self.id = None;
+    }
+}
+
+impl Drop for CloseOnDrop {
+    fn drop(&mut self) {
+        if let Some(id) = self.id.take() {
+            let relay = self.relay.clone();
+            thread::spawn(async move {
+                tracing::debug!("Closing subscription {id} with {}", relay.url);
+                if let Err(e) = relay.send_msg(ClientMessage::close(id), None).await {
+                    tracing::error!("Impossible to close subscription with {}: {e}", relay.url);
+                }
+            });
+        }
+    }
+}
+
+/// Compose [`RelayInfo`] from [`RelayInformationDocument`]
+#[cfg(feature = "nip11")]
+pub(crate) fn relay_info_from_document(url: Url, document: &RelayInformationDocument) -> RelayInfo {
+    RelayInfo {
+        url,
+        name: document.name.clone(),
+        description: document.description.clone(),
+        icon: document.icon.clone(),
+        contact: document.contact.clone(),
+        updated_at: Timestamp::now(),
+    }
+}
+
 impl Relay {
     /// Create new `Relay`
     pub fn new(
@@ -299,17 +472,34 @@ impl Relay {
             status: Arc::new(RwLock::new(RelayStatus::Initialized)),
             #[cfg(feature = "nip11")]
             document: Arc::new(RwLock::new(RelayInformationDocument::new())),
+            #[cfg(feature = "nip11")]
+            document_fetched_at: Arc::new(AtomicU64::new(0)),
             opts,
             stats: RelayConnectionStats::new(),
             database,
             scheduled_for_stop: Arc::new(AtomicBool::new(false)),
             scheduled_for_termination: Arc::new(AtomicBool::new(false)),
+            authenticated: Arc::new(AtomicBool::new(false)),
+            auth_challenge: Arc::new(RwLock::new(None)),
+            automatic_authentication: Arc::new(AtomicBool::new(false)),
+            consecutive_failures: Arc::new(AtomicUsize::new(0)),
+            capabilities: Arc::new(RwLock::new(HashMap::new())),
             pool_sender,
             relay_sender,
             relay_receiver: Arc::new(Mutex::new(relay_receiver)),
             notification_sender,
             subscriptions: Arc::new(RwLock::new(HashMap::new())),
             limits,
+            #[cfg(feature = "debug")]
+            debug: DebugCapture::new(),
+        }
+    }
+
+    /// Share the automatic authentication (NIP42) flag of the pool
+    pub(crate) fn with_automatic_authentication(self, enabled: Arc<AtomicBool>) -> Self {
+        Self {
+            automatic_authentication: enabled,
+            ..self
         }
     }
 
@@ -321,7 +511,7 @@ impl Relay {
     /// Get proxy
     #[cfg(not(target_arch = "wasm32"))]
     pub fn proxy(&self) -> Option<SocketAddr> {
-        self.opts.proxy
+        self.opts.proxy.addr()
     }
 
     /// Get [`RelayStatus`]
@@ -341,12 +531,21 @@ impl Relay {
         let mut s = self.status.write().await;
         *s = status;
 
+        // Authentication is bound to the connection
+        self.authenticated.store(false, Ordering::SeqCst);
+        let mut challenge = self.auth_challenge.write().await;
+        *challenge = None;
+
         // Send notification
-        if let Err(e) = self.pool_sender.try_send(RelayPoolMessage::RelayStatus {
+        self.notify_pool(RelayPoolMessage::RelayStatus {
             relay_url: self.url(),
             status,
-        }) {
-            tracing::error!("Impossible to send RelayPoolMessage::RelayStatus message: {e}");
+        });
+    }
+
+    fn notify_pool(&self, msg: RelayPoolMessage) {
+        if let Err(e) = self.pool_sender.try_send(msg) {
+            tracing::error!("Impossible to send message to the pool: {e}");
         }
     }
 
@@ -355,6 +554,35 @@ impl Relay {
         self.status().await == RelayStatus::Connected
     }
 
+    /// Check if [`Relay`] accepted an `AUTH` for the current connection (NIP42)
+    pub fn is_authenticated(&self) -> bool {
+        self.authenticated.load(Ordering::SeqCst)
+    }
+
+    /// Get the last `AUTH` challenge received for the current connection (NIP42)
+    pub async fn auth_challenge(&self) -> Option<String> {
+        let challenge = self.auth_challenge.read().await;
+        challenge.clone()
+    }
+
+    async fn set_auth_challenge(&self, challenge: String) {
+        let mut c = self.auth_challenge.write().await;
+        *c = Some(challenge);
+    }
+
+    /// Wait until the relay accept an `AUTH` (NIP42)
+    ///
+    /// Return `false` on timeout.
+    pub(crate) async fn wait_for_authentication(&self, timeout: Duration) -> bool {
+        time::timeout(Some(timeout), async {
+            while !self.is_authenticated() {
+                thread::sleep(AUTH_POLL_INTERVAL).await;
+            }
+        })
+        .await
+        .is_some()
+    }
+
     /// Get [`RelayInformationDocument`]
     #[cfg(feature = "nip11")]
     pub async fn document(&self) -> RelayInformationDocument {
@@ -370,8 +598,87 @@ impl Relay {
 
     #[cfg(feature = "nip11")]
     async fn set_document(&self, document: RelayInformationDocument) {
+        // Cache relay info
+        let info: RelayInfo = relay_info_from_document(self.url(), &document);
+        match self.database.save_relay_info(info).await {
+            Ok(()) | Err(DatabaseError::NotSupported) => (),
+            Err(e) => tracing::error!("Impossible to save relay info of {}: {e}", self.url),
+        }
+
         let mut d = self.document.write().await;
         *d = document;
+        self.document_fetched_at
+            .store(Timestamp::now().as_u64(), Ordering::SeqCst);
+    }
+
+    /// Fetch the [`RelayInformationDocument`] (not served over unix sockets)
+    #[cfg(feature = "nip11")]
+    async fn fetch_document(&self) {
+        if self.url.scheme() == "ws+unix" {
+            return;
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        let proxy = self.proxy();
+        #[cfg(target_arch = "wasm32")]
+        let proxy = None;
+        match RelayInformationDocument::get(self.url(), proxy).await {
+            Ok(document) => self.set_document(document).await,
+            Err(e) => tracing::error!(
+                "Impossible to get information document from {}: {}",
+                self.url,
+                e
+            ),
+        };
+    }
+
+    /// Check if the cached [`RelayInformationDocument`] must be refreshed
+    #[cfg(feature = "nip11")]
+    fn is_document_expired(&self) -> bool {
+        let fetched_at: u64 = self.document_fetched_at.load(Ordering::SeqCst);
+        Timestamp::now().as_u64().saturating_sub(fetched_at) >= DOCUMENT_REFRESH_INTERVAL.as_secs()
+    }
+
+    /// Check if relay supports a [`RelayCapability`]
+    ///
+    /// Consult the capabilities discovered at runtime and the cached NIP11 document (`supported_nips` and `limitation`).
+    /// Return `None` if unknown.
+    pub async fn supports(&self, capability: RelayCapability) -> Option<bool> {
+        let capabilities = self.capabilities.read().await;
+        if let Some(supported) = capabilities.get(&capability) {
+            return Some(*supported);
+        }
+
+        #[cfg(feature = "nip11")]
+        if let Some(nip) = capability.nip() {
+            let document = self.document.read().await;
+
+            // Relays requiring authentication support it, even if not announced
+            if capability == RelayCapability::Auth && document.auth_required() {
+                return Some(true);
+            }
+
+            if let Some(supported) = document.supports_nip(nip) {
+                return Some(supported);
+            }
+        }
+
+        None
+    }
+
+    async fn set_capability(&self, capability: RelayCapability, supported: bool) {
+        let mut capabilities = self.capabilities.write().await;
+        capabilities.insert(capability, supported);
+    }
+
+    /// Return error if filters require a [`RelayCapability`] not supported by the relay
+    async fn check_filters_capabilities(&self, filters: &[Filter]) -> Result<(), Error> {
+        if filters.iter().any(|f| f.search.is_some())
+            && self.supports(RelayCapability::Search).await == Some(false)
+        {
+            return Err(Error::UnsupportedCapability(RelayCapability::Search));
+        }
+        Ok(())
     }
 
     /// Get [`ActiveSubscription`]
@@ -389,6 +696,18 @@ impl Relay {
         subscription.get(internal_id).cloned()
     }
 
+    /// Get [`InternalSubscriptionId`] and [`ActiveSubscription`] by [`SubscriptionId`]
+    pub async fn subscription_by_id(
+        &self,
+        id: &SubscriptionId,
+    ) -> Option<(InternalSubscriptionId, ActiveSubscription)> {
+        let subscriptions = self.subscriptions.read().await;
+        subscriptions
+            .iter()
+            .find(|(_, sub)| &sub.id == id)
+            .map(|(internal_id, sub)| (internal_id.clone(), sub.clone()))
+    }
+
     async fn update_subscription_filters(
         &self,
         internal_id: InternalSubscriptionId,
@@ -396,10 +715,35 @@ impl Relay {
     ) {
         let mut s = self.subscriptions.write().await;
         s.entry(internal_id)
-            .and_modify(|sub| sub.filters = filters.clone())
+            .and_modify(|sub| {
+                // New filters: the events received so far may not match them
+                if sub.filters != filters {
+                    sub.filters = filters.clone();
+                    sub.last_event_at = Arc::new(AtomicU64::new(0));
+                }
+                sub.reset_eose();
+            })
             .or_insert_with(|| ActiveSubscription::with_filters(filters));
     }
 
+    /// Update the timestamp of the last event received for the subscription
+    async fn update_last_event_at(&self, subscription_id: &str, created_at: u64) {
+        let subscription_id = SubscriptionId::new(subscription_id);
+        let subscriptions = self.subscriptions.read().await;
+        if let Some(sub) = subscriptions.values().find(|sub| sub.id == subscription_id) {
+            sub.update_last_event_at(created_at);
+        }
+    }
+
+    /// Mark the stored events of the subscription as received
+    async fn set_eose(&self, subscription_id: &str) {
+        let subscription_id = SubscriptionId::new(subscription_id);
+        let subscriptions = self.subscriptions.read().await;
+        if let Some(sub) = subscriptions.values().find(|sub| sub.id == subscription_id) {
+            sub.set_eose();
+        }
+    }
+
     /// Get [`RelayOptions`]
     pub fn opts(&self) -> RelayOptions {
         self.opts.clone()
@@ -410,6 +754,29 @@ impl Relay {
         self.stats.clone()
     }
 
+    /// Get raw WebSocket frames stream
+    ///
+    /// Emit every text frame sent to or received from the relay.
+    #[cfg(feature = "debug")]
+    pub fn debug_notifications(&self) -> broadcast::Receiver<DebugFrame> {
+        self.debug.subscribe()
+    }
+
+    /// Get the last raw WebSocket frames captured
+    ///
+    /// The capture is disabled by default: see [`RelayOptions::debug_capture`].
+    #[cfg(feature = "debug")]
+    pub async fn debug_capture(&self) -> Vec<DebugFrame> {
+        self.debug.frames().await
+    }
+
+    #[cfg(feature = "debug")]
+    async fn debug_frame(&self, direction: FrameDirection, data: &[u8]) {
+        self.debug
+            .record(&self.url, direction, data, self.opts.get_debug_capture())
+            .await;
+    }
+
     /// Get queue len
     pub fn queue(&self) -> usize {
         self.relay_sender.max_capacity() - self.relay_sender.capacity()
@@ -440,8 +807,13 @@ impl Relay {
         self.schedule_for_stop(false);
         self.schedule_for_termination(false);
 
-        if let RelayStatus::Initialized | RelayStatus::Stopped | RelayStatus::Terminated =
-            self.status().await
+        // Manual connection: start again the reconnection backoff
+        self.consecutive_failures.store(0, Ordering::SeqCst);
+
+        if let RelayStatus::Initialized
+        | RelayStatus::Stopped
+        | RelayStatus::Terminated
+        | RelayStatus::GaveUp = self.status().await
         {
             if self.opts.get_reconnect() {
                 if connection_timeout.is_some() {
@@ -492,32 +864,52 @@ impl Relay {
                             RelayStatus::Initialized
                             | RelayStatus::Pending
                             | RelayStatus::Disconnected => {
-                                relay.try_connect(connection_timeout).await
+                                relay.try_connect(connection_timeout).await;
+
+                                // Give up after too many consecutive failed attempts
+                                if let Some(max) = relay.opts.reconnect_policy.max_attempts {
+                                    let attempts: usize =
+                                        relay.consecutive_failures.load(Ordering::SeqCst);
+                                    if attempts >= max {
+                                        relay.set_status(RelayStatus::GaveUp).await;
+                                        relay.notify_pool(RelayPoolMessage::PermanentFailure {
+                                            relay_url: relay.url(),
+                                            attempts,
+                                        });
+                                        tracing::warn!(
+                                            "Auto connect loop terminated for {} [{attempts} consecutive failures]",
+                                            relay.url
+                                        );
+                                        break;
+                                    }
+                                }
                             }
-                            RelayStatus::Stopped | RelayStatus::Terminated => {
+                            RelayStatus::Stopped
+                            | RelayStatus::Terminated
+                            | RelayStatus::GaveUp => {
                                 tracing::debug!("Auto connect loop terminated for {}", relay.url);
                                 break;
                             }
                             _ => (),
                         };
 
-                        let retry_sec: u64 = if relay.opts.get_adjust_retry_sec() {
-                            let var: u64 =
-                                relay.stats.attempts().saturating_sub(relay.stats.success()) as u64;
-                            if var >= 3 {
-                                let retry_interval: i64 =
-                                    cmp::min(MIN_RETRY_SEC * (1 + var), MAX_ADJ_RETRY_SEC) as i64;
-                                let jitter: i64 = rand::thread_rng().gen_range(-1..=1);
-                                retry_interval.saturating_add(jitter) as u64
-                            } else {
-                                relay.opts().get_retry_sec()
-                            }
+                        let policy: ReconnectPolicy = relay.opts.reconnect_policy;
+                        let failures: usize = if policy.reset_on_success {
+                            relay.consecutive_failures.load(Ordering::SeqCst)
                         } else {
-                            relay.opts().get_retry_sec()
+                            relay.stats.attempts().saturating_sub(relay.stats.success())
                         };
+                        let mut retry: Duration = policy.delay(failures);
+                        let jitter: u64 = policy.jitter.as_millis() as u64;
+                        if jitter > 0 {
+                            let jitter: u64 = rand::thread_rng().gen_range(0..=jitter * 2);
+                            retry = (retry + Duration::from_millis(jitter))
+                                .saturating_sub(policy.jitter)
+                                .max(MIN_RECONNECT_DELAY);
+                        }
 
-                        tracing::trace!("{} retry time set to {retry_sec} secs", relay.url);
-                        thread::sleep(Duration::from_secs(retry_sec)).await;
+                        tracing::trace!("{} retry time set to {retry:?}", relay.url);
+                        thread::sleep(retry).await;
                     }
                 });
             } else if connection_timeout.is_some() {
@@ -538,24 +930,11 @@ impl Relay {
         self.set_status(RelayStatus::Connecting).await;
         tracing::debug!("Connecting to {}", url);
 
-        // Request `RelayInformationDocument`
+        // Request `RelayInformationDocument`, if not cached or expired
         #[cfg(feature = "nip11")]
-        {
+        if self.is_document_expired() {
             let relay = self.clone();
-            thread::spawn(async move {
-                #[cfg(not(target_arch = "wasm32"))]
-                let proxy = relay.proxy();
-                #[cfg(target_arch = "wasm32")]
-                let proxy = None;
-                match RelayInformationDocument::get(relay.url(), proxy).await {
-                    Ok(document) => relay.set_document(document).await,
-                    Err(e) => tracing::error!(
-                        "Impossible to get information document from {}: {}",
-                        relay.url,
-                        e
-                    ),
-                };
-            });
+            thread::spawn(async move { relay.fetch_document().await });
         }
 
         let timeout: Option<Duration> = if self.stats.attempts() > 1 {
@@ -565,7 +944,19 @@ impl Relay {
             // First attempt, use external timeout
             connection_timeout
         };
-        #[cfg(not(target_arch = "wasm32"))]
+        #[cfg(all(not(target_arch = "wasm32"), unix))]
+        let connection = if unix::is_unix_url(&self.url) {
+            unix::connect(&self.url, timeout)
+                .await
+                .map(|(tx, rx)| (Either::Right(tx), Either::Right(rx)))
+                .map_err(|e| e.to_string())
+        } else {
+            async_wsocket::native::connect(&self.url, self.proxy(), timeout)
+                .await
+                .map(|(tx, rx)| (Either::Left(tx), Either::Left(rx)))
+                .map_err(|e| e.to_string())
+        };
+        #[cfg(all(not(target_arch = "wasm32"), not(unix)))]
         let connection = async_wsocket::native::connect(&self.url, self.proxy(), timeout).await;
         #[cfg(target_arch = "wasm32")]
         let connection = async_wsocket::wasm::connect(&self.url, timeout).await;
@@ -577,6 +968,7 @@ impl Relay {
                 tracing::info!("Connected to {}", url);
 
                 self.stats.new_success();
+                self.consecutive_failures.store(0, Ordering::SeqCst);
 
                 #[cfg(not(target_arch = "wasm32"))]
                 let ping_abort_handle: AbortHandle = {
@@ -584,23 +976,34 @@ impl Relay {
                     thread::abortable(async move {
                         tracing::debug!("Relay Ping Thread Started");
 
-                        loop {
-                            if relay.stats.ping.last_nonce() != 0 && !relay.stats.ping.replied() {
-                                tracing::warn!("{} not replied to ping", relay.url);
-                                relay.stats.ping.reset();
-                                break;
-                            }
+                        // Too short values would mark as dead any connection
+                        let interval: Duration =
+                            cmp::max(relay.opts.ping_interval, MIN_PING_INTERVAL);
+                        let pong_timeout: Duration =
+                            relay.opts.pong_timeout.clamp(MIN_PONG_TIMEOUT, interval);
 
+                        loop {
                             let nonce: u64 = rand::thread_rng().gen();
                             if relay.stats.ping.set_last_nonce(nonce)
                                 && relay.stats.ping.set_replied(false)
                             {
+                                let (tx, rx) = oneshot::channel::<bool>();
                                 if let Err(e) =
-                                    relay.send_relay_event(RelayEvent::Ping { nonce }, None)
+                                    relay.send_relay_event(RelayEvent::Ping { nonce }, Some(tx))
                                 {
                                     tracing::error!("Impossible to ping {}: {e}", relay.url);
                                     break;
                                 };
+
+                                // The ping may wait behind the rate limited messages:
+                                // start the pong timer only once it's actually written
+                                match rx.await {
+                                    Ok(true) => (),
+                                    Ok(false) | Err(_) => {
+                                        tracing::error!("Impossible to ping {}", relay.url);
+                                        break;
+                                    }
+                                }
                             } else {
                                 tracing::warn!(
                                     "`last_nonce` or `replied` not updated for {}!",
@@ -608,7 +1011,18 @@ impl Relay {
                                 );
                             }
 
-                            thread::sleep(Duration::from_secs(PING_INTERVAL)).await;
+                            // Dead connection (i.e. dropped by a NAT): no pong received in time
+                            thread::sleep(pong_timeout).await;
+                            if !relay.stats.ping.replied() {
+                                tracing::warn!(
+                                    "{} not replied to ping within {pong_timeout:?}",
+                                    relay.url
+                                );
+                                relay.stats.ping.reset();
+                                break;
+                            }
+
+                            thread::sleep(interval.saturating_sub(pong_timeout)).await;
                         }
 
                         tracing::debug!("Exited from Ping Thread of {}", relay.url);
@@ -619,22 +1033,47 @@ impl Relay {
                     })
                 };
 
+                // Periodically refresh the `RelayInformationDocument`
+                #[cfg(feature = "nip11")]
+                let document_abort_handle = {
+                    let relay = self.clone();
+                    thread::abortable(async move {
+                        loop {
+                            thread::sleep(DOCUMENT_REFRESH_INTERVAL).await;
+                            relay.fetch_document().await;
+                        }
+                    })
+                };
+
                 let relay = self.clone();
                 thread::spawn(async move {
                     tracing::debug!("Relay Event Thread Started");
                     let mut rx = relay.relay_receiver.lock().await;
+                    let mut limiter: Option<RateLimiter> =
+                        relay.opts.rate_limits.map(RateLimiter::new);
                     while let Some((relay_event, oneshot_sender)) = rx.recv().await {
                         match relay_event {
                             RelayEvent::SendMsg(msg) => {
+                                let is_event: bool = msg.is_event();
+                                if let Some(limiter) = &mut limiter {
+                                    limiter.acquire(is_event).await;
+                                }
                                 let json = msg.as_json();
                                 let size: usize = json.as_bytes().len();
                                 tracing::debug!(
                                     "Sending {json} to {} (size: {size} bytes)",
                                     relay.url
                                 );
+                                #[cfg(feature = "debug")]
+                                relay
+                                    .debug_frame(FrameDirection::Outbound, json.as_bytes())
+                                    .await;
                                 match ws_tx.send(WsMessage::Text(json)).await {
                                     Ok(_) => {
                                         relay.stats.add_bytes_sent(size);
+                                        if is_event {
+                                            relay.stats.add_events_sent(1);
+                                        }
                                         if let Some(sender) = oneshot_sender {
                                             if let Err(e) = sender.send(true) {
                                                 tracing::error!(
@@ -670,13 +1109,41 @@ impl Relay {
                                     "Sending {len} messages to {} (size: {size} bytes)",
                                     relay.url
                                 );
-                                let msgs = msgs
-                                    .into_iter()
-                                    .map(|msg| Ok(WsMessage::Text(msg.as_json())));
-                                let mut stream = futures_util::stream::iter(msgs);
-                                match ws_tx.send_all(&mut stream).await {
+                                let events: Vec<bool> =
+                                    msgs.iter().map(|msg| msg.is_event()).collect();
+                                let events_count: usize = events.iter().filter(|e| **e).count();
+                                let msgs: Vec<String> =
+                                    msgs.into_iter().map(|msg| msg.as_json()).collect();
+                                #[cfg(feature = "debug")]
+                                for json in msgs.iter() {
+                                    relay
+                                        .debug_frame(FrameDirection::Outbound, json.as_bytes())
+                                        .await;
+                                }
+                                // With rate limits, send one message at a time to respect them,
+                                // otherwise queue all the messages and flush them at once
+                                let mut res = Ok(());
+                                for (json, is_event) in msgs.into_iter().zip(events) {
+                                    let msg = WsMessage::Text(json);
+                                    let sent = match &mut limiter {
+                                        Some(limiter) => {
+                                            limiter.acquire(is_event).await;
+                                            ws_tx.send(msg).await
+                                        }
+                                        None => ws_tx.feed(msg).await,
+                                    };
+                                    if let Err(e) = sent {
+                                        res = Err(Box::new(e));
+                                        break;
+                                    }
+                                }
+                                if res.is_ok() && limiter.is_none() {
+                                    res = ws_tx.flush().await.map_err(Box::new);
+                                }
+                                match res {
                                     Ok(_) => {
                                         relay.stats.add_bytes_sent(size);
+                                        relay.stats.add_events_sent(events_count);
                                         if let Some(sender) = oneshot_sender {
                                             if let Err(e) = sender.send(true) {
                                                 tracing::error!(
@@ -713,6 +1180,9 @@ impl Relay {
                                     Ok(_) => {
                                         relay.stats.ping.just_sent().await;
                                         tracing::debug!("Ping {} (nonce {})", relay.url, nonce);
+                                        if let Some(sender) = oneshot_sender {
+                                            let _ = sender.send(true);
+                                        }
                                     }
                                     Err(e) => {
                                         tracing::error!(
@@ -720,11 +1190,16 @@ impl Relay {
                                             relay.url(),
                                             e.to_string()
                                         );
+                                        if let Some(sender) = oneshot_sender {
+                                            let _ = sender.send(false);
+                                        }
                                     }
                                 }
                             }
                             RelayEvent::Close => {
-                                let _ = ws_tx.close().await;
+                                // The closing handshake may never complete on a dead connection
+                                let _ = time::timeout(Some(Duration::from_secs(5)), ws_tx.close())
+                                    .await;
                                 relay.set_status(RelayStatus::Disconnected).await;
                                 tracing::info!("Disconnected from {}", url);
                                 break;
@@ -754,6 +1229,8 @@ impl Relay {
 
                     #[cfg(not(target_arch = "wasm32"))]
                     ping_abort_handle.abort();
+                    #[cfg(feature = "nip11")]
+                    document_abort_handle.abort();
                 });
 
                 let relay = self.clone();
@@ -761,6 +1238,9 @@ impl Relay {
                     tracing::debug!("Relay Message Thread Started");
 
                     async fn func(relay: &Relay, data: Vec<u8>) -> bool {
+                        #[cfg(feature = "debug")]
+                        relay.debug_frame(FrameDirection::Inbound, &data).await;
+
                         let size: usize = data.len();
                         let max_size: usize = relay.limits.messages.max_size as usize;
                         relay.stats.add_bytes_received(size);
@@ -772,6 +1252,31 @@ impl Relay {
                                         relay.url,
                                         msg
                                     );
+                                    relay.stats.new_message_received(&msg);
+                                    if let RawRelayMessage::Auth { challenge } = &msg {
+                                        relay.set_auth_challenge(challenge.clone()).await;
+                                    }
+                                    match &msg {
+                                        RawRelayMessage::Event {
+                                            subscription_id,
+                                            event,
+                                        } => {
+                                            if let Some(created_at) =
+                                                event.get("created_at").and_then(|c| c.as_u64())
+                                            {
+                                                relay
+                                                    .update_last_event_at(
+                                                        subscription_id,
+                                                        created_at,
+                                                    )
+                                                    .await;
+                                            }
+                                        }
+                                        RawRelayMessage::EndOfStoredEvents(subscription_id) => {
+                                            relay.set_eose(subscription_id).await;
+                                        }
+                                        _ => (),
+                                    }
                                     if let Err(err) = relay
                                         .pool_sender
                                         .send(RelayPoolMessage::ReceivedMsg {
@@ -862,6 +1367,7 @@ impl Relay {
                 }
             }
             Err(err) => {
+                self.consecutive_failures.fetch_add(1, Ordering::SeqCst);
                 self.set_status(RelayStatus::Disconnected).await;
                 tracing::error!("Impossible to connect to {}: {}", url, err);
             }
@@ -884,6 +1390,7 @@ impl Relay {
         if status.ne(&RelayStatus::Disconnected)
             && status.ne(&RelayStatus::Stopped)
             && status.ne(&RelayStatus::Terminated)
+            && status.ne(&RelayStatus::GaveUp)
         {
             self.send_relay_event(RelayEvent::Close, None)?;
         }
@@ -897,6 +1404,7 @@ impl Relay {
         if status.ne(&RelayStatus::Disconnected)
             && status.ne(&RelayStatus::Stopped)
             && status.ne(&RelayStatus::Terminated)
+            && status.ne(&RelayStatus::GaveUp)
         {
             self.send_relay_event(RelayEvent::Stop, None)?;
         }
@@ -910,6 +1418,7 @@ impl Relay {
         if status.ne(&RelayStatus::Disconnected)
             && status.ne(&RelayStatus::Stopped)
             && status.ne(&RelayStatus::Terminated)
+            && status.ne(&RelayStatus::GaveUp)
         {
             self.send_relay_event(RelayEvent::Terminate, None)?;
         }
@@ -989,9 +1498,73 @@ impl Relay {
     }
 
     /// Send event and wait for `OK` relay msg
+    ///
+    /// Return [`Error::Limitation`] if the event violates the relay limitation (NIP11).
     pub async fn send_event(&self, event: Event, opts: RelaySendOptions) -> Result<EventId, Error> {
+        if event.is_protected() && !self.is_authenticated() {
+            return Err(Error::NotAuthenticated);
+        }
+
+        self.message_limits()
+            .await
+            .check_event(&event)
+            .map_err(Error::Limitation)?;
+
         let id: EventId = event.id();
+        let msg: ClientMessage = ClientMessage::event(event);
+        let now = Instant::now();
+        let res = match self.send_event_msg(id, msg.clone(), opts).await {
+            // Send again when the relay require authentication (NIP42)
+            Err(Error::EventNotPublished(message))
+                if message.starts_with(AUTH_REQUIRED_PREFIX)
+                    && self.automatic_authentication.load(Ordering::SeqCst) =>
+            {
+                if self.wait_for_authentication(opts.timeout).await {
+                    self.send_event_msg(id, msg, opts).await
+                } else {
+                    Err(Error::EventNotPublished(message))
+                }
+            }
+            res => res,
+        };
+        if res.is_ok() {
+            self.stats.save_publish_latency(now.elapsed());
+        }
+        res
+    }
+
+    /// Authenticate to relay (NIP42)
+    ///
+    /// Send the `AUTH` event (see `EventBuilder::auth`) and wait for the `OK` message.
+    /// The authentication is valid until the relay disconnects.
+    ///
+    /// <https://github.com/nostr-protocol/nips/blob/master/42.md>
+    pub async fn auth(&self, event: Event, opts: RelaySendOptions) -> Result<(), Error> {
+        if let Err(e) = self
+            .send_event_msg(event.id(), ClientMessage::auth(event), opts)
+            .await
+        {
+            self.notify_pool(RelayPoolMessage::AuthenticationFailed {
+                relay_url: self.url(),
+                error: e.to_string(),
+            });
+            return Err(e);
+        }
+        self.authenticated.store(true, Ordering::SeqCst);
+        self.set_capability(RelayCapability::Auth, true).await;
+        self.notify_pool(RelayPoolMessage::Authenticated {
+            relay_url: self.url(),
+        });
+        Ok(())
+    }
 
+    /// Send `EVENT` or `AUTH` message and wait for the `OK` message
+    async fn send_event_msg(
+        &self,
+        id: EventId,
+        msg: ClientMessage,
+        opts: RelaySendOptions,
+    ) -> Result<EventId, Error> {
         if opts.skip_disconnected
             && !self.is_connected().await
             && self.stats.attempts() > 1
@@ -1003,7 +1576,7 @@ impl Relay {
         }
 
         time::timeout(Some(opts.timeout), async {
-            self.send_msg(ClientMessage::event(event), None).await?;
+            self.send_msg(msg, None).await?;
             let mut notifications = self.notification_sender.subscribe();
             while let Ok(notification) = notifications.recv().await {
                 match notification {
@@ -1028,7 +1601,8 @@ impl Relay {
                         if opts.skip_disconnected && relay_url == self.url {
                             if let RelayStatus::Disconnected
                             | RelayStatus::Stopped
-                            | RelayStatus::Terminated = status
+                            | RelayStatus::Terminated
+                            | RelayStatus::GaveUp = status
                             {
                                 return Err(Error::EventNotPublished(String::from(
                                     "relay not connected (status changed)",
@@ -1046,6 +1620,9 @@ impl Relay {
     }
 
     /// Send multiple [`Event`] at once
+    ///
+    /// The events that violate the relay limitation (NIP11) are not sent,
+    /// and reported as not published.
     pub async fn batch_event(
         &self,
         events: Vec<Event>,
@@ -1065,6 +1642,70 @@ impl Relay {
             )));
         }
 
+        // Skip the events violating the relay limitation, instead of failing the whole batch
+        let limits: MessageLimits = self.message_limits().await;
+        let mut rejected: HashMap<EventId, String> = HashMap::new();
+        let events: Vec<Event> = events
+            .into_iter()
+            .filter(|event| match limits.check_event(event) {
+                Ok(()) => true,
+                Err(e) => {
+                    rejected.insert(event.id(), Error::Limitation(e).to_string());
+                    false
+                }
+            })
+            .collect();
+
+        if events.is_empty() {
+            return Err(Error::EventsNotPublished(rejected));
+        }
+
+        let (mut published, mut not_published) =
+            self.send_events_batch(events.clone(), opts).await?;
+
+        // Send again the events rejected because the relay require authentication (NIP42)
+        let auth_required: Vec<Event> = events
+            .into_iter()
+            .filter(|event| {
+                not_published
+                    .get(&event.id())
+                    .map_or(false, |message| message.starts_with(AUTH_REQUIRED_PREFIX))
+            })
+            .collect();
+        if !auth_required.is_empty()
+            && self.automatic_authentication.load(Ordering::SeqCst)
+            && self.wait_for_authentication(opts.timeout).await
+        {
+            for event in auth_required.iter() {
+                not_published.remove(&event.id());
+            }
+            let (p, np) = self.send_events_batch(auth_required, opts).await?;
+            published.extend(p);
+            not_published.extend(np);
+        }
+
+        not_published.extend(rejected);
+
+        if !published.is_empty() && not_published.is_empty() {
+            Ok(())
+        } else if !published.is_empty() && !not_published.is_empty() {
+            Err(Error::PartialPublish {
+                published: published.into_iter().collect(),
+                not_published,
+            })
+        } else {
+            Err(Error::EventsNotPublished(not_published))
+        }
+    }
+
+    /// Send the events and wait for the `OK` messages
+    ///
+    /// Return the published and the not published events.
+    async fn send_events_batch(
+        &self,
+        events: Vec<Event>,
+        opts: RelaySendOptions,
+    ) -> Result<(HashSet<EventId>, HashMap<EventId, String>), Error> {
         let mut msgs: Vec<ClientMessage> = Vec::with_capacity(events.len());
         let mut missing: HashSet<EventId> = HashSet::new();
 
@@ -1101,7 +1742,8 @@ impl Relay {
                         if opts.skip_disconnected && relay_url == self.url {
                             if let RelayStatus::Disconnected
                             | RelayStatus::Stopped
-                            | RelayStatus::Terminated = status
+                            | RelayStatus::Terminated
+                            | RelayStatus::GaveUp = status
                             {
                                 return Err(Error::EventNotPublished(String::from(
                                     "relay not connected (status changed)",
@@ -1117,16 +1759,7 @@ impl Relay {
                 }
             }
 
-            if !published.is_empty() && not_published.is_empty() {
-                Ok(())
-            } else if !published.is_empty() && !not_published.is_empty() {
-                Err(Error::PartialPublish {
-                    published: published.into_iter().collect(),
-                    not_published,
-                })
-            } else {
-                Err(Error::EventsNotPublished(not_published))
-            }
+            Ok((published, not_published))
         })
         .await
         .ok_or(Error::Timeout)?
@@ -1142,7 +1775,13 @@ impl Relay {
 
         for (internal_id, sub) in subscriptions.into_iter() {
             if !sub.filters.is_empty() {
-                self.send_msg(ClientMessage::req(sub.id.clone(), sub.filters), wait)
+                self.notify_pool(RelayPoolMessage::Subscribed {
+                    relay_url: self.url(),
+                    subscription_id: sub.id.clone(),
+                });
+                let filters: Vec<Filter> = sub.resubscribe_filters();
+                sub.reset_eose();
+                self.send_msg(ClientMessage::req(sub.id.clone(), filters), wait)
                     .await?;
             } else {
                 tracing::warn!("Subscription '{internal_id}' has empty filters");
@@ -1165,12 +1804,33 @@ impl Relay {
             .subscription(&internal_id)
             .await
             .ok_or(Error::InternalIdNotFound)?;
-        self.send_msg(ClientMessage::req(sub.id, sub.filters), wait)
+        let filters: Vec<Filter> = sub.resubscribe_filters();
+        sub.reset_eose();
+        self.notify_pool(RelayPoolMessage::Subscribed {
+            relay_url: self.url(),
+            subscription_id: sub.id.clone(),
+        });
+        self.send_msg(ClientMessage::req(sub.id, filters), wait)
             .await?;
 
         Ok(())
     }
 
+    /// Send again the `REQ` of the active subscription with `id`
+    ///
+    /// Used after a `CLOSED` message (i.e. `auth-required:`).
+    pub(crate) async fn resubscribe_id(
+        &self,
+        id: &SubscriptionId,
+        wait: Option<Duration>,
+    ) -> Result<(), Error> {
+        let (internal_id, _) = self
+            .subscription_by_id(id)
+            .await
+            .ok_or(Error::InternalIdNotFound)?;
+        self.resubscribe(internal_id, wait).await
+    }
+
     /// Subscribe to filters
     ///
     /// Internal Subscription ID set to `InternalSubscriptionId::Default`
@@ -1184,6 +1844,8 @@ impl Relay {
     }
 
     /// Subscribe with custom internal ID
+    ///
+    /// Overlapping filters are combined, to send fewer filters in the `REQ` (see [`Filter::simplify`]).
     pub async fn subscribe_with_internal_id(
         &self,
         internal_id: InternalSubscriptionId,
@@ -1198,6 +1860,17 @@ impl Relay {
             return Err(Error::FiltersEmpty);
         }
 
+        let filters: Vec<Filter> = Filter::simplify(filters);
+
+        // Check the relay limitation, before storing the subscription
+        let limits: MessageLimits = self.message_limits().await;
+        let size: usize = ClientMessage::req(SubscriptionId::generate(), filters.clone())
+            .as_json()
+            .len();
+        limits
+            .check_req(filters.len(), size)
+            .map_err(Error::Limitation)?;
+
         self.update_subscription_filters(internal_id.clone(), filters)
             .await;
         self.resubscribe(internal_id, wait).await
@@ -1221,10 +1894,12 @@ impl Relay {
             return Err(Error::ReadDisabled);
         }
 
-        let mut subscriptions = self.subscriptions().await;
-        let subscription = subscriptions
-            .remove(&internal_id)
-            .ok_or(Error::InternalIdNotFound)?;
+        let subscription = {
+            let mut subscriptions = self.subscriptions.write().await;
+            subscriptions
+                .remove(&internal_id)
+                .ok_or(Error::InternalIdNotFound)?
+        };
         self.send_msg(ClientMessage::close(subscription.id), wait)
             .await?;
         Ok(())
@@ -1236,7 +1911,11 @@ impl Relay {
             return Err(Error::ReadDisabled);
         }
 
-        let subscriptions = self.subscriptions().await;
+        // Remove the subscriptions, to not subscribe again on reconnection
+        let subscriptions = {
+            let mut subscriptions = self.subscriptions.write().await;
+            std::mem::take(&mut *subscriptions)
+        };
 
         for sub in subscriptions.into_values() {
             self.send_msg(ClientMessage::close(sub.id.clone()), wait)
@@ -1268,7 +1947,18 @@ impl Relay {
 
         let mut notifications = self.notification_sender.subscribe();
         time::timeout(Some(timeout), async {
-            while let Ok(notification) = notifications.recv().await {
+            loop {
+                let notification = match notifications.recv().await {
+                    Ok(notification) => notification,
+                    Err(RecvError::Lagged(skipped)) => {
+                        tracing::warn!(
+                            "Skipped {skipped} notifications while waiting events for subscription {id} from {}",
+                            self.url
+                        );
+                        continue;
+                    }
+                    Err(RecvError::Closed) => break,
+                };
                 if let RelayPoolNotification::Message { message, .. } = notification {
                     match message {
                         RelayMessage::Event {
@@ -1301,6 +1991,14 @@ impl Relay {
                                 }
                             }
                         }
+                        RelayMessage::Closed {
+                            subscription_id,
+                            message,
+                        } => {
+                            if subscription_id.eq(&id) {
+                                return Err(Error::SubscriptionClosed(message));
+                            }
+                        }
                         RelayMessage::Ok { .. } => (),
                         _ => {
                             tracing::debug!(
@@ -1311,13 +2009,26 @@ impl Relay {
                     };
                 }
             }
+
+            Ok(())
         })
         .await
-        .ok_or(Error::Timeout)?;
+        .ok_or(Error::Timeout)??;
 
         if let FilterOptions::WaitDurationAfterEOSE(duration) = opts {
             time::timeout(Some(duration), async {
-                while let Ok(notification) = notifications.recv().await {
+                loop {
+                    let notification = match notifications.recv().await {
+                        Ok(notification) => notification,
+                        Err(RecvError::Lagged(skipped)) => {
+                            tracing::warn!(
+                                "Skipped {skipped} notifications while waiting events for subscription {id} from {}",
+                                self.url
+                            );
+                            continue;
+                        }
+                        Err(RecvError::Closed) => break,
+                    };
                     if let RelayPoolNotification::Message {
                         message:
                             RelayMessage::Event {
@@ -1340,7 +2051,7 @@ impl Relay {
     }
 
     /// Get events of filters with custom callback
-    async fn get_events_of_with_callback<F>(
+    pub(crate) async fn get_events_of_with_callback<F>(
         &self,
         filters: Vec<Filter>,
         timeout: Duration,
@@ -1354,20 +2065,164 @@ impl Relay {
             return Err(Error::ReadDisabled);
         }
 
+        self.check_filters_capabilities(&filters).await?;
+
+        // Split filters to fit the relay limitation
+        let limits: ReqLimits = self.req_limits().await;
+        for chunk in limits.chunks(filters).into_iter() {
+            match chunk {
+                ReqChunk::Single(filters) => {
+                    self.req_and_handle_events_of(filters, timeout, opts, &callback)
+                        .await?
+                }
+                ReqChunk::Paginated {
+                    filter,
+                    total,
+                    page,
+                } => {
+                    self.paginated_events_of(*filter, total, page, timeout, opts, &callback)
+                        .await?
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn req_and_handle_events_of<F>(
+        &self,
+        filters: Vec<Filter>,
+        timeout: Duration,
+        opts: FilterOptions,
+        callback: impl Fn(Event) -> F,
+    ) -> Result<(), Error>
+    where
+        F: Future<Output = ()>,
+    {
         let id = SubscriptionId::generate();
 
-        self.send_msg(ClientMessage::req(id.clone(), filters), None)
-            .await?;
+        // Close the subscription also if this future is dropped (i.e. cancelled) or fails,
+        // including while the REQ is still being sent
+        let guard = CloseOnDrop::new(self.clone(), id.clone());
 
-        self.handle_events_of(id.clone(), timeout, opts, callback)
+        self.send_msg(ClientMessage::req(id.clone(), filters.clone()), None)
             .await?;
 
+        match self
+            .handle_events_of(id.clone(), timeout, opts, &callback)
+            .await
+        {
+            // Request again when the relay require authentication (NIP42)
+            Err(Error::SubscriptionClosed(message))
+                if message.starts_with(AUTH_REQUIRED_PREFIX)
+                    && self.automatic_authentication.load(Ordering::SeqCst) =>
+            {
+                if !self.wait_for_authentication(timeout).await {
+                    return Err(Error::SubscriptionClosed(message));
+                }
+                self.send_msg(ClientMessage::req(id.clone(), filters), None)
+                    .await?;
+                self.handle_events_of(id.clone(), timeout, opts, &callback)
+                    .await?;
+            }
+            res => res?,
+        }
+
         // Unsubscribe
+        guard.disarm();
         self.send_msg(ClientMessage::close(id), None).await?;
 
         Ok(())
     }
 
+    /// Request `total` events of filter, in pages of max `page` events
+    ///
+    /// Every page is requested with `until` set to the oldest event received so far:
+    /// events at the page boundary are deduplicated.
+    async fn paginated_events_of<F>(
+        &self,
+        mut filter: Filter,
+        total: usize,
+        page: usize,
+        timeout: Duration,
+        opts: FilterOptions,
+        callback: impl Fn(Event) -> F,
+    ) -> Result<(), Error>
+    where
+        F: Future<Output = ()>,
+    {
+        let seen: Mutex<HashSet<EventId>> = Mutex::new(HashSet::new());
+        let oldest: AtomicU64 = AtomicU64::new(u64::MAX);
+        let mut remaining: usize = total;
+
+        while remaining > 0 {
+            let limit: usize = cmp::min(remaining, page);
+            filter.limit = Some(limit);
+
+            let returned: AtomicUsize = AtomicUsize::new(0);
+            let received: AtomicUsize = AtomicUsize::new(0);
+            let seen_ref = &seen;
+            let oldest_ref = &oldest;
+            let returned_ref = &returned;
+            let received_ref = &received;
+            let callback = &callback;
+            self.req_and_handle_events_of(
+                vec![filter.clone()],
+                timeout,
+                opts,
+                move |event| async move {
+                    returned_ref.fetch_add(1, Ordering::SeqCst);
+                    let is_new: bool = seen_ref.lock().await.insert(event.id());
+                    if is_new {
+                        received_ref.fetch_add(1, Ordering::SeqCst);
+                        oldest_ref.fetch_min(event.created_at().as_u64(), Ordering::SeqCst);
+                        callback(event).await;
+                    }
+                },
+            )
+            .await?;
+
+            let returned: usize = returned.load(Ordering::SeqCst);
+            let received: usize = received.load(Ordering::SeqCst);
+            remaining = remaining.saturating_sub(received);
+
+            // Relay has no more events or no progress
+            if returned < limit || received == 0 {
+                break;
+            }
+
+            tracing::debug!(
+                "Requesting next page of events from {} ({remaining} remaining)",
+                self.url
+            );
+            filter.until = Some(Timestamp::from(oldest.load(Ordering::SeqCst)));
+        }
+
+        Ok(())
+    }
+
+    async fn message_limits(&self) -> MessageLimits {
+        #[cfg(feature = "nip11")]
+        {
+            let document = self.document.read().await;
+            MessageLimits::from_document(&document)
+        }
+
+        #[cfg(not(feature = "nip11"))]
+        MessageLimits::default()
+    }
+
+    async fn req_limits(&self) -> ReqLimits {
+        #[cfg(feature = "nip11")]
+        {
+            let document = self.document.read().await;
+            ReqLimits::from_document(&document)
+        }
+
+        #[cfg(not(feature = "nip11"))]
+        ReqLimits::default()
+    }
+
     /// Get events of filters
     ///
     /// Get events from local database and relay
@@ -1393,51 +2248,37 @@ impl Relay {
 
     /// Request events of filter. All events will be sent to notification listener,
     /// until the EOSE "end of stored events" message is received from the relay.
-    pub fn req_events_of(&self, filters: Vec<Filter>, timeout: Duration, opts: FilterOptions) {
+    ///
+    /// Return a [`ReqHandle`] to track completion or cancel the request.
+    pub async fn req_events_of(
+        &self,
+        filters: Vec<Filter>,
+        timeout: Duration,
+        opts: FilterOptions,
+    ) -> ReqHandle {
         if !self.opts.get_read() {
             tracing::error!("{}", Error::ReadDisabled);
         }
 
-        let relay = self.clone();
-        thread::spawn(async move {
-            let id = SubscriptionId::generate();
-
-            // Subscribe
-            if let Err(e) = relay
-                .send_msg(ClientMessage::req(id.clone(), filters), None)
-                .await
-            {
-                tracing::error!(
-                    "Impossible to send REQ to {}: {}",
-                    relay.url(),
-                    e.to_string()
-                );
-            };
-
-            if let Err(e) = relay
-                .handle_events_of(id.clone(), timeout, opts, |_| async {})
-                .await
-            {
-                tracing::error!("{e}");
-            }
-
-            // Unsubscribe
-            if let Err(e) = relay.send_msg(ClientMessage::close(id), None).await {
-                tracing::error!(
-                    "Impossible to close subscription with {}: {}",
-                    relay.url(),
-                    e.to_string()
-                );
-            }
-        });
+        let handle = ReqHandle::new();
+        handle.spawn(self.clone(), filters, timeout, opts).await;
+        handle
     }
 
     /// Count events of filters
+    ///
+    /// Return [`Error::UnsupportedCapability`] if the relay is known to not support `COUNT` (NIP45).
     pub async fn count_events_of(
         &self,
         filters: Vec<Filter>,
         timeout: Duration,
     ) -> Result<usize, Error> {
+        if self.supports(RelayCapability::Count).await == Some(false) {
+            return Err(Error::UnsupportedCapability(RelayCapability::Count));
+        }
+
+        self.check_filters_capabilities(&filters).await?;
+
         let id = SubscriptionId::generate();
         self.send_msg(ClientMessage::count(id.clone(), filters), None)
             .await?;
@@ -1473,6 +2314,9 @@ impl Relay {
     }
 
     /// Negentropy reconciliation
+    ///
+    /// Return [`Error::NegentropyNotSupported`] without contacting the relay
+    /// if it's already known to not support negentropy.
     pub async fn reconcile(
         &self,
         filter: Filter,
@@ -1483,6 +2327,10 @@ impl Relay {
             return Err(Error::ReadDisabled);
         }
 
+        if self.supports(RelayCapability::Negentropy).await == Some(false) {
+            return Err(Error::NegentropyNotSupported);
+        }
+
         if !self.is_connected().await
             && self.stats.attempts() > 1
             && self.stats.uptime() < MIN_UPTIME
@@ -1511,7 +2359,7 @@ impl Relay {
         let mut temp_notifications = self.notification_sender.subscribe();
 
         // Check if negentropy it's supported
-        time::timeout(Some(opts.initial_timeout), async {
+        let res: Result<(), Error> = time::timeout(Some(opts.initial_timeout), async {
             while let Ok(notification) = temp_notifications.recv().await {
                 if let RelayPoolNotification::Message { relay_url, message } = notification {
                     if relay_url == self.url {
@@ -1549,7 +2397,20 @@ impl Relay {
             Ok::<(), Error>(())
         })
         .await
-        .ok_or(Error::Timeout)??;
+        .ok_or(Error::Timeout)?;
+
+        match res {
+            Ok(()) | Err(Error::NegentropyReconciliation(..)) => {
+                self.set_capability(RelayCapability::Negentropy, true).await
+            }
+            Err(Error::NegentropyNotSupported) => {
+                self.set_capability(RelayCapability::Negentropy, false)
+                    .await
+            }
+            Err(..) => (),
+        };
+
+        res?;
 
         while let Ok(notification) = notifications.recv().await {
             match notification {
@@ -1621,10 +2482,10 @@ impl Relay {
                                                 self.url
                                             );
                                             self.send_msg(
-                                                ClientMessage::NegMsg {
-                                                    subscription_id: sub_id.clone(),
-                                                    message: query.to_hex(),
-                                                },
+                                                ClientMessage::neg_msg(
+                                                    sub_id.clone(),
+                                                    query.to_hex(),
+                                                ),
                                                 None,
                                             )
                                             .await?;
@@ -1671,6 +2532,10 @@ impl Relay {
 
     /// Check if relay support negentropy protocol
     pub async fn support_negentropy(&self) -> Result<bool, Error> {
+        if let Some(supported) = self.supports(RelayCapability::Negentropy).await {
+            return Ok(supported);
+        }
+
         let pk = Keys::generate();
         let filter = Filter::new().author(pk.public_key());
         match self
@@ -2,48 +2,75 @@
 // Copyright (c) 2023-2024 Rust Nostr Developers
 // Distributed under the MIT software license
 
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
 #[cfg(not(target_arch = "wasm32"))]
 use std::net::SocketAddr;
-use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+#[cfg(feature = "debug")]
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 
+use nostr::secp256k1::XOnlyPublicKey;
+use nostr::{Event, Kind, Url};
+use serde::{Deserialize, Serialize};
+
 use crate::client::options::DEFAULT_SEND_TIMEOUT;
 
-pub const DEFAULT_RETRY_SEC: u64 = 10;
-pub const MIN_RETRY_SEC: u64 = 5;
-pub const MAX_ADJ_RETRY_SEC: u64 = 60;
+#[cfg(not(target_arch = "wasm32"))]
+pub(super) const DEFAULT_PING_INTERVAL: Duration = Duration::from_secs(55);
+#[cfg(not(target_arch = "wasm32"))]
+pub(super) const DEFAULT_PONG_TIMEOUT: Duration = Duration::from_secs(10);
+#[cfg(not(target_arch = "wasm32"))]
+pub(super) const MIN_PING_INTERVAL: Duration = Duration::from_secs(5);
+#[cfg(not(target_arch = "wasm32"))]
+pub(super) const MIN_PONG_TIMEOUT: Duration = Duration::from_secs(1);
+pub(super) const MIN_RECONNECT_DELAY: Duration = Duration::from_secs(5);
 
 /// [`Relay`](super::Relay) options
 #[derive(Debug, Clone)]
 pub struct RelayOptions {
-    /// Proxy
+    /// Proxy (default: inherit the client proxy)
     #[cfg(not(target_arch = "wasm32"))]
-    pub proxy: Option<SocketAddr>,
+    pub proxy: RelayProxy,
     /// Allow/disallow read actions (default: true)
     read: Arc<AtomicBool>,
     /// Allow/disallow write actions (default: true)
     write: Arc<AtomicBool>,
     /// Enable/disable auto reconnection (default: true)
     reconnect: Arc<AtomicBool>,
-    /// Retry connection time (default: 10 sec)
-    ///
-    /// Are allowed values `>=` 5 secs
-    retry_sec: Arc<AtomicU64>,
-    /// Automatically adjust retry seconds based on success/attempts (default: true)
-    adjust_retry_sec: Arc<AtomicBool>,
+    /// Reconnection backoff policy
+    pub reconnect_policy: ReconnectPolicy,
+    /// Interval between keepalive pings (default: 55 sec, min: 5 sec)
+    #[cfg(not(target_arch = "wasm32"))]
+    pub ping_interval: Duration,
+    /// Max time to wait for the pong, before marking the connection as dead (default: 10 sec, min: 1 sec)
+    #[cfg(not(target_arch = "wasm32"))]
+    pub pong_timeout: Duration,
+    /// Outgoing message rate limits (default: None, unlimited)
+    pub rate_limits: Option<RateLimits>,
+    /// Max number of raw frames kept in the debug capture (default: 0, disabled)
+    #[cfg(feature = "debug")]
+    debug_capture: Arc<AtomicUsize>,
 }
 
 impl Default for RelayOptions {
     fn default() -> Self {
         Self {
             #[cfg(not(target_arch = "wasm32"))]
-            proxy: None,
+            proxy: RelayProxy::default(),
             read: Arc::new(AtomicBool::new(true)),
             write: Arc::new(AtomicBool::new(true)),
             reconnect: Arc::new(AtomicBool::new(true)),
-            retry_sec: Arc::new(AtomicU64::new(DEFAULT_RETRY_SEC)),
-            adjust_retry_sec: Arc::new(AtomicBool::new(true)),
+            reconnect_policy: ReconnectPolicy::default(),
+            #[cfg(not(target_arch = "wasm32"))]
+            ping_interval: DEFAULT_PING_INTERVAL,
+            #[cfg(not(target_arch = "wasm32"))]
+            pong_timeout: DEFAULT_PONG_TIMEOUT,
+            rate_limits: None,
+            #[cfg(feature = "debug")]
+            debug_capture: Arc::new(AtomicUsize::new(0)),
         }
     }
 }
@@ -55,8 +82,20 @@ impl RelayOptions {
     }
 
     /// Set proxy
+    ///
+    /// If `None`, connect directly, also if the client has a proxy.
     #[cfg(not(target_arch = "wasm32"))]
     pub fn proxy(mut self, proxy: Option<SocketAddr>) -> Self {
+        self.proxy = match proxy {
+            Some(addr) => RelayProxy::Socks5(addr),
+            None => RelayProxy::Direct,
+        };
+        self
+    }
+
+    /// Set [`RelayProxy`]
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn relay_proxy(mut self, proxy: RelayProxy) -> Self {
         self.proxy = proxy;
         self
     }
@@ -118,53 +157,234 @@ impl RelayOptions {
             .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |_| Some(reconnect));
     }
 
-    /// Set retry seconds option
-    pub fn retry_sec(self, retry_sec: u64) -> Self {
-        let retry_sec = if retry_sec >= MIN_RETRY_SEC {
-            retry_sec
-        } else {
-            DEFAULT_RETRY_SEC
-        };
+    /// Set reconnection backoff policy
+    pub fn reconnect_policy(self, policy: ReconnectPolicy) -> Self {
         Self {
-            retry_sec: Arc::new(AtomicU64::new(retry_sec)),
+            reconnect_policy: policy,
             ..self
         }
     }
 
-    pub(crate) fn get_retry_sec(&self) -> u64 {
-        self.retry_sec.load(Ordering::SeqCst)
+    /// Set interval between keepalive pings
+    ///
+    /// Values lower than 5 secs are raised to 5 secs.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn ping_interval(self, interval: Duration) -> Self {
+        Self {
+            ping_interval: interval,
+            ..self
+        }
     }
 
-    /// Set retry_sec option
-    pub fn update_retry_sec(&self, retry_sec: u64) {
-        if retry_sec >= MIN_RETRY_SEC {
-            let _ = self
-                .retry_sec
-                .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |_| Some(retry_sec));
-        } else {
-            tracing::warn!("Relay options: retry_sec it's less then the minimum value allowed (min: {MIN_RETRY_SEC} secs)");
+    /// Set max time to wait for the pong
+    ///
+    /// If the relay doesn't reply in time, the connection is considered dead:
+    /// the relay is marked as disconnected and the [`ReconnectPolicy`] is applied.
+    /// Capped to the ping interval. Values lower than 1 sec are raised to 1 sec.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn pong_timeout(self, timeout: Duration) -> Self {
+        Self {
+            pong_timeout: timeout,
+            ..self
         }
     }
 
-    /// Automatically adjust retry seconds based on success/attempts (default: true)
-    pub fn adjust_retry_sec(self, adjust_retry_sec: bool) -> Self {
+    /// Limit the outgoing messages (default: None, unlimited)
+    ///
+    /// Messages exceeding the limits are queued and sent as soon as allowed,
+    /// to avoid `rate-limited` rejections. Applied from the next connection.
+    pub fn rate_limits(self, limits: Option<RateLimits>) -> Self {
         Self {
-            adjust_retry_sec: Arc::new(AtomicBool::new(adjust_retry_sec)),
+            rate_limits: limits,
             ..self
         }
     }
 
-    pub(crate) fn get_adjust_retry_sec(&self) -> bool {
-        self.adjust_retry_sec.load(Ordering::SeqCst)
+    /// Keep the last `size` raw frames in the debug capture (default: 0, disabled)
+    ///
+    /// See [`Relay::debug_capture`](super::Relay::debug_capture).
+    #[cfg(feature = "debug")]
+    pub fn debug_capture(self, size: usize) -> Self {
+        Self {
+            debug_capture: Arc::new(AtomicUsize::new(size)),
+            ..self
+        }
     }
 
-    /// Set adjust_retry_sec option
-    pub fn update_adjust_retry_sec(&self, adjust_retry_sec: bool) {
+    #[cfg(feature = "debug")]
+    pub(crate) fn get_debug_capture(&self) -> usize {
+        self.debug_capture.load(Ordering::SeqCst)
+    }
+
+    /// Set debug capture size
+    #[cfg(feature = "debug")]
+    pub fn update_debug_capture(&self, size: usize) {
         let _ = self
-            .adjust_retry_sec
-            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |_| {
-                Some(adjust_retry_sec)
-            });
+            .debug_capture
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |_| Some(size));
+    }
+}
+
+/// Proxy of a [`Relay`](super::Relay) connection
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum RelayProxy {
+    /// Use the client proxy, if any (see [`Options::proxy`](crate::Options::proxy))
+    #[default]
+    Inherit,
+    /// Connect directly, also if the client has a proxy
+    Direct,
+    /// Connect through a SOCKS5 proxy (i.e. Tor)
+    Socks5(SocketAddr),
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl RelayProxy {
+    /// Get proxy address
+    ///
+    /// Return `None` if the connection is direct or the proxy is inherited.
+    pub fn addr(&self) -> Option<SocketAddr> {
+        match self {
+            Self::Socks5(addr) => Some(*addr),
+            Self::Inherit | Self::Direct => None,
+        }
+    }
+}
+
+/// Reconnection backoff policy of a [`Relay`](super::Relay)
+///
+/// After `n` consecutive failed attempts, the next one is delayed by `initial * multiplier^(n - 1)`,
+/// capped to `max` and randomized by `±jitter`. The delay is never less than 5 secs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReconnectPolicy {
+    /// Delay after the first failed attempt (default: 10 sec)
+    pub initial: Duration,
+    /// Max delay between attempts (default: 60 sec)
+    pub max: Duration,
+    /// Growth factor of the delay, for every failed attempt (default: 2)
+    pub multiplier: u32,
+    /// Max random jitter added or subtracted to the delay (default: 1 sec)
+    pub jitter: Duration,
+    /// Restart the backoff from `initial` after a successful connection (default: true)
+    ///
+    /// If disabled, the delay keeps growing with the total number of failed attempts.
+    pub reset_on_success: bool,
+    /// Give up after N consecutive failed attempts (default: None, never give up)
+    ///
+    /// When the limit is reached, the relay status is set to [`RelayStatus::GaveUp`](super::RelayStatus::GaveUp)
+    /// and a [`RelayHookEvent::PermanentFailure`](super::RelayHookEvent::PermanentFailure) is dispatched.
+    pub max_attempts: Option<usize>,
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        Self {
+            initial: Duration::from_secs(10),
+            max: Duration::from_secs(60),
+            multiplier: 2,
+            jitter: Duration::from_secs(1),
+            reset_on_success: true,
+            max_attempts: None,
+        }
+    }
+}
+
+impl ReconnectPolicy {
+    /// New default [`ReconnectPolicy`]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Retry with a fixed delay, without backoff and jitter
+    pub fn fixed(delay: Duration) -> Self {
+        Self {
+            initial: delay,
+            max: delay,
+            multiplier: 1,
+            jitter: Duration::ZERO,
+            ..Self::default()
+        }
+    }
+
+    /// Set delay after the first failed attempt
+    pub fn initial(self, initial: Duration) -> Self {
+        Self { initial, ..self }
+    }
+
+    /// Set max delay between attempts
+    pub fn max(self, max: Duration) -> Self {
+        Self { max, ..self }
+    }
+
+    /// Set growth factor of the delay
+    pub fn multiplier(self, multiplier: u32) -> Self {
+        Self { multiplier, ..self }
+    }
+
+    /// Set max random jitter
+    pub fn jitter(self, jitter: Duration) -> Self {
+        Self { jitter, ..self }
+    }
+
+    /// Restart the backoff after a successful connection
+    pub fn reset_on_success(self, reset_on_success: bool) -> Self {
+        Self {
+            reset_on_success,
+            ..self
+        }
+    }
+
+    /// Give up after N consecutive failed attempts
+    pub fn max_attempts(self, max_attempts: Option<usize>) -> Self {
+        Self {
+            max_attempts,
+            ..self
+        }
+    }
+
+    /// Delay after `failures` failed attempts, without jitter (min 5 secs)
+    pub fn delay(&self, failures: usize) -> Duration {
+        let exp: u32 = u32::try_from(failures.saturating_sub(1)).unwrap_or(u32::MAX);
+        let delay: Duration = self
+            .multiplier
+            .checked_pow(exp)
+            .and_then(|factor| self.initial.checked_mul(factor))
+            .unwrap_or(self.max);
+        delay.min(self.max).max(MIN_RECONNECT_DELAY)
+    }
+}
+
+/// Outgoing message rate limits of a [`Relay`](super::Relay)
+///
+/// Implemented as token buckets: bursts up to the limit are allowed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RateLimits {
+    /// Max number of messages per second, of any type (default: None, unlimited)
+    pub messages_per_sec: Option<u32>,
+    /// Max number of `EVENT` messages per minute (default: None, unlimited)
+    pub events_per_min: Option<u32>,
+}
+
+impl RateLimits {
+    /// New unlimited [`RateLimits`]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Max number of messages per second, of any type
+    pub fn messages_per_sec(self, max: u32) -> Self {
+        Self {
+            messages_per_sec: Some(max),
+            ..self
+        }
+    }
+
+    /// Max number of `EVENT` messages per minute
+    pub fn events_per_min(self, max: u32) -> Self {
+        Self {
+            events_per_min: Some(max),
+            ..self
+        }
     }
 }
 
@@ -223,6 +443,223 @@ pub enum FilterOptions {
     WaitDurationAfterEOSE(Duration),
 }
 
+/// Order of the events returned by [`GetEventsOptions`] queries
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EventsOrder {
+    /// Order in which the events have been collected (stored events first, then the events received from relays)
+    #[default]
+    Insertion,
+    /// Oldest first
+    CreatedAtAsc,
+    /// Newest first
+    CreatedAtDesc,
+}
+
+/// Options to get events of filters
+#[derive(Debug, Clone, Copy, Default)]
+pub struct GetEventsOptions {
+    /// Options of the single relay queries
+    pub filter: FilterOptions,
+    /// Order of the results (default: insertion order)
+    pub order: EventsOrder,
+    /// Keep only the newest event of every replaceable coordinate (default: false)
+    pub collapse_replaceable: bool,
+    /// Max number of events returned, across all relays (default: unlimited)
+    pub max_events: Option<usize>,
+}
+
+impl GetEventsOptions {
+    /// New default [`GetEventsOptions`]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set [`FilterOptions`]
+    pub fn filter(self, filter: FilterOptions) -> Self {
+        Self { filter, ..self }
+    }
+
+    /// Set results order
+    pub fn order(self, order: EventsOrder) -> Self {
+        Self { order, ..self }
+    }
+
+    /// Keep only the newest event of every replaceable coordinate
+    pub fn collapse_replaceable(self, collapse_replaceable: bool) -> Self {
+        Self {
+            collapse_replaceable,
+            ..self
+        }
+    }
+
+    /// Max number of events returned, across all relays
+    ///
+    /// Applied after ordering: with [`EventsOrder::CreatedAtDesc`] the newest events are kept.
+    pub fn max_events(self, max_events: Option<usize>) -> Self {
+        Self { max_events, ..self }
+    }
+
+    /// Collapse, sort and truncate the events
+    pub(crate) fn apply(&self, mut events: Vec<Event>) -> Vec<Event> {
+        if self.collapse_replaceable {
+            events = collapse_replaceable(events);
+        }
+
+        match self.order {
+            EventsOrder::Insertion => (),
+            EventsOrder::CreatedAtAsc => events.sort_by_key(|e| e.created_at()),
+            EventsOrder::CreatedAtDesc => events.sort_by_key(|e| Reverse(e.created_at())),
+        }
+
+        if let Some(max_events) = self.max_events {
+            events.truncate(max_events);
+        }
+
+        events
+    }
+}
+
+/// Options to rebroadcast stored events
+#[derive(Debug, Clone, Default)]
+pub struct RebroadcastOptions {
+    /// Relays to send the events to (default: all the relays of the pool)
+    pub relays: Option<HashSet<Url>>,
+    /// Send options
+    pub send: RelaySendOptions,
+}
+
+impl RebroadcastOptions {
+    /// New default [`RebroadcastOptions`]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Send the events only to these relays
+    ///
+    /// Relays not in the pool are skipped.
+    pub fn relays<I>(self, urls: I) -> Self
+    where
+        I: IntoIterator<Item = Url>,
+    {
+        Self {
+            relays: Some(urls.into_iter().collect()),
+            ..self
+        }
+    }
+
+    /// Set [`RelaySendOptions`]
+    pub fn send(self, send: RelaySendOptions) -> Self {
+        Self { send, ..self }
+    }
+}
+
+/// Keep only the newest event of every replaceable coordinate, preserving the order
+///
+/// With the same `created_at`, the event with the lowest ID is kept (NIP01).
+fn collapse_replaceable(events: Vec<Event>) -> Vec<Event> {
+    let mut collapsed: Vec<Event> = Vec::with_capacity(events.len());
+    let mut coordinates: HashMap<(Kind, XOnlyPublicKey, String), usize> = HashMap::new();
+    for event in events.into_iter() {
+        let identifier: String = if event.is_replaceable() {
+            String::new()
+        } else if event.is_parameterized_replaceable() {
+            event.identifier().unwrap_or_default().to_string()
+        } else {
+            collapsed.push(event);
+            continue;
+        };
+
+        let coordinate = (event.kind(), event.author(), identifier);
+        match coordinates.get(&coordinate) {
+            Some(index) => {
+                let current: &Event = &collapsed[*index];
+                if (event.created_at(), current.id()) > (current.created_at(), event.id()) {
+                    collapsed[*index] = event;
+                }
+            }
+            None => {
+                coordinates.insert(coordinate, collapsed.len());
+                collapsed.push(event);
+            }
+        }
+    }
+    collapsed
+}
+
+/// `REQ` distribution strategy across relays
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ReqDistribution {
+    /// Send the whole filters to every relay
+    #[default]
+    Broadcast,
+    /// Partition (round-robin) the authors across relays for the filters with more than `threshold` authors
+    ///
+    /// Cut the bandwidth roughly by the number of relays (e.g. initial sync of big contact lists),
+    /// at the cost of redundancy: the events of an author are requested only to one relay.
+    PartitionAuthors {
+        /// Min number of authors to partition a filter
+        threshold: usize,
+    },
+}
+
+/// Subscription overflow policy
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// Drop the events exceeding the limit
+    #[default]
+    Drop,
+    /// Save the events exceeding the limit into the database, without sending notifications
+    Persist,
+    /// Close the subscription
+    Close,
+}
+
+/// Per-subscription limits
+///
+/// Bound the events received for each subscription, to avoid that a misbehaving relay
+/// flood the notifications (and the memory) of the client.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SubscriptionLimits {
+    /// Max number of events per subscription in `interval` (default: 5000)
+    pub max_events: usize,
+    /// Counting interval (default: 60 secs)
+    pub interval: Duration,
+    /// Policy applied to the events exceeding the limit (default: drop)
+    pub policy: OverflowPolicy,
+}
+
+impl Default for SubscriptionLimits {
+    fn default() -> Self {
+        Self {
+            max_events: 5000,
+            interval: Duration::from_secs(60),
+            policy: OverflowPolicy::default(),
+        }
+    }
+}
+
+impl SubscriptionLimits {
+    /// New default limits
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Max number of events per subscription in `interval`
+    pub fn max_events(self, max_events: usize) -> Self {
+        Self { max_events, ..self }
+    }
+
+    /// Counting interval
+    pub fn interval(self, interval: Duration) -> Self {
+        Self { interval, ..self }
+    }
+
+    /// Policy applied to the events exceeding the limit
+    pub fn policy(self, policy: OverflowPolicy) -> Self {
+        Self { policy, ..self }
+    }
+}
+
 /// Relay Pool Options
 #[derive(Debug, Clone, Copy)]
 pub struct RelayPoolOptions {
@@ -232,6 +669,14 @@ pub struct RelayPoolOptions {
     pub task_channel_size: usize,
     /// Shutdown on [RelayPool](super::pool::RelayPool) drop
     pub shutdown_on_drop: bool,
+    /// `REQ` distribution strategy for `get_events_of` and `req_events_of` (default: broadcast)
+    pub req_distribution: ReqDistribution,
+    /// Per-subscription limits (default: `None`, unbounded)
+    pub subscription_limits: Option<SubscriptionLimits>,
+    /// Number of relays that must complete `get_events_of` before returning (default: `None`, wait for all)
+    pub req_quorum: Option<usize>,
+    /// Drop the expired events (NIP40) received from relays, without notifying them (default: true)
+    pub drop_expired_events: bool,
 }
 
 impl Default for RelayPoolOptions {
@@ -240,6 +685,10 @@ impl Default for RelayPoolOptions {
             notification_channel_size: 1024,
             task_channel_size: 1024,
             shutdown_on_drop: false,
+            req_distribution: ReqDistribution::default(),
+            subscription_limits: None,
+            req_quorum: None,
+            drop_expired_events: true,
         }
     }
 }
@@ -257,6 +706,43 @@ impl RelayPoolOptions {
             ..self
         }
     }
+
+    /// `REQ` distribution strategy for `get_events_of` and `req_events_of` (default: broadcast)
+    pub fn req_distribution(self, value: ReqDistribution) -> Self {
+        Self {
+            req_distribution: value,
+            ..self
+        }
+    }
+
+    /// Per-subscription limits
+    pub fn subscription_limits(self, value: Option<SubscriptionLimits>) -> Self {
+        Self {
+            subscription_limits: value,
+            ..self
+        }
+    }
+
+    /// Number of relays that must complete `get_events_of` before returning (default: `None`, wait for all)
+    ///
+    /// Once the quorum is reached, the queries still running on the other relays are cancelled and their `REQ`s closed.
+    /// Ignored with [`ReqDistribution::PartitionAuthors`], since every relay receives different filters.
+    pub fn req_quorum(self, value: Option<usize>) -> Self {
+        Self {
+            req_quorum: value,
+            ..self
+        }
+    }
+
+    /// Drop the expired events (NIP40) received from relays, without notifying them (default: true)
+    ///
+    /// <https://github.com/nostr-protocol/nips/blob/master/40.md>
+    pub fn drop_expired_events(self, value: bool) -> Self {
+        Self {
+            drop_expired_events: value,
+            ..self
+        }
+    }
 }
 
 /// Negentropy reconciliation options
@@ -347,3 +833,143 @@ impl NegentropyOptions {
         self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use nostr::{EventBuilder, Keys, Tag, Timestamp};
+
+    use super::*;
+
+    fn event(keys: &Keys, kind: Kind, content: &str, tags: Vec<Tag>, created_at: u64) -> Event {
+        EventBuilder::new(kind, content, tags)
+            .custom_created_at(Timestamp::from(created_at))
+            .to_event(keys)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_reconnect_delay() {
+        let policy = ReconnectPolicy::default();
+        assert_eq!(policy.delay(0), Duration::from_secs(10));
+        assert_eq!(policy.delay(1), Duration::from_secs(10));
+        assert_eq!(policy.delay(2), Duration::from_secs(20));
+        assert_eq!(policy.delay(3), Duration::from_secs(40));
+        assert_eq!(policy.delay(4), Duration::from_secs(60));
+
+        // Overflow
+        assert_eq!(policy.delay(usize::MAX), Duration::from_secs(60));
+
+        let policy = ReconnectPolicy::fixed(Duration::from_secs(30));
+        assert_eq!(policy.delay(1), Duration::from_secs(30));
+        assert_eq!(policy.delay(10), Duration::from_secs(30));
+    }
+
+    #[test]
+    fn test_reconnect_min_delay() {
+        let policy = ReconnectPolicy::fixed(Duration::ZERO);
+        assert_eq!(policy.delay(1), MIN_RECONNECT_DELAY);
+
+        let policy = ReconnectPolicy::default()
+            .initial(Duration::from_secs(1))
+            .max(Duration::from_secs(2));
+        assert_eq!(policy.delay(1), MIN_RECONNECT_DELAY);
+
+        let policy = ReconnectPolicy::default().multiplier(0);
+        assert_eq!(policy.delay(1), Duration::from_secs(10));
+        assert_eq!(policy.delay(2), MIN_RECONNECT_DELAY);
+    }
+
+    #[test]
+    fn test_collapse_replaceable() {
+        let keys = Keys::generate();
+        let other = Keys::generate();
+
+        let note = event(&keys, Kind::TextNote, "note", Vec::new(), 1);
+        let old_metadata = event(&keys, Kind::Metadata, "old", Vec::new(), 1);
+        let new_metadata = event(&keys, Kind::Metadata, "new", Vec::new(), 2);
+        let other_metadata = event(&other, Kind::Metadata, "other", Vec::new(), 1);
+
+        let events = vec![
+            old_metadata.clone(),
+            note.clone(),
+            other_metadata.clone(),
+            new_metadata.clone(),
+        ];
+        assert_eq!(
+            collapse_replaceable(events),
+            vec![new_metadata.clone(), note.clone(), other_metadata]
+        );
+
+        // An older event doesn't replace a newer one
+        let events = vec![new_metadata.clone(), old_metadata, note.clone()];
+        assert_eq!(collapse_replaceable(events), vec![new_metadata, note]);
+    }
+
+    #[test]
+    fn test_collapse_parameterized_replaceable() {
+        let keys = Keys::generate();
+        let kind = Kind::LongFormTextNote;
+
+        let a_old = event(
+            &keys,
+            kind,
+            "a old",
+            vec![Tag::Identifier(String::from("a"))],
+            1,
+        );
+        let a_new = event(
+            &keys,
+            kind,
+            "a new",
+            vec![Tag::Identifier(String::from("a"))],
+            2,
+        );
+        let b = event(
+            &keys,
+            kind,
+            "b",
+            vec![Tag::Identifier(String::from("b"))],
+            1,
+        );
+        let empty = event(&keys, kind, "empty", Vec::new(), 1);
+        let empty_identifier = event(
+            &keys,
+            kind,
+            "empty id",
+            vec![Tag::Identifier(String::new())],
+            3,
+        );
+
+        let events = vec![
+            a_old,
+            b.clone(),
+            a_new.clone(),
+            empty,
+            empty_identifier.clone(),
+        ];
+        assert_eq!(
+            collapse_replaceable(events),
+            vec![a_new, b, empty_identifier]
+        );
+    }
+
+    #[test]
+    fn test_collapse_replaceable_same_timestamp() {
+        let keys = Keys::generate();
+
+        let first = event(&keys, Kind::ContactList, "first", Vec::new(), 1);
+        let second = event(&keys, Kind::ContactList, "second", Vec::new(), 1);
+        let lowest = if first.id() < second.id() {
+            first.clone()
+        } else {
+            second.clone()
+        };
+
+        // The event with the lowest ID is kept, regardless of the order
+        assert_eq!(
+            collapse_replaceable(vec![first.clone(), second.clone()]),
+            vec![lowest.clone()]
+        );
+        assert_eq!(collapse_replaceable(vec![second, first]), vec![lowest]);
+    }
+}
@@ -0,0 +1,119 @@
+// Copyright (c) 2022-2023 Yuki Kishimoto
+// Copyright (c) 2023-2024 Rust Nostr Developers
+// Distributed under the MIT software license
+
+//! Output
+
+use std::collections::{HashMap, HashSet};
+use std::ops::Deref;
+use std::time::Duration;
+
+use nostr::Url;
+
+use super::capability::{RelayPoolReport, RelayReport};
+
+/// Output of the sending APIs
+///
+/// Contains the value and the per-relay [`RelayReport`].
+/// It's returned also if all the relays failed: check it with [`Output::is_success`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Output<T> {
+    /// Value
+    pub val: T,
+    /// Per-relay report
+    pub relays: RelayPoolReport<()>,
+    /// Time taken by each relay
+    pub timings: HashMap<Url, Duration>,
+}
+
+impl<T> Deref for Output<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.val
+    }
+}
+
+impl<T> Output<T> {
+    pub(crate) fn new(val: T) -> Self {
+        Self {
+            val,
+            relays: HashMap::new(),
+            timings: HashMap::new(),
+        }
+    }
+
+    pub(crate) fn add<E>(&mut self, url: Url, result: Result<(), E>, elapsed: Duration)
+    where
+        E: ToString,
+    {
+        let report: RelayReport<()> = match result {
+            Ok(()) => RelayReport::Success(()),
+            Err(e) => RelayReport::Failed(e.to_string()),
+        };
+        self.insert(url, report, elapsed);
+    }
+
+    pub(crate) fn insert(&mut self, url: Url, report: RelayReport<()>, elapsed: Duration) {
+        self.relays.insert(url.clone(), report);
+        self.timings.insert(url, elapsed);
+    }
+
+    /// Merge the report of another send of the same value (i.e. another batch)
+    ///
+    /// A relay is reported as failed if failed at least once. The timings are summed.
+    pub(crate) fn merge(&mut self, other: Output<T>) {
+        for (url, report) in other.relays.into_iter() {
+            let failed: bool = self
+                .relays
+                .get(&url)
+                .map_or(false, |current| current.is_err());
+            if !failed {
+                self.relays.insert(url, report);
+            }
+        }
+        for (url, elapsed) in other.timings.into_iter() {
+            *self.timings.entry(url).or_default() += elapsed;
+        }
+    }
+
+    /// Relays that succeeded (also with a fallback)
+    pub fn success(&self) -> HashSet<Url> {
+        self.relays
+            .iter()
+            .filter(|(_, report)| !report.is_err())
+            .map(|(url, _)| url.clone())
+            .collect()
+    }
+
+    /// Relays that failed or have been skipped, with the reason
+    pub fn failed(&self) -> HashMap<Url, String> {
+        self.relays
+            .iter()
+            .filter_map(|(url, report)| match report {
+                RelayReport::Failed(e) => Some((url.clone(), e.clone())),
+                RelayReport::Skipped(capability) => {
+                    Some((url.clone(), format!("{capability} not supported")))
+                }
+                RelayReport::Success(..) | RelayReport::Fallback { .. } => None,
+            })
+            .collect()
+    }
+
+    /// Check if succeeded for at least one relay
+    pub fn is_success(&self) -> bool {
+        self.relays.values().any(|report| !report.is_err())
+    }
+
+    /// Map the value
+    pub fn map<U, F>(self, f: F) -> Output<U>
+    where
+        F: FnOnce(T) -> U,
+    {
+        Output {
+            val: f(self.val),
+            relays: self.relays,
+            timings: self.timings,
+        }
+    }
+}
@@ -5,26 +5,36 @@
 //! Relay Pool
 
 use std::collections::{HashMap, HashSet};
+use std::future::Future;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 
+use async_utility::futures_util::stream::{self, FuturesUnordered, Stream, StreamExt};
 use async_utility::thread;
 use nostr::message::MessageHandleError;
 use nostr::nips::nip01::Coordinate;
+use nostr::secp256k1::XOnlyPublicKey;
+use nostr::types::time::Instant;
 use nostr::{
     event, ClientMessage, Event, EventId, Filter, JsonUtil, MissingPartialEvent, PartialEvent,
     RawRelayMessage, RelayMessage, SubscriptionId, Timestamp, Url,
 };
-use nostr_database::{DatabaseError, DynNostrDatabase, IntoNostrDatabase, MemoryDatabase, Order};
+use nostr_database::{
+    DatabaseError, DynNostrDatabase, IntoNostrDatabase, MemoryDatabase, Order, SendOutsideWasm,
+};
 use thiserror::Error;
 use tokio::sync::mpsc::{self, Receiver, Sender};
 use tokio::sync::{broadcast, Mutex, RwLock};
 
-use super::options::RelayPoolOptions;
+use super::hooks::RelayHooks;
+use super::middleware::EventMiddleware;
+use super::options::{OverflowPolicy, RelayPoolOptions, ReqDistribution, SubscriptionLimits};
+use super::output::Output;
 use super::{
-    Error as RelayError, FilterOptions, InternalSubscriptionId, Limits, NegentropyOptions, Relay,
-    RelayOptions, RelaySendOptions, RelayStatus,
+    CountReport, Error as RelayError, FilterOptions, InternalSubscriptionId, Limits,
+    NegentropyOptions, Relay, RelayCapability, RelayHookEvent, RelayOptions, RelayPoolReport,
+    RelayPoolSnapshot, RelayReport, RelaySendOptions, RelaySnapshot, RelayStatus, ReqHandle,
 };
 use crate::util::TryIntoUrl;
 
@@ -70,6 +80,9 @@ pub enum Error {
     /// Relay not found
     #[error("relay not found")]
     RelayNotFound,
+    /// Relay group not found
+    #[error("relay group not found")]
+    GroupNotFound,
     /// Event expired
     #[error("event expired")]
     EventExpired,
@@ -92,6 +105,32 @@ pub enum RelayPoolMessage {
         /// Relay Status
         status: RelayStatus,
     },
+    /// Relay accepted `AUTH`
+    Authenticated {
+        /// Relay url
+        relay_url: Url,
+    },
+    /// Relay rejected `AUTH`
+    AuthenticationFailed {
+        /// Relay url
+        relay_url: Url,
+        /// Error
+        error: String,
+    },
+    /// `REQ` about to be sent (new subscription or resubscription)
+    Subscribed {
+        /// Relay url
+        relay_url: Url,
+        /// Subscription ID
+        subscription_id: SubscriptionId,
+    },
+    /// Relay auto-reconnection given up
+    PermanentFailure {
+        /// Relay url
+        relay_url: Url,
+        /// Number of consecutive failed connection attempts
+        attempts: usize,
+    },
     /// Stop
     Stop,
     /// Shutdown
@@ -105,6 +144,10 @@ pub enum RelayPoolNotification {
     Event {
         /// Relay url
         relay_url: Url,
+        /// Subscription ID (the one sent to the relay)
+        ///
+        /// Use [`SubscriptionHandle::is_subscription`] to check if it belongs to a named subscription.
+        subscription_id: SubscriptionId,
         /// Event
         event: Event,
     },
@@ -128,24 +171,117 @@ pub enum RelayPoolNotification {
     Shutdown,
 }
 
+/// Per-subscription event counters
+#[derive(Debug, Default)]
+struct SubscriptionCounters {
+    /// Number of events and window start, by relay and subscription
+    counters: HashMap<(Url, String), (usize, Instant)>,
+    /// Subscriptions closed due to overflow, with closing time
+    closed: HashMap<(Url, String), Instant>,
+    last_cleanup: Option<Instant>,
+}
+
+impl SubscriptionCounters {
+    /// Count the event and return the policy to apply if the limit is exceeded
+    fn increment(
+        &mut self,
+        relay_url: &Url,
+        subscription_id: &str,
+        limits: &SubscriptionLimits,
+    ) -> Option<OverflowPolicy> {
+        let key: (Url, String) = (relay_url.clone(), subscription_id.to_string());
+
+        let now = Instant::now();
+        self.cleanup(now, limits.interval);
+
+        // Subscription already closed
+        if self.closed.contains_key(&key) {
+            return Some(OverflowPolicy::Drop);
+        }
+
+        let (count, start) = self.counters.entry(key.clone()).or_insert((0, now));
+        if now.duration_since(*start) >= limits.interval {
+            *count = 0;
+            *start = now;
+        }
+        *count += 1;
+
+        if *count <= limits.max_events {
+            return None;
+        }
+
+        if limits.policy == OverflowPolicy::Close {
+            self.counters.remove(&key);
+            self.closed.insert(key, now);
+        }
+
+        Some(limits.policy)
+    }
+
+    /// Reset the counter of a subscription (i.e. when subscribing again)
+    fn reset(&mut self, relay_url: &Url, subscription_id: &SubscriptionId) {
+        let key: (Url, String) = (relay_url.clone(), subscription_id.to_string());
+        self.counters.remove(&key);
+        self.closed.remove(&key);
+    }
+
+    /// Remove all the counters of a relay (i.e. when disconnected)
+    fn remove_relay(&mut self, relay_url: &Url) {
+        self.counters.retain(|(url, _), _| url != relay_url);
+        self.closed.retain(|(url, _), _| url != relay_url);
+    }
+
+    /// Remove the expired counters and closed subscriptions
+    ///
+    /// The closed subscriptions are kept for an interval, to drop the events already in flight.
+    fn cleanup(&mut self, now: Instant, interval: Duration) {
+        match self.last_cleanup {
+            Some(last) if now.duration_since(last) < interval => (),
+            _ => {
+                self.counters
+                    .retain(|_, (_, start)| now.duration_since(*start) < interval);
+                self.closed
+                    .retain(|_, closed_at| now.duration_since(*closed_at) < interval);
+                self.last_cleanup = Some(now);
+            }
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 struct RelayPoolTask {
     database: Arc<DynNostrDatabase>,
+    relays: Arc<RwLock<HashMap<Url, Relay>>>,
     receiver: Arc<Mutex<Receiver<RelayPoolMessage>>>,
     notification_sender: broadcast::Sender<RelayPoolNotification>,
+    subscription_limits: Option<SubscriptionLimits>,
+    drop_expired_events: bool,
+    hooks: RelayHooks,
+    middleware: EventMiddleware,
     running: Arc<AtomicBool>,
 }
 
 impl RelayPoolTask {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         database: Arc<DynNostrDatabase>,
+        relays: Arc<RwLock<HashMap<Url, Relay>>>,
         pool_task_receiver: Receiver<RelayPoolMessage>,
         notification_sender: broadcast::Sender<RelayPoolNotification>,
+        subscription_limits: Option<SubscriptionLimits>,
+        drop_expired_events: bool,
+        hooks: RelayHooks,
+        middleware: EventMiddleware,
     ) -> Self {
         Self {
             database,
+            relays,
             receiver: Arc::new(Mutex::new(pool_task_receiver)),
             notification_sender,
+            subscription_limits,
+            drop_expired_events,
+            hooks,
+            middleware,
             running: Arc::new(AtomicBool::new(false)),
         }
     }
@@ -169,11 +305,44 @@ impl RelayPoolTask {
             let this = self.clone();
             thread::spawn(async move {
                 let mut receiver = this.receiver.lock().await;
+                let mut counters = SubscriptionCounters::default();
+                // Relays connected, to dispatch `Disconnected` only once per connection
+                let mut connected: HashSet<Url> = HashSet::new();
                 while let Some(msg) = receiver.recv().await {
                     match msg {
                         RelayPoolMessage::ReceivedMsg { relay_url, msg } => {
-                            match this.handle_relay_message(relay_url.clone(), msg).await {
-                                Ok(Some(msg)) => {
+                            // Per-subscription limits
+                            let overflow: Option<OverflowPolicy> =
+                                match (&msg, &this.subscription_limits) {
+                                    (
+                                        RawRelayMessage::Event {
+                                            subscription_id, ..
+                                        },
+                                        Some(limits),
+                                    ) => counters.increment(&relay_url, subscription_id, limits),
+                                    _ => None,
+                                };
+
+                            let notify: bool = match overflow {
+                                Some(OverflowPolicy::Drop) => continue,
+                                Some(OverflowPolicy::Close) => {
+                                    if let RawRelayMessage::Event {
+                                        subscription_id, ..
+                                    } = msg
+                                    {
+                                        this.close_subscription(&relay_url, subscription_id).await;
+                                    }
+                                    continue;
+                                }
+                                Some(OverflowPolicy::Persist) => false,
+                                None => true,
+                            };
+
+                            match this
+                                .handle_relay_message(relay_url.clone(), msg, notify)
+                                .await
+                            {
+                                Ok(Some(msg)) if notify => {
                                     let _ = this.notification_sender.send(
                                         RelayPoolNotification::Message {
                                             relay_url: relay_url.clone(),
@@ -195,17 +364,66 @@ impl RelayPoolTask {
                                         _ => (),
                                     }
                                 }
-                                Ok(None) => (),
+                                Ok(..) => (),
                                 Err(e) => tracing::error!(
                                     "Impossible to handle relay message from {relay_url}: {e}"
                                 ),
                             }
                         }
                         RelayPoolMessage::RelayStatus { relay_url, status } => {
+                            // Subscriptions are sent again on reconnection
+                            if status != RelayStatus::Connected {
+                                counters.remove_relay(&relay_url);
+                            }
+
+                            match status {
+                                RelayStatus::Connected => {
+                                    connected.insert(relay_url.clone());
+                                    this.hooks
+                                        .dispatch(&relay_url, RelayHookEvent::Connected)
+                                        .await
+                                }
+                                // Failed reconnection attempts set the status to disconnected again
+                                RelayStatus::Disconnected => {
+                                    if connected.remove(&relay_url) {
+                                        this.hooks
+                                            .dispatch(&relay_url, RelayHookEvent::Disconnected)
+                                            .await
+                                    }
+                                }
+                                _ => {
+                                    connected.remove(&relay_url);
+                                }
+                            }
                             let _ = this
                                 .notification_sender
                                 .send(RelayPoolNotification::RelayStatus { relay_url, status });
                         }
+                        RelayPoolMessage::Authenticated { relay_url } => {
+                            this.hooks
+                                .dispatch(&relay_url, RelayHookEvent::Authenticated)
+                                .await;
+                        }
+                        RelayPoolMessage::AuthenticationFailed { relay_url, error } => {
+                            this.hooks
+                                .dispatch(
+                                    &relay_url,
+                                    RelayHookEvent::AuthenticationFailed { error },
+                                )
+                                .await;
+                        }
+                        RelayPoolMessage::Subscribed {
+                            relay_url,
+                            subscription_id,
+                        } => counters.reset(&relay_url, &subscription_id),
+                        RelayPoolMessage::PermanentFailure {
+                            relay_url,
+                            attempts,
+                        } => {
+                            this.hooks
+                                .dispatch(&relay_url, RelayHookEvent::PermanentFailure { attempts })
+                                .await;
+                        }
                         RelayPoolMessage::Stop => {
                             tracing::debug!("Received stop msg");
                             this.set_running_to(false);
@@ -236,11 +454,34 @@ impl RelayPoolTask {
         }
     }
 
+    /// Close subscription exceeding the limits
+    async fn close_subscription(&self, relay_url: &Url, subscription_id: String) {
+        tracing::warn!(
+            "Subscription {subscription_id} exceeded the limits: closing it on {relay_url}"
+        );
+        let relays = self.relays.read().await;
+        if let Some(relay) = relays.get(relay_url) {
+            let id = SubscriptionId::new(subscription_id);
+            let res = match relay.subscription_by_id(&id).await {
+                // Remove the subscription, to not subscribe again on reconnection
+                Some((internal_id, ..)) => {
+                    relay.unsubscribe_with_internal_id(internal_id, None).await
+                }
+                // Not an active subscription (i.e. a `get_events_of` request)
+                None => relay.send_msg(ClientMessage::close(id), None).await,
+            };
+            if let Err(e) = res {
+                tracing::error!("Impossible to close subscription on {relay_url}: {e}");
+            }
+        }
+    }
+
     #[tracing::instrument(skip(self), level = "trace")]
     async fn handle_relay_message(
         &self,
         relay_url: Url,
         msg: RawRelayMessage,
+        notify: bool,
     ) -> Result<Option<RelayMessage>, Error> {
         match msg {
             RawRelayMessage::Event {
@@ -318,27 +559,41 @@ impl RelayPoolTask {
                 let event: Event = partial_event.merge(missing)?;
 
                 // Check if it's expired
-                if event.is_expired() {
+                if self.drop_expired_events && event.is_expired() {
                     return Err(Error::EventExpired);
                 }
 
                 // Verify event
                 event.verify()?;
 
+                // Filter or transform the event with the incoming hooks
+                let event: Event = match self.middleware.incoming(&relay_url, event).await? {
+                    Some(event) => event,
+                    None => {
+                        tracing::debug!(
+                            "Event filtered out by incoming hooks: relay_url={relay_url}"
+                        );
+                        return Ok(None);
+                    }
+                };
+
                 // Save event
                 self.database.save_event(&event).await?;
 
+                let subscription_id = SubscriptionId::new(subscription_id);
+
                 // If not seen, send RelayPoolNotification::Event
-                if !seen {
+                if !seen && notify {
                     let _ = self.notification_sender.send(RelayPoolNotification::Event {
                         relay_url,
+                        subscription_id: subscription_id.clone(),
                         event: event.clone(),
                     });
                 }
 
                 // Compose RelayMessage
                 Ok(Some(RelayMessage::Event {
-                    subscription_id: SubscriptionId::new(subscription_id),
+                    subscription_id,
                     event: Box::new(event),
                 }))
             }
@@ -347,6 +602,82 @@ impl RelayPoolTask {
     }
 }
 
+/// Handle of a subscription with a custom internal ID
+///
+/// Update or close the subscription independently from the others.
+#[derive(Debug, Clone)]
+pub struct SubscriptionHandle {
+    id: InternalSubscriptionId,
+    relays: Arc<RwLock<HashMap<Url, Relay>>>,
+    subscriptions: Arc<RwLock<HashMap<InternalSubscriptionId, Vec<Filter>>>>,
+    wait: Option<Duration>,
+}
+
+impl SubscriptionHandle {
+    /// Get [`InternalSubscriptionId`]
+    pub fn id(&self) -> &InternalSubscriptionId {
+        &self.id
+    }
+
+    /// Get subscription filters
+    ///
+    /// Return `None` if the subscription is closed.
+    pub async fn filters(&self) -> Option<Vec<Filter>> {
+        let subscriptions = self.subscriptions.read().await;
+        subscriptions.get(&self.id).cloned()
+    }
+
+    /// Update subscription filters
+    pub async fn update(&self, filters: Vec<Filter>) -> Output<()> {
+        let mut subscriptions = self.subscriptions.write().await;
+        subscriptions.insert(self.id.clone(), filters.clone());
+        drop(subscriptions);
+
+        let relays = self.relays.read().await.clone();
+        let mut output: Output<()> = Output::new(());
+        for (url, relay) in relays.into_iter() {
+            let now = Instant::now();
+            let res = relay
+                .subscribe_with_internal_id(self.id.clone(), filters.clone(), self.wait)
+                .await;
+            if let Err(e) = &res {
+                tracing::error!("{e}");
+            }
+            output.add(url, res, now.elapsed());
+        }
+        output
+    }
+
+    /// Close subscription
+    pub async fn close(&self) {
+        let mut subscriptions = self.subscriptions.write().await;
+        subscriptions.remove(&self.id);
+        drop(subscriptions);
+
+        let relays = self.relays.read().await.clone();
+        for relay in relays.values() {
+            if let Err(e) = relay
+                .unsubscribe_with_internal_id(self.id.clone(), self.wait)
+                .await
+            {
+                tracing::error!("{e}");
+            }
+        }
+    }
+
+    /// Check if the [`SubscriptionId`] received from a relay belongs to this subscription
+    pub async fn is_subscription(&self, relay_url: &Url, subscription_id: &SubscriptionId) -> bool {
+        let relays = self.relays.read().await;
+        match relays.get(relay_url) {
+            Some(relay) => match relay.subscription(&self.id).await {
+                Some(subscription) => &subscription.id() == subscription_id,
+                None => false,
+            },
+            None => false,
+        }
+    }
+}
+
 /// Relay Pool
 #[derive(Debug, Clone)]
 pub struct RelayPool {
@@ -355,7 +686,12 @@ pub struct RelayPool {
     pool_task_sender: Sender<RelayPoolMessage>,
     notification_sender: broadcast::Sender<RelayPoolNotification>,
     filters: Arc<RwLock<Vec<Filter>>>,
+    subscriptions: Arc<RwLock<HashMap<InternalSubscriptionId, Vec<Filter>>>>,
+    groups: Arc<RwLock<HashMap<String, HashSet<Url>>>>,
     pool_task: RelayPoolTask,
+    hooks: RelayHooks,
+    middleware: EventMiddleware,
+    automatic_authentication: Arc<AtomicBool>,
     opts: RelayPoolOptions,
     dropped: Arc<AtomicBool>,
 }
@@ -396,20 +732,33 @@ impl RelayPool {
         let (pool_task_sender, pool_task_receiver) = mpsc::channel(opts.task_channel_size);
 
         let database: Arc<DynNostrDatabase> = database.into_nostr_database();
+        let relays: Arc<RwLock<HashMap<Url, Relay>>> = Arc::new(RwLock::new(HashMap::new()));
+        let hooks = RelayHooks::default();
+        let middleware = EventMiddleware::default();
 
         let relay_pool_task = RelayPoolTask::new(
             database.clone(),
+            relays.clone(),
             pool_task_receiver,
             notification_sender.clone(),
+            opts.subscription_limits,
+            opts.drop_expired_events,
+            hooks.clone(),
+            middleware.clone(),
         );
 
         let pool = Self {
             database,
-            relays: Arc::new(RwLock::new(HashMap::new())),
+            relays,
             pool_task_sender,
             notification_sender,
             filters: Arc::new(RwLock::new(Vec::new())),
+            subscriptions: Arc::new(RwLock::new(HashMap::new())),
+            groups: Arc::new(RwLock::new(HashMap::new())),
             pool_task: relay_pool_task,
+            hooks,
+            middleware,
+            automatic_authentication: Arc::new(AtomicBool::new(false)),
             opts,
             dropped: Arc::new(AtomicBool::new(false)),
         };
@@ -456,6 +805,35 @@ impl RelayPool {
         self.notification_sender.subscribe()
     }
 
+    /// Enable/disable the retry of the events rejected with `auth-required:`, after the authentication (NIP42)
+    ///
+    /// The `AUTH` must be sent by the caller (see [`Relay::auth`]).
+    pub(crate) fn update_automatic_authentication(&self, enabled: bool) {
+        self.automatic_authentication
+            .store(enabled, Ordering::SeqCst);
+    }
+
+    /// Register an async hook, called on relay connection events
+    ///
+    /// Hooks are called in a separated task, in addition to the [`RelayPoolNotification`]s.
+    pub async fn add_hook<F, Fut>(&self, hook: F)
+    where
+        F: Fn(Url, RelayHookEvent) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + SendOutsideWasm + 'static,
+    {
+        self.hooks.add(hook).await;
+    }
+
+    /// Remove all the registered hooks
+    pub async fn clear_hooks(&self) {
+        self.hooks.clear().await;
+    }
+
+    /// Get the event middleware
+    pub(crate) fn middleware(&self) -> &EventMiddleware {
+        &self.middleware
+    }
+
     /// Get database
     pub fn database(&self) -> Arc<DynNostrDatabase> {
         self.database.clone()
@@ -505,7 +883,8 @@ impl RelayPool {
                 self.notification_sender.clone(),
                 opts,
                 Limits::default(),
-            );
+            )
+            .with_automatic_authentication(self.automatic_authentication.clone());
             relays.insert(relay.url(), relay);
             Ok(true)
         } else {
@@ -524,11 +903,119 @@ impl RelayPool {
         if let Some(relay) = relays.remove(&url) {
             self.disconnect_relay(&relay).await?;
         }
+
+        // Remove from groups
+        let mut groups = self.groups.write().await;
+        for urls in groups.values_mut() {
+            urls.remove(&url);
+        }
+        groups.retain(|_, urls| !urls.is_empty());
+
+        Ok(())
+    }
+
+    /// Get relay groups
+    pub async fn groups(&self) -> HashMap<String, HashSet<Url>> {
+        self.groups.read().await.clone()
+    }
+
+    /// Add relay to a group (i.e. `dm`, `feed`, `search` or `backup`)
+    ///
+    /// The group is created if not exists. The relay must be already added to the pool.
+    pub async fn add_relay_to_group<S, U>(&self, group: S, url: U) -> Result<(), Error>
+    where
+        S: Into<String>,
+        U: TryIntoUrl,
+        Error: From<<U as TryIntoUrl>::Err>,
+    {
+        let url: Url = url.try_into_url()?;
+        let relays = self.relays.read().await;
+        if !relays.contains_key(&url) {
+            return Err(Error::RelayNotFound);
+        }
+        let mut groups = self.groups.write().await;
+        groups.entry(group.into()).or_default().insert(url);
+        Ok(())
+    }
+
+    /// Remove relay from a group
+    ///
+    /// The group is removed when empty. The relay is **NOT** removed from the pool.
+    pub async fn remove_relay_from_group<U>(&self, group: &str, url: U) -> Result<(), Error>
+    where
+        U: TryIntoUrl,
+        Error: From<<U as TryIntoUrl>::Err>,
+    {
+        let url: Url = url.try_into_url()?;
+        let mut groups = self.groups.write().await;
+        let urls: &mut HashSet<Url> = groups.get_mut(group).ok_or(Error::GroupNotFound)?;
+        urls.remove(&url);
+        if urls.is_empty() {
+            groups.remove(group);
+        }
+        Ok(())
+    }
+
+    /// Get relays of a group
+    pub async fn group_relays(&self, group: &str) -> Result<HashMap<Url, Relay>, Error> {
+        let urls: HashSet<Url> = {
+            let groups = self.groups.read().await;
+            groups.get(group).cloned().ok_or(Error::GroupNotFound)?
+        };
+        let relays = self.relays.read().await;
+        Ok(relays
+            .iter()
+            .filter(|(url, ..)| urls.contains(url))
+            .map(|(url, relay)| (url.clone(), relay.clone()))
+            .collect())
+    }
+
+    /// Take a [`RelayPoolSnapshot`]
+    ///
+    /// Capture relays, their options and active subscriptions and the pool subscription filters.
+    pub async fn snapshot(&self) -> RelayPoolSnapshot {
+        let relays = self.relays().await;
+        let mut snapshots: Vec<RelaySnapshot> = Vec::with_capacity(relays.len());
+        for relay in relays.values() {
+            snapshots.push(RelaySnapshot::from_relay(relay).await);
+        }
+        RelayPoolSnapshot {
+            relays: snapshots,
+            filters: self.subscription_filters().await,
+        }
+    }
+
+    /// Restore a [`RelayPoolSnapshot`]
+    ///
+    /// Relays already in the pool keep their current options.
+    /// This method **NOT** automatically start connection with relays!
+    pub async fn restore(&self, snapshot: RelayPoolSnapshot) -> Result<(), Error> {
+        for relay_snapshot in snapshot.relays.into_iter() {
+            self.add_relay(&relay_snapshot.url, relay_snapshot.opts())
+                .await?;
+            let relay: Relay = self.relay(&relay_snapshot.url).await?;
+            for (internal_id, filters) in relay_snapshot.internal_subscriptions().into_iter() {
+                if !filters.is_empty() {
+                    relay
+                        .update_subscription_filters(internal_id, filters)
+                        .await;
+                }
+            }
+        }
+
+        if !snapshot.filters.is_empty() {
+            self.update_subscription_filters(snapshot.filters).await;
+        }
+
         Ok(())
     }
 
     /// Send client message
-    pub async fn send_msg(&self, msg: ClientMessage, wait: Option<Duration>) -> Result<(), Error> {
+    pub async fn send_msg(
+        &self,
+        msg: ClientMessage,
+        wait: Option<Duration>,
+    ) -> Result<Output<()>, Error> {
         let relays = self.relays().await;
 
         if relays.is_empty() {
@@ -539,33 +1026,28 @@ impl RelayPool {
             self.database.save_event(event).await?;
         }
 
-        let sent_to_at_least_one_relay: Arc<AtomicBool> = Arc::new(AtomicBool::new(false));
         let mut handles = Vec::new();
 
         for (url, relay) in relays.into_iter() {
             let msg = msg.clone();
-            let sent = sent_to_at_least_one_relay.clone();
             let handle = thread::spawn(async move {
-                match relay.send_msg(msg, wait).await {
-                    Ok(_) => {
-                        let _ =
-                            sent.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |_| Some(true));
-                    }
-                    Err(e) => tracing::error!("Impossible to send msg to {url}: {e}"),
+                let now = Instant::now();
+                let res = relay.send_msg(msg, wait).await;
+                if let Err(e) = &res {
+                    tracing::error!("Impossible to send msg to {url}: {e}");
                 }
+                (url, res, now.elapsed())
             });
             handles.push(handle);
         }
 
+        let mut output: Output<()> = Output::new(());
         for handle in handles.into_iter().flatten() {
-            handle.join().await?;
-        }
-
-        if !sent_to_at_least_one_relay.load(Ordering::SeqCst) {
-            return Err(Error::MsgNotSent);
+            let (url, res, elapsed) = handle.join().await?;
+            output.add(url, res, elapsed);
         }
 
-        Ok(())
+        Ok(output)
     }
 
     /// Send multiple client messages at once
@@ -573,7 +1055,7 @@ impl RelayPool {
         &self,
         msgs: Vec<ClientMessage>,
         wait: Option<Duration>,
-    ) -> Result<(), Error> {
+    ) -> Result<Output<()>, Error> {
         let relays = self.relays().await;
 
         if relays.is_empty() {
@@ -587,34 +1069,29 @@ impl RelayPool {
             }
         }
 
-        let sent_to_at_least_one_relay: Arc<AtomicBool> = Arc::new(AtomicBool::new(false));
         let mut handles = Vec::new();
 
         for (url, relay) in relays.into_iter() {
             let len = msgs.len();
             let msgs = msgs.clone();
-            let sent = sent_to_at_least_one_relay.clone();
             let handle = thread::spawn(async move {
-                match relay.batch_msg(msgs, wait).await {
-                    Ok(_) => {
-                        let _ =
-                            sent.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |_| Some(true));
-                    }
-                    Err(e) => tracing::error!("Impossible to send {len} messages to {url}: {e}"),
+                let now = Instant::now();
+                let res = relay.batch_msg(msgs, wait).await;
+                if let Err(e) = &res {
+                    tracing::error!("Impossible to send {len} messages to {url}: {e}");
                 }
+                (url, res, now.elapsed())
             });
             handles.push(handle);
         }
 
+        let mut output: Output<()> = Output::new(());
         for handle in handles.into_iter().flatten() {
-            handle.join().await?;
-        }
-
-        if !sent_to_at_least_one_relay.load(Ordering::SeqCst) {
-            return Err(Error::MsgNotSent);
+            let (url, res, elapsed) = handle.join().await?;
+            output.add(url, res, elapsed);
         }
 
-        Ok(())
+        Ok(output)
     }
 
     /// Send client message to a single relay
@@ -623,7 +1100,7 @@ impl RelayPool {
         url: U,
         msg: ClientMessage,
         wait: Option<Duration>,
-    ) -> Result<(), Error>
+    ) -> Result<Output<()>, Error>
     where
         U: TryIntoUrl,
         Error: From<<U as TryIntoUrl>::Err>,
@@ -636,52 +1113,103 @@ impl RelayPool {
 
         let relays = self.relays().await;
         if let Some(relay) = relays.get(&url) {
-            relay.send_msg(msg, wait).await?;
-            Ok(())
+            let now = Instant::now();
+            let res = relay.send_msg(msg, wait).await;
+            let mut output: Output<()> = Output::new(());
+            output.add(url, res, now.elapsed());
+            Ok(output)
         } else {
             Err(Error::RelayNotFound)
         }
     }
 
     /// Send event and wait for `OK` relay msg
-    pub async fn send_event(&self, event: Event, opts: RelaySendOptions) -> Result<EventId, Error> {
+    pub async fn send_event(
+        &self,
+        event: Event,
+        opts: RelaySendOptions,
+    ) -> Result<Output<EventId>, Error> {
         let relays = self.relays().await;
+        self.send_event_to_relays(relays, event, opts).await
+    }
 
+    /// Send event to the relays of a group and wait for `OK` relay msg
+    pub async fn send_event_to_group(
+        &self,
+        group: &str,
+        event: Event,
+        opts: RelaySendOptions,
+    ) -> Result<Output<EventId>, Error> {
+        let relays = self.group_relays(group).await?;
+        self.send_event_to_relays(relays, event, opts).await
+    }
+
+    /// Send event to a set of relays and wait for `OK` relay msg
+    ///
+    /// Relays not in the pool are skipped.
+    pub async fn send_event_to_urls<I>(
+        &self,
+        urls: I,
+        event: Event,
+        opts: RelaySendOptions,
+    ) -> Result<Output<EventId>, Error>
+    where
+        I: IntoIterator<Item = Url>,
+    {
+        let urls: HashSet<Url> = urls.into_iter().collect();
+        let relays: HashMap<Url, Relay> = self
+            .relays()
+            .await
+            .into_iter()
+            .filter(|(url, ..)| urls.contains(url))
+            .collect();
+        self.send_event_to_relays(relays, event, opts).await
+    }
+
+    async fn send_event_to_relays(
+        &self,
+        mut relays: HashMap<Url, Relay>,
+        event: Event,
+        opts: RelaySendOptions,
+    ) -> Result<Output<EventId>, Error> {
         if relays.is_empty() {
             return Err(Error::NoRelays);
         }
 
+        // Protected events (NIP70) must be sent only to relays where we are authenticated
+        if event.is_protected() {
+            relays.retain(|_, relay| relay.is_authenticated());
+            if relays.is_empty() {
+                return Err(Error::EventNotPublished(event.id()));
+            }
+        }
+
         self.database.save_event(&event).await?;
 
-        let sent_to_at_least_one_relay: Arc<AtomicBool> = Arc::new(AtomicBool::new(false));
         let mut handles = Vec::new();
 
         let event_id: EventId = event.id();
 
         for (url, relay) in relays.into_iter() {
             let event = event.clone();
-            let sent = sent_to_at_least_one_relay.clone();
             let handle = thread::spawn(async move {
-                match relay.send_event(event, opts).await {
-                    Ok(_) => {
-                        let _ =
-                            sent.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |_| Some(true));
-                    }
-                    Err(e) => tracing::error!("Impossible to send event to {url}: {e}"),
+                let now = Instant::now();
+                let res = relay.send_event(event, opts).await.map(|_| ());
+                if let Err(e) = &res {
+                    tracing::error!("Impossible to send event to {url}: {e}");
                 }
+                (url, res, now.elapsed())
             });
             handles.push(handle);
         }
 
+        let mut output: Output<EventId> = Output::new(event_id);
         for handle in handles.into_iter().flatten() {
-            handle.join().await?;
+            let (url, res, elapsed) = handle.join().await?;
+            output.add(url, res, elapsed);
         }
 
-        if !sent_to_at_least_one_relay.load(Ordering::SeqCst) {
-            return Err(Error::EventNotPublished(event_id));
-        }
-
-        Ok(event_id)
+        Ok(output)
     }
 
     /// Send multiple [`Event`] at once
@@ -689,9 +1217,39 @@ impl RelayPool {
         &self,
         events: Vec<Event>,
         opts: RelaySendOptions,
-    ) -> Result<(), Error> {
+    ) -> Result<Output<()>, Error> {
         let relays = self.relays().await;
+        self.batch_event_to_relays(relays, events, opts).await
+    }
 
+    /// Send multiple [`Event`] at once to specific relays
+    ///
+    /// Relays not in the pool are skipped.
+    pub async fn batch_event_to_urls<I>(
+        &self,
+        urls: I,
+        events: Vec<Event>,
+        opts: RelaySendOptions,
+    ) -> Result<Output<()>, Error>
+    where
+        I: IntoIterator<Item = Url>,
+    {
+        let urls: HashSet<Url> = urls.into_iter().collect();
+        let relays: HashMap<Url, Relay> = self
+            .relays()
+            .await
+            .into_iter()
+            .filter(|(url, ..)| urls.contains(url))
+            .collect();
+        self.batch_event_to_relays(relays, events, opts).await
+    }
+
+    async fn batch_event_to_relays(
+        &self,
+        relays: HashMap<Url, Relay>,
+        events: Vec<Event>,
+        opts: RelaySendOptions,
+    ) -> Result<Output<()>, Error> {
         if relays.is_empty() {
             return Err(Error::NoRelays);
         }
@@ -701,34 +1259,29 @@ impl RelayPool {
             self.database.save_event(event).await?;
         }
 
-        let sent_to_at_least_one_relay: Arc<AtomicBool> = Arc::new(AtomicBool::new(false));
         let mut handles = Vec::new();
 
         for (url, relay) in relays.into_iter() {
             let len = events.len();
             let events = events.clone();
-            let sent = sent_to_at_least_one_relay.clone();
             let handle = thread::spawn(async move {
-                match relay.batch_event(events, opts).await {
-                    Ok(_) => {
-                        let _ =
-                            sent.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |_| Some(true));
-                    }
-                    Err(e) => tracing::error!("Impossible to send {len} events to {url}: {e}"),
+                let now = Instant::now();
+                let res = relay.batch_event(events, opts).await;
+                if let Err(e) = &res {
+                    tracing::error!("Impossible to send {len} events to {url}: {e}");
                 }
+                (url, res, now.elapsed())
             });
             handles.push(handle);
         }
 
+        let mut output: Output<()> = Output::new(());
         for handle in handles.into_iter().flatten() {
-            handle.join().await?;
-        }
-
-        if !sent_to_at_least_one_relay.load(Ordering::SeqCst) {
-            return Err(Error::EventsNotPublished);
+            let (url, res, elapsed) = handle.join().await?;
+            output.add(url, res, elapsed);
         }
 
-        Ok(())
+        Ok(output)
     }
 
     /// Send event to a single relay
@@ -737,7 +1290,7 @@ impl RelayPool {
         url: U,
         event: Event,
         opts: RelaySendOptions,
-    ) -> Result<EventId, Error>
+    ) -> Result<Output<EventId>, Error>
     where
         U: TryIntoUrl,
         Error: From<<U as TryIntoUrl>::Err>,
@@ -746,7 +1299,12 @@ impl RelayPool {
         self.database.save_event(&event).await?;
         let relays = self.relays().await;
         if let Some(relay) = relays.get(&url) {
-            Ok(relay.send_event(event, opts).await?)
+            let now = Instant::now();
+            let event_id: EventId = event.id();
+            let res = relay.send_event(event, opts).await.map(|_| ());
+            let mut output: Output<EventId> = Output::new(event_id);
+            output.add(url, res, now.elapsed());
+            Ok(output)
         } else {
             Err(Error::RelayNotFound)
         }
@@ -755,23 +1313,28 @@ impl RelayPool {
     /// Subscribe to filters
     ///
     /// Internal Subscription ID set to `InternalSubscriptionId::Pool`
-    pub async fn subscribe(&self, filters: Vec<Filter>, wait: Option<Duration>) {
+    pub async fn subscribe(&self, filters: Vec<Filter>, wait: Option<Duration>) -> Output<()> {
         let relays = self.relays().await;
         self.update_subscription_filters(filters.clone()).await;
-        for relay in relays.values() {
-            if let Err(e) = relay
+        let mut output: Output<()> = Output::new(());
+        for (url, relay) in relays.into_iter() {
+            let now = Instant::now();
+            let res = relay
                 .subscribe_with_internal_id(InternalSubscriptionId::Pool, filters.clone(), wait)
-                .await
-            {
+                .await;
+            if let Err(e) = &res {
                 tracing::error!("{e}");
             }
+            output.add(url, res, now.elapsed());
         }
+        output
     }
 
     /// Unsubscribe from filters
     ///
     /// Internal Subscription ID set to `InternalSubscriptionId::Pool`
     pub async fn unsubscribe(&self, wait: Option<Duration>) {
+        self.update_subscription_filters(Vec::new()).await;
         let relays = self.relays().await;
         for relay in relays.values() {
             if let Err(e) = relay
@@ -783,6 +1346,121 @@ impl RelayPool {
         }
     }
 
+    /// Subscribe to filters with a custom internal subscription ID
+    ///
+    /// The subscription is independent from the one managed by [`RelayPool::subscribe`]
+    /// and is applied also to the relays connected later.
+    /// Calling again this method with the same ID replace the filters.
+    pub async fn subscribe_with_internal_id(
+        &self,
+        internal_id: InternalSubscriptionId,
+        filters: Vec<Filter>,
+        wait: Option<Duration>,
+    ) -> Output<()> {
+        self.subscription_handle(internal_id, wait)
+            .update(filters)
+            .await
+    }
+
+    /// Unsubscribe from filters with a custom internal subscription ID
+    pub async fn unsubscribe_with_internal_id(
+        &self,
+        internal_id: InternalSubscriptionId,
+        wait: Option<Duration>,
+    ) {
+        self.subscription_handle(internal_id, wait).close().await
+    }
+
+    /// Get [`SubscriptionHandle`] of a custom internal subscription ID
+    pub fn subscription_handle(
+        &self,
+        internal_id: InternalSubscriptionId,
+        wait: Option<Duration>,
+    ) -> SubscriptionHandle {
+        SubscriptionHandle {
+            id: internal_id,
+            relays: self.relays.clone(),
+            subscriptions: self.subscriptions.clone(),
+            wait,
+        }
+    }
+
+    /// Get [`SubscriptionHandle`] of an existing custom internal subscription ID
+    ///
+    /// Return `None` if the subscription doesn't exist or has been closed.
+    pub async fn subscription(
+        &self,
+        internal_id: &InternalSubscriptionId,
+        wait: Option<Duration>,
+    ) -> Option<SubscriptionHandle> {
+        let subscriptions = self.subscriptions.read().await;
+        if subscriptions.contains_key(internal_id) {
+            Some(self.subscription_handle(internal_id.clone(), wait))
+        } else {
+            None
+        }
+    }
+
+    /// Get filters of the subscriptions with a custom internal subscription ID
+    pub async fn subscriptions(&self) -> HashMap<InternalSubscriptionId, Vec<Filter>> {
+        self.subscriptions.read().await.clone()
+    }
+
+    /// Close all subscriptions, including the ones with a custom internal subscription ID
+    pub async fn unsubscribe_all(&self, wait: Option<Duration>) {
+        self.update_subscription_filters(Vec::new()).await;
+        self.subscriptions.write().await.clear();
+
+        let relays = self.relays().await;
+        for relay in relays.values() {
+            if let Err(e) = relay.unsubscribe_all(wait).await {
+                tracing::error!("{e}");
+            }
+        }
+    }
+
+    /// Subscribe to filters on the relays of a group
+    ///
+    /// Internal Subscription ID set to `InternalSubscriptionId::Custom("group:<name>")`
+    pub async fn subscribe_group(
+        &self,
+        group: &str,
+        filters: Vec<Filter>,
+        wait: Option<Duration>,
+    ) -> Result<Output<()>, Error> {
+        let relays = self.group_relays(group).await?;
+        let mut output: Output<()> = Output::new(());
+        for (url, relay) in relays.into_iter() {
+            let now = Instant::now();
+            let res = relay
+                .subscribe_with_internal_id(group_internal_id(group), filters.clone(), wait)
+                .await;
+            if let Err(e) = &res {
+                tracing::error!("{e}");
+            }
+            output.add(url, res, now.elapsed());
+        }
+        Ok(output)
+    }
+
+    /// Unsubscribe from filters on the relays of a group
+    pub async fn unsubscribe_group(
+        &self,
+        group: &str,
+        wait: Option<Duration>,
+    ) -> Result<(), Error> {
+        let relays = self.group_relays(group).await?;
+        for relay in relays.values() {
+            if let Err(e) = relay
+                .unsubscribe_with_internal_id(group_internal_id(group), wait)
+                .await
+            {
+                tracing::error!("{e}");
+            }
+        }
+        Ok(())
+    }
+
     /// Get events of filters
     ///
     /// Get events from local database and relays
@@ -791,6 +1469,74 @@ impl RelayPool {
         filters: Vec<Filter>,
         timeout: Duration,
         opts: FilterOptions,
+    ) -> Result<Vec<Event>, Error> {
+        let relays = self.relays().await;
+        self.get_events_from_relays(relays, filters, timeout, opts)
+            .await
+    }
+
+    /// Get events of filters from the relays of a group
+    ///
+    /// Get events from local database and group relays
+    pub async fn get_events_from_group(
+        &self,
+        group: &str,
+        filters: Vec<Filter>,
+        timeout: Duration,
+        opts: FilterOptions,
+    ) -> Result<Vec<Event>, Error> {
+        let relays = self.group_relays(group).await?;
+        self.get_events_from_relays(relays, filters, timeout, opts)
+            .await
+    }
+
+    /// Get events of filters, sending to each relay its own filters
+    ///
+    /// Get events from local database (matching `filters`) and relays. Relays not in the pool are skipped.
+    pub async fn get_events_routed(
+        &self,
+        filters: Vec<Filter>,
+        routes: HashMap<Url, Vec<Filter>>,
+        timeout: Duration,
+        opts: FilterOptions,
+    ) -> Result<Vec<Event>, Error> {
+        let relays = self.relays().await;
+        let targets: HashMap<Url, (Relay, Vec<Filter>)> = routes
+            .into_iter()
+            .filter_map(|(url, filters)| {
+                let relay: Relay = relays.get(&url)?.clone();
+                Some((url, (relay, filters)))
+            })
+            .collect();
+        self.get_events_from_targets(filters, targets, None, timeout, opts)
+            .await
+    }
+
+    async fn get_events_from_relays(
+        &self,
+        relays: HashMap<Url, Relay>,
+        filters: Vec<Filter>,
+        timeout: Duration,
+        opts: FilterOptions,
+    ) -> Result<Vec<Event>, Error> {
+        // The quorum makes sense only if every relay receives the same filters
+        let quorum: Option<usize> = match self.opts.req_distribution {
+            ReqDistribution::Broadcast => self.opts.req_quorum,
+            ReqDistribution::PartitionAuthors { .. } => None,
+        };
+
+        let targets = self.distribute_filters(relays, filters.clone());
+        self.get_events_from_targets(filters, targets, quorum, timeout, opts)
+            .await
+    }
+
+    async fn get_events_from_targets(
+        &self,
+        filters: Vec<Filter>,
+        targets: HashMap<Url, (Relay, Vec<Filter>)>,
+        quorum: Option<usize>,
+        timeout: Duration,
+        opts: FilterOptions,
     ) -> Result<Vec<Event>, Error> {
         // Get stored events
         let stored_events: Vec<Event> = self
@@ -799,58 +1545,302 @@ impl RelayPool {
             .await
             .unwrap_or_default();
 
-        // Compose IDs and Events collections
-        let ids: Arc<Mutex<HashSet<EventId>>> =
-            Arc::new(Mutex::new(stored_events.iter().map(|e| e.id()).collect()));
-        let events: Arc<Mutex<Vec<Event>>> = Arc::new(Mutex::new(stored_events));
+        // Events merger: deduplicate the events received from the relays
+        let ids: HashSet<EventId> = stored_events.iter().map(|e| e.id()).collect();
+        let merger: Mutex<(HashSet<EventId>, Vec<Event>)> = Mutex::new((ids, stored_events));
+
+        // Query relays concurrently
+        let merger_ref = &merger;
+        let mut tasks = FuturesUnordered::new();
+        for (url, (relay, filters)) in targets.into_iter() {
+            tasks.push(async move {
+                let res = relay
+                    .get_events_of_with_callback(filters, timeout, opts, |event| async move {
+                        let mut merger = merger_ref.lock().await;
+                        let (ids, events) = &mut *merger;
+                        if ids.insert(event.id()) {
+                            events.push(event);
+                        }
+                    })
+                    .await;
+                (url, res)
+            });
+        }
+
+        let mut completed: usize = 0;
+        while let Some((url, res)) = tasks.next().await {
+            match res {
+                Ok(()) => completed += 1,
+                Err(e) => tracing::error!("Failed to get events from {url}: {e}"),
+            }
+
+            if let Some(quorum) = quorum {
+                if completed >= quorum {
+                    tracing::debug!("Quorum reached: {completed} relays completed");
+                    break;
+                }
+            }
+        }
+
+        // Cancel the outstanding relay queries (their `REQ`s are closed)
+        drop(tasks);
+
+        let (.., events) = merger.into_inner();
+        Ok(events)
+    }
+
+    /// Stream events of filters
+    ///
+    /// Like [`RelayPool::get_events_of`], but the events are yielded as they arrive (stored events first)
+    /// instead of being collected into a `Vec`. Duplicated events are skipped.
+    ///
+    /// The stream ends when all the relays have completed the request.
+    /// If the stream is dropped, the outstanding relay queries are cancelled.
+    pub async fn stream_events_of(
+        &self,
+        filters: Vec<Filter>,
+        timeout: Duration,
+        opts: FilterOptions,
+    ) -> impl Stream<Item = Event> {
+        let relays = self.relays().await;
+        let targets = self.distribute_filters(relays, filters.clone());
+        self.stream_events_from_targets(filters, targets, timeout, opts)
+    }
+
+    /// Stream events of filters, sending to each relay its own filters
+    ///
+    /// See [`RelayPool::stream_events_of`]. Relays not in the pool are skipped.
+    pub async fn stream_events_routed(
+        &self,
+        filters: Vec<Filter>,
+        routes: HashMap<Url, Vec<Filter>>,
+        timeout: Duration,
+        opts: FilterOptions,
+    ) -> impl Stream<Item = Event> {
+        let relays = self.relays().await;
+        let targets: HashMap<Url, (Relay, Vec<Filter>)> = routes
+            .into_iter()
+            .filter_map(|(url, filters)| {
+                let relay: Relay = relays.get(&url)?.clone();
+                Some((url, (relay, filters)))
+            })
+            .collect();
+        self.stream_events_from_targets(filters, targets, timeout, opts)
+    }
+
+    fn stream_events_from_targets(
+        &self,
+        filters: Vec<Filter>,
+        targets: HashMap<Url, (Relay, Vec<Filter>)>,
+        timeout: Duration,
+        opts: FilterOptions,
+    ) -> impl Stream<Item = Event> {
+        // Unbounded: the relay callbacks must never wait for the consumer,
+        // otherwise the notification receivers lag and events are lost.
+        let (tx, rx) = mpsc::unbounded_channel::<Event>();
+        let database = self.database.clone();
+
+        thread::spawn(async move {
+            // Stored events
+            let mut ids: HashSet<EventId> = HashSet::new();
+            for event in database
+                .query(filters, Order::Desc)
+                .await
+                .unwrap_or_default()
+                .into_iter()
+            {
+                ids.insert(event.id());
+                if tx.send(event).is_err() {
+                    return;
+                }
+            }
+
+            // Query relays concurrently
+            let ids: Mutex<HashSet<EventId>> = Mutex::new(ids);
+            let ids_ref = &ids;
+            let tx_ref = &tx;
+            let mut tasks = FuturesUnordered::new();
+            for (url, (relay, filters)) in targets.into_iter() {
+                tasks.push(async move {
+                    let res = relay
+                        .get_events_of_with_callback(filters, timeout, opts, |event| async move {
+                            let mut ids = ids_ref.lock().await;
+                            if ids.insert(event.id()) {
+                                drop(ids);
+                                let _ = tx_ref.send(event);
+                            }
+                        })
+                        .await;
+                    (url, res)
+                });
+            }
+
+            loop {
+                tokio::select! {
+                    // Stream dropped: cancel the outstanding relay queries
+                    _ = tx.closed() => break,
+                    res = tasks.next() => match res {
+                        Some((url, Err(e))) => {
+                            tracing::error!("Failed to stream events from {url}: {e}")
+                        }
+                        Some(..) => (),
+                        None => break,
+                    },
+                }
+            }
+        });
+
+        stream::unfold(rx, |mut rx| async move {
+            let event: Event = rx.recv().await?;
+            Some((event, rx))
+        })
+    }
+
+    /// Count events of filters
+    ///
+    /// Send `COUNT` (NIP45) to the relays and count the matching events in the local database.
+    /// Relays that don't support `COUNT` fall back to `REQ`, counting the received events.
+    pub async fn count_events_of(
+        &self,
+        filters: Vec<Filter>,
+        timeout: Duration,
+    ) -> Result<CountReport, Error> {
+        let database: usize = self.database.count(filters.clone()).await?;
+
+        let report: Arc<Mutex<RelayPoolReport<usize>>> = Arc::new(Mutex::new(HashMap::new()));
 
-        // Get relays and start query
         let mut handles = Vec::new();
         let relays = self.relays().await;
         for (url, relay) in relays.into_iter() {
             let filters = filters.clone();
-            let ids = ids.clone();
-            let events = events.clone();
+            let report = report.clone();
             let handle = thread::spawn(async move {
-                if let Err(e) = relay
-                    .get_events_of_with_callback(filters, timeout, opts, |event| async {
-                        let mut ids = ids.lock().await;
-                        if !ids.contains(&event.id()) {
-                            let mut events = events.lock().await;
-                            ids.insert(event.id());
-                            events.push(event);
+                let res: RelayReport<usize> =
+                    match relay.count_events_of(filters.clone(), timeout).await {
+                        Ok(count) => RelayReport::Success(count),
+                        Err(RelayError::UnsupportedCapability(RelayCapability::Count)) => {
+                            let ids: Mutex<HashSet<EventId>> = Mutex::new(HashSet::new());
+                            let ids_ref = &ids;
+                            let res = relay
+                                .get_events_of_with_callback(
+                                    filters,
+                                    timeout,
+                                    FilterOptions::ExitOnEOSE,
+                                    |event| async move {
+                                        let mut ids = ids_ref.lock().await;
+                                        ids.insert(event.id());
+                                    },
+                                )
+                                .await;
+                            match res {
+                                Ok(()) => RelayReport::Fallback {
+                                    capability: RelayCapability::Count,
+                                    output: ids.into_inner().len(),
+                                },
+                                Err(RelayError::UnsupportedCapability(capability)) => {
+                                    RelayReport::Skipped(capability)
+                                }
+                                Err(e) => RelayReport::Failed(e.to_string()),
+                            }
                         }
-                    })
-                    .await
-                {
-                    tracing::error!("Failed to get events from {url}: {e}");
-                }
+                        Err(RelayError::UnsupportedCapability(capability)) => {
+                            RelayReport::Skipped(capability)
+                        }
+                        Err(e) => {
+                            tracing::error!("Failed to count events from {url}: {e}");
+                            RelayReport::Failed(e.to_string())
+                        }
+                    };
+                let mut report = report.lock().await;
+                report.insert(url, res);
             });
             handles.push(handle);
         }
 
-        // Join threads
         for handle in handles.into_iter().flatten() {
             handle.join().await?;
         }
 
-        Ok(events.lock_owned().await.clone())
+        Ok(CountReport {
+            relays: report.lock_owned().await.clone(),
+            database,
+        })
     }
 
     /// Request events of filter.
     ///
     /// If the events aren't already stored in the database, will be sent to notification listener
     /// until the EOSE "end of stored events" message is received from the relay.
+    ///
+    /// Return a [`ReqHandle`] to track completion (`EOSE` from all relays) or cancel the request.
     pub async fn req_events_of(
         &self,
         filters: Vec<Filter>,
         timeout: Duration,
         opts: FilterOptions,
-    ) {
+    ) -> ReqHandle {
+        let handle = ReqHandle::new();
         let relays = self.relays().await;
-        for relay in relays.values() {
-            relay.req_events_of(filters.clone(), timeout, opts);
+        for (relay, filters) in self.distribute_filters(relays, filters).into_values() {
+            handle.spawn(relay, filters, timeout, opts).await;
+        }
+        handle
+    }
+
+    /// Distribute filters across relays, according to the [`ReqDistribution`] strategy
+    fn distribute_filters(
+        &self,
+        relays: HashMap<Url, Relay>,
+        filters: Vec<Filter>,
+    ) -> HashMap<Url, (Relay, Vec<Filter>)> {
+        let threshold: usize = match self.opts.req_distribution {
+            ReqDistribution::PartitionAuthors { threshold } => threshold,
+            ReqDistribution::Broadcast => {
+                return relays
+                    .into_iter()
+                    .map(|(url, relay)| (url, (relay, filters.clone())))
+                    .collect();
+            }
+        };
+
+        // Only relays with read actions enabled can receive a partition
+        let mut relays: Vec<(Url, Relay)> = relays
+            .into_iter()
+            .filter(|(_, relay)| relay.opts().get_read())
+            .collect();
+        relays.sort_by(|(a, ..), (b, ..)| a.cmp(b));
+
+        let mut distributed: Vec<Vec<Filter>> = vec![Vec::new(); relays.len()];
+        for filter in filters.into_iter() {
+            if relays.len() > 1 && filter.authors.len() > threshold {
+                // Round-robin
+                let mut authors: Vec<XOnlyPublicKey> = filter.authors.iter().copied().collect();
+                authors.sort();
+                let mut partitions: Vec<Vec<XOnlyPublicKey>> = vec![Vec::new(); relays.len()];
+                for (index, author) in authors.into_iter().enumerate() {
+                    partitions[index % relays.len()].push(author);
+                }
+
+                for (relay_filters, partition) in distributed.iter_mut().zip(partitions) {
+                    if !partition.is_empty() {
+                        let mut filter: Filter = filter.clone();
+                        filter.authors = partition.into_iter().collect();
+                        relay_filters.push(filter);
+                    }
+                }
+            } else {
+                for relay_filters in distributed.iter_mut() {
+                    relay_filters.push(filter.clone());
+                }
+            }
         }
+
+        relays
+            .into_iter()
+            .zip(distributed)
+            .filter(|(_, filters)| !filters.is_empty())
+            .map(|((url, relay), filters)| (url, (relay, filters)))
+            .collect()
     }
 
     /// Connect to all added relays and keep connection alive
@@ -895,6 +1885,12 @@ impl RelayPool {
         relay
             .update_subscription_filters(InternalSubscriptionId::Pool, filters)
             .await;
+        let subscriptions = self.subscriptions.read().await.clone();
+        for (internal_id, filters) in subscriptions.into_iter() {
+            relay
+                .update_subscription_filters(internal_id, filters)
+                .await;
+        }
         relay.connect(connection_timeout).await;
     }
 
@@ -905,36 +1901,78 @@ impl RelayPool {
     }
 
     /// Negentropy reconciliation
-    pub async fn reconcile(&self, filter: Filter, opts: NegentropyOptions) -> Result<(), Error> {
+    ///
+    /// Relays that don't support negentropy fall back to `REQ`.
+    pub async fn reconcile(
+        &self,
+        filter: Filter,
+        opts: NegentropyOptions,
+    ) -> Result<Output<()>, Error> {
         let items: Vec<(EventId, Timestamp)> =
             self.database.negentropy_items(filter.clone()).await?;
         self.reconcile_with_items(filter, items, opts).await
     }
 
     /// Negentropy reconciliation with custom items
+    ///
+    /// Relays that don't support negentropy fall back to `REQ`.
     pub async fn reconcile_with_items(
         &self,
         filter: Filter,
         items: Vec<(EventId, Timestamp)>,
         opts: NegentropyOptions,
-    ) -> Result<(), Error> {
+    ) -> Result<Output<()>, Error> {
         let mut handles = Vec::new();
         let relays = self.relays().await;
         for (url, relay) in relays.into_iter() {
             let filter = filter.clone();
             let my_items = items.clone();
             let handle = thread::spawn(async move {
-                if let Err(e) = relay.reconcile(filter, my_items, opts).await {
-                    tracing::error!("Failed to get reconcile with {url}: {e}");
-                }
+                let now = Instant::now();
+                let report: RelayReport<()> =
+                    match relay.reconcile(filter.clone(), my_items, opts).await {
+                        Ok(()) => RelayReport::Success(()),
+                        Err(RelayError::NegentropyNotSupported) => {
+                            match relay
+                                .get_events_of_with_callback(
+                                    vec![filter],
+                                    opts.static_get_events_timeout,
+                                    FilterOptions::ExitOnEOSE,
+                                    |_| async {},
+                                )
+                                .await
+                            {
+                                Ok(()) => RelayReport::Fallback {
+                                    capability: RelayCapability::Negentropy,
+                                    output: (),
+                                },
+                                Err(RelayError::UnsupportedCapability(capability)) => {
+                                    RelayReport::Skipped(capability)
+                                }
+                                Err(e) => RelayReport::Failed(e.to_string()),
+                            }
+                        }
+                        Err(e) => {
+                            tracing::error!("Failed to get reconcile with {url}: {e}");
+                            RelayReport::Failed(e.to_string())
+                        }
+                    };
+                (url, report, now.elapsed())
             });
             handles.push(handle);
         }
 
+        let mut output: Output<()> = Output::new(());
         for handle in handles.into_iter().flatten() {
-            handle.join().await?;
+            let (url, report, elapsed) = handle.join().await?;
+            output.insert(url, report, elapsed);
         }
 
-        Ok(())
+        Ok(output)
     }
 }
+
+/// Internal subscription ID of a relay group
+fn group_internal_id(group: &str) -> InternalSubscriptionId {
+    InternalSubscriptionId::Custom(format!("group:{group}"))
+}
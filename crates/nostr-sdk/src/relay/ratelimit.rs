@@ -0,0 +1,110 @@
+// Copyright (c) 2022-2023 Yuki Kishimoto
+// Copyright (c) 2023-2024 Rust Nostr Developers
+// Distributed under the MIT software license
+
+//! Outgoing message rate limiting
+
+use std::time::Duration;
+
+use async_utility::thread;
+use nostr::types::time::Instant;
+
+use super::options::RateLimits;
+
+/// Token bucket
+///
+/// Allow bursts up to `capacity` messages, refilled at `capacity` tokens per `period`.
+#[derive(Debug)]
+struct TokenBucket {
+    capacity: f64,
+    tokens: f64,
+    /// Tokens per second
+    rate: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(capacity: u32, period: Duration) -> Self {
+        let capacity: f64 = f64::from(capacity.max(1));
+        Self {
+            capacity,
+            tokens: capacity,
+            rate: capacity / period.as_secs_f64(),
+            last_refill: Instant::now(),
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed: f64 = now.duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.capacity);
+        self.last_refill = now;
+    }
+
+    /// Time to wait before a token is available
+    fn wait_time(&mut self, now: Instant) -> Duration {
+        self.refill(now);
+        if self.tokens >= 1.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64((1.0 - self.tokens) / self.rate)
+        }
+    }
+
+    fn consume(&mut self) {
+        self.tokens -= 1.0;
+    }
+}
+
+/// Per-relay outgoing rate limiter
+///
+/// Owned by the task that writes to the relay: messages exceeding the limits wait in its queue.
+#[derive(Debug)]
+pub(crate) struct RateLimiter {
+    messages: Option<TokenBucket>,
+    events: Option<TokenBucket>,
+}
+
+impl RateLimiter {
+    pub fn new(limits: RateLimits) -> Self {
+        Self {
+            messages: limits
+                .messages_per_sec
+                .map(|max| TokenBucket::new(max, Duration::from_secs(1))),
+            events: limits
+                .events_per_min
+                .map(|max| TokenBucket::new(max, Duration::from_secs(60))),
+        }
+    }
+
+    /// Wait until a message (`EVENT` if `is_event`) can be sent, and consume its tokens
+    pub async fn acquire(&mut self, is_event: bool) {
+        loop {
+            let now = Instant::now();
+            let mut wait: Duration = Duration::ZERO;
+            if let Some(bucket) = &mut self.messages {
+                wait = wait.max(bucket.wait_time(now));
+            }
+            if is_event {
+                if let Some(bucket) = &mut self.events {
+                    wait = wait.max(bucket.wait_time(now));
+                }
+            }
+
+            if wait.is_zero() {
+                break;
+            }
+
+            tracing::debug!("Rate limit reached: waiting {wait:?}");
+            thread::sleep(wait).await;
+        }
+
+        if let Some(bucket) = &mut self.messages {
+            bucket.consume();
+        }
+        if is_event {
+            if let Some(bucket) = &mut self.events {
+                bucket.consume();
+            }
+        }
+    }
+}
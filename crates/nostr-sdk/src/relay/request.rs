@@ -0,0 +1,143 @@
+// Copyright (c) 2022-2023 Yuki Kishimoto
+// Copyright (c) 2023-2024 Rust Nostr Developers
+// Distributed under the MIT software license
+
+//! Request handle
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_utility::futures_util::stream::AbortHandle;
+use async_utility::thread;
+use nostr::{Filter, Url};
+use tokio::sync::{Mutex, Notify, RwLock};
+
+use super::{FilterOptions, Relay};
+
+/// Status of a request on a single relay
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReqStatus {
+    /// Waiting for stored events
+    Pending,
+    /// `EOSE` received (or timeout reached)
+    Completed,
+    /// Request failed
+    Failed(String),
+    /// Request cancelled
+    Cancelled,
+}
+
+impl ReqStatus {
+    /// Check if the request is no longer in flight
+    pub fn is_done(&self) -> bool {
+        !matches!(self, Self::Pending)
+    }
+}
+
+/// Handle of an in-flight `REQ`
+///
+/// Events are still delivered to the notification listener.
+/// Cancelling the handle sends `CLOSE` to the relays that haven't completed yet.
+#[derive(Debug, Clone, Default)]
+pub struct ReqHandle {
+    statuses: Arc<RwLock<HashMap<Url, ReqStatus>>>,
+    abort_handles: Arc<Mutex<HashMap<Url, AbortHandle>>>,
+    notify: Arc<Notify>,
+}
+
+impl ReqHandle {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Spawn the request for `relay`, tracking its status
+    pub(crate) async fn spawn(
+        &self,
+        relay: Relay,
+        filters: Vec<Filter>,
+        timeout: Duration,
+        opts: FilterOptions,
+    ) {
+        let url: Url = relay.url();
+        self.statuses
+            .write()
+            .await
+            .insert(url.clone(), ReqStatus::Pending);
+
+        let handle = self.clone();
+        let abort_handle: AbortHandle = thread::abortable(async move {
+            // Events are sent to notification listener
+            let status = match relay
+                .get_events_of_with_callback(filters, timeout, opts, |_| async {})
+                .await
+            {
+                Ok(()) => ReqStatus::Completed,
+                Err(e) => {
+                    tracing::error!("Impossible to request events from {}: {e}", relay.url());
+                    ReqStatus::Failed(e.to_string())
+                }
+            };
+            handle.set_status(&relay.url(), status).await;
+        });
+
+        self.abort_handles.lock().await.insert(url, abort_handle);
+    }
+
+    /// Update status, only if the request is still pending
+    async fn set_status(&self, url: &Url, status: ReqStatus) {
+        let mut statuses = self.statuses.write().await;
+        if let Some(current) = statuses.get_mut(url) {
+            if *current == ReqStatus::Pending {
+                *current = status;
+            }
+        }
+        drop(statuses);
+        self.notify.notify_waiters();
+    }
+
+    /// Get the request status of a relay
+    pub async fn status(&self, url: &Url) -> Option<ReqStatus> {
+        self.statuses.read().await.get(url).cloned()
+    }
+
+    /// Get the request status of all relays
+    pub async fn statuses(&self) -> HashMap<Url, ReqStatus> {
+        self.statuses.read().await.clone()
+    }
+
+    /// Check if all relays completed, failed or have been cancelled
+    pub async fn is_completed(&self) -> bool {
+        self.statuses.read().await.values().all(|s| s.is_done())
+    }
+
+    /// Wait until all relays completed, failed or have been cancelled
+    pub async fn wait(&self) {
+        loop {
+            let notified = self.notify.notified();
+            if self.is_completed().await {
+                break;
+            }
+            notified.await;
+        }
+    }
+
+    /// Cancel the request
+    ///
+    /// Relays still pending are marked as [`ReqStatus::Cancelled`].
+    pub async fn cancel(&self) {
+        let mut abort_handles = self.abort_handles.lock().await;
+        for (_, abort_handle) in abort_handles.drain() {
+            abort_handle.abort();
+        }
+
+        let mut statuses = self.statuses.write().await;
+        for status in statuses.values_mut() {
+            if *status == ReqStatus::Pending {
+                *status = ReqStatus::Cancelled;
+            }
+        }
+        drop(statuses);
+        self.notify.notify_waiters();
+    }
+}
@@ -0,0 +1,210 @@
+// Copyright (c) 2022-2023 Yuki Kishimoto
+// Copyright (c) 2023-2024 Rust Nostr Developers
+// Distributed under the MIT software license
+
+//! Relay Pool snapshot
+
+use std::collections::HashMap;
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Duration;
+
+use nostr::{serde_json, Filter, JsonUtil, Url};
+use serde::{Deserialize, Serialize};
+
+#[cfg(not(target_arch = "wasm32"))]
+use super::options::{DEFAULT_PING_INTERVAL, DEFAULT_PONG_TIMEOUT};
+#[cfg(not(target_arch = "wasm32"))]
+use super::RelayProxy;
+use super::{InternalSubscriptionId, RateLimits, ReconnectPolicy, Relay, RelayOptions};
+
+/// [`Relay`] snapshot
+///
+/// Capture all the [`RelayOptions`] and the active subscriptions.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RelaySnapshot {
+    /// Relay url
+    pub url: Url,
+    /// Proxy
+    #[cfg(not(target_arch = "wasm32"))]
+    #[serde(default = "default_proxy")]
+    pub proxy: RelayProxy,
+    /// Read actions allowed
+    pub read: bool,
+    /// Write actions allowed
+    pub write: bool,
+    /// Auto reconnection enabled
+    pub reconnect: bool,
+    /// Reconnection backoff policy
+    #[serde(default)]
+    pub reconnect_policy: ReconnectPolicy,
+    /// Interval between keepalive pings
+    #[cfg(not(target_arch = "wasm32"))]
+    #[serde(default = "default_ping_interval")]
+    pub ping_interval: Duration,
+    /// Max time to wait for the pong
+    #[cfg(not(target_arch = "wasm32"))]
+    #[serde(default = "default_pong_timeout")]
+    pub pong_timeout: Duration,
+    /// Outgoing message rate limits
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rate_limits: Option<RateLimits>,
+    /// Max number of raw frames kept in the debug capture
+    #[cfg(feature = "debug")]
+    #[serde(default)]
+    pub debug_capture: usize,
+    /// Active subscriptions filters, by internal subscription ID
+    #[serde(default)]
+    pub subscriptions: HashMap<String, Vec<Filter>>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn default_proxy() -> RelayProxy {
+    RelayProxy::default()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn default_ping_interval() -> Duration {
+    DEFAULT_PING_INTERVAL
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn default_pong_timeout() -> Duration {
+    DEFAULT_PONG_TIMEOUT
+}
+
+impl RelaySnapshot {
+    pub(crate) async fn from_relay(relay: &Relay) -> Self {
+        let opts: RelayOptions = relay.opts();
+        Self {
+            url: relay.url(),
+            #[cfg(not(target_arch = "wasm32"))]
+            proxy: opts.proxy,
+            read: opts.get_read(),
+            write: opts.get_write(),
+            reconnect: opts.get_reconnect(),
+            reconnect_policy: opts.reconnect_policy,
+            #[cfg(not(target_arch = "wasm32"))]
+            ping_interval: opts.ping_interval,
+            #[cfg(not(target_arch = "wasm32"))]
+            pong_timeout: opts.pong_timeout,
+            rate_limits: opts.rate_limits,
+            #[cfg(feature = "debug")]
+            debug_capture: opts.get_debug_capture(),
+            subscriptions: relay
+                .subscriptions()
+                .await
+                .into_iter()
+                .map(|(internal_id, sub)| (internal_id.to_string(), sub.filters()))
+                .collect(),
+        }
+    }
+
+    /// Compose [`RelayOptions`]
+    pub fn opts(&self) -> RelayOptions {
+        let opts: RelayOptions = RelayOptions::new()
+            .read(self.read)
+            .write(self.write)
+            .reconnect(self.reconnect)
+            .reconnect_policy(self.reconnect_policy)
+            .rate_limits(self.rate_limits);
+
+        #[cfg(not(target_arch = "wasm32"))]
+        let opts: RelayOptions = {
+            let mut opts: RelayOptions = opts
+                .ping_interval(self.ping_interval)
+                .pong_timeout(self.pong_timeout);
+            opts.proxy = self.proxy;
+            opts
+        };
+
+        #[cfg(feature = "debug")]
+        let opts: RelayOptions = opts.debug_capture(self.debug_capture);
+
+        opts
+    }
+
+    /// Get subscriptions with parsed [`InternalSubscriptionId`]
+    pub fn internal_subscriptions(&self) -> HashMap<InternalSubscriptionId, Vec<Filter>> {
+        self.subscriptions
+            .iter()
+            .map(|(internal_id, filters)| {
+                (
+                    InternalSubscriptionId::from(internal_id.as_str()),
+                    filters.clone(),
+                )
+            })
+            .collect()
+    }
+}
+
+/// [`RelayPool`](super::pool::RelayPool) snapshot
+///
+/// Can be serialized, persisted and used to rebuild the pool with `RelayPool::restore`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RelayPoolSnapshot {
+    /// Relays
+    pub relays: Vec<RelaySnapshot>,
+    /// Pool subscription filters
+    #[serde(default)]
+    pub filters: Vec<Filter>,
+}
+
+impl JsonUtil for RelayPoolSnapshot {
+    type Err = serde_json::Error;
+}
+
+#[cfg(test)]
+mod tests {
+    use nostr::Kind;
+
+    use super::*;
+    use crate::relay::pool::RelayPool;
+    use crate::relay::RelayPoolOptions;
+
+    #[tokio::test]
+    async fn test_snapshot_restore() {
+        let url = Url::parse("wss://relay.example.com").unwrap();
+        let policy = ReconnectPolicy::fixed(Duration::from_secs(30));
+        let limits = RateLimits {
+            messages_per_sec: Some(5),
+            events_per_min: Some(60),
+        };
+        let opts = RelayOptions::new()
+            .read(false)
+            .reconnect(false)
+            .reconnect_policy(policy)
+            .rate_limits(Some(limits))
+            .proxy(None)
+            .ping_interval(Duration::from_secs(30))
+            .pong_timeout(Duration::from_secs(5));
+
+        let pool = RelayPool::new(RelayPoolOptions::default());
+        pool.add_relay(&url, opts).await.unwrap();
+        let filters = vec![Filter::new().kind(Kind::TextNote).limit(10)];
+        pool.relay(&url)
+            .await
+            .unwrap()
+            .update_subscription_filters(InternalSubscriptionId::from("sub"), filters)
+            .await;
+
+        // Snapshot -> JSON -> restore
+        let snapshot = pool.snapshot().await;
+        let snapshot_json = snapshot.as_json();
+        let restored = RelayPool::new(RelayPoolOptions::default());
+        restored
+            .restore(RelayPoolSnapshot::from_json(snapshot_json).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(restored.snapshot().await, snapshot);
+
+        let opts: RelayOptions = restored.relay(&url).await.unwrap().opts();
+        assert!(!opts.get_read());
+        assert!(opts.get_write());
+        assert!(!opts.get_reconnect());
+        assert_eq!(opts.reconnect_policy, policy);
+        assert_eq!(opts.rate_limits, Some(limits));
+        assert_eq!(opts.proxy, RelayProxy::Direct);
+        assert_eq!(opts.ping_interval, Duration::from_secs(30));
+        assert_eq!(opts.pong_timeout, Duration::from_secs(5));
+    }
+}
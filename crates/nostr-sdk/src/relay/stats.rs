@@ -10,10 +10,11 @@ use std::collections::VecDeque;
 use std::sync::atomic::AtomicBool;
 use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 #[cfg(not(target_arch = "wasm32"))]
-use std::time::{Duration, Instant};
+use std::time::Instant;
 
-use nostr::Timestamp;
+use nostr::{RawRelayMessage, Timestamp};
 #[cfg(not(target_arch = "wasm32"))]
 use tokio::sync::RwLock;
 
@@ -92,6 +93,15 @@ pub struct RelayConnectionStats {
     success: Arc<AtomicUsize>,
     bytes_sent: Arc<AtomicUsize>,
     bytes_received: Arc<AtomicUsize>,
+    events_sent: Arc<AtomicUsize>,
+    events_received: Arc<AtomicUsize>,
+    oks_accepted: Arc<AtomicUsize>,
+    oks_rejected: Arc<AtomicUsize>,
+    eoses: Arc<AtomicUsize>,
+    auth_challenges: Arc<AtomicUsize>,
+    /// Sum of the publish latencies, in microseconds
+    publish_latency_sum: Arc<AtomicU64>,
+    publish_count: Arc<AtomicU64>,
     connected_at: Arc<AtomicU64>,
     first_connection_timestamp: Arc<AtomicU64>,
     #[cfg(not(target_arch = "wasm32"))]
@@ -114,6 +124,14 @@ impl RelayConnectionStats {
             success: Arc::new(AtomicUsize::new(0)),
             bytes_sent: Arc::new(AtomicUsize::new(0)),
             bytes_received: Arc::new(AtomicUsize::new(0)),
+            events_sent: Arc::new(AtomicUsize::new(0)),
+            events_received: Arc::new(AtomicUsize::new(0)),
+            oks_accepted: Arc::new(AtomicUsize::new(0)),
+            oks_rejected: Arc::new(AtomicUsize::new(0)),
+            eoses: Arc::new(AtomicUsize::new(0)),
+            auth_challenges: Arc::new(AtomicUsize::new(0)),
+            publish_latency_sum: Arc::new(AtomicU64::new(0)),
+            publish_count: Arc::new(AtomicU64::new(0)),
             connected_at: Arc::new(AtomicU64::new(0)),
             first_connection_timestamp: Arc::new(AtomicU64::new(0)),
             #[cfg(not(target_arch = "wasm32"))]
@@ -154,6 +172,43 @@ impl RelayConnectionStats {
         self.bytes_received.load(Ordering::SeqCst)
     }
 
+    /// Number of `EVENT` messages sent
+    pub fn events_sent(&self) -> usize {
+        self.events_sent.load(Ordering::SeqCst)
+    }
+
+    /// Number of `EVENT` messages received
+    pub fn events_received(&self) -> usize {
+        self.events_received.load(Ordering::SeqCst)
+    }
+
+    /// Number of `OK` messages received with `true` status
+    pub fn oks_accepted(&self) -> usize {
+        self.oks_accepted.load(Ordering::SeqCst)
+    }
+
+    /// Number of `OK` messages received with `false` status
+    pub fn oks_rejected(&self) -> usize {
+        self.oks_rejected.load(Ordering::SeqCst)
+    }
+
+    /// Number of `EOSE` messages received
+    pub fn eoses(&self) -> usize {
+        self.eoses.load(Ordering::SeqCst)
+    }
+
+    /// Number of `AUTH` challenges received (NIP42)
+    pub fn auth_challenges(&self) -> usize {
+        self.auth_challenges.load(Ordering::SeqCst)
+    }
+
+    /// Average time between the sending of an event and its `OK` acceptance
+    pub fn publish_latency(&self) -> Option<Duration> {
+        let count: u64 = self.publish_count.load(Ordering::SeqCst);
+        let sum: u64 = self.publish_latency_sum.load(Ordering::SeqCst);
+        sum.checked_div(count).map(Duration::from_micros)
+    }
+
     /// Get UNIX timestamp of the last connection
     pub fn connected_at(&self) -> Timestamp {
         Timestamp::from(self.connected_at.load(Ordering::SeqCst))
@@ -210,6 +265,29 @@ impl RelayConnectionStats {
         }
     }
 
+    pub(crate) fn add_events_sent(&self, count: usize) {
+        self.events_sent.fetch_add(count, Ordering::SeqCst);
+    }
+
+    /// Count a received message
+    pub(crate) fn new_message_received(&self, msg: &RawRelayMessage) {
+        let counter: &AtomicUsize = match msg {
+            RawRelayMessage::Event { .. } => &self.events_received,
+            RawRelayMessage::Ok { status: true, .. } => &self.oks_accepted,
+            RawRelayMessage::Ok { status: false, .. } => &self.oks_rejected,
+            RawRelayMessage::EndOfStoredEvents(..) => &self.eoses,
+            RawRelayMessage::Auth { .. } => &self.auth_challenges,
+            _ => return,
+        };
+        counter.fetch_add(1, Ordering::SeqCst);
+    }
+
+    pub(crate) fn save_publish_latency(&self, latency: Duration) {
+        let micros: u64 = u64::try_from(latency.as_micros()).unwrap_or(u64::MAX);
+        self.publish_latency_sum.fetch_add(micros, Ordering::SeqCst);
+        self.publish_count.fetch_add(1, Ordering::SeqCst);
+    }
+
     #[cfg(not(target_arch = "wasm32"))]
     pub(crate) async fn save_latency(&self, latency: Duration) {
         let mut latencies = self.latencies.write().await;
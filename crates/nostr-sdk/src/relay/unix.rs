@@ -0,0 +1,79 @@
+// Copyright (c) 2022-2023 Yuki Kishimoto
+// Copyright (c) 2023-2024 Rust Nostr Developers
+// Distributed under the MIT software license
+
+//! Unix domain socket transport
+//!
+//! Relay URLs with the `ws+unix` scheme are connected through a unix socket, skipping TCP/TLS.
+//! The socket path and the HTTP request path are separated by `:`
+//! (i.e. `ws+unix:///run/strfry.sock` or `ws+unix:///run/strfry.sock:/nostr`).
+
+use std::time::Duration;
+
+use async_wsocket::futures_util::stream::{SplitSink, SplitStream};
+use async_wsocket::futures_util::StreamExt;
+use async_wsocket::WsMessage;
+use nostr::Url;
+use thiserror::Error;
+use tokio::net::UnixStream;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::Error as WsError;
+use tokio_tungstenite::WebSocketStream;
+
+/// Unix socket relay URL scheme
+pub const UNIX_SCHEME: &str = "ws+unix";
+
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(60);
+
+type WebSocket = WebSocketStream<UnixStream>;
+pub(crate) type Sink = SplitSink<WebSocket, WsMessage>;
+pub(crate) type Stream = SplitStream<WebSocket>;
+
+/// Unix socket transport error
+#[derive(Debug, Error)]
+pub enum Error {
+    /// I/O error
+    #[error(transparent)]
+    IO(#[from] std::io::Error),
+    /// Websocket error
+    #[error(transparent)]
+    Ws(#[from] WsError),
+    /// Socket path not found in URL
+    #[error("socket path not found")]
+    SocketPathNotFound,
+    /// Timeout
+    #[error("timeout")]
+    Timeout,
+}
+
+/// Check if the URL must be connected through a unix socket
+pub fn is_unix_url(url: &Url) -> bool {
+    url.scheme() == UNIX_SCHEME
+}
+
+/// Split URL path into socket path and HTTP request path
+fn split_path(url: &Url) -> (&str, &str) {
+    match url.path().split_once(':') {
+        Some((socket, path)) if path.starts_with('/') => (socket, path),
+        _ => (url.path(), "/"),
+    }
+}
+
+pub(crate) async fn connect(url: &Url, timeout: Option<Duration>) -> Result<(Sink, Stream), Error> {
+    let (socket, path) = split_path(url);
+    if socket.is_empty() {
+        return Err(Error::SocketPathNotFound);
+    }
+
+    // The host is only used for the `Host` header
+    let request = format!("ws://localhost{path}").into_client_request()?;
+    let timeout: Duration = timeout.unwrap_or(DEFAULT_TIMEOUT);
+    let (stream, _) = tokio::time::timeout(timeout, async {
+        let stream = UnixStream::connect(socket).await?;
+        Ok::<_, Error>(tokio_tungstenite::client_async(request, stream).await?)
+    })
+    .await
+    .map_err(|_| Error::Timeout)??;
+
+    Ok(stream.split())
+}
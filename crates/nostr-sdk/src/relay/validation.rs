@@ -0,0 +1,370 @@
+// Copyright (c) 2022-2023 Yuki Kishimoto
+// Copyright (c) 2023-2024 Rust Nostr Developers
+// Distributed under the MIT software license
+
+//! Message validation
+//!
+//! Check the outgoing messages against the relay limitation (NIP11),
+//! to not send messages that would be rejected anyway.
+
+#[cfg(feature = "nip11")]
+use nostr::nips::nip11::RelayInformationDocument;
+use nostr::nips::nip13;
+use nostr::{Event, JsonUtil, Timestamp};
+use thiserror::Error;
+
+#[cfg(feature = "nip11")]
+use super::chunking::positive;
+
+/// Size of `["EVENT",]`
+const EVENT_MSG_OVERHEAD: usize = 10;
+
+/// Relay limitation violation
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum LimitViolation {
+    /// Message too large
+    #[error("message too large: size={size}, max={max}")]
+    MessageTooLarge {
+        /// Message size, in bytes
+        size: usize,
+        /// Max size allowed by the relay
+        max: usize,
+    },
+    /// Too many filters in the `REQ`
+    #[error("too many filters: count={count}, max={max}")]
+    TooManyFilters {
+        /// Number of filters
+        count: usize,
+        /// Max number of filters allowed by the relay
+        max: usize,
+    },
+    /// Too many tags in the event
+    #[error("too many tags: count={count}, max={max}")]
+    TooManyTags {
+        /// Number of tags
+        count: usize,
+        /// Max number of tags allowed by the relay
+        max: usize,
+    },
+    /// Event content too long
+    #[error("content too long: len={len}, max={max}")]
+    ContentTooLong {
+        /// Number of characters
+        len: usize,
+        /// Max number of characters allowed by the relay
+        max: usize,
+    },
+    /// Event PoW difficulty too low (NIP13)
+    #[error("insufficient PoW: difficulty={difficulty}, min={min}")]
+    InsufficientPow {
+        /// Event difficulty
+        difficulty: u8,
+        /// Min difficulty required by the relay
+        min: u8,
+    },
+    /// Event `created_at` out of the range accepted by the relay
+    #[error("created_at out of range: {created_at}")]
+    CreatedAtOutOfRange {
+        /// Event timestamp
+        created_at: Timestamp,
+    },
+}
+
+/// Relay limits for outgoing messages
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct MessageLimits {
+    /// Max size of a message, in bytes
+    pub max_message_length: Option<usize>,
+    /// Max number of filters per `REQ`
+    pub max_filters: Option<usize>,
+    /// Max number of tags of an event
+    pub max_event_tags: Option<usize>,
+    /// Max number of characters of the event content
+    pub max_content_length: Option<usize>,
+    /// Min PoW difficulty of an event
+    pub min_pow_difficulty: Option<u8>,
+    /// Max age of `created_at`, in seconds
+    pub created_at_lower_limit: Option<u64>,
+    /// Max distance in the future of `created_at`, in seconds
+    pub created_at_upper_limit: Option<u64>,
+}
+
+impl MessageLimits {
+    #[cfg(feature = "nip11")]
+    pub fn from_document(document: &RelayInformationDocument) -> Self {
+        match &document.limitation {
+            Some(limitation) => Self {
+                max_message_length: positive(limitation.max_message_length),
+                max_filters: positive(limitation.max_filters),
+                max_event_tags: positive(limitation.max_event_tags),
+                max_content_length: positive(limitation.max_content_length),
+                min_pow_difficulty: positive(limitation.min_pow_difficulty)
+                    .map(|d| d.min(u8::MAX as usize) as u8),
+                created_at_lower_limit: limitation
+                    .created_at_lower_limit
+                    .map(|t| t.as_u64())
+                    .filter(|t| *t > 0),
+                created_at_upper_limit: limitation
+                    .created_at_upper_limit
+                    .map(|t| t.as_u64())
+                    .filter(|t| *t > 0),
+            },
+            None => Self::default(),
+        }
+    }
+
+    /// Check the `EVENT` message
+    pub fn check_event(&self, event: &Event) -> Result<(), LimitViolation> {
+        if self.max_message_length.is_some() {
+            self.check_size(event.as_json().len() + EVENT_MSG_OVERHEAD)?;
+        }
+
+        if let Some(max) = self.max_event_tags {
+            let count: usize = event.tags().len();
+            if count > max {
+                return Err(LimitViolation::TooManyTags { count, max });
+            }
+        }
+
+        if let Some(max) = self.max_content_length {
+            let len: usize = event.content().chars().count();
+            if len > max {
+                return Err(LimitViolation::ContentTooLong { len, max });
+            }
+        }
+
+        if let Some(min) = self.min_pow_difficulty {
+            let difficulty: u8 = nip13::get_leading_zero_bits(event.id().as_bytes());
+            if difficulty < min {
+                return Err(LimitViolation::InsufficientPow { difficulty, min });
+            }
+        }
+
+        let created_at: u64 = event.created_at().as_u64();
+        let now: u64 = Timestamp::now().as_u64();
+        if let Some(lower) = self.created_at_lower_limit {
+            if created_at < now.saturating_sub(lower) {
+                return Err(LimitViolation::CreatedAtOutOfRange {
+                    created_at: event.created_at(),
+                });
+            }
+        }
+        if let Some(upper) = self.created_at_upper_limit {
+            if created_at > now.saturating_add(upper) {
+                return Err(LimitViolation::CreatedAtOutOfRange {
+                    created_at: event.created_at(),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Check the `REQ` message of a subscription
+    pub fn check_req(&self, filters_count: usize, size: usize) -> Result<(), LimitViolation> {
+        self.check_size(size)?;
+
+        if let Some(max) = self.max_filters {
+            if filters_count > max {
+                return Err(LimitViolation::TooManyFilters {
+                    count: filters_count,
+                    max,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    fn check_size(&self, size: usize) -> Result<(), LimitViolation> {
+        match self.max_message_length {
+            Some(max) if size > max => Err(LimitViolation::MessageTooLarge { size, max }),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use nostr::{EventBuilder, Keys, Tag};
+
+    use super::*;
+
+    #[test]
+    fn test_check_event() {
+        let keys = Keys::generate();
+        let event = EventBuilder::text_note("abc", [Tag::Hashtag(String::from("a"))])
+            .to_event(&keys)
+            .unwrap();
+        let size: usize = event.as_json().len() + EVENT_MSG_OVERHEAD;
+
+        assert!(MessageLimits::default().check_event(&event).is_ok());
+
+        let limits = MessageLimits {
+            max_message_length: Some(size - 1),
+            ..Default::default()
+        };
+        assert_eq!(
+            limits.check_event(&event),
+            Err(LimitViolation::MessageTooLarge {
+                size,
+                max: size - 1
+            })
+        );
+
+        let limits = MessageLimits {
+            max_event_tags: Some(0),
+            ..Default::default()
+        };
+        assert_eq!(
+            limits.check_event(&event),
+            Err(LimitViolation::TooManyTags { count: 1, max: 0 })
+        );
+
+        let limits = MessageLimits {
+            max_content_length: Some(2),
+            ..Default::default()
+        };
+        assert_eq!(
+            limits.check_event(&event),
+            Err(LimitViolation::ContentTooLong { len: 3, max: 2 })
+        );
+
+        let difficulty: u8 = nip13::get_leading_zero_bits(event.id().as_bytes());
+        let limits = MessageLimits {
+            min_pow_difficulty: Some(difficulty + 1),
+            ..Default::default()
+        };
+        assert_eq!(
+            limits.check_event(&event),
+            Err(LimitViolation::InsufficientPow {
+                difficulty,
+                min: difficulty + 1
+            })
+        );
+
+        // Limits exactly met
+        let limits = MessageLimits {
+            max_message_length: Some(size),
+            max_event_tags: Some(1),
+            max_content_length: Some(3),
+            min_pow_difficulty: Some(difficulty),
+            created_at_lower_limit: Some(60),
+            created_at_upper_limit: Some(60),
+            ..Default::default()
+        };
+        assert!(limits.check_event(&event).is_ok());
+    }
+
+    #[test]
+    fn test_check_event_created_at() {
+        let keys = Keys::generate();
+        let limits = MessageLimits {
+            created_at_lower_limit: Some(60),
+            created_at_upper_limit: Some(60),
+            ..Default::default()
+        };
+
+        let old = EventBuilder::text_note("old", [])
+            .custom_created_at(Timestamp::now() - 3600_u64)
+            .to_event(&keys)
+            .unwrap();
+        assert_eq!(
+            limits.check_event(&old),
+            Err(LimitViolation::CreatedAtOutOfRange {
+                created_at: old.created_at()
+            })
+        );
+
+        let future = EventBuilder::text_note("future", [])
+            .custom_created_at(Timestamp::now() + 3600_u64)
+            .to_event(&keys)
+            .unwrap();
+        assert_eq!(
+            limits.check_event(&future),
+            Err(LimitViolation::CreatedAtOutOfRange {
+                created_at: future.created_at()
+            })
+        );
+    }
+
+    #[test]
+    fn test_check_req() {
+        let limits = MessageLimits {
+            max_message_length: Some(100),
+            max_filters: Some(2),
+            ..Default::default()
+        };
+        assert!(limits.check_req(2, 100).is_ok());
+        assert_eq!(
+            limits.check_req(1, 101),
+            Err(LimitViolation::MessageTooLarge {
+                size: 101,
+                max: 100
+            })
+        );
+        assert_eq!(
+            limits.check_req(3, 50),
+            Err(LimitViolation::TooManyFilters { count: 3, max: 2 })
+        );
+        assert!(MessageLimits::default().check_req(100, 100_000).is_ok());
+    }
+
+    #[test]
+    #[cfg(feature = "nip11")]
+    fn test_from_document() {
+        use core::str::FromStr;
+
+        use nostr::nips::nip11::Limitation;
+
+        assert_eq!(
+            MessageLimits::from_document(&RelayInformationDocument::default()),
+            MessageLimits::default()
+        );
+
+        // Zero and negative values mean no limit
+        let document = RelayInformationDocument {
+            limitation: Some(Limitation {
+                max_message_length: Some(0),
+                max_filters: Some(-1),
+                max_event_tags: Some(0),
+                max_content_length: Some(-10),
+                min_pow_difficulty: Some(-1),
+                created_at_lower_limit: Some(Timestamp::from(0)),
+                created_at_upper_limit: Some(Timestamp::from_str("-1").unwrap()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        assert_eq!(
+            MessageLimits::from_document(&document),
+            MessageLimits::default()
+        );
+
+        let document = RelayInformationDocument {
+            limitation: Some(Limitation {
+                max_message_length: Some(16384),
+                max_filters: Some(10),
+                max_event_tags: Some(100),
+                max_content_length: Some(8196),
+                min_pow_difficulty: Some(300),
+                created_at_lower_limit: Some(Timestamp::from(31536000)),
+                created_at_upper_limit: Some(Timestamp::from(3)),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        assert_eq!(
+            MessageLimits::from_document(&document),
+            MessageLimits {
+                max_message_length: Some(16384),
+                max_filters: Some(10),
+                max_event_tags: Some(100),
+                max_content_length: Some(8196),
+                min_pow_difficulty: Some(u8::MAX),
+                created_at_lower_limit: Some(31536000),
+                created_at_upper_limit: Some(3),
+            }
+        );
+    }
+}
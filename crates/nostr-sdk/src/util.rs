@@ -4,8 +4,16 @@
 
 //! Util
 
+use std::pin::Pin;
+
+pub use async_utility::futures_util::stream::{Stream, StreamExt};
 use nostr::url::{ParseError, Url};
 
+/// Boxed [`Stream`]
+///
+/// Use [`StreamExt`] to consume it (i.e. `stream.next().await`).
+pub type BoxedStream<T> = Pin<Box<dyn Stream<Item = T> + Send>>;
+
 /// Try into [`Url`]
 pub trait TryIntoUrl {
     /// Error
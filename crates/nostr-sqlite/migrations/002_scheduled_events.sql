@@ -0,0 +1,9 @@
+PRAGMA user_version = 2; -- Schema version
+
+CREATE TABLE IF NOT EXISTS scheduled_events (
+    event_id BLOB PRIMARY KEY NOT NULL,
+    publish_at INTEGER NOT NULL,
+    event BLOB NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS scheduled_events_publish_at_index ON scheduled_events(publish_at);
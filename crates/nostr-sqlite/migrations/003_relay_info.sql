@@ -0,0 +1,10 @@
+PRAGMA user_version = 3; -- Schema version
+
+CREATE TABLE IF NOT EXISTS relay_info (
+    relay_url TEXT PRIMARY KEY NOT NULL,
+    name TEXT,
+    description TEXT,
+    icon TEXT,
+    contact TEXT,
+    updated_at INTEGER NOT NULL
+);
@@ -0,0 +1,9 @@
+PRAGMA user_version = 4; -- Schema version
+
+CREATE TABLE IF NOT EXISTS outbox_events (
+    event_id BLOB PRIMARY KEY NOT NULL,
+    created_at INTEGER NOT NULL,
+    event BLOB NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS outbox_events_created_at_index ON outbox_events(created_at);
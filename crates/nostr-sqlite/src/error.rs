@@ -2,7 +2,7 @@
 // Copyright (c) 2023-2024 Rust Nostr Developers
 // Distributed under the MIT software license
 
-use deadpool_sqlite::{CreatePoolError, InteractError, PoolError};
+use deadpool_sqlite::{BuildError, CreatePoolError, InteractError, PoolError};
 use nostr_database::{flatbuffers, DatabaseError};
 use thiserror::Error;
 
@@ -19,6 +19,9 @@ pub enum Error {
     CreateDeadPool(#[from] CreatePoolError),
     /// Pool error
     #[error(transparent)]
+    BuildDeadPool(#[from] BuildError),
+    /// Pool error
+    #[error(transparent)]
     DeadPool(#[from] PoolError),
     /// Pool error
     #[error("{0}")]
@@ -38,6 +41,10 @@ pub enum Error {
     /// Not found
     #[error("sqlite: {0} not found")]
     NotFound(String),
+    /// Wrong passphrase
+    #[cfg(feature = "sqlcipher")]
+    #[error("sqlite: wrong passphrase or not an encrypted database")]
+    WrongPassphrase,
 }
 
 impl From<InteractError> for Error {
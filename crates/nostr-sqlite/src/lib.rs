@@ -8,7 +8,7 @@
 #![warn(missing_docs)]
 #![warn(rustdoc::bare_urls)]
 
-use std::collections::{BTreeSet, HashSet};
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::path::Path;
 use std::sync::Arc;
 
@@ -17,11 +17,14 @@ pub extern crate nostr_database as database;
 
 use async_trait::async_trait;
 use deadpool_sqlite::{Config, Object, Pool, Runtime};
+#[cfg(feature = "sqlcipher")]
+use deadpool_sqlite::{Hook, HookError, HookErrorCause, Manager};
 use nostr::nips::nip01::Coordinate;
 use nostr::{Event, EventId, Filter, Timestamp, Url};
 use nostr_database::{
     Backend, DatabaseIndexes, DatabaseOptions, EventIndexResult, FlatBufferBuilder,
-    FlatBufferDecode, FlatBufferEncode, NostrDatabase, Order, RawEvent,
+    FlatBufferDecode, FlatBufferEncode, IntegrityIssue, IntegrityReport, NostrDatabase, Order,
+    RawEvent, RelayInfo,
 };
 use rusqlite::config::DbConfig;
 use tokio::sync::RwLock;
@@ -48,7 +51,60 @@ impl SQLiteDatabase {
     {
         let cfg = Config::new(path.as_ref());
         let pool = cfg.create_pool(Runtime::Tokio1)?;
+        Self::with_pool(pool).await
+    }
 
+    /// Open encrypted SQLite store (SQLCipher)
+    ///
+    /// Every connection is keyed with the `passphrase` before use, so events (e.g. DMs and private lists)
+    /// aren't stored in plaintext on disk. Opening an existing store with a wrong passphrase will fail.
+    #[cfg(feature = "sqlcipher")]
+    pub async fn open_encrypted<P, S>(path: P, passphrase: S) -> Result<Self, Error>
+    where
+        P: AsRef<Path>,
+        S: Into<String>,
+    {
+        let passphrase: String = passphrase.into();
+        let cfg = Config::new(path.as_ref());
+        let manager = Manager::from_config(&cfg, Runtime::Tokio1);
+        let pool = Pool::builder(manager)
+            .runtime(Runtime::Tokio1)
+            .post_create(Hook::async_fn(move |conn, _| {
+                let passphrase: String = passphrase.clone();
+                Box::pin(async move {
+                    conn.interact(move |conn| conn.pragma_update(None, "key", passphrase))
+                        .await
+                        .map_err(|e| HookError::Abort(HookErrorCause::Message(e.to_string())))?
+                        .map_err(|e| HookError::Abort(HookErrorCause::Backend(e)))?;
+                    Ok(())
+                })
+            }))
+            .build()?;
+
+        // Check the key before running migrations: SQLCipher accepts any key and fails only on first read
+        let conn = pool.get().await?;
+        conn.interact(|conn| {
+            conn.query_row("SELECT count(*) FROM sqlite_master;", [], |row| {
+                row.get::<usize, i64>(0)
+            })
+        })
+        .await?
+        .map_err(|e| match e {
+            rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error {
+                    code: rusqlite::ErrorCode::NotADatabase,
+                    ..
+                },
+                _,
+            ) => Error::WrongPassphrase,
+            e => Error::Sqlite(e),
+        })?;
+        drop(conn);
+
+        Self::with_pool(pool).await
+    }
+
+    async fn with_pool(pool: Pool) -> Result<Self, Error> {
         // Execute migrations
         let conn = pool.get().await?;
         migration::run(&conn).await?;
@@ -341,4 +397,299 @@ impl NostrDatabase for SQLiteDatabase {
 
         Ok(())
     }
+
+    #[tracing::instrument(skip_all)]
+    async fn verify_integrity(&self, prune: bool) -> Result<IntegrityReport, Self::Err> {
+        let conn = self.acquire().await?;
+        let (mut report, rowids, unidentified) = conn
+            .interact(move |conn| {
+                let mut stmt = conn.prepare_cached("SELECT rowid, event_id, event FROM events;")?;
+                let mut rows = stmt.query([])?;
+                let mut report = IntegrityReport::default();
+                // The rows are deleted by rowid, since the stored event ID may be corrupted too
+                let mut rowids: HashMap<EventId, i64> = HashMap::new();
+                let mut unidentified: Vec<i64> = Vec::new();
+                while let Some(row) = rows.next()? {
+                    let rowid: i64 = row.get(0)?;
+                    let event_id: String = row.get(1)?;
+                    let buf: Vec<u8> = row.get(2)?;
+                    let event: Option<Event> = Event::decode(&buf).ok();
+                    let event_id: EventId = match EventId::from_hex(&event_id) {
+                        Ok(event_id) => event_id,
+                        Err(e) => match &event {
+                            Some(event) => event.id(),
+                            None => {
+                                tracing::error!(
+                                    "Impossible to identify stored event {event_id}: {e}"
+                                );
+                                unidentified.push(rowid);
+                                continue;
+                            }
+                        },
+                    };
+                    match event {
+                        Some(event) => report.verify_event(event_id, &event),
+                        None => {
+                            report.checked += 1;
+                            report.add(event_id, IntegrityIssue::Undecodable);
+                        }
+                    }
+                    rowids.insert(event_id, rowid);
+                }
+                Ok::<(IntegrityReport, HashMap<EventId, i64>, Vec<i64>), Error>((
+                    report,
+                    rowids,
+                    unidentified,
+                ))
+            })
+            .await??;
+
+        let stored: HashSet<EventId> = rowids.keys().copied().collect();
+        report.extend(self.indexes.check_integrity(&stored).await);
+
+        if prune && (!report.is_ok() || !unidentified.is_empty()) {
+            // Valid events with only index issues are indexed again, the other rows are deleted
+            let mut to_reindex: Vec<i64> = Vec::new();
+            let mut to_delete: Vec<i64> = unidentified;
+            for (event_id, issue) in report.corrupted.iter() {
+                if let Some(rowid) = rowids.get(event_id).copied() {
+                    if issue.is_index_issue() {
+                        to_reindex.push(rowid);
+                    } else {
+                        to_delete.push(rowid);
+                    }
+                }
+            }
+
+            self.indexes.remove(report.corrupted.keys()).await;
+
+            let events: Vec<(i64, Event)> = conn
+                .interact(move |conn| {
+                    let mut stmt =
+                        conn.prepare_cached("SELECT event FROM events WHERE rowid = ?;")?;
+                    let mut events: Vec<(i64, Event)> = Vec::with_capacity(to_reindex.len());
+                    for rowid in to_reindex.into_iter() {
+                        let buf: Vec<u8> = stmt.query_row([rowid], |row| row.get(0))?;
+                        events.push((rowid, Event::decode(&buf)?));
+                    }
+                    Ok::<Vec<(i64, Event)>, Error>(events)
+                })
+                .await??;
+
+            let mut to_discard: HashSet<EventId> = HashSet::new();
+            for (rowid, event) in events.into_iter() {
+                let result: EventIndexResult = self.indexes.index_event(&event).await;
+
+                // Replaced or deleted in the meantime
+                if !result.to_store {
+                    to_delete.push(rowid);
+                }
+
+                to_discard.extend(result.to_discard);
+                report.reindexed += 1;
+            }
+
+            conn.interact(move |conn| {
+                let mut stmt = conn.prepare_cached("DELETE FROM events WHERE rowid = ?;")?;
+                for rowid in to_delete.into_iter() {
+                    stmt.execute([rowid])?;
+                }
+                let mut stmt = conn.prepare_cached("DELETE FROM events WHERE event_id = ?;")?;
+                for event_id in to_discard.into_iter() {
+                    stmt.execute([event_id.to_hex()])?;
+                }
+                Ok::<(), Error>(())
+            })
+            .await??;
+
+            report.pruned = true;
+        }
+
+        Ok(report)
+    }
+
+    async fn save_scheduled_event(&self, event: &Event) -> Result<(), Self::Err> {
+        // Acquire FlatBuffers Builder
+        let mut fbb = self.fbb.write().await;
+
+        // Encode
+        let event_id: EventId = event.id();
+        let publish_at: u64 = event.created_at().as_u64();
+        let value: Vec<u8> = event.encode(&mut fbb).to_vec();
+
+        // Save event
+        let conn = self.acquire().await?;
+        conn.interact(move |conn| {
+            conn.execute(
+                "INSERT OR REPLACE INTO scheduled_events (event_id, publish_at, event) VALUES (?, ?, ?);",
+                (event_id.to_hex(), publish_at as i64, value),
+            )
+        })
+        .await??;
+
+        Ok(())
+    }
+
+    async fn scheduled_events(&self) -> Result<Vec<Event>, Self::Err> {
+        let conn = self.acquire().await?;
+        Ok(conn
+            .interact(move |conn| {
+                let mut stmt = conn.prepare_cached(
+                    "SELECT event FROM scheduled_events ORDER BY publish_at ASC;",
+                )?;
+                let mut rows = stmt.query([])?;
+                let mut events = Vec::new();
+                while let Some(row) = rows.next()? {
+                    let buf: Vec<u8> = row.get(0)?;
+                    events.push(Event::decode(&buf)?);
+                }
+                Ok::<Vec<Event>, Error>(events)
+            })
+            .await??)
+    }
+
+    async fn remove_scheduled_event(&self, event_id: EventId) -> Result<bool, Self::Err> {
+        let conn = self.acquire().await?;
+        let removed: usize = conn
+            .interact(move |conn| {
+                conn.execute(
+                    "DELETE FROM scheduled_events WHERE event_id = ?;",
+                    [event_id.to_hex()],
+                )
+            })
+            .await??;
+        Ok(removed > 0)
+    }
+
+    async fn save_outbox_event(&self, event: &Event) -> Result<(), Self::Err> {
+        // Acquire FlatBuffers Builder
+        let mut fbb = self.fbb.write().await;
+
+        // Encode
+        let event_id: EventId = event.id();
+        let created_at: u64 = event.created_at().as_u64();
+        let value: Vec<u8> = event.encode(&mut fbb).to_vec();
+
+        // Save event
+        let conn = self.acquire().await?;
+        conn.interact(move |conn| {
+            conn.execute(
+                "INSERT OR REPLACE INTO outbox_events (event_id, created_at, event) VALUES (?, ?, ?);",
+                (event_id.to_hex(), created_at as i64, value),
+            )
+        })
+        .await??;
+
+        Ok(())
+    }
+
+    async fn outbox_events(&self) -> Result<Vec<Event>, Self::Err> {
+        let conn = self.acquire().await?;
+        Ok(conn
+            .interact(move |conn| {
+                let mut stmt = conn
+                    .prepare_cached("SELECT event FROM outbox_events ORDER BY created_at ASC;")?;
+                let mut rows = stmt.query([])?;
+                let mut events = Vec::new();
+                while let Some(row) = rows.next()? {
+                    let buf: Vec<u8> = row.get(0)?;
+                    events.push(Event::decode(&buf)?);
+                }
+                Ok::<Vec<Event>, Error>(events)
+            })
+            .await??)
+    }
+
+    async fn remove_outbox_event(&self, event_id: EventId) -> Result<bool, Self::Err> {
+        let conn = self.acquire().await?;
+        let removed: usize = conn
+            .interact(move |conn| {
+                conn.execute(
+                    "DELETE FROM outbox_events WHERE event_id = ?;",
+                    [event_id.to_hex()],
+                )
+            })
+            .await??;
+        Ok(removed > 0)
+    }
+
+    async fn save_relay_info(&self, info: RelayInfo) -> Result<(), Self::Err> {
+        let conn = self.acquire().await?;
+        conn.interact(move |conn| {
+            conn.execute(
+                "INSERT OR REPLACE INTO relay_info (relay_url, name, description, icon, contact, updated_at) VALUES (?, ?, ?, ?, ?, ?);",
+                (
+                    info.url.to_string(),
+                    info.name,
+                    info.description,
+                    info.icon,
+                    info.contact,
+                    info.updated_at.as_u64() as i64,
+                ),
+            )
+        })
+        .await??;
+        Ok(())
+    }
+
+    async fn relay_info(&self, url: &Url) -> Result<Option<RelayInfo>, Self::Err> {
+        let url: Url = url.clone();
+        let conn = self.acquire().await?;
+        Ok(conn
+            .interact(move |conn| {
+                let mut stmt = conn.prepare_cached(
+                    "SELECT name, description, icon, contact, updated_at FROM relay_info WHERE relay_url = ?;",
+                )?;
+                let mut rows = stmt.query([url.to_string()])?;
+                match rows.next()? {
+                    Some(row) => {
+                        let updated_at: i64 = row.get(4)?;
+                        Ok::<Option<RelayInfo>, Error>(Some(RelayInfo {
+                            url,
+                            name: row.get(0)?,
+                            description: row.get(1)?,
+                            icon: row.get(2)?,
+                            contact: row.get(3)?,
+                            updated_at: Timestamp::from(updated_at as u64),
+                        }))
+                    }
+                    None => Ok(None),
+                }
+            })
+            .await??)
+    }
+}
+
+#[cfg(all(test, feature = "sqlcipher"))]
+mod tests {
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_open_encrypted() {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos();
+        let path = std::env::temp_dir().join(format!("nostr-sqlcipher-{nanos}.db"));
+
+        // Create and key a new store
+        let db = SQLiteDatabase::open_encrypted(&path, "right")
+            .await
+            .unwrap();
+        drop(db);
+
+        // Reopen with the right passphrase
+        let db = SQLiteDatabase::open_encrypted(&path, "right")
+            .await
+            .unwrap();
+        drop(db);
+
+        // Reopen with a wrong passphrase
+        let res = SQLiteDatabase::open_encrypted(&path, "wrong").await;
+        assert!(matches!(res, Err(Error::WrongPassphrase)));
+
+        let _ = std::fs::remove_file(&path);
+    }
 }
@@ -11,7 +11,7 @@ use thiserror::Error;
 use super::Error;
 
 /// Latest database version
-pub const DB_VERSION: usize = 1;
+pub const DB_VERSION: usize = 4;
 
 /// Startup DB Pragmas
 pub const STARTUP_SQL: &str = r##"
@@ -55,18 +55,18 @@ pub(crate) async fn run(conn: &Object) -> Result<(), Error> {
 
                 // for initialized but out-of-date schemas, proceed to
                 // upgrade sequentially until we are current.
-                // if curr_version == 1 {
-                // curr_version = mig_1_to_2(conn)?;
-                // }
-                //
-                // if curr_version == 2 {
-                // curr_version = mig_2_to_3(conn)?;
-                // }
-                //
-                // if curr_version == 3 {
-                // curr_version = mig_3_to_4(conn)?;
-                // }
-                //
+                if curr_version == 1 {
+                    curr_version = mig_1_to_2(conn)?;
+                }
+
+                if curr_version == 2 {
+                    curr_version = mig_2_to_3(conn)?;
+                }
+
+                if curr_version == 3 {
+                    curr_version = mig_3_to_4(conn)?;
+                }
+
                 // if curr_version == 4 {
                 // curr_version = mig_4_to_5(conn)?;
                 // }
@@ -109,8 +109,20 @@ fn mig_init(conn: &mut Connection) -> Result<usize, Error> {
     Ok(1)
 }
 
-// fn mig_1_to_2(conn: &mut Connection) -> Result<usize, Error> {
-// conn.execute_batch(include_str!("../../migrations/002_notifications.sql"))?;
-// tracing::info!("database schema upgraded v1 -> v2");
-// Ok(2)
-// }
+fn mig_1_to_2(conn: &mut Connection) -> Result<usize, Error> {
+    conn.execute_batch(include_str!("../migrations/002_scheduled_events.sql"))?;
+    tracing::info!("database schema upgraded v1 -> v2");
+    Ok(2)
+}
+
+fn mig_2_to_3(conn: &mut Connection) -> Result<usize, Error> {
+    conn.execute_batch(include_str!("../migrations/003_relay_info.sql"))?;
+    tracing::info!("database schema upgraded v2 -> v3");
+    Ok(3)
+}
+
+fn mig_3_to_4(conn: &mut Connection) -> Result<usize, Error> {
+    conn.execute_batch(include_str!("../migrations/004_outbox_events.sql"))?;
+    tracing::info!("database schema upgraded v3 -> v4");
+    Ok(4)
+}
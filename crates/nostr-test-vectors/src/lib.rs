@@ -0,0 +1,235 @@
+// Copyright (c) 2022-2023 Yuki Kishimoto
+// Copyright (c) 2023-2024 Rust Nostr Developers
+// Distributed under the MIT software license
+
+//! Nostr test vectors
+//!
+//! Machine-readable reference vectors, to keep the serializations of the `nostr` crate
+//! byte-compatible with other implementations.
+
+#![forbid(unsafe_code)]
+#![warn(missing_docs)]
+
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+
+const NIP01: &str = include_str!("../vectors/nip01.json");
+const NIP04: &str = include_str!("../vectors/nip04.json");
+const NIP19: &str = include_str!("../vectors/nip19.json");
+const NIP26: &str = include_str!("../vectors/nip26.json");
+const NIP44: &str = include_str!("../../nostr/src/nips/nip44/nip44.vectors.json");
+const NIP57: &str = include_str!("../vectors/nip57.json");
+
+fn parse<T>(json: &str) -> T
+where
+    T: DeserializeOwned,
+{
+    serde_json::from_str(json).expect("Invalid test vectors")
+}
+
+/// NIP01 vectors
+#[derive(Debug, Clone, Deserialize)]
+pub struct Nip01Vectors {
+    /// Signed events
+    pub events: Vec<EventVector>,
+}
+
+/// Signed event
+#[derive(Debug, Clone, Deserialize)]
+pub struct EventVector {
+    /// Description
+    pub description: String,
+    /// Expected event ID
+    pub id: String,
+    /// Raw event JSON, as produced by the original implementation
+    pub json: String,
+}
+
+/// NIP04 vectors
+#[derive(Debug, Clone, Deserialize)]
+pub struct Nip04Vectors {
+    /// Payloads to decrypt
+    pub decrypt: Vec<Nip04DecryptVector>,
+}
+
+/// NIP04 payload
+#[derive(Debug, Clone, Deserialize)]
+pub struct Nip04DecryptVector {
+    /// Sender secret key
+    pub sender_secret_key: String,
+    /// Receiver secret key
+    pub receiver_secret_key: String,
+    /// Plaintext
+    pub plaintext: String,
+    /// Encrypted payload (`<ciphertext>?iv=<iv>`)
+    pub payload: String,
+}
+
+/// NIP19 vectors
+#[derive(Debug, Clone, Deserialize)]
+pub struct Nip19Vectors {
+    /// `npub`
+    pub public_keys: Vec<Bech32Vector>,
+    /// `nsec`
+    pub secret_keys: Vec<Bech32Vector>,
+    /// `note`
+    pub event_ids: Vec<Bech32Vector>,
+    /// `nprofile`
+    pub profiles: Vec<ProfileVector>,
+    /// `nevent`
+    pub events: Vec<Nip19EventVector>,
+}
+
+/// Bech32 encoding of a 32 bytes value
+#[derive(Debug, Clone, Deserialize)]
+pub struct Bech32Vector {
+    /// Hex
+    pub hex: String,
+    /// Bech32
+    pub bech32: String,
+}
+
+/// `nprofile` entity
+#[derive(Debug, Clone, Deserialize)]
+pub struct ProfileVector {
+    /// Public key
+    pub public_key: String,
+    /// Relays
+    pub relays: Vec<String>,
+    /// Bech32
+    pub bech32: String,
+}
+
+/// `nevent` entity
+#[derive(Debug, Clone, Deserialize)]
+pub struct Nip19EventVector {
+    /// Event ID
+    pub event_id: String,
+    /// Author
+    pub author: Option<String>,
+    /// Kind
+    pub kind: Option<u64>,
+    /// Relays
+    pub relays: Vec<String>,
+    /// Bech32
+    pub bech32: String,
+}
+
+/// NIP26 vectors
+#[derive(Debug, Clone, Deserialize)]
+pub struct Nip26Vectors {
+    /// Delegation tokens
+    pub tokens: Vec<DelegationTokenVector>,
+    /// Delegation signatures
+    pub signatures: Vec<DelegationSignatureVector>,
+    /// Delegation tags
+    pub tags: Vec<DelegationTagVector>,
+}
+
+/// Delegation token
+#[derive(Debug, Clone, Deserialize)]
+pub struct DelegationTokenVector {
+    /// Delegatee public key
+    pub delegatee: String,
+    /// Conditions
+    pub conditions: String,
+    /// Unhashed token
+    pub token: String,
+}
+
+/// Delegation signature
+#[derive(Debug, Clone, Deserialize)]
+pub struct DelegationSignatureVector {
+    /// Delegator secret key
+    pub delegator_secret_key: String,
+    /// Delegatee public key
+    pub delegatee: String,
+    /// Conditions
+    pub conditions: String,
+    /// Signature
+    pub signature: String,
+}
+
+/// Delegation tag
+#[derive(Debug, Clone, Deserialize)]
+pub struct DelegationTagVector {
+    /// Delegator public key
+    pub delegator: String,
+    /// Conditions
+    pub conditions: String,
+    /// Signature
+    pub signature: String,
+    /// Tag JSON
+    pub json: String,
+}
+
+/// NIP57 vectors
+#[derive(Debug, Clone, Deserialize)]
+pub struct Nip57Vectors {
+    /// Zap receipts
+    pub receipts: Vec<ZapReceiptVector>,
+    /// Private zap requests
+    pub private_zaps: Vec<PrivateZapVector>,
+}
+
+/// Zap receipt
+#[derive(Debug, Clone, Deserialize)]
+pub struct ZapReceiptVector {
+    /// Description
+    pub description: String,
+    /// Receipt event JSON
+    pub json: String,
+    /// ID of the zap request embedded in the `description` tag
+    pub zap_request_id: String,
+    /// Amount (millisats) of the zap request
+    pub amount: u64,
+    /// `bolt11` invoice
+    pub bolt11: String,
+}
+
+/// Private zap request
+#[derive(Debug, Clone, Deserialize)]
+pub struct PrivateZapVector {
+    /// Sender secret key
+    pub sender_secret_key: String,
+    /// Receiver public key
+    pub receiver_public_key: String,
+    /// Zap request event JSON
+    pub zap_request: String,
+    /// Decrypted message
+    pub message: String,
+    /// Decrypted message author
+    pub message_author: String,
+}
+
+/// Get NIP01 vectors
+pub fn nip01() -> Nip01Vectors {
+    parse(NIP01)
+}
+
+/// Get NIP04 vectors
+pub fn nip04() -> Nip04Vectors {
+    parse(NIP04)
+}
+
+/// Get NIP19 vectors
+pub fn nip19() -> Nip19Vectors {
+    parse(NIP19)
+}
+
+/// Get NIP26 vectors
+pub fn nip26() -> Nip26Vectors {
+    parse(NIP26)
+}
+
+/// Get the official NIP44 vectors, as raw JSON
+///
+/// <https://github.com/paulmillr/nip44>
+pub fn nip44() -> serde_json::Value {
+    parse(NIP44)
+}
+
+/// Get NIP57 vectors
+pub fn nip57() -> Nip57Vectors {
+    parse(NIP57)
+}
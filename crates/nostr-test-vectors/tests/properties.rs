@@ -0,0 +1,96 @@
+// Copyright (c) 2022-2023 Yuki Kishimoto
+// Copyright (c) 2023-2024 Rust Nostr Developers
+// Distributed under the MIT software license
+
+use nostr::nips::nip04;
+use nostr::nips::nip19::{Nip19Event, Nip19Profile};
+use nostr::nips::nip44::{self, Version};
+use nostr::nips::nip59::{self, RANGE_RANDOM_TIMESTAMP_TWEAK};
+use nostr::secp256k1::{SecretKey, XOnlyPublicKey};
+use nostr::{Event, EventBuilder, FromBech32, JsonUtil, Keys, Kind, Tag, Timestamp, ToBech32};
+use proptest::prelude::*;
+
+fn keys() -> impl Strategy<Value = Keys> {
+    any::<[u8; 32]>()
+        .prop_filter_map("invalid secret key", |bytes| {
+            SecretKey::from_slice(&bytes).ok()
+        })
+        .prop_map(Keys::new)
+}
+
+fn relays() -> impl Strategy<Value = Vec<String>> {
+    prop::collection::vec("wss://[a-z]{1,20}\\.[a-z]{2,5}", 0..4)
+}
+
+proptest! {
+    #[test]
+    fn keys_bech32_roundtrip(keys in keys()) {
+        let public_key: XOnlyPublicKey = keys.public_key();
+        let npub = public_key.to_bech32().unwrap();
+        prop_assert_eq!(XOnlyPublicKey::from_bech32(npub).unwrap(), public_key);
+
+        let secret_key: SecretKey = keys.secret_key().unwrap();
+        let nsec = secret_key.to_bech32().unwrap();
+        prop_assert_eq!(SecretKey::from_bech32(nsec).unwrap(), secret_key);
+    }
+
+    #[test]
+    fn nip19_entities_roundtrip(keys in keys(), relays in relays(), kind in any::<u16>()) {
+        let profile = Nip19Profile::new(keys.public_key(), relays.clone());
+        let nprofile = profile.to_bech32().unwrap();
+        prop_assert_eq!(Nip19Profile::from_bech32(nprofile).unwrap(), profile);
+
+        let event = EventBuilder::new(Kind::from(kind as u64), "", []).to_event(&keys).unwrap();
+        let nevent = event.to_nevent(relays);
+        let decoded = Nip19Event::from_bech32(nevent.to_bech32().unwrap()).unwrap();
+        prop_assert_eq!(decoded, nevent);
+    }
+
+    #[test]
+    fn event_json_roundtrip(keys in keys(), content in ".*", hashtags in prop::collection::vec("[^\u{0}]{0,16}", 0..4)) {
+        let tags = hashtags.into_iter().map(Tag::Hashtag);
+        let event = EventBuilder::new(Kind::TextNote, content, tags).to_event(&keys).unwrap();
+
+        let json = event.as_json();
+        let decoded = Event::from_json(&json).unwrap();
+        prop_assert!(decoded.verify().is_ok());
+        prop_assert_eq!(decoded.as_json(), json);
+        prop_assert_eq!(decoded, event);
+    }
+
+    #[test]
+    fn nip04_roundtrip(sender in keys(), receiver in keys(), content in ".*") {
+        let payload = nip04::encrypt(&sender.secret_key().unwrap(), &receiver.public_key(), &content).unwrap();
+        let plaintext = nip04::decrypt(&receiver.secret_key().unwrap(), &sender.public_key(), payload).unwrap();
+        prop_assert_eq!(plaintext, content);
+    }
+
+    #[test]
+    fn nip44_roundtrip(sender in keys(), receiver in keys(), content in ".{1,512}") {
+        let payload = nip44::encrypt(&sender.secret_key().unwrap(), &receiver.public_key(), &content, Version::V2).unwrap();
+        let plaintext = nip44::decrypt(&receiver.secret_key().unwrap(), &sender.public_key(), payload).unwrap();
+        prop_assert_eq!(plaintext, content);
+    }
+
+    #[test]
+    fn nip59_gift_wrap_roundtrip(sender in keys(), receiver in keys(), content in ".*") {
+        let receiver_pubkey = receiver.public_key();
+        let gift_wrap = EventBuilder::private_msg_rumor(receiver_pubkey, content.clone(), None)
+            .gift_wrap_to(&sender, &receiver_pubkey)
+            .unwrap();
+        prop_assert_eq!(gift_wrap.kind(), Kind::GiftWrap);
+        prop_assert!(gift_wrap.verify().is_ok());
+        prop_assert_ne!(gift_wrap.author(), sender.public_key());
+
+        let max_tweak = RANGE_RANDOM_TIMESTAMP_TWEAK.end;
+        let now = Timestamp::now();
+        prop_assert!(gift_wrap.created_at() <= now);
+        prop_assert!(gift_wrap.created_at() >= now - (max_tweak + 10));
+
+        let unwrapped = nip59::extract_rumor(&receiver, &gift_wrap).unwrap();
+        prop_assert_eq!(unwrapped.sender, sender.public_key());
+        prop_assert_eq!(unwrapped.rumor.kind, Kind::PrivateDirectMessage);
+        prop_assert_eq!(unwrapped.rumor.content, content);
+        prop_assert_eq!(unwrapped.rumor.tags, vec![Tag::public_key(receiver_pubkey)]);
+    }
+}
@@ -0,0 +1,222 @@
+// Copyright (c) 2022-2023 Yuki Kishimoto
+// Copyright (c) 2023-2024 Rust Nostr Developers
+// Distributed under the MIT software license
+
+use std::str::FromStr;
+
+use nostr::nips::nip19::{Nip19Event, Nip19Profile};
+use nostr::nips::nip26::{self, Conditions, DelegationTag, DelegationToken};
+use nostr::nips::nip44::{self, ConversationKey};
+use nostr::nips::{nip04, nip57};
+use nostr::secp256k1::schnorr::Signature;
+use nostr::secp256k1::{SecretKey, XOnlyPublicKey};
+use nostr::{Event, EventId, FromBech32, JsonUtil, Keys, Kind, Tag, ToBech32};
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+#[test]
+fn nip01_events() {
+    for vector in nostr_test_vectors::nip01().events.into_iter() {
+        let event = Event::from_json(&vector.json).unwrap();
+
+        // Canonical serialization
+        let id = EventId::new(
+            &event.author(),
+            event.created_at(),
+            &event.kind(),
+            event.tags(),
+            event.content(),
+        );
+        assert_eq!(id.to_hex(), vector.id, "{}", vector.description);
+        assert!(event.verify().is_ok(), "{}", vector.description);
+
+        // Byte-exact JSON round-trip
+        assert_eq!(event.as_json(), vector.json, "{}", vector.description);
+    }
+}
+
+#[test]
+fn nip04_decrypt() {
+    for vector in nostr_test_vectors::nip04().decrypt.into_iter() {
+        let sender = Keys::new(SecretKey::from_str(&vector.sender_secret_key).unwrap());
+        let receiver = Keys::new(SecretKey::from_str(&vector.receiver_secret_key).unwrap());
+
+        let plaintext = nip04::decrypt(
+            &receiver.secret_key().unwrap(),
+            &sender.public_key(),
+            &vector.payload,
+        )
+        .unwrap();
+        assert_eq!(plaintext, vector.plaintext);
+
+        // Shared secret is symmetric
+        let plaintext = nip04::decrypt(
+            &sender.secret_key().unwrap(),
+            &receiver.public_key(),
+            &vector.payload,
+        )
+        .unwrap();
+        assert_eq!(plaintext, vector.plaintext);
+    }
+}
+
+#[test]
+fn nip19_encodings() {
+    let vectors = nostr_test_vectors::nip19();
+
+    for vector in vectors.public_keys.into_iter() {
+        let public_key = XOnlyPublicKey::from_str(&vector.hex).unwrap();
+        assert_eq!(public_key.to_bech32().unwrap(), vector.bech32);
+        assert_eq!(
+            XOnlyPublicKey::from_bech32(&vector.bech32).unwrap(),
+            public_key
+        );
+    }
+
+    for vector in vectors.secret_keys.into_iter() {
+        let secret_key = SecretKey::from_str(&vector.hex).unwrap();
+        assert_eq!(secret_key.to_bech32().unwrap(), vector.bech32);
+        assert_eq!(SecretKey::from_bech32(&vector.bech32).unwrap(), secret_key);
+    }
+
+    for vector in vectors.event_ids.into_iter() {
+        let event_id = EventId::from_hex(&vector.hex).unwrap();
+        assert_eq!(event_id.to_bech32().unwrap(), vector.bech32);
+        assert_eq!(EventId::from_bech32(&vector.bech32).unwrap(), event_id);
+    }
+
+    for vector in vectors.profiles.into_iter() {
+        let public_key = XOnlyPublicKey::from_str(&vector.public_key).unwrap();
+        let profile = Nip19Profile::new(public_key, vector.relays);
+        assert_eq!(profile.to_bech32().unwrap(), vector.bech32);
+        assert_eq!(Nip19Profile::from_bech32(&vector.bech32).unwrap(), profile);
+    }
+
+    for vector in vectors.events.into_iter() {
+        let event_id = EventId::from_hex(&vector.event_id).unwrap();
+        let mut nevent = Nip19Event::new(event_id, vector.relays);
+        if let Some(author) = vector.author {
+            nevent = nevent.author(XOnlyPublicKey::from_str(&author).unwrap());
+        }
+        if let Some(kind) = vector.kind {
+            nevent = nevent.kind(Kind::from(kind));
+        }
+        assert_eq!(nevent.to_bech32().unwrap(), vector.bech32);
+        assert_eq!(Nip19Event::from_bech32(&vector.bech32).unwrap(), nevent);
+    }
+}
+
+#[test]
+fn nip26_delegations() {
+    let vectors = nostr_test_vectors::nip26();
+
+    for vector in vectors.tokens.into_iter() {
+        let delegatee = XOnlyPublicKey::from_str(&vector.delegatee).unwrap();
+        let conditions = Conditions::from_str(&vector.conditions).unwrap();
+        let token = DelegationToken::new(delegatee, conditions);
+        assert_eq!(token.to_string(), vector.token);
+    }
+
+    for vector in vectors.signatures.into_iter() {
+        let delegator = Keys::new(SecretKey::from_str(&vector.delegator_secret_key).unwrap());
+        let delegatee = XOnlyPublicKey::from_str(&vector.delegatee).unwrap();
+        let conditions = Conditions::from_str(&vector.conditions).unwrap();
+        let signature = Signature::from_str(&vector.signature).unwrap();
+        assert!(nip26::verify_delegation_signature(
+            delegator.public_key(),
+            signature,
+            delegatee,
+            conditions
+        )
+        .is_ok());
+    }
+
+    for vector in vectors.tags.into_iter() {
+        let tag = DelegationTag::from_json(&vector.json).unwrap();
+        assert_eq!(tag.delegator_pubkey().to_string(), vector.delegator);
+        assert_eq!(tag.conditions().to_string(), vector.conditions);
+        assert_eq!(tag.signature().to_string(), vector.signature);
+        assert_eq!(tag.as_json(), vector.json);
+    }
+}
+
+#[test]
+fn nip44_v2() {
+    let vectors = nostr_test_vectors::nip44();
+    let valid = &vectors["v2"]["valid"];
+
+    for vector in valid["get_conversation_key"].as_array().unwrap() {
+        let secret_key = SecretKey::from_str(vector["sec1"].as_str().unwrap()).unwrap();
+        let public_key = XOnlyPublicKey::from_str(vector["pub2"].as_str().unwrap()).unwrap();
+        let conversation_key = ConversationKey::derive(&secret_key, &public_key);
+        assert_eq!(
+            to_hex(conversation_key.as_bytes()),
+            vector["conversation_key"].as_str().unwrap()
+        );
+    }
+
+    for vector in valid["encrypt_decrypt"].as_array().unwrap() {
+        let sec1 = SecretKey::from_str(vector["sec1"].as_str().unwrap()).unwrap();
+        let sec2 = Keys::new(SecretKey::from_str(vector["sec2"].as_str().unwrap()).unwrap());
+        let conversation_key = ConversationKey::derive(&sec1, &sec2.public_key());
+        assert_eq!(
+            to_hex(conversation_key.as_bytes()),
+            vector["conversation_key"].as_str().unwrap()
+        );
+
+        let plaintext = nip44::decrypt_with_conversation_key(
+            &conversation_key,
+            vector["ciphertext"].as_str().unwrap(),
+        )
+        .unwrap();
+        assert_eq!(plaintext, vector["plaintext"].as_str().unwrap());
+    }
+}
+
+#[test]
+fn nip57_zaps() {
+    let vectors = nostr_test_vectors::nip57();
+
+    for vector in vectors.receipts.into_iter() {
+        let receipt = Event::from_json(&vector.json).unwrap();
+        assert_eq!(receipt.kind(), Kind::ZapReceipt, "{}", vector.description);
+        assert!(receipt.verify().is_ok(), "{}", vector.description);
+        assert_eq!(receipt.as_json(), vector.json, "{}", vector.description);
+
+        let mut bolt11: Option<&str> = None;
+        let mut zap_request: Option<Event> = None;
+        for tag in receipt.iter_tags() {
+            match tag {
+                Tag::Bolt11(invoice) => bolt11 = Some(invoice),
+                Tag::Description(description) => {
+                    zap_request = Some(Event::from_json(description).unwrap())
+                }
+                _ => (),
+            }
+        }
+        assert_eq!(bolt11, Some(vector.bolt11.as_str()));
+
+        let zap_request = zap_request.expect("Missing zap request");
+        assert_eq!(zap_request.kind(), Kind::ZapRequest);
+        assert_eq!(zap_request.id().to_hex(), vector.zap_request_id);
+        assert!(zap_request.verify().is_ok());
+        assert!(zap_request.iter_tags().any(|t| matches!(
+            t,
+            Tag::Amount { millisats, .. } if *millisats == vector.amount
+        )));
+    }
+
+    for vector in vectors.private_zaps.into_iter() {
+        let sender = SecretKey::from_str(&vector.sender_secret_key).unwrap();
+        let receiver = XOnlyPublicKey::from_str(&vector.receiver_public_key).unwrap();
+        let zap_request = Event::from_json(&vector.zap_request).unwrap();
+        assert!(zap_request.verify().is_ok());
+
+        let message = nip57::decrypt_private_zap_message(&sender, &receiver, &zap_request).unwrap();
+        assert_eq!(message.content(), vector.message);
+        assert_eq!(message.author().to_string(), vector.message_author);
+        assert!(message.verify().is_ok());
+    }
+}
@@ -4,10 +4,14 @@
 
 //! Event builder
 
+use alloc::collections::BTreeSet;
+use alloc::format;
 use alloc::string::{String, ToString};
 use alloc::vec::Vec;
 use core::fmt;
 use core::ops::Range;
+#[cfg(feature = "std")]
+use core::time::Duration;
 
 #[cfg(feature = "std")]
 use bitcoin::secp256k1::rand;
@@ -18,21 +22,33 @@ use url_fork::Url;
 
 use super::kind::{Kind, NIP90_JOB_REQUEST_RANGE, NIP90_JOB_RESULT_RANGE};
 use super::tag::ImageDimensions;
-use super::{Event, EventId, Marker, Tag, TagKind, UnsignedEvent};
+use super::{Event, EventId, EventTemplate, Marker, Tag, TagKind, UnsignedEvent, ValidationReport};
 use crate::key::{self, Keys};
 #[cfg(feature = "nip04")]
 use crate::nips::nip04;
+#[cfg(not(feature = "std"))]
+use crate::nips::nip13;
+#[cfg(feature = "std")]
+use crate::nips::nip13::{CancellationToken, Miner};
 use crate::nips::nip15::{ProductData, StallData};
+use crate::nips::nip19::Nip19Event;
+use crate::nips::nip21::{self, NostrURI};
+use crate::nips::nip25::ExternalContent;
+#[cfg(all(feature = "std", feature = "nip59"))]
+use crate::nips::nip44::{self, Version};
 #[cfg(all(feature = "std", feature = "nip46"))]
 use crate::nips::nip46::Message as NostrConnectMessage;
 use crate::nips::nip53::LiveEvent;
 #[cfg(feature = "nip57")]
 use crate::nips::nip57::ZapRequestData;
 use crate::nips::nip58::Error as Nip58Error;
+#[cfg(all(feature = "std", feature = "nip59"))]
+use crate::nips::nip59;
+use crate::nips::nip71::VideoEvent;
 use crate::nips::nip90::DataVendingMachineStatus;
 use crate::nips::nip94::FileMetadata;
 use crate::nips::nip98::HttpData;
-use crate::nips::{nip13, nip58};
+use crate::nips::{nip10, nip27, nip58};
 #[cfg(feature = "std")]
 use crate::types::time::Instant;
 use crate::types::time::TimeSupplier;
@@ -42,6 +58,10 @@ use crate::util::EventIdOrCoordinate;
 use crate::SECP256K1;
 use crate::{JsonUtil, RelayMetadata, UncheckedUrl};
 
+/// Interval at which `created_at` is refreshed while mining the POW nonce
+#[cfg(feature = "std")]
+const CREATED_AT_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
 /// Wrong kind error
 #[derive(Debug)]
 pub enum WrongKindError {
@@ -77,8 +97,13 @@ pub enum Error {
     /// NIP04 error
     #[cfg(feature = "nip04")]
     NIP04(nip04::Error),
+    /// NIP44 error
+    #[cfg(all(feature = "std", feature = "nip59"))]
+    NIP44(nip44::Error),
     /// NIP58 error
     NIP58(nip58::Error),
+    /// NIP21 error
+    NIP21(nip21::Error),
     /// Wrong kind
     WrongKind {
         /// The received wrong kind
@@ -86,6 +111,8 @@ pub enum Error {
         /// The expected kind (single or range)
         expected: WrongKindError,
     },
+    /// POW mining cancelled
+    POWCancelled,
 }
 
 #[cfg(feature = "std")]
@@ -102,10 +129,14 @@ impl fmt::Display for Error {
             Self::OpenTimestamps(e) => write!(f, "NIP03: {e}"),
             #[cfg(feature = "nip04")]
             Self::NIP04(e) => write!(f, "NIP04: {e}"),
+            #[cfg(all(feature = "std", feature = "nip59"))]
+            Self::NIP44(e) => write!(f, "NIP44: {e}"),
             Self::NIP58(e) => write!(f, "NIP58: {e}"),
+            Self::NIP21(e) => write!(f, "NIP21: {e}"),
             Self::WrongKind { received, expected } => {
                 write!(f, "Wrong kind: received={received}, expected={expected}")
             }
+            Self::POWCancelled => write!(f, "POW mining cancelled"),
         }
     }
 }
@@ -148,12 +179,25 @@ impl From<nip04::Error> for Error {
     }
 }
 
+#[cfg(all(feature = "std", feature = "nip59"))]
+impl From<nip44::Error> for Error {
+    fn from(e: nip44::Error) -> Self {
+        Self::NIP44(e)
+    }
+}
+
 impl From<nip58::Error> for Error {
     fn from(e: nip58::Error) -> Self {
         Self::NIP58(e)
     }
 }
 
+impl From<nip21::Error> for Error {
+    fn from(e: nip21::Error) -> Self {
+        Self::NIP21(e)
+    }
+}
+
 /// [`Event`] builder
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct EventBuilder {
@@ -161,6 +205,8 @@ pub struct EventBuilder {
     tags: Vec<Tag>,
     content: String,
     custom_created_at: Option<Timestamp>,
+    #[cfg(feature = "std")]
+    created_at_tweak: Option<Range<u64>>,
 }
 
 impl EventBuilder {
@@ -175,15 +221,172 @@ impl EventBuilder {
             tags: tags.into_iter().collect(),
             content: content.into(),
             custom_created_at: None,
+            #[cfg(feature = "std")]
+            created_at_tweak: None,
         }
     }
 
+    /// Get [`Kind`]
+    pub fn kind(&self) -> Kind {
+        self.kind
+    }
+
+    /// Get content
+    pub fn content(&self) -> &str {
+        &self.content
+    }
+
+    /// Get tags
+    pub fn tags(&self) -> &[Tag] {
+        &self.tags
+    }
+
     /// Set a custom `created_at` UNIX timestamp
     pub fn custom_created_at(mut self, created_at: Timestamp) -> Self {
         self.custom_created_at = Some(created_at);
         self
     }
 
+    /// Randomize `created_at`, removing a random number of seconds in `range`
+    ///
+    /// Applied both to now and to the custom `created_at`, when the event is built.
+    /// Reduce the timing-analysis leakage (i.e. NIP59 recommends up to 2 days in the past for seals and gift wraps).
+    #[cfg(feature = "std")]
+    pub fn tweak_created_at(mut self, range: Range<u64>) -> Self {
+        self.created_at_tweak = Some(range);
+        self
+    }
+
+    fn compute_created_at<T>(&self, supplier: &T) -> Timestamp
+    where
+        T: TimeSupplier,
+    {
+        #[allow(unused_mut)]
+        let mut created_at: Timestamp = self
+            .custom_created_at
+            .unwrap_or_else(|| Timestamp::now_with_supplier(supplier));
+        #[cfg(feature = "std")]
+        if let Some(range) = &self.created_at_tweak {
+            created_at.tweak_in_range(range.clone());
+        }
+        created_at
+    }
+
+    /// Add tags
+    pub fn add_tags<I>(mut self, tags: I) -> Self
+    where
+        I: IntoIterator<Item = Tag>,
+    {
+        self.tags.extend(tags);
+        self
+    }
+
+    /// Append the tags of the hashtags, URLs and `nostr:` mentions found in the content
+    ///
+    /// `t` for hashtags, `r` for URLs, `p` for `npub`/`nprofile`, `q` for `note`/`nevent` and `a` for `naddr`
+    /// (see [`nip27::extract_tags`]). Tags already present are not duplicated.
+    ///
+    /// <https://github.com/nostr-protocol/nips/blob/master/27.md>
+    pub fn autotag(mut self) -> Self {
+        for tag in nip27::extract_tags(&self.content).into_iter() {
+            if !self.tags.contains(&tag) {
+                self.tags.push(tag);
+            }
+        }
+        self
+    }
+
+    /// Remove duplicated tags, keeping the first occurrence
+    pub fn dedup_tags(mut self) -> Self {
+        let mut seen: BTreeSet<Tag> = BTreeSet::new();
+        self.tags.retain(|t| seen.insert(t.clone()));
+        self
+    }
+
+    /// Remove all the tags of [`TagKind`]
+    pub fn remove_tag_kind(mut self, kind: TagKind) -> Self {
+        self.tags.retain(|t| t.kind() != kind);
+        self
+    }
+
+    /// Validate tag values (ids, public keys, coordinates and relay URLs)
+    pub fn validate(&self) -> ValidationReport {
+        ValidationReport::from(self.tags.as_slice())
+    }
+
+    /// Mark the event as protected (`-` tag)
+    ///
+    /// Relays should accept the event only if published by its author, after `AUTH`.
+    ///
+    /// <https://github.com/nostr-protocol/nips/blob/master/70.md>
+    pub fn protected(mut self) -> Self {
+        if !self.tags.contains(&Tag::Protected) {
+            self.tags.push(Tag::Protected);
+        }
+        self
+    }
+
+    /// Mark the content as sensitive (`content-warning` tag)
+    ///
+    /// Replace the previous content warning, if any.
+    ///
+    /// <https://github.com/nostr-protocol/nips/blob/master/36.md>
+    pub fn content_warning<S>(mut self, reason: Option<S>) -> Self
+    where
+        S: Into<String>,
+    {
+        self.tags.retain(|t| t.kind() != TagKind::ContentWarning);
+        self.tags.push(Tag::ContentWarning {
+            reason: reason.map(Into::into),
+        });
+        self
+    }
+
+    /// Set the alternative description of the event (`alt` tag)
+    ///
+    /// Replace the previous `alt` tag, if any. Useful for custom kinds,
+    /// so clients that don't support them can show a short human-readable description.
+    ///
+    /// <https://github.com/nostr-protocol/nips/blob/master/31.md>
+    pub fn alt<S>(mut self, description: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.tags.retain(|t| t.kind() != TagKind::Alt);
+        self.tags.push(Tag::Alt(description.into()));
+        self
+    }
+
+    /// Attach a default `alt` tag (NIP31), if missing
+    ///
+    /// Text notes (kind 1) are skipped.
+    pub fn default_alt(self) -> Self {
+        if self.kind == Kind::TextNote || self.tags.iter().any(|t| t.kind() == TagKind::Alt) {
+            return self;
+        }
+
+        let description: String = match self.kind {
+            Kind::Metadata => String::from("User profile metadata"),
+            Kind::ContactList => String::from("Follow list"),
+            Kind::EncryptedDirectMessage => String::from("Encrypted direct message"),
+            Kind::EventDeletion => String::from("Event deletion request"),
+            Kind::Repost => String::from("Repost"),
+            Kind::GenericRepost => String::from("Generic repost"),
+            Kind::Reaction => String::from("Reaction"),
+            Kind::ExternalReaction => String::from("Reaction to external content"),
+            Kind::ChannelMessage => String::from("Public chat message"),
+            Kind::ZapRequest => String::from("Zap request"),
+            Kind::ZapReceipt => String::from("Zap receipt"),
+            Kind::RelayList => String::from("Relay list"),
+            Kind::LongFormTextNote => String::from("Long-form article"),
+            Kind::FileMetadata => String::from("File metadata"),
+            Kind::LiveEvent => String::from("Live event"),
+            kind => format!("Event of kind {kind}"),
+        };
+
+        self.alt(description)
+    }
+
     /// Build [`Event`]
     pub fn to_event_with_ctx<C, R, T>(
         self,
@@ -212,9 +415,7 @@ impl EventBuilder {
     where
         T: TimeSupplier,
     {
-        let created_at: Timestamp = self
-            .custom_created_at
-            .unwrap_or_else(|| Timestamp::now_with_supplier(supplier));
+        let created_at: Timestamp = self.compute_created_at(supplier);
         let id = EventId::new(&pubkey, created_at, &self.kind, &self.tags, &self.content);
         UnsignedEvent {
             id,
@@ -247,6 +448,8 @@ impl EventBuilder {
     }
 
     /// Build unsigned POW [`Event`]
+    ///
+    /// With the `std` feature, the nonce is mined in parallel, on all the available threads.
     pub fn to_unsigned_pow_event_with_supplier<T>(
         self,
         supplier: &T,
@@ -256,31 +459,41 @@ impl EventBuilder {
     where
         T: TimeSupplier,
     {
-        let mut nonce: u128 = 0;
-        let mut tags: Vec<Tag> = self.tags;
-
         #[cfg(feature = "std")]
-        let now = Instant::now();
+        {
+            // The miner is not shared, so can't be cancelled
+            self.mine_pow(supplier, pubkey, &Miner::new(difficulty))
+                .expect("POW mining without cancellation")
+        }
+
+        #[cfg(not(feature = "std"))]
+        {
+            self.mine_pow_single_thread(supplier, pubkey, difficulty)
+        }
+    }
+
+    #[cfg(not(feature = "std"))]
+    fn mine_pow_single_thread<T>(
+        mut self,
+        supplier: &T,
+        pubkey: XOnlyPublicKey,
+        difficulty: u8,
+    ) -> UnsignedEvent
+    where
+        T: TimeSupplier,
+    {
+        let mut nonce: u128 = 0;
+        let mut tags: Vec<Tag> = core::mem::take(&mut self.tags);
 
         loop {
             nonce += 1;
 
             tags.push(Tag::POW { nonce, difficulty });
 
-            let created_at: Timestamp = self
-                .custom_created_at
-                .unwrap_or_else(|| Timestamp::now_with_supplier(supplier));
+            let created_at: Timestamp = self.compute_created_at(supplier);
             let id = EventId::new(&pubkey, created_at, &self.kind, &tags, &self.content);
 
             if nip13::get_leading_zero_bits(id.inner()) >= difficulty {
-                #[cfg(feature = "std")]
-                tracing::debug!(
-                    "{} iterations in {} ms. Avg rate {} hashes/second",
-                    nonce,
-                    now.elapsed().as_millis(),
-                    nonce * 1000 / std::cmp::max(1, now.elapsed().as_millis())
-                );
-
                 return UnsignedEvent {
                     id,
                     pubkey,
@@ -309,7 +522,29 @@ impl EventBuilder {
         self.to_unsigned_event_with_supplier(&Instant::now(), pubkey)
     }
 
+    /// Build [`EventTemplate`]
+    pub fn to_event_template_with_supplier<T>(self, supplier: &T) -> EventTemplate
+    where
+        T: TimeSupplier,
+    {
+        let created_at: Timestamp = self.compute_created_at(supplier);
+        EventTemplate {
+            created_at,
+            kind: self.kind,
+            tags: self.tags,
+            content: self.content,
+        }
+    }
+
+    /// Build [`EventTemplate`]
+    #[cfg(feature = "std")]
+    pub fn to_event_template(self) -> EventTemplate {
+        self.to_event_template_with_supplier(&Instant::now())
+    }
+
     /// Build POW [`Event`]
+    ///
+    /// The nonce is mined in parallel, on all the available threads.
     #[cfg(feature = "std")]
     pub fn to_pow_event(self, keys: &Keys, difficulty: u8) -> Result<Event, Error> {
         self.to_pow_event_with_ctx(
@@ -321,11 +556,114 @@ impl EventBuilder {
         )
     }
 
+    /// Build POW [`Event`], stopping if `cancel` is cancelled
+    ///
+    /// The nonce is mined in parallel, on all the available threads.
+    /// Return [`Error::POWCancelled`] if cancelled before the nonce is found.
+    #[cfg(feature = "std")]
+    pub fn to_pow_event_with_cancellation(
+        self,
+        keys: &Keys,
+        difficulty: u8,
+        cancel: &CancellationToken,
+    ) -> Result<Event, Error> {
+        let unsigned: UnsignedEvent =
+            self.to_unsigned_pow_event_with_cancellation(keys.public_key(), difficulty, cancel)?;
+        Ok(unsigned.sign(keys)?)
+    }
+
+    /// Build POW [`Event`] with a custom [`Miner`] (i.e. to get progress reports)
+    ///
+    /// Return [`Error::POWCancelled`] if the miner is cancelled before the nonce is found.
+    #[cfg(feature = "std")]
+    pub fn to_pow_event_with_miner(self, keys: &Keys, miner: &Miner) -> Result<Event, Error> {
+        let unsigned: UnsignedEvent =
+            self.to_unsigned_pow_event_with_miner(keys.public_key(), miner)?;
+        Ok(unsigned.sign(keys)?)
+    }
+
     /// Build unsigned POW [`Event`]
+    ///
+    /// The nonce is mined in parallel, on all the available threads.
     #[cfg(feature = "std")]
     pub fn to_unsigned_pow_event(self, pubkey: XOnlyPublicKey, difficulty: u8) -> UnsignedEvent {
         self.to_unsigned_pow_event_with_supplier(&Instant::now(), pubkey, difficulty)
     }
+
+    /// Build unsigned POW [`Event`], stopping if `cancel` is cancelled
+    ///
+    /// The nonce is mined in parallel, on all the available threads.
+    /// Return [`Error::POWCancelled`] if cancelled before the nonce is found.
+    #[cfg(feature = "std")]
+    pub fn to_unsigned_pow_event_with_cancellation(
+        self,
+        pubkey: XOnlyPublicKey,
+        difficulty: u8,
+        cancel: &CancellationToken,
+    ) -> Result<UnsignedEvent, Error> {
+        let miner: Miner = Miner::new(difficulty).cancellation(cancel.clone());
+        self.to_unsigned_pow_event_with_miner(pubkey, &miner)
+    }
+
+    /// Build unsigned POW [`Event`] with a custom [`Miner`] (i.e. to get progress reports)
+    ///
+    /// Return [`Error::POWCancelled`] if the miner is cancelled before the nonce is found.
+    #[cfg(feature = "std")]
+    pub fn to_unsigned_pow_event_with_miner(
+        self,
+        pubkey: XOnlyPublicKey,
+        miner: &Miner,
+    ) -> Result<UnsignedEvent, Error> {
+        self.mine_pow(&Instant::now(), pubkey, miner)
+            .ok_or(Error::POWCancelled)
+    }
+
+    /// Mine the POW nonce, refreshing `created_at` every `CREATED_AT_REFRESH_INTERVAL`
+    /// (unless a custom `created_at` is set)
+    #[cfg(feature = "std")]
+    fn mine_pow<T>(
+        &self,
+        supplier: &T,
+        pubkey: XOnlyPublicKey,
+        miner: &Miner,
+    ) -> Option<UnsignedEvent>
+    where
+        T: TimeSupplier,
+    {
+        let difficulty: u8 = miner.difficulty();
+        let compose_tags = |nonce: u128| -> Vec<Tag> {
+            let mut tags: Vec<Tag> = self.tags.clone();
+            tags.push(Tag::POW { nonce, difficulty });
+            tags
+        };
+
+        let refresh_interval: Option<Duration> = match self.custom_created_at {
+            Some(..) => None,
+            None => Some(CREATED_AT_REFRESH_INTERVAL),
+        };
+
+        let now = Instant::now();
+        let (created_at, nonce) = miner.mine_rounds(
+            refresh_interval,
+            || self.compute_created_at(supplier),
+            |created_at, nonce| {
+                let tags: Vec<Tag> = compose_tags(nonce);
+                EventId::new(&pubkey, *created_at, &self.kind, &tags, &self.content)
+            },
+        )?;
+
+        tracing::debug!("Nonce {nonce} found in {} ms", now.elapsed().as_millis());
+
+        let tags: Vec<Tag> = compose_tags(nonce);
+        Some(UnsignedEvent {
+            id: EventId::new(&pubkey, created_at, &self.kind, &tags, &self.content),
+            pubkey,
+            created_at,
+            kind: self.kind,
+            tags,
+            content: self.content.clone(),
+        })
+    }
 }
 
 impl EventBuilder {
@@ -397,6 +735,18 @@ impl EventBuilder {
         Self::new(Kind::TextNote, content, tags)
     }
 
+    /// Text note, tagging the hashtags, URLs and `nostr:` mentions of the content
+    ///
+    /// See [`EventBuilder::autotag`].
+    ///
+    /// <https://github.com/nostr-protocol/nips/blob/master/01.md>
+    pub fn text_note_autotag<S>(content: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self::text_note(content, []).autotag()
+    }
+
     /// Text note
     ///
     /// <https://github.com/nostr-protocol/nips/blob/master/01.md>
@@ -409,6 +759,67 @@ impl EventBuilder {
         Self::text_note(content, tags)
     }
 
+    /// Text note reply
+    ///
+    /// Set the `root` and `reply` marked `e` tags and the `p` tags of the thread participants.
+    /// If `root` is `None`, the root is taken from the `reply_to` tags
+    /// (or `reply_to` is considered the root if it hasn't a `root` marked tag).
+    ///
+    /// `public_key` is the author of the reply, excluded from the `p` tags
+    /// (see [`nip10::reply_public_key_tags`]).
+    ///
+    /// <https://github.com/nostr-protocol/nips/blob/master/10.md>
+    pub fn text_note_reply<S>(
+        content: S,
+        reply_to: &Event,
+        root: Option<&Event>,
+        public_key: &XOnlyPublicKey,
+    ) -> Self
+    where
+        S: Into<String>,
+    {
+        let mut tags: Vec<Tag> = Vec::new();
+
+        // Root
+        let root: Option<(EventId, Option<UncheckedUrl>)> = match root {
+            Some(root) => Some((root.id(), None)),
+            None => reply_to.iter_tags().find_map(|t| match t {
+                Tag::Event {
+                    event_id,
+                    relay_url,
+                    marker: Some(Marker::Root),
+                } => Some((*event_id, relay_url.clone())),
+                _ => None,
+            }),
+        };
+
+        match root {
+            Some((root_id, relay_url)) if root_id != reply_to.id() => {
+                tags.push(Tag::Event {
+                    event_id: root_id,
+                    relay_url,
+                    marker: Some(Marker::Root),
+                });
+                tags.push(Tag::Event {
+                    event_id: reply_to.id(),
+                    relay_url: None,
+                    marker: Some(Marker::Reply),
+                });
+            }
+            // Top level reply: only the `root` marker
+            _ => tags.push(Tag::Event {
+                event_id: reply_to.id(),
+                relay_url: None,
+                marker: Some(Marker::Root),
+            }),
+        }
+
+        // Participants
+        tags.extend(nip10::reply_public_key_tags(reply_to, Some(public_key)));
+
+        Self::text_note(content, tags)
+    }
+
     /// Long-form text note (generally referred to as "articles" or "blog posts").
     ///
     /// <https://github.com/nostr-protocol/nips/blob/master/23.md>
@@ -520,6 +931,124 @@ impl EventBuilder {
         Self::encrypted_direct_msg(sender_keys, receiver_pubkey, content, reply_to)
     }
 
+    /// Private Direct Message rumor
+    ///
+    /// The returned builder is the unsigned rumor (kind `14`):
+    /// use [`EventBuilder::gift_wrap_to`] to seal and gift wrap it.
+    ///
+    /// <https://github.com/nostr-protocol/nips/blob/master/17.md>
+    pub fn private_msg_rumor<S>(
+        receiver: XOnlyPublicKey,
+        message: S,
+        reply_to: Option<EventId>,
+    ) -> Self
+    where
+        S: Into<String>,
+    {
+        let mut tags: Vec<Tag> = vec![Tag::public_key(receiver)];
+        if let Some(reply_to) = reply_to {
+            tags.push(Tag::event(reply_to));
+        }
+        Self::new(Kind::PrivateDirectMessage, message, tags)
+    }
+
+    /// Seal
+    ///
+    /// Encrypt the rumor to the receiver. The `created_at` is randomized (see [`nip59::random_timestamp`]).
+    ///
+    /// <https://github.com/nostr-protocol/nips/blob/master/59.md>
+    #[cfg(all(feature = "std", feature = "nip59"))]
+    pub fn seal(
+        sender_keys: &Keys,
+        receiver_pubkey: &XOnlyPublicKey,
+        rumor: UnsignedEvent,
+    ) -> Result<Self, Error> {
+        let content: String = nip44::encrypt(
+            &sender_keys.secret_key()?,
+            receiver_pubkey,
+            rumor.as_json(),
+            Version::V2,
+        )?;
+        Ok(
+            Self::new(Kind::Seal, content, [])
+                .tweak_created_at(nip59::RANGE_RANDOM_TIMESTAMP_TWEAK),
+        )
+    }
+
+    /// Gift Wrap from seal
+    ///
+    /// The gift wrap is signed with random ephemeral keys and its `created_at` is randomized.
+    ///
+    /// <https://github.com/nostr-protocol/nips/blob/master/59.md>
+    #[cfg(all(feature = "std", feature = "nip59"))]
+    pub fn gift_wrap_from_seal(
+        receiver: &XOnlyPublicKey,
+        seal: &Event,
+        expiration: Option<Timestamp>,
+    ) -> Result<Event, Error> {
+        if seal.kind() != Kind::Seal {
+            return Err(Error::WrongKind {
+                received: seal.kind(),
+                expected: WrongKindError::Single(Kind::Seal),
+            });
+        }
+
+        let keys: Keys = Keys::generate();
+        let content: String =
+            nip44::encrypt(&keys.secret_key()?, receiver, seal.as_json(), Version::V2)?;
+
+        let mut tags: Vec<Tag> = vec![Tag::public_key(*receiver)];
+        if let Some(timestamp) = expiration {
+            tags.push(Tag::Expiration(timestamp));
+        }
+
+        Self::new(Kind::GiftWrap, content, tags)
+            .tweak_created_at(nip59::RANGE_RANDOM_TIMESTAMP_TWEAK)
+            .to_event(&keys)
+    }
+
+    /// Gift Wrap
+    ///
+    /// Seal the rumor with the sender keys and wrap it for the receiver.
+    ///
+    /// <https://github.com/nostr-protocol/nips/blob/master/59.md>
+    #[cfg(all(feature = "std", feature = "nip59"))]
+    pub fn gift_wrap(
+        sender_keys: &Keys,
+        receiver: &XOnlyPublicKey,
+        rumor: UnsignedEvent,
+        expiration: Option<Timestamp>,
+    ) -> Result<Event, Error> {
+        let seal: Event = Self::seal(sender_keys, receiver, rumor)?.to_event(sender_keys)?;
+        Self::gift_wrap_from_seal(receiver, &seal, expiration)
+    }
+
+    /// Build the rumor and gift wrap it to the receiver (rumor → seal → gift wrap)
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use nostr::prelude::*;
+    ///
+    /// # fn main() -> Result<()> {
+    /// let keys = Keys::generate();
+    /// let receiver = Keys::generate().public_key();
+    /// let gift_wrap: Event =
+    ///     EventBuilder::private_msg_rumor(receiver, "Hello", None).gift_wrap_to(&keys, &receiver)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// <https://github.com/nostr-protocol/nips/blob/master/59.md>
+    #[cfg(all(feature = "std", feature = "nip59"))]
+    pub fn gift_wrap_to(
+        self,
+        sender_keys: &Keys,
+        receiver: &XOnlyPublicKey,
+    ) -> Result<Event, Error> {
+        let rumor: UnsignedEvent = self.to_unsigned_event(sender_keys.public_key());
+        Self::gift_wrap(sender_keys, receiver, rumor, None)
+    }
+
     /// Repost event
     pub fn repost(event_id: EventId, public_key: XOnlyPublicKey) -> Self {
         Self::new(
@@ -529,6 +1058,69 @@ impl EventBuilder {
         )
     }
 
+    /// Generic repost
+    ///
+    /// Repost of any event kind: the reposted event is embedded in the content.
+    ///
+    /// <https://github.com/nostr-protocol/nips/blob/master/18.md>
+    pub fn generic_repost(event: &Event, relay_url: Option<Url>) -> Self {
+        let relay_url: Option<UncheckedUrl> = relay_url.map(|u| u.into());
+        let mut tags: Vec<Tag> = vec![
+            Tag::Event {
+                event_id: event.id(),
+                relay_url: relay_url.clone(),
+                marker: None,
+            },
+            Tag::public_key(event.author()),
+            Tag::Kind(event.kind()),
+        ];
+
+        if let Some(identifier) = event.identifier() {
+            tags.push(Tag::A {
+                kind: event.kind(),
+                public_key: event.author(),
+                identifier: identifier.to_string(),
+                relay_url,
+            });
+        }
+
+        Self::new(Kind::GenericRepost, event.as_json(), tags)
+    }
+
+    /// Quote repost
+    ///
+    /// Text note with a `q` tag and a `nostr:nevent` mention of the quoted event appended to the content.
+    ///
+    /// <https://github.com/nostr-protocol/nips/blob/master/18.md>
+    pub fn quote_repost<S>(
+        content: S,
+        quoted: &Event,
+        relay_url: Option<Url>,
+    ) -> Result<Self, Error>
+    where
+        S: Into<String>,
+    {
+        let relay_url: Option<UncheckedUrl> = relay_url.map(|u| u.into());
+        let nevent = Nip19Event::new(quoted.id(), relay_url.iter().map(|u| u.to_string()));
+
+        let mut content: String = content.into();
+        if !content.is_empty() {
+            content.push_str("\n\n");
+        }
+        content.push_str(&nevent.to_nostr_uri()?);
+
+        Ok(Self::text_note(
+            content,
+            [
+                Tag::Quote {
+                    event_id: quoted.id(),
+                    relay_url,
+                },
+                Tag::public_key(quoted.author()),
+            ],
+        ))
+    }
+
     /// Create delete event
     pub fn delete<I, T>(ids: I) -> Self
     where
@@ -564,6 +1156,25 @@ impl EventBuilder {
         )
     }
 
+    /// Add reaction (like/upvote, dislike/downvote or emoji) to external content (i.e. a website)
+    ///
+    /// <https://github.com/nostr-protocol/nips/blob/master/25.md>
+    ///
+    /// # Example
+    /// ```rust,no_run
+    /// use nostr::nips::nip25::ExternalContent;
+    /// use nostr::{EventBuilder, Url};
+    ///
+    /// let url = Url::parse("https://example.com").unwrap();
+    /// let builder = EventBuilder::external_reaction(ExternalContent::Url(url), "+");
+    /// ```
+    pub fn external_reaction<S>(target: ExternalContent, content: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self::new(Kind::ExternalReaction, content, target.tags())
+    }
+
     /// Add reaction (like/upvote, dislike/downvote or emoji) to an event
     #[deprecated(since = "0.27.0", note = "Use `reaction` instead")]
     pub fn new_reaction<S>(event_id: EventId, public_key: XOnlyPublicKey, content: S) -> Self
@@ -1137,6 +1748,18 @@ impl EventBuilder {
         Self::new(Kind::FileMetadata, description.into(), tags)
     }
 
+    /// Video event
+    ///
+    /// Horizontal or vertical video, according to [`VideoEvent::orientation`].
+    ///
+    /// <https://github.com/nostr-protocol/nips/blob/master/71.md>
+    pub fn video_event(video: VideoEvent) -> Self {
+        let kind: Kind = video.orientation.kind();
+        let description: String = video.description.clone();
+        let tags: Vec<Tag> = video.into();
+        Self::new(kind, description, tags)
+    }
+
     /// HTTP Auth
     ///
     /// <https://github.com/nostr-protocol/nips/blob/master/98.md>
@@ -1187,6 +1810,8 @@ mod tests {
     use bitcoin::secp256k1::SecretKey;
 
     use super::*;
+    #[cfg(feature = "std")]
+    use crate::nips::nip13;
 
     #[test]
     #[cfg(feature = "std")]
@@ -1206,6 +1831,269 @@ mod tests {
         assert_eq!(event, deserialized);
     }
 
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_protected_event() {
+        let keys = Keys::generate();
+
+        let event = EventBuilder::text_note("hello", [])
+            .to_event(&keys)
+            .unwrap();
+        assert!(!event.is_protected());
+
+        let event = EventBuilder::text_note("hello", [])
+            .protected()
+            .protected()
+            .to_event(&keys)
+            .unwrap();
+        assert!(event.is_protected());
+        assert_eq!(event.tags().len(), 1);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_tag_utilities() {
+        let keys = Keys::generate();
+
+        let event = EventBuilder::text_note("hello", [Tag::Hashtag(String::from("nostr"))])
+            .add_tags([
+                Tag::Hashtag(String::from("rust")),
+                Tag::Hashtag(String::from("nostr")),
+                Tag::Alt(String::from("Text note")),
+            ])
+            .dedup_tags()
+            .remove_tag_kind(TagKind::Alt)
+            .to_event(&keys)
+            .unwrap();
+        assert_eq!(
+            event.tags(),
+            &[
+                Tag::Hashtag(String::from("nostr")),
+                Tag::Hashtag(String::from("rust"))
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_text_note_autotag() {
+        let keys = Keys::generate();
+        let content = "GM #Nostr, see https://example.com and nostr:npub14f8usejl26twx0dhuxjh9cas7keav9vr0v8nvtwtrjqx3vycc76qqh9nsy";
+
+        let event = EventBuilder::text_note_autotag(content)
+            .to_event(&keys)
+            .unwrap();
+        assert_eq!(event.content(), content);
+        assert_eq!(
+            event.tags(),
+            &[
+                Tag::Hashtag(String::from("nostr")),
+                Tag::Reference(String::from("https://example.com/")),
+                Tag::public_key(
+                    XOnlyPublicKey::from_str(
+                        "aa4fc8665f5696e33db7e1a572e3b0f5b3d615837b0f362dcb1c8068b098c7b4"
+                    )
+                    .unwrap()
+                ),
+            ]
+        );
+
+        // Tags already present are not duplicated
+        let event = EventBuilder::text_note(content, [Tag::Hashtag(String::from("nostr"))])
+            .autotag()
+            .to_event(&keys)
+            .unwrap();
+        assert_eq!(event.tags().len(), 3);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_tweak_created_at() {
+        let keys = Keys::generate();
+        let created_at = Timestamp::from(1682060685);
+
+        let event = EventBuilder::text_note("hello", [])
+            .custom_created_at(created_at)
+            .tweak_created_at(60..3600)
+            .to_event(&keys)
+            .unwrap();
+        assert!(event.created_at() <= created_at - 60_u64);
+        assert!(event.created_at() > created_at - 3600_u64);
+        event.verify().unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_alt() {
+        let keys = Keys::generate();
+
+        let event = EventBuilder::text_note("hello", [])
+            .default_alt()
+            .to_event(&keys)
+            .unwrap();
+        assert!(event.tags().is_empty());
+
+        let event = EventBuilder::new(Kind::Custom(30_999), "", [])
+            .default_alt()
+            .to_event(&keys)
+            .unwrap();
+        assert_eq!(
+            event.tags(),
+            &[Tag::Alt(String::from("Event of kind 30999"))]
+        );
+
+        let event = EventBuilder::new(Kind::Custom(30_999), "", [])
+            .alt("First")
+            .alt("Custom description")
+            .default_alt()
+            .to_event(&keys)
+            .unwrap();
+        assert_eq!(
+            event.tags(),
+            &[Tag::Alt(String::from("Custom description"))]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_text_note_reply() {
+        let alice = Keys::generate();
+        let bob = Keys::generate();
+        let carol = Keys::generate();
+
+        let root = EventBuilder::text_note("root", [])
+            .to_event(&alice)
+            .unwrap();
+
+        // Top level reply
+        let reply = EventBuilder::text_note_reply("reply", &root, None, &bob.public_key())
+            .to_event(&bob)
+            .unwrap();
+        assert_eq!(
+            reply.tags(),
+            &[
+                Tag::Event {
+                    event_id: root.id(),
+                    relay_url: None,
+                    marker: Some(Marker::Root),
+                },
+                Tag::public_key(alice.public_key()),
+            ]
+        );
+
+        // Nested reply: root taken from the replied event
+        let nested = EventBuilder::text_note_reply("nested", &reply, None, &carol.public_key())
+            .to_event(&carol)
+            .unwrap();
+        assert_eq!(
+            nested.tags(),
+            &[
+                Tag::Event {
+                    event_id: root.id(),
+                    relay_url: None,
+                    marker: Some(Marker::Root),
+                },
+                Tag::Event {
+                    event_id: reply.id(),
+                    relay_url: None,
+                    marker: Some(Marker::Reply),
+                },
+                Tag::public_key(bob.public_key()),
+                Tag::public_key(alice.public_key()),
+            ]
+        );
+
+        // Explicit root
+        let nested2 =
+            EventBuilder::text_note_reply("nested", &reply, Some(&root), &carol.public_key())
+                .to_event(&carol)
+                .unwrap();
+        assert_eq!(nested.tags(), nested2.tags());
+
+        // The reply author is not tagged
+        let nested3 = EventBuilder::text_note_reply("nested", &nested, None, &alice.public_key())
+            .to_event(&alice)
+            .unwrap();
+        assert_eq!(
+            nested3.public_keys().collect::<Vec<_>>(),
+            vec![&carol.public_key(), &bob.public_key()]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_reposts() {
+        let keys = Keys::generate();
+        let url = Url::parse("wss://relay.damus.io").unwrap();
+
+        let article = EventBuilder::long_form_text_note(
+            "Lorem ipsum",
+            [Tag::Identifier("lorem".to_string())],
+        )
+        .to_event(&keys)
+        .unwrap();
+
+        let repost = EventBuilder::generic_repost(&article, Some(url.clone()))
+            .to_event(&keys)
+            .unwrap();
+        assert_eq!(repost.kind(), Kind::GenericRepost);
+        assert_eq!(Event::from_json(repost.content()).unwrap(), article);
+        assert!(repost.tags().contains(&Tag::Kind(Kind::LongFormTextNote)));
+        assert_eq!(repost.coordinates().count(), 1);
+
+        let quote = EventBuilder::quote_repost("Interesting", &article, Some(url))
+            .unwrap()
+            .to_event(&keys)
+            .unwrap();
+        assert_eq!(quote.kind(), Kind::TextNote);
+        assert!(quote.content().starts_with("Interesting\n\nnostr:nevent1"));
+        assert_eq!(
+            quote.tags()[0],
+            Tag::Quote {
+                event_id: article.id(),
+                relay_url: Some(UncheckedUrl::from("wss://relay.damus.io/")),
+            }
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_external_reaction() {
+        let keys = Keys::generate();
+
+        let url = Url::parse("https://example.com/article").unwrap();
+        let event = EventBuilder::external_reaction(ExternalContent::Url(url), "+")
+            .to_event(&keys)
+            .unwrap();
+        assert_eq!(event.kind(), Kind::ExternalReaction);
+        assert_eq!(event.content(), "+");
+        assert_eq!(
+            event.tags(),
+            &[
+                Tag::Reference(String::from("https://example.com/article")),
+                Tag::Generic(TagKind::K, vec![String::from("web")]),
+            ]
+        );
+
+        let event = EventBuilder::external_reaction(
+            ExternalContent::Id(String::from("isbn:9780765382030")),
+            "-",
+        )
+        .to_event(&keys)
+        .unwrap();
+        assert_eq!(
+            event.tags(),
+            &[Tag::Generic(
+                TagKind::I,
+                vec![String::from("isbn:9780765382030")]
+            )]
+        );
+
+        // Round trip
+        let event = Event::from_json(event.as_json()).unwrap();
+        assert!(event.verify().is_ok());
+    }
+
     #[test]
     #[cfg(all(feature = "std", feature = "nip04"))]
     fn test_encrypted_direct_msg() {
@@ -1484,4 +2372,33 @@ mod tests {
         assert_eq!(profile_badges.kind(), Kind::ProfileBadges);
         assert_eq!(profile_badges.tags(), example_event.tags());
     }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_pow_event() {
+        let keys = Keys::generate();
+        let event = EventBuilder::text_note("POW", [])
+            .to_pow_event(&keys, 8)
+            .unwrap();
+        assert!(event.verify().is_ok());
+        assert!(nip13::get_leading_zero_bits(event.id().inner()) >= 8);
+        assert!(event
+            .tags()
+            .iter()
+            .any(|t| matches!(t, Tag::POW { difficulty: 8, .. })));
+
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+        assert!(matches!(
+            EventBuilder::text_note("POW", []).to_pow_event_with_cancellation(&keys, 255, &cancel),
+            Err(Error::POWCancelled)
+        ));
+
+        // Custom miner
+        let miner = Miner::new(8).threads(2).on_progress(|_| {});
+        let event = EventBuilder::text_note("POW", [])
+            .to_pow_event_with_miner(&keys, &miner)
+            .unwrap();
+        assert!(nip13::get_leading_zero_bits(event.id().inner()) >= 8);
+    }
 }
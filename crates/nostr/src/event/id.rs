@@ -7,10 +7,16 @@
 use alloc::string::{String, ToString};
 use core::fmt;
 use core::str::FromStr;
+#[cfg(feature = "std")]
+use std::io;
 
 use bitcoin::hashes::sha256::Hash as Sha256Hash;
 use bitcoin::hashes::Hash;
 use bitcoin::secp256k1::XOnlyPublicKey;
+#[cfg(feature = "std")]
+use serde::Serialize;
+#[cfg(feature = "std")]
+use serde_json::ser::{CharEscape, CompactFormatter, Formatter, Serializer};
 use serde_json::{json, Value};
 
 use super::{Kind, Tag};
@@ -59,6 +65,9 @@ pub struct EventId(Sha256Hash);
 
 impl EventId {
     /// Generate [`EventId`]
+    ///
+    /// With the `std` feature, the serialized event is streamed into the hash engine,
+    /// without building the whole JSON in memory (useful for events with big contents).
     pub fn new(
         pubkey: &XOnlyPublicKey,
         created_at: Timestamp,
@@ -66,11 +75,44 @@ impl EventId {
         tags: &[Tag],
         content: &str,
     ) -> Self {
+        #[cfg(feature = "std")]
+        {
+            let mut engine = Sha256Hash::engine();
+            // Writing into the hash engine can't fail
+            if serde_json::to_writer(&mut engine, &(0, pubkey, created_at, kind, tags, content))
+                .is_ok()
+            {
+                return Self(Sha256Hash::from_engine(engine));
+            }
+        }
+
         let json: Value = json!([0, pubkey, created_at, kind, tags, content]);
         let event_str: String = json.to_string();
         Self(Sha256Hash::hash(event_str.as_bytes()))
     }
 
+    /// Generate [`EventId`] with the strict NIP-01 canonical serialization
+    ///
+    /// Strings are escaped exactly as NIP-01 requires: only `\n`, `\"`, `\\`, `\r`, `\t`, `\b` and `\f`,
+    /// while all the other characters (also the other control characters) are included verbatim.
+    /// [`EventId::new`] escapes the other control characters as `\u00XX`, like most of the JSON serializers.
+    ///
+    /// <https://github.com/nostr-protocol/nips/blob/master/01.md>
+    #[cfg(feature = "std")]
+    pub fn new_canonical(
+        pubkey: &XOnlyPublicKey,
+        created_at: Timestamp,
+        kind: &Kind,
+        tags: &[Tag],
+        content: &str,
+    ) -> Self {
+        let mut engine = Sha256Hash::engine();
+        let mut serializer = Serializer::with_formatter(&mut engine, CanonicalFormatter);
+        // Writing into the hash engine can't fail
+        let _ = (0, pubkey, created_at, kind, tags, content).serialize(&mut serializer);
+        Self(Sha256Hash::from_engine(engine))
+    }
+
     /// [`EventId`] hex string
     pub fn from_hex<S>(hex: S) -> Result<Self, Error>
     where
@@ -115,6 +157,23 @@ impl EventId {
     }
 }
 
+/// JSON formatter that escapes the strings as NIP-01 requires
+#[cfg(feature = "std")]
+struct CanonicalFormatter;
+
+#[cfg(feature = "std")]
+impl Formatter for CanonicalFormatter {
+    fn write_char_escape<W>(&mut self, writer: &mut W, char_escape: CharEscape) -> io::Result<()>
+    where
+        W: ?Sized + io::Write,
+    {
+        match char_escape {
+            CharEscape::AsciiControl(byte) => writer.write_all(&[byte]),
+            char_escape => CompactFormatter.write_char_escape(writer, char_escape),
+        }
+    }
+}
+
 impl FromStr for EventId {
     type Err = Error;
 
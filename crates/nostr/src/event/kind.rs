@@ -4,13 +4,23 @@
 
 //! Kind
 
+#[cfg(feature = "std")]
+use alloc::collections::BTreeMap;
+#[cfg(feature = "std")]
+use alloc::format;
+#[cfg(feature = "std")]
+use alloc::string::String;
 use core::cmp::Ordering;
 use core::fmt;
 use core::hash::{Hash, Hasher};
 use core::num::ParseIntError;
 use core::ops::{Add, Range};
 use core::str::FromStr;
+#[cfg(feature = "std")]
+use std::sync::RwLock;
 
+#[cfg(feature = "std")]
+use once_cell::sync::Lazy;
 use serde::de::{Deserialize, Deserializer, Error, Visitor};
 use serde::ser::{Serialize, Serializer};
 
@@ -27,6 +37,26 @@ pub const EPHEMERAL_RANGE: Range<u64> = 20_000..30_000;
 /// Parameterized replaceable range
 pub const PARAMETERIZED_REPLACEABLE_RANGE: Range<u64> = 30_000..40_000;
 
+/// Names of the kinds registered with [`Kind::register`]
+#[cfg(feature = "std")]
+static REGISTERED_KINDS: Lazy<RwLock<BTreeMap<u64, String>>> =
+    Lazy::new(|| RwLock::new(BTreeMap::new()));
+
+/// [`Kind`] category, based on how relays store the events
+///
+/// <https://github.com/nostr-protocol/nips/blob/master/01.md>
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum KindCategory {
+    /// Regular: stored by relays
+    Regular,
+    /// Replaceable: only the latest event of the author is stored
+    Replaceable,
+    /// Ephemeral: not stored by relays
+    Ephemeral,
+    /// Addressable (parameterized replaceable): only the latest event of the author for every `d` tag is stored
+    Addressable,
+}
+
 /// Event [`Kind`]
 #[derive(Debug, Clone, Copy)]
 pub enum Kind {
@@ -44,10 +74,20 @@ pub enum Kind {
     EncryptedDirectMessage,
     /// Event Deletion (NIP09)
     EventDeletion,
+    /// Seal (NIP59)
+    Seal,
+    /// Private Direct Message (NIP17)
+    PrivateDirectMessage,
+    /// Gift Wrap (NIP59)
+    GiftWrap,
     /// Repost (NIP18)
     Repost,
+    /// Generic Repost (NIP18)
+    GenericRepost,
     /// Reaction (NIP25)
     Reaction,
+    /// Reaction to external content (NIP25)
+    ExternalReaction,
     /// Badge Award (NIP58)
     BadgeAward,
     /// Channel Creation (NIP28)
@@ -86,6 +126,8 @@ pub enum Kind {
     PinList,
     /// Relay List Metadata (NIP65)
     RelayList,
+    /// Bookmarks (NIP51)
+    Bookmarks,
     /// Client Authentication (NIP42)
     Authentication,
     /// Wallet Connect Request (NIP47)
@@ -98,6 +140,8 @@ pub enum Kind {
     CategorizedPeopleList,
     /// Categorized Bookmark List (NIP51)
     CategorizedBookmarkList,
+    /// Bookmark Set (NIP51)
+    BookmarkSet,
     /// Live Event (NIP53)
     LiveEvent,
     /// Live Event Message (NIP53)
@@ -112,6 +156,10 @@ pub enum Kind {
     ApplicationSpecificData,
     /// File Metadata (NIP94)
     FileMetadata,
+    /// Horizontal Video (NIP71)
+    HorizontalVideo,
+    /// Vertical Video (NIP71)
+    VerticalVideo,
     /// HTTP Auth (NIP98)
     HttpAuth,
     /// Set stall (NIP15)
@@ -217,6 +265,93 @@ impl Kind {
     pub fn is_parameterized_replaceable(&self) -> bool {
         PARAMETERIZED_REPLACEABLE_RANGE.contains(&self.as_u64())
     }
+
+    /// Check if [`Kind`] is `Addressable` (alias of [`Kind::is_parameterized_replaceable`])
+    pub fn is_addressable(&self) -> bool {
+        self.is_parameterized_replaceable()
+    }
+
+    /// Check if [`Kind`] is a direct message (NIP04 or NIP17)
+    pub fn is_dm(&self) -> bool {
+        *self == Kind::EncryptedDirectMessage || *self == Kind::PrivateDirectMessage
+    }
+
+    /// Get [`KindCategory`]
+    ///
+    /// Kinds outside the NIP01 ranges are [`KindCategory::Regular`].
+    pub fn category(&self) -> KindCategory {
+        if self.is_replaceable() {
+            KindCategory::Replaceable
+        } else if self.is_ephemeral() {
+            KindCategory::Ephemeral
+        } else if self.is_parameterized_replaceable() {
+            KindCategory::Addressable
+        } else {
+            KindCategory::Regular
+        }
+    }
+
+    /// Register a name for the [`Kind`]
+    ///
+    /// Useful for applications that use custom kinds: the name is returned by [`Kind::name`]
+    /// and the kind can be found with [`Kind::from_name`]. Replace the previous name, if any.
+    #[cfg(feature = "std")]
+    pub fn register<S>(&self, name: S)
+    where
+        S: Into<String>,
+    {
+        if let Ok(mut kinds) = REGISTERED_KINDS.write() {
+            kinds.insert(self.as_u64(), name.into());
+        }
+    }
+
+    /// Remove the name registered with [`Kind::register`]
+    #[cfg(feature = "std")]
+    pub fn unregister(&self) {
+        if let Ok(mut kinds) = REGISTERED_KINDS.write() {
+            kinds.remove(&self.as_u64());
+        }
+    }
+
+    /// Get [`Kind`] name
+    ///
+    /// Return the registered name (see [`Kind::register`]) or the name of the known kinds (i.e. `TextNote`).
+    #[cfg(feature = "std")]
+    pub fn name(&self) -> Option<String> {
+        if let Some(name) = REGISTERED_KINDS
+            .read()
+            .ok()
+            .and_then(|kinds| kinds.get(&self.as_u64()).cloned())
+        {
+            return Some(name);
+        }
+
+        // Re-parse to get the known variant also for `Kind::Custom`
+        match Self::from(self.as_u64()) {
+            Self::JobRequest(..)
+            | Self::JobResult(..)
+            | Self::Regular(..)
+            | Self::Replaceable(..)
+            | Self::Ephemeral(..)
+            | Self::ParameterizedReplaceable(..)
+            | Self::Custom(..) => None,
+            kind => Some(format!("{kind:?}")),
+        }
+    }
+
+    /// Find the [`Kind`] registered with name (see [`Kind::register`])
+    #[cfg(feature = "std")]
+    pub fn from_name<S>(name: S) -> Option<Self>
+    where
+        S: AsRef<str>,
+    {
+        let name: &str = name.as_ref();
+        let kinds = REGISTERED_KINDS.read().ok()?;
+        kinds
+            .iter()
+            .find(|(_, n)| n.as_str() == name)
+            .map(|(kind, _)| Self::from(*kind))
+    }
 }
 
 impl fmt::Display for Kind {
@@ -235,8 +370,13 @@ impl From<u64> for Kind {
             1040 => Self::OpenTimestamps,
             4 => Self::EncryptedDirectMessage,
             5 => Self::EventDeletion,
+            13 => Self::Seal,
+            14 => Self::PrivateDirectMessage,
+            1059 => Self::GiftWrap,
             6 => Self::Repost,
+            16 => Self::GenericRepost,
             7 => Self::Reaction,
+            17 => Self::ExternalReaction,
             8 => Self::BadgeAward,
             40 => Self::ChannelCreation,
             41 => Self::ChannelMetadata,
@@ -256,12 +396,14 @@ impl From<u64> for Kind {
             10000 => Self::MuteList,
             10001 => Self::PinList,
             10002 => Self::RelayList,
+            10003 => Self::Bookmarks,
             22242 => Self::Authentication,
             23194 => Self::WalletConnectRequest,
             23195 => Self::WalletConnectResponse,
             24133 => Self::NostrConnect,
             30000 => Self::CategorizedPeopleList,
             30001 => Self::CategorizedBookmarkList,
+            30003 => Self::BookmarkSet,
             30311 => Self::LiveEvent,
             1311 => Self::LiveEventMessage,
             30008 => Self::ProfileBadges,
@@ -271,6 +413,8 @@ impl From<u64> for Kind {
             30023 => Self::LongFormTextNote,
             30078 => Self::ApplicationSpecificData,
             1063 => Self::FileMetadata,
+            34235 => Self::HorizontalVideo,
+            34236 => Self::VerticalVideo,
             27235 => Self::HttpAuth,
             7000 => Self::JobFeedback,
             x if (NIP90_JOB_REQUEST_RANGE).contains(&x) => Self::JobRequest(x as u16),
@@ -296,8 +440,13 @@ impl From<Kind> for u64 {
             Kind::OpenTimestamps => 1040,
             Kind::EncryptedDirectMessage => 4,
             Kind::EventDeletion => 5,
+            Kind::Seal => 13,
+            Kind::PrivateDirectMessage => 14,
+            Kind::GiftWrap => 1059,
             Kind::Repost => 6,
+            Kind::GenericRepost => 16,
             Kind::Reaction => 7,
+            Kind::ExternalReaction => 17,
             Kind::BadgeAward => 8,
             Kind::ChannelCreation => 40,
             Kind::ChannelMetadata => 41,
@@ -317,12 +466,14 @@ impl From<Kind> for u64 {
             Kind::MuteList => 10000,
             Kind::PinList => 10001,
             Kind::RelayList => 10002,
+            Kind::Bookmarks => 10003,
             Kind::Authentication => 22242,
             Kind::WalletConnectRequest => 23194,
             Kind::WalletConnectResponse => 23195,
             Kind::NostrConnect => 24133,
             Kind::CategorizedPeopleList => 30000,
             Kind::CategorizedBookmarkList => 30001,
+            Kind::BookmarkSet => 30003,
             Kind::LiveEvent => 30311,
             Kind::LiveEventMessage => 1311,
             Kind::ProfileBadges => 30008,
@@ -332,6 +483,8 @@ impl From<Kind> for u64 {
             Kind::LongFormTextNote => 30023,
             Kind::ApplicationSpecificData => 30078,
             Kind::FileMetadata => 1063,
+            Kind::HorizontalVideo => 34235,
+            Kind::VerticalVideo => 34236,
             Kind::HttpAuth => 27235,
             Kind::JobFeedback => 7000,
             Kind::JobRequest(u) => u as u64,
@@ -424,6 +577,39 @@ mod tests {
         assert_ne!(Kind::TextNote, Kind::Custom(2));
     }
 
+    #[test]
+    fn test_kind_category() {
+        assert_eq!(Kind::TextNote.category(), KindCategory::Regular);
+        assert_eq!(Kind::Metadata.category(), KindCategory::Replaceable);
+        assert_eq!(Kind::RelayList.category(), KindCategory::Replaceable);
+        assert_eq!(Kind::Custom(20001).category(), KindCategory::Ephemeral);
+        assert_eq!(Kind::LongFormTextNote.category(), KindCategory::Addressable);
+        assert!(Kind::Custom(30023).is_addressable());
+        assert!(Kind::Custom(4).is_dm());
+        assert!(Kind::PrivateDirectMessage.is_dm());
+        assert!(!Kind::GiftWrap.is_dm());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_kind_register() {
+        assert_eq!(Kind::TextNote.name(), Some(String::from("TextNote")));
+        assert_eq!(
+            Kind::Custom(30023).name(),
+            Some(String::from("LongFormTextNote"))
+        );
+        assert_eq!(Kind::Custom(31_999).name(), None);
+
+        let kind = Kind::Custom(31_999);
+        kind.register("MyAppEvent");
+        assert_eq!(kind.name(), Some(String::from("MyAppEvent")));
+        assert_eq!(Kind::from_name("MyAppEvent"), Some(kind));
+
+        kind.unregister();
+        assert_eq!(kind.name(), None);
+        assert_eq!(Kind::from_name("MyAppEvent"), None);
+    }
+
     #[test]
     fn test_kind_is_parameterized_replaceable() {
         assert!(Kind::ParameterizedReplaceable(32122).is_parameterized_replaceable());
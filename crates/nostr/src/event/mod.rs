@@ -22,16 +22,24 @@ pub mod builder;
 pub mod id;
 pub mod kind;
 pub mod partial;
+pub mod raw;
 pub mod tag;
+pub mod template;
 pub mod unsigned;
+pub mod validation;
 
 pub use self::builder::EventBuilder;
 pub use self::id::EventId;
-pub use self::kind::Kind;
+pub use self::kind::{Kind, KindCategory};
 pub use self::partial::{MissingPartialEvent, PartialEvent};
+pub use self::raw::RawEvent;
 pub use self::tag::{Marker, Tag, TagKind};
+pub use self::template::EventTemplate;
 pub use self::unsigned::UnsignedEvent;
+pub use self::validation::ValidationReport;
 use crate::nips::nip01::Coordinate;
+use crate::nips::nip19::Nip19Event;
+use crate::nips::nip92::MediaAttachment;
 #[cfg(feature = "std")]
 use crate::types::time::Instant;
 use crate::types::time::TimeSupplier;
@@ -87,6 +95,10 @@ impl From<bitcoin::hashes::hex::Error> for Error {
     }
 }
 
+/// Min number of events to verify in parallel in [`Event::verify_batch`]
+#[cfg(all(feature = "std", not(target_arch = "wasm32")))]
+const VERIFY_BATCH_PARALLEL_THRESHOLD: usize = 64;
+
 /// [`Event`] struct
 #[derive(Debug, Clone)]
 pub struct Event {
@@ -234,6 +246,42 @@ impl Event {
         self.verify_signature_with_ctx(secp)
     }
 
+    /// Verify [`EventId`] and [`Signature`] of many events
+    ///
+    /// Events are verified in parallel, splitting them across the available threads
+    /// (sequentially on `wasm32` or for small batches).
+    /// Return the verification result of each event, in the same order.
+    #[cfg(feature = "std")]
+    pub fn verify_batch(events: &[Self]) -> Vec<Result<(), Error>> {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let threads: usize = std::thread::available_parallelism()
+                .map(|n| n.get())
+                .unwrap_or(1);
+            if threads > 1 && events.len() >= VERIFY_BATCH_PARALLEL_THRESHOLD {
+                let chunk_size: usize = (events.len() + threads - 1) / threads;
+                return std::thread::scope(|s| {
+                    let handles: Vec<_> = events
+                        .chunks(chunk_size)
+                        .map(|chunk| {
+                            s.spawn(move || chunk.iter().map(|e| e.verify()).collect::<Vec<_>>())
+                        })
+                        .collect();
+                    handles
+                        .into_iter()
+                        .flat_map(|handle| {
+                            handle
+                                .join()
+                                .unwrap_or_else(|e| std::panic::resume_unwind(e))
+                        })
+                        .collect()
+                });
+            }
+        }
+
+        events.iter().map(|e| e.verify()).collect()
+    }
+
     /// Verify if the [`EventId`] it's composed correctly
     pub fn verify_id(&self) -> Result<(), Error> {
         let id: EventId = EventId::new(
@@ -250,6 +298,26 @@ impl Event {
         }
     }
 
+    /// Verify if the [`EventId`] match the strict NIP-01 canonical serialization
+    ///
+    /// Useful for relays and bridges to detect events whose ID was computed with a non-canonical escaping
+    /// (see [`EventId::new_canonical`]).
+    #[cfg(feature = "std")]
+    pub fn verify_canonical_id(&self) -> Result<(), Error> {
+        let id: EventId = EventId::new_canonical(
+            &self.inner.pubkey,
+            self.inner.created_at,
+            &self.inner.kind,
+            &self.inner.tags,
+            &self.inner.content,
+        );
+        if id == self.inner.id {
+            Ok(())
+        } else {
+            Err(Error::InvalidId)
+        }
+    }
+
     /// Verify only event [`Signature`]
     #[cfg(feature = "std")]
     pub fn verify_signature(&self) -> Result<(), Error> {
@@ -294,11 +362,50 @@ impl Event {
     where
         T: TimeSupplier,
     {
-        if let Some(timestamp) = self.expiration() {
-            let now: Timestamp = Timestamp::now_with_supplier(supplier);
-            return timestamp < &now;
+        let now: Timestamp = Timestamp::now_with_supplier(supplier);
+        self.is_expired_at(now)
+    }
+
+    /// Returns `true` if the event has an expiration tag that is expired at the given [`Timestamp`].
+    /// If an event has no `Expiration` tag, then it will return `false`.
+    ///
+    /// <https://github.com/nostr-protocol/nips/blob/master/40.md>
+    pub fn is_expired_at(&self, timestamp: Timestamp) -> bool {
+        match self.expiration() {
+            Some(expiration) => expiration < &timestamp,
+            None => false,
         }
-        false
+    }
+
+    /// Get content warning (NIP36)
+    ///
+    /// Return `None` if the event hasn't a `content-warning` tag,
+    /// `Some(None)` if the content warning hasn't a reason.
+    ///
+    /// <https://github.com/nostr-protocol/nips/blob/master/36.md>
+    pub fn content_warning(&self) -> Option<Option<&str>> {
+        self.iter_tags().find_map(|t| match t {
+            Tag::ContentWarning { reason } => Some(reason.as_deref()),
+            _ => None,
+        })
+    }
+
+    /// Get the media attachments (`imeta` tags)
+    ///
+    /// Malformed `imeta` tags are skipped.
+    ///
+    /// <https://github.com/nostr-protocol/nips/blob/master/92.md>
+    pub fn media_attachments(&self) -> Vec<MediaAttachment> {
+        self.iter_tags()
+            .filter_map(|t| MediaAttachment::from_tag(t).ok())
+            .collect()
+    }
+
+    /// Check if event is protected (`-` tag)
+    ///
+    /// <https://github.com/nostr-protocol/nips/blob/master/70.md>
+    pub fn is_protected(&self) -> bool {
+        self.iter_tags().any(|t| t == &Tag::Protected)
     }
 
     /// Check if [`Kind`] is a NIP90 job request
@@ -390,6 +497,46 @@ impl Event {
             _ => None,
         })
     }
+
+    /// Validate tag values (ids, public keys, coordinates and relay URLs)
+    pub fn validate_tags(&self) -> ValidationReport {
+        ValidationReport::from(self.tags())
+    }
+
+    /// Compose `nevent` entity (event id, author, kind and relay hints)
+    ///
+    /// <https://github.com/nostr-protocol/nips/blob/master/19.md>
+    pub fn to_nevent<I, S>(&self, relays: I) -> Nip19Event
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Nip19Event::new(self.id(), relays)
+            .author(self.author())
+            .kind(self.kind())
+    }
+
+    /// Compose `naddr` entity (kind, author, identifier and relay hints)
+    ///
+    /// Return `None` if the event is not replaceable or parameterized replaceable.
+    ///
+    /// <https://github.com/nostr-protocol/nips/blob/master/19.md>
+    pub fn to_naddr<I, S>(&self, relays: I) -> Option<Coordinate>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        if !self.is_replaceable() && !self.is_parameterized_replaceable() {
+            return None;
+        }
+
+        let mut coordinate = Coordinate::new(self.kind(), self.author());
+        if let Some(identifier) = self.identifier() {
+            coordinate = coordinate.identifier(identifier);
+        }
+        coordinate.relays = relays.into_iter().map(|u| u.into()).collect();
+        Some(coordinate)
+    }
 }
 
 impl JsonUtil for Event {
@@ -474,7 +621,7 @@ impl<'de> Deserialize<'de> for Event {
 mod tests {
     use super::*;
     #[cfg(feature = "std")]
-    use crate::Keys;
+    use crate::{Keys, UncheckedUrl};
 
     #[test]
     fn test_tags_deser_without_recommended_relay() {
@@ -500,6 +647,35 @@ mod tests {
         assert_eq!(Kind::Custom(123), deserialized.kind());
     }
 
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_to_nevent_and_naddr() {
+        let keys = Keys::generate();
+        let note: Event = EventBuilder::text_note("Hello", [])
+            .to_event(&keys)
+            .unwrap();
+
+        let nevent = note.to_nevent(["wss://relay.damus.io"]);
+        assert_eq!(nevent.event_id, note.id());
+        assert_eq!(nevent.author, Some(keys.public_key()));
+        assert_eq!(nevent.kind, Some(Kind::TextNote));
+        assert_eq!(nevent.relays, vec![String::from("wss://relay.damus.io")]);
+        assert!(note.to_naddr(["wss://relay.damus.io"]).is_none());
+
+        let article: Event = EventBuilder::new(
+            Kind::LongFormTextNote,
+            "Article",
+            [Tag::Identifier(String::from("my-article"))],
+        )
+        .to_event(&keys)
+        .unwrap();
+        let naddr = article.to_naddr(["wss://relay.damus.io"]).unwrap();
+        assert_eq!(naddr.kind, Kind::LongFormTextNote);
+        assert_eq!(naddr.pubkey, keys.public_key());
+        assert_eq!(naddr.identifier, "my-article");
+        assert_eq!(naddr.relays, vec![String::from("wss://relay.damus.io")]);
+    }
+
     #[test]
     #[cfg(feature = "std")]
     fn test_event_expired() {
@@ -510,6 +686,29 @@ mod tests {
                 .unwrap();
 
         assert!(&event.is_expired());
+        assert_eq!(event.expiration(), Some(&Timestamp::from(1600000000)));
+        assert!(event.is_expired_at(Timestamp::from(1600000001)));
+        assert!(!event.is_expired_at(Timestamp::from(1600000000)));
+        assert!(!event.is_expired_at(Timestamp::from(1500000000)));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_media_attachments() {
+        let keys = Keys::generate();
+        let attachment = MediaAttachment::new(UncheckedUrl::from("https://example.com/a.png"))
+            .mime_type("image/png")
+            .alt("A picture");
+        let event = EventBuilder::text_note(
+            "https://example.com/a.png",
+            [
+                attachment.to_tag(),
+                Tag::parse(vec!["imeta", "m image/png"]).unwrap(),
+            ],
+        )
+        .to_event(&keys)
+        .unwrap();
+        assert_eq!(event.media_attachments(), vec![attachment]);
     }
 
     #[test]
@@ -539,6 +738,30 @@ mod tests {
         assert!(!&event.is_expired());
     }
 
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_event_content_warning() {
+        let my_keys = Keys::generate();
+        let event = EventBuilder::text_note("my content", [])
+            .to_event(&my_keys)
+            .unwrap();
+        assert_eq!(event.content_warning(), None);
+
+        let event = EventBuilder::text_note("my content", [])
+            .content_warning(None::<String>)
+            .to_event(&my_keys)
+            .unwrap();
+        assert_eq!(event.content_warning(), Some(None));
+
+        let event = EventBuilder::text_note("my content", [])
+            .content_warning(Some("first"))
+            .content_warning(Some("nudity"))
+            .to_event(&my_keys)
+            .unwrap();
+        assert_eq!(event.content_warning(), Some(Some("nudity")));
+        assert_eq!(event.tags().len(), 1);
+    }
+
     #[test]
     fn test_verify_event_id() {
         let event = Event::from_json(r#"{"content":"","created_at":1698412975,"id":"f55c30722f056e330d8a7a6a9ba1522f7522c0f1ced1c93d78ea833c78a3d6ec","kind":3,"pubkey":"f831caf722214748c72db4829986bd0cbb2bb8b3aeade1c959624a52a9629046","sig":"5092a9ffaecdae7d7794706f085ff5852befdf79df424cc3419bb797bf515ae05d4f19404cb8324b8b4380a4bd497763ac7b0f3b1b63ef4d3baa17e5f5901808","tags":[["p","4ddeb9109a8cd29ba279a637f5ec344f2479ee07df1f4043f3fe26d8948cfef9","",""],["p","bb6fd06e156929649a73e6b278af5e648214a69d88943702f1fb627c02179b95","",""],["p","b8b8210f33888fdbf5cedee9edf13c3e9638612698fe6408aff8609059053420","",""],["p","9dcee4fabcd690dc1da9abdba94afebf82e1e7614f4ea92d61d52ef9cd74e083","",""],["p","3eea9e831fefdaa8df35187a204d82edb589a36b170955ac5ca6b88340befaa0","",""],["p","885238ab4568f271b572bf48b9d6f99fa07644731f288259bd395998ee24754e","",""],["p","568a25c71fba591e39bebe309794d5c15d27dbfa7114cacb9f3586ea1314d126","",""]]}"#).unwrap();
@@ -548,6 +771,110 @@ mod tests {
         event.verify_id().unwrap();
     }
 
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_event_id_streaming() {
+        use bitcoin::hashes::sha256::Hash as Sha256Hash;
+        use serde_json::json;
+
+        let keys = Keys::generate();
+        let public_key = keys.public_key();
+        let created_at = Timestamp::from(1703184271);
+        let kind = Kind::LongFormTextNote;
+        let tags = vec![
+            Tag::Identifier(String::from("big")),
+            Tag::parse(vec!["t", "quote\"s", "extra"]).unwrap(),
+        ];
+        let content: String = "Line with \"quotes\", \\ and unicode ⚡\n".repeat(50_000);
+
+        let json = json!([0, public_key, created_at, kind, tags, content]).to_string();
+        let expected =
+            EventId::from_hash(<Sha256Hash as bitcoin::hashes::Hash>::hash(json.as_bytes()));
+        assert_eq!(
+            EventId::new(&public_key, created_at, &kind, &tags, &content),
+            expected
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_canonical_id() {
+        use bitcoin::hashes::sha256::Hash as Sha256Hash;
+
+        let keys = Keys::generate();
+        let public_key = keys.public_key();
+        let created_at = Timestamp::from(1703184271);
+
+        // Same ID when there aren't control characters to escape as `\u00XX`
+        let content = "Line with \"quotes\", \\, \t, \u{8}, \u{c}, unicode ⚡ and /\r\n";
+        assert_eq!(
+            EventId::new_canonical(&public_key, created_at, &Kind::TextNote, &[], content),
+            EventId::new(&public_key, created_at, &Kind::TextNote, &[], content)
+        );
+
+        // Other control characters are included verbatim
+        let content = "a\u{1}b\u{1f}\n";
+        let json = format!("[0,\"{public_key}\",1703184271,1,[],\"a\u{1}b\u{1f}\\n\"]");
+        let expected =
+            EventId::from_hash(<Sha256Hash as bitcoin::hashes::Hash>::hash(json.as_bytes()));
+        let canonical_id =
+            EventId::new_canonical(&public_key, created_at, &Kind::TextNote, &[], content);
+        assert_eq!(canonical_id, expected);
+        assert_ne!(
+            canonical_id,
+            EventId::new(&public_key, created_at, &Kind::TextNote, &[], content)
+        );
+
+        let event = EventBuilder::text_note(content, [])
+            .custom_created_at(created_at)
+            .to_event(&keys)
+            .unwrap();
+        assert!(event.verify_id().is_ok());
+        assert!(matches!(event.verify_canonical_id(), Err(Error::InvalidId)));
+
+        let event = EventBuilder::text_note("hello", [])
+            .to_event(&keys)
+            .unwrap();
+        assert!(event.verify_canonical_id().is_ok());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_verify_batch() {
+        let keys = Keys::generate();
+        let mut events: Vec<Event> = (0..100)
+            .map(|i| {
+                EventBuilder::text_note(format!("Note {i}"), [])
+                    .to_event(&keys)
+                    .unwrap()
+            })
+            .collect();
+
+        // Tamper content
+        let event = events[42].clone();
+        events[42] = Event::new(
+            event.id(),
+            event.author(),
+            event.created_at(),
+            event.kind(),
+            event.tags().to_vec(),
+            "Tampered",
+            event.signature(),
+        );
+
+        let results = Event::verify_batch(&events);
+        assert_eq!(results.len(), events.len());
+        for (i, res) in results.into_iter().enumerate() {
+            if i == 42 {
+                assert!(matches!(res, Err(Error::InvalidId)));
+            } else {
+                assert!(res.is_ok());
+            }
+        }
+
+        assert!(Event::verify_batch(&events[..3]).iter().all(|r| r.is_ok()));
+    }
+
     // Test only with `std` feature due to `serde_json` preserve_order feature.
     #[test]
     #[cfg(feature = "std")]
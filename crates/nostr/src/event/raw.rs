@@ -0,0 +1,329 @@
+// Copyright (c) 2022-2023 Yuki Kishimoto
+// Copyright (c) 2023-2024 Rust Nostr Developers
+// Distributed under the MIT software license
+
+//! Raw Event for zero-copy deserialization
+//!
+//! Strings are borrowed from the JSON input and tags and kind are not converted to their typed representation,
+//! until [`RawEvent::into_owned`] is called.
+
+use alloc::borrow::Cow;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use core::str::FromStr;
+
+use bitcoin::hashes::sha256::Hash as Sha256Hash;
+use bitcoin::hashes::Hash;
+use bitcoin::secp256k1::schnorr::Signature;
+use bitcoin::secp256k1::{self, Message, Secp256k1, Verification, XOnlyPublicKey};
+use serde::de::{self, Deserializer, Visitor};
+use serde::{Deserialize, Serialize};
+
+use super::{id, tag};
+#[cfg(feature = "std")]
+use crate::SECP256K1;
+use crate::{Event, EventId, Kind, Tag, Timestamp};
+
+/// [`RawEvent`] error
+#[derive(Debug)]
+pub enum Error {
+    /// Error serializing or deserializing JSON data
+    Json(serde_json::Error),
+    /// Secp256k1 error
+    Secp256k1(secp256k1::Error),
+    /// Event ID error
+    EventId(id::Error),
+    /// Tag parse
+    Tag(tag::Error),
+    /// Invalid event id
+    InvalidId,
+    /// Invalid signature
+    InvalidSignature,
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Json(e) => write!(f, "Json: {e}"),
+            Self::Secp256k1(e) => write!(f, "Secp256k1: {e}"),
+            Self::EventId(e) => write!(f, "Event ID: {e}"),
+            Self::Tag(e) => write!(f, "Tag: {e}"),
+            Self::InvalidId => write!(f, "Invalid event id"),
+            Self::InvalidSignature => write!(f, "Invalid signature"),
+        }
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(e: serde_json::Error) -> Self {
+        Self::Json(e)
+    }
+}
+
+impl From<secp256k1::Error> for Error {
+    fn from(e: secp256k1::Error) -> Self {
+        Self::Secp256k1(e)
+    }
+}
+
+impl From<id::Error> for Error {
+    fn from(e: id::Error) -> Self {
+        Self::EventId(e)
+    }
+}
+
+impl From<tag::Error> for Error {
+    fn from(e: tag::Error) -> Self {
+        Self::Tag(e)
+    }
+}
+
+/// Raw event
+///
+/// Deserialize an event borrowing the strings from the input, without parsing the tags
+/// (useful for relays and indexers that process lots of events).
+/// Strings containing escape sequences are the only ones allocated.
+///
+/// # Example
+/// ```rust,no_run
+/// use nostr::RawEvent;
+///
+/// # let json = r#"{"id":"","pubkey":"","created_at":0,"kind":1,"tags":[],"content":"","sig":""}"#;
+/// let raw = RawEvent::from_json(json).unwrap();
+/// raw.verify().unwrap();
+/// let event = raw.into_owned().unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct RawEvent<'a> {
+    /// ID (hex)
+    pub id: &'a str,
+    /// Author (hex)
+    pub pubkey: &'a str,
+    /// Timestamp (seconds)
+    pub created_at: u64,
+    /// Kind
+    pub kind: u64,
+    /// Tags
+    #[serde(borrow, deserialize_with = "deserialize_tags")]
+    pub tags: Vec<Vec<Cow<'a, str>>>,
+    /// Content
+    #[serde(borrow)]
+    pub content: Cow<'a, str>,
+    /// Signature (hex)
+    pub sig: &'a str,
+}
+
+impl<'a> RawEvent<'a> {
+    /// Deserialize [`RawEvent`] from JSON, borrowing from the input
+    pub fn from_json<T>(json: &'a T) -> Result<Self, Error>
+    where
+        T: AsRef<[u8]> + ?Sized,
+    {
+        Ok(serde_json::from_slice(json.as_ref())?)
+    }
+
+    /// Parse [`EventId`]
+    pub fn event_id(&self) -> Result<EventId, Error> {
+        Ok(EventId::from_hex(self.id)?)
+    }
+
+    /// Parse author public key
+    pub fn author(&self) -> Result<XOnlyPublicKey, Error> {
+        Ok(XOnlyPublicKey::from_str(self.pubkey)?)
+    }
+
+    /// Parse [`Signature`]
+    pub fn signature(&self) -> Result<Signature, Error> {
+        Ok(Signature::from_str(self.sig)?)
+    }
+
+    /// Get [`Kind`]
+    pub fn kind(&self) -> Kind {
+        Kind::from(self.kind)
+    }
+
+    /// Get [`Timestamp`]
+    pub fn created_at(&self) -> Timestamp {
+        Timestamp::from(self.created_at)
+    }
+
+    /// Compute the [`EventId`] from the raw fields
+    pub fn compute_id(&self) -> Result<EventId, Error> {
+        let data = (
+            0,
+            self.pubkey,
+            self.created_at,
+            self.kind,
+            &self.tags,
+            &self.content,
+        );
+
+        #[cfg(feature = "std")]
+        {
+            let mut engine = Sha256Hash::engine();
+            serde_json::to_writer(&mut engine, &data)?;
+            Ok(EventId::from_hash(Sha256Hash::from_engine(engine)))
+        }
+
+        #[cfg(not(feature = "std"))]
+        {
+            let json: Vec<u8> = serde_json::to_vec(&data)?;
+            Ok(EventId::from_hash(Sha256Hash::hash(&json)))
+        }
+    }
+
+    /// Verify both [`EventId`] and [`Signature`]
+    #[cfg(feature = "std")]
+    pub fn verify(&self) -> Result<(), Error> {
+        self.verify_with_ctx(&SECP256K1)
+    }
+
+    /// Verify [`EventId`] and [`Signature`]
+    pub fn verify_with_ctx<C>(&self, secp: &Secp256k1<C>) -> Result<(), Error>
+    where
+        C: Verification,
+    {
+        let id: EventId = self.event_id()?;
+        if self.compute_id()? != id {
+            return Err(Error::InvalidId);
+        }
+
+        let message = Message::from_slice(id.as_bytes())?;
+        secp.verify_schnorr(&self.signature()?, &message, &self.author()?)
+            .map_err(|_| Error::InvalidSignature)
+    }
+
+    /// Extract identifier (`d` tag), if exists.
+    pub fn identifier(&self) -> Option<&str> {
+        for tag in self.tags.iter() {
+            if let Some("d") = tag.first().map(|x| x.as_ref()) {
+                return tag.get(1).map(|x| x.as_ref());
+            }
+        }
+        None
+    }
+
+    /// Parse all the fields and compose [`Event`]
+    ///
+    /// **This method NOT verify the signature!**
+    pub fn into_owned(self) -> Result<Event, Error> {
+        let mut tags: Vec<Tag> = Vec::with_capacity(self.tags.len());
+        for tag in self.tags.into_iter() {
+            tags.push(Tag::parse(tag)?);
+        }
+
+        Ok(Event::new(
+            EventId::from_hex(self.id)?,
+            XOnlyPublicKey::from_str(self.pubkey)?,
+            Timestamp::from(self.created_at),
+            Kind::from(self.kind),
+            tags,
+            self.content.into_owned(),
+            Signature::from_str(self.sig)?,
+        ))
+    }
+}
+
+/// Borrow the string if it doesn't contain escape sequences
+struct CowStr<'a>(Cow<'a, str>);
+
+impl<'de> Deserialize<'de> for CowStr<'de> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct CowStrVisitor;
+
+        impl<'de> Visitor<'de> for CowStrVisitor {
+            type Value = CowStr<'de>;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "a string")
+            }
+
+            fn visit_borrowed_str<E>(self, v: &'de str) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Ok(CowStr(Cow::Borrowed(v)))
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Ok(CowStr(Cow::Owned(String::from(v))))
+            }
+
+            fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Ok(CowStr(Cow::Owned(v)))
+            }
+        }
+
+        deserializer.deserialize_str(CowStrVisitor)
+    }
+}
+
+fn deserialize_tags<'de, D>(deserializer: D) -> Result<Vec<Vec<Cow<'de, str>>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let tags: Vec<Vec<CowStr<'de>>> = Vec::deserialize(deserializer)?;
+    Ok(tags
+        .into_iter()
+        .map(|tag| tag.into_iter().map(|s| s.0).collect())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "std")]
+    use crate::{EventBuilder, JsonUtil, Keys};
+
+    #[test]
+    fn test_raw_event_borrow() {
+        let json = r#"{"content":"uRuvYr585B80L6rSJiHocw==?iv=oh6LVqdsYYol3JfFnXTbPA==","created_at":1640839235,"id":"2be17aa3031bdcb006f0fce80c146dea9c1c0268b0af2398bb673365c6444d45","kind":4,"pubkey":"f86c44a2de95d9149b51c6a29afeabba264c18e2fa7c49de93424a0c56947785","sig":"a5d9290ef9659083c490b303eb7ee41356d8778ff19f2f91776c8dc4443388a64ffcf336e61af4c25c05ac3ae952d1ced889ed655b67790891222aaa15b99fdd","tags":[["p","13adc511de7e1cfcf1c6b7f6365fb5a03442d7bcacf565ea57fa7770912c023d"]]}"#;
+        let raw = RawEvent::from_json(json).unwrap();
+        assert!(matches!(raw.content, Cow::Borrowed(..)));
+        assert!(matches!(raw.tags[0][1], Cow::Borrowed(..)));
+        assert_eq!(raw.kind(), Kind::EncryptedDirectMessage);
+
+        let event: Event = raw.clone().into_owned().unwrap();
+        assert_eq!(event.id(), raw.event_id().unwrap());
+        assert_eq!(event.tags().len(), 1);
+        assert_eq!(event.content(), raw.content);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_raw_event_verify() {
+        let keys = Keys::generate();
+        let event: Event = EventBuilder::text_note(
+            "Hello \"nostr\"\nwith escapes and unicode: ⚡",
+            [Tag::parse(vec!["t", "a\\b", "extra"]).unwrap()],
+        )
+        .to_event(&keys)
+        .unwrap();
+        let json: String = event.as_json();
+
+        let raw = RawEvent::from_json(&json).unwrap();
+        assert!(matches!(raw.content, Cow::Owned(..)));
+        assert_eq!(raw.compute_id().unwrap(), event.id());
+        raw.verify().unwrap();
+        assert_eq!(raw.identifier(), None);
+        assert_eq!(raw.into_owned().unwrap(), event);
+
+        // Tampered content
+        let json: String = json.replace("Hello", "Hallo");
+        let raw = RawEvent::from_json(&json).unwrap();
+        assert!(matches!(raw.verify(), Err(Error::InvalidId)));
+    }
+}
@@ -20,6 +20,7 @@ use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use url_fork::{ParseError, Url};
 
 use super::id::{self, EventId};
+use super::validation;
 use crate::nips::nip26::{Conditions, Error as Nip26Error};
 use crate::nips::nip48::Protocol;
 use crate::nips::nip53::{self, LiveEventMarker, LiveEventStatus};
@@ -426,6 +427,14 @@ pub enum TagKind {
     Emoji,
     /// Request (NIP90)
     Request,
+    /// Protected event (NIP70)
+    Protected,
+    /// Alternative description (NIP31)
+    Alt,
+    /// Kind
+    K,
+    /// Quote (NIP18)
+    Q,
     /// Custom tag kind
     Custom(String),
 }
@@ -483,6 +492,10 @@ impl fmt::Display for TagKind {
             Self::Proxy => write!(f, "proxy"),
             Self::Emoji => write!(f, "emoji"),
             Self::Request => write!(f, "request"),
+            Self::Protected => write!(f, "-"),
+            Self::Alt => write!(f, "alt"),
+            Self::K => write!(f, "k"),
+            Self::Q => write!(f, "q"),
             Self::Custom(tag) => write!(f, "{tag}"),
         }
     }
@@ -544,6 +557,10 @@ where
             "proxy" => Self::Proxy,
             "emoji" => Self::Emoji,
             "request" => Self::Request,
+            "-" => Self::Protected,
+            "alt" => Self::Alt,
+            "k" => Self::K,
+            "q" => Self::Q,
             t => Self::Custom(t.to_owned()),
         }
     }
@@ -655,6 +672,13 @@ pub enum Tag {
         status: DataVendingMachineStatus,
         extra_info: Option<String>,
     },
+    Protected,
+    Alt(String),
+    Kind(Kind),
+    Quote {
+        event_id: EventId,
+        relay_url: Option<UncheckedUrl>,
+    },
 }
 
 impl Tag {
@@ -761,6 +785,47 @@ impl Tag {
             Self::Proxy { .. } => TagKind::Proxy,
             Self::Emoji { .. } => TagKind::Emoji,
             Self::Request(..) => TagKind::Request,
+            Self::Protected => TagKind::Protected,
+            Self::Alt(..) => TagKind::Alt,
+            Self::Kind(..) => TagKind::K,
+            Self::Quote { .. } => TagKind::Q,
+        }
+    }
+
+    /// Get the first value of the tag (the element after the tag kind), if any
+    ///
+    /// Works for both typed and [`Tag::Generic`] tags.
+    pub fn content(&self) -> Option<String> {
+        self.as_vec().into_iter().nth(1)
+    }
+
+    /// Get the [`EventId`] of an `e` or `q` tag
+    ///
+    /// Fall back to parse the value of a [`Tag::Generic`] tag (i.e. an `e` tag with an unknown marker).
+    pub fn as_event_id(&self) -> Option<EventId> {
+        match self {
+            Self::Event { event_id, .. }
+            | Self::EventReport(event_id, ..)
+            | Self::Quote { event_id, .. } => Some(*event_id),
+            Self::Generic(TagKind::E | TagKind::Q, values) => {
+                EventId::from_hex(values.first()?).ok()
+            }
+            _ => None,
+        }
+    }
+
+    /// Get the public key of a `p` or `P` tag
+    ///
+    /// Fall back to parse the value of a [`Tag::Generic`] tag.
+    pub fn as_public_key(&self) -> Option<XOnlyPublicKey> {
+        match self {
+            Self::PublicKey { public_key, .. }
+            | Self::PubKeyReport(public_key, ..)
+            | Self::PubKeyLiveEvent { public_key, .. } => Some(*public_key),
+            Self::Generic(TagKind::P | TagKind::UpperP, values) => {
+                XOnlyPublicKey::from_str(values.first()?).ok()
+            }
+            _ => None,
         }
     }
 }
@@ -772,6 +837,42 @@ where
     type Error = Error;
 
     fn try_from(tag: Vec<S>) -> Result<Self, Self::Error> {
+        let first: &str = match tag.first() {
+            Some(kind) => kind.as_ref(),
+            None => return Err(Error::KindNotFound),
+        };
+
+        // Keep the typed tag only if it serializes back to the same vector,
+        // otherwise the event ID and signature could not be verified anymore.
+        match Self::parse_typed(&tag) {
+            Ok(typed)
+                if typed
+                    .as_vec()
+                    .iter()
+                    .map(|s| s.as_str())
+                    .eq(tag.iter().map(|s| s.as_ref())) =>
+            {
+                Ok(typed)
+            }
+            _ => {
+                let mut kind: TagKind = TagKind::from(first);
+                if kind.to_string() != first {
+                    kind = TagKind::Custom(first.to_string());
+                }
+                Ok(Self::Generic(
+                    kind,
+                    tag[1..].iter().map(|s| s.as_ref().to_owned()).collect(),
+                ))
+            }
+        }
+    }
+}
+
+impl Tag {
+    fn parse_typed<S>(tag: &[S]) -> Result<Self, Error>
+    where
+        S: AsRef<str>,
+    {
         let tag_len: usize = tag.len();
         let tag_kind: TagKind = match tag.first() {
             Some(kind) => TagKind::from(kind),
@@ -790,6 +891,7 @@ where
             match tag_kind {
                 TagKind::ContentWarning => Ok(Self::ContentWarning { reason: None }),
                 TagKind::Anon => Ok(Self::Anon { msg: None }),
+                TagKind::Protected => Ok(Self::Protected),
                 _ => Ok(Self::Generic(tag_kind, Vec::new())),
             }
         } else if tag_len == 2 {
@@ -823,7 +925,7 @@ where
                 }
                 TagKind::E => Ok(Self::event(EventId::from_hex(tag_1)?)),
                 TagKind::R => {
-                    if tag_1.starts_with("ws://") || tag_1.starts_with("wss://") {
+                    if validation::is_relay_url(tag_1) {
                         Ok(Self::RelayMetadata(UncheckedUrl::from(tag_1), None))
                     } else {
                         Ok(Self::Reference(tag_1.to_owned()))
@@ -838,6 +940,19 @@ where
                 }),
                 TagKind::Expiration => Ok(Self::Expiration(Timestamp::from_str(tag_1)?)),
                 TagKind::Subject => Ok(Self::Subject(tag_1.to_owned())),
+                TagKind::Alt => Ok(Self::Alt(tag_1.to_owned())),
+                TagKind::K => match Kind::from_str(tag_1) {
+                    Ok(kind) => Ok(Self::Kind(kind)),
+                    // External content kind (i.e. `web`)
+                    Err(_) => Ok(Self::Generic(tag_kind, vec![tag_1.to_owned()])),
+                },
+                TagKind::Q => match EventId::from_hex(tag_1) {
+                    Ok(event_id) => Ok(Self::Quote {
+                        event_id,
+                        relay_url: None,
+                    }),
+                    Err(_) => Ok(Self::Generic(tag_kind, vec![tag_1.to_owned()])),
+                },
                 TagKind::Challenge => Ok(Self::Challenge(tag_1.to_owned())),
                 TagKind::Title => Ok(Self::Title(tag_1.to_owned())),
                 TagKind::Image => Ok(Self::Image(UncheckedUrl::from(tag_1), None)),
@@ -856,6 +971,8 @@ where
                 TagKind::Url => Ok(Self::Url(Url::parse(tag_1)?)),
                 TagKind::M => Ok(Self::MimeType(tag_1.to_owned())),
                 TagKind::X => Ok(Self::Sha256(Sha256Hash::from_str(tag_1)?)),
+                TagKind::Size => Ok(Self::Size(tag_1.parse()?)),
+                TagKind::Dim => Ok(Self::Dim(ImageDimensions::from_str(tag_1)?)),
                 TagKind::Magnet => Ok(Self::Magnet(tag_1.to_owned())),
                 TagKind::Blurhash => Ok(Self::Blurhash(tag_1.to_owned())),
                 TagKind::Streaming => Ok(Self::Streaming(UncheckedUrl::from(tag_1))),
@@ -925,6 +1042,16 @@ where
                         }
                     }
                 }
+                TagKind::Q => match EventId::from_hex(tag_1) {
+                    Ok(event_id) => Ok(Self::Quote {
+                        event_id,
+                        relay_url: (!tag_2.is_empty()).then_some(UncheckedUrl::from(tag_2)),
+                    }),
+                    Err(_) => Ok(Self::Generic(
+                        tag_kind,
+                        tag[1..].iter().map(|s| s.as_ref().to_owned()).collect(),
+                    )),
+                },
                 TagKind::I => match Identity::new(tag_1, tag_2) {
                     Ok(identity) => Ok(Self::ExternalIdentity(identity)),
                     Err(_) => Ok(Self::Generic(
@@ -1274,6 +1401,19 @@ impl From<Tag> for Vec<String> {
                 }
                 tag
             }
+            Tag::Protected => vec![TagKind::Protected.to_string()],
+            Tag::Alt(description) => vec![TagKind::Alt.to_string(), description],
+            Tag::Kind(kind) => vec![TagKind::K.to_string(), kind.to_string()],
+            Tag::Quote {
+                event_id,
+                relay_url,
+            } => {
+                let mut tag = vec![TagKind::Q.to_string(), event_id.to_hex()];
+                if let Some(relay_url) = relay_url {
+                    tag.push(relay_url.to_string());
+                }
+                tag
+            }
         }
     }
 }
@@ -1449,6 +1589,31 @@ mod tests {
             Tag::ContentWarning { reason: None }.as_vec()
         );
 
+        assert_eq!(vec!["-"], Tag::Protected.as_vec());
+
+        assert_eq!(
+            vec!["alt", "Short note"],
+            Tag::Alt(String::from("Short note")).as_vec()
+        );
+
+        assert_eq!(vec!["k", "1"], Tag::Kind(Kind::TextNote).as_vec());
+
+        assert_eq!(
+            vec![
+                "q",
+                "378f145897eea948952674269945e88612420db35791784abf0616b4fed56ef7",
+                "wss://relay.damus.io"
+            ],
+            Tag::Quote {
+                event_id: EventId::from_hex(
+                    "378f145897eea948952674269945e88612420db35791784abf0616b4fed56ef7"
+                )
+                .unwrap(),
+                relay_url: Some(UncheckedUrl::from("wss://relay.damus.io")),
+            }
+            .as_vec()
+        );
+
         assert_eq!(
             vec![
                 "p",
@@ -1745,6 +1910,61 @@ mod tests {
             Tag::ContentWarning { reason: None }
         );
 
+        assert_eq!(Tag::parse(vec!["-"]).unwrap(), Tag::Protected);
+
+        assert_eq!(
+            Tag::parse(vec!["alt", "Short note"]).unwrap(),
+            Tag::Alt(String::from("Short note"))
+        );
+
+        assert_eq!(
+            Tag::parse(vec!["k", "30023"]).unwrap(),
+            Tag::Kind(Kind::LongFormTextNote)
+        );
+
+        assert_eq!(
+            Tag::parse(vec!["k", "web"]).unwrap(),
+            Tag::Generic(TagKind::K, vec![String::from("web")])
+        );
+
+        assert_eq!(
+            Tag::parse(vec![
+                "q",
+                "378f145897eea948952674269945e88612420db35791784abf0616b4fed56ef7"
+            ])
+            .unwrap(),
+            Tag::Quote {
+                event_id: EventId::from_hex(
+                    "378f145897eea948952674269945e88612420db35791784abf0616b4fed56ef7"
+                )
+                .unwrap(),
+                relay_url: None,
+            }
+        );
+
+        assert_eq!(
+            Tag::parse(vec!["q", "not-an-event-id"]).unwrap(),
+            Tag::Generic(TagKind::Q, vec![String::from("not-an-event-id")])
+        );
+
+        assert_eq!(
+            Tag::parse(vec!["q", "not-an-event-id", "wss://relay.damus.io"]).unwrap(),
+            Tag::Generic(
+                TagKind::Q,
+                vec![
+                    String::from("not-an-event-id"),
+                    String::from("wss://relay.damus.io")
+                ]
+            )
+        );
+
+        assert_eq!(Tag::parse(vec!["size", "1024"]).unwrap(), Tag::Size(1024));
+
+        assert_eq!(
+            Tag::parse(vec!["dim", "640x480"]).unwrap(),
+            Tag::Dim(ImageDimensions::new(640, 480))
+        );
+
         assert_eq!(
             Tag::parse(vec![
                 "p",
@@ -2040,6 +2260,80 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn test_tag_lossless_round_trip() {
+        let event_id = "378f145897eea948952674269945e88612420db35791784abf0616b4fed56ef7";
+        let public_key = "13adc511de7e1cfcf1c6b7f6365fb5a03442d7bcacf565ea57fa7770912c023d";
+        let tags: Vec<Vec<&str>> = vec![
+            vec!["e", event_id, "wss://relay.damus.io", ""],
+            vec!["e", event_id, "", ""],
+            vec!["e", event_id, "wss://relay.damus.io", "fork", "extra"],
+            vec!["e", "not-an-event-id"],
+            vec!["p", public_key, "", "", "extra"],
+            vec!["p", "invalid"],
+            vec!["r", "wss://relay.damus.io", "foo"],
+            vec!["t", "nostr", "extra", "more"],
+            vec!["anon", ""],
+            vec!["expiration", "not-a-timestamp"],
+            vec!["custom", "a", "b", "c", "d", "e"],
+            vec!["zap", public_key, "wss://relay.damus.io", "1", "extra"],
+        ];
+
+        for tag in tags.into_iter() {
+            assert_eq!(Tag::parse(tag.clone()).unwrap().as_vec(), tag);
+        }
+    }
+
+    #[test]
+    fn test_tag_typed_accessors() {
+        let event_id =
+            EventId::from_hex("378f145897eea948952674269945e88612420db35791784abf0616b4fed56ef7")
+                .unwrap();
+        let public_key = XOnlyPublicKey::from_str(
+            "13adc511de7e1cfcf1c6b7f6365fb5a03442d7bcacf565ea57fa7770912c023d",
+        )
+        .unwrap();
+
+        // Typed
+        let tag = Tag::event(event_id);
+        assert_eq!(tag.as_event_id(), Some(event_id));
+        assert_eq!(tag.content(), Some(event_id.to_hex()));
+        assert_eq!(
+            Tag::public_key(public_key).as_public_key(),
+            Some(public_key)
+        );
+
+        // Generic fallback
+        let tag = Tag::parse(vec![
+            "e".to_string(),
+            event_id.to_hex(),
+            "wss://relay.damus.io".to_string(),
+            "fork".to_string(),
+            "extra".to_string(),
+        ])
+        .unwrap();
+        assert!(matches!(tag, Tag::Generic(TagKind::E, ..)));
+        assert_eq!(tag.as_event_id(), Some(event_id));
+        assert_eq!(tag.as_public_key(), None);
+
+        let tag = Tag::parse(vec![
+            "p".to_string(),
+            public_key.to_string(),
+            String::new(),
+            String::new(),
+            "extra".to_string(),
+        ])
+        .unwrap();
+        assert!(matches!(tag, Tag::Generic(TagKind::P, ..)));
+        assert_eq!(tag.as_public_key(), Some(public_key));
+
+        let tag = Tag::parse(vec!["e", "not-an-event-id"]).unwrap();
+        assert_eq!(tag.as_event_id(), None);
+        assert_eq!(tag.content(), Some(String::from("not-an-event-id")));
+
+        assert_eq!(Tag::parse(vec!["-"]).unwrap().content(), None);
+    }
 }
 
 #[cfg(bench)]
@@ -0,0 +1,124 @@
+// Copyright (c) 2022-2023 Yuki Kishimoto
+// Copyright (c) 2023-2024 Rust Nostr Developers
+// Distributed under the MIT software license
+
+//! Event template
+//!
+//! Event without `id`, `pubkey` and `sig`, as exchanged with NIP07 and NIP46 signers.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use bitcoin::secp256k1::XOnlyPublicKey;
+
+use super::unsigned;
+use crate::{EventBuilder, EventId, JsonUtil, Kind, Tag, Timestamp, UnsignedEvent};
+
+/// [`EventTemplate`] struct
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct EventTemplate {
+    /// Timestamp (seconds)
+    pub created_at: Timestamp,
+    /// Kind
+    pub kind: Kind,
+    /// Vector of [`Tag`]
+    pub tags: Vec<Tag>,
+    /// Content
+    pub content: String,
+}
+
+impl EventTemplate {
+    /// New [`EventTemplate`]
+    pub fn new<S, I>(created_at: Timestamp, kind: Kind, content: S, tags: I) -> Self
+    where
+        S: Into<String>,
+        I: IntoIterator<Item = Tag>,
+    {
+        Self {
+            created_at,
+            kind,
+            tags: tags.into_iter().collect(),
+            content: content.into(),
+        }
+    }
+
+    /// Compose [`UnsignedEvent`] for `pubkey`
+    pub fn to_unsigned_event(self, pubkey: XOnlyPublicKey) -> UnsignedEvent {
+        let id = EventId::new(
+            &pubkey,
+            self.created_at,
+            &self.kind,
+            &self.tags,
+            &self.content,
+        );
+        UnsignedEvent {
+            id,
+            pubkey,
+            created_at: self.created_at,
+            kind: self.kind,
+            tags: self.tags,
+            content: self.content,
+        }
+    }
+}
+
+impl From<UnsignedEvent> for EventTemplate {
+    fn from(unsigned: UnsignedEvent) -> Self {
+        Self {
+            created_at: unsigned.created_at,
+            kind: unsigned.kind,
+            tags: unsigned.tags,
+            content: unsigned.content,
+        }
+    }
+}
+
+impl From<EventTemplate> for EventBuilder {
+    fn from(template: EventTemplate) -> Self {
+        EventBuilder::new(template.kind, template.content, template.tags)
+            .custom_created_at(template.created_at)
+    }
+}
+
+impl JsonUtil for EventTemplate {
+    type Err = unsigned::Error;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "std")]
+    use crate::Keys;
+
+    #[test]
+    fn test_event_template_json() {
+        let json = r#"{"kind":1,"content":"Hello","tags":[["t","nostr"]],"created_at":1700000000}"#;
+        let template = EventTemplate::from_json(json).unwrap();
+        assert_eq!(template.kind, Kind::TextNote);
+        assert_eq!(template.created_at, Timestamp::from(1700000000));
+        assert_eq!(template.tags, vec![Tag::Hashtag(String::from("nostr"))]);
+
+        // No `id`, `pubkey` or `sig`
+        let value: serde_json::Value = serde_json::from_str(&template.as_json()).unwrap();
+        let mut fields: Vec<&String> = value.as_object().unwrap().keys().collect();
+        fields.sort();
+        assert_eq!(fields, vec!["content", "created_at", "kind", "tags"]);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_event_template_conversions() {
+        let keys = Keys::generate();
+        let template = EventTemplate::new(Timestamp::from(1700000000), Kind::TextNote, "Hello", []);
+
+        let unsigned: UnsignedEvent = template.clone().to_unsigned_event(keys.public_key());
+        assert_eq!(
+            unsigned,
+            EventBuilder::from(template.clone()).to_unsigned_event(keys.public_key())
+        );
+        assert_eq!(EventTemplate::from(unsigned.clone()), template);
+
+        let event = unsigned.sign(&keys).unwrap();
+        assert!(event.verify().is_ok());
+    }
+}
@@ -87,6 +87,47 @@ pub struct UnsignedEvent {
 }
 
 impl UnsignedEvent {
+    /// Add tags and recompute the [`EventId`]
+    pub fn add_tags<I>(mut self, tags: I) -> Self
+    where
+        I: IntoIterator<Item = Tag>,
+    {
+        self.tags.extend(tags);
+        self.recompute_id()
+    }
+
+    /// Remove the tags matching the predicate and recompute the [`EventId`]
+    pub fn remove_tags<F>(mut self, mut predicate: F) -> Self
+    where
+        F: FnMut(&Tag) -> bool,
+    {
+        self.tags.retain(|t| !predicate(t));
+        self.recompute_id()
+    }
+
+    /// Replace content and recompute the [`EventId`]
+    pub fn replace_content<S>(self, content: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self {
+            content: content.into(),
+            ..self
+        }
+        .recompute_id()
+    }
+
+    fn recompute_id(self) -> Self {
+        let id: EventId = EventId::new(
+            &self.pubkey,
+            self.created_at,
+            &self.kind,
+            &self.tags,
+            &self.content,
+        );
+        Self { id, ..self }
+    }
+
     /// Sign an [`UnsignedEvent`]
     #[cfg(feature = "std")]
     pub fn sign(self, keys: &Keys) -> Result<Event, Error> {
@@ -148,3 +189,29 @@ impl UnsignedEvent {
 impl JsonUtil for UnsignedEvent {
     type Err = Error;
 }
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::{EventBuilder, TagKind};
+
+    #[test]
+    fn test_edit_unsigned_event() {
+        let keys = Keys::generate();
+        let unsigned = EventBuilder::text_note("Hello", [Tag::Hashtag(String::from("nostr"))])
+            .to_unsigned_event(keys.public_key());
+
+        let edited = unsigned
+            .clone()
+            .add_tags([Tag::Alt(String::from("Text note"))])
+            .remove_tags(|t| t.kind() == TagKind::T)
+            .replace_content("Hello world");
+        assert_ne!(edited.id, unsigned.id);
+        assert_eq!(edited.tags, vec![Tag::Alt(String::from("Text note"))]);
+        assert_eq!(edited.content, "Hello world");
+
+        // Signature is valid for the new ID
+        let event: Event = edited.sign(&keys).unwrap();
+        event.verify().unwrap();
+    }
+}
@@ -0,0 +1,258 @@
+// Copyright (c) 2022-2023 Yuki Kishimoto
+// Copyright (c) 2023-2024 Rust Nostr Developers
+// Distributed under the MIT software license
+
+//! Tag values validation
+//!
+//! Shape checks for relay URLs, hex ids/public keys and coordinates,
+//! to reject malformed events with a precise reason.
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+use core::str::FromStr;
+
+use url_fork::Url;
+
+use super::tag::{Tag, TagKind};
+use crate::Kind;
+
+/// Tag value validation error
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidationError {
+    /// Tag without kind
+    EmptyTag,
+    /// Missing tag value
+    MissingValue(TagKind),
+    /// Not a `ws://` or `wss://` URL with an host
+    InvalidRelayUrl(String),
+    /// Not a 64 chars lowercase hex string
+    InvalidHex(String),
+    /// Not a `<kind>:<pubkey>:<identifier>` coordinate
+    InvalidCoordinate(String),
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ValidationError {}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::EmptyTag => write!(f, "Empty tag"),
+            Self::MissingValue(kind) => write!(f, "Missing value for `{kind}` tag"),
+            Self::InvalidRelayUrl(url) => write!(f, "Invalid relay URL: {url}"),
+            Self::InvalidHex(value) => write!(f, "Invalid 64-chars hex value: {value}"),
+            Self::InvalidCoordinate(value) => write!(f, "Invalid coordinate: {value}"),
+        }
+    }
+}
+
+/// Issue found in a tag
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TagIssue {
+    /// Tag position
+    pub index: usize,
+    /// Reason
+    pub error: ValidationError,
+}
+
+impl fmt::Display for TagIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "tag {}: {}", self.index, self.error)
+    }
+}
+
+/// Tags validation report
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ValidationReport {
+    issues: Vec<TagIssue>,
+}
+
+impl ValidationReport {
+    /// Validate raw tags
+    pub fn from_tags<I, T, S>(tags: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: AsRef<[S]>,
+        S: AsRef<str>,
+    {
+        let issues = tags
+            .into_iter()
+            .enumerate()
+            .filter_map(|(index, tag)| {
+                validate_tag(tag.as_ref())
+                    .err()
+                    .map(|error| TagIssue { index, error })
+            })
+            .collect();
+        Self { issues }
+    }
+
+    /// Check if no issues have been found
+    pub fn is_valid(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// Get issues
+    pub fn issues(&self) -> &[TagIssue] {
+        &self.issues
+    }
+
+    /// Convert into [`Result`], returning the first issue
+    pub fn into_result(self) -> Result<(), TagIssue> {
+        match self.issues.into_iter().next() {
+            Some(issue) => Err(issue),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<'a> From<&'a [Tag]> for ValidationReport {
+    fn from(tags: &'a [Tag]) -> Self {
+        Self::from_tags(tags.iter().map(|t| t.as_vec()))
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.issues.is_empty() {
+            return write!(f, "valid");
+        }
+        for (i, issue) in self.issues.iter().enumerate() {
+            if i > 0 {
+                write!(f, "; ")?;
+            }
+            write!(f, "{issue}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Check if `url` is a `ws://` or `wss://` URL with an host
+pub fn is_relay_url(url: &str) -> bool {
+    match Url::parse(url) {
+        Ok(url) => matches!(url.scheme(), "ws" | "wss") && url.has_host(),
+        Err(_) => false,
+    }
+}
+
+/// Check if `value` is a 64 chars lowercase hex string (event id or public key)
+pub fn is_hex64(value: &str) -> bool {
+    value.len() == 64
+        && value
+            .bytes()
+            .all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b))
+}
+
+/// Check if `value` is a `<kind>:<pubkey>:<identifier>` coordinate
+pub fn is_coordinate(value: &str) -> bool {
+    let mut kpi = value.splitn(3, ':');
+    match (kpi.next(), kpi.next(), kpi.next()) {
+        (Some(kind), Some(pubkey), Some(_)) => Kind::from_str(kind).is_ok() && is_hex64(pubkey),
+        _ => false,
+    }
+}
+
+/// Validate raw tag values
+///
+/// Checks `e`, `p` and `q` values (64-chars hex), `a` values (coordinate),
+/// `relay` values and `r` values with a websocket scheme (relay URL).
+pub fn validate_tag<S>(tag: &[S]) -> Result<(), ValidationError>
+where
+    S: AsRef<str>,
+{
+    let kind: TagKind = match tag.first() {
+        Some(kind) => TagKind::from(kind.as_ref()),
+        None => return Err(ValidationError::EmptyTag),
+    };
+    let value: Option<&str> = tag.get(1).map(|v| v.as_ref());
+
+    match kind {
+        TagKind::E | TagKind::P | TagKind::UpperP | TagKind::Q => {
+            let value = value.ok_or(ValidationError::MissingValue(kind))?;
+            if !is_hex64(value) {
+                return Err(ValidationError::InvalidHex(value.to_string()));
+            }
+        }
+        TagKind::A => {
+            let value = value.ok_or(ValidationError::MissingValue(kind))?;
+            if !is_coordinate(value) {
+                return Err(ValidationError::InvalidCoordinate(value.to_string()));
+            }
+        }
+        TagKind::Relay => {
+            let value = value.ok_or(ValidationError::MissingValue(kind))?;
+            if !is_relay_url(value) {
+                return Err(ValidationError::InvalidRelayUrl(value.to_string()));
+            }
+        }
+        TagKind::R => {
+            let value = value.ok_or(ValidationError::MissingValue(kind))?;
+            // `r` tags are also used for generic references
+            if (value.starts_with("ws://") || value.starts_with("wss://")) && !is_relay_url(value) {
+                return Err(ValidationError::InvalidRelayUrl(value.to_string()));
+            }
+        }
+        _ => (),
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PUBKEY: &str = "aa4fc8665f5696e33db7e1a572e3b0f5b3d615837b0f362dcb1c8068b098c7b4";
+
+    #[test]
+    fn test_validators() {
+        assert!(is_relay_url("wss://relay.damus.io"));
+        assert!(is_relay_url("ws://127.0.0.1:7777"));
+        assert!(!is_relay_url("https://relay.damus.io"));
+        assert!(!is_relay_url("wss://"));
+
+        assert!(is_hex64(PUBKEY));
+        assert!(!is_hex64(&PUBKEY.to_uppercase()));
+        assert!(!is_hex64(&PUBKEY[1..]));
+
+        assert!(is_coordinate(&format!("30023:{PUBKEY}:my:article")));
+        assert!(is_coordinate(&format!("10000:{PUBKEY}:")));
+        assert!(!is_coordinate(&format!("article:{PUBKEY}:id")));
+        assert!(!is_coordinate("30023:abc:id"));
+    }
+
+    #[test]
+    fn test_validation_report() {
+        let tags = vec![
+            vec!["p", PUBKEY],
+            vec!["e", "abc"],
+            vec!["r", "https://example.com"],
+            vec!["r", "wss://"],
+            vec!["relay"],
+            vec!["t", "nostr"],
+        ];
+        let report = ValidationReport::from_tags(&tags);
+        assert!(!report.is_valid());
+        assert_eq!(
+            report.issues(),
+            &[
+                TagIssue {
+                    index: 1,
+                    error: ValidationError::InvalidHex(String::from("abc"))
+                },
+                TagIssue {
+                    index: 3,
+                    error: ValidationError::InvalidRelayUrl(String::from("wss://"))
+                },
+                TagIssue {
+                    index: 4,
+                    error: ValidationError::MissingValue(TagKind::Relay)
+                },
+            ]
+        );
+
+        let valid: Vec<Vec<&str>> = vec![vec!["p", PUBKEY], vec!["relay", "wss://relay.damus.io"]];
+        assert!(ValidationReport::from_tags(&valid).into_result().is_ok());
+    }
+}
@@ -19,6 +19,7 @@ pub use bitcoin::secp256k1::{
     self, KeyPair, Message, PublicKey, Secp256k1, SecretKey, Signing, XOnlyPublicKey,
 };
 
+pub mod rotation;
 #[cfg(feature = "std")]
 pub mod vanity;
 
@@ -36,6 +37,8 @@ pub enum Error {
     InvalidPublicKey,
     /// Secret key missing
     SkMissing,
+    /// Watch-only keys (public key only): can't sign
+    WatchOnly,
     /// Unsupported char
     InvalidChar(char),
     /// Secp256k1 error
@@ -51,6 +54,7 @@ impl fmt::Display for Error {
             Self::InvalidSecretKey => write!(f, "Invalid secret key"),
             Self::InvalidPublicKey => write!(f, "Invalid public key"),
             Self::SkMissing => write!(f, "Secret key missing"),
+            Self::WatchOnly => write!(f, "Watch-only keys: can't sign"),
             Self::InvalidChar(c) => write!(f, "Unsupported char: {c}"),
             Self::Secp256k1(e) => write!(f, "Secp256k1: {e}"),
         }
@@ -82,13 +86,31 @@ pub trait FromPkStr: Sized {
 }
 
 /// Keys
-#[derive(Debug, Clone, PartialEq, Eq)]
+///
+/// The secret key is erased on drop and never printed by [`Debug`](fmt::Debug).
+#[derive(Clone, PartialEq, Eq)]
 pub struct Keys {
     public_key: XOnlyPublicKey,
     key_pair: Option<KeyPair>,
     secret_key: Option<SecretKey>,
 }
 
+impl fmt::Debug for Keys {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Keys")
+            .field("public_key", &self.public_key)
+            .field(
+                "secret_key",
+                &if self.secret_key.is_some() {
+                    "<redacted>"
+                } else {
+                    "None"
+                },
+            )
+            .finish()
+    }
+}
+
 #[cfg(feature = "std")]
 impl Keys {
     /// Initialize from secret key.
@@ -146,6 +168,8 @@ impl Keys {
     }
 
     /// Initialize with public key only (no secret key).
+    ///
+    /// The [`Keys`] are watch-only: signing return [`Error::WatchOnly`].
     pub fn from_public_key(public_key: XOnlyPublicKey) -> Self {
         Self {
             public_key,
@@ -154,6 +178,18 @@ impl Keys {
         }
     }
 
+    /// Initialize watch-only [`Keys`] (alias of [`Keys::from_public_key`])
+    ///
+    /// Useful for read-only clients, that need only the public key to query events.
+    pub fn watch_only(public_key: XOnlyPublicKey) -> Self {
+        Self::from_public_key(public_key)
+    }
+
+    /// Check if [`Keys`] are watch-only (no secret key)
+    pub fn is_watch_only(&self) -> bool {
+        self.secret_key.is_none()
+    }
+
     /// Generate random [`Keys`] with custom [`Rng`]
     pub fn generate_with_ctx<C, R>(secp: &Secp256k1<C>, rng: &mut R) -> Self
     where
@@ -228,6 +264,10 @@ impl Keys {
         C: Signing,
         R: Rng + CryptoRng,
     {
+        if self.is_watch_only() {
+            return Err(Error::WatchOnly);
+        }
+
         let keypair: &KeyPair = &self.key_pair(secp)?;
         Ok(secp.sign_schnorr_with_rng(message, keypair, rng))
     }
@@ -272,6 +312,47 @@ impl Drop for Keys {
             sk.non_secure_erase();
             tracing::trace!("Secret Key dropped.");
         }
+        if let Some(key_pair) = self.key_pair.as_mut() {
+            key_pair.non_secure_erase();
+        }
         self.secret_key = None;
+        self.key_pair = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "std")]
+    use crate::EventBuilder;
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_watch_only() {
+        let keys = Keys::generate();
+        assert!(!keys.is_watch_only());
+
+        let watch_only = Keys::watch_only(keys.public_key());
+        assert!(watch_only.is_watch_only());
+        assert_eq!(watch_only.public_key(), keys.public_key());
+        assert_eq!(watch_only.secret_key(), Err(Error::SkMissing));
+
+        let message = Message::from_slice(&[0xab; 32]).unwrap();
+        assert_eq!(watch_only.sign_schnorr(&message), Err(Error::WatchOnly));
+        assert!(EventBuilder::text_note("hello", [])
+            .to_event(&watch_only)
+            .is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_redacted_debug() {
+        let secret_key =
+            SecretKey::from_str("6b911fd37cdf5c81d4c0adb1ab7fa822ed253ab0ad9aa18d77257c88b29b718e")
+                .unwrap();
+        let keys = Keys::new(secret_key);
+        let debug: String = format!("{keys:?}");
+        assert!(debug.contains("<redacted>"));
+        assert!(!debug.contains(&secret_key.display_secret().to_string()));
     }
 }
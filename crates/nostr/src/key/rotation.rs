@@ -0,0 +1,398 @@
+// Copyright (c) 2022-2023 Yuki Kishimoto
+// Copyright (c) 2023-2024 Rust Nostr Developers
+// Distributed under the MIT software license
+
+//! Key rotation
+//!
+//! Helpers to migrate an identity from an old key to a new one:
+//! the old key publishes a final note and a metadata pointing to the new key,
+//! both including a `rotation` tag that carries a NIP26 delegation from the old key to the new one.
+//!
+//! `["rotation", "<new pubkey>", "<conditions>", "<delegation signature>"]`
+//!
+//! During the transition, the new key can sign events with a NIP26 `delegation` tag
+//! (see [`KeyRotation::delegation_tag`]), so clients can link them to the old identity.
+
+use alloc::string::{String, ToString};
+use core::fmt;
+use core::str::FromStr;
+
+#[cfg(feature = "std")]
+use bitcoin::secp256k1::rand;
+use bitcoin::secp256k1::rand::{CryptoRng, Rng};
+use bitcoin::secp256k1::schnorr::Signature;
+use bitcoin::secp256k1::{Secp256k1, Signing, Verification, XOnlyPublicKey};
+
+use super::Keys;
+use crate::event::{self, Event, EventBuilder, Kind, Tag, TagKind};
+use crate::nips::nip26::{self, Conditions, DelegationTag, EventProperties};
+use crate::types::Metadata;
+use crate::util::JsonUtil;
+#[cfg(feature = "std")]
+use crate::SECP256K1;
+
+/// Rotation tag name
+pub const ROTATION_TAG: &str = "rotation";
+/// Metadata field pointing to the new public key
+pub const ROTATION_METADATA_FIELD: &str = "rotated_to";
+
+/// Key rotation error
+#[derive(Debug, PartialEq, Eq)]
+pub enum Error {
+    /// NIP26 error
+    NIP26(nip26::Error),
+    /// Event error
+    Event(String),
+    /// Rotation tag not found
+    RotationTagNotFound,
+    /// Invalid rotation tag
+    InvalidRotationTag,
+    /// Event not authored by the old key
+    WrongAuthor,
+    /// Event not signed by the new key
+    NotSignedByNewKey,
+    /// Metadata doesn't point to the new key
+    MetadataMismatch,
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NIP26(e) => write!(f, "NIP26: {e}"),
+            Self::Event(e) => write!(f, "Event: {e}"),
+            Self::RotationTagNotFound => write!(f, "Rotation tag not found"),
+            Self::InvalidRotationTag => write!(f, "Invalid rotation tag"),
+            Self::WrongAuthor => write!(f, "Event not authored by the old key"),
+            Self::NotSignedByNewKey => write!(f, "Event not signed by the new key"),
+            Self::MetadataMismatch => write!(f, "Metadata doesn't point to the new key"),
+        }
+    }
+}
+
+impl From<nip26::Error> for Error {
+    fn from(e: nip26::Error) -> Self {
+        Self::NIP26(e)
+    }
+}
+
+impl From<event::Error> for Error {
+    fn from(e: event::Error) -> Self {
+        Self::Event(e.to_string())
+    }
+}
+
+/// Key rotation claim (old key -> new key)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyRotation {
+    old_public_key: XOnlyPublicKey,
+    new_public_key: XOnlyPublicKey,
+    conditions: Conditions,
+    signature: Signature,
+}
+
+impl KeyRotation {
+    /// Compose new key rotation, signing a NIP26 delegation from `old_keys` to `new_public_key`
+    ///
+    /// The `conditions` should limit the transition period (ex. `created_at<...`).
+    #[cfg(feature = "std")]
+    pub fn new(
+        old_keys: &Keys,
+        new_public_key: XOnlyPublicKey,
+        conditions: Conditions,
+    ) -> Result<Self, Error> {
+        Self::new_with_ctx(
+            &SECP256K1,
+            &mut rand::thread_rng(),
+            old_keys,
+            new_public_key,
+            conditions,
+        )
+    }
+
+    /// Compose new key rotation, signing a NIP26 delegation from `old_keys` to `new_public_key`
+    pub fn new_with_ctx<C, R>(
+        secp: &Secp256k1<C>,
+        rng: &mut R,
+        old_keys: &Keys,
+        new_public_key: XOnlyPublicKey,
+        conditions: Conditions,
+    ) -> Result<Self, Error>
+    where
+        C: Signing,
+        R: Rng + CryptoRng,
+    {
+        let delegation: DelegationTag =
+            DelegationTag::new_with_ctx(secp, rng, old_keys, new_public_key, conditions)?;
+        Ok(Self {
+            old_public_key: delegation.delegator_pubkey(),
+            new_public_key,
+            conditions: delegation.conditions(),
+            signature: delegation.signature(),
+        })
+    }
+
+    /// Get old public key
+    pub fn old_public_key(&self) -> XOnlyPublicKey {
+        self.old_public_key
+    }
+
+    /// Get new public key
+    pub fn new_public_key(&self) -> XOnlyPublicKey {
+        self.new_public_key
+    }
+
+    /// Get delegation conditions
+    pub fn conditions(&self) -> Conditions {
+        self.conditions.clone()
+    }
+
+    /// Get `rotation` tag
+    pub fn rotation_tag(&self) -> Tag {
+        Tag::Generic(
+            TagKind::Custom(String::from(ROTATION_TAG)),
+            vec![
+                self.new_public_key.to_string(),
+                self.conditions.to_string(),
+                self.signature.to_string(),
+            ],
+        )
+    }
+
+    /// Get NIP26 `delegation` tag, to add to the events signed by the new key during the transition
+    pub fn delegation_tag(&self) -> Tag {
+        Tag::Delegation {
+            delegator: self.old_public_key,
+            conditions: self.conditions.clone(),
+            sig: self.signature,
+        }
+    }
+
+    /// Final note to sign with the old key
+    pub fn final_note<S>(&self, content: S) -> EventBuilder
+    where
+        S: Into<String>,
+    {
+        EventBuilder::new(
+            Kind::TextNote,
+            content,
+            [Tag::public_key(self.new_public_key), self.rotation_tag()],
+        )
+    }
+
+    /// Metadata to sign with the old key, pointing to the new key
+    pub fn metadata(&self, metadata: Metadata) -> EventBuilder {
+        let metadata: Metadata =
+            metadata.custom_field(ROTATION_METADATA_FIELD, self.new_public_key.to_string());
+        EventBuilder::new(Kind::Metadata, metadata.as_json(), [self.rotation_tag()])
+    }
+
+    /// Parse key rotation claim from an event signed by the old key (final note or metadata)
+    ///
+    /// **This method doesn't verify the claim**: use [`KeyRotation::verify`].
+    pub fn from_event(event: &Event) -> Result<Self, Error> {
+        let values = event
+            .iter_tags()
+            .find_map(|t| match t {
+                Tag::Generic(TagKind::Custom(kind), values) if kind == ROTATION_TAG => Some(values),
+                _ => None,
+            })
+            .ok_or(Error::RotationTagNotFound)?;
+
+        if values.len() != 3 {
+            return Err(Error::InvalidRotationTag);
+        }
+
+        Ok(Self {
+            old_public_key: event.author(),
+            new_public_key: XOnlyPublicKey::from_str(&values[0])
+                .map_err(|_| Error::InvalidRotationTag)?,
+            conditions: Conditions::from_str(&values[1])?,
+            signature: Signature::from_str(&values[2]).map_err(|_| Error::InvalidRotationTag)?,
+        })
+    }
+
+    /// Verify key rotation claim
+    ///
+    /// Check that the `event` is signed by the old key, that includes this rotation
+    /// and that the delegation signature from the old key to the new one is valid.
+    /// If the event is a metadata, check also that it points to the new key.
+    #[cfg(feature = "std")]
+    pub fn verify(&self, event: &Event) -> Result<(), Error> {
+        self.verify_with_ctx(&SECP256K1, event)
+    }
+
+    /// Verify key rotation claim
+    ///
+    /// Check that the `event` is signed by the old key, that includes this rotation
+    /// and that the delegation signature from the old key to the new one is valid.
+    /// If the event is a metadata, check also that it points to the new key.
+    pub fn verify_with_ctx<C>(&self, secp: &Secp256k1<C>, event: &Event) -> Result<(), Error>
+    where
+        C: Verification,
+    {
+        if event.author_ref() != &self.old_public_key {
+            return Err(Error::WrongAuthor);
+        }
+
+        event.verify_with_ctx(secp)?;
+
+        if &Self::from_event(event)? != self {
+            return Err(Error::InvalidRotationTag);
+        }
+
+        nip26::verify_delegation_signature_with_ctx(
+            secp,
+            self.old_public_key,
+            self.signature,
+            self.new_public_key,
+            self.conditions.clone(),
+        )?;
+
+        if event.kind() == Kind::Metadata {
+            let metadata: Metadata =
+                Metadata::from_json(event.content()).map_err(|_| Error::MetadataMismatch)?;
+            let new_public_key: String = self.new_public_key.to_string();
+            match metadata.custom.get(ROTATION_METADATA_FIELD) {
+                Some(value) if value.as_str() == Some(new_public_key.as_str()) => (),
+                _ => return Err(Error::MetadataMismatch),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Verify that an event was signed by the new key during the transition period
+    #[cfg(feature = "std")]
+    pub fn verify_transition_event(&self, event: &Event) -> Result<(), Error> {
+        self.verify_transition_event_with_ctx(&SECP256K1, event)
+    }
+
+    /// Verify that an event was signed by the new key during the transition period
+    pub fn verify_transition_event_with_ctx<C>(
+        &self,
+        secp: &Secp256k1<C>,
+        event: &Event,
+    ) -> Result<(), Error>
+    where
+        C: Verification,
+    {
+        if event.author_ref() != &self.new_public_key {
+            return Err(Error::NotSignedByNewKey);
+        }
+
+        event.verify_with_ctx(secp)?;
+
+        let delegation = DelegationTag::try_from(self.delegation_tag().as_vec())?;
+        delegation.validate_with_ctx(
+            secp,
+            self.new_public_key,
+            &EventProperties::from_event(event),
+        )?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "std")]
+    use crate::nips::nip26::Condition;
+    #[cfg(feature = "std")]
+    use crate::Timestamp;
+
+    #[cfg(feature = "std")]
+    fn transition_conditions() -> Conditions {
+        let now = Timestamp::now();
+        let mut conditions = Conditions::new();
+        conditions.add(Condition::CreatedAfter(now.as_u64() - 60));
+        conditions.add(Condition::CreatedBefore(now.as_u64() + 86400));
+        conditions
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_key_rotation() {
+        let old_keys = Keys::generate();
+        let new_keys = Keys::generate();
+
+        let rotation =
+            KeyRotation::new(&old_keys, new_keys.public_key(), transition_conditions()).unwrap();
+
+        // Final note
+        let note = rotation
+            .final_note("I moved to a new key")
+            .to_event(&old_keys)
+            .unwrap();
+        let note = Event::from_json(note.as_json()).unwrap();
+        let parsed = KeyRotation::from_event(&note).unwrap();
+        assert_eq!(parsed, rotation);
+        assert!(parsed.verify(&note).is_ok());
+
+        // Metadata
+        let metadata = rotation
+            .metadata(Metadata::new().name("old"))
+            .to_event(&old_keys)
+            .unwrap();
+        assert!(KeyRotation::from_event(&metadata)
+            .unwrap()
+            .verify(&metadata)
+            .is_ok());
+
+        // Transition event
+        let event = EventBuilder::text_note("Hello from the new key", [rotation.delegation_tag()])
+            .to_event(&new_keys)
+            .unwrap();
+        assert!(rotation.verify_transition_event(&event).is_ok());
+        assert_eq!(
+            rotation.verify_transition_event(&note).unwrap_err(),
+            Error::NotSignedByNewKey
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_forged_key_rotation() {
+        let old_keys = Keys::generate();
+        let new_keys = Keys::generate();
+        let attacker_keys = Keys::generate();
+
+        // Attacker claims the rotation of the old key
+        let rotation =
+            KeyRotation::new(&old_keys, new_keys.public_key(), transition_conditions()).unwrap();
+        let note = rotation
+            .final_note("I moved to a new key")
+            .to_event(&attacker_keys)
+            .unwrap();
+        assert_eq!(rotation.verify(&note).unwrap_err(), Error::WrongAuthor);
+
+        // Attacker signs its own claim, with a delegation signed by its key
+        let forged = KeyRotation::new(
+            &attacker_keys,
+            new_keys.public_key(),
+            transition_conditions(),
+        )
+        .unwrap();
+        let note = EventBuilder::text_note("I moved to a new key", [forged.rotation_tag()])
+            .to_event(&old_keys)
+            .unwrap();
+        let parsed = KeyRotation::from_event(&note).unwrap();
+        assert!(parsed.verify(&note).is_err());
+
+        // Metadata not pointing to the new key
+        let metadata = EventBuilder::new(
+            Kind::Metadata,
+            Metadata::new().name("old").as_json(),
+            [rotation.rotation_tag()],
+        )
+        .to_event(&old_keys)
+        .unwrap();
+        assert_eq!(
+            rotation.verify(&metadata).unwrap_err(),
+            Error::MetadataMismatch
+        );
+    }
+}
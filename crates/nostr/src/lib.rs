@@ -33,9 +33,10 @@ extern crate serde;
 
 #[cfg(feature = "nip06")]
 pub use bip39;
-pub use bitcoin::{bech32, hashes, secp256k1};
+pub use bitcoin::{self, bech32, hashes, secp256k1};
+pub use negentropy;
+pub use serde_json;
 pub use url_fork::{self as url, Url};
-pub use {bitcoin, negentropy, serde_json};
 
 pub mod event;
 pub mod key;
@@ -50,7 +51,8 @@ pub use self::event::tag::{
     TagKind,
 };
 pub use self::event::{
-    Event, EventBuilder, EventId, Kind, MissingPartialEvent, PartialEvent, UnsignedEvent,
+    Event, EventBuilder, EventId, EventTemplate, Kind, KindCategory, MissingPartialEvent,
+    PartialEvent, RawEvent, UnsignedEvent, ValidationReport,
 };
 pub use self::key::Keys;
 pub use self::message::{
@@ -157,6 +157,22 @@ impl ClientMessage {
         })
     }
 
+    /// Create `NEG-MSG` message
+    pub fn neg_msg<S>(subscription_id: SubscriptionId, message: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self::NegMsg {
+            subscription_id,
+            message: message.into(),
+        }
+    }
+
+    /// Create `NEG-CLOSE` message
+    pub fn neg_close(subscription_id: SubscriptionId) -> Self {
+        Self::NegClose { subscription_id }
+    }
+
     /// Check if is an `EVENT` message
     pub fn is_event(&self) -> bool {
         matches!(self, ClientMessage::Event(_))
@@ -172,6 +188,16 @@ impl ClientMessage {
         matches!(self, ClientMessage::Close(_))
     }
 
+    /// Check if is a negentropy message (`NEG-OPEN`, `NEG-MSG` or `NEG-CLOSE`)
+    pub fn is_negentropy(&self) -> bool {
+        matches!(
+            self,
+            ClientMessage::NegOpen { .. }
+                | ClientMessage::NegMsg { .. }
+                | ClientMessage::NegClose { .. }
+        )
+    }
+
     /// Serialize as [`Value`]
     pub fn as_value(&self) -> Value {
         match self {
@@ -424,6 +450,47 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_client_message_count() {
+        let filters = vec![Filter::new().kind(Kind::Reaction), Filter::new().limit(10)];
+
+        let client_count = ClientMessage::count(SubscriptionId::new("count"), filters);
+        let json = r#"["COUNT","count",{"kinds":[7]},{"limit":10}]"#;
+        assert_eq!(client_count.as_json(), json);
+        assert_eq!(ClientMessage::from_json(json).unwrap(), client_count);
+
+        assert!(ClientMessage::from_json(r#"["COUNT"]"#).is_err());
+    }
+
+    #[test]
+    fn test_client_message_negentropy() {
+        let json = r#"["NEG-OPEN","neg",{"kinds":[1]},16,"6181"]"#;
+        let msg = ClientMessage::NegOpen {
+            subscription_id: SubscriptionId::new("neg"),
+            filter: Box::new(Filter::new().kind(Kind::TextNote)),
+            id_size: 16,
+            initial_message: String::from("6181"),
+        };
+        assert_eq!(msg.as_json(), json);
+        assert_eq!(ClientMessage::from_json(json).unwrap(), msg);
+        assert!(msg.is_negentropy());
+
+        let json = r#"["NEG-MSG","neg","6181"]"#;
+        let msg = ClientMessage::neg_msg(SubscriptionId::new("neg"), "6181");
+        assert_eq!(msg.as_json(), json);
+        assert_eq!(ClientMessage::from_json(json).unwrap(), msg);
+
+        let json = r#"["NEG-CLOSE","neg"]"#;
+        let msg = ClientMessage::neg_close(SubscriptionId::new("neg"));
+        assert_eq!(msg.as_json(), json);
+        assert_eq!(ClientMessage::from_json(json).unwrap(), msg);
+
+        assert!(ClientMessage::from_json(r#"["NEG-OPEN","neg",{}]"#).is_err());
+        assert!(ClientMessage::from_json(r#"["NEG-MSG","neg"]"#).is_err());
+        assert!(ClientMessage::from_json(r#"["NEG-CLOSE"]"#).is_err());
+        assert!(!ClientMessage::close(SubscriptionId::new("neg")).is_negentropy());
+    }
+
     #[test]
     fn test_negative_timestamp() {
         let req = json!([
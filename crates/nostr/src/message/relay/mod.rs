@@ -268,7 +268,7 @@ impl RelayMessage {
         Self::auth(challenge)
     }
 
-    /// Create  `EVENT` message
+    /// Create `COUNT` message
     pub fn count(subscription_id: SubscriptionId, count: usize) -> Self {
         Self::Count {
             subscription_id,
@@ -276,12 +276,34 @@ impl RelayMessage {
         }
     }
 
-    /// Create new `EVENT` message
+    /// Create new `COUNT` message
     #[deprecated(since = "0.27.0", note = "Use `count` instead")]
     pub fn new_count(subscription_id: SubscriptionId, count: usize) -> Self {
         Self::count(subscription_id, count)
     }
 
+    /// Create `NEG-MSG` message
+    pub fn neg_msg<S>(subscription_id: SubscriptionId, message: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self::NegMsg {
+            subscription_id,
+            message: message.into(),
+        }
+    }
+
+    /// Create `NEG-ERR` message
+    pub fn neg_err<C>(subscription_id: SubscriptionId, code: C) -> Self
+    where
+        C: Into<NegentropyErrorCode>,
+    {
+        Self::NegErr {
+            subscription_id,
+            code: code.into(),
+        }
+    }
+
     fn as_value(&self) -> Value {
         match self {
             Self::Event {
@@ -590,6 +612,64 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_handle_valid_count() {
+        let valid_count_msg = r#"["COUNT","sub1",{"count":42}]"#;
+        let handled_valid_count_msg = RelayMessage::count(SubscriptionId::new("sub1"), 42);
+
+        assert_eq!(
+            RelayMessage::from_json(valid_count_msg).unwrap(),
+            handled_valid_count_msg
+        );
+        assert_eq!(handled_valid_count_msg.as_json(), valid_count_msg);
+    }
+
+    #[test]
+    fn test_handle_invalid_count() {
+        // Missing count object
+        assert!(RelayMessage::from_json(r#"["COUNT","sub1"]"#).is_err());
+
+        // Missing `count` field
+        assert!(RelayMessage::from_json(r#"["COUNT","sub1",{"total":42}]"#).is_err());
+
+        // The count is not an integer
+        assert!(RelayMessage::from_json(r#"["COUNT","sub1",{"count":"42"}]"#).is_err())
+    }
+
+    #[test]
+    fn test_handle_negentropy() {
+        let json = r#"["NEG-MSG","neg","6181"]"#;
+        let msg = RelayMessage::neg_msg(SubscriptionId::new("neg"), "6181");
+        assert_eq!(RelayMessage::from_json(json).unwrap(), msg);
+        assert_eq!(msg.as_json(), json);
+
+        let json = r#"["NEG-ERR","neg","RESULTS_TOO_BIG"]"#;
+        let msg = RelayMessage::neg_err(SubscriptionId::new("neg"), "RESULTS_TOO_BIG");
+        assert_eq!(
+            msg,
+            RelayMessage::NegErr {
+                subscription_id: SubscriptionId::new("neg"),
+                code: NegentropyErrorCode::ResultsTooBig
+            }
+        );
+        assert_eq!(RelayMessage::from_json(json).unwrap(), msg);
+        assert_eq!(msg.as_json(), json);
+
+        let json = r#"["NEG-ERR","neg","blocked: too many queries"]"#;
+        let msg = RelayMessage::from_json(json).unwrap();
+        assert_eq!(
+            msg,
+            RelayMessage::neg_err(
+                SubscriptionId::new("neg"),
+                NegentropyErrorCode::Other(String::from("blocked: too many queries"))
+            )
+        );
+        assert_eq!(msg.as_json(), json);
+
+        assert!(RelayMessage::from_json(r#"["NEG-MSG","neg"]"#).is_err());
+        assert!(RelayMessage::from_json(r#"["NEG-ERR","neg"]"#).is_err());
+    }
+
     #[test]
     fn test_raw_relay_message() {
         pub const SAMPLE_EVENT: &'static str = r#"["EVENT", "random_string", {"id":"70b10f70c1318967eddf12527799411b1a9780ad9c43858f5e5fcd45486a13a5","pubkey":"379e863e8357163b5bce5d2688dc4f1dcc2d505222fb8d74db600f30535dfdfe","created_at":1612809991,"kind":1,"tags":[],"content":"test","sig":"273a9cd5d11455590f4359500bccb7a89428262b96b3ea87a756b770964472f8c3e87f5d5e64d8d2e859a71462a3f477b554565c4f2f326cb01dd7620db71502"}]"#;
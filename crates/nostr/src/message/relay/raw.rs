@@ -159,7 +159,7 @@ impl RawRelayMessage {
             }
         }
 
-        // Relay response format: ["EVENT", <subscription id>, <event JSON>]
+        // Relay response format: ["COUNT", <subscription id>, {"count": <integer>}]
         if v[0] == "COUNT" {
             if v_len >= 3 {
                 let map = v[2]
@@ -8,7 +8,9 @@
 #[cfg(not(feature = "std"))]
 use alloc::collections::{BTreeMap as AllocMap, BTreeSet as AllocSet};
 use alloc::string::{String, ToString};
+use alloc::vec::Vec;
 use core::fmt;
+use core::ops::{Bound, RangeBounds};
 use core::str::FromStr;
 #[cfg(feature = "std")]
 use std::collections::{HashMap as AllocMap, HashSet as AllocSet};
@@ -24,6 +26,7 @@ use serde::ser::{SerializeMap, Serializer};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
+use crate::nips::nip01::Coordinate;
 use crate::{EventId, JsonUtil, Kind, Timestamp};
 
 /// Alphabet Error
@@ -317,6 +320,12 @@ impl IntoGenericTagValue for &str {
     }
 }
 
+impl IntoGenericTagValue for &Coordinate {
+    fn into_generic_tag_value(self) -> GenericTagValue {
+        GenericTagValue::String(self.to_string())
+    }
+}
+
 /// Subscription filters
 #[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Filter {
@@ -486,6 +495,29 @@ impl Filter {
         self.remove_custom_tag(Alphabet::P, pubkeys)
     }
 
+    /// Add coordinate (`#a` tag)
+    ///
+    /// To get the event identified by the coordinate, convert it to [`Filter`] (`Filter::from(coordinate)`).
+    pub fn coordinate(self, coordinate: &Coordinate) -> Self {
+        self.custom_tag(Alphabet::A, vec![coordinate])
+    }
+
+    /// Add coordinates (`#a` tag)
+    pub fn coordinates<'a, I>(self, coordinates: I) -> Self
+    where
+        I: IntoIterator<Item = &'a Coordinate>,
+    {
+        self.custom_tag(Alphabet::A, coordinates)
+    }
+
+    /// Remove coordinates
+    pub fn remove_coordinates<'a, I>(self, coordinates: I) -> Self
+    where
+        I: IntoIterator<Item = &'a Coordinate>,
+    {
+        self.remove_custom_tag(Alphabet::A, coordinates)
+    }
+
     /// Add hashtag
     ///
     /// <https://github.com/nostr-protocol/nips/blob/master/12.md>
@@ -507,6 +539,21 @@ impl Filter {
         self.custom_tag(Alphabet::T, hashtags.into_iter().map(|s| s.into()))
     }
 
+    /// Add languages (`l` tag with ISO-639-1 codes)
+    ///
+    /// Match the `l` tags of the events. The languages detected from the content by the database
+    /// (`nostr-database` with the `language` feature) are matched only by the database indexes,
+    /// not by the relays.
+    ///
+    /// <https://github.com/nostr-protocol/nips/blob/master/32.md>
+    pub fn languages<I, S>(self, languages: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.custom_tag(Alphabet::L, languages.into_iter().map(|s| s.into()))
+    }
+
     /// Remove hashtags
     pub fn remove_hashtags<I, S>(self, hashtags: I) -> Self
     where
@@ -627,6 +674,32 @@ impl Filter {
         }
     }
 
+    /// Set `since` and `until` from a [`Timestamp`] range
+    ///
+    /// `since` and `until` are both inclusive, so excluded bounds are moved by one second
+    /// (i.e. `a..b` becomes `since = a` and `until = b - 1`).
+    /// Unbounded ends remove the corresponding field.
+    pub fn within<R>(self, range: R) -> Self
+    where
+        R: RangeBounds<Timestamp>,
+    {
+        let since: Option<Timestamp> = match range.start_bound() {
+            Bound::Included(t) => Some(*t),
+            Bound::Excluded(t) => Some(*t + 1u64),
+            Bound::Unbounded => None,
+        };
+        let until: Option<Timestamp> = match range.end_bound() {
+            Bound::Included(t) => Some(*t),
+            Bound::Excluded(t) => Some(*t - 1u64),
+            Bound::Unbounded => None,
+        };
+        Self {
+            since,
+            until,
+            ..self
+        }
+    }
+
     /// Add limit
     ///
     /// Maximum number of events to be returned in the initial query
@@ -682,6 +755,200 @@ impl Filter {
     pub fn is_empty(&self) -> bool {
         self == &Filter::default()
     }
+
+    /// Check if all the events matched by this [`Filter`] are matched also by `other`
+    ///
+    /// A [`Filter`] with `limit` is considered subset only of the same filter or of a filter without `limit`.
+    pub fn is_subset_of(&self, other: &Self) -> bool {
+        if self == other {
+            return true;
+        }
+
+        if other.limit.is_some() {
+            return false;
+        }
+
+        if other.search.is_some() && self.search != other.search {
+            return false;
+        }
+
+        if !is_subset(&self.ids, &other.ids)
+            || !is_subset(&self.authors, &other.authors)
+            || !is_subset(&self.kinds, &other.kinds)
+        {
+            return false;
+        }
+
+        for (tag, values) in other.generic_tags.iter() {
+            if !values.is_empty() {
+                match self.generic_tag_values(tag) {
+                    Some(self_values) if self_values.is_subset(values) => (),
+                    _ => return false,
+                }
+            }
+        }
+
+        let since: bool = match other.since {
+            Some(since) => self.since.map_or(false, |s| s >= since),
+            None => true,
+        };
+        let until: bool = match other.until {
+            Some(until) => self.until.map_or(false, |u| u <= until),
+            None => true,
+        };
+        since && until
+    }
+
+    /// Check if an event can be matched by both this [`Filter`] and `other`
+    ///
+    /// Generic tags never make two filters disjoint, since an event can have more tags of the same kind.
+    pub fn intersects(&self, other: &Self) -> bool {
+        if is_disjoint(&self.ids, &other.ids)
+            || is_disjoint(&self.authors, &other.authors)
+            || is_disjoint(&self.kinds, &other.kinds)
+        {
+            return false;
+        }
+
+        let since: i64 = core::cmp::max(
+            self.since.map_or(i64::MIN, |t| t.as_i64()),
+            other.since.map_or(i64::MIN, |t| t.as_i64()),
+        );
+        let until: i64 = core::cmp::min(
+            self.until.map_or(i64::MAX, |t| t.as_i64()),
+            other.until.map_or(i64::MAX, |t| t.as_i64()),
+        );
+        since <= until
+    }
+
+    /// Merge with `other`, if the result match exactly the events matched by the two filters
+    ///
+    /// Two filters can be merged if:
+    /// * one is subset of the other (see [`Filter::is_subset_of`]);
+    /// * they differ only in one list (i.e. `authors` or `#p`), that become the union of the two lists;
+    /// * they differ only in `since` and `until`, and the two time windows overlap.
+    ///
+    /// Filters with `limit` are merged only if one is subset of the other.
+    pub fn merge(&self, other: &Self) -> Option<Self> {
+        if other.is_subset_of(self) {
+            return Some(self.clone());
+        }
+
+        if self.is_subset_of(other) {
+            return Some(other.clone());
+        }
+
+        if self.search != other.search || self.limit.is_some() || other.limit.is_some() {
+            return None;
+        }
+
+        let ids: bool = self.ids != other.ids;
+        let authors: bool = self.authors != other.authors;
+        let kinds: bool = self.kinds != other.kinds;
+        let mut tags: Vec<Alphabet> = self
+            .generic_tags
+            .keys()
+            .chain(other.generic_tags.keys())
+            .filter(|tag| self.generic_tag_values(tag) != other.generic_tag_values(tag))
+            .copied()
+            .collect();
+        tags.sort();
+        tags.dedup();
+        let diff: usize = ids as usize + authors as usize + kinds as usize + tags.len();
+
+        if self.since == other.since && self.until == other.until {
+            if diff != 1 {
+                return None;
+            }
+
+            let mut filter: Self = self.clone();
+            if ids {
+                filter.ids.extend(other.ids.iter().copied());
+            } else if authors {
+                filter.authors.extend(other.authors.iter().copied());
+            } else if kinds {
+                filter.kinds.extend(other.kinds.iter().copied());
+            } else if let (Some(tag), Some(values)) = (
+                tags.first(),
+                tags.first().and_then(|tag| other.generic_tag_values(tag)),
+            ) {
+                filter
+                    .generic_tags
+                    .entry(*tag)
+                    .or_default()
+                    .extend(values.iter().cloned());
+            }
+            return Some(filter);
+        }
+
+        if diff != 0 {
+            return None;
+        }
+
+        // Join the time windows, if they overlap or are adjacent
+        let since: i64 = core::cmp::max(
+            self.since.map_or(i64::MIN, |t| t.as_i64()),
+            other.since.map_or(i64::MIN, |t| t.as_i64()),
+        );
+        let until: i64 = core::cmp::min(
+            self.until.map_or(i64::MAX, |t| t.as_i64()),
+            other.until.map_or(i64::MAX, |t| t.as_i64()),
+        );
+        if since > until.saturating_add(1) {
+            return None;
+        }
+
+        Some(Self {
+            since: self.since.zip(other.since).map(|(a, b)| a.min(b)),
+            until: self.until.zip(other.until).map(|(a, b)| a.max(b)),
+            ..self.clone()
+        })
+    }
+
+    /// Simplify a list of filters, removing the redundant ones and merging the others
+    /// when possible (see [`Filter::merge`])
+    ///
+    /// The returned filters match exactly the same events of the input ones.
+    pub fn simplify<I>(filters: I) -> Vec<Self>
+    where
+        I: IntoIterator<Item = Self>,
+    {
+        let mut simplified: Vec<Self> = Vec::new();
+        for mut filter in filters.into_iter() {
+            // Merge until the filter can't be combined with the others
+            while let Some((index, merged)) = simplified
+                .iter()
+                .enumerate()
+                .find_map(|(index, f)| f.merge(&filter).map(|merged| (index, merged)))
+            {
+                simplified.remove(index);
+                filter = merged;
+            }
+            simplified.push(filter);
+        }
+        simplified
+    }
+
+    fn generic_tag_values(&self, tag: &Alphabet) -> Option<&AllocSet<GenericTagValue>> {
+        self.generic_tags
+            .get(tag)
+            .filter(|values| !values.is_empty())
+    }
+}
+
+/// An empty list match everything
+fn is_subset<T>(set: &AllocSet<T>, other: &AllocSet<T>) -> bool
+where
+    T: Eq + Ord + core::hash::Hash,
+{
+    other.is_empty() || (!set.is_empty() && set.is_subset(other))
+}
+
+fn is_disjoint<T>(set: &AllocSet<T>, other: &AllocSet<T>) -> bool
+where
+    T: Eq + Ord + core::hash::Hash,
+{
+    !set.is_empty() && !other.is_empty() && set.is_disjoint(other)
 }
 
 impl JsonUtil for Filter {
@@ -751,6 +1018,24 @@ where
 mod test {
     use super::*;
 
+    #[test]
+    fn test_filter_within() {
+        let start = Timestamp::from(1_700_000_000);
+        let end = Timestamp::from(1_700_086_400);
+
+        let filter = Filter::new().within(start..end);
+        assert_eq!(filter.since, Some(start));
+        assert_eq!(filter.until, Some(Timestamp::from(1_700_086_399)));
+
+        let filter = Filter::new().within(start..=end);
+        assert_eq!(filter.since, Some(start));
+        assert_eq!(filter.until, Some(end));
+
+        let filter = Filter::new().until(end).within(start..);
+        assert_eq!(filter.since, Some(start));
+        assert_eq!(filter.until, None);
+    }
+
     #[test]
     fn test_kind_concatenation() {
         let filter = Filter::new()
@@ -784,6 +1069,23 @@ mod test {
         assert_eq!(filter, Filter::new().id(event_id));
     }
 
+    #[test]
+    fn test_filter_coordinate() {
+        let public_key = XOnlyPublicKey::from_str(
+            "379e863e8357163b5bce5d2688dc4f1dcc2d505222fb8d74db600f30535dfdfe",
+        )
+        .unwrap();
+        let coordinate = Coordinate::new(Kind::LongFormTextNote, public_key).identifier("test");
+        let filter = Filter::new().coordinate(&coordinate);
+        assert_eq!(
+            filter.as_json(),
+            r##"{"#a":["30023:379e863e8357163b5bce5d2688dc4f1dcc2d505222fb8d74db600f30535dfdfe:test"]}"##
+        );
+
+        let filter = filter.remove_coordinates([&coordinate]);
+        assert_eq!(filter, Filter::new().coordinates([]));
+    }
+
     #[test]
     fn test_remove_custom_tag() {
         let filter = Filter::new().custom_tag(Alphabet::C, vec!["test", "test2"]);
@@ -848,6 +1150,125 @@ mod test {
         assert_eq!(filter, Filter::new().search("test"));
     }
 
+    #[test]
+    fn test_filter_subset_and_intersects() {
+        let pk = XOnlyPublicKey::from_str(
+            "379e863e8357163b5bce5d2688dc4f1dcc2d505222fb8d74db600f30535dfdfe",
+        )
+        .unwrap();
+        let notes = Filter::new().kind(Kind::TextNote);
+        let author_notes = notes.clone().author(pk).since(Timestamp::from(10));
+
+        assert!(author_notes.is_subset_of(&notes));
+        assert!(!notes.is_subset_of(&author_notes));
+        assert!(author_notes.is_subset_of(&Filter::new()));
+        assert!(!author_notes.is_subset_of(&notes.clone().limit(10)));
+        assert!(notes.clone().hashtag("a").is_subset_of(&notes));
+        assert!(!notes
+            .clone()
+            .hashtag("a")
+            .is_subset_of(&notes.clone().hashtag("b")));
+
+        assert!(notes.intersects(&author_notes));
+        assert!(!notes.intersects(&Filter::new().kind(Kind::Reaction)));
+        assert!(!author_notes.intersects(&notes.clone().until(Timestamp::from(9))));
+        assert!(notes
+            .clone()
+            .hashtag("a")
+            .intersects(&notes.clone().hashtag("b")));
+    }
+
+    #[test]
+    fn test_filter_merge() {
+        let pk = XOnlyPublicKey::from_str(
+            "379e863e8357163b5bce5d2688dc4f1dcc2d505222fb8d74db600f30535dfdfe",
+        )
+        .unwrap();
+
+        // Subset
+        let notes = Filter::new().kind(Kind::TextNote);
+        assert_eq!(notes.merge(&notes.clone().author(pk)), Some(notes.clone()));
+
+        // Differ only in one list
+        let merged = notes.merge(&Filter::new().kind(Kind::Reaction)).unwrap();
+        assert_eq!(
+            merged,
+            Filter::new().kinds([Kind::TextNote, Kind::Reaction])
+        );
+        let merged = notes
+            .clone()
+            .hashtag("a")
+            .merge(&notes.clone().hashtag("b"))
+            .unwrap();
+        assert_eq!(merged, notes.clone().hashtags(["a", "b"]));
+
+        // Differ in two lists
+        assert_eq!(
+            notes.merge(&Filter::new().kind(Kind::Reaction).author(pk)),
+            None
+        );
+
+        // Time windows
+        let first = notes
+            .clone()
+            .within(Timestamp::from(10)..=Timestamp::from(20));
+        let second = notes
+            .clone()
+            .within(Timestamp::from(21)..=Timestamp::from(30));
+        assert_eq!(
+            first.merge(&second),
+            Some(
+                notes
+                    .clone()
+                    .within(Timestamp::from(10)..=Timestamp::from(30))
+            )
+        );
+        let third = notes.clone().since(Timestamp::from(40));
+        assert_eq!(first.merge(&third), None);
+        assert_eq!(
+            second.merge(&notes.clone().since(Timestamp::from(25))),
+            Some(notes.clone().since(Timestamp::from(21)))
+        );
+
+        // Limit
+        assert_eq!(
+            notes
+                .clone()
+                .limit(10)
+                .merge(&notes.clone().kind(Kind::Reaction).limit(10)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_filter_simplify() {
+        let pk = XOnlyPublicKey::from_str(
+            "379e863e8357163b5bce5d2688dc4f1dcc2d505222fb8d74db600f30535dfdfe",
+        )
+        .unwrap();
+        let filters = vec![
+            Filter::new().kind(Kind::TextNote).author(pk),
+            Filter::new().kind(Kind::Reaction).author(pk),
+            Filter::new().kind(Kind::Repost).author(pk),
+            Filter::new()
+                .kind(Kind::TextNote)
+                .author(pk)
+                .hashtag("nostr"),
+            Filter::new().kind(Kind::Metadata).limit(1),
+        ];
+        assert_eq!(
+            Filter::simplify(filters),
+            vec![
+                Filter::new()
+                    .kinds([Kind::TextNote, Kind::Reaction, Kind::Repost])
+                    .author(pk),
+                Filter::new().kind(Kind::Metadata).limit(1),
+            ]
+        );
+
+        assert!(Filter::simplify(Vec::new()).is_empty());
+    }
+
     #[test]
     fn test_filter_is_empty() {
         let filter = Filter::new().identifier("test");
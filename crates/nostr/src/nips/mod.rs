@@ -15,13 +15,16 @@ pub mod nip05;
 pub mod nip06;
 #[cfg(all(feature = "nip07", target_arch = "wasm32"))]
 pub mod nip07;
+pub mod nip10;
 #[cfg(all(feature = "std", feature = "nip11"))]
 pub mod nip11;
 pub mod nip13;
 pub mod nip15;
 pub mod nip19;
 pub mod nip21;
+pub mod nip25;
 pub mod nip26;
+pub mod nip27;
 #[cfg(feature = "nip44")]
 pub mod nip44;
 #[cfg(all(feature = "std", feature = "nip46"))]
@@ -29,11 +32,18 @@ pub mod nip46;
 #[cfg(feature = "nip47")]
 pub mod nip47;
 pub mod nip48;
+pub mod nip51;
 pub mod nip53;
 #[cfg(feature = "nip57")]
 pub mod nip57;
 pub mod nip58;
+#[cfg(all(feature = "std", feature = "nip59"))]
+pub mod nip59;
 pub mod nip65;
+pub mod nip71;
 pub mod nip90;
+pub mod nip92;
 pub mod nip94;
+#[cfg(all(feature = "std", feature = "nip96"))]
+pub mod nip96;
 pub mod nip98;
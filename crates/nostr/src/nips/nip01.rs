@@ -15,6 +15,7 @@ use core::str::FromStr;
 
 use bitcoin::secp256k1::{self, XOnlyPublicKey};
 
+use super::nip19::FromBech32;
 use crate::event::id;
 use crate::{Filter, Kind, Tag, UncheckedUrl};
 
@@ -29,6 +30,8 @@ pub enum Error {
     ParseInt(ParseIntError),
     /// Invalid coordinate
     InvalidCoordinate,
+    /// Invalid event ID or coordinate
+    InvalidEventIdOrCoordinate,
 }
 
 #[cfg(feature = "std")]
@@ -41,6 +44,7 @@ impl fmt::Display for Error {
             Self::EventId(e) => write!(f, "Event ID: {e}"),
             Self::ParseInt(e) => write!(f, "Parse Int: {e}"),
             Self::InvalidCoordinate => write!(f, "Invalid coordinate"),
+            Self::InvalidEventIdOrCoordinate => write!(f, "Invalid event ID or coordinate"),
         }
     }
 }
@@ -126,11 +130,25 @@ impl From<Coordinate> for Filter {
     }
 }
 
+impl From<&Coordinate> for Filter {
+    fn from(value: &Coordinate) -> Self {
+        Self::from(value.clone())
+    }
+}
+
+impl fmt::Display for Coordinate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}:{}", self.kind, self.pubkey, self.identifier)
+    }
+}
+
+/// Parse [`Coordinate`] from a raw `a` tag value (`<kind>:<pubkey>:<d-identifier>`),
+/// a `naddr` or a `nostr:naddr` URI
 impl FromStr for Coordinate {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut kpi = s.split(':');
+        let mut kpi = s.splitn(3, ':');
         if let (Some(kind_str), Some(pubkey_str), Some(identifier)) =
             (kpi.next(), kpi.next(), kpi.next())
         {
@@ -141,7 +159,8 @@ impl FromStr for Coordinate {
                 relays: Vec::new(),
             })
         } else {
-            Err(Error::InvalidCoordinate)
+            let naddr: &str = s.strip_prefix("nostr:").unwrap_or(s);
+            Self::from_bech32(naddr).map_err(|_| Error::InvalidCoordinate)
         }
     }
 }
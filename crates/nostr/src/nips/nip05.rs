@@ -6,18 +6,31 @@
 //!
 //! <https://github.com/nostr-protocol/nips/blob/master/05.md>
 
+use alloc::collections::BTreeMap;
 use alloc::string::{String, ToString};
+use alloc::sync::Arc;
 use alloc::vec::Vec;
 use core::fmt;
 use core::str::FromStr;
+use core::time::Duration;
+use std::collections::HashMap;
 use std::net::SocketAddr;
+use std::sync::Mutex;
 
 use bitcoin::secp256k1::{self, XOnlyPublicKey};
+use futures_util::stream::{self, StreamExt};
 #[cfg(not(target_arch = "wasm32"))]
 use reqwest::Proxy;
 use serde_json::Value;
+use url_fork::Url;
 
 use crate::nips::nip19::Nip19Profile;
+use crate::types::time::Instant;
+
+/// Max number of concurrent HTTP requests of [`verify_batch`]
+const MAX_CONCURRENT_REQUESTS: usize = 16;
+/// Default TTL of the [`Nip05Cache`] entries
+const DEFAULT_CACHE_TTL: Duration = Duration::from_secs(3600);
 
 /// `NIP05` error
 #[derive(Debug)]
@@ -67,21 +80,34 @@ impl From<secp256k1::Error> for Error {
     }
 }
 
-fn compose_url<S>(nip05: S) -> Result<(String, String), Error>
-where
-    S: Into<String>,
-{
-    let nip05: String = nip05.into();
+fn split_identifier(nip05: &str) -> Result<(&str, &str), Error> {
     let data: Vec<&str> = nip05.split('@').collect();
     if data.len() != 2 {
         return Err(Error::InvalidFormat);
     }
-    let name: &str = data[0];
-    let domain: &str = data[1];
-    let url = format!("https://{domain}/.well-known/nostr.json?name={name}");
+    Ok((data[0], data[1]))
+}
+
+fn compose_url<S>(nip05: S) -> Result<(Url, String), Error>
+where
+    S: Into<String>,
+{
+    let nip05: String = nip05.into();
+    let (name, domain) = split_identifier(&nip05)?;
+    let url: Url = well_known_url(domain, Some(name))?;
     Ok((url, name.to_string()))
 }
 
+/// Compose the `.well-known/nostr.json` URL of `domain`, with the URL-encoded `name` query
+fn well_known_url(domain: &str, name: Option<&str>) -> Result<Url, Error> {
+    let mut url: Url = Url::parse(&format!("https://{domain}/.well-known/nostr.json"))
+        .map_err(|_| Error::InvalidFormat)?;
+    if let Some(name) = name {
+        url.query_pairs_mut().append_pair("name", name);
+    }
+    Ok(url)
+}
+
 fn get_key_from_json<S>(json: Value, name: S) -> Option<XOnlyPublicKey>
 where
     S: Into<String>,
@@ -151,6 +177,235 @@ where
     verify_json(public_key, json, name)
 }
 
+fn build_client(_proxy: Option<SocketAddr>) -> Result<reqwest::Client, Error> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        let mut builder = reqwest::Client::builder();
+        if let Some(proxy) = _proxy {
+            let proxy = format!("socks5h://{proxy}");
+            builder = builder.proxy(Proxy::all(proxy)?);
+        }
+        Ok(builder.build()?)
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    Ok(reqwest::Client::new())
+}
+
+/// Cache of the `.well-known/nostr.json` responses
+///
+/// Shared by the clones. Failed requests are not cached.
+#[derive(Debug, Clone)]
+pub struct Nip05Cache {
+    ttl: Duration,
+    responses: Arc<Mutex<HashMap<Url, (Instant, Value)>>>,
+}
+
+impl Default for Nip05Cache {
+    fn default() -> Self {
+        Self::new(DEFAULT_CACHE_TTL)
+    }
+}
+
+impl Nip05Cache {
+    /// New cache, keeping the responses for `ttl`
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            responses: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Remove all the responses
+    pub fn clear(&self) {
+        if let Ok(mut responses) = self.responses.lock() {
+            responses.clear();
+        }
+    }
+
+    fn get(&self, url: &Url) -> Option<Value> {
+        let mut responses = self.responses.lock().ok()?;
+        match responses.get(url) {
+            Some((fetched_at, json)) if fetched_at.elapsed() < self.ttl => Some(json.clone()),
+            Some(..) => {
+                responses.remove(url);
+                None
+            }
+            None => None,
+        }
+    }
+
+    fn insert(&self, url: Url, json: Value) {
+        if let Ok(mut responses) = self.responses.lock() {
+            responses.insert(url, (Instant::now(), json));
+        }
+    }
+}
+
+async fn fetch_json(
+    client: &reqwest::Client,
+    cache: &Nip05Cache,
+    url: Url,
+) -> Result<Value, Error> {
+    if let Some(json) = cache.get(&url) {
+        return Ok(json);
+    }
+
+    let res = client.get(url.clone()).send().await?;
+    let json: Value = serde_json::from_str(&res.text().await?)?;
+    cache.insert(url, json.clone());
+    Ok(json)
+}
+
+/// Verify the identifiers of a single domain
+///
+/// The whole `nostr.json` of the domain is fetched once: the identifiers not listed there
+/// (i.e. servers that reply only for the requested name) are fetched one by one.
+async fn verify_domain(
+    client: &reqwest::Client,
+    cache: &Nip05Cache,
+    domain: &str,
+    identifiers: Vec<(usize, XOnlyPublicKey, String)>,
+) -> Vec<(usize, Result<(), Error>)> {
+    let json: Option<Value> = match well_known_url(domain, None) {
+        Ok(url) => match fetch_json(client, cache, url).await {
+            Ok(json) => Some(json),
+            Err(e) => {
+                tracing::debug!("Impossible to fetch NIP05 data of {domain}: {e}");
+                None
+            }
+        },
+        Err(e) => {
+            tracing::debug!("Invalid NIP05 domain {domain}: {e}");
+            None
+        }
+    };
+
+    let mut results: Vec<(usize, Result<(), Error>)> = Vec::with_capacity(identifiers.len());
+    let mut missing: Vec<(usize, XOnlyPublicKey, String)> = Vec::new();
+
+    for (index, public_key, name) in identifiers.into_iter() {
+        match json
+            .as_ref()
+            .and_then(|json| get_key_from_json(json.clone(), name.clone()))
+        {
+            Some(pubkey) if pubkey == public_key => results.push((index, Ok(()))),
+            _ => missing.push((index, public_key, name)),
+        }
+    }
+
+    let missing = missing
+        .into_iter()
+        .map(|(index, public_key, name)| async move {
+            let res = match well_known_url(domain, Some(&name)) {
+                Ok(url) => match fetch_json(client, cache, url).await {
+                    Ok(json) => verify_json(public_key, json, name),
+                    Err(e) => Err(e),
+                },
+                Err(e) => Err(e),
+            };
+            (index, res)
+        });
+    results.extend(
+        stream::iter(missing)
+            .buffer_unordered(MAX_CONCURRENT_REQUESTS)
+            .collect::<Vec<_>>()
+            .await,
+    );
+
+    results
+}
+
+/// Verify many NIP05 identifiers at once
+///
+/// The identifiers are verified concurrently (up to 16 domains at once) and the `.well-known` document
+/// of each domain is fetched only once. Return the result of each `(public key, identifier)` pair,
+/// in the same order of the input.
+///
+/// To reuse the responses across the calls, see [`verify_batch_with_cache`].
+///
+/// **Proxy is ignored for WASM targets!**
+pub async fn verify_batch<I, S>(
+    identifiers: I,
+    proxy: Option<SocketAddr>,
+) -> Vec<(XOnlyPublicKey, String, Result<(), Error>)>
+where
+    I: IntoIterator<Item = (XOnlyPublicKey, S)>,
+    S: Into<String>,
+{
+    verify_batch_with_cache(identifiers, proxy, &Nip05Cache::default()).await
+}
+
+/// Verify many NIP05 identifiers at once, using the responses in `cache`
+///
+/// See [`verify_batch`].
+///
+/// **Proxy is ignored for WASM targets!**
+pub async fn verify_batch_with_cache<I, S>(
+    identifiers: I,
+    proxy: Option<SocketAddr>,
+    cache: &Nip05Cache,
+) -> Vec<(XOnlyPublicKey, String, Result<(), Error>)>
+where
+    I: IntoIterator<Item = (XOnlyPublicKey, S)>,
+    S: Into<String>,
+{
+    let identifiers: Vec<(XOnlyPublicKey, String)> = identifiers
+        .into_iter()
+        .map(|(public_key, nip05)| (public_key, nip05.into()))
+        .collect();
+
+    let mut results: Vec<Option<Result<(), Error>>> = identifiers.iter().map(|_| None).collect();
+
+    let client: reqwest::Client = match build_client(proxy) {
+        Ok(client) => client,
+        Err(e) => {
+            tracing::error!("Impossible to build NIP05 HTTP client: {e}");
+            return identifiers
+                .into_iter()
+                .map(|(public_key, nip05)| (public_key, nip05, Err(Error::ImpossibleToVerify)))
+                .collect();
+        }
+    };
+
+    // Group by domain
+    let mut domains: BTreeMap<String, Vec<(usize, XOnlyPublicKey, String)>> = BTreeMap::new();
+    for (index, (public_key, nip05)) in identifiers.iter().enumerate() {
+        match split_identifier(nip05) {
+            Ok((name, domain)) => domains.entry(domain.to_lowercase()).or_default().push((
+                index,
+                *public_key,
+                name.to_string(),
+            )),
+            Err(e) => results[index] = Some(Err(e)),
+        }
+    }
+
+    let client = &client;
+    let futures = domains.into_iter().map(|(domain, identifiers)| async move {
+        verify_domain(client, cache, &domain, identifiers).await
+    });
+    let domains_results: Vec<Vec<(usize, Result<(), Error>)>> = stream::iter(futures)
+        .buffer_unordered(MAX_CONCURRENT_REQUESTS)
+        .collect()
+        .await;
+    for (index, res) in domains_results.into_iter().flatten() {
+        results[index] = Some(res);
+    }
+
+    identifiers
+        .into_iter()
+        .zip(results)
+        .map(|((public_key, nip05), res)| {
+            (
+                public_key,
+                nip05,
+                res.unwrap_or(Err(Error::ImpossibleToVerify)),
+            )
+        })
+        .collect()
+}
+
 /// Verify NIP05
 #[cfg(not(target_arch = "wasm32"))]
 #[cfg(feature = "blocking")]
@@ -233,3 +488,24 @@ where
 
     Ok(Nip19Profile { public_key, relays })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_well_known_url() {
+        let (url, name) = compose_url("a b&c=d@example.com").unwrap();
+        assert_eq!(name, "a b&c=d");
+        assert_eq!(
+            url.as_str(),
+            "https://example.com/.well-known/nostr.json?name=a+b%26c%3Dd"
+        );
+
+        assert_eq!(
+            well_known_url("example.com", None).unwrap().as_str(),
+            "https://example.com/.well-known/nostr.json"
+        );
+        assert!(compose_url("example.com").is_err());
+    }
+}
@@ -8,11 +8,13 @@
 
 #[cfg(feature = "std")]
 use alloc::string::String;
+use alloc::vec::Vec;
 use core::fmt;
+use core::ops::Range;
 use core::str::FromStr;
 
 use bip39::Mnemonic;
-use bitcoin::bip32::{DerivationPath, ExtendedPrivKey};
+use bitcoin::bip32::{ChildNumber, DerivationPath, ExtendedPrivKey};
 use bitcoin::hashes::hmac::{Hmac, HmacEngine};
 use bitcoin::hashes::{sha512, Hash, HashEngine};
 #[cfg(feature = "std")]
@@ -78,11 +80,35 @@ pub trait FromMnemonic: Sized {
         passphrase: Option<S>,
         account: Option<u32>,
     ) -> Result<Self, Self::Err>
+    where
+        S: Into<String>,
+    {
+        Self::from_mnemonic_advanced(mnemonic, passphrase, account, None, None)
+    }
+
+    /// Derive from BIP-39 mnemonics with **custom** `account`, `typ` (change) and `index` (ENGLISH wordlist).
+    ///
+    /// Derivation path: `m/44'/1237'/<account>'/<typ>/<index>` (all default to `0`).
+    #[cfg(feature = "std")]
+    fn from_mnemonic_advanced<S>(
+        mnemonic: S,
+        passphrase: Option<S>,
+        account: Option<u32>,
+        typ: Option<u32>,
+        index: Option<u32>,
+    ) -> Result<Self, Self::Err>
     where
         S: Into<String>,
     {
         let passphrase: Option<String> = passphrase.map(|p| p.into());
-        Self::from_mnemonic_with_ctx(&SECP256K1, &mnemonic.into(), passphrase.as_deref(), account)
+        Self::from_mnemonic_advanced_with_ctx(
+            &SECP256K1,
+            &mnemonic.into(),
+            passphrase.as_deref(),
+            account,
+            typ,
+            index,
+        )
     }
 
     /// Derive from BIP-39 mnemonics with **custom account** (ENGLISH wordlist).
@@ -92,6 +118,23 @@ pub trait FromMnemonic: Sized {
         passphrase: Option<&str>,
         account: Option<u32>,
     ) -> Result<Self, Self::Err>
+    where
+        C: Signing,
+    {
+        Self::from_mnemonic_advanced_with_ctx(secp, mnemonic, passphrase, account, None, None)
+    }
+
+    /// Derive from BIP-39 mnemonics with **custom** `account`, `typ` (change) and `index` (ENGLISH wordlist).
+    ///
+    /// Derivation path: `m/44'/1237'/<account>'/<typ>/<index>` (all default to `0`).
+    fn from_mnemonic_advanced_with_ctx<C>(
+        secp: &Secp256k1<C>,
+        mnemonic: &str,
+        passphrase: Option<&str>,
+        account: Option<u32>,
+        typ: Option<u32>,
+        index: Option<u32>,
+    ) -> Result<Self, Self::Err>
     where
         C: Signing;
 }
@@ -108,28 +151,88 @@ pub trait GenerateMnemonic {
         R: RngCore;
 }
 
+fn root_key(mnemonic: &str, passphrase: Option<&str>) -> Result<ExtendedPrivKey, Error> {
+    let mnemonic: Mnemonic = Mnemonic::from_str(mnemonic)?;
+    let seed: [u8; 64] = mnemonic.to_seed_normalized(passphrase.unwrap_or_default());
+    Ok(ExtendedPrivKey::new_master(Network::Bitcoin, &seed)?)
+}
+
+fn derive<C>(
+    secp: &Secp256k1<C>,
+    root_key: &ExtendedPrivKey,
+    account: u32,
+    typ: u32,
+    index: u32,
+) -> Result<Keys, Error>
+where
+    C: Signing,
+{
+    let path = DerivationPath::from(vec![
+        ChildNumber::from_hardened_idx(44)?,
+        ChildNumber::from_hardened_idx(1237)?,
+        ChildNumber::from_hardened_idx(account)?,
+        ChildNumber::from_normal_idx(typ)?,
+        ChildNumber::from_normal_idx(index)?,
+    ]);
+    let child_xprv = root_key.derive_priv(secp, &path)?;
+    Ok(Keys::new_with_ctx(secp, child_xprv.private_key))
+}
+
 impl FromMnemonic for Keys {
     type Err = Error;
 
-    fn from_mnemonic_with_ctx<C>(
+    fn from_mnemonic_advanced_with_ctx<C>(
         secp: &Secp256k1<C>,
         mnemonic: &str,
         passphrase: Option<&str>,
         account: Option<u32>,
+        typ: Option<u32>,
+        index: Option<u32>,
     ) -> Result<Self, Self::Err>
     where
         C: Signing,
     {
-        let mnemonic: Mnemonic = Mnemonic::from_str(mnemonic)?;
-        let seed: [u8; 64] = mnemonic.to_seed_normalized(passphrase.unwrap_or_default());
-        let root_key = ExtendedPrivKey::new_master(Network::Bitcoin, &seed)?;
-        let account: u32 = account.unwrap_or_default();
-        let path = DerivationPath::from_str(&format!("m/44'/1237'/{account}'/0/0"))?;
-        let child_xprv = root_key.derive_priv(secp, &path)?;
-        Ok(Self::new_with_ctx(secp, child_xprv.private_key))
+        let root_key = root_key(mnemonic, passphrase)?;
+        derive(
+            secp,
+            &root_key,
+            account.unwrap_or_default(),
+            typ.unwrap_or_default(),
+            index.unwrap_or_default(),
+        )
     }
 }
 
+/// Derive many identities (one for each account in `accounts`) from BIP-39 mnemonics (ENGLISH wordlist).
+///
+/// The seed is computed only once. Derivation path: `m/44'/1237'/<account>'/0/0`.
+#[cfg(feature = "std")]
+pub fn derive_identities(
+    mnemonic: &str,
+    passphrase: Option<&str>,
+    accounts: Range<u32>,
+) -> Result<Vec<Keys>, Error> {
+    derive_identities_with_ctx(&SECP256K1, mnemonic, passphrase, accounts)
+}
+
+/// Derive many identities (one for each account in `accounts`) from BIP-39 mnemonics (ENGLISH wordlist).
+///
+/// The seed is computed only once. Derivation path: `m/44'/1237'/<account>'/0/0`.
+pub fn derive_identities_with_ctx<C>(
+    secp: &Secp256k1<C>,
+    mnemonic: &str,
+    passphrase: Option<&str>,
+    accounts: Range<u32>,
+) -> Result<Vec<Keys>, Error>
+where
+    C: Signing,
+{
+    let root_key = root_key(mnemonic, passphrase)?;
+    accounts
+        .map(|account| derive(secp, &root_key, account, 0, 0))
+        .collect()
+}
+
 impl GenerateMnemonic for Keys {
     type Err = Error;
 
@@ -173,4 +276,42 @@ mod tests {
                 .unwrap()
         );
     }
+
+    #[test]
+    fn test_nip06_advanced() {
+        let secp = Secp256k1::new();
+
+        let mnemonic: &str =
+            "leader monkey parrot ring guide accident before fence cannon height naive bean";
+        let keys =
+            Keys::from_mnemonic_advanced_with_ctx(&secp, mnemonic, None, None, None, None).unwrap();
+        assert_eq!(
+            keys.secret_key().unwrap(),
+            SecretKey::from_str("7f7ff03d123792d6ac594bfa67bf6d0c0ab55b6b1fdb6249303fe861f1ccba9a")
+                .unwrap()
+        );
+
+        // Different type and index
+        let keys_1 =
+            Keys::from_mnemonic_advanced_with_ctx(&secp, mnemonic, None, None, Some(0), Some(1))
+                .unwrap();
+        let keys_2 =
+            Keys::from_mnemonic_advanced_with_ctx(&secp, mnemonic, None, None, Some(1), Some(0))
+                .unwrap();
+        assert_ne!(keys_1, keys);
+        assert_ne!(keys_2, keys);
+        assert_ne!(keys_1, keys_2);
+
+        // Many identities
+        let identities = derive_identities_with_ctx(&secp, mnemonic, None, 0..3).unwrap();
+        assert_eq!(identities.len(), 3);
+        assert_eq!(identities[0], keys);
+        assert_eq!(
+            identities[2],
+            Keys::from_mnemonic_with_ctx(&secp, mnemonic, None, Some(2)).unwrap()
+        );
+
+        // Hardened index out of range
+        assert!(Keys::from_mnemonic_with_ctx(&secp, mnemonic, None, Some(u32::MAX)).is_err());
+    }
 }
@@ -0,0 +1,345 @@
+// Copyright (c) 2022-2023 Yuki Kishimoto
+// Copyright (c) 2023-2024 Rust Nostr Developers
+// Distributed under the MIT software license
+
+//! NIP10
+//!
+//! <https://github.com/nostr-protocol/nips/blob/master/10.md>
+
+use alloc::collections::BTreeSet;
+use alloc::vec::Vec;
+
+use bitcoin::secp256k1::XOnlyPublicKey;
+
+use crate::event::tag::Marker;
+use crate::{Event, EventId, Kind, Tag};
+
+/// Get the `e` tags of the event, with their markers
+fn event_tags(event: &Event) -> Vec<(&EventId, Option<&Marker>)> {
+    event
+        .iter_tags()
+        .filter_map(|tag| match tag {
+            Tag::Event {
+                event_id, marker, ..
+            } => Some((event_id, marker.as_ref())),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Get the root of the thread the event belongs to
+///
+/// The `e` tag marked as `root` or, for the deprecated positional `e` tags, the first one.
+/// Return `None` if the event is not a reply.
+pub fn root_id(event: &Event) -> Option<EventId> {
+    let tags = event_tags(event);
+    if let Some((id, ..)) = tags.iter().find(|(_, m)| *m == Some(&Marker::Root)) {
+        return Some(**id);
+    }
+    if tags.iter().any(|(_, m)| m.is_some()) {
+        return None;
+    }
+    tags.first().map(|(id, ..)| **id)
+}
+
+/// Get the event the event is a direct reply to
+///
+/// The `e` tag marked as `reply` (or `root`, for top level replies) or, for the deprecated
+/// positional `e` tags, the last one. Return `None` if the event is not a reply.
+pub fn reply_to_id(event: &Event) -> Option<EventId> {
+    let tags = event_tags(event);
+    if let Some((id, ..)) = tags.iter().find(|(_, m)| *m == Some(&Marker::Reply)) {
+        return Some(**id);
+    }
+    if tags.iter().any(|(_, m)| m.is_some()) {
+        return tags
+            .iter()
+            .find(|(_, m)| *m == Some(&Marker::Root))
+            .map(|(id, ..)| **id);
+    }
+    tags.last().map(|(id, ..)| **id)
+}
+
+/// Get the public keys to tag (`p` tags) in a reply to `reply_to`
+///
+/// The author of `reply_to` followed by the public keys already tagged in it, without duplicates.
+/// `exclude` is usually the public key of the reply author.
+pub fn reply_public_keys(
+    reply_to: &Event,
+    exclude: Option<&XOnlyPublicKey>,
+) -> Vec<XOnlyPublicKey> {
+    let mut public_keys: Vec<XOnlyPublicKey> = Vec::new();
+    for public_key in core::iter::once(reply_to.author_ref()).chain(reply_to.public_keys()) {
+        if Some(public_key) != exclude && !public_keys.contains(public_key) {
+            public_keys.push(*public_key);
+        }
+    }
+    public_keys
+}
+
+/// Compose the `p` tags for a reply to `reply_to` (see [`reply_public_keys`])
+pub fn reply_public_key_tags(reply_to: &Event, exclude: Option<&XOnlyPublicKey>) -> Vec<Tag> {
+    reply_public_keys(reply_to, exclude)
+        .into_iter()
+        .map(Tag::public_key)
+        .collect()
+}
+
+/// Thread of text notes (kind `1`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Thread {
+    /// Event
+    pub event: Event,
+    /// Direct replies, sorted by [`Timestamp`](crate::Timestamp)
+    pub replies: Vec<Thread>,
+}
+
+impl Thread {
+    /// New thread without replies
+    pub fn new(event: Event) -> Self {
+        Self {
+            event,
+            replies: Vec::new(),
+        }
+    }
+
+    /// Build the thread of `root` from its replies
+    ///
+    /// Replies are attached to their parent (see [`reply_to_id`]), up to `depth` levels
+    /// (unlimited if `None`). Orphans, events of other kinds and duplicates are skipped.
+    pub fn from_events<I>(root: Event, events: I, depth: Option<usize>) -> Self
+    where
+        I: IntoIterator<Item = Event>,
+    {
+        let mut thread = Self::new(root);
+        let mut pending: Vec<Event> = events.into_iter().collect();
+        pending.sort_by_key(|e| e.created_at());
+
+        // Loop since replies may come before their parents
+        loop {
+            let mut attached: bool = false;
+            pending.retain(|event| {
+                let parent: EventId = match reply_to_id(event) {
+                    Some(parent) => parent,
+                    None => return false,
+                };
+                let parent_depth: usize = match thread.depth_of(&parent) {
+                    Some(d) => d,
+                    None => return true,
+                };
+                if depth.map_or(true, |depth| parent_depth < depth) {
+                    attached |= thread.insert(event.clone());
+                }
+                false
+            });
+            if !attached {
+                break;
+            }
+        }
+
+        thread
+    }
+
+    /// Root event ID
+    pub fn id(&self) -> EventId {
+        self.event.id()
+    }
+
+    /// Insert a reply (i.e. received from a live subscription)
+    ///
+    /// Return `false` if the parent is not in the thread, or if the event is already in it.
+    pub fn insert(&mut self, event: Event) -> bool {
+        if event.kind() != Kind::TextNote || self.find(&event.id()).is_some() {
+            return false;
+        }
+        match reply_to_id(&event).and_then(|parent| self.find_mut(&parent)) {
+            Some(parent) => {
+                let pos: usize = parent
+                    .replies
+                    .partition_point(|r| r.event.created_at() <= event.created_at());
+                parent.replies.insert(pos, Self::new(event));
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Find an event in the thread
+    pub fn find(&self, id: &EventId) -> Option<&Self> {
+        if self.event.id() == *id {
+            return Some(self);
+        }
+        self.replies.iter().find_map(|r| r.find(id))
+    }
+
+    fn find_mut(&mut self, id: &EventId) -> Option<&mut Self> {
+        if self.event.id() == *id {
+            return Some(self);
+        }
+        self.replies.iter_mut().find_map(|r| r.find_mut(id))
+    }
+
+    /// Depth of an event in the thread (`0` for the root)
+    pub fn depth_of(&self, id: &EventId) -> Option<usize> {
+        if self.event.id() == *id {
+            return Some(0);
+        }
+        self.replies
+            .iter()
+            .find_map(|r| r.depth_of(id))
+            .map(|d| d + 1)
+    }
+
+    /// IDs of all the events in the thread
+    pub fn ids(&self) -> BTreeSet<EventId> {
+        let mut ids: BTreeSet<EventId> = BTreeSet::new();
+        self.collect_ids(&mut ids);
+        ids
+    }
+
+    fn collect_ids(&self, ids: &mut BTreeSet<EventId>) {
+        ids.insert(self.event.id());
+        for reply in self.replies.iter() {
+            reply.collect_ids(ids);
+        }
+    }
+
+    /// Number of events in the thread, root included
+    pub fn len(&self) -> usize {
+        1 + self.replies.iter().map(|r| r.len()).sum::<usize>()
+    }
+
+    /// Always `false`, since the thread contains at least the root
+    pub fn is_empty(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "std")]
+    use crate::{EventBuilder, Keys, Timestamp};
+
+    #[cfg(feature = "std")]
+    fn reply(keys: &Keys, root: &Event, parent: &Event, created_at: u64) -> Event {
+        let mut tags = vec![Tag::Event {
+            event_id: root.id(),
+            relay_url: None,
+            marker: Some(Marker::Root),
+        }];
+        if root.id() != parent.id() {
+            tags.push(Tag::Event {
+                event_id: parent.id(),
+                relay_url: None,
+                marker: Some(Marker::Reply),
+            });
+        }
+        EventBuilder::text_note("reply", tags)
+            .custom_created_at(Timestamp::from(created_at))
+            .to_event(keys)
+            .unwrap()
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_reply_public_keys() {
+        let alice = Keys::generate();
+        let bob = Keys::generate();
+        let carol = Keys::generate();
+
+        let event = EventBuilder::text_note(
+            "hello",
+            [
+                Tag::public_key(carol.public_key()),
+                Tag::public_key(bob.public_key()),
+                Tag::public_key(carol.public_key()),
+            ],
+        )
+        .to_event(&alice)
+        .unwrap();
+
+        assert_eq!(
+            reply_public_keys(&event, None),
+            vec![alice.public_key(), carol.public_key(), bob.public_key()]
+        );
+        assert_eq!(
+            reply_public_keys(&event, Some(&bob.public_key())),
+            vec![alice.public_key(), carol.public_key()]
+        );
+        assert_eq!(
+            reply_public_key_tags(&event, Some(&alice.public_key())),
+            vec![
+                Tag::public_key(carol.public_key()),
+                Tag::public_key(bob.public_key())
+            ]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_root_and_reply_to_id() {
+        let keys = Keys::generate();
+        let root = EventBuilder::text_note("root", []).to_event(&keys).unwrap();
+        let a = reply(&keys, &root, &root, 1);
+        let b = reply(&keys, &root, &a, 2);
+        assert_eq!(root_id(&root), None);
+        assert_eq!(reply_to_id(&root), None);
+        assert_eq!(root_id(&a), Some(root.id()));
+        assert_eq!(reply_to_id(&a), Some(root.id()));
+        assert_eq!(root_id(&b), Some(root.id()));
+        assert_eq!(reply_to_id(&b), Some(a.id()));
+
+        // Deprecated positional tags
+        let positional =
+            EventBuilder::text_note("reply", [Tag::event(root.id()), Tag::event(a.id())])
+                .to_event(&keys)
+                .unwrap();
+        assert_eq!(root_id(&positional), Some(root.id()));
+        assert_eq!(reply_to_id(&positional), Some(a.id()));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_thread() {
+        let keys = Keys::generate();
+        let root = EventBuilder::text_note("root", []).to_event(&keys).unwrap();
+        let other = EventBuilder::text_note("other", [])
+            .to_event(&keys)
+            .unwrap();
+        let a = reply(&keys, &root, &root, 2);
+        let b = reply(&keys, &root, &root, 1);
+        let c = reply(&keys, &root, &a, 3);
+        let d = reply(&keys, &root, &c, 4);
+        let orphan = reply(&keys, &other, &other, 5);
+
+        // Children before parents and duplicates
+        let events = vec![
+            d.clone(),
+            c.clone(),
+            a.clone(),
+            b.clone(),
+            a.clone(),
+            orphan.clone(),
+        ];
+        let thread = Thread::from_events(root.clone(), events.clone(), None);
+        assert_eq!(thread.len(), 5);
+        assert_eq!(thread.replies[0].event, b);
+        assert_eq!(thread.replies[1].event, a);
+        assert_eq!(thread.replies[1].replies[0].replies[0].event, d);
+        assert_eq!(thread.depth_of(&d.id()), Some(3));
+        assert!(thread.find(&orphan.id()).is_none());
+
+        let mut thread = Thread::from_events(root.clone(), events, Some(2));
+        assert_eq!(thread.len(), 4);
+        assert!(thread.find(&d.id()).is_none());
+
+        // Live updates
+        let e = reply(&keys, &root, &b, 6);
+        assert!(thread.insert(e.clone()));
+        assert!(!thread.insert(e.clone()));
+        assert!(!thread.insert(orphan));
+        assert_eq!(thread.depth_of(&e.id()), Some(2));
+        assert!(thread.ids().contains(&e.id()));
+    }
+}
@@ -111,6 +111,8 @@ pub struct Limitation {
     pub max_filters: Option<i32>,
     /// Relay will clamp each filter's limit value to this number
     pub max_limit: Option<i32>,
+    /// Relay will use this value as `limit` for the filters without one
+    pub default_limit: Option<i32>,
     /// Maximum length of subscription id as a string
     pub max_subid_length: Option<i32>,
     /// Maximum number of elements in the tags list
@@ -123,6 +125,9 @@ pub struct Limitation {
     pub auth_required: Option<bool>,
     /// Relay requires payment before a new connection may perform any action
     pub payment_required: Option<bool>,
+    /// Relay requires some kind of condition to be fulfilled in order to accept events
+    /// (not necessarily, but including, payment and authentication)
+    pub restricted_writes: Option<bool>,
     /// 'created_at' lower limit
     pub created_at_lower_limit: Option<Timestamp>,
     /// 'created_at' upper limit
@@ -177,7 +182,7 @@ pub struct FeeSchedule {
     /// The duration for which the fee is valid
     pub period: Option<i32>,
     /// The event kinds the fee allows the client to publish to the relay
-    pub kinds: Option<Vec<String>>,
+    pub kinds: Option<Vec<u16>>,
 }
 
 impl RelayInformationDocument {
@@ -186,6 +191,51 @@ impl RelayInformationDocument {
         Self::default()
     }
 
+    /// Check if the relay announces support for `nip`
+    ///
+    /// Return `None` if the relay doesn't announce the supported NIPs.
+    pub fn supports_nip(&self, nip: u16) -> Option<bool> {
+        match &self.supported_nips {
+            Some(supported_nips) if !supported_nips.is_empty() => {
+                Some(supported_nips.contains(&nip))
+            }
+            _ => None,
+        }
+    }
+
+    /// Check if the relay requires NIP42 authentication
+    pub fn auth_required(&self) -> bool {
+        self.limitation
+            .as_ref()
+            .and_then(|l| l.auth_required)
+            .unwrap_or(false)
+    }
+
+    /// Check if the relay requires payment
+    pub fn payment_required(&self) -> bool {
+        self.limitation
+            .as_ref()
+            .and_then(|l| l.payment_required)
+            .unwrap_or(false)
+    }
+
+    /// Check if the relay restricts writes (i.e. payment, authentication, ...)
+    pub fn restricted_writes(&self) -> bool {
+        self.limitation
+            .as_ref()
+            .and_then(|l| l.restricted_writes)
+            .unwrap_or(false)
+    }
+
+    /// Get the min PoW difficulty required to publish events
+    pub fn min_pow_difficulty(&self) -> u8 {
+        self.limitation
+            .as_ref()
+            .and_then(|l| l.min_pow_difficulty)
+            .map(|d| d.clamp(0, u8::MAX as i32) as u8)
+            .unwrap_or_default()
+    }
+
     /// Get Relay Information Document
     ///
     /// **Proxy is ignored for WASM targets!**
@@ -295,4 +345,71 @@ mod tests {
 
         assert!(got == expected, "got: {:?}, expected: {:?}", got, expected);
     }
+
+    #[test]
+    fn test_deserialize_document() {
+        let json = r#"{
+            "name": "JellyFish",
+            "description": "Stay Immortal!",
+            "pubkey": "bf2bee5281149c7c350f5d12ae32f514c7864ff10805182f4178538c2c421007",
+            "contact": "hi@dezh.tech",
+            "supported_nips": [1, 9, 11, 13, 17, 40, 42, 59, 62, 70],
+            "software": "https://github.com/dezh-tech/immortal",
+            "version": "immortal - 0.0.9",
+            "icon": "https://jellyfish.land/jellyfish.png",
+            "limitation": {
+                "max_message_length": 70000,
+                "max_subscriptions": 350,
+                "max_filters": 10,
+                "max_limit": 5000,
+                "max_subid_length": 256,
+                "max_event_tags": 2000,
+                "max_content_length": 70000,
+                "min_pow_difficulty": 8,
+                "auth_required": false,
+                "payment_required": true,
+                "restricted_writes": true,
+                "created_at_lower_limit": 0,
+                "created_at_upper_limit": 2147483647,
+                "default_limit": 500
+            },
+            "retention": [
+                {"kinds": [0, 1, [5, 7], [40, 49]], "time": 3600},
+                {"count": 1000}
+            ],
+            "relay_countries": ["*"],
+            "language_tags": ["en", "en-419"],
+            "tags": ["sfw-only"],
+            "posting_policy": "https://jellyfish.land/tos.txt",
+            "payments_url": "https://jellyfish.land/relay",
+            "fees": {
+                "subscription": [{"amount": 3000, "unit": "msats", "period": 2628003}],
+                "publication": [{"kinds": [4], "amount": 100, "unit": "msats"}]
+            }
+        }"#;
+        let document: RelayInformationDocument = serde_json::from_str(json).unwrap();
+
+        assert_eq!(document.supports_nip(42), Some(true));
+        assert_eq!(document.supports_nip(45), Some(false));
+        assert!(!document.auth_required());
+        assert!(document.payment_required());
+        assert!(document.restricted_writes());
+        assert_eq!(document.min_pow_difficulty(), 8);
+        assert_eq!(document.retention.len(), 2);
+        assert_eq!(document.language_tags, vec!["en", "en-419"]);
+        assert_eq!(
+            document.icon.as_deref(),
+            Some("https://jellyfish.land/jellyfish.png")
+        );
+
+        let limitation = document.limitation.unwrap();
+        assert_eq!(limitation.default_limit, Some(500));
+        assert_eq!(limitation.max_filters, Some(10));
+
+        let fees = document.fees.unwrap();
+        assert!(fees.admission.is_empty());
+        assert_eq!(fees.publication[0].kinds, Some(vec![4]));
+
+        assert_eq!(RelayInformationDocument::new().supports_nip(1), None);
+    }
 }
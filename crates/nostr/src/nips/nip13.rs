@@ -9,6 +9,26 @@
 
 use alloc::string::String;
 use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use core::fmt;
+#[cfg(feature = "std")]
+use core::time::Duration;
+#[cfg(feature = "std")]
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicU8, Ordering};
+#[cfg(feature = "std")]
+use std::sync::{Arc, Mutex};
+#[cfg(feature = "std")]
+use std::thread;
+
+#[cfg(feature = "std")]
+use crate::types::time::Instant;
+
+/// Number of hashes after which a mining thread updates the shared counter
+#[cfg(feature = "std")]
+const HASHES_BATCH: u64 = 1024;
+/// Max time the progress reporter sleeps before checking if mining is done
+#[cfg(feature = "std")]
+const PROGRESS_POLL_INTERVAL: Duration = Duration::from_millis(10);
 
 /// Gets the number of leading zero bits. Result is between 0 and 255.
 pub fn get_leading_zero_bits<T>(h: T) -> u8
@@ -56,6 +76,375 @@ pub fn get_prefixes_for_difficulty(leading_zero_bits: u8) -> Vec<String> {
     r
 }
 
+/// POW mining cancellation token
+///
+/// Clones share the same state: cancel a clone to stop the miner.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+#[cfg(feature = "std")]
+impl CancellationToken {
+    /// New cancellation token
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancel
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    /// Check if cancelled
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+}
+
+/// Expected number of hashes to find a nonce with `difficulty` leading zero bits (`2^difficulty`)
+#[cfg(feature = "std")]
+pub fn expected_hashes(difficulty: u8) -> f64 {
+    2_f64.powi(difficulty as i32)
+}
+
+/// Estimate the time needed to mine `difficulty` at `hash_rate` hashes/second
+///
+/// Return `None` if the hash rate is unknown (zero).
+#[cfg(feature = "std")]
+pub fn estimate_duration(difficulty: u8, hash_rate: f64) -> Option<Duration> {
+    if hash_rate <= 0.0 || !hash_rate.is_finite() {
+        return None;
+    }
+
+    let secs: f64 = expected_hashes(difficulty) / hash_rate;
+    if secs >= u64::MAX as f64 {
+        Some(Duration::MAX)
+    } else {
+        Some(Duration::from_secs_f64(secs))
+    }
+}
+
+/// POW mining progress
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MiningProgress {
+    /// Target difficulty
+    pub difficulty: u8,
+    /// Hashes tried
+    pub hashes: u64,
+    /// Best difficulty (leading zero bits) found so far
+    pub best_difficulty: u8,
+    /// Elapsed time since the start of mining
+    pub elapsed: Duration,
+}
+
+#[cfg(feature = "std")]
+impl MiningProgress {
+    /// Hash rate (hashes/second)
+    pub fn hash_rate(&self) -> f64 {
+        let secs: f64 = self.elapsed.as_secs_f64();
+        if secs > 0.0 {
+            self.hashes as f64 / secs
+        } else {
+            0.0
+        }
+    }
+
+    /// Estimated remaining time to reach the target difficulty
+    ///
+    /// Mining is memoryless: the remaining time doesn't depend on the hashes already tried.
+    pub fn eta(&self) -> Option<Duration> {
+        if self.best_difficulty >= self.difficulty {
+            return Some(Duration::ZERO);
+        }
+        estimate_duration(self.difficulty, self.hash_rate())
+    }
+}
+
+#[cfg(feature = "std")]
+type ProgressCallback = Arc<dyn Fn(MiningProgress) + Send + Sync>;
+
+/// POW miner
+///
+/// Mine in parallel on [`Miner::threads`] threads, reporting the [`MiningProgress`] every
+/// [`Miner::progress_interval`] to the [`Miner::on_progress`] callback, until a nonce is found
+/// or the [`CancellationToken`] is cancelled.
+///
+/// # Example
+/// ```rust,no_run
+/// use nostr::nips::nip13::Miner;
+/// use nostr::{EventBuilder, Keys};
+///
+/// let keys = Keys::generate();
+/// let miner = Miner::new(20).on_progress(|progress| {
+///     println!(
+///         "{} hashes, best difficulty {}, ETA {:?}",
+///         progress.hashes,
+///         progress.best_difficulty,
+///         progress.eta()
+///     );
+/// });
+/// let event = EventBuilder::text_note("POW text note", [])
+///     .to_pow_event_with_miner(&keys, &miner)
+///     .unwrap();
+/// ```
+#[cfg(feature = "std")]
+#[derive(Clone)]
+pub struct Miner {
+    difficulty: u8,
+    threads: usize,
+    cancel: CancellationToken,
+    progress_interval: Duration,
+    on_progress: Option<ProgressCallback>,
+}
+
+#[cfg(feature = "std")]
+impl fmt::Debug for Miner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Miner")
+            .field("difficulty", &self.difficulty)
+            .field("threads", &self.threads)
+            .field("cancel", &self.cancel)
+            .field("progress_interval", &self.progress_interval)
+            .finish()
+    }
+}
+
+#[cfg(feature = "std")]
+impl Miner {
+    /// New miner for `difficulty`, using all the available threads
+    pub fn new(difficulty: u8) -> Self {
+        Self {
+            difficulty,
+            threads: thread::available_parallelism()
+                .map(|n| n.get())
+                .unwrap_or(1),
+            cancel: CancellationToken::new(),
+            progress_interval: Duration::from_secs(1),
+            on_progress: None,
+        }
+    }
+
+    /// Set number of threads (default: available parallelism)
+    pub fn threads(self, threads: usize) -> Self {
+        Self {
+            threads: threads.max(1),
+            ..self
+        }
+    }
+
+    /// Set cancellation token
+    pub fn cancellation(self, cancel: CancellationToken) -> Self {
+        Self { cancel, ..self }
+    }
+
+    /// Set progress reporting interval (default: 1 sec)
+    pub fn progress_interval(self, interval: Duration) -> Self {
+        Self {
+            progress_interval: interval,
+            ..self
+        }
+    }
+
+    /// Set progress callback
+    ///
+    /// Called every [`Miner::progress_interval`] and once more when mining stops.
+    pub fn on_progress<F>(self, callback: F) -> Self
+    where
+        F: Fn(MiningProgress) + Send + Sync + 'static,
+    {
+        Self {
+            on_progress: Some(Arc::new(callback)),
+            ..self
+        }
+    }
+
+    /// Get target difficulty
+    pub fn difficulty(&self) -> u8 {
+        self.difficulty
+    }
+
+    /// Get cancellation token
+    pub fn cancellation_token(&self) -> &CancellationToken {
+        &self.cancel
+    }
+
+    /// Mine a nonce whose `hash` has at least [`Miner::difficulty`] leading zero bits
+    ///
+    /// The nonces are split across the threads: the thread `i` tries `i + 1`, `i + 1 + threads`, ...
+    ///
+    /// Return `None` if cancelled.
+    pub fn mine<F, T>(&self, hash: F) -> Option<u128>
+    where
+        F: Fn(u128) -> T + Sync,
+        T: AsRef<[u8]>,
+    {
+        self.mine_rounds(None, || (), |_, nonce| hash(nonce))
+            .map(|(_, nonce)| nonce)
+    }
+
+    /// Mine in rounds of `round_duration`, calling `new_round` at the start of each round
+    ///
+    /// The round value is passed to `hash` (i.e. to refresh the event `created_at` while mining)
+    /// and returned with the nonce. The progress is reported across the rounds.
+    ///
+    /// Return `None` if cancelled.
+    pub(crate) fn mine_rounds<R, N, F, T>(
+        &self,
+        round_duration: Option<Duration>,
+        mut new_round: N,
+        hash: F,
+    ) -> Option<(R, u128)>
+    where
+        R: Sync,
+        N: FnMut() -> R,
+        F: Fn(&R, u128) -> T + Sync,
+        T: AsRef<[u8]>,
+    {
+        let state = MiningState {
+            hashes: AtomicU64::new(0),
+            best: AtomicU8::new(0),
+            start: Instant::now(),
+        };
+
+        let mut res: Option<(R, u128)> = None;
+        while !self.cancel.is_cancelled() {
+            let round: R = new_round();
+            let deadline: Option<Instant> = round_duration.map(|d| Instant::now() + d);
+            if let Some(nonce) = self.mine_round(&state, deadline, |nonce| hash(&round, nonce)) {
+                res = Some((round, nonce));
+                break;
+            }
+        }
+
+        if let Some(on_progress) = &self.on_progress {
+            on_progress(state.progress(self.difficulty));
+        }
+
+        res
+    }
+
+    /// Mine until a nonce is found, the miner is cancelled or the `deadline` is reached
+    fn mine_round<F, T>(
+        &self,
+        state: &MiningState,
+        deadline: Option<Instant>,
+        hash: F,
+    ) -> Option<u128>
+    where
+        F: Fn(u128) -> T + Sync,
+        T: AsRef<[u8]>,
+    {
+        let difficulty: u8 = self.difficulty;
+        let threads: usize = self.threads.max(1);
+        let cancel: &CancellationToken = &self.cancel;
+        let found: Mutex<Option<u128>> = Mutex::new(None);
+        let done: AtomicBool = AtomicBool::new(false);
+        let expired = || deadline.map_or(false, |deadline| Instant::now() >= deadline);
+
+        thread::scope(|scope| {
+            for i in 0..threads {
+                let hash = &hash;
+                let found = &found;
+                let done = &done;
+                let expired = &expired;
+                scope.spawn(move || {
+                    let mut nonce: u128 = i as u128 + 1;
+                    let mut count: u64 = 0;
+                    while !done.load(Ordering::Relaxed) && !cancel.is_cancelled() {
+                        let leading_zeros: u8 = get_leading_zero_bits(hash(nonce));
+                        count += 1;
+
+                        if leading_zeros > state.best.load(Ordering::Relaxed) {
+                            state.best.fetch_max(leading_zeros, Ordering::Relaxed);
+                        }
+
+                        if leading_zeros >= difficulty {
+                            done.store(true, Ordering::Relaxed);
+                            if let Ok(mut found) = found.lock() {
+                                found.get_or_insert(nonce);
+                            }
+                            break;
+                        }
+
+                        // Flush the counter (and check the deadline) in batches, to not contend on the atomic
+                        if count == HASHES_BATCH {
+                            state.hashes.fetch_add(count, Ordering::Relaxed);
+                            count = 0;
+
+                            if expired() {
+                                break;
+                            }
+                        }
+
+                        nonce += threads as u128;
+                    }
+                    state.hashes.fetch_add(count, Ordering::Relaxed);
+                });
+            }
+
+            // Report progress from the calling thread
+            if let Some(on_progress) = &self.on_progress {
+                let mut last_report = Instant::now();
+                while !done.load(Ordering::Relaxed) && !cancel.is_cancelled() && !expired() {
+                    thread::sleep(PROGRESS_POLL_INTERVAL.min(self.progress_interval));
+                    if last_report.elapsed() >= self.progress_interval {
+                        on_progress(state.progress(difficulty));
+                        last_report = Instant::now();
+                    }
+                }
+            }
+        });
+
+        found.into_inner().ok().flatten()
+    }
+}
+
+/// Counters shared by the mining threads, across the rounds
+#[cfg(feature = "std")]
+struct MiningState {
+    hashes: AtomicU64,
+    best: AtomicU8,
+    start: Instant,
+}
+
+#[cfg(feature = "std")]
+impl MiningState {
+    fn progress(&self, difficulty: u8) -> MiningProgress {
+        MiningProgress {
+            difficulty,
+            hashes: self.hashes.load(Ordering::Relaxed),
+            best_difficulty: self.best.load(Ordering::Relaxed),
+            elapsed: self.start.elapsed(),
+        }
+    }
+}
+
+/// Mine a nonce whose `hash` has at least `difficulty` leading zero bits, using `threads` threads
+///
+/// The nonces are split across the threads: the thread `i` tries `i + 1`, `i + 1 + threads`, ...
+/// Use [`Miner`] to get progress reports.
+///
+/// Return `None` if cancelled.
+#[cfg(feature = "std")]
+pub fn mine<F, T>(
+    difficulty: u8,
+    threads: usize,
+    cancel: &CancellationToken,
+    hash: F,
+) -> Option<u128>
+where
+    F: Fn(u128) -> T + Sync,
+    T: AsRef<[u8]>,
+{
+    Miner::new(difficulty)
+        .threads(threads)
+        .cancellation(cancel.clone())
+        .mine(hash)
+}
+
 #[cfg(test)]
 pub mod tests {
     use core::str::FromStr;
@@ -434,4 +823,67 @@ pub mod tests {
             ]
         );
     }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_mine() {
+        use bitcoin::hashes::Hash;
+
+        let hash = |nonce: u128| Sha256Hash::hash(&nonce.to_be_bytes());
+        let cancel = CancellationToken::new();
+        let nonce: u128 = mine(12, 4, &cancel, hash).unwrap();
+        assert!(get_leading_zero_bits(hash(nonce)) >= 12);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_miner_progress() {
+        use bitcoin::hashes::Hash;
+
+        let reports: Arc<Mutex<Vec<MiningProgress>>> = Arc::new(Mutex::new(Vec::new()));
+        let r = reports.clone();
+        let miner = Miner::new(12)
+            .threads(2)
+            .progress_interval(Duration::from_millis(1))
+            .on_progress(move |progress| r.lock().unwrap().push(progress));
+        let hash = |nonce: u128| Sha256Hash::hash(&nonce.to_be_bytes());
+        let nonce: u128 = miner.mine(hash).unwrap();
+        assert!(get_leading_zero_bits(hash(nonce)) >= 12);
+
+        // At least the final report
+        let reports = reports.lock().unwrap();
+        let last: &MiningProgress = reports.last().unwrap();
+        assert_eq!(last.difficulty, 12);
+        assert!(last.hashes > 0);
+        assert!(last.best_difficulty >= 12);
+        assert_eq!(last.eta(), Some(Duration::ZERO));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_estimate_duration() {
+        assert_eq!(expected_hashes(0), 1.0);
+        assert_eq!(expected_hashes(10), 1024.0);
+        assert_eq!(estimate_duration(10, 1024.0), Some(Duration::from_secs(1)));
+        assert_eq!(estimate_duration(10, 0.0), None);
+        assert_eq!(estimate_duration(255, 1.0), Some(Duration::MAX));
+
+        let progress = MiningProgress {
+            difficulty: 20,
+            hashes: 2048,
+            best_difficulty: 9,
+            elapsed: Duration::from_secs(2),
+        };
+        assert_eq!(progress.hash_rate(), 1024.0);
+        assert_eq!(progress.eta(), Some(Duration::from_secs(1024)));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_mine_cancelled() {
+        let cancel = CancellationToken::new();
+        cancel.clone().cancel();
+        assert!(cancel.is_cancelled());
+        assert!(mine(255, 2, &cancel, |_| [0xff_u8; 32]).is_none());
+    }
 }
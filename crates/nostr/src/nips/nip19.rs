@@ -19,7 +19,7 @@ use bitcoin::secp256k1::{self, SecretKey, XOnlyPublicKey};
 
 use super::nip01::Coordinate;
 use crate::event::id::{self, EventId};
-use crate::Kind;
+use crate::{Filter, Kind};
 
 pub const PREFIX_BECH32_SECRET_KEY: &str = "nsec";
 pub const PREFIX_BECH32_PUBLIC_KEY: &str = "npub";
@@ -312,6 +312,7 @@ impl ToBech32 for EventId {
 pub struct Nip19Event {
     pub event_id: EventId,
     pub author: Option<XOnlyPublicKey>,
+    pub kind: Option<Kind>,
     pub relays: Vec<String>,
 }
 
@@ -324,13 +325,27 @@ impl Nip19Event {
         Self {
             event_id,
             author: None,
+            kind: None,
             relays: relays.into_iter().map(|u| u.into()).collect(),
         }
     }
 
+    /// Set author
+    pub fn author(mut self, author: XOnlyPublicKey) -> Self {
+        self.author = Some(author);
+        self
+    }
+
+    /// Set kind
+    pub fn kind(mut self, kind: Kind) -> Self {
+        self.kind = Some(kind);
+        self
+    }
+
     fn from_bech32_data(mut data: Vec<u8>) -> Result<Self, Error> {
         let mut event_id: Option<EventId> = None;
         let mut author: Option<XOnlyPublicKey> = None;
+        let mut kind: Option<Kind> = None;
         let mut relays: Vec<String> = Vec::new();
 
         while !data.is_empty() {
@@ -353,6 +368,12 @@ impl Nip19Event {
                         author = Some(XOnlyPublicKey::from_slice(bytes)?);
                     }
                 }
+                KIND if kind.is_none() => {
+                    let k: u64 =
+                        u32::from_be_bytes(bytes.try_into().map_err(|_| Error::TryFromSlice)?)
+                            as u64;
+                    kind = Some(Kind::from(k));
+                }
                 RELAY => {
                     relays.push(String::from_utf8(bytes.to_vec())?);
                 }
@@ -365,6 +386,7 @@ impl Nip19Event {
         Ok(Self {
             event_id: event_id.ok_or_else(|| Error::FieldMissing("event id".to_string()))?,
             author,
+            kind,
             relays,
         })
     }
@@ -400,11 +422,36 @@ impl ToBech32 for Nip19Event {
             bytes.extend(relay.as_bytes());
         }
 
+        // Author
+        if let Some(author) = &self.author {
+            bytes.extend([AUTHOR, 32]);
+            bytes.extend(author.serialize());
+        }
+
+        // Kind
+        if let Some(kind) = &self.kind {
+            bytes.extend([KIND, 4]);
+            bytes.extend(kind.as_u32().to_be_bytes());
+        }
+
         let data = bytes.to_base32();
         Ok(bech32::encode(PREFIX_BECH32_EVENT, data, Variant::Bech32)?)
     }
 }
 
+impl From<Nip19Event> for Filter {
+    fn from(value: Nip19Event) -> Self {
+        let mut filter: Filter = Filter::new().id(value.event_id);
+        if let Some(author) = value.author {
+            filter = filter.author(author);
+        }
+        if let Some(kind) = value.kind {
+            filter = filter.kind(kind);
+        }
+        filter
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub struct Nip19Profile {
     pub public_key: XOnlyPublicKey,
@@ -677,4 +724,30 @@ mod tests {
         let event = Nip19Event::from_bech32(nevent).unwrap();
         assert_eq!(event.author, Some(expected_pubkey));
     }
+
+    #[test]
+    fn nevent_author_and_kind_roundtrip() {
+        let event_id =
+            EventId::from_hex("d94a3f4dd87b9a3b0bed183b32e916fa29c8020107845d1752d72697fe5309a5")
+                .unwrap();
+        let author = XOnlyPublicKey::from_str(
+            "32e1827635450ebb3c5a7d12c1f8e7b2b514439ac10a67eef3d9fd9c5c68e245",
+        )
+        .unwrap();
+        let nevent = Nip19Event::new(event_id, ["wss://relay.damus.io"])
+            .author(author)
+            .kind(Kind::TextNote);
+
+        let decoded = Nip19Event::from_bech32(nevent.to_bech32().unwrap()).unwrap();
+        assert_eq!(decoded, nevent);
+
+        let filter: Filter = decoded.into();
+        assert_eq!(
+            filter,
+            Filter::new()
+                .id(event_id)
+                .author(author)
+                .kind(Kind::TextNote)
+        );
+    }
 }
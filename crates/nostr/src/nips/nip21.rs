@@ -14,6 +14,7 @@ use bitcoin::secp256k1::XOnlyPublicKey;
 use super::nip01::Coordinate;
 use super::nip19::{self, FromBech32, Nip19, Nip19Event, Nip19Profile, ToBech32};
 use crate::event::id::EventId;
+use crate::{Filter, Kind};
 
 /// URI scheme
 pub const SCHEME: &str = "nostr";
@@ -168,6 +169,25 @@ impl Nip21 {
             Self::Coordinate(val) => Ok(val.to_bech32()?),
         }
     }
+
+    /// Compose [`Filter`] to fetch the referenced entity
+    ///
+    /// For `npub` and `nprofile`, the filter matches the profile metadata.
+    pub fn to_filter(&self) -> Filter {
+        match self {
+            Self::Pubkey(public_key) => Filter::new()
+                .author(*public_key)
+                .kind(Kind::Metadata)
+                .limit(1),
+            Self::Profile(profile) => Filter::new()
+                .author(profile.public_key)
+                .kind(Kind::Metadata)
+                .limit(1),
+            Self::EventId(id) => Filter::new().id(*id),
+            Self::Event(event) => Filter::from(event.clone()),
+            Self::Coordinate(coordinate) => Filter::from(coordinate.clone()).limit(1),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -220,6 +240,42 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_to_filter() {
+        let public_key = XOnlyPublicKey::from_str(
+            "32e1827635450ebb3c5a7d12c1f8e7b2b514439ac10a67eef3d9fd9c5c68e245",
+        )
+        .unwrap();
+
+        let nprofile = Nip21::parse("nostr:nprofile1qqsr9cvzwc652r4m83d86ykplrnm9dg5gwdvzzn8ameanlvut35wy3gpz3mhxue69uhhyetvv9ujuerpd46hxtnfduyu75sw").unwrap();
+        assert_eq!(
+            nprofile.to_filter(),
+            Filter::new()
+                .author(public_key)
+                .kind(Kind::Metadata)
+                .limit(1)
+        );
+
+        let event_id =
+            EventId::from_hex("d94a3f4dd87b9a3b0bed183b32e916fa29c8020107845d1752d72697fe5309a5")
+                .unwrap();
+        let note =
+            Nip21::parse("nostr:note1m99r7nwc0wdrkzldrqan96gklg5usqspq7z9696j6unf0ljnpxjspqfw99")
+                .unwrap();
+        assert_eq!(note.to_filter(), Filter::new().id(event_id));
+
+        let coordinate = Coordinate::new(Kind::LongFormTextNote, public_key).identifier("article");
+        let naddr = Nip21::parse(coordinate.to_nostr_uri().unwrap()).unwrap();
+        assert_eq!(
+            naddr.to_filter(),
+            Filter::new()
+                .kind(Kind::LongFormTextNote)
+                .author(public_key)
+                .identifier("article")
+                .limit(1)
+        );
+    }
+
     #[test]
     fn test_unsupported_from_nostr_uri() {
         assert_eq!(
@@ -0,0 +1,159 @@
+// Copyright (c) 2022-2023 Yuki Kishimoto
+// Copyright (c) 2023-2024 Rust Nostr Developers
+// Distributed under the MIT software license
+
+//! NIP25
+//!
+//! <https://github.com/nostr-protocol/nips/blob/master/25.md>
+
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use bitcoin::secp256k1::XOnlyPublicKey;
+use url_fork::Url;
+
+use crate::{Event, EventId, Kind, Tag, TagKind};
+
+/// External content kind for websites (`k` tag)
+pub const WEB_CONTENT_KIND: &str = "web";
+
+/// External content target of a reaction
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ExternalContent {
+    /// Website URL (`r` tag)
+    Url(Url),
+    /// External content ID (`i` tag, i.e. `isbn:9780765382030`)
+    Id(String),
+}
+
+impl From<Url> for ExternalContent {
+    fn from(url: Url) -> Self {
+        Self::Url(url)
+    }
+}
+
+impl ExternalContent {
+    /// Compose reaction tags
+    pub fn tags(&self) -> Vec<Tag> {
+        match self {
+            Self::Url(url) => vec![
+                Tag::Reference(url.to_string()),
+                Tag::Generic(TagKind::K, vec![WEB_CONTENT_KIND.to_string()]),
+            ],
+            Self::Id(id) => vec![Tag::Generic(TagKind::I, vec![id.clone()])],
+        }
+    }
+}
+
+/// Aggregated reactions of an event
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Reactions {
+    /// Total number of reactions
+    pub total: usize,
+    /// Number of likes (`+` or empty content)
+    pub likes: usize,
+    /// Number of dislikes (`-` content)
+    pub dislikes: usize,
+    /// Number of reactions by content (i.e. `+`, `-` or emoji)
+    pub by_content: BTreeMap<String, usize>,
+    /// Reactions by author
+    pub by_author: BTreeMap<XOnlyPublicKey, Vec<String>>,
+}
+
+impl Reactions {
+    /// Aggregate the reactions (kind `7`) to `event_id`
+    ///
+    /// The reacted event is the last `e` tag. Other events, duplicated events and
+    /// the same reaction sent multiple times by the same author are skipped.
+    pub fn from_events<'a, I>(event_id: &EventId, events: I) -> Self
+    where
+        I: IntoIterator<Item = &'a Event>,
+    {
+        let mut reactions = Self::default();
+        let mut seen: BTreeSet<(XOnlyPublicKey, String)> = BTreeSet::new();
+
+        for event in events.into_iter() {
+            if event.kind() != Kind::Reaction || event.event_ids().last() != Some(event_id) {
+                continue;
+            }
+
+            let content: String = match event.content() {
+                "" => String::from("+"),
+                content => content.to_string(),
+            };
+
+            if !seen.insert((event.author(), content.clone())) {
+                continue;
+            }
+
+            reactions.total += 1;
+            match content.as_str() {
+                "+" => reactions.likes += 1,
+                "-" => reactions.dislikes += 1,
+                _ => (),
+            }
+            *reactions.by_content.entry(content.clone()).or_default() += 1;
+            reactions
+                .by_author
+                .entry(event.author())
+                .or_default()
+                .push(content);
+        }
+
+        reactions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "std")]
+    use crate::{EventBuilder, Keys};
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_reactions() {
+        let alice = Keys::generate();
+        let bob = Keys::generate();
+        let note = EventBuilder::text_note("Hello", [])
+            .to_event(&alice)
+            .unwrap();
+        let other = EventBuilder::text_note("Other", [])
+            .to_event(&alice)
+            .unwrap();
+
+        let events = vec![
+            EventBuilder::reaction(note.id(), alice.public_key(), "+")
+                .to_event(&alice)
+                .unwrap(),
+            EventBuilder::reaction(note.id(), alice.public_key(), "")
+                .to_event(&bob)
+                .unwrap(),
+            EventBuilder::reaction(note.id(), alice.public_key(), "+")
+                .to_event(&bob)
+                .unwrap(),
+            EventBuilder::reaction(note.id(), alice.public_key(), "-")
+                .to_event(&bob)
+                .unwrap(),
+            EventBuilder::reaction(note.id(), alice.public_key(), "🤙")
+                .to_event(&bob)
+                .unwrap(),
+            EventBuilder::reaction(other.id(), alice.public_key(), "+")
+                .to_event(&bob)
+                .unwrap(),
+            note.clone(),
+        ];
+
+        let reactions = Reactions::from_events(&note.id(), &events);
+        assert_eq!(reactions.total, 4);
+        assert_eq!(reactions.likes, 2);
+        assert_eq!(reactions.dislikes, 1);
+        assert_eq!(reactions.by_content.get("🤙"), Some(&1));
+        assert_eq!(
+            reactions.by_author.get(&alice.public_key()).unwrap().len(),
+            1
+        );
+        assert_eq!(reactions.by_author.get(&bob.public_key()).unwrap().len(), 3);
+    }
+}
@@ -0,0 +1,312 @@
+// Copyright (c) 2022-2023 Yuki Kishimoto
+// Copyright (c) 2023-2024 Rust Nostr Developers
+// Distributed under the MIT software license
+
+//! NIP27
+//!
+//! Parse event content references (`nostr:` URIs), hashtags and URLs.
+//!
+//! <https://github.com/nostr-protocol/nips/blob/master/27.md>
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use super::nip21::{Nip21, SCHEME};
+use crate::{Tag, UncheckedUrl, Url};
+
+const HTTP_SCHEMES: [&str; 2] = ["https://", "http://"];
+
+/// Content entity
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Entity {
+    /// `nostr:` URI (`npub`, `nprofile`, `note`, `nevent` or `naddr`)
+    Nostr(Nip21),
+    /// Hashtag, without the leading `#`
+    Hashtag(String),
+    /// HTTP(S) URL
+    Url(Url),
+}
+
+impl Entity {
+    /// Compose the [`Tag`] referencing the entity
+    ///
+    /// * hashtags: `t` tag (lowercase);
+    /// * URLs: `r` tag;
+    /// * `npub` and `nprofile`: `p` tag;
+    /// * `note` and `nevent`: `q` tag;
+    /// * `naddr`: `a` tag.
+    pub fn to_tag(&self) -> Tag {
+        match self {
+            Self::Hashtag(hashtag) => Tag::Hashtag(hashtag.to_lowercase()),
+            Self::Url(url) => Tag::Reference(url.to_string()),
+            Self::Nostr(Nip21::Pubkey(public_key)) => Tag::public_key(*public_key),
+            Self::Nostr(Nip21::Profile(profile)) => Tag::PublicKey {
+                public_key: profile.public_key,
+                relay_url: profile.relays.first().map(UncheckedUrl::from),
+                alias: None,
+                uppercase: false,
+            },
+            Self::Nostr(Nip21::EventId(event_id)) => Tag::Quote {
+                event_id: *event_id,
+                relay_url: None,
+            },
+            Self::Nostr(Nip21::Event(event)) => Tag::Quote {
+                event_id: event.event_id,
+                relay_url: event.relays.first().map(UncheckedUrl::from),
+            },
+            Self::Nostr(Nip21::Coordinate(coordinate)) => Tag::from(coordinate.clone()),
+        }
+    }
+}
+
+/// Content entity with its byte range in the content
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContentEntity {
+    /// Entity
+    pub entity: Entity,
+    /// Byte range in the content
+    pub range: Range<usize>,
+}
+
+/// Parse content entities
+///
+/// Invalid `nostr:` URIs and URLs are skipped. Entities are returned in order of appearance.
+///
+/// # Example
+/// ```rust
+/// use nostr::nips::nip27::{self, Entity};
+///
+/// let content = "GM #nostr! https://example.com";
+/// let entities = nip27::parse_content(content);
+/// assert_eq!(entities.len(), 2);
+/// assert_eq!(entities[0].entity, Entity::Hashtag(String::from("nostr")));
+/// assert_eq!(&content[entities[1].range.clone()], "https://example.com");
+/// ```
+pub fn parse_content<S>(content: S) -> Vec<ContentEntity>
+where
+    S: AsRef<str>,
+{
+    let content: &str = content.as_ref();
+    let mut entities: Vec<ContentEntity> = Vec::new();
+    let mut pos: usize = 0;
+
+    while pos < content.len() {
+        let rest: &str = &content[pos..];
+        let entity: Option<(Entity, usize)> = if is_boundary(content, pos) {
+            parse_nostr(rest)
+                .or_else(|| parse_url(rest))
+                .or_else(|| parse_hashtag(rest))
+        } else {
+            None
+        };
+
+        match entity {
+            Some((entity, len)) => {
+                entities.push(ContentEntity {
+                    entity,
+                    range: pos..pos + len,
+                });
+                pos += len;
+            }
+            None => {
+                // Move to the next char
+                pos += rest.chars().next().map_or(1, char::len_utf8);
+            }
+        }
+    }
+
+    entities
+}
+
+/// Extract the tags referencing the entities of the content (see [`Entity::to_tag`])
+///
+/// Duplicated tags are skipped.
+pub fn extract_tags<S>(content: S) -> Vec<Tag>
+where
+    S: AsRef<str>,
+{
+    let mut tags: Vec<Tag> = Vec::new();
+    for entity in parse_content(content).into_iter() {
+        let tag: Tag = entity.entity.to_tag();
+        if !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    tags
+}
+
+/// Check if an entity can start at `pos` (not in the middle of a word)
+fn is_boundary(content: &str, pos: usize) -> bool {
+    match content[..pos].chars().next_back() {
+        Some(c) => !c.is_alphanumeric() && c != '_' && c != '#' && c != '/',
+        None => true,
+    }
+}
+
+fn parse_nostr(rest: &str) -> Option<(Entity, usize)> {
+    let data: &str = rest.strip_prefix(SCHEME)?.strip_prefix(':')?;
+    let len: usize = data
+        .find(|c: char| !c.is_ascii_alphanumeric())
+        .unwrap_or(data.len());
+    let uri_len: usize = SCHEME.len() + 1 + len;
+    let nip21: Nip21 = Nip21::parse(&rest[..uri_len]).ok()?;
+    Some((Entity::Nostr(nip21), uri_len))
+}
+
+fn parse_url(rest: &str) -> Option<(Entity, usize)> {
+    if !HTTP_SCHEMES.iter().any(|scheme| rest.starts_with(scheme)) {
+        return None;
+    }
+
+    let end: usize = rest.find(char::is_whitespace).unwrap_or(rest.len());
+    let mut url: &str = &rest[..end];
+
+    // Trim trailing punctuation (i.e. end of sentence), keeping balanced parentheses
+    while let Some(c) = url.chars().next_back() {
+        let trim: bool = match c {
+            '.' | ',' | ';' | ':' | '!' | '?' | '\'' | '"' | ']' | '}' | '>' => true,
+            ')' => url.matches('(').count() < url.matches(')').count(),
+            _ => false,
+        };
+        if !trim {
+            break;
+        }
+        url = &url[..url.len() - c.len_utf8()];
+    }
+
+    let parsed: Url = Url::parse(url).ok()?;
+    parsed.host_str()?;
+    Some((Entity::Url(parsed), url.len()))
+}
+
+fn parse_hashtag(rest: &str) -> Option<(Entity, usize)> {
+    let tag: &str = rest.strip_prefix('#')?;
+    let len: usize = tag
+        .find(|c: char| !c.is_alphanumeric() && c != '_')
+        .unwrap_or(tag.len());
+    let tag: &str = &tag[..len];
+
+    // Skip empty or numeric-only hashtags (i.e. `#1`)
+    if tag.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    Some((Entity::Hashtag(tag.to_string()), len + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use core::str::FromStr;
+
+    use bitcoin::secp256k1::XOnlyPublicKey;
+
+    use super::*;
+    use crate::nips::nip19::Nip19Profile;
+
+    #[test]
+    fn test_parse_nostr_uris() {
+        let content = "Hello nostr:npub14f8usejl26twx0dhuxjh9cas7keav9vr0v8nvtwtrjqx3vycc76qqh9nsy, follow nostr:nprofile1qqsr9cvzwc652r4m83d86ykplrnm9dg5gwdvzzn8ameanlvut35wy3gpz3mhxue69uhhyetvv9ujuerpd46hxtnfduyu75sw.";
+        let entities = parse_content(content);
+        assert_eq!(entities.len(), 2);
+
+        let pubkey = XOnlyPublicKey::from_str(
+            "aa4fc8665f5696e33db7e1a572e3b0f5b3d615837b0f362dcb1c8068b098c7b4",
+        )
+        .unwrap();
+        assert_eq!(entities[0].entity, Entity::Nostr(Nip21::Pubkey(pubkey)));
+        assert_eq!(
+            &content[entities[0].range.clone()],
+            "nostr:npub14f8usejl26twx0dhuxjh9cas7keav9vr0v8nvtwtrjqx3vycc76qqh9nsy"
+        );
+
+        let profile = Nip19Profile::new(
+            XOnlyPublicKey::from_str(
+                "32e1827635450ebb3c5a7d12c1f8e7b2b514439ac10a67eef3d9fd9c5c68e245",
+            )
+            .unwrap(),
+            ["wss://relay.damus.io"],
+        );
+        assert_eq!(entities[1].entity, Entity::Nostr(Nip21::Profile(profile)));
+        assert!(content[entities[1].range.clone()].starts_with("nostr:nprofile1"));
+        assert_eq!(entities[1].range.end, content.len() - 1);
+    }
+
+    #[test]
+    fn test_extract_tags() {
+        let content = "GM #Nostr #nostr, https://example.com nostr:npub14f8usejl26twx0dhuxjh9cas7keav9vr0v8nvtwtrjqx3vycc76qqh9nsy nostr:note1fntxtkcy9pjwucqwa9mddn7v03wwwsu9j330jj350nvhpky2tuaspk6nqc";
+        let tags = extract_tags(content);
+        assert_eq!(
+            tags.iter().map(|t| t.as_vec()).collect::<Vec<_>>(),
+            vec![
+                vec!["t", "nostr"],
+                vec!["r", "https://example.com/"],
+                vec![
+                    "p",
+                    "aa4fc8665f5696e33db7e1a572e3b0f5b3d615837b0f362dcb1c8068b098c7b4"
+                ],
+                vec![
+                    "q",
+                    "4cd665db042864ee600ee976d6cfcc7c5ce743859462f94a347cd970d88a5f3b"
+                ],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid_nostr_uri() {
+        assert!(parse_content("nostr:npub1invalid and nostr:").is_empty());
+        // Not at a word boundary
+        assert!(parse_content(
+            "xnostr:npub14f8usejl26twx0dhuxjh9cas7keav9vr0v8nvtwtrjqx3vycc76qqh9nsy"
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn test_parse_hashtags() {
+        let content = "#GM from #rust_nostr, see issue #123 or a#b ##";
+        let entities = parse_content(content);
+        assert_eq!(
+            entities,
+            vec![
+                ContentEntity {
+                    entity: Entity::Hashtag(String::from("GM")),
+                    range: 0..3,
+                },
+                ContentEntity {
+                    entity: Entity::Hashtag(String::from("rust_nostr")),
+                    range: 9..20,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_urls() {
+        let content = "Check https://example.com/path?q=1#frag. (see http://en.wikipedia.org/wiki/Nostr_(protocol)) ftp://example.com";
+        let entities = parse_content(content);
+        assert_eq!(entities.len(), 2);
+        assert_eq!(
+            &content[entities[0].range.clone()],
+            "https://example.com/path?q=1#frag"
+        );
+        assert_eq!(
+            entities[0].entity,
+            Entity::Url(Url::parse("https://example.com/path?q=1#frag").unwrap())
+        );
+        assert_eq!(
+            &content[entities[1].range.clone()],
+            "http://en.wikipedia.org/wiki/Nostr_(protocol)"
+        );
+    }
+
+    #[test]
+    fn test_parse_unicode() {
+        let content = "ciào #café 🚀 https://example.com";
+        let entities = parse_content(content);
+        assert_eq!(entities.len(), 2);
+        assert_eq!(&content[entities[0].range.clone()], "#café");
+        assert_eq!(&content[entities[1].range.clone()], "https://example.com");
+    }
+}
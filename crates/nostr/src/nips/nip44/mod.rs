@@ -22,7 +22,7 @@ use chacha20::XChaCha20;
 
 pub mod v2;
 
-use self::v2::ConversationKey;
+pub use self::v2::ConversationKey;
 use crate::util;
 
 /// Error
@@ -221,6 +221,43 @@ where
     }
 }
 
+/// Encrypt with a pre-computed [`ConversationKey`] (V2) - EXPERIMENTAL
+///
+/// The [`ConversationKey`] can be derived once per peer and reused for many messages.
+#[cfg(feature = "std")]
+pub fn encrypt_with_conversation_key<T>(
+    conversation_key: &ConversationKey,
+    content: T,
+) -> Result<String, Error>
+where
+    T: AsRef<[u8]>,
+{
+    v2::encrypt(conversation_key, content)
+}
+
+/// Decrypt with a pre-computed [`ConversationKey`]
+///
+/// Only V2 payloads are supported.
+pub fn decrypt_with_conversation_key<T>(
+    conversation_key: &ConversationKey,
+    payload: T,
+) -> Result<String, Error>
+where
+    T: AsRef<[u8]>,
+{
+    // Decode base64 payload
+    let payload: Vec<u8> = general_purpose::STANDARD.decode(payload)?;
+
+    // Get version byte
+    let version: u8 = *payload.first().ok_or(Error::VersionNotFound)?;
+
+    match Version::try_from(version)? {
+        Version::V2 => v2::decrypt(conversation_key, &payload),
+        #[allow(deprecated)]
+        Version::V1 => Err(Error::UnknownVersion(version)),
+    }
+}
+
 #[cfg(test)]
 #[cfg(feature = "std")]
 mod tests {
@@ -295,4 +332,42 @@ mod tests {
             String::from("a")
         );
     }
+
+    #[test]
+    fn test_nip44_conversation_key() {
+        let alice_sk =
+            SecretKey::from_str("5c0c523f52a5b6fad39ed2403092df8cebc36318b39383bca6c00808626fab3a")
+                .unwrap();
+        let bob_sk =
+            SecretKey::from_str("4b22aa260e4acb7021e32f38a6cdf4b673c6a277755bfce287e370c924dc936d")
+                .unwrap();
+        let secp = Secp256k1::new();
+        let alice_pk = XOnlyPublicKey::from_keypair(&KeyPair::from_secret_key(&secp, &alice_sk)).0;
+        let bob_pk = XOnlyPublicKey::from_keypair(&KeyPair::from_secret_key(&secp, &bob_sk)).0;
+
+        // Same key for both sides of the conversation
+        let alice_conversation_key = ConversationKey::derive(&alice_sk, &bob_pk);
+        let bob_conversation_key = ConversationKey::derive(&bob_sk, &alice_pk);
+        assert_eq!(alice_conversation_key, bob_conversation_key);
+
+        let content = String::from("hello");
+        let encrypted_content =
+            encrypt_with_conversation_key(&alice_conversation_key, &content).unwrap();
+        assert_eq!(
+            decrypt_with_conversation_key(&bob_conversation_key, &encrypted_content).unwrap(),
+            content
+        );
+        assert_eq!(
+            decrypt(&bob_sk, &alice_pk, &encrypted_content).unwrap(),
+            content
+        );
+
+        // V1 payloads are not supported
+        let payload =
+            "AUXEhLosA5eFMYOtumkiFW4Joq1OPmkU8k/25+3+VDFvOU39qkUDl1aiy8Q+0ozTwbhD57VJoIYayYS++hE=";
+        assert_eq!(
+            decrypt_with_conversation_key(&bob_conversation_key, payload).unwrap_err(),
+            Error::UnknownVersion(1)
+        );
+    }
 }
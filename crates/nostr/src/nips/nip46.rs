@@ -30,7 +30,7 @@ use crate::event::unsigned::{self, UnsignedEvent};
 use crate::key::{self, Keys};
 #[cfg(feature = "std")]
 use crate::SECP256K1;
-use crate::{Event, JsonUtil};
+use crate::{Event, EventTemplate, JsonUtil};
 
 /// NIP46 error
 #[derive(Debug)]
@@ -134,8 +134,15 @@ pub enum Request {
     GetPublicKey,
     /// Sign [`UnsignedEvent`]
     SignEvent(UnsignedEvent),
+    /// Sign [`EventTemplate`] (without `id` and `pubkey`, as sent by other signer clients)
+    SignEventTemplate(EventTemplate),
     /// Connect
-    Connect(XOnlyPublicKey),
+    Connect {
+        /// Public key
+        public_key: XOnlyPublicKey,
+        /// Optional secret (i.e. from [`BunkerURI`])
+        secret: Option<String>,
+    },
     /// Disconnect
     Disconnect,
     /// Delegate
@@ -169,8 +176,8 @@ impl Request {
         match self {
             Self::Describe => "describe".to_string(),
             Self::GetPublicKey => "get_public_key".to_string(),
-            Self::SignEvent(_) => "sign_event".to_string(),
-            Self::Connect(_) => "connect".to_string(),
+            Self::SignEvent(_) | Self::SignEventTemplate(_) => "sign_event".to_string(),
+            Self::Connect { .. } => "connect".to_string(),
             Self::Disconnect => "disconnect".to_string(),
             Self::Delegate { .. } => "delegate".to_string(),
             Self::Nip04Encrypt { .. } => "nip04_encrypt".to_string(),
@@ -185,7 +192,14 @@ impl Request {
             Self::Describe => Vec::new(),
             Self::GetPublicKey => Vec::new(),
             Self::SignEvent(event) => vec![json!(event)],
-            Self::Connect(pubkey) => vec![json!(pubkey)],
+            Self::SignEventTemplate(template) => vec![json!(template)],
+            Self::Connect { public_key, secret } => {
+                let mut params = vec![json!(public_key)];
+                if let Some(secret) = secret {
+                    params.push(json!(secret));
+                }
+                params
+            }
             Self::Disconnect => Vec::new(),
             Self::Delegate {
                 public_key,
@@ -231,7 +245,13 @@ impl Request {
                 let signed_event = unsigned_event.sign_with_ctx(secp, rng, keys)?;
                 Some(Response::SignEvent(signed_event))
             }
-            Self::Connect(_) => None,
+            Self::SignEventTemplate(template) => {
+                let signed_event = template
+                    .to_unsigned_event(keys.public_key())
+                    .sign_with_ctx(secp, rng, keys)?;
+                Some(Response::SignEvent(signed_event))
+            }
+            Self::Connect { .. } => None,
             Self::Disconnect => None,
             Self::Delegate {
                 public_key,
@@ -284,6 +304,8 @@ pub struct DelegationResult {
 /// Response
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum Response {
+    /// Connect acknowledged
+    Connect,
     /// Describe
     Describe(Vec<String>),
     /// Get public key
@@ -351,6 +373,7 @@ impl Message {
         Self::Response {
             id: req_id.into(),
             result: res.map(|res| match res {
+                Response::Connect => json!(CONNECT_ACK),
                 Response::Describe(v) => json!(v),
                 Response::GetPublicKey(pubkey) => json!(pubkey),
                 Response::SignEvent(sig) => json!(sig),
@@ -387,20 +410,30 @@ impl Message {
                 "get_public_key" => Ok(Request::GetPublicKey),
                 "sign_event" => {
                     if let Some(value) = params.first() {
-                        let unsigned_event: UnsignedEvent =
-                            serde_json::from_value(value.to_owned())?;
-                        Ok(Request::SignEvent(unsigned_event))
+                        // Other signer clients send just the event template
+                        match serde_json::from_value::<UnsignedEvent>(value.to_owned()) {
+                            Ok(unsigned_event) => Ok(Request::SignEvent(unsigned_event)),
+                            Err(_) => {
+                                let template: EventTemplate =
+                                    serde_json::from_value(value.to_owned())?;
+                                Ok(Request::SignEventTemplate(template))
+                            }
+                        }
                     } else {
                         Err(Error::InvalidRequest)
                     }
                 }
                 "connect" => {
-                    if params.len() != 1 {
+                    if params.is_empty() || params.len() > 2 {
                         return Err(Error::InvalidParamsLength);
                     }
 
-                    let pubkey: XOnlyPublicKey = serde_json::from_value(params[0].to_owned())?;
-                    Ok(Request::Connect(pubkey))
+                    let public_key: XOnlyPublicKey = serde_json::from_value(params[0].to_owned())?;
+                    let secret: Option<String> = match params.get(1) {
+                        Some(value) => serde_json::from_value(value.to_owned())?,
+                        None => None,
+                    };
+                    Ok(Request::Connect { public_key, secret })
                 }
                 "disconnect" => Ok(Request::Disconnect),
                 "delegate" => {
@@ -498,6 +531,10 @@ where
 
 /// NIP46 URI Scheme
 pub const NOSTR_CONNECT_URI_SCHEME: &str = "nostrconnect";
+/// Result of an accepted `connect` request
+pub const CONNECT_ACK: &str = "ack";
+/// Bunker URI scheme
+pub const BUNKER_URI_SCHEME: &str = "bunker";
 
 /// Nostr Connect Metadata
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
@@ -680,11 +717,112 @@ impl fmt::Display for NostrConnectURI {
     }
 }
 
+/// Bunker URI
+///
+/// `bunker://<signer-pubkey>?relay=<relay-url>&secret=<optional-secret>`
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct BunkerURI {
+    /// Signer Pubkey
+    pub signer_public_key: XOnlyPublicKey,
+    /// URLs of the relays where the `Signer` is listening for requests.
+    pub relays: Vec<Url>,
+    /// Optional secret for the connect request
+    pub secret: Option<String>,
+}
+
+impl BunkerURI {
+    /// Create new [`BunkerURI`]
+    pub fn new(signer_public_key: XOnlyPublicKey, relays: Vec<Url>) -> Self {
+        Self {
+            signer_public_key,
+            relays,
+            secret: None,
+        }
+    }
+
+    /// Set secret
+    pub fn secret<S>(self, secret: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self {
+            secret: Some(secret.into()),
+            ..self
+        }
+    }
+
+    /// Compose `connect` [`Request`]
+    pub fn connect_request(&self) -> Request {
+        Request::Connect {
+            public_key: self.signer_public_key,
+            secret: self.secret.clone(),
+        }
+    }
+}
+
+impl FromStr for BunkerURI {
+    type Err = Error;
+
+    fn from_str(uri: &str) -> Result<Self, Self::Err> {
+        let url = Url::parse(uri)?;
+
+        if url.scheme() != BUNKER_URI_SCHEME {
+            return Err(Error::InvalidURIScheme);
+        }
+
+        if let Some(pubkey) = url.domain() {
+            let signer_public_key = XOnlyPublicKey::from_str(pubkey)?;
+
+            let mut relays: Vec<Url> = Vec::new();
+            let mut secret: Option<String> = None;
+
+            for (key, value) in url.query_pairs() {
+                match key {
+                    Cow::Borrowed("relay") => {
+                        let value = value.to_string();
+                        relays.push(Url::parse(&value)?);
+                    }
+                    Cow::Borrowed("secret") => {
+                        secret = Some(value.to_string());
+                    }
+                    _ => (),
+                }
+            }
+
+            if !relays.is_empty() {
+                return Ok(Self {
+                    signer_public_key,
+                    relays,
+                    secret,
+                });
+            }
+        }
+
+        Err(Error::InvalidURI)
+    }
+}
+
+impl fmt::Display for BunkerURI {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{BUNKER_URI_SCHEME}://{}", self.signer_public_key)?;
+        for (index, relay_url) in self.relays.iter().enumerate() {
+            let separator: char = if index == 0 { '?' } else { '&' };
+            write!(f, "{separator}relay={}", url_encode(relay_url.to_string()))?;
+        }
+        if let Some(secret) = &self.secret {
+            let separator: char = if self.relays.is_empty() { '?' } else { '&' };
+            write!(f, "{separator}secret={}", url_encode(secret))?;
+        }
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod test {
     use core::str::FromStr;
 
     use super::*;
+    use crate::{Kind, Timestamp};
 
     #[test]
     fn test_uri() {
@@ -714,4 +852,75 @@ mod test {
         let app_name = "Example";
         assert_eq!(uri, NostrConnectURI::new(pubkey, relay_url, app_name));
     }
+
+    #[test]
+    fn test_bunker_uri() {
+        let signer_public_key = XOnlyPublicKey::from_str(
+            "b889ff5b1513b641e2a139f661a661364979c5beee91842f8f0ef42ab558e9d4",
+        )
+        .unwrap();
+        let relays = vec![
+            Url::parse("wss://relay.damus.io").unwrap(),
+            Url::parse("wss://nos.lol").unwrap(),
+        ];
+        let uri = BunkerURI::new(signer_public_key, relays).secret("abcd");
+        let expected = "bunker://b889ff5b1513b641e2a139f661a661364979c5beee91842f8f0ef42ab558e9d4?relay=wss%3A%2F%2Frelay.damus.io%2F&relay=wss%3A%2F%2Fnos.lol%2F&secret=abcd";
+        assert_eq!(uri.to_string(), expected);
+        assert_eq!(BunkerURI::from_str(expected).unwrap(), uri);
+
+        let uri = BunkerURI::from_str("bunker://b889ff5b1513b641e2a139f661a661364979c5beee91842f8f0ef42ab558e9d4?relay=wss://relay.damus.io").unwrap();
+        assert_eq!(uri.relays.len(), 1);
+        assert!(uri.secret.is_none());
+
+        // Missing relay
+        assert!(BunkerURI::from_str(
+            "bunker://b889ff5b1513b641e2a139f661a661364979c5beee91842f8f0ef42ab558e9d4"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_connect_request() {
+        let public_key = XOnlyPublicKey::from_str(
+            "b889ff5b1513b641e2a139f661a661364979c5beee91842f8f0ef42ab558e9d4",
+        )
+        .unwrap();
+        let req = Request::Connect {
+            public_key,
+            secret: Some(String::from("abcd")),
+        };
+        let msg = Message::request(req.clone());
+        assert_eq!(msg.to_request().unwrap(), req);
+
+        let req = Request::Connect {
+            public_key,
+            secret: None,
+        };
+        let msg = Message::request(req.clone());
+        assert_eq!(msg.to_request().unwrap(), req);
+    }
+
+    #[test]
+    fn test_sign_event_template_request() {
+        let keys = Keys::generate();
+        let template = EventTemplate::new(Timestamp::from(1700000000), Kind::TextNote, "Hello", []);
+
+        let req = Request::SignEventTemplate(template.clone());
+        let msg = Message::request(req.clone());
+        assert_eq!(msg.to_request().unwrap(), req);
+
+        match req.generate_response(&keys).unwrap() {
+            Some(Response::SignEvent(event)) => {
+                assert_eq!(event.author(), keys.public_key());
+                assert_eq!(event.created_at(), template.created_at);
+                assert_eq!(event.content(), "Hello");
+            }
+            res => panic!("Unexpected response: {res:?}"),
+        }
+
+        // Full unsigned events are still parsed as such
+        let unsigned = template.to_unsigned_event(keys.public_key());
+        let msg = Message::request(Request::SignEvent(unsigned.clone()));
+        assert_eq!(msg.to_request().unwrap(), Request::SignEvent(unsigned));
+    }
 }
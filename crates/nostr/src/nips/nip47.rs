@@ -122,6 +122,7 @@ impl fmt::Display for Method {
             Method::LookupInvoice => write!(f, "lookup_invoice"),
             Method::ListInvoices => write!(f, "list_invoices"),
             Method::ListPayments => write!(f, "list_payments"),
+            Method::ListTransactions => write!(f, "list_transactions"),
             Method::GetBalance => write!(f, "get_balance"),
         }
     }
@@ -138,6 +139,7 @@ impl FromStr for Method {
             "lookup_invoice" => Ok(Method::LookupInvoice),
             "list_invoices" => Ok(Method::ListInvoices),
             "list_payments" => Ok(Method::ListPayments),
+            "list_transactions" => Ok(Method::ListTransactions),
             "get_balance" => Ok(Method::GetBalance),
             _ => Err(Error::InvalidURI),
         }
@@ -153,6 +155,12 @@ pub struct NIP47Error {
     pub message: String,
 }
 
+impl fmt::Display for NIP47Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({:?})", self.message, self.code)
+    }
+}
+
 /// Method
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum Method {
@@ -174,6 +182,9 @@ pub enum Method {
     /// List Payments
     #[serde(rename = "list_payments")]
     ListPayments,
+    /// List Transactions
+    #[serde(rename = "list_transactions")]
+    ListTransactions,
     /// Get Balance
     #[serde(rename = "get_balance")]
     GetBalance,
@@ -194,6 +205,8 @@ pub enum RequestParams {
     ListInvoices(ListInvoicesRequestParams),
     /// List Payments
     ListPayments(ListPaymentsRequestParams),
+    /// List Transactions
+    ListTransactions(ListTransactionsRequestParams),
     /// Get Balance
     GetBalance,
 }
@@ -210,6 +223,7 @@ impl Serialize for RequestParams {
             RequestParams::LookupInvoice(p) => p.serialize(serializer),
             RequestParams::ListInvoices(p) => p.serialize(serializer),
             RequestParams::ListPayments(p) => p.serialize(serializer),
+            RequestParams::ListTransactions(p) => p.serialize(serializer),
             RequestParams::GetBalance => serializer.serialize_none(),
         }
     }
@@ -312,6 +326,40 @@ pub struct ListPaymentsRequestParams {
     pub offset: Option<u64>,
 }
 
+/// Transaction Type
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TransactionType {
+    /// Incoming payments
+    Incoming,
+    /// Outgoing payments
+    Outgoing,
+}
+
+/// List Transactions Request Params
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ListTransactionsRequestParams {
+    /// Starting timestamp in seconds since epoch
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from: Option<u64>,
+    /// Ending timestamp in seconds since epoch
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub until: Option<u64>,
+    /// Number of transactions to return
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u64>,
+    /// Offset of the first transaction to return
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offset: Option<u64>,
+    /// If true, include unpaid invoices
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unpaid: Option<bool>,
+    /// Transaction type (all types if `None`)
+    #[serde(rename = "type")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transaction_type: Option<TransactionType>,
+}
+
 /// NIP47 Request
 #[derive(Debug, Clone, Serialize, PartialEq, Eq)]
 pub struct Request {
@@ -359,6 +407,11 @@ impl Request {
                 let params: ListPaymentsRequestParams = serde_json::from_value(template.params)?;
                 RequestParams::ListPayments(params)
             }
+            Method::ListTransactions => {
+                let params: ListTransactionsRequestParams =
+                    serde_json::from_value(template.params)?;
+                RequestParams::ListTransactions(params)
+            }
             Method::GetBalance => RequestParams::GetBalance,
         };
 
@@ -426,6 +479,54 @@ pub struct ListPaymentResponseResult {
     pub preimage: Option<String>,
 }
 
+/// NIP47 Response Result
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct TransactionResponseResult {
+    /// Transaction type
+    #[serde(rename = "type")]
+    pub transaction_type: TransactionType,
+    /// Bolt11 invoice
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub invoice: Option<String>,
+    /// Invoice description
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Invoice description hash
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description_hash: Option<String>,
+    /// Payment preimage
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preimage: Option<String>,
+    /// Payment hash
+    pub payment_hash: String,
+    /// Amount in millisatoshis
+    pub amount: u64,
+    /// Fees paid in millisatoshis
+    #[serde(default)]
+    pub fees_paid: u64,
+    /// Creation timestamp in seconds since epoch
+    pub created_at: u64,
+    /// Expiration timestamp in seconds since epoch
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<u64>,
+    /// Settlement timestamp in seconds since epoch
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub settled_at: Option<u64>,
+}
+
+/// NIP47 Response Result
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ListTransactionsResponseResult {
+    /// Transactions
+    pub transactions: Vec<TransactionResponseResult>,
+}
+
 #[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 /// Budget renewal type
@@ -468,6 +569,8 @@ pub enum ResponseResult {
     ListInvoices(Vec<LookupInvoiceResponseResult>),
     /// List Payments
     ListPayments(Vec<ListPaymentResponseResult>),
+    /// List Transactions
+    ListTransactions(ListTransactionsResponseResult),
     /// Get Balance
     GetBalance(GetBalanceResponseResult),
 }
@@ -484,6 +587,7 @@ impl Serialize for ResponseResult {
             ResponseResult::LookupInvoice(p) => p.serialize(serializer),
             ResponseResult::ListInvoices(p) => p.serialize(serializer),
             ResponseResult::ListPayments(p) => p.serialize(serializer),
+            ResponseResult::ListTransactions(p) => p.serialize(serializer),
             ResponseResult::GetBalance(p) => p.serialize(serializer),
         }
     }
@@ -542,6 +646,10 @@ impl Response {
                     let result: Vec<ListPaymentResponseResult> = serde_json::from_value(result)?;
                     ResponseResult::ListPayments(result)
                 }
+                Method::ListTransactions => {
+                    let result: ListTransactionsResponseResult = serde_json::from_value(result)?;
+                    ResponseResult::ListTransactions(result)
+                }
                 Method::GetBalance => {
                     let result: GetBalanceResponseResult = serde_json::from_value(result)?;
                     ResponseResult::GetBalance(result)
@@ -577,6 +685,16 @@ impl<'de> Deserialize<'de> for Response {
     }
 }
 
+/// Parse the content of the wallet service info event (kind `13194`)
+///
+/// Unknown methods are skipped.
+pub fn supported_methods(content: &str) -> Vec<Method> {
+    content
+        .split_whitespace()
+        .filter_map(|m| Method::from_str(m).ok())
+        .collect()
+}
+
 fn url_encode<T>(data: T) -> String
 where
     T: AsRef<[u8]>,
@@ -588,7 +706,10 @@ where
 pub const NOSTR_WALLET_CONNECT_URI_SCHEME: &str = "nostr+walletconnect";
 
 /// Nostr Connect URI
-#[derive(Debug, Clone, Eq, PartialEq)]
+///
+/// The secret is erased on drop and redacted in [`Debug`](fmt::Debug) and [`Display`](fmt::Display):
+/// use [`NostrWalletConnectURI::display_secret`] to get the full URI.
+#[derive(Clone, Eq, PartialEq)]
 pub struct NostrWalletConnectURI {
     /// App Pubkey
     pub public_key: XOnlyPublicKey,
@@ -615,6 +736,42 @@ impl NostrWalletConnectURI {
             lud16,
         })
     }
+
+    /// Get the full URI, **including the secret**
+    pub fn display_secret(&self) -> String {
+        self.format(&self.secret.display_secret().to_string())
+    }
+
+    /// Format the URI with `secret` (not URL-encoded: hex or placeholder)
+    fn format(&self, secret: &str) -> String {
+        let mut uri: String = format!(
+            "{NOSTR_WALLET_CONNECT_URI_SCHEME}://{}?relay={}&secret={}",
+            self.public_key,
+            url_encode(self.relay_url.to_string()),
+            secret
+        );
+        if let Some(lud16) = &self.lud16 {
+            uri.push_str(&format!("&lud16={}", url_encode(lud16)));
+        }
+        uri
+    }
+}
+
+impl fmt::Debug for NostrWalletConnectURI {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NostrWalletConnectURI")
+            .field("public_key", &self.public_key)
+            .field("relay_url", &self.relay_url)
+            .field("secret", &"<redacted>")
+            .field("lud16", &self.lud16)
+            .finish()
+    }
+}
+
+impl Drop for NostrWalletConnectURI {
+    fn drop(&mut self) {
+        self.secret.non_secure_erase();
+    }
 }
 
 impl FromStr for NostrWalletConnectURI {
@@ -667,19 +824,10 @@ impl FromStr for NostrWalletConnectURI {
     }
 }
 
+/// Redacted URI (the secret is replaced by `<redacted>`)
 impl fmt::Display for NostrWalletConnectURI {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(
-            f,
-            "{NOSTR_WALLET_CONNECT_URI_SCHEME}://{}?relay={}&secret={}",
-            self.public_key,
-            url_encode(self.relay_url.to_string()),
-            url_encode(self.secret.display_secret().to_string())
-        )?;
-        if let Some(lud16) = &self.lud16 {
-            write!(f, "&lud16={}", url_encode(lud16))?;
-        }
-        Ok(())
+        write!(f, "{}", self.format("<redacted>"))
     }
 }
 
@@ -688,7 +836,7 @@ impl Serialize for NostrWalletConnectURI {
     where
         S: serde::Serializer,
     {
-        serializer.serialize_str(&self.to_string())
+        serializer.serialize_str(&self.display_secret())
     }
 }
 
@@ -726,9 +874,16 @@ mod test {
         )
         .unwrap();
         assert_eq!(
-            uri.to_string(),
+            uri.display_secret(),
             "nostr+walletconnect://b889ff5b1513b641e2a139f661a661364979c5beee91842f8f0ef42ab558e9d4?relay=wss%3A%2F%2Frelay.damus.io%2F&secret=71a8c14c1407c113601079c4302dab36460f0ccd0ad506f1f2dc73b5100e4f3c&lud16=nostr%40nostr.com".to_string()
         );
+
+        // Redacted
+        assert_eq!(
+            uri.to_string(),
+            "nostr+walletconnect://b889ff5b1513b641e2a139f661a661364979c5beee91842f8f0ef42ab558e9d4?relay=wss%3A%2F%2Frelay.damus.io%2F&secret=<redacted>&lud16=nostr%40nostr.com".to_string()
+        );
+        assert!(!format!("{uri:?}").contains(&secret.display_secret().to_string()));
     }
 
     #[test]
@@ -782,4 +937,49 @@ mod test {
             panic!("Invalid request params");
         }
     }
+
+    #[test]
+    fn test_supported_methods() {
+        assert_eq!(
+            supported_methods("pay_invoice get_balance  list_transactions unknown_method"),
+            vec![
+                Method::PayInvoice,
+                Method::GetBalance,
+                Method::ListTransactions
+            ]
+        );
+        assert!(supported_methods("").is_empty());
+    }
+
+    #[test]
+    fn test_list_transactions() {
+        let request = Request {
+            method: Method::ListTransactions,
+            params: RequestParams::ListTransactions(ListTransactionsRequestParams {
+                limit: Some(10),
+                transaction_type: Some(TransactionType::Incoming),
+                ..Default::default()
+            }),
+        };
+        assert_eq!(
+            request.as_json(),
+            r#"{"method":"list_transactions","params":{"limit":10,"type":"incoming"}}"#
+        );
+        assert_eq!(Request::from_json(request.as_json()).unwrap(), request);
+
+        let response = r#"{"result_type":"list_transactions","result":{"transactions":[{"type":"outgoing","invoice":"lnbc1","payment_hash":"abcd","amount":21000,"fees_paid":1000,"created_at":1700000000,"settled_at":1700000001}]}}"#;
+        let response = Response::from_json(response).unwrap();
+        match response.result {
+            Some(ResponseResult::ListTransactions(result)) => {
+                let tx = &result.transactions[0];
+                assert_eq!(tx.transaction_type, TransactionType::Outgoing);
+                assert_eq!(tx.invoice.as_deref(), Some("lnbc1"));
+                assert_eq!(tx.amount, 21000);
+                assert_eq!(tx.fees_paid, 1000);
+                assert_eq!(tx.settled_at, Some(1700000001));
+                assert_eq!(tx.expires_at, None);
+            }
+            _ => panic!("Invalid response result"),
+        }
+    }
 }
@@ -0,0 +1,589 @@
+// Copyright (c) 2022-2023 Yuki Kishimoto
+// Copyright (c) 2023-2024 Rust Nostr Developers
+// Distributed under the MIT software license
+
+//! NIP51
+//!
+//! <https://github.com/nostr-protocol/nips/blob/master/51.md>
+
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+
+use bitcoin::secp256k1::XOnlyPublicKey;
+
+use super::nip01::Coordinate;
+use crate::event::tag;
+use crate::{Event, EventBuilder, EventId, Kind, Tag, TagKind, UncheckedUrl};
+
+const WORD: &str = "word";
+
+/// NIP51 error
+#[derive(Debug)]
+pub enum Error {
+    /// Json error
+    Json(serde_json::Error),
+    /// Tag error
+    Tag(tag::Error),
+    /// Invalid kind
+    InvalidKind,
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Json(e) => write!(f, "Json: {e}"),
+            Self::Tag(e) => write!(f, "Tag: {e}"),
+            Self::InvalidKind => write!(f, "invalid kind"),
+        }
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(e: serde_json::Error) -> Self {
+        Self::Json(e)
+    }
+}
+
+impl From<tag::Error> for Error {
+    fn from(e: tag::Error) -> Self {
+        Self::Tag(e)
+    }
+}
+
+/// Mute list item
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum MuteItem {
+    /// Public key (`p` tag)
+    PublicKey(XOnlyPublicKey),
+    /// Thread (`e` tag)
+    Thread(EventId),
+    /// Hashtag (`t` tag)
+    Hashtag(String),
+    /// Word (`word` tag)
+    Word(String),
+}
+
+impl From<XOnlyPublicKey> for MuteItem {
+    fn from(public_key: XOnlyPublicKey) -> Self {
+        Self::PublicKey(public_key)
+    }
+}
+
+impl From<EventId> for MuteItem {
+    fn from(event_id: EventId) -> Self {
+        Self::Thread(event_id)
+    }
+}
+
+impl MuteItem {
+    /// Compose [`MuteItem`] from [`Tag`]
+    ///
+    /// Return `None` if the tag is not a mute list item.
+    pub fn from_tag(tag: &Tag) -> Option<Self> {
+        match tag {
+            Tag::PublicKey {
+                public_key,
+                uppercase: false,
+                ..
+            } => Some(Self::PublicKey(*public_key)),
+            Tag::Event { event_id, .. } => Some(Self::Thread(*event_id)),
+            Tag::Hashtag(hashtag) => Some(Self::Hashtag(hashtag.clone())),
+            Tag::Generic(TagKind::Custom(kind), values) if kind == WORD => {
+                values.first().map(|word| Self::Word(word.clone()))
+            }
+            _ => None,
+        }
+    }
+
+    /// Convert to [`Tag`]
+    pub fn to_tag(&self) -> Tag {
+        match self {
+            Self::PublicKey(public_key) => Tag::public_key(*public_key),
+            Self::Thread(event_id) => Tag::event(*event_id),
+            Self::Hashtag(hashtag) => Tag::Hashtag(hashtag.clone()),
+            Self::Word(word) => Tag::Generic(TagKind::Custom(WORD.to_string()), vec![word.clone()]),
+        }
+    }
+}
+
+/// Mute list (kind `10000`)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MuteList {
+    /// Public items (tags of the event)
+    pub public: Vec<MuteItem>,
+    /// Private items (encrypted in the content of the event)
+    pub private: Vec<MuteItem>,
+    /// Other public tags (not mute items), preserved when updating the list
+    pub other_tags: Vec<Tag>,
+}
+
+impl MuteList {
+    /// New empty [`MuteList`]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Compose [`MuteList`] from the public items of the event
+    ///
+    /// The private items must be decrypted from the content and parsed with [`MuteList::parse_private`].
+    pub fn from_event(event: &Event) -> Result<Self, Error> {
+        if event.kind() != Kind::MuteList {
+            return Err(Error::InvalidKind);
+        }
+
+        let mut list = Self::new();
+        for tag in event.iter_tags() {
+            match MuteItem::from_tag(tag) {
+                Some(item) => list.public.push(item),
+                None => list.other_tags.push(tag.clone()),
+            }
+        }
+        Ok(list)
+    }
+
+    /// Parse the private items from the decrypted content (JSON array of tags)
+    pub fn parse_private<S>(json: S) -> Result<Vec<MuteItem>, Error>
+    where
+        S: AsRef<str>,
+    {
+        let tags: Vec<Vec<String>> = serde_json::from_str(json.as_ref())?;
+        let mut items: Vec<MuteItem> = Vec::with_capacity(tags.len());
+        for tag in tags.into_iter() {
+            if let Some(item) = MuteItem::from_tag(&Tag::parse(tag)?) {
+                items.push(item);
+            }
+        }
+        Ok(items)
+    }
+
+    /// Serialize the private items as JSON array of tags, to encrypt in the content
+    ///
+    /// Return an empty string if there aren't private items.
+    pub fn private_json(&self) -> String {
+        if self.private.is_empty() {
+            return String::new();
+        }
+
+        let tags: Vec<Vec<String>> = self.private.iter().map(|i| i.to_tag().as_vec()).collect();
+        serde_json::json!(tags).to_string()
+    }
+
+    /// Compose the [`EventBuilder`] with the already encrypted private items
+    pub fn to_event_builder<S>(&self, encrypted_content: S) -> EventBuilder
+    where
+        S: Into<String>,
+    {
+        let tags = self
+            .public
+            .iter()
+            .map(|i| i.to_tag())
+            .chain(self.other_tags.iter().cloned());
+        EventBuilder::new(Kind::MuteList, encrypted_content, tags)
+    }
+
+    /// Check if the item is in the public or private section
+    pub fn contains(&self, item: &MuteItem) -> bool {
+        self.public.contains(item) || self.private.contains(item)
+    }
+
+    /// Add item to the public or private section
+    ///
+    /// Return `false` if the item was already in the list.
+    pub fn add(&mut self, item: MuteItem, private: bool) -> bool {
+        if self.contains(&item) {
+            return false;
+        }
+
+        if private {
+            self.private.push(item);
+        } else {
+            self.public.push(item);
+        }
+
+        true
+    }
+
+    /// Remove item from both the public and private sections
+    ///
+    /// Return `false` if the item wasn't in the list.
+    pub fn remove(&mut self, item: &MuteItem) -> bool {
+        let len: usize = self.public.len() + self.private.len();
+        self.public.retain(|i| i != item);
+        self.private.retain(|i| i != item);
+        self.public.len() + self.private.len() != len
+    }
+
+    /// Iterate public and private items
+    pub fn iter(&self) -> impl Iterator<Item = &MuteItem> {
+        self.public.iter().chain(self.private.iter())
+    }
+
+    /// Check if the event is muted
+    ///
+    /// An event is muted if the author is muted, if it's (or reply to) a muted thread,
+    /// if has a muted hashtag or if the content contains a muted word (case-insensitive).
+    pub fn is_muted(&self, event: &Event) -> bool {
+        let content: String = event.content().to_lowercase();
+        self.iter().any(|item| match item {
+            MuteItem::PublicKey(public_key) => &event.author() == public_key,
+            MuteItem::Thread(event_id) => {
+                &event.id() == event_id || event.event_ids().any(|id| id == event_id)
+            }
+            MuteItem::Hashtag(hashtag) => event.iter_tags().any(|tag| match tag {
+                Tag::Hashtag(t) => t.eq_ignore_ascii_case(hashtag),
+                _ => false,
+            }),
+            MuteItem::Word(word) => !word.is_empty() && content.contains(&word.to_lowercase()),
+        })
+    }
+}
+
+/// Bookmark
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Bookmark {
+    /// Note (`e` tag)
+    Event(EventId),
+    /// Parameterized replaceable event, like a long-form article (`a` tag)
+    Coordinate(Coordinate),
+    /// Hashtag (`t` tag)
+    Hashtag(String),
+    /// URL (`r` tag)
+    Url(UncheckedUrl),
+}
+
+impl From<EventId> for Bookmark {
+    fn from(event_id: EventId) -> Self {
+        Self::Event(event_id)
+    }
+}
+
+impl From<Coordinate> for Bookmark {
+    fn from(coordinate: Coordinate) -> Self {
+        Self::Coordinate(coordinate)
+    }
+}
+
+impl From<&Event> for Bookmark {
+    /// Bookmark the [`Coordinate`] of parameterized replaceable events (i.e. long-form articles)
+    /// and the [`EventId`] of the others
+    fn from(event: &Event) -> Self {
+        if event.is_parameterized_replaceable() {
+            Self::Coordinate(
+                Coordinate::new(event.kind(), event.author())
+                    .identifier(event.identifier().unwrap_or_default()),
+            )
+        } else {
+            Self::Event(event.id())
+        }
+    }
+}
+
+impl Bookmark {
+    /// Compose [`Bookmark`] from [`Tag`]
+    ///
+    /// Return `None` if the tag is not a bookmark.
+    pub fn from_tag(tag: &Tag) -> Option<Self> {
+        match tag {
+            Tag::Event { event_id, .. } => Some(Self::Event(*event_id)),
+            Tag::A {
+                kind,
+                public_key,
+                identifier,
+                relay_url,
+            } => Some(Self::Coordinate(Coordinate {
+                kind: *kind,
+                pubkey: *public_key,
+                identifier: identifier.clone(),
+                relays: relay_url.iter().map(|url| url.to_string()).collect(),
+            })),
+            Tag::Hashtag(hashtag) => Some(Self::Hashtag(hashtag.clone())),
+            Tag::Reference(url) => Some(Self::Url(UncheckedUrl::from(url.as_str()))),
+            _ => None,
+        }
+    }
+
+    /// Convert to [`Tag`]
+    pub fn to_tag(&self) -> Tag {
+        match self {
+            Self::Event(event_id) => Tag::event(*event_id),
+            Self::Coordinate(coordinate) => Tag::A {
+                kind: coordinate.kind,
+                public_key: coordinate.pubkey,
+                identifier: coordinate.identifier.clone(),
+                relay_url: coordinate.relays.first().map(UncheckedUrl::from),
+            },
+            Self::Hashtag(hashtag) => Tag::Hashtag(hashtag.clone()),
+            Self::Url(url) => Tag::Reference(url.to_string()),
+        }
+    }
+
+    /// Check if the bookmarks point to the same target (relay hints are ignored)
+    fn is_same(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Coordinate(a), Self::Coordinate(b)) => {
+                a.kind == b.kind && a.pubkey == b.pubkey && a.identifier == b.identifier
+            }
+            (a, b) => a == b,
+        }
+    }
+}
+
+/// Bookmarks (kind `10003`) or bookmark set (kind `30003`)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Bookmarks {
+    /// Bookmark set identifier (`None` for the kind `10003` list)
+    pub identifier: Option<String>,
+    /// Bookmarks
+    pub items: Vec<Bookmark>,
+    /// Other tags (not bookmarks, i.e. `title`), preserved when updating the list
+    pub other_tags: Vec<Tag>,
+}
+
+impl Bookmarks {
+    /// New empty bookmark list (kind `10003`)
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// New empty bookmark set (kind `30003`)
+    pub fn set<S>(identifier: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self {
+            identifier: Some(identifier.into()),
+            items: Vec::new(),
+            other_tags: Vec::new(),
+        }
+    }
+
+    /// Compose [`Bookmarks`] from kind `10003` or `30003` event
+    pub fn from_event(event: &Event) -> Result<Self, Error> {
+        let identifier: Option<String> = match event.kind() {
+            Kind::Bookmarks => None,
+            Kind::BookmarkSet => Some(event.identifier().unwrap_or_default().to_string()),
+            _ => return Err(Error::InvalidKind),
+        };
+
+        let mut list = Self {
+            identifier,
+            ..Default::default()
+        };
+        for tag in event.iter_tags() {
+            match Bookmark::from_tag(tag) {
+                Some(bookmark) => list.items.push(bookmark),
+                // The identifier of the set is already parsed
+                None if list.identifier.is_some() && matches!(tag, Tag::Identifier(..)) => (),
+                None => list.other_tags.push(tag.clone()),
+            }
+        }
+        Ok(list)
+    }
+
+    /// Get [`Kind`]
+    pub fn kind(&self) -> Kind {
+        match self.identifier {
+            Some(..) => Kind::BookmarkSet,
+            None => Kind::Bookmarks,
+        }
+    }
+
+    /// Compose the [`EventBuilder`]
+    ///
+    /// `content` is used for the (already encrypted) private items, if any.
+    pub fn to_event_builder<S>(&self, content: S) -> EventBuilder
+    where
+        S: Into<String>,
+    {
+        let mut tags: Vec<Tag> = Vec::with_capacity(self.items.len() + self.other_tags.len() + 1);
+        if let Some(identifier) = &self.identifier {
+            tags.push(Tag::Identifier(identifier.clone()));
+        }
+        tags.extend(self.items.iter().map(|i| i.to_tag()));
+        tags.extend(self.other_tags.iter().cloned());
+        EventBuilder::new(self.kind(), content, tags)
+    }
+
+    /// Check if the list contains the bookmark (relay hints are ignored)
+    pub fn contains(&self, bookmark: &Bookmark) -> bool {
+        self.items.iter().any(|i| i.is_same(bookmark))
+    }
+
+    /// Add bookmark
+    ///
+    /// Return `false` if the bookmark was already in the list.
+    pub fn add(&mut self, bookmark: Bookmark) -> bool {
+        if self.contains(&bookmark) {
+            return false;
+        }
+        self.items.push(bookmark);
+        true
+    }
+
+    /// Remove bookmark (relay hints are ignored)
+    ///
+    /// Return `false` if the bookmark wasn't in the list.
+    pub fn remove(&mut self, bookmark: &Bookmark) -> bool {
+        let len: usize = self.items.len();
+        self.items.retain(|i| !i.is_same(bookmark));
+        self.items.len() != len
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::str::FromStr;
+
+    use super::*;
+    use crate::Keys;
+
+    #[test]
+    fn test_mute_item_tag() {
+        let public_key = XOnlyPublicKey::from_str(
+            "68d81165918100b7da43fc28f7d1fc12554466e1115886b9e7bb326f65ec4272",
+        )
+        .unwrap();
+        let items = [
+            MuteItem::PublicKey(public_key),
+            MuteItem::Thread(EventId::all_zeros()),
+            MuteItem::Hashtag(String::from("nostr")),
+            MuteItem::Word(String::from("spam")),
+        ];
+        for item in items.into_iter() {
+            let tag = Tag::parse(item.to_tag().as_vec()).unwrap();
+            assert_eq!(MuteItem::from_tag(&tag), Some(item));
+        }
+
+        assert_eq!(MuteItem::from_tag(&Tag::Title(String::from("a"))), None);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_mute_list_private() {
+        let mut list = MuteList::new();
+        assert_eq!(list.private_json(), "");
+
+        assert!(list.add(MuteItem::Word(String::from("spam")), true));
+        assert!(list.add(MuteItem::Hashtag(String::from("nsfw")), false));
+        assert!(!list.add(MuteItem::Word(String::from("spam")), false));
+
+        let json = list.private_json();
+        assert_eq!(json, r#"[["word","spam"]]"#);
+        assert_eq!(MuteList::parse_private(json).unwrap(), list.private);
+
+        let keys = Keys::generate();
+        let event = list.to_event_builder("").to_event(&keys).unwrap();
+        let parsed = MuteList::from_event(&event).unwrap();
+        assert_eq!(parsed.public, list.public);
+        assert!(parsed.private.is_empty());
+
+        // Unknown tags are preserved
+        let title = Tag::Title(String::from("Muted"));
+        let event = EventBuilder::new(
+            Kind::MuteList,
+            "",
+            [title.clone(), Tag::event(EventId::all_zeros())],
+        )
+        .to_event(&keys)
+        .unwrap();
+        let parsed = MuteList::from_event(&event).unwrap();
+        assert_eq!(parsed.public, vec![MuteItem::Thread(EventId::all_zeros())]);
+        assert_eq!(parsed.other_tags, vec![title.clone()]);
+        let event = parsed.to_event_builder("").to_event(&keys).unwrap();
+        assert!(event.iter_tags().any(|t| t == &title));
+
+        assert!(list.remove(&MuteItem::Word(String::from("spam"))));
+        assert!(!list.remove(&MuteItem::Word(String::from("spam"))));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_bookmarks() {
+        let keys = Keys::generate();
+        let article =
+            EventBuilder::long_form_text_note("Article", [Tag::Identifier(String::from("abc"))])
+                .to_event(&keys)
+                .unwrap();
+        let note = EventBuilder::text_note("Note", []).to_event(&keys).unwrap();
+
+        let bookmark = Bookmark::from(&article);
+        assert_eq!(
+            bookmark,
+            Bookmark::Coordinate(
+                Coordinate::new(Kind::LongFormTextNote, keys.public_key()).identifier("abc")
+            )
+        );
+        assert_eq!(Bookmark::from(&note), Bookmark::Event(note.id()));
+
+        let mut list = Bookmarks::set("rust");
+        assert!(list.add(bookmark));
+        assert!(list.add(Bookmark::from(&note)));
+        assert!(list.add(Bookmark::Url(UncheckedUrl::from("https://rust-nostr.org"))));
+        assert!(!list.add(Bookmark::Event(note.id())));
+
+        let event = list.to_event_builder("").to_event(&keys).unwrap();
+        assert_eq!(event.kind(), Kind::BookmarkSet);
+        assert_eq!(Bookmarks::from_event(&event).unwrap(), list);
+
+        // Relay hints are ignored
+        let mut coordinate =
+            Coordinate::new(Kind::LongFormTextNote, keys.public_key()).identifier("abc");
+        coordinate.relays.push(String::from("wss://relay.damus.io"));
+        assert!(list.remove(&Bookmark::Coordinate(coordinate)));
+        assert_eq!(list.items.len(), 2);
+
+        assert!(Bookmarks::from_event(&note).is_err());
+
+        // Unknown tags are preserved
+        let title = Tag::Title(String::from("Rust"));
+        let event = EventBuilder::new(
+            Kind::BookmarkSet,
+            "",
+            [
+                Tag::Identifier(String::from("rust")),
+                title.clone(),
+                Tag::Hashtag(String::from("rust")),
+            ],
+        )
+        .to_event(&keys)
+        .unwrap();
+        let parsed = Bookmarks::from_event(&event).unwrap();
+        assert_eq!(parsed.identifier.as_deref(), Some("rust"));
+        assert_eq!(parsed.items, vec![Bookmark::Hashtag(String::from("rust"))]);
+        assert_eq!(parsed.other_tags, vec![title]);
+        let event = parsed.to_event_builder("").to_event(&keys).unwrap();
+        assert_eq!(Bookmarks::from_event(&event).unwrap(), parsed);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_is_muted() {
+        let keys = Keys::generate();
+        let event = EventBuilder::text_note("Buy my SPAM now", [Tag::Hashtag(String::from("Ads"))])
+            .to_event(&keys)
+            .unwrap();
+
+        let mut list = MuteList::new();
+        assert!(!list.is_muted(&event));
+
+        list.add(MuteItem::Word(String::from("spam")), true);
+        assert!(list.is_muted(&event));
+
+        let mut list = MuteList::new();
+        list.add(MuteItem::Hashtag(String::from("ads")), false);
+        assert!(list.is_muted(&event));
+
+        let mut list = MuteList::new();
+        list.add(MuteItem::PublicKey(keys.public_key()), false);
+        assert!(list.is_muted(&event));
+
+        let mut list = MuteList::new();
+        list.add(MuteItem::Thread(event.id()), false);
+        assert!(list.is_muted(&event));
+    }
+}
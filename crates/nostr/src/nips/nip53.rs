@@ -6,6 +6,7 @@
 //!
 //! <https://github.com/nostr-protocol/nips/blob/master/53.md>
 
+use alloc::collections::BTreeMap;
 use alloc::string::{String, ToString};
 use alloc::vec::Vec;
 use core::fmt;
@@ -21,12 +22,18 @@ use crate::{ImageDimensions, Tag, Timestamp, UncheckedUrl};
 pub enum Error {
     /// Unknown [`LiveEventMarker`]
     UnknownLiveEventMarker(String),
+    /// Identifier missing (no `d` tag)
+    MissingIdentifier,
 }
 
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Self::UnknownLiveEventMarker(u) => write!(f, "Unknown live event marker: {u}"),
+            Self::MissingIdentifier => write!(f, "Missing identifier"),
         }
     }
 }
@@ -105,6 +112,7 @@ where
 }
 
 /// Live Event Host
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct LiveEventHost {
     /// Host public key
     pub public_key: XOnlyPublicKey,
@@ -115,6 +123,7 @@ pub struct LiveEventHost {
 }
 
 /// Live Event
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct LiveEvent {
     /// Unique event ID
     pub id: String,
@@ -258,3 +267,258 @@ impl From<LiveEvent> for Vec<Tag> {
         tags
     }
 }
+
+impl TryFrom<Vec<Tag>> for LiveEvent {
+    type Error = Error;
+
+    fn try_from(tags: Vec<Tag>) -> Result<Self, Self::Error> {
+        let id: String = match tags.iter().find(|t| matches!(t, Tag::Identifier(_))) {
+            Some(Tag::Identifier(id)) => id.clone(),
+            _ => return Err(Error::MissingIdentifier),
+        };
+
+        let mut live_event = LiveEvent::new(id);
+
+        for tag in tags.into_iter() {
+            match tag {
+                Tag::Title(title) => live_event.title = Some(title),
+                Tag::Summary(summary) => live_event.summary = Some(summary),
+                Tag::Image(image, dim) => live_event.image = Some((image, dim)),
+                Tag::Hashtag(hashtag) => live_event.hashtags.push(hashtag),
+                Tag::Streaming(url) => live_event.streaming = Some(url),
+                Tag::Recording(url) => live_event.recording = Some(url),
+                Tag::Starts(starts) => live_event.starts = Some(starts),
+                Tag::Ends(ends) => live_event.ends = Some(ends),
+                Tag::LiveEventStatus(status) => live_event.status = Some(status),
+                Tag::CurrentParticipants(num) => live_event.current_participants = Some(num),
+                Tag::TotalParticipants(num) => live_event.total_participants = Some(num),
+                Tag::Relays(relays) => live_event.relays.extend(relays),
+                Tag::PubKeyLiveEvent {
+                    public_key,
+                    relay_url,
+                    marker,
+                    proof,
+                } => match marker {
+                    LiveEventMarker::Host => {
+                        live_event.host = Some(LiveEventHost {
+                            public_key,
+                            relay_url,
+                            proof,
+                        })
+                    }
+                    LiveEventMarker::Speaker => live_event.speakers.push((public_key, relay_url)),
+                    LiveEventMarker::Participant => {
+                        live_event.participants.push((public_key, relay_url))
+                    }
+                },
+                _ => (),
+            }
+        }
+
+        Ok(live_event)
+    }
+}
+
+/// Live Event update
+///
+/// Changes between two versions of the same [`LiveEvent`] (see [`LiveEvent::diff`]).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LiveEventUpdate {
+    /// Status changed
+    StatusChanged {
+        /// Previous status
+        from: Option<LiveEventStatus>,
+        /// New status
+        to: Option<LiveEventStatus>,
+    },
+    /// Streaming URL changed
+    StreamingChanged(Option<UncheckedUrl>),
+    /// Host, speaker or participant joined
+    ParticipantJoined {
+        /// Public key
+        public_key: XOnlyPublicKey,
+        /// Role
+        marker: LiveEventMarker,
+    },
+    /// Host, speaker or participant left
+    ParticipantLeft {
+        /// Public key
+        public_key: XOnlyPublicKey,
+        /// Role
+        marker: LiveEventMarker,
+    },
+}
+
+impl LiveEvent {
+    /// New empty [`LiveEvent`]
+    pub fn new<S>(id: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self {
+            id: id.into(),
+            title: None,
+            summary: None,
+            image: None,
+            hashtags: Vec::new(),
+            streaming: None,
+            recording: None,
+            starts: None,
+            ends: None,
+            status: None,
+            current_participants: None,
+            total_participants: None,
+            relays: Vec::new(),
+            host: None,
+            speakers: Vec::new(),
+            participants: Vec::new(),
+        }
+    }
+
+    /// Get host, speakers and participants with their [`LiveEventMarker`]
+    pub fn members(&self) -> BTreeMap<XOnlyPublicKey, LiveEventMarker> {
+        let mut members: BTreeMap<XOnlyPublicKey, LiveEventMarker> = BTreeMap::new();
+        for (public_key, ..) in self.participants.iter() {
+            members.insert(*public_key, LiveEventMarker::Participant);
+        }
+        for (public_key, ..) in self.speakers.iter() {
+            members.insert(*public_key, LiveEventMarker::Speaker);
+        }
+        if let Some(host) = &self.host {
+            members.insert(host.public_key, LiveEventMarker::Host);
+        }
+        members
+    }
+
+    /// Compute the [`LiveEventUpdate`]s needed to go from `self` to `new`
+    ///
+    /// A role change is reported as [`LiveEventUpdate::ParticipantLeft`] with the old marker,
+    /// followed by [`LiveEventUpdate::ParticipantJoined`] with the new one.
+    pub fn diff(&self, new: &LiveEvent) -> Vec<LiveEventUpdate> {
+        let mut updates: Vec<LiveEventUpdate> = Vec::new();
+
+        if self.status != new.status {
+            updates.push(LiveEventUpdate::StatusChanged {
+                from: self.status.clone(),
+                to: new.status.clone(),
+            });
+        }
+
+        if self.streaming != new.streaming {
+            updates.push(LiveEventUpdate::StreamingChanged(new.streaming.clone()));
+        }
+
+        let old_members = self.members();
+        let new_members = new.members();
+
+        for (public_key, marker) in old_members.iter() {
+            if new_members.get(public_key) != Some(marker) {
+                updates.push(LiveEventUpdate::ParticipantLeft {
+                    public_key: *public_key,
+                    marker: marker.clone(),
+                });
+            }
+        }
+
+        for (public_key, marker) in new_members.into_iter() {
+            if old_members.get(&public_key) != Some(&marker) {
+                updates.push(LiveEventUpdate::ParticipantJoined { public_key, marker });
+            }
+        }
+
+        updates
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::str::FromStr;
+
+    use super::*;
+
+    const PUBLIC_KEY_A: &str = "f831caf722214748c72db4829986bd0cbb2bb8b3aeade1c959624a52a9629046";
+    const PUBLIC_KEY_B: &str = "b2d670de53b27691c0c3400225b65c35a26d06093bcc41f48ffc71e0907f9d4a";
+
+    fn build_live_event(status: LiveEventStatus) -> LiveEvent {
+        let tags = vec![
+            Tag::Identifier(String::from("my-stream")),
+            Tag::Title(String::from("My stream")),
+            Tag::Streaming(UncheckedUrl::from("https://example.com/stream.m3u8")),
+            Tag::LiveEventStatus(status),
+            Tag::PubKeyLiveEvent {
+                public_key: XOnlyPublicKey::from_str(PUBLIC_KEY_A).unwrap(),
+                relay_url: None,
+                marker: LiveEventMarker::Host,
+                proof: None,
+            },
+        ];
+        LiveEvent::try_from(tags).unwrap()
+    }
+
+    #[test]
+    fn test_parse_live_event() {
+        let live_event = build_live_event(LiveEventStatus::Planned);
+        assert_eq!(live_event.id, "my-stream");
+        assert_eq!(live_event.title, Some(String::from("My stream")));
+        assert_eq!(live_event.status, Some(LiveEventStatus::Planned));
+        assert_eq!(
+            live_event.host.map(|h| h.public_key),
+            Some(XOnlyPublicKey::from_str(PUBLIC_KEY_A).unwrap())
+        );
+
+        let tags: Vec<Tag> = build_live_event(LiveEventStatus::Live).into();
+        assert_eq!(
+            LiveEvent::try_from(tags).unwrap(),
+            build_live_event(LiveEventStatus::Live)
+        );
+
+        assert!(matches!(
+            LiveEvent::try_from(vec![Tag::Title(String::from("No id"))]),
+            Err(Error::MissingIdentifier)
+        ));
+    }
+
+    #[test]
+    fn test_live_event_diff() {
+        let planned = build_live_event(LiveEventStatus::Planned);
+        assert!(planned.diff(&planned).is_empty());
+
+        let mut live = build_live_event(LiveEventStatus::Live);
+        let public_key_b = XOnlyPublicKey::from_str(PUBLIC_KEY_B).unwrap();
+        live.participants.push((public_key_b, None));
+        live.streaming = None;
+
+        assert_eq!(
+            planned.diff(&live),
+            vec![
+                LiveEventUpdate::StatusChanged {
+                    from: Some(LiveEventStatus::Planned),
+                    to: Some(LiveEventStatus::Live),
+                },
+                LiveEventUpdate::StreamingChanged(None),
+                LiveEventUpdate::ParticipantJoined {
+                    public_key: public_key_b,
+                    marker: LiveEventMarker::Participant,
+                },
+            ]
+        );
+
+        let ended = build_live_event(LiveEventStatus::Ended);
+        assert_eq!(
+            live.diff(&ended),
+            vec![
+                LiveEventUpdate::StatusChanged {
+                    from: Some(LiveEventStatus::Live),
+                    to: Some(LiveEventStatus::Ended),
+                },
+                LiveEventUpdate::StreamingChanged(Some(UncheckedUrl::from(
+                    "https://example.com/stream.m3u8"
+                ))),
+                LiveEventUpdate::ParticipantLeft {
+                    public_key: public_key_b,
+                    marker: LiveEventMarker::Participant,
+                },
+            ]
+        );
+    }
+}
@@ -6,9 +6,12 @@
 //!
 //! <https://github.com/nostr-protocol/nips/blob/master/57.md>
 
+use alloc::collections::{BTreeMap, BTreeSet};
 use alloc::string::{String, ToString};
 use alloc::vec::Vec;
 use core::fmt;
+#[cfg(feature = "std")]
+use std::net::SocketAddr;
 
 use aes::cipher::block_padding::Pkcs7;
 use aes::cipher::{BlockDecryptMut, BlockEncryptMut, KeyIvInit};
@@ -21,6 +24,9 @@ use bitcoin::secp256k1::rand::rngs::OsRng;
 use bitcoin::secp256k1::rand::{CryptoRng, RngCore};
 use bitcoin::secp256k1::{self, Secp256k1, SecretKey, Signing, XOnlyPublicKey};
 use cbc::{Decryptor, Encryptor};
+#[cfg(all(feature = "std", not(target_arch = "wasm32")))]
+use reqwest::Proxy;
+use url_fork::Url;
 
 use super::nip01::Coordinate;
 use crate::event::builder::Error as BuilderError;
@@ -31,7 +37,8 @@ use crate::types::time::TimeSupplier;
 #[cfg(feature = "std")]
 use crate::SECP256K1;
 use crate::{
-    event, util, Event, EventBuilder, EventId, JsonUtil, Keys, Kind, Tag, Timestamp, UncheckedUrl,
+    event, util, Event, EventBuilder, EventId, JsonUtil, Keys, Kind, Metadata, Tag, Timestamp,
+    UncheckedUrl,
 };
 
 type Aes256CbcEnc = Encryptor<Aes256>;
@@ -39,6 +46,7 @@ type Aes256CbcDec = Decryptor<Aes256>;
 
 const PRIVATE_ZAP_MSG_BECH32_PREFIX: &str = "pzap";
 const PRIVATE_ZAP_IV_BECH32_PREFIX: &str = "iv";
+const LNURL_BECH32_PREFIX: &str = "lnurl";
 
 #[allow(missing_docs)]
 #[derive(Debug)]
@@ -54,6 +62,30 @@ pub enum Error {
     WrongBech32PrefixOrVariant,
     /// Wrong encryption block mode
     WrongBlockMode,
+    /// Url parse error
+    Url(url_fork::ParseError),
+    /// JSON error
+    Json(serde_json::Error),
+    /// Reqwest error
+    #[cfg(feature = "std")]
+    Reqwest(reqwest::Error),
+    /// Invalid lightning address or LNURL
+    InvalidLnurl,
+    /// The recipient has no lightning address or LNURL
+    LnurlNotFound,
+    /// The LNURL service doesn't support zaps
+    ZapsNotSupported,
+    /// Amount out of the range accepted by the LNURL service
+    AmountOutOfRange {
+        /// Min amount (millisats)
+        min: u64,
+        /// Max amount (millisats)
+        max: u64,
+    },
+    /// LNURL service error
+    Lnurl(String),
+    /// Invalid zap receipt
+    InvalidZapReceipt(String),
 }
 
 #[cfg(feature = "std")]
@@ -74,6 +106,18 @@ impl fmt::Display for Error {
                 f,
                 "Wrong encryption block mode. The content must be encrypted using CBC mode!"
             ),
+            Self::Url(e) => write!(f, "Url: {e}"),
+            Self::Json(e) => write!(f, "Json: {e}"),
+            #[cfg(feature = "std")]
+            Self::Reqwest(e) => write!(f, "Reqwest: {e}"),
+            Self::InvalidLnurl => write!(f, "Invalid lightning address or LNURL"),
+            Self::LnurlNotFound => write!(f, "Lightning address or LNURL not found"),
+            Self::ZapsNotSupported => write!(f, "The LNURL service doesn't support zaps"),
+            Self::AmountOutOfRange { min, max } => {
+                write!(f, "Amount out of range: min {min} msat, max {max} msat")
+            }
+            Self::Lnurl(reason) => write!(f, "LNURL service: {reason}"),
+            Self::InvalidZapReceipt(reason) => write!(f, "Invalid zap receipt: {reason}"),
         }
     }
 }
@@ -108,6 +152,25 @@ impl From<secp256k1::Error> for Error {
     }
 }
 
+impl From<url_fork::ParseError> for Error {
+    fn from(e: url_fork::ParseError) -> Self {
+        Self::Url(e)
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    fn from(e: serde_json::Error) -> Self {
+        Self::Json(e)
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<reqwest::Error> for Error {
+    fn from(e: reqwest::Error) -> Self {
+        Self::Reqwest(e)
+    }
+}
+
 // /// Zap Type
 // #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 // pub enum ZapType {
@@ -388,6 +451,357 @@ pub fn decrypt_private_zap_message(
     Ok(Event::from_json(result)?)
 }
 
+/// Get the LNURL-pay endpoint of a lightning address (`name@domain`, LUD16) or of a bech32 LNURL (LUD06)
+pub fn lnurl_pay_url<S>(address: S) -> Result<Url, Error>
+where
+    S: AsRef<str>,
+{
+    let address: &str = address.as_ref().trim();
+
+    // Lightning address
+    if let Some((name, domain)) = address.split_once('@') {
+        if name.is_empty() || domain.is_empty() {
+            return Err(Error::InvalidLnurl);
+        }
+        let url = Url::parse(&format!("https://{domain}/.well-known/lnurlp/{name}"))?;
+        return Ok(url);
+    }
+
+    // LNURL
+    let address: &str = address.strip_prefix("lightning:").unwrap_or(address);
+    let (hrp, data, checksum) = bech32::decode(address)?;
+    if hrp != LNURL_BECH32_PREFIX || checksum != Variant::Bech32 {
+        return Err(Error::WrongBech32PrefixOrVariant);
+    }
+    let url: Vec<u8> = Vec::from_base32(&data)?;
+    let url: String = String::from_utf8(url).map_err(|_| Error::InvalidLnurl)?;
+    Ok(Url::parse(&url)?)
+}
+
+/// Get the LNURL-pay endpoint from the lightning address (`lud16`) or the LNURL (`lud06`) of the [`Metadata`]
+///
+/// The lightning address takes precedence.
+pub fn lnurl_pay_url_from_metadata(metadata: &Metadata) -> Result<Url, Error> {
+    [&metadata.lud16, &metadata.lud06]
+        .into_iter()
+        .flatten()
+        .find(|address| !address.trim().is_empty())
+        .ok_or(Error::LnurlNotFound)
+        .and_then(lnurl_pay_url)
+}
+
+/// Encode an url as bech32 LNURL (LUD01)
+pub fn lnurl_encode(url: &Url) -> Result<String, Error> {
+    Ok(bech32::encode(
+        LNURL_BECH32_PREFIX,
+        url.as_str().as_bytes().to_base32(),
+        Variant::Bech32,
+    )?)
+}
+
+/// LNURL-pay service parameters (LUD06)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LnUrlPayResponse {
+    /// Url where to request the invoice
+    pub callback: Url,
+    /// Min amount (millisats)
+    pub min_sendable: u64,
+    /// Max amount (millisats)
+    pub max_sendable: u64,
+    /// If the service accepts zap requests
+    #[serde(default)]
+    pub allows_nostr: bool,
+    /// Public key used by the service to sign the zap receipts
+    #[serde(default)]
+    pub nostr_pubkey: Option<XOnlyPublicKey>,
+}
+
+impl LnUrlPayResponse {
+    /// Get the public key that signs the zap receipts, if the service supports zaps
+    pub fn zapper(&self) -> Option<XOnlyPublicKey> {
+        if self.allows_nostr {
+            self.nostr_pubkey
+        } else {
+            None
+        }
+    }
+
+    /// Compose the url to request a zap invoice of `amount` (millisats)
+    ///
+    /// `lnurl` is the bech32 LNURL of the recipient.
+    pub fn zap_invoice_url(
+        &self,
+        zap_request: &Event,
+        amount: u64,
+        lnurl: Option<&str>,
+    ) -> Result<Url, Error> {
+        if self.zapper().is_none() {
+            return Err(Error::ZapsNotSupported);
+        }
+
+        if amount < self.min_sendable || amount > self.max_sendable {
+            return Err(Error::AmountOutOfRange {
+                min: self.min_sendable,
+                max: self.max_sendable,
+            });
+        }
+
+        let mut url: Url = self.callback.clone();
+        {
+            let mut query = url.query_pairs_mut();
+            query.append_pair("amount", &amount.to_string());
+            query.append_pair("nostr", &zap_request.as_json());
+            if let Some(lnurl) = lnurl {
+                query.append_pair("lnurl", lnurl);
+            }
+        }
+        Ok(url)
+    }
+}
+
+/// LNURL service response
+#[cfg(feature = "std")]
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum LnUrlResponse<T> {
+    Error { status: String, reason: String },
+    Ok(T),
+}
+
+#[cfg(feature = "std")]
+impl<T> LnUrlResponse<T> {
+    fn into_result(self) -> Result<T, Error> {
+        match self {
+            Self::Ok(res) => Ok(res),
+            Self::Error { status, reason } => Err(Error::Lnurl(format!("{status}: {reason}"))),
+        }
+    }
+}
+
+/// Zap invoice response
+#[cfg(feature = "std")]
+#[derive(Deserialize)]
+struct ZapInvoiceResponse {
+    pr: String,
+}
+
+/// Get the LNURL-pay service parameters
+///
+/// **Proxy is ignored for WASM targets!**
+#[cfg(feature = "std")]
+pub async fn get_lnurl_pay(
+    url: &Url,
+    _proxy: Option<SocketAddr>,
+) -> Result<LnUrlPayResponse, Error> {
+    let client: reqwest::Client = http_client(_proxy)?;
+    let json: String = client.get(url.as_str()).send().await?.text().await?;
+    let res: LnUrlResponse<LnUrlPayResponse> = serde_json::from_str(&json)?;
+    res.into_result()
+}
+
+/// Request a bolt11 invoice of `amount` (millisats) for the zap request
+///
+/// `lnurl` is the bech32 LNURL of the recipient.
+///
+/// **Proxy is ignored for WASM targets!**
+#[cfg(feature = "std")]
+pub async fn get_zap_invoice(
+    pay: &LnUrlPayResponse,
+    zap_request: &Event,
+    amount: u64,
+    lnurl: Option<&str>,
+    _proxy: Option<SocketAddr>,
+) -> Result<String, Error> {
+    let url: Url = pay.zap_invoice_url(zap_request, amount, lnurl)?;
+    let client: reqwest::Client = http_client(_proxy)?;
+    let json: String = client.get(url.as_str()).send().await?.text().await?;
+    let res: LnUrlResponse<ZapInvoiceResponse> = serde_json::from_str(&json)?;
+    Ok(res.into_result()?.pr)
+}
+
+#[cfg(feature = "std")]
+fn http_client(_proxy: Option<SocketAddr>) -> Result<reqwest::Client, Error> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        let mut builder = reqwest::Client::builder();
+        if let Some(proxy) = _proxy {
+            let proxy = format!("socks5h://{proxy}");
+            builder = builder.proxy(Proxy::all(proxy)?);
+        }
+        Ok(builder.build()?)
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    Ok(reqwest::Client::new())
+}
+
+/// Get the amount (millisats) of a bolt11 invoice
+///
+/// Return `None` if the invoice is invalid or doesn't specify the amount.
+pub fn bolt11_amount_msat(invoice: &str) -> Option<u64> {
+    let invoice: String = invoice.to_lowercase();
+    let hrp: &str = invoice.get(..invoice.rfind('1')?)?.strip_prefix("ln")?;
+
+    // Skip the currency prefix (i.e. `bc`, `tb`, `bcrt`)
+    let amount: &str = hrp.trim_start_matches(|c: char| c.is_ascii_alphabetic());
+    let (digits, multiplier) = match amount.chars().last()? {
+        c if c.is_ascii_digit() => (amount, None),
+        c => (amount.get(..amount.len() - 1)?, Some(c)),
+    };
+    if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let value: u64 = digits.parse().ok()?;
+
+    // Millisats per unit
+    match multiplier {
+        None => value.checked_mul(100_000_000_000),
+        Some('m') => value.checked_mul(100_000_000),
+        Some('u') => value.checked_mul(100_000),
+        Some('n') => value.checked_mul(100),
+        Some('p') if value % 10 == 0 => Some(value / 10),
+        _ => None,
+    }
+}
+
+/// Validated zap receipt (kind `9735`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ZapReceipt {
+    /// Zap receipt ID
+    pub id: EventId,
+    /// Zap receipt author (the LNURL server key)
+    pub zapper: XOnlyPublicKey,
+    /// Zap request author
+    pub sender: XOnlyPublicKey,
+    /// Zapped public key
+    pub recipient: XOnlyPublicKey,
+    /// Zapped event
+    pub event_id: Option<EventId>,
+    /// Amount (millisats)
+    pub amount_msat: u64,
+    /// Zap request message
+    pub message: String,
+}
+
+impl ZapReceipt {
+    /// Parse and validate zap receipt
+    ///
+    /// Check that the `description` is a valid (signed) zap request for the same recipient and event
+    /// and that the amount of the `bolt11` invoice matches the zap request `amount`, if any.
+    ///
+    /// The receipt author must be checked against the `nostrPubkey` of the recipient LNURL
+    /// (see [`LnUrlPayResponse::zapper`]).
+    pub fn from_event(receipt: &Event) -> Result<Self, Error> {
+        if receipt.kind() != Kind::ZapReceipt {
+            return Err(Error::InvalidZapReceipt(String::from("wrong kind")));
+        }
+
+        let mut bolt11: Option<&str> = None;
+        let mut description: Option<&str> = None;
+        for tag in receipt.iter_tags() {
+            match tag {
+                Tag::Bolt11(invoice) => bolt11 = Some(invoice),
+                Tag::Description(desc) => description = Some(desc),
+                _ => (),
+            }
+        }
+
+        let invalid = |reason: &str| Error::InvalidZapReceipt(reason.to_string());
+
+        let zap_request: Event =
+            Event::from_json(description.ok_or_else(|| invalid("description not found"))?)
+                .map_err(|_| invalid("invalid zap request"))?;
+        if zap_request.kind() != Kind::ZapRequest {
+            return Err(invalid("description is not a zap request"));
+        }
+        zap_request
+            .verify()
+            .map_err(|_| invalid("invalid zap request signature"))?;
+
+        let recipient: XOnlyPublicKey = *zap_request
+            .public_keys()
+            .next()
+            .ok_or_else(|| invalid("zap request recipient not found"))?;
+        if receipt.public_keys().next() != Some(&recipient) {
+            return Err(invalid("recipient mismatch"));
+        }
+
+        let event_id: Option<EventId> = zap_request.event_ids().next().copied();
+        if receipt.event_ids().next().copied() != event_id {
+            return Err(invalid("event mismatch"));
+        }
+
+        let amount_msat: u64 = bolt11
+            .and_then(bolt11_amount_msat)
+            .ok_or_else(|| invalid("invalid bolt11 amount"))?;
+        let requested: Option<u64> = zap_request.iter_tags().find_map(|tag| match tag {
+            Tag::Amount { millisats, .. } => Some(*millisats),
+            _ => None,
+        });
+        if requested.map_or(false, |requested| requested != amount_msat) {
+            return Err(invalid("amount mismatch"));
+        }
+
+        Ok(Self {
+            id: receipt.id(),
+            zapper: receipt.author(),
+            sender: zap_request.author(),
+            recipient,
+            event_id,
+            amount_msat,
+            message: zap_request.content().to_string(),
+        })
+    }
+}
+
+/// Aggregated zaps of an event
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Zaps {
+    /// Number of zaps
+    pub count: usize,
+    /// Total amount (millisats)
+    pub total_msat: u64,
+    /// Total amount (millisats) by sender
+    pub by_sender: BTreeMap<XOnlyPublicKey, u64>,
+    /// Valid zap receipts
+    pub receipts: Vec<ZapReceipt>,
+}
+
+impl Zaps {
+    /// Aggregate the zap receipts of `event_id`
+    ///
+    /// `zapper` is the `nostrPubkey` of the recipient LNURL (see [`LnUrlPayResponse::zapper`]):
+    /// the receipts not signed by it, the invalid and duplicated ones are skipped.
+    pub fn from_receipts<'a, I>(event_id: &EventId, zapper: &XOnlyPublicKey, receipts: I) -> Self
+    where
+        I: IntoIterator<Item = &'a Event>,
+    {
+        let mut zaps = Self::default();
+        let mut seen: BTreeSet<EventId> = BTreeSet::new();
+
+        for receipt in receipts.into_iter() {
+            if &receipt.author() != zapper || !seen.insert(receipt.id()) {
+                continue;
+            }
+
+            match ZapReceipt::from_event(receipt) {
+                Ok(receipt) if receipt.event_id.as_ref() == Some(event_id) => {
+                    zaps.count += 1;
+                    zaps.total_msat = zaps.total_msat.saturating_add(receipt.amount_msat);
+                    let sender = zaps.by_sender.entry(receipt.sender).or_default();
+                    *sender = sender.saturating_add(receipt.amount_msat);
+                    zaps.receipts.push(receipt);
+                }
+                Ok(..) => (),
+                Err(e) => tracing::debug!("Skipping zap receipt {}: {e}", receipt.id()),
+            }
+        }
+
+        zaps
+    }
+}
+
 #[cfg(feature = "std")]
 #[cfg(test)]
 mod tests {
@@ -417,4 +831,122 @@ mod tests {
 
         assert_eq!(msg, private_zap_msg.content())
     }
+
+    #[test]
+    fn test_lnurl_pay_url() {
+        assert_eq!(
+            lnurl_pay_url("alice@example.com").unwrap().as_str(),
+            "https://example.com/.well-known/lnurlp/alice"
+        );
+        assert!(lnurl_pay_url("@example.com").is_err());
+
+        // LUD01 example
+        let lnurl = "LNURL1DP68GURN8GHJ7UM9WFMXJCM99E3K7MF0V9CXJ0M385EKVCENXC6R2C35XVUKXEFCV5MKVV34X5EKZD3EV56NYD3HXQURZEPEXEJXXEPNXSCRVWFNV9NXZCN9XQ6XYEFHVGCXXCMYXYMNSERXFQ5FNS";
+        let url: Url = lnurl_pay_url(lnurl).unwrap();
+        assert_eq!(
+            url.as_str(),
+            "https://service.com/api?q=3fc3645b439ce8e7f2553a69e5267081d96dcd340693afabe04be7b0ccd178df"
+        );
+        assert_eq!(lnurl_encode(&url).unwrap(), lnurl.to_lowercase());
+
+        let metadata = Metadata::new().lud06("").lud16("alice@example.com");
+        assert_eq!(
+            lnurl_pay_url_from_metadata(&metadata).unwrap(),
+            lnurl_pay_url("alice@example.com").unwrap()
+        );
+        assert!(matches!(
+            lnurl_pay_url_from_metadata(&Metadata::new().lud06("")),
+            Err(Error::LnurlNotFound)
+        ));
+    }
+
+    #[test]
+    fn test_zap_invoice_url() {
+        let keys = Keys::generate();
+        let json = r#"{"callback":"https://example.com/lnurlp/alice/callback","maxSendable":100000000,"minSendable":1000,"metadata":"[]","tag":"payRequest","allowsNostr":true,"nostrPubkey":"79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798"}"#;
+        let pay: LnUrlPayResponse = serde_json::from_str(json).unwrap();
+        assert!(pay.zapper().is_some());
+
+        let data = ZapRequestData::new(keys.public_key(), []).amount(21_000);
+        let zap_request: Event = EventBuilder::public_zap_request(data)
+            .to_event(&keys)
+            .unwrap();
+        let url: Url = pay
+            .zap_invoice_url(&zap_request, 21_000, Some("lnurl1abc"))
+            .unwrap();
+        let pairs: Vec<(String, String)> = url.query_pairs().into_owned().collect();
+        assert_eq!(pairs[0], (String::from("amount"), String::from("21000")));
+        assert_eq!(
+            Event::from_json(&pairs[1].1).unwrap().id(),
+            zap_request.id()
+        );
+        assert_eq!(pairs[2], (String::from("lnurl"), String::from("lnurl1abc")));
+
+        assert!(matches!(
+            pay.zap_invoice_url(&zap_request, 10, None),
+            Err(Error::AmountOutOfRange { .. })
+        ));
+
+        // Error response
+        let res: LnUrlResponse<ZapInvoiceResponse> =
+            serde_json::from_str(r#"{"status":"ERROR","reason":"Amount too low"}"#).unwrap();
+        assert!(matches!(res.into_result(), Err(Error::Lnurl(..))));
+    }
+
+    #[test]
+    fn test_bolt11_amount_msat() {
+        assert_eq!(bolt11_amount_msat("lnbc2500u1pvjluez"), Some(250_000_000));
+        assert_eq!(bolt11_amount_msat("LNBC20M1PVJLUEZ"), Some(2_000_000_000));
+        assert_eq!(bolt11_amount_msat("lntb1u1pvjluez"), Some(100_000));
+        assert_eq!(bolt11_amount_msat("lnbcrt50n1pvjluez"), Some(5_000));
+        assert_eq!(bolt11_amount_msat("lnbc10p1pvjluez"), Some(1));
+        assert_eq!(bolt11_amount_msat("lnbc1p1pvjluez"), None);
+        assert_eq!(bolt11_amount_msat("lnbc1pvjluez"), None);
+        assert_eq!(bolt11_amount_msat("invoice"), None);
+    }
+
+    #[test]
+    fn test_zap_receipts() {
+        let sender = Keys::generate();
+        let recipient = Keys::generate();
+        let zapper = Keys::generate();
+        let note = EventBuilder::text_note("Hello", [])
+            .to_event(&recipient)
+            .unwrap();
+
+        let data = ZapRequestData::new(recipient.public_key(), [])
+            .amount(250_000_000)
+            .event_id(note.id());
+        let zap_request: Event = EventBuilder::public_zap_request(data)
+            .to_event(&sender)
+            .unwrap();
+
+        let receipt: Event =
+            EventBuilder::zap_receipt("lnbc2500u1pvjluez", None, zap_request.clone())
+                .to_event(&zapper)
+                .unwrap();
+        let parsed = ZapReceipt::from_event(&receipt).unwrap();
+        assert_eq!(parsed.sender, sender.public_key());
+        assert_eq!(parsed.recipient, recipient.public_key());
+        assert_eq!(parsed.event_id, Some(note.id()));
+        assert_eq!(parsed.amount_msat, 250_000_000);
+
+        // Amount mismatch
+        let wrong_amount: Event =
+            EventBuilder::zap_receipt("lnbc10u1pvjluez", None, zap_request.clone())
+                .to_event(&zapper)
+                .unwrap();
+        assert!(ZapReceipt::from_event(&wrong_amount).is_err());
+
+        // Fake zapper
+        let fake: Event = EventBuilder::zap_receipt("lnbc2500u1pvjluez", None, zap_request)
+            .to_event(&Keys::generate())
+            .unwrap();
+
+        let receipts = [receipt.clone(), receipt, wrong_amount, fake];
+        let zaps = Zaps::from_receipts(&note.id(), &zapper.public_key(), &receipts);
+        assert_eq!(zaps.count, 1);
+        assert_eq!(zaps.total_msat, 250_000_000);
+        assert_eq!(zaps.by_sender.get(&sender.public_key()), Some(&250_000_000));
+    }
 }
@@ -0,0 +1,193 @@
+// Copyright (c) 2022-2023 Yuki Kishimoto
+// Copyright (c) 2023-2024 Rust Nostr Developers
+// Distributed under the MIT software license
+
+//! NIP59
+//!
+//! <https://github.com/nostr-protocol/nips/blob/master/59.md>
+
+use alloc::string::String;
+use core::fmt;
+use core::ops::Range;
+
+use bitcoin::secp256k1::{SecretKey, XOnlyPublicKey};
+
+use super::nip44;
+use crate::event::unsigned::{self, UnsignedEvent};
+use crate::event::{self, Event};
+use crate::key::{self, Keys};
+use crate::{JsonUtil, Kind, Timestamp};
+
+/// Range for random timestamp tweak (up to 2 days in the past)
+pub const RANGE_RANDOM_TIMESTAMP_TWEAK: Range<u64> = 0..172800;
+
+/// NIP59 error
+#[derive(Debug)]
+pub enum Error {
+    /// Key error
+    Key(key::Error),
+    /// Event error
+    Event(event::Error),
+    /// Unsigned event error
+    Unsigned(unsigned::Error),
+    /// NIP44 error
+    NIP44(nip44::Error),
+    /// Not Gift Wrap event
+    NotGiftWrap,
+    /// Not Seal event
+    NotSeal,
+    /// The author of the rumor is not the author of the seal
+    SenderMismatch,
+}
+
+impl std::error::Error for Error {}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Key(e) => write!(f, "Key: {e}"),
+            Self::Event(e) => write!(f, "Event: {e}"),
+            Self::Unsigned(e) => write!(f, "Unsigned event: {e}"),
+            Self::NIP44(e) => write!(f, "NIP44: {e}"),
+            Self::NotGiftWrap => write!(f, "Not Gift Wrap event"),
+            Self::NotSeal => write!(f, "Not Seal event"),
+            Self::SenderMismatch => write!(f, "Rumor author doesn't match seal author"),
+        }
+    }
+}
+
+impl From<key::Error> for Error {
+    fn from(e: key::Error) -> Self {
+        Self::Key(e)
+    }
+}
+
+impl From<event::Error> for Error {
+    fn from(e: event::Error) -> Self {
+        Self::Event(e)
+    }
+}
+
+impl From<unsigned::Error> for Error {
+    fn from(e: unsigned::Error) -> Self {
+        Self::Unsigned(e)
+    }
+}
+
+impl From<nip44::Error> for Error {
+    fn from(e: nip44::Error) -> Self {
+        Self::NIP44(e)
+    }
+}
+
+/// Unwrapped Gift Wrap
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnwrappedGift {
+    /// The public key of the sender (author of the seal)
+    pub sender: XOnlyPublicKey,
+    /// The rumor
+    pub rumor: UnsignedEvent,
+}
+
+impl UnwrappedGift {
+    /// Unwrap Gift Wrap event
+    ///
+    /// Decrypt the gift wrap and the seal, verify the seal signature
+    /// and check that the rumor is authored by the seal signer.
+    pub fn from_gift_wrap(receiver_keys: &Keys, gift_wrap: &Event) -> Result<Self, Error> {
+        if gift_wrap.kind() != Kind::GiftWrap {
+            return Err(Error::NotGiftWrap);
+        }
+
+        let secret_key: SecretKey = receiver_keys.secret_key()?;
+
+        // Decrypt and verify seal
+        let seal: String =
+            nip44::decrypt(&secret_key, gift_wrap.author_ref(), gift_wrap.content())?;
+        let seal: Event = Event::from_json(seal)?;
+        if seal.kind() != Kind::Seal {
+            return Err(Error::NotSeal);
+        }
+        seal.verify()?;
+
+        // Decrypt rumor
+        let rumor: String = nip44::decrypt(&secret_key, seal.author_ref(), seal.content())?;
+        let rumor: UnsignedEvent = UnsignedEvent::from_json(rumor)?;
+        if rumor.pubkey != seal.author() {
+            return Err(Error::SenderMismatch);
+        }
+
+        Ok(Self {
+            sender: seal.author(),
+            rumor,
+        })
+    }
+}
+
+/// Extract `rumor` from Gift Wrap event
+pub fn extract_rumor(receiver_keys: &Keys, gift_wrap: &Event) -> Result<UnwrappedGift, Error> {
+    UnwrappedGift::from_gift_wrap(receiver_keys, gift_wrap)
+}
+
+/// Get a random timestamp in the past, in [`RANGE_RANDOM_TIMESTAMP_TWEAK`]
+///
+/// Used for seals and gift wraps, so the `created_at` doesn't leak when the rumor was created.
+pub fn random_timestamp() -> Timestamp {
+    Timestamp::tweaked_in_range(RANGE_RANDOM_TIMESTAMP_TWEAK)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EventBuilder;
+
+    #[test]
+    fn test_gift_wrap_roundtrip() {
+        let sender_keys = Keys::generate();
+        let receiver_keys = Keys::generate();
+        let receiver = receiver_keys.public_key();
+
+        let gift_wrap: Event = EventBuilder::private_msg_rumor(receiver, "Hello", None)
+            .gift_wrap_to(&sender_keys, &receiver)
+            .unwrap();
+        assert_eq!(gift_wrap.kind(), Kind::GiftWrap);
+        assert_ne!(gift_wrap.author(), sender_keys.public_key());
+        assert!(gift_wrap.created_at() <= Timestamp::now());
+        gift_wrap.verify().unwrap();
+
+        let unwrapped = extract_rumor(&receiver_keys, &gift_wrap).unwrap();
+        assert_eq!(unwrapped.sender, sender_keys.public_key());
+        assert_eq!(unwrapped.rumor.kind, Kind::PrivateDirectMessage);
+        assert_eq!(unwrapped.rumor.content, "Hello");
+
+        // Only the receiver can unwrap
+        assert!(extract_rumor(&sender_keys, &gift_wrap).is_err());
+
+        // Not a gift wrap
+        let note = EventBuilder::text_note("Hello", [])
+            .to_event(&sender_keys)
+            .unwrap();
+        assert!(matches!(
+            extract_rumor(&receiver_keys, &note),
+            Err(Error::NotGiftWrap)
+        ));
+    }
+
+    #[test]
+    fn test_sender_mismatch() {
+        let sender_keys = Keys::generate();
+        let other_keys = Keys::generate();
+        let receiver_keys = Keys::generate();
+        let receiver = receiver_keys.public_key();
+
+        // Rumor authored by another public key
+        let rumor: UnsignedEvent =
+            EventBuilder::text_note("Hello", []).to_unsigned_event(other_keys.public_key());
+        let gift_wrap: Event =
+            EventBuilder::gift_wrap(&sender_keys, &receiver, rumor, None).unwrap();
+        assert!(matches!(
+            extract_rumor(&receiver_keys, &gift_wrap),
+            Err(Error::SenderMismatch)
+        ));
+    }
+}
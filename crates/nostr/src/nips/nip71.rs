@@ -0,0 +1,660 @@
+// Copyright (c) 2022-2023 Yuki Kishimoto
+// Copyright (c) 2023-2024 Rust Nostr Developers
+// Distributed under the MIT software license
+
+//! NIP71
+//!
+//! <https://github.com/nostr-protocol/nips/blob/master/71.md>
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+use core::time::Duration;
+
+use bitcoin::hashes::sha256::Hash as Sha256Hash;
+use url_fork::Url;
+
+use crate::{Event, ImageDimensions, Kind, Tag, TagKind, Timestamp, UncheckedUrl};
+
+/// NIP71 Error
+#[derive(Debug, PartialEq, Eq)]
+pub enum Error {
+    /// Not a video event kind
+    WrongKind,
+    /// Identifier missing (no `d` tag)
+    MissingIdentifier,
+    /// Title missing (no `title` tag)
+    MissingTitle,
+    /// URL missing (no `url` tag)
+    MissingUrl,
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::WrongKind => write!(f, "Wrong kind"),
+            Self::MissingIdentifier => write!(f, "Missing identifier"),
+            Self::MissingTitle => write!(f, "Missing title"),
+            Self::MissingUrl => write!(f, "Missing url"),
+        }
+    }
+}
+
+/// Video orientation
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum VideoOrientation {
+    /// Horizontal (landscape) video
+    #[default]
+    Horizontal,
+    /// Vertical (portrait) video
+    Vertical,
+}
+
+impl VideoOrientation {
+    /// Get event [`Kind`]
+    pub fn kind(&self) -> Kind {
+        match self {
+            Self::Horizontal => Kind::HorizontalVideo,
+            Self::Vertical => Kind::VerticalVideo,
+        }
+    }
+}
+
+impl TryFrom<Kind> for VideoOrientation {
+    type Error = Error;
+
+    fn try_from(kind: Kind) -> Result<Self, Self::Error> {
+        match kind {
+            Kind::HorizontalVideo => Ok(Self::Horizontal),
+            Kind::VerticalVideo => Ok(Self::Vertical),
+            _ => Err(Error::WrongKind),
+        }
+    }
+}
+
+/// Text track (captions, subtitles, chapters, ...)
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TextTrack {
+    /// Url of the track file
+    pub url: UncheckedUrl,
+    /// Track type (ex. `captions`, `subtitles`, `chapters` or `metadata`)
+    pub track_type: Option<String>,
+    /// Language code
+    pub language: Option<String>,
+}
+
+impl TextTrack {
+    /// New [`TextTrack`]
+    pub fn new(url: UncheckedUrl) -> Self {
+        Self {
+            url,
+            track_type: None,
+            language: None,
+        }
+    }
+
+    fn to_tag(&self) -> Tag {
+        let mut values: Vec<String> = vec![self.url.to_string()];
+        match (&self.track_type, &self.language) {
+            (Some(track_type), Some(language)) => {
+                values.push(track_type.clone());
+                values.push(language.clone());
+            }
+            (Some(track_type), None) => values.push(track_type.clone()),
+            (None, Some(language)) => {
+                values.push(String::new());
+                values.push(language.clone());
+            }
+            (None, None) => (),
+        }
+        Tag::Generic(TagKind::Custom(String::from("text-track")), values)
+    }
+
+    fn from_values(values: &[String]) -> Option<Self> {
+        let url: &String = values.first()?;
+        Some(Self {
+            url: UncheckedUrl::from(url.as_str()),
+            track_type: values.get(1).filter(|s| !s.is_empty()).cloned(),
+            language: values.get(2).filter(|s| !s.is_empty()).cloned(),
+        })
+    }
+}
+
+/// Video segment (chapter)
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct VideoSegment {
+    /// Start of the segment
+    pub start: Duration,
+    /// End of the segment
+    pub end: Duration,
+    /// Title
+    pub title: String,
+    /// Thumbnail url
+    pub thumbnail: Option<UncheckedUrl>,
+}
+
+impl VideoSegment {
+    /// New [`VideoSegment`]
+    pub fn new<S>(start: Duration, end: Duration, title: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self {
+            start,
+            end,
+            title: title.into(),
+            thumbnail: None,
+        }
+    }
+
+    /// Set thumbnail url
+    pub fn thumbnail(self, url: UncheckedUrl) -> Self {
+        Self {
+            thumbnail: Some(url),
+            ..self
+        }
+    }
+
+    /// Compose `segment` [`Tag`]
+    pub fn to_tag(&self) -> Tag {
+        let mut values: Vec<String> = vec![
+            format_timestamp(self.start),
+            format_timestamp(self.end),
+            self.title.clone(),
+        ];
+        if let Some(thumbnail) = &self.thumbnail {
+            values.push(thumbnail.to_string());
+        }
+        Tag::Generic(TagKind::Custom(String::from("segment")), values)
+    }
+
+    /// Parse [`VideoSegment`] from `segment` [`Tag`]
+    ///
+    /// Return `None` if it's not a `segment` tag or if it's malformed.
+    pub fn from_tag(tag: &Tag) -> Option<Self> {
+        match tag {
+            Tag::Generic(TagKind::Custom(kind), values) if kind == "segment" => {
+                Self::from_values(values)
+            }
+            _ => None,
+        }
+    }
+
+    fn from_values(values: &[String]) -> Option<Self> {
+        Some(Self {
+            start: parse_timestamp(values.first()?)?,
+            end: parse_timestamp(values.get(1)?)?,
+            title: values.get(2).cloned().unwrap_or_default(),
+            thumbnail: values
+                .get(3)
+                .filter(|s| !s.is_empty())
+                .map(|s| UncheckedUrl::from(s.as_str())),
+        })
+    }
+}
+
+/// Parse `HH:MM:SS.sss` timestamp
+fn parse_timestamp(timestamp: &str) -> Option<Duration> {
+    let mut parts = timestamp.splitn(3, ':');
+    let hours: u64 = parts.next()?.parse().ok()?;
+    let minutes: u64 = parts.next()?.parse().ok()?;
+    let seconds: &str = parts.next()?;
+    let (secs, millis): (&str, u32) = match seconds.split_once('.') {
+        Some((secs, fraction)) => {
+            // Milliseconds precision
+            let fraction: String = format!("{fraction:0<3}");
+            (secs, fraction.get(..3)?.parse().ok()?)
+        }
+        None => (seconds, 0),
+    };
+    let secs: u64 = hours
+        .checked_mul(3600)?
+        .checked_add(minutes.checked_mul(60)?)?
+        .checked_add(secs.parse().ok()?)?;
+    Some(Duration::new(secs, millis * 1_000_000))
+}
+
+/// Format as `HH:MM:SS.sss` timestamp
+fn format_timestamp(duration: Duration) -> String {
+    let secs: u64 = duration.as_secs();
+    format!(
+        "{:02}:{:02}:{:02}.{:03}",
+        secs / 3600,
+        secs % 3600 / 60,
+        secs % 60,
+        duration.subsec_millis()
+    )
+}
+
+/// Video Event
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VideoEvent {
+    /// Unique identifier (`d` tag)
+    pub identifier: String,
+    /// Orientation
+    pub orientation: VideoOrientation,
+    /// Title
+    pub title: String,
+    /// Video url
+    pub url: Url,
+    /// Summary or description of the video (event content)
+    pub description: String,
+    /// MIME type
+    pub mime_type: Option<String>,
+    /// SHA256 of file
+    pub hash: Option<Sha256Hash>,
+    /// Size in bytes
+    pub size: Option<usize>,
+    /// Size in pixels
+    pub dim: Option<ImageDimensions>,
+    /// Duration in seconds
+    pub duration: Option<u64>,
+    /// Magnet
+    pub magnet: Option<String>,
+    /// First publication timestamp
+    pub published_at: Option<Timestamp>,
+    /// Thumbnails
+    pub thumbnails: Vec<(UncheckedUrl, Option<ImageDimensions>)>,
+    /// Preview images
+    pub images: Vec<(UncheckedUrl, Option<ImageDimensions>)>,
+    /// Text tracks
+    pub text_tracks: Vec<TextTrack>,
+    /// Segments (chapters)
+    pub segments: Vec<VideoSegment>,
+    /// Hashtags
+    pub hashtags: Vec<String>,
+}
+
+impl VideoEvent {
+    /// New [`VideoEvent`]
+    pub fn new<S, T>(identifier: S, title: T, url: Url) -> Self
+    where
+        S: Into<String>,
+        T: Into<String>,
+    {
+        Self {
+            identifier: identifier.into(),
+            orientation: VideoOrientation::default(),
+            title: title.into(),
+            url,
+            description: String::new(),
+            mime_type: None,
+            hash: None,
+            size: None,
+            dim: None,
+            duration: None,
+            magnet: None,
+            published_at: None,
+            thumbnails: Vec::new(),
+            images: Vec::new(),
+            text_tracks: Vec::new(),
+            segments: Vec::new(),
+            hashtags: Vec::new(),
+        }
+    }
+
+    /// Set orientation
+    pub fn orientation(self, orientation: VideoOrientation) -> Self {
+        Self {
+            orientation,
+            ..self
+        }
+    }
+
+    /// Set description
+    pub fn description<S>(self, description: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self {
+            description: description.into(),
+            ..self
+        }
+    }
+
+    /// Set MIME type
+    pub fn mime_type<S>(self, mime_type: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self {
+            mime_type: Some(mime_type.into()),
+            ..self
+        }
+    }
+
+    /// Set SHA256 of file
+    pub fn hash(self, hash: Sha256Hash) -> Self {
+        Self {
+            hash: Some(hash),
+            ..self
+        }
+    }
+
+    /// Set file size (bytes)
+    pub fn size(self, size: usize) -> Self {
+        Self {
+            size: Some(size),
+            ..self
+        }
+    }
+
+    /// Set video size (pixels)
+    pub fn dimensions(self, dim: ImageDimensions) -> Self {
+        Self {
+            dim: Some(dim),
+            ..self
+        }
+    }
+
+    /// Set duration (seconds)
+    pub fn duration(self, duration: u64) -> Self {
+        Self {
+            duration: Some(duration),
+            ..self
+        }
+    }
+
+    /// Set magnet
+    pub fn magnet<S>(self, magnet: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self {
+            magnet: Some(magnet.into()),
+            ..self
+        }
+    }
+
+    /// Set first publication timestamp
+    pub fn published_at(self, published_at: Timestamp) -> Self {
+        Self {
+            published_at: Some(published_at),
+            ..self
+        }
+    }
+
+    /// Add thumbnail
+    pub fn thumbnail(mut self, url: UncheckedUrl, dim: Option<ImageDimensions>) -> Self {
+        self.thumbnails.push((url, dim));
+        self
+    }
+
+    /// Add preview image
+    pub fn image(mut self, url: UncheckedUrl, dim: Option<ImageDimensions>) -> Self {
+        self.images.push((url, dim));
+        self
+    }
+
+    /// Add text track
+    pub fn text_track(mut self, track: TextTrack) -> Self {
+        self.text_tracks.push(track);
+        self
+    }
+
+    /// Add segment
+    pub fn segment(mut self, segment: VideoSegment) -> Self {
+        self.segments.push(segment);
+        self
+    }
+
+    /// Add hashtag
+    pub fn hashtag<S>(mut self, hashtag: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.hashtags.push(hashtag.into());
+        self
+    }
+
+    /// Parse [`VideoEvent`] from [`Event`]
+    pub fn from_event(event: &Event) -> Result<Self, Error> {
+        let orientation: VideoOrientation = VideoOrientation::try_from(event.kind())?;
+
+        let identifier: &str = event.identifier().ok_or(Error::MissingIdentifier)?;
+        let title: &String = event
+            .iter_tags()
+            .find_map(|t| match t {
+                Tag::Title(title) => Some(title),
+                _ => None,
+            })
+            .ok_or(Error::MissingTitle)?;
+        let url: &Url = event
+            .iter_tags()
+            .find_map(|t| match t {
+                Tag::Url(url) => Some(url),
+                _ => None,
+            })
+            .ok_or(Error::MissingUrl)?;
+
+        let mut video = Self::new(identifier, title, url.clone())
+            .orientation(orientation)
+            .description(event.content());
+
+        for tag in event.iter_tags() {
+            match tag {
+                Tag::MimeType(mime_type) => video.mime_type = Some(mime_type.clone()),
+                Tag::Sha256(hash) => video.hash = Some(*hash),
+                Tag::Size(size) => video.size = Some(*size),
+                Tag::Dim(dim) => video.dim = Some(*dim),
+                Tag::Magnet(magnet) => video.magnet = Some(magnet.clone()),
+                Tag::PublishedAt(timestamp) => video.published_at = Some(*timestamp),
+                Tag::Thumb(url, dim) => video.thumbnails.push((url.clone(), *dim)),
+                Tag::Image(url, dim) => video.images.push((url.clone(), *dim)),
+                Tag::Hashtag(hashtag) => video.hashtags.push(hashtag.clone()),
+                Tag::Generic(TagKind::Custom(kind), values) => match kind.as_str() {
+                    "duration" => {
+                        video.duration = values.first().and_then(|d| d.parse().ok());
+                    }
+                    "text-track" => {
+                        if let Some(track) = TextTrack::from_values(values) {
+                            video.text_tracks.push(track);
+                        }
+                    }
+                    "segment" => {
+                        if let Some(segment) = VideoSegment::from_values(values) {
+                            video.segments.push(segment);
+                        }
+                    }
+                    _ => (),
+                },
+                _ => (),
+            }
+        }
+
+        Ok(video)
+    }
+}
+
+impl From<VideoEvent> for Vec<Tag> {
+    fn from(video: VideoEvent) -> Self {
+        let VideoEvent {
+            identifier,
+            title,
+            url,
+            mime_type,
+            hash,
+            size,
+            dim,
+            duration,
+            magnet,
+            published_at,
+            thumbnails,
+            images,
+            text_tracks,
+            segments,
+            hashtags,
+            ..
+        } = video;
+
+        let mut tags: Vec<Tag> = vec![
+            Tag::Identifier(identifier),
+            Tag::Title(title),
+            Tag::Url(url),
+        ];
+
+        if let Some(mime_type) = mime_type {
+            tags.push(Tag::MimeType(mime_type));
+        }
+
+        if let Some(hash) = hash {
+            tags.push(Tag::Sha256(hash));
+        }
+
+        if let Some(size) = size {
+            tags.push(Tag::Size(size));
+        }
+
+        if let Some(dim) = dim {
+            tags.push(Tag::Dim(dim));
+        }
+
+        if let Some(duration) = duration {
+            tags.push(Tag::Generic(
+                TagKind::Custom(String::from("duration")),
+                vec![duration.to_string()],
+            ));
+        }
+
+        if let Some(magnet) = magnet {
+            tags.push(Tag::Magnet(magnet));
+        }
+
+        if let Some(published_at) = published_at {
+            tags.push(Tag::PublishedAt(published_at));
+        }
+
+        for (url, dim) in thumbnails.into_iter() {
+            tags.push(Tag::Thumb(url, dim));
+        }
+
+        for (url, dim) in images.into_iter() {
+            tags.push(Tag::Image(url, dim));
+        }
+
+        for track in text_tracks.iter() {
+            tags.push(track.to_tag());
+        }
+
+        for segment in segments.iter() {
+            tags.push(segment.to_tag());
+        }
+
+        for hashtag in hashtags.into_iter() {
+            tags.push(Tag::Hashtag(hashtag));
+        }
+
+        tags
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{EventBuilder, JsonUtil, Keys};
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_video_event_round_trip() {
+        let keys = Keys::generate();
+
+        let video = VideoEvent::new(
+            "my-video",
+            "My video",
+            Url::parse("https://example.com/video.mp4").unwrap(),
+        )
+        .orientation(VideoOrientation::Vertical)
+        .description("A short video")
+        .mime_type("video/mp4")
+        .dimensions(ImageDimensions {
+            width: 1080,
+            height: 1920,
+        })
+        .duration(42)
+        .thumbnail(UncheckedUrl::from("https://example.com/thumb.jpg"), None)
+        .text_track(TextTrack {
+            url: UncheckedUrl::from("https://example.com/captions.vtt"),
+            track_type: Some(String::from("captions")),
+            language: Some(String::from("en")),
+        })
+        .segment(
+            VideoSegment::new(Duration::ZERO, Duration::from_millis(90_500), "Intro")
+                .thumbnail(UncheckedUrl::from("https://example.com/intro.jpg")),
+        )
+        .segment(VideoSegment::new(
+            Duration::from_millis(90_500),
+            Duration::from_secs(3725),
+            "Main",
+        ))
+        .hashtag("nostr");
+
+        let event = EventBuilder::video_event(video.clone())
+            .to_event(&keys)
+            .unwrap();
+        assert_eq!(event.kind(), Kind::VerticalVideo);
+        assert_eq!(event.content(), "A short video");
+
+        let event = Event::from_json(event.as_json()).unwrap();
+        assert_eq!(VideoEvent::from_event(&event).unwrap(), video);
+    }
+
+    #[test]
+    fn test_video_segment() {
+        let tag = Tag::parse(vec![
+            "segment",
+            "00:01:30.5",
+            "01:02:05.000",
+            "Main",
+            "https://example.com/main.jpg",
+        ])
+        .unwrap();
+        let segment = VideoSegment::from_tag(&tag).unwrap();
+        assert_eq!(segment.start, Duration::from_millis(90_500));
+        assert_eq!(segment.end, Duration::from_secs(3725));
+        assert_eq!(segment.title, "Main");
+        assert_eq!(
+            segment.to_tag().as_vec(),
+            vec![
+                "segment",
+                "00:01:30.500",
+                "01:02:05.000",
+                "Main",
+                "https://example.com/main.jpg"
+            ]
+        );
+
+        let tag = Tag::parse(vec!["segment", "00:01", "00:02:00", "Title"]).unwrap();
+        assert_eq!(VideoSegment::from_tag(&tag), None);
+        assert_eq!(
+            VideoSegment::from_tag(&Tag::Hashtag(String::from("a"))),
+            None
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_video_event_missing_fields() {
+        let keys = Keys::generate();
+
+        let event = EventBuilder::new(
+            Kind::HorizontalVideo,
+            "",
+            [Tag::Identifier(String::from("id"))],
+        )
+        .to_event(&keys)
+        .unwrap();
+        assert_eq!(
+            VideoEvent::from_event(&event).unwrap_err(),
+            Error::MissingTitle
+        );
+
+        let event = EventBuilder::text_note("", []).to_event(&keys).unwrap();
+        assert_eq!(
+            VideoEvent::from_event(&event).unwrap_err(),
+            Error::WrongKind
+        );
+    }
+}
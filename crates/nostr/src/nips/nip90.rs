@@ -6,20 +6,37 @@
 //!
 //! <https://github.com/nostr-protocol/nips/blob/master/90.md>
 
+use alloc::string::{String, ToString};
 use core::fmt;
 use core::str::FromStr;
 
+use bitcoin::secp256k1::XOnlyPublicKey;
+
+use crate::{Event, EventId, Kind, Tag};
+
 /// DVM Error
 #[derive(Debug)]
 pub enum Error {
     /// Unknown status
     UnknownStatus,
+    /// Not a job feedback event (kind `7000`)
+    NotJobFeedback,
+    /// Missing status (no `status` tag)
+    MissingStatus,
+    /// Missing job request ID (no `e` tag)
+    MissingJobRequestId,
 }
 
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Self::UnknownStatus => write!(f, "Unknown status"),
+            Self::NotJobFeedback => write!(f, "Not a job feedback event"),
+            Self::MissingStatus => write!(f, "Missing status"),
+            Self::MissingJobRequestId => write!(f, "Missing job request ID"),
         }
     }
 }
@@ -65,3 +82,110 @@ impl FromStr for DataVendingMachineStatus {
         }
     }
 }
+
+/// Job feedback (kind `7000`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JobFeedback {
+    /// Job request ID
+    pub job_request_id: EventId,
+    /// Service provider
+    pub provider: XOnlyPublicKey,
+    /// Status
+    pub status: DataVendingMachineStatus,
+    /// Extra info about the status
+    pub extra_info: Option<String>,
+    /// Requested amount (millisats)
+    pub amount_millisats: Option<u64>,
+    /// Bolt11 invoice
+    pub bolt11: Option<String>,
+    /// Payload (i.e. partial results)
+    pub payload: Option<String>,
+}
+
+impl JobFeedback {
+    /// Parse job feedback [`Event`]
+    pub fn from_event(event: &Event) -> Result<Self, Error> {
+        if event.kind() != Kind::JobFeedback {
+            return Err(Error::NotJobFeedback);
+        }
+
+        let mut job_request_id: Option<EventId> = None;
+        let mut status: Option<(DataVendingMachineStatus, Option<String>)> = None;
+        let mut amount: Option<(u64, Option<String>)> = None;
+        for tag in event.iter_tags() {
+            match tag {
+                Tag::Event { event_id, .. } if job_request_id.is_none() => {
+                    job_request_id = Some(*event_id)
+                }
+                Tag::DataVendingMachineStatus {
+                    status: s,
+                    extra_info,
+                } => status = Some((*s, extra_info.clone())),
+                Tag::Amount { millisats, bolt11 } => amount = Some((*millisats, bolt11.clone())),
+                _ => (),
+            }
+        }
+
+        let (status, extra_info) = status.ok_or(Error::MissingStatus)?;
+        let (amount_millisats, bolt11) = match amount {
+            Some((millisats, bolt11)) => (Some(millisats), bolt11),
+            None => (None, None),
+        };
+        Ok(Self {
+            job_request_id: job_request_id.ok_or(Error::MissingJobRequestId)?,
+            provider: event.author(),
+            status,
+            extra_info,
+            amount_millisats,
+            bolt11,
+            payload: if event.content().is_empty() {
+                None
+            } else {
+                Some(event.content().to_string())
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "std")]
+    use crate::{EventBuilder, Keys};
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_job_feedback() {
+        let customer = Keys::generate();
+        let provider = Keys::generate();
+
+        let request = EventBuilder::job_request(Kind::JobRequest(5001), [])
+            .unwrap()
+            .to_event(&customer)
+            .unwrap();
+        let feedback = EventBuilder::job_feedback(
+            &request,
+            DataVendingMachineStatus::PaymentRequired,
+            Some(String::from("Pay first")),
+            1000,
+            Some(String::from("lnbc10n1...")),
+            None,
+        )
+        .to_event(&provider)
+        .unwrap();
+
+        let feedback = JobFeedback::from_event(&feedback).unwrap();
+        assert_eq!(feedback.job_request_id, request.id());
+        assert_eq!(feedback.provider, provider.public_key());
+        assert_eq!(feedback.status, DataVendingMachineStatus::PaymentRequired);
+        assert_eq!(feedback.extra_info, Some(String::from("Pay first")));
+        assert_eq!(feedback.amount_millisats, Some(1000));
+        assert_eq!(feedback.bolt11, Some(String::from("lnbc10n1...")));
+        assert_eq!(feedback.payload, None);
+
+        assert!(matches!(
+            JobFeedback::from_event(&request),
+            Err(Error::NotJobFeedback)
+        ));
+    }
+}
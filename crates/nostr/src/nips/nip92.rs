@@ -0,0 +1,307 @@
+// Copyright (c) 2022-2023 Yuki Kishimoto
+// Copyright (c) 2023-2024 Rust Nostr Developers
+// Distributed under the MIT software license
+
+//! NIP92
+//!
+//! <https://github.com/nostr-protocol/nips/blob/master/92.md>
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+use core::str::FromStr;
+
+use bitcoin::hashes::sha256::Hash as Sha256Hash;
+
+use crate::event::tag;
+use crate::{ImageDimensions, Tag, TagKind, UncheckedUrl};
+
+/// NIP92 error
+#[derive(Debug)]
+pub enum Error {
+    /// Tag error
+    Tag(tag::Error),
+    /// Hex error
+    Hex(bitcoin::hashes::hex::Error),
+    /// Not an `imeta` tag
+    WrongTag,
+    /// URL missing (no `url` field)
+    MissingUrl,
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Tag(e) => write!(f, "Tag: {e}"),
+            Self::Hex(e) => write!(f, "Hex: {e}"),
+            Self::WrongTag => write!(f, "Not an imeta tag"),
+            Self::MissingUrl => write!(f, "Missing url"),
+        }
+    }
+}
+
+impl From<tag::Error> for Error {
+    fn from(e: tag::Error) -> Self {
+        Self::Tag(e)
+    }
+}
+
+impl From<bitcoin::hashes::hex::Error> for Error {
+    fn from(e: bitcoin::hashes::hex::Error) -> Self {
+        Self::Hex(e)
+    }
+}
+
+/// Media attachment (`imeta` tag)
+///
+/// Every field of the tag is a space-delimited key/value pair (i.e. `"m image/jpeg"`).
+/// Unknown fields are kept in [`MediaAttachment::custom`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MediaAttachment {
+    /// Url
+    pub url: UncheckedUrl,
+    /// MIME type
+    pub mime_type: Option<String>,
+    /// SHA256 of file
+    pub hash: Option<Sha256Hash>,
+    /// Size in pixels
+    pub dim: Option<ImageDimensions>,
+    /// Blurhash
+    pub blurhash: Option<String>,
+    /// Description (for accessibility)
+    pub alt: Option<String>,
+    /// Fallback urls
+    pub fallback: Vec<UncheckedUrl>,
+    /// Other fields
+    pub custom: Vec<(String, String)>,
+}
+
+impl MediaAttachment {
+    /// New [`MediaAttachment`]
+    pub fn new(url: UncheckedUrl) -> Self {
+        Self {
+            url,
+            mime_type: None,
+            hash: None,
+            dim: None,
+            blurhash: None,
+            alt: None,
+            fallback: Vec::new(),
+            custom: Vec::new(),
+        }
+    }
+
+    /// Set MIME type
+    pub fn mime_type<S>(self, mime_type: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self {
+            mime_type: Some(mime_type.into()),
+            ..self
+        }
+    }
+
+    /// Set SHA256 of file
+    pub fn hash(self, hash: Sha256Hash) -> Self {
+        Self {
+            hash: Some(hash),
+            ..self
+        }
+    }
+
+    /// Set size (pixels)
+    pub fn dimensions(self, dim: ImageDimensions) -> Self {
+        Self {
+            dim: Some(dim),
+            ..self
+        }
+    }
+
+    /// Set blurhash
+    pub fn blurhash<S>(self, blurhash: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self {
+            blurhash: Some(blurhash.into()),
+            ..self
+        }
+    }
+
+    /// Set description
+    pub fn alt<S>(self, alt: S) -> Self
+    where
+        S: Into<String>,
+    {
+        Self {
+            alt: Some(alt.into()),
+            ..self
+        }
+    }
+
+    /// Add fallback url
+    pub fn fallback(mut self, url: UncheckedUrl) -> Self {
+        self.fallback.push(url);
+        self
+    }
+
+    /// Add custom field
+    pub fn custom_field<K, V>(mut self, key: K, value: V) -> Self
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        self.custom.push((key.into(), value.into()));
+        self
+    }
+
+    /// Parse [`MediaAttachment`] from `imeta` [`Tag`]
+    pub fn from_tag(tag: &Tag) -> Result<Self, Error> {
+        match tag {
+            Tag::Generic(TagKind::Custom(kind), values) if kind == "imeta" => {
+                Self::from_values(values)
+            }
+            _ => Err(Error::WrongTag),
+        }
+    }
+
+    fn from_values(values: &[String]) -> Result<Self, Error> {
+        let url: &str = values
+            .iter()
+            .find_map(|v| v.strip_prefix("url "))
+            .ok_or(Error::MissingUrl)?;
+        let mut attachment = Self::new(UncheckedUrl::from(url));
+
+        for value in values.iter() {
+            let (key, value): (&str, &str) = match value.split_once(' ') {
+                Some(field) => field,
+                None => continue,
+            };
+            match key {
+                "url" => (),
+                "m" => attachment.mime_type = Some(value.to_string()),
+                "x" => attachment.hash = Some(Sha256Hash::from_str(value)?),
+                "dim" => attachment.dim = Some(ImageDimensions::from_str(value)?),
+                "blurhash" => attachment.blurhash = Some(value.to_string()),
+                "alt" => attachment.alt = Some(value.to_string()),
+                "fallback" => attachment.fallback.push(UncheckedUrl::from(value)),
+                key => attachment.custom.push((key.to_string(), value.to_string())),
+            }
+        }
+
+        Ok(attachment)
+    }
+
+    /// Compose `imeta` [`Tag`]
+    pub fn to_tag(&self) -> Tag {
+        let mut values: Vec<String> = vec![format!("url {}", self.url)];
+
+        if let Some(mime_type) = &self.mime_type {
+            values.push(format!("m {mime_type}"));
+        }
+
+        if let Some(hash) = &self.hash {
+            values.push(format!("x {hash}"));
+        }
+
+        if let Some(dim) = &self.dim {
+            values.push(format!("dim {dim}"));
+        }
+
+        if let Some(blurhash) = &self.blurhash {
+            values.push(format!("blurhash {blurhash}"));
+        }
+
+        if let Some(alt) = &self.alt {
+            values.push(format!("alt {alt}"));
+        }
+
+        for url in self.fallback.iter() {
+            values.push(format!("fallback {url}"));
+        }
+
+        for (key, value) in self.custom.iter() {
+            values.push(format!("{key} {value}"));
+        }
+
+        Tag::Generic(TagKind::Custom(String::from("imeta")), values)
+    }
+}
+
+impl From<MediaAttachment> for Tag {
+    fn from(attachment: MediaAttachment) -> Self {
+        attachment.to_tag()
+    }
+}
+
+impl TryFrom<&Tag> for MediaAttachment {
+    type Error = Error;
+
+    fn try_from(tag: &Tag) -> Result<Self, Self::Error> {
+        Self::from_tag(tag)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_media_attachment() {
+        let tag = Tag::parse(vec![
+            "imeta",
+            "url https://nostr.build/i/my-image.jpg",
+            "m image/jpeg",
+            "blurhash eVF$^OI:${M{o#*0-nNFxakD-?xVM}WEWB%iNKxvR-oetmo#R-aen$",
+            "dim 3024x4032",
+            "alt A scenic photo overlooking the coast of Costa Rica",
+            "x 0d7d5a8e5a6c1b1e8a2f47d8b19fd6e4c3a3f5e1b6d5c4b3a2f1e0d9c8b7a6f5",
+            "fallback https://nostrcheck.me/alt1.jpg",
+            "fallback https://void.cat/alt1.jpg",
+            "summary With spaces",
+        ])
+        .unwrap();
+
+        let attachment = MediaAttachment::from_tag(&tag).unwrap();
+        assert_eq!(
+            attachment.url,
+            UncheckedUrl::from("https://nostr.build/i/my-image.jpg")
+        );
+        assert_eq!(attachment.mime_type.as_deref(), Some("image/jpeg"));
+        assert_eq!(attachment.dim, Some(ImageDimensions::new(3024, 4032)));
+        assert_eq!(
+            attachment.alt.as_deref(),
+            Some("A scenic photo overlooking the coast of Costa Rica")
+        );
+        assert_eq!(attachment.fallback.len(), 2);
+        assert_eq!(
+            attachment.custom,
+            vec![(String::from("summary"), String::from("With spaces"))]
+        );
+        assert!(attachment.hash.is_some());
+
+        // Round trip
+        assert_eq!(
+            MediaAttachment::from_tag(&attachment.to_tag()).unwrap(),
+            attachment
+        );
+
+        // Errors
+        assert!(matches!(
+            MediaAttachment::from_tag(&Tag::parse(vec!["imeta", "m image/jpeg"]).unwrap()),
+            Err(Error::MissingUrl)
+        ));
+        assert!(matches!(
+            MediaAttachment::from_tag(&Tag::Hashtag(String::from("nostr"))),
+            Err(Error::WrongTag)
+        ));
+        assert!(MediaAttachment::from_tag(
+            &Tag::parse(vec!["imeta", "url https://example.com/a.png", "dim 10"]).unwrap()
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,585 @@
+// Copyright (c) 2022-2023 Yuki Kishimoto
+// Copyright (c) 2023-2024 Rust Nostr Developers
+// Distributed under the MIT software license
+
+//! NIP96
+//!
+//! HTTP File Storage Integration
+//!
+//! <https://github.com/nostr-protocol/nips/blob/master/96.md>
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+use std::net::SocketAddr;
+
+use bitcoin::hashes::sha256::Hash as Sha256Hash;
+use bitcoin::hashes::Hash;
+#[cfg(not(target_arch = "wasm32"))]
+use reqwest::Proxy;
+use url_fork::Url;
+
+use super::nip94::{FileMetadata, FileMetadataError};
+use super::nip98::{self, HttpData};
+use crate::{Event, HttpMethod, ImageDimensions, JsonUtil, Tag, UncheckedUrl};
+
+/// Server configuration path
+pub const SERVER_CONFIG_PATH: &str = "/.well-known/nostr/nip96.json";
+
+/// `NIP96` error
+#[derive(Debug)]
+pub enum Error {
+    /// The server configuration is invalid
+    InvalidServerConfig,
+    /// The server configuration is not accessible
+    InaccessibleServerConfig,
+    /// Invalid URL
+    InvalidUrl,
+    /// The server response is invalid
+    InvalidResponse,
+    /// The server refused the upload
+    UploadFailed(String),
+    /// The upload is still processing
+    Processing(Option<String>),
+    /// The server didn't return the file metadata
+    MissingFileMetadata,
+    /// The SHA256 hash returned by the server don't match the uploaded file
+    HashMismatch,
+    /// NIP94 error
+    FileMetadata(FileMetadataError),
+    /// Reqwest error
+    Reqwest(reqwest::Error),
+}
+
+impl std::error::Error for Error {}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidServerConfig => write!(f, "The server configuration is invalid"),
+            Self::InaccessibleServerConfig => {
+                write!(f, "The server configuration is not accessible")
+            }
+            Self::InvalidUrl => write!(f, "Invalid URL"),
+            Self::InvalidResponse => write!(f, "The server response is invalid"),
+            Self::UploadFailed(msg) => write!(f, "Upload failed: {msg}"),
+            Self::Processing(url) => match url {
+                Some(url) => write!(f, "The upload is still processing: {url}"),
+                None => write!(f, "The upload is still processing"),
+            },
+            Self::MissingFileMetadata => write!(f, "The server didn't return the file metadata"),
+            Self::HashMismatch => write!(f, "The SHA256 hash don't match the uploaded file"),
+            Self::FileMetadata(e) => write!(f, "{e}"),
+            Self::Reqwest(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+impl From<FileMetadataError> for Error {
+    fn from(e: FileMetadataError) -> Self {
+        Self::FileMetadata(e)
+    }
+}
+
+impl From<reqwest::Error> for Error {
+    fn from(e: reqwest::Error) -> Self {
+        Self::Reqwest(e)
+    }
+}
+
+/// Server configuration
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ServerConfig {
+    /// API URL, where to upload the files
+    pub api_url: Url,
+    /// URL where to download the files, if different from the API URL
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub download_url: Option<Url>,
+    /// URL of the server the uploads are delegated to
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delegated_to_url: Option<Url>,
+    /// Supported NIPs
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub supported_nips: Vec<u16>,
+    /// Terms of service URL
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tos_url: Option<Url>,
+    /// Accepted MIME types (i.e. `image/*`)
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub content_types: Vec<String>,
+}
+
+impl JsonUtil for ServerConfig {
+    type Err = serde_json::Error;
+}
+
+impl ServerConfig {
+    /// Get the server configuration URL
+    pub fn url(server_url: &Url) -> Result<Url, Error> {
+        server_url
+            .join(SERVER_CONFIG_PATH)
+            .map_err(|_| Error::InvalidUrl)
+    }
+
+    /// Check if the server accept the MIME type
+    ///
+    /// Any MIME type is accepted if the server doesn't announce the content types.
+    pub fn accepts(&self, mime_type: &str) -> bool {
+        if self.content_types.is_empty() {
+            return true;
+        }
+
+        self.content_types
+            .iter()
+            .any(|accepted| match accepted.strip_suffix("/*") {
+                Some(prefix) => mime_type
+                    .split_once('/')
+                    .map_or(false, |(t, _)| t == prefix),
+                None => accepted == mime_type,
+            })
+    }
+
+    /// Get server configuration
+    ///
+    /// **Proxy is ignored for WASM targets!**
+    pub async fn get(server_url: &Url, _proxy: Option<SocketAddr>) -> Result<Self, Error> {
+        let client: reqwest::Client = http_client(_proxy)?;
+        let url: Url = Self::url(server_url)?;
+        match client.get(url.to_string()).send().await {
+            Ok(response) => {
+                let json: String = response.text().await?;
+                tracing::debug!("Response: {json}");
+                Self::from_json(json).map_err(|_| Error::InvalidServerConfig)
+            }
+            Err(_) => Err(Error::InaccessibleServerConfig),
+        }
+    }
+}
+
+/// Upload status
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UploadStatus {
+    /// Success
+    Success,
+    /// Error
+    Error,
+    /// Processing (delayed)
+    Processing,
+}
+
+/// NIP94 event returned by the server
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UploadEvent {
+    /// Tags
+    #[serde(default)]
+    pub tags: Vec<Vec<String>>,
+    /// Content
+    #[serde(default)]
+    pub content: String,
+}
+
+/// Upload response
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UploadResponse {
+    /// Status
+    pub status: UploadStatus,
+    /// Human readable message
+    #[serde(default)]
+    pub message: String,
+    /// URL to poll when the upload is still processing
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub processing_url: Option<String>,
+    /// NIP94 event
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nip94_event: Option<UploadEvent>,
+}
+
+impl JsonUtil for UploadResponse {
+    type Err = serde_json::Error;
+}
+
+impl UploadResponse {
+    /// Get the [`FileMetadata`] of the uploaded file
+    ///
+    /// The hash returned by the server is verified against the uploaded `data`: the original file hash
+    /// (`ox` tag) if returned, otherwise the file hash (`x` tag).
+    /// If the server didn't transform the file, the size and the dimensions (PNG, JPEG, GIF and WebP)
+    /// are computed from `data`, otherwise the ones returned by the server are kept.
+    pub fn file_metadata(&self, data: &[u8], mime_type: &str) -> Result<FileMetadata, Error> {
+        match self.status {
+            UploadStatus::Success => (),
+            UploadStatus::Error => return Err(Error::UploadFailed(self.message.clone())),
+            UploadStatus::Processing => return Err(Error::Processing(self.processing_url.clone())),
+        }
+
+        let event: &UploadEvent = self
+            .nip94_event
+            .as_ref()
+            .ok_or(Error::MissingFileMetadata)?;
+        let hash = Sha256Hash::hash(data);
+
+        let mut tags: Vec<Tag> = Vec::with_capacity(event.tags.len() + 2);
+        let mut original_hash: Option<String> = None;
+        for tag in event.tags.iter() {
+            if tag.first().map(|t| t.as_str()) == Some("ox") {
+                original_hash = tag.get(1).cloned();
+                continue;
+            }
+
+            // Skip unknown or invalid tags
+            if let Ok(tag) = Tag::parse(tag.clone()) {
+                tags.push(tag);
+            }
+        }
+
+        // The server may transform the file: the `x` tag is the hash of the served file
+        // and the `ox` tag the hash of the uploaded one.
+        let served_hash: Option<Sha256Hash> = tags.iter().find_map(|t| match t {
+            Tag::Sha256(hash) => Some(*hash),
+            _ => None,
+        });
+        match original_hash {
+            Some(original_hash) => {
+                if original_hash != hash.to_string() {
+                    return Err(Error::HashMismatch);
+                }
+            }
+            None => {
+                if let Some(served_hash) = served_hash {
+                    if served_hash != hash {
+                        return Err(Error::HashMismatch);
+                    }
+                }
+            }
+        }
+
+        if served_hash.is_none() {
+            tags.push(Tag::Sha256(hash));
+        }
+        if !tags.iter().any(|t| matches!(t, Tag::MimeType(..))) {
+            tags.push(Tag::MimeType(mime_type.to_string()));
+        }
+
+        let mut metadata = FileMetadata::try_from(tags)?;
+        if metadata.hash == hash {
+            metadata = metadata.size(data.len());
+            if let Some(dim) = image_dimensions(data) {
+                metadata = metadata.dimensions(dim);
+            }
+        }
+        Ok(metadata)
+    }
+}
+
+/// Read the dimensions from the header of a PNG, JPEG, GIF or WebP image
+fn image_dimensions(data: &[u8]) -> Option<ImageDimensions> {
+    let be_u16 = |i: usize| -> Option<u64> {
+        let b = data.get(i..i + 2)?;
+        Some(u16::from_be_bytes([b[0], b[1]]) as u64)
+    };
+    let le_u16 = |i: usize| -> Option<u64> {
+        let b = data.get(i..i + 2)?;
+        Some(u16::from_le_bytes([b[0], b[1]]) as u64)
+    };
+    let be_u32 = |i: usize| -> Option<u64> {
+        let b = data.get(i..i + 4)?;
+        Some(u32::from_be_bytes([b[0], b[1], b[2], b[3]]) as u64)
+    };
+    let le_u24 = |i: usize| -> Option<u64> {
+        let b = data.get(i..i + 3)?;
+        Some(u32::from_le_bytes([b[0], b[1], b[2], 0]) as u64)
+    };
+
+    // PNG: IHDR chunk
+    if data.starts_with(b"\x89PNG\r\n\x1a\n") && data.get(12..16) == Some(&b"IHDR"[..]) {
+        return Some(ImageDimensions::new(be_u32(16)?, be_u32(20)?));
+    }
+
+    // GIF: logical screen descriptor
+    if data.starts_with(b"GIF87a") || data.starts_with(b"GIF89a") {
+        return Some(ImageDimensions::new(le_u16(6)?, le_u16(8)?));
+    }
+
+    // JPEG: start of frame segment
+    if data.starts_with(&[0xFF, 0xD8]) {
+        let mut i: usize = 2;
+        loop {
+            if *data.get(i)? != 0xFF {
+                return None;
+            }
+            let marker: u8 = *data.get(i + 1)?;
+            match marker {
+                // Fill byte
+                0xFF => i += 1,
+                // Markers without payload
+                0x01 | 0xD0..=0xD7 => i += 2,
+                // SOF markers (not DHT, JPG and DAC)
+                0xC0..=0xCF if !matches!(marker, 0xC4 | 0xC8 | 0xCC) => {
+                    return Some(ImageDimensions::new(be_u16(i + 7)?, be_u16(i + 5)?));
+                }
+                _ => i += 2 + be_u16(i + 2)? as usize,
+            }
+        }
+    }
+
+    // WebP
+    if data.starts_with(b"RIFF") && data.get(8..12) == Some(&b"WEBP"[..]) {
+        return match data.get(12..16)? {
+            // Lossy
+            b"VP8 " if data.get(23..26) == Some(&[0x9D, 0x01, 0x2A][..]) => Some(
+                ImageDimensions::new(le_u16(26)? & 0x3FFF, le_u16(28)? & 0x3FFF),
+            ),
+            // Lossless
+            b"VP8L" if data.get(20) == Some(&0x2F) => {
+                let b = data.get(21..25)?;
+                let (b0, b1, b2, b3) = (b[0] as u64, b[1] as u64, b[2] as u64, b[3] as u64);
+                Some(ImageDimensions::new(
+                    1 + (b0 | (b1 & 0x3F) << 8),
+                    1 + (b1 >> 6 | b2 << 2 | (b3 & 0x0F) << 10),
+                ))
+            }
+            // Extended
+            b"VP8X" => Some(ImageDimensions::new(1 + le_u24(24)?, 1 + le_u24(27)?)),
+            _ => None,
+        };
+    }
+
+    None
+}
+
+/// Get the [`HttpData`] to sign (NIP98) to upload `data`
+pub fn upload_http_data(config: &ServerConfig, data: &[u8]) -> HttpData {
+    HttpData::new(
+        UncheckedUrl::from(config.api_url.to_string()),
+        HttpMethod::POST,
+    )
+    .body(data)
+}
+
+/// Build the `multipart/form-data` body
+///
+/// Return the `Content-Type` header and the body.
+pub fn multipart_form_data(data: &[u8], mime_type: &str) -> (String, Vec<u8>) {
+    // The hash of the data can't be part of the data itself
+    let boundary: String = format!("nostr-nip96-{}", Sha256Hash::hash(data));
+
+    let mut body: Vec<u8> = Vec::with_capacity(data.len() + 512);
+    body.extend_from_slice(format!("--{boundary}\r\n").as_bytes());
+    body.extend_from_slice(
+        format!(
+            "Content-Disposition: form-data; name=\"size\"\r\n\r\n{}\r\n",
+            data.len()
+        )
+        .as_bytes(),
+    );
+    body.extend_from_slice(format!("--{boundary}\r\n").as_bytes());
+    body.extend_from_slice(
+        format!("Content-Disposition: form-data; name=\"content_type\"\r\n\r\n{mime_type}\r\n")
+            .as_bytes(),
+    );
+    body.extend_from_slice(format!("--{boundary}\r\n").as_bytes());
+    body.extend_from_slice(b"Content-Disposition: form-data; name=\"file\"; filename=\"file\"\r\n");
+    body.extend_from_slice(format!("Content-Type: {mime_type}\r\n\r\n").as_bytes());
+    body.extend_from_slice(data);
+    body.extend_from_slice(format!("\r\n--{boundary}--\r\n").as_bytes());
+
+    (format!("multipart/form-data; boundary={boundary}"), body)
+}
+
+/// Upload `data` to the server
+///
+/// `auth` must be the HTTP auth event (NIP98) signed for [`upload_http_data`].
+///
+/// **Proxy is ignored for WASM targets!**
+pub async fn upload_data(
+    config: &ServerConfig,
+    auth: &Event,
+    data: &[u8],
+    mime_type: &str,
+    _proxy: Option<SocketAddr>,
+) -> Result<UploadResponse, Error> {
+    let client: reqwest::Client = http_client(_proxy)?;
+    let authorization: String = nip98::authorization_header(auth);
+    let (content_type, body) = multipart_form_data(data, mime_type);
+    let response = client
+        .post(config.api_url.to_string())
+        .header("Authorization", authorization)
+        .header("Content-Type", content_type)
+        .body(body)
+        .send()
+        .await?;
+    let json: String = response.text().await?;
+    tracing::debug!("Response: {json}");
+    UploadResponse::from_json(json).map_err(|_| Error::InvalidResponse)
+}
+
+fn http_client(_proxy: Option<SocketAddr>) -> Result<reqwest::Client, Error> {
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        let mut builder = reqwest::Client::builder();
+        if let Some(proxy) = _proxy {
+            let proxy = format!("socks5h://{proxy}");
+            builder = builder.proxy(Proxy::all(proxy)?);
+        }
+        Ok(builder.build()?)
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    Ok(reqwest::Client::new())
+}
+
+#[cfg(test)]
+mod tests {
+    use core::str::FromStr;
+
+    use super::*;
+
+    const DATA: &[u8] = b"nostr";
+
+    fn response(tags: &str) -> UploadResponse {
+        UploadResponse::from_json(format!(
+            r#"{{"status":"success","message":"Upload successful.","nip94_event":{{"tags":{tags},"content":""}}}}"#
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_server_config() {
+        let json = r#"{"api_url":"https://nostr.build/api/v2/nip96/upload","download_url":"https://media.nostr.build","supported_nips":[94,96,98],"tos_url":"https://nostr.build/tos/","content_types":["image/*","video/mp4"],"plans":{}}"#;
+        let config = ServerConfig::from_json(json).unwrap();
+        assert_eq!(
+            config.api_url,
+            Url::parse("https://nostr.build/api/v2/nip96/upload").unwrap()
+        );
+        assert_eq!(config.supported_nips, vec![94, 96, 98]);
+        assert!(config.accepts("image/png"));
+        assert!(config.accepts("video/mp4"));
+        assert!(!config.accepts("video/webm"));
+        assert!(!config.accepts("application/pdf"));
+
+        let url = Url::parse("https://nostr.build").unwrap();
+        assert_eq!(
+            ServerConfig::url(&url).unwrap(),
+            Url::parse("https://nostr.build/.well-known/nostr/nip96.json").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_upload_file_metadata() {
+        let hash = Sha256Hash::hash(DATA);
+        let res = response(&format!(
+            r#"[["url","https://example.com/file.png"],["ox","{hash}"],["x","{hash}"],["m","image/png"],["dim","800x600"]]"#
+        ));
+        let metadata = res.file_metadata(DATA, "image/png").unwrap();
+        assert_eq!(
+            metadata.url,
+            Url::parse("https://example.com/file.png").unwrap()
+        );
+        assert_eq!(metadata.hash, hash);
+        assert_eq!(metadata.size, Some(DATA.len()));
+        assert_eq!(metadata.dim.map(|d| (d.width, d.height)), Some((800, 600)));
+
+        // Missing `x` and `m` tags
+        let res = response(r#"[["url","https://example.com/file.png"]]"#);
+        let metadata = res.file_metadata(DATA, "image/png").unwrap();
+        assert_eq!(metadata.hash, hash);
+        assert_eq!(metadata.mime_type, "image/png");
+
+        // Transformed file
+        let other = Sha256Hash::from_str(
+            "1aea8e98e0e5d969b7124f553b88dfae47d1f00472ea8c0dbf4ac4577d39ef02",
+        )
+        .unwrap();
+        let res = response(&format!(
+            r#"[["url","https://example.com/file.png"],["ox","{hash}"],["x","{other}"]]"#
+        ));
+        let metadata = res.file_metadata(DATA, "image/png").unwrap();
+        assert_eq!(metadata.hash, other);
+        assert_eq!(metadata.size, None);
+
+        // Hash mismatch
+        let res = response(&format!(
+            r#"[["url","https://example.com/file.png"],["ox","{other}"]]"#
+        ));
+        assert!(matches!(
+            res.file_metadata(DATA, "image/png").unwrap_err(),
+            Error::HashMismatch
+        ));
+        let res = response(&format!(
+            r#"[["url","https://example.com/file.png"],["x","{other}"]]"#
+        ));
+        assert!(matches!(
+            res.file_metadata(DATA, "image/png").unwrap_err(),
+            Error::HashMismatch
+        ));
+
+        // Dimensions computed from the uploaded image
+        let mut png: Vec<u8> = b"\x89PNG\r\n\x1a\n\x00\x00\x00\x0dIHDR".to_vec();
+        png.extend_from_slice(&[0, 0, 0x04, 0x00, 0, 0, 0x03, 0x00]);
+        let res = response(r#"[["url","https://example.com/file.png"],["dim","1x1"]]"#);
+        let metadata = res.file_metadata(&png, "image/png").unwrap();
+        assert_eq!(metadata.hash, Sha256Hash::hash(&png));
+        assert_eq!(metadata.size, Some(png.len()));
+        assert_eq!(metadata.dim.map(|d| (d.width, d.height)), Some((1024, 768)));
+
+        let res =
+            UploadResponse::from_json(r#"{"status":"error","message":"File too large"}"#).unwrap();
+        assert!(matches!(
+            res.file_metadata(DATA, "image/png").unwrap_err(),
+            Error::UploadFailed(msg) if msg == "File too large"
+        ));
+    }
+
+    #[test]
+    fn test_image_dimensions() {
+        let dim = |data: &[u8]| image_dimensions(data).map(|d| (d.width, d.height));
+
+        let mut png: Vec<u8> = b"\x89PNG\r\n\x1a\n\x00\x00\x00\x0dIHDR".to_vec();
+        png.extend_from_slice(&[0, 0, 0x01, 0x40, 0, 0, 0x00, 0xF0]);
+        assert_eq!(dim(&png), Some((320, 240)));
+
+        assert_eq!(dim(b"GIF89a\x40\x01\xF0\x00"), Some((320, 240)));
+
+        // APP0 segment followed by SOF0
+        let jpeg: &[u8] = &[
+            0xFF, 0xD8, 0xFF, 0xE0, 0x00, 0x04, 0x00, 0x00, 0xFF, 0xC0, 0x00, 0x11, 0x08, 0x00,
+            0xF0, 0x01, 0x40,
+        ];
+        assert_eq!(dim(jpeg), Some((320, 240)));
+
+        let mut webp: Vec<u8> = b"RIFF\x00\x00\x00\x00WEBPVP8X\x0a\x00\x00\x00".to_vec();
+        webp.extend_from_slice(&[0, 0, 0, 0, 0x3F, 0x01, 0x00, 0xEF, 0x00, 0x00]);
+        assert_eq!(dim(&webp), Some((320, 240)));
+
+        let mut webp: Vec<u8> = b"RIFF\x00\x00\x00\x00WEBPVP8L\x05\x00\x00\x00".to_vec();
+        webp.extend_from_slice(&[0x2F, 0x8F, 0xC1, 0x4A, 0x00]);
+        assert_eq!(dim(&webp), Some((400, 300)));
+
+        assert_eq!(dim(DATA), None);
+        assert_eq!(dim(&png[..20]), None);
+        assert_eq!(dim(&jpeg[..12]), None);
+    }
+
+    #[test]
+    fn test_multipart_form_data() {
+        let (content_type, body) = multipart_form_data(DATA, "text/plain");
+        let boundary = content_type
+            .strip_prefix("multipart/form-data; boundary=")
+            .unwrap();
+        let body = String::from_utf8(body).unwrap();
+        assert!(body.starts_with(&format!("--{boundary}\r\n")));
+        assert!(body.ends_with(&format!("\r\n--{boundary}--\r\n")));
+        assert!(body.contains(
+            "name=\"file\"; filename=\"file\"\r\nContent-Type: text/plain\r\n\r\nnostr\r\n"
+        ));
+        assert!(body.contains("name=\"size\"\r\n\r\n5\r\n"));
+    }
+}
@@ -9,13 +9,29 @@
 //!
 //! <https://github.com/nostr-protocol/nips/blob/master/98.md>
 
+#[cfg(feature = "nip98")]
+use alloc::format;
+#[cfg(feature = "nip98")]
+use alloc::string::String;
 use alloc::vec::Vec;
 use core::fmt;
 
+#[cfg(feature = "nip98")]
+use base64::engine::{general_purpose, Engine};
 use bitcoin::hashes::sha256::Hash as Sha256Hash;
-
+use bitcoin::hashes::Hash;
+#[cfg(all(feature = "std", feature = "nip98"))]
+use bitcoin::secp256k1::XOnlyPublicKey;
+
+#[cfg(feature = "nip98")]
+use crate::{Event, JsonUtil};
+#[cfg(all(feature = "std", feature = "nip98"))]
+use crate::{EventBuilder, UnsignedEvent};
 use crate::{HttpMethod, Tag, UncheckedUrl};
 
+/// `Authorization` header scheme
+pub const AUTHORIZATION_SCHEME: &str = "Nostr";
+
 /// [`HttpData`] required tags
 #[derive(Debug)]
 pub enum RequiredTags {
@@ -88,6 +104,32 @@ impl HttpData {
             ..self
         }
     }
+
+    /// Add the SHA256 hash of the request body
+    pub fn body<T>(self, body: T) -> Self
+    where
+        T: AsRef<[u8]>,
+    {
+        self.payload(Sha256Hash::hash(body.as_ref()))
+    }
+
+    /// Build the unsigned HTTP auth event (kind `27235`) of `public_key`
+    ///
+    /// Sign it with any signer (i.e. keys, NIP07 or NIP46) and build the `Authorization` header value
+    /// with [`authorization_header`].
+    #[cfg(all(feature = "std", feature = "nip98"))]
+    pub fn to_unsigned_event(self, public_key: XOnlyPublicKey) -> UnsignedEvent {
+        EventBuilder::http_auth(self).to_unsigned_event(public_key)
+    }
+}
+
+/// Build the `Authorization` header value of an already signed HTTP auth event (kind `27235`)
+///
+/// Return `Nostr <base64-encoded event>`.
+#[cfg(feature = "nip98")]
+pub fn authorization_header(event: &Event) -> String {
+    let token: String = general_purpose::STANDARD.encode(event.as_json());
+    format!("{AUTHORIZATION_SCHEME} {token}")
 }
 
 impl From<HttpData> for Vec<Tag> {
@@ -142,3 +184,34 @@ impl TryFrom<Vec<Tag>> for HttpData {
         })
     }
 }
+
+#[cfg(all(test, feature = "std", feature = "nip98"))]
+mod tests {
+    use core::str::FromStr;
+
+    use super::*;
+    use crate::{Keys, Kind};
+
+    #[test]
+    fn test_authorization_header() {
+        let keys = Keys::generate();
+        let url = UncheckedUrl::from_str("https://example.com/upload").unwrap();
+        let body = b"nostr";
+        let data = HttpData::new(url.clone(), HttpMethod::POST).body(body);
+        assert_eq!(data.payload, Some(Sha256Hash::hash(body)));
+
+        let unsigned: UnsignedEvent = data.to_unsigned_event(keys.public_key());
+        let header: String = authorization_header(&unsigned.sign(&keys).unwrap());
+        let token: &str = header.strip_prefix("Nostr ").unwrap();
+        let json: Vec<u8> = general_purpose::STANDARD.decode(token).unwrap();
+        let event = Event::from_json(json).unwrap();
+        assert!(event.verify().is_ok());
+        assert_eq!(event.kind(), Kind::HttpAuth);
+        assert_eq!(event.author(), keys.public_key());
+
+        let data = HttpData::try_from(event.tags().to_vec()).unwrap();
+        assert_eq!(data.url, url);
+        assert_eq!(data.method, HttpMethod::POST);
+        assert_eq!(data.payload, Some(Sha256Hash::hash(body)));
+    }
+}
@@ -37,13 +37,16 @@ pub use crate::nips::nip05::{self, *};
 pub use crate::nips::nip06::{self, *};
 #[cfg(all(feature = "nip07", target_arch = "wasm32"))]
 pub use crate::nips::nip07::{self, *};
+pub use crate::nips::nip10::{self, *};
 #[cfg(all(feature = "std", feature = "nip11"))]
 pub use crate::nips::nip11::{self, *};
 pub use crate::nips::nip13::{self, *};
 pub use crate::nips::nip15::{self, *};
 pub use crate::nips::nip19::{self, *};
 pub use crate::nips::nip21::{self, *};
+pub use crate::nips::nip25::{self, *};
 pub use crate::nips::nip26::{self, *};
+pub use crate::nips::nip27::{self, *};
 #[cfg(feature = "nip44")]
 pub use crate::nips::nip44::{self, *};
 #[cfg(all(feature = "std", feature = "nip46"))]
@@ -51,12 +54,18 @@ pub use crate::nips::nip46::{self, *};
 #[cfg(feature = "nip47")]
 pub use crate::nips::nip47::{self, *};
 pub use crate::nips::nip48::{self, *};
+pub use crate::nips::nip51::{self, *};
 pub use crate::nips::nip53::{self, *};
 #[cfg(feature = "nip57")]
 pub use crate::nips::nip57::{self, *};
+#[cfg(all(feature = "std", feature = "nip59"))]
+pub use crate::nips::nip59::{self, *};
 pub use crate::nips::nip65::{self, *};
 pub use crate::nips::nip90::{self, *};
+pub use crate::nips::nip92::{self, *};
 pub use crate::nips::nip94::{self, *};
+#[cfg(all(feature = "std", feature = "nip96"))]
+pub use crate::nips::nip96::{self, *};
 pub use crate::nips::nip98::{self, *};
 pub use crate::types::*;
 pub use crate::util::*;
@@ -197,6 +197,31 @@ impl Metadata {
         self.custom.insert(field_name.into(), value.into());
         self
     }
+
+    /// Get custom metadata field
+    pub fn get_custom_field<K>(&self, field_name: K) -> Option<&Value>
+    where
+        K: AsRef<str>,
+    {
+        self.custom.get(field_name.as_ref())
+    }
+
+    /// Set custom metadata field, returning the previous value (if any)
+    pub fn set_custom_field<K, S>(&mut self, field_name: K, value: S) -> Option<Value>
+    where
+        K: Into<String>,
+        S: Into<Value>,
+    {
+        self.custom.insert(field_name.into(), value.into())
+    }
+
+    /// Remove custom metadata field, returning its value (if any)
+    pub fn remove_custom_field<K>(&mut self, field_name: K) -> Option<Value>
+    where
+        K: AsRef<str>,
+    {
+        self.custom.remove(field_name.as_ref())
+    }
 }
 
 impl JsonUtil for Metadata {
@@ -295,4 +320,41 @@ mod tests {
         );
         assert_eq!(metadata, Metadata::from_json(metadata.as_json()).unwrap());
     }
+
+    #[test]
+    fn test_custom_fields_round_trip() {
+        let content = r#"{"name":"myname","banner_dark":"https://example.com/banner.png","pronouns":"they/them","reactions":false}"#;
+        let mut metadata = Metadata::from_json(content).unwrap();
+        assert_eq!(metadata.name, Some(String::from("myname")));
+        assert_eq!(
+            metadata.get_custom_field("banner_dark"),
+            Some(&Value::String(String::from(
+                "https://example.com/banner.png"
+            )))
+        );
+        assert_eq!(
+            metadata.get_custom_field("reactions"),
+            Some(&Value::Bool(false))
+        );
+        assert_eq!(metadata.get_custom_field("name"), None);
+
+        assert_eq!(
+            metadata.set_custom_field("reactions", true),
+            Some(Value::Bool(false))
+        );
+        assert_eq!(
+            metadata.remove_custom_field("pronouns"),
+            Some(Value::String(String::from("they/them")))
+        );
+
+        let json: Value = serde_json::from_str(&metadata.as_json()).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "name": "myname",
+                "banner_dark": "https://example.com/banner.png",
+                "reactions": true
+            })
+        );
+    }
 }
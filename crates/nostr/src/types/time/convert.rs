@@ -0,0 +1,71 @@
+// Copyright (c) 2022-2023 Yuki Kishimoto
+// Copyright (c) 2023-2024 Rust Nostr Developers
+// Distributed under the MIT software license
+
+//! Conversions from/to `chrono` and `time` types
+
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, TimeZone, Utc};
+#[cfg(feature = "time")]
+use time::OffsetDateTime;
+
+use super::Timestamp;
+
+impl Timestamp {
+    /// Convert to [`DateTime<Utc>`]
+    ///
+    /// Return `None` if out of range.
+    #[cfg(feature = "chrono")]
+    pub fn to_chrono(&self) -> Option<DateTime<Utc>> {
+        DateTime::from_timestamp(self.0, 0)
+    }
+
+    /// Convert to [`OffsetDateTime`] (UTC)
+    ///
+    /// Return `None` if out of range.
+    #[cfg(feature = "time")]
+    pub fn to_offset_date_time(&self) -> Option<OffsetDateTime> {
+        OffsetDateTime::from_unix_timestamp(self.0).ok()
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl<Tz> From<DateTime<Tz>> for Timestamp
+where
+    Tz: TimeZone,
+{
+    fn from(datetime: DateTime<Tz>) -> Self {
+        Self(datetime.timestamp())
+    }
+}
+
+#[cfg(feature = "time")]
+impl From<OffsetDateTime> for Timestamp {
+    fn from(datetime: OffsetDateTime) -> Self {
+        Self(datetime.unix_timestamp())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "chrono")]
+    fn test_chrono_round_trip() {
+        let timestamp = Timestamp::from(1682060685);
+        let datetime: DateTime<Utc> = timestamp.to_chrono().unwrap();
+        assert_eq!(datetime.to_rfc3339(), "2023-04-21T07:04:45+00:00");
+        assert_eq!(Timestamp::from(datetime), timestamp);
+    }
+
+    #[test]
+    #[cfg(feature = "time")]
+    fn test_time_round_trip() {
+        let timestamp = Timestamp::from(1682060685);
+        let datetime: OffsetDateTime = timestamp.to_offset_date_time().unwrap();
+        assert_eq!(datetime.year(), 2023);
+        assert_eq!(datetime.day(), 21);
+        assert_eq!(Timestamp::from(datetime), timestamp);
+    }
+}
@@ -7,7 +7,7 @@
 use alloc::string::String;
 use alloc::vec::Vec;
 use core::fmt;
-use core::ops::{Add, Sub};
+use core::ops::{Add, Range, RangeInclusive, Sub};
 use core::str::FromStr;
 use core::time::Duration;
 
@@ -15,12 +15,18 @@ use core::time::Duration;
 use bitcoin::secp256k1::rand::rngs::OsRng;
 use bitcoin::secp256k1::rand::Rng;
 
+#[cfg(any(feature = "chrono", feature = "time"))]
+mod convert;
 mod supplier;
 
 pub use self::supplier::TimeSupplier;
 #[cfg(feature = "std")]
 pub use self::supplier::{Instant, SystemTime, UNIX_EPOCH};
 
+const SECS_PER_DAY: u64 = 86400;
+/// Range of the default tweak (max 65535 secs)
+const DEFAULT_TWEAK_RANGE: Range<u64> = 0..(u16::MAX as u64 + 1);
+
 /// Unix timestamp in seconds
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub struct Timestamp(i64);
@@ -52,8 +58,17 @@ impl Timestamp {
     /// Remove a random number of seconds from now (max 65535 secs)
     #[cfg(feature = "std")]
     pub fn tweaked() -> Self {
+        Self::tweaked_in_range(DEFAULT_TWEAK_RANGE)
+    }
+
+    /// Get tweaked UNIX timestamp
+    ///
+    /// Remove a random number of seconds, in `range`, from now.
+    /// Useful to not leak when an event was created (i.e. NIP59 seals and gift wraps).
+    #[cfg(feature = "std")]
+    pub fn tweaked_in_range(range: Range<u64>) -> Self {
         let mut now: Timestamp = Self::now();
-        now.tweak();
+        now.tweak_in_range(range);
         now
     }
 
@@ -61,19 +76,40 @@ impl Timestamp {
     ///
     /// Remove a random number of seconds from now (max 65535 secs)
     pub fn tweaked_with_supplier_and_rng<T, R>(supplier: &T, rng: &mut R) -> Self
+    where
+        T: TimeSupplier,
+        R: Rng,
+    {
+        Self::tweaked_in_range_with_supplier_and_rng(supplier, rng, DEFAULT_TWEAK_RANGE)
+    }
+
+    /// Get tweaked UNIX timestamp
+    ///
+    /// Remove a random number of seconds, in `range`, from now.
+    pub fn tweaked_in_range_with_supplier_and_rng<T, R>(
+        supplier: &T,
+        rng: &mut R,
+        range: Range<u64>,
+    ) -> Self
     where
         T: TimeSupplier,
         R: Rng,
     {
         let mut now: Timestamp = Self::now_with_supplier(supplier);
-        now.tweak_with_rng(rng);
+        now.tweak_in_range_with_rng(rng, range);
         now
     }
 
     /// Remove a random number of seconds from [`Timestamp`] (max 65535 secs)
     #[cfg(feature = "std")]
     pub fn tweak(&mut self) {
-        self.tweak_with_rng(&mut OsRng);
+        self.tweak_in_range(DEFAULT_TWEAK_RANGE);
+    }
+
+    /// Remove a random number of seconds, in `range`, from [`Timestamp`]
+    #[cfg(feature = "std")]
+    pub fn tweak_in_range(&mut self, range: Range<u64>) {
+        self.tweak_in_range_with_rng(&mut OsRng, range);
     }
 
     /// Remove a random number of seconds from [`Timestamp`] (max 65535 secs)
@@ -81,8 +117,69 @@ impl Timestamp {
     where
         R: Rng,
     {
-        let secs: u16 = rng.gen_range(0..=u16::MAX);
-        self.0 -= secs as i64;
+        self.tweak_in_range_with_rng(rng, DEFAULT_TWEAK_RANGE);
+    }
+
+    /// Remove a random number of seconds, in `range`, from [`Timestamp`]
+    ///
+    /// An empty `range` leave the [`Timestamp`] unchanged.
+    pub fn tweak_in_range_with_rng<R>(&mut self, rng: &mut R, range: Range<u64>)
+    where
+        R: Rng,
+    {
+        if !range.is_empty() {
+            let secs: u64 = rng.gen_range(range);
+            *self = *self - secs;
+        }
+    }
+
+    /// Add [`Duration`], returning `None` on overflow
+    pub fn add_duration(&self, duration: Duration) -> Option<Self> {
+        let secs: i64 = i64::try_from(duration.as_secs()).ok()?;
+        self.0.checked_add(secs).map(Self)
+    }
+
+    /// Subtract [`Duration`], returning `None` on overflow
+    pub fn sub_duration(&self, duration: Duration) -> Option<Self> {
+        let secs: i64 = i64::try_from(duration.as_secs()).ok()?;
+        self.0.checked_sub(secs).map(Self)
+    }
+
+    /// Add days, returning `None` on overflow
+    pub fn add_days(&self, days: u64) -> Option<Self> {
+        self.add_duration(Duration::from_secs(days.checked_mul(SECS_PER_DAY)?))
+    }
+
+    /// Subtract days, returning `None` on overflow
+    pub fn sub_days(&self, days: u64) -> Option<Self> {
+        self.sub_duration(Duration::from_secs(days.checked_mul(SECS_PER_DAY)?))
+    }
+
+    /// Get the start of the UTC day (`00:00:00`) of this [`Timestamp`]
+    pub fn start_of_day(&self) -> Self {
+        Self(self.0 - self.0.rem_euclid(SECS_PER_DAY as i64))
+    }
+
+    /// Get the UTC day of this [`Timestamp`], as half-open range (`00:00:00` of the day..`00:00:00` of the next day)
+    pub fn day_range(&self) -> Range<Self> {
+        let start: Self = self.start_of_day();
+        start..start + SECS_PER_DAY
+    }
+
+    /// Get the range from `duration` ago to now (both inclusive)
+    #[cfg(feature = "std")]
+    pub fn last(duration: Duration) -> RangeInclusive<Self> {
+        let now: Self = Self::now();
+        now - duration..=now
+    }
+
+    /// Get the range from `duration` ago to now (both inclusive), using a specified [`TimeSupplier`]
+    pub fn last_with_supplier<T>(duration: Duration, supplier: &T) -> RangeInclusive<Self>
+    where
+        T: TimeSupplier,
+    {
+        let now: Self = Self::now_with_supplier(supplier);
+        now - duration..=now
     }
 
     /// Get timestamp as [`u64`]
@@ -255,6 +352,53 @@ impl Sub<i64> for Timestamp {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_checked_arithmetic() {
+        let timestamp = Timestamp::from(1682060685);
+        assert_eq!(
+            timestamp.add_days(1),
+            Some(Timestamp::from(1682060685 + 86400))
+        );
+        assert_eq!(
+            timestamp.sub_duration(Duration::from_secs(60)),
+            Some(Timestamp::from(1682060625))
+        );
+        assert_eq!(timestamp.add_duration(Duration::from_secs(u64::MAX)), None);
+        assert_eq!(timestamp.add_days(u64::MAX), None);
+        assert_eq!(Timestamp(i64::MIN).sub_days(1), None);
+    }
+
+    #[test]
+    fn test_day_range() {
+        let timestamp = Timestamp::from(1682060685); // 2023-04-21T07:04:45Z
+        let range = timestamp.day_range();
+        assert_eq!(range.start.to_human_datetime(), "2023-04-21T00:00:00Z");
+        assert_eq!(range.end.to_human_datetime(), "2023-04-22T00:00:00Z");
+        assert!(range.contains(&timestamp));
+        assert!(!range.contains(&range.end));
+
+        assert_eq!(Timestamp(-1).start_of_day(), Timestamp(-86400));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_tweak() {
+        let timestamp = Timestamp::from(1682060685);
+        for _ in 0..100 {
+            let mut tweaked = timestamp;
+            tweaked.tweak_in_range(10..3600);
+            assert!(tweaked <= timestamp - 10_u64);
+            assert!(tweaked > timestamp - 3600_u64);
+        }
+
+        let mut tweaked = timestamp;
+        tweaked.tweak_in_range(0..0);
+        assert_eq!(tweaked, timestamp);
+
+        let now = Timestamp::now();
+        assert!(Timestamp::tweaked_in_range(0..60) <= now + 1_u64);
+    }
+
     #[test]
     fn test_timestamp_to_human_datetime() {
         let timestamp = Timestamp::from(1682060685);
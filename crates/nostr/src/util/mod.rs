@@ -5,6 +5,7 @@
 //! Util
 
 use alloc::string::String;
+use core::str::FromStr;
 
 use bitcoin::secp256k1::{ecdh, Parity, PublicKey, SecretKey, XOnlyPublicKey};
 #[cfg(feature = "std")]
@@ -17,8 +18,9 @@ use serde::Serialize;
 #[cfg(feature = "nip44")]
 pub mod hkdf;
 
-use crate::nips::nip01::Coordinate;
-use crate::{EventId, Tag};
+use crate::nips::nip01::{self, Coordinate};
+use crate::nips::nip19::{FromBech32, Nip19, Nip19Event};
+use crate::{EventId, Filter, Tag};
 
 /// Generate shared key
 ///
@@ -65,6 +67,7 @@ where
 }
 
 /// Event ID or Coordinate
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum EventIdOrCoordinate {
     /// Event ID
     Id(EventId),
@@ -92,3 +95,94 @@ impl From<Coordinate> for EventIdOrCoordinate {
         Self::Coordinate(coordinate)
     }
 }
+
+impl From<&Coordinate> for EventIdOrCoordinate {
+    fn from(coordinate: &Coordinate) -> Self {
+        Self::Coordinate(coordinate.clone())
+    }
+}
+
+impl From<Nip19Event> for EventIdOrCoordinate {
+    fn from(event: Nip19Event) -> Self {
+        Self::Id(event.event_id)
+    }
+}
+
+impl From<EventIdOrCoordinate> for Filter {
+    fn from(value: EventIdOrCoordinate) -> Self {
+        match value {
+            EventIdOrCoordinate::Id(id) => Filter::new().id(id),
+            EventIdOrCoordinate::Coordinate(a) => a.into(),
+        }
+    }
+}
+
+/// Parse from hex event ID, raw coordinate (`<kind>:<pubkey>:<d-identifier>`),
+/// `note`, `nevent` or `naddr` (also as `nostr:` URIs)
+impl FromStr for EventIdOrCoordinate {
+    type Err = nip01::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(id) = EventId::from_hex(s) {
+            return Ok(Self::Id(id));
+        }
+
+        let bech32: &str = s.strip_prefix("nostr:").unwrap_or(s);
+        match Nip19::from_bech32(bech32) {
+            Ok(Nip19::EventId(id)) => Ok(Self::Id(id)),
+            Ok(Nip19::Event(event)) => Ok(event.into()),
+            Ok(Nip19::Coordinate(coordinate)) => Ok(Self::Coordinate(coordinate)),
+            Ok(..) => Err(nip01::Error::InvalidEventIdOrCoordinate),
+            Err(..) => Coordinate::from_str(s)
+                .map(Self::Coordinate)
+                .map_err(|_| nip01::Error::InvalidEventIdOrCoordinate),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::secp256k1::XOnlyPublicKey;
+
+    use super::*;
+    use crate::nips::nip19::ToBech32;
+    use crate::Kind;
+
+    #[test]
+    fn test_parse_event_id_or_coordinate() {
+        let id =
+            EventId::from_hex("378f145897eea948952674269945e88612420db35791784abf0616b4fed56ef7")
+                .unwrap();
+        let public_key = XOnlyPublicKey::from_str(
+            "13adc511de7e1cfcf1c6b7f6365fb5a03442d7bcacf565ea57fa7770912c023d",
+        )
+        .unwrap();
+        let coordinate = Coordinate::new(Kind::LongFormTextNote, public_key).identifier("a:b");
+
+        assert_eq!(
+            EventIdOrCoordinate::from_str(&id.to_hex()).unwrap(),
+            EventIdOrCoordinate::Id(id)
+        );
+        assert_eq!(
+            EventIdOrCoordinate::from_str(&id.to_bech32().unwrap()).unwrap(),
+            EventIdOrCoordinate::Id(id)
+        );
+        assert_eq!(
+            EventIdOrCoordinate::from_str(&coordinate.to_string()).unwrap(),
+            EventIdOrCoordinate::Coordinate(coordinate.clone())
+        );
+
+        let naddr = coordinate.to_bech32().unwrap();
+        assert_eq!(
+            EventIdOrCoordinate::from_str(&naddr).unwrap(),
+            EventIdOrCoordinate::Coordinate(coordinate.clone())
+        );
+        assert_eq!(
+            EventIdOrCoordinate::from_str(&format!("nostr:{naddr}")).unwrap(),
+            EventIdOrCoordinate::Coordinate(coordinate)
+        );
+
+        assert!(EventIdOrCoordinate::from_str(&public_key.to_bech32().unwrap()).is_err());
+        assert!(EventIdOrCoordinate::from_str("invalid").is_err());
+    }
+}